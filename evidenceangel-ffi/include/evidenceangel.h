@@ -0,0 +1,372 @@
+/* EvidenceAngel C API. This file is generated by cbindgen from src/lib.rs. */
+
+#ifndef EVIDENCEANGEL_H
+#define EVIDENCEANGEL_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+/**
+ * The length of a buffer that can hold a test case ID, including the terminating NUL.
+ */
+#define EA_ID_LENGTH 37
+
+/**
+ * The test case hasn't been run.
+ */
+#define EA_STATUS_NONE 0
+
+/**
+ * The test case passed.
+ */
+#define EA_STATUS_PASS 1
+
+/**
+ * The test case failed.
+ */
+#define EA_STATUS_FAIL 2
+
+/**
+ * Plain text, from UTF-8 data.
+ */
+#define EA_EVIDENCE_KIND_TEXT 0
+
+/**
+ * Rich text, from UTF-8 data.
+ */
+#define EA_EVIDENCE_KIND_RICH_TEXT 1
+
+/**
+ * A PNG or JPEG image.
+ */
+#define EA_EVIDENCE_KIND_IMAGE 2
+
+/**
+ * Any file.
+ */
+#define EA_EVIDENCE_KIND_FILE 3
+
+/**
+ * An HTTP request and response, from UTF-8 data.
+ */
+#define EA_EVIDENCE_KIND_HTTP 4
+
+/**
+ * A log, from UTF-8 data in any format `EvidenceAngel` can detect.
+ */
+#define EA_EVIDENCE_KIND_LOG 5
+
+/**
+ * An MP4 or `WebM` video.
+ */
+#define EA_EVIDENCE_KIND_VIDEO 6
+
+/**
+ * An HTML document.
+ */
+#define EA_EXPORT_FORMAT_HTML 0
+
+/**
+ * An Excel workbook.
+ */
+#define EA_EXPORT_FORMAT_EXCEL 1
+
+/**
+ * A ZIP of the evidence files.
+ */
+#define EA_EXPORT_FORMAT_ZIP_OF_FILES 2
+
+/**
+ * The result of an API call. The codes from [`EaError::LockNotObtained`] to
+ * [`EaError::InvalidCompressionLevel`] mirror the errors raised by `EvidenceAngel` itself.
+ */
+typedef enum EaError {
+  /**
+   * The call succeeded.
+   */
+  EA_ERROR_OK = 0,
+  /**
+   * The package is already open elsewhere.
+   */
+  EA_ERROR_LOCK_NOT_OBTAINED = 1,
+  /**
+   * An I/O error from the system.
+   */
+  EA_ERROR_IO = 2,
+  /**
+   * The package couldn't be read or written as a ZIP file.
+   */
+  EA_ERROR_ZIP = 3,
+  /**
+   * The package is corrupt.
+   */
+  EA_ERROR_CORRUPT_EVIDENCE_PACKAGE = 4,
+  /**
+   * The package manifest isn't valid JSON.
+   */
+  EA_ERROR_INVALID_MANIFEST = 5,
+  /**
+   * The package couldn't be created.
+   */
+  EA_ERROR_FAILED_TO_CREATE_PACKAGE = 6,
+  /**
+   * A test case couldn't be saved.
+   */
+  EA_ERROR_FAILED_TO_SAVE_TEST_CASE = 7,
+  /**
+   * A test case couldn't be read.
+   */
+  EA_ERROR_INVALID_TEST_CASE = 8,
+  /**
+   * An image couldn't be decoded or encoded, or this library was built without support for
+   * processing images.
+   */
+  EA_ERROR_IMAGE = 9,
+  /**
+   * The data of some evidence isn't valid for its kind.
+   */
+  EA_ERROR_INVALID_EVIDENCE_DATA = 10,
+  /**
+   * Some media is missing from the package.
+   */
+  EA_ERROR_MEDIA_MISSING = 11,
+  /**
+   * The package manifest failed schema validation.
+   */
+  EA_ERROR_MANIFEST_SCHEMA_VALIDATION_FAILED = 12,
+  /**
+   * A test case failed schema validation.
+   */
+  EA_ERROR_TEST_CASE_SCHEMA_VALIDATION_FAILED = 13,
+  /**
+   * A rule for redacting secrets isn't valid.
+   */
+  EA_ERROR_INVALID_SECRET_RULE = 14,
+  /**
+   * A test case template isn't valid.
+   */
+  EA_ERROR_INVALID_TEMPLATE = 15,
+  /**
+   * The test case doesn't exist.
+   */
+  EA_ERROR_DOESNT_EXIST = 16,
+  /**
+   * An export failed.
+   */
+  EA_ERROR_OTHER_EXPORT_ERROR = 17,
+  /**
+   * A compression level isn't accepted by the compression algorithm.
+   */
+  EA_ERROR_INVALID_COMPRESSION_LEVEL = 18,
+  /**
+   * A required pointer argument was NULL.
+   */
+  EA_ERROR_NULL_ARGUMENT = 100,
+  /**
+   * A string argument wasn't valid UTF-8.
+   */
+  EA_ERROR_INVALID_UTF8 = 101,
+  /**
+   * An argument wasn't valid, such as an unknown kind or a malformed test case ID.
+   */
+  EA_ERROR_INVALID_ARGUMENT = 102,
+  /**
+   * `EvidenceAngel` panicked. The package should be closed without saving.
+   */
+  EA_ERROR_PANIC = 103,
+} EaError;
+
+/**
+ * An open evidence package. This is only ever handled by pointer.
+ */
+typedef struct EaPackage EaPackage;
+
+/**
+ * The status of a test case.
+ */
+typedef uint32_t EaStatus;
+
+/**
+ * The kind of evidence to add.
+ */
+typedef uint32_t EaEvidenceKind;
+
+/**
+ * The format to export to.
+ */
+typedef uint32_t EaExportFormat;
+
+#ifdef __cplusplus
+extern "C" {
+#endif  // __cplusplus
+
+/**
+ * Get the version of this library, such as `1.5.0`. The string is statically allocated.
+ */
+const char *ea_version(void);
+
+/**
+ * Get a message describing the last error raised on this thread, or NULL if the last call
+ * succeeded. The string is valid until the next call on this thread.
+ */
+const char *ea_last_error_message(void);
+
+/**
+ * Create a new package with no authors, replacing any file at `path`. On success, `*out` is
+ * set to the package, which must be closed with [`ea_package_close`].
+ *
+ * # Safety
+ *
+ * `path` and `title` must be NUL-terminated strings, and `out` must be valid to write a
+ * pointer to.
+ */
+EaError ea_package_create(const char *path, const char *title, EaPackage **out);
+
+/**
+ * Open an existing package. On success, `*out` is set to the package, which must be closed
+ * with [`ea_package_close`].
+ *
+ * # Safety
+ *
+ * `path` must be a NUL-terminated string, and `out` must be valid to write a pointer to.
+ */
+EaError ea_package_open(const char *path, EaPackage **out);
+
+/**
+ * Save a package to disk.
+ *
+ * # Safety
+ *
+ * `package` must be an open package.
+ */
+EaError ea_package_save(EaPackage *package);
+
+/**
+ * Close a package without saving it, releasing its lock. Passing NULL does nothing.
+ *
+ * # Safety
+ *
+ * `package` must be NULL or an open package, which mustn't be used again.
+ */
+void ea_package_close(EaPackage *package);
+
+/**
+ * Add an author to a package. `email` may be NULL.
+ *
+ * # Safety
+ *
+ * `package` must be an open package, `name` must be a NUL-terminated string and `email` must
+ * be NULL or a NUL-terminated string.
+ */
+EaError ea_package_add_author(EaPackage *package, const char *name, const char *email);
+
+/**
+ * Get how many test cases are in a package.
+ *
+ * # Safety
+ *
+ * `package` must be an open package, and `count_out` must be valid to write to.
+ */
+EaError ea_package_test_case_count(EaPackage *package, size_t *count_out);
+
+/**
+ * Get the ID of the test case at a zero-based index in a package, writing it to `id_out`.
+ *
+ * # Safety
+ *
+ * `package` must be an open package, and `id_out` must point to at least [`EA_ID_LENGTH`]
+ * writable bytes.
+ */
+EaError ea_package_test_case_id(EaPackage *package, size_t index, char *id_out);
+
+/**
+ * Export a package, redacting secrets if the package's settings ask for it.
+ *
+ * # Safety
+ *
+ * `package` must be an open package, and `path` must be a NUL-terminated string.
+ */
+EaError ea_package_export(EaPackage *package, EaExportFormat format, const char *path);
+
+/**
+ * Create a test case, executed now, writing its ID to `id_out`.
+ *
+ * # Safety
+ *
+ * `package` must be an open package, `title` must be a NUL-terminated string, and `id_out`
+ * must point to at least [`EA_ID_LENGTH`] writable bytes.
+ */
+EaError ea_test_case_create(EaPackage *package, const char *title, char *id_out);
+
+/**
+ * Set the status of a test case.
+ *
+ * # Safety
+ *
+ * `package` must be an open package, and `test_case` must be a NUL-terminated string.
+ */
+EaError ea_test_case_set_status(EaPackage *package, const char *test_case, EaStatus status);
+
+/**
+ * Set the value of a custom metadata field of a test case, or remove it if `value` is NULL.
+ *
+ * # Safety
+ *
+ * `package` must be an open package, `test_case` and `field` must be NUL-terminated strings,
+ * and `value` must be NULL or a NUL-terminated string.
+ */
+EaError ea_test_case_set_custom_field(EaPackage *package,
+                                      const char *test_case,
+                                      const char *field,
+                                      const char *value);
+
+/**
+ * Add evidence to a test case from `length` bytes of data. `filename` is required for file
+ * evidence and ignored otherwise. `caption` may be NULL.
+ *
+ * # Safety
+ *
+ * `package` must be an open package, `test_case` must be a NUL-terminated string, `data` must
+ * point to at least `length` readable bytes (or may be NULL if `length` is 0), and `filename`
+ * and `caption` must be NULL or NUL-terminated strings.
+ */
+EaError ea_evidence_add_bytes(EaPackage *package,
+                              const char *test_case,
+                              EaEvidenceKind kind,
+                              const uint8_t *data,
+                              size_t length,
+                              const char *filename,
+                              const char *caption);
+
+/**
+ * Add evidence to a test case from a file. `caption` may be NULL.
+ *
+ * # Safety
+ *
+ * `package` must be an open package, `test_case` and `path` must be NUL-terminated strings,
+ * and `caption` must be NULL or a NUL-terminated string.
+ */
+EaError ea_evidence_add_file(EaPackage *package,
+                             const char *test_case,
+                             EaEvidenceKind kind,
+                             const char *path,
+                             const char *caption);
+
+/**
+ * Export a test case, redacting secrets if the package's settings ask for it.
+ *
+ * # Safety
+ *
+ * `package` must be an open package, and `test_case` and `path` must be NUL-terminated
+ * strings.
+ */
+EaError ea_test_case_export(EaPackage *package,
+                            const char *test_case,
+                            EaExportFormat format,
+                            const char *path);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* EVIDENCEANGEL_H */
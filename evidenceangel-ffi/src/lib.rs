@@ -0,0 +1,800 @@
+#![deny(missing_docs)]
+#![deny(clippy::missing_docs_in_private_items)]
+#![warn(clippy::pedantic)]
+
+//! # `EvidenceAngel` C API
+//!
+//! A C-compatible API to work with evidence packages from automation frameworks written in
+//! other languages. The header for this API is `include/evidenceangel.h`.
+//!
+//! Every function that can fail returns an [`EaError`]. When it isn't [`EaError::Ok`],
+//! [`ea_last_error_message`] describes what went wrong.
+
+use std::{
+    cell::RefCell,
+    ffi::{CStr, CString, c_char},
+    fmt, fs,
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+    ptr, slice,
+};
+
+use evidenceangel::{
+    Author, EvidenceBuilder, EvidencePackage, HttpExchange, Log, LogFormat, SecretRedactor,
+    TestCase, TestCasePassStatus,
+    exporters::{ExportOptions, Exporter, ExporterRegistry},
+};
+use uuid::Uuid;
+
+/// The length of a buffer that can hold a test case ID, including the terminating NUL.
+pub const EA_ID_LENGTH: usize = 37;
+
+/// The result of an API call. The codes from [`EaError::LockNotObtained`] to
+/// [`EaError::OtherExportError`] mirror the errors raised by `EvidenceAngel` itself.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum EaError {
+    /// The call succeeded.
+    Ok = 0,
+    /// The package is already open elsewhere.
+    LockNotObtained = 1,
+    /// An I/O error from the system.
+    Io = 2,
+    /// The package couldn't be read or written as a ZIP file.
+    Zip = 3,
+    /// The package is corrupt.
+    CorruptEvidencePackage = 4,
+    /// The package manifest isn't valid JSON.
+    InvalidManifest = 5,
+    /// The package couldn't be created.
+    FailedToCreatePackage = 6,
+    /// A test case couldn't be saved.
+    FailedToSaveTestCase = 7,
+    /// A test case couldn't be read.
+    InvalidTestCase = 8,
+    /// An image couldn't be decoded or encoded, or this library was built without support for
+    /// processing images.
+    Image = 9,
+    /// The data of some evidence isn't valid for its kind.
+    InvalidEvidenceData = 10,
+    /// Some media is missing from the package.
+    MediaMissing = 11,
+    /// The package manifest failed schema validation.
+    ManifestSchemaValidationFailed = 12,
+    /// A test case failed schema validation.
+    TestCaseSchemaValidationFailed = 13,
+    /// A rule for redacting secrets isn't valid.
+    InvalidSecretRule = 14,
+    /// A test case template isn't valid.
+    InvalidTemplate = 15,
+    /// The test case doesn't exist.
+    DoesntExist = 16,
+    /// An export failed.
+    OtherExportError = 17,
+    /// A compression level isn't accepted by the compression algorithm.
+    InvalidCompressionLevel = 18,
+    /// A required pointer argument was NULL.
+    NullArgument = 100,
+    /// A string argument wasn't valid UTF-8.
+    InvalidUtf8 = 101,
+    /// An argument wasn't valid, such as an unknown kind or a malformed test case ID.
+    InvalidArgument = 102,
+    /// `EvidenceAngel` panicked. The package should be closed without saving.
+    Panic = 103,
+}
+
+/// The status of a test case.
+pub type EaStatus = u32;
+/// The test case hasn't been run.
+pub const EA_STATUS_NONE: EaStatus = 0;
+/// The test case passed.
+pub const EA_STATUS_PASS: EaStatus = 1;
+/// The test case failed.
+pub const EA_STATUS_FAIL: EaStatus = 2;
+
+/// The kind of evidence to add.
+pub type EaEvidenceKind = u32;
+/// Plain text, from UTF-8 data.
+pub const EA_EVIDENCE_KIND_TEXT: EaEvidenceKind = 0;
+/// Rich text, from UTF-8 data.
+pub const EA_EVIDENCE_KIND_RICH_TEXT: EaEvidenceKind = 1;
+/// A PNG or JPEG image.
+pub const EA_EVIDENCE_KIND_IMAGE: EaEvidenceKind = 2;
+/// Any file.
+pub const EA_EVIDENCE_KIND_FILE: EaEvidenceKind = 3;
+/// An HTTP request and response, from UTF-8 data.
+pub const EA_EVIDENCE_KIND_HTTP: EaEvidenceKind = 4;
+/// A log, from UTF-8 data in any format `EvidenceAngel` can detect.
+pub const EA_EVIDENCE_KIND_LOG: EaEvidenceKind = 5;
+/// An MP4 or `WebM` video.
+pub const EA_EVIDENCE_KIND_VIDEO: EaEvidenceKind = 6;
+
+/// The format to export to.
+pub type EaExportFormat = u32;
+/// An HTML document.
+pub const EA_EXPORT_FORMAT_HTML: EaExportFormat = 0;
+/// An Excel workbook.
+pub const EA_EXPORT_FORMAT_EXCEL: EaExportFormat = 1;
+/// A ZIP of the evidence files.
+pub const EA_EXPORT_FORMAT_ZIP_OF_FILES: EaExportFormat = 2;
+
+/// An open evidence package. This is only ever handled by pointer.
+pub struct EaPackage {
+    /// The package.
+    package: EvidencePackage,
+}
+
+thread_local! {
+    /// The message describing the last error on this thread.
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// An error raised by an API call.
+enum FfiError {
+    /// An error raised by `EvidenceAngel`.
+    Package(evidenceangel::Error),
+    /// A required pointer argument was NULL.
+    NullArgument(&'static str),
+    /// A string argument wasn't valid UTF-8.
+    InvalidUtf8(&'static str),
+    /// An argument wasn't valid.
+    InvalidArgument(String),
+}
+
+impl From<evidenceangel::Error> for FfiError {
+    fn from(value: evidenceangel::Error) -> Self {
+        Self::Package(value)
+    }
+}
+
+impl fmt::Display for FfiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Package(e) => write!(f, "{e}"),
+            Self::NullArgument(name) => write!(f, "{name} must not be NULL"),
+            Self::InvalidUtf8(name) => write!(f, "{name} must be valid UTF-8"),
+            Self::InvalidArgument(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl FfiError {
+    /// Get the code returned to C for this error.
+    #[allow(
+        clippy::match_same_arms,
+        clippy::match_wildcard_for_single_variants,
+        reason = "`Error::Image` can't be named if evidenceangel is built without image processing"
+    )]
+    fn code(&self) -> EaError {
+        use evidenceangel::Error;
+
+        match self {
+            Self::Package(e) => match e {
+                Error::LockNotObtained => EaError::LockNotObtained,
+                Error::Io(_) => EaError::Io,
+                Error::Zip(_) => EaError::Zip,
+                Error::CorruptEvidencePackage(_) => EaError::CorruptEvidencePackage,
+                Error::InvalidManifest(_) => EaError::InvalidManifest,
+                Error::FailedToCreatePackage(_) => EaError::FailedToCreatePackage,
+                Error::FailedToSaveTestCase(_) => EaError::FailedToSaveTestCase,
+                Error::InvalidTestCase(_, _) => EaError::InvalidTestCase,
+                Error::ImageProcessingUnavailable => EaError::Image,
+                Error::InvalidEvidenceData(_) => EaError::InvalidEvidenceData,
+                Error::MediaMissing(_) => EaError::MediaMissing,
+                Error::ManifestSchemaValidationFailed => EaError::ManifestSchemaValidationFailed,
+                Error::TestCaseSchemaValidationFailed => EaError::TestCaseSchemaValidationFailed,
+                Error::InvalidSecretRule(_) => EaError::InvalidSecretRule,
+                Error::InvalidTemplate(_) => EaError::InvalidTemplate,
+                Error::InvalidCompressionLevel(_) => EaError::InvalidCompressionLevel,
+                Error::DoesntExist(_) => EaError::DoesntExist,
+                Error::OtherExportError(_) => EaError::OtherExportError,
+                // This can only be `Error::Image`, which exists whenever evidenceangel is built
+                // with image processing, even if it is turned on by another crate in the build
+                #[allow(
+                    unreachable_patterns,
+                    reason = "unreachable if evidenceangel is built without image processing"
+                )]
+                _ => EaError::Image,
+            },
+            Self::NullArgument(_) => EaError::NullArgument,
+            Self::InvalidUtf8(_) => EaError::InvalidUtf8,
+            Self::InvalidArgument(_) => EaError::InvalidArgument,
+        }
+    }
+}
+
+/// Set the message describing the last error on this thread.
+fn set_last_error(message: Option<String>) {
+    // Messages can't contain NUL, so cut them short at the first one
+    let message = message.map(|message| {
+        let end = message.find('\0').unwrap_or(message.len());
+        CString::new(&message[..end]).unwrap_or_default()
+    });
+    LAST_ERROR.with_borrow_mut(|last| *last = message);
+}
+
+/// Run the body of an API call, converting its errors and panics into an error code.
+fn ffi_call<F>(body: F) -> EaError
+where
+    F: FnOnce() -> Result<(), FfiError>,
+{
+    match panic::catch_unwind(AssertUnwindSafe(body)) {
+        Ok(Ok(())) => {
+            set_last_error(None);
+            EaError::Ok
+        }
+        Ok(Err(e)) => {
+            set_last_error(Some(e.to_string()));
+            e.code()
+        }
+        Err(payload) => {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(ToString::to_string)
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".to_string());
+            set_last_error(Some(format!("EvidenceAngel panicked: {message}")));
+            EaError::Panic
+        }
+    }
+}
+
+/// Read a required string argument.
+///
+/// # Safety
+///
+/// `ptr` must be NULL or point to a NUL-terminated string that outlives the returned reference.
+unsafe fn str_arg<'a>(ptr: *const c_char, name: &'static str) -> Result<&'a str, FfiError> {
+    // SAFETY: upheld by the caller
+    unsafe { opt_str_arg(ptr, name) }?.ok_or(FfiError::NullArgument(name))
+}
+
+/// Read an optional string argument, which is [`None`] if NULL.
+///
+/// # Safety
+///
+/// `ptr` must be NULL or point to a NUL-terminated string that outlives the returned reference.
+unsafe fn opt_str_arg<'a>(
+    ptr: *const c_char,
+    name: &'static str,
+) -> Result<Option<&'a str>, FfiError> {
+    if ptr.is_null() {
+        return Ok(None);
+    }
+    // SAFETY: upheld by the caller
+    unsafe { CStr::from_ptr(ptr) }
+        .to_str()
+        .map(Some)
+        .map_err(|_| FfiError::InvalidUtf8(name))
+}
+
+/// Read a package argument.
+///
+/// # Safety
+///
+/// `package` must be NULL or a package returned by [`ea_package_create`] or
+/// [`ea_package_open`] which hasn't been closed, and isn't used elsewhere at the same time.
+unsafe fn package_arg<'a>(package: *mut EaPackage) -> Result<&'a mut EvidencePackage, FfiError> {
+    // SAFETY: upheld by the caller
+    unsafe { package.as_mut() }
+        .map(|package| &mut package.package)
+        .ok_or(FfiError::NullArgument("package"))
+}
+
+/// Parse a test case ID argument.
+///
+/// # Safety
+///
+/// `ptr` must be NULL or point to a NUL-terminated string.
+unsafe fn test_case_id_arg(ptr: *const c_char) -> Result<Uuid, FfiError> {
+    // SAFETY: upheld by the caller
+    let id = unsafe { str_arg(ptr, "test_case") }?;
+    Uuid::parse_str(id)
+        .map_err(|_| FfiError::InvalidArgument(format!("{id} isn't a valid test case ID")))
+}
+
+/// Find a test case in a package.
+fn test_case(package: &mut EvidencePackage, id: Uuid) -> Result<&mut TestCase, FfiError> {
+    package
+        .test_case_mut(id)?
+        .ok_or(FfiError::Package(evidenceangel::Error::DoesntExist(id)))
+}
+
+/// Write a test case ID into a buffer of [`EA_ID_LENGTH`] bytes.
+///
+/// # Safety
+///
+/// `buffer` must be NULL or point to at least [`EA_ID_LENGTH`] writable bytes.
+unsafe fn write_id(buffer: *mut c_char, id: Uuid) -> Result<(), FfiError> {
+    if buffer.is_null() {
+        return Err(FfiError::NullArgument("id_out"));
+    }
+    let id = CString::new(id.hyphenated().to_string()).unwrap_or_default();
+    let bytes = id.as_bytes_with_nul();
+    // SAFETY: the ID and its NUL are exactly EA_ID_LENGTH bytes, which the caller guarantees
+    // fit in the buffer
+    unsafe { ptr::copy_nonoverlapping(bytes.as_ptr().cast(), buffer, bytes.len()) };
+    Ok(())
+}
+
+/// Get a redactor if the package's settings redact secrets on export.
+fn export_redactor(package: &EvidencePackage) -> Result<Option<SecretRedactor>, FfiError> {
+    match package.metadata().redaction() {
+        Some(settings) if *settings.redact_on_export() => Ok(Some(SecretRedactor::new(settings)?)),
+        _ => Ok(None),
+    }
+}
+
+/// Export a package or test case with an exporter, redacting secrets if the package's settings
+/// ask for it.
+fn export(
+    exporter: &mut dyn Exporter,
+    package: &mut EvidencePackage,
+    case: Option<Uuid>,
+    path: PathBuf,
+) -> Result<(), FfiError> {
+    let options = ExportOptions::default();
+    let redactor = export_redactor(package)?;
+    match (case, redactor) {
+        (None, None) => exporter.export_package(package, path, &options)?,
+        (None, Some(redactor)) => {
+            exporter.export_package_redacted(package, path, &options, &redactor)?;
+        }
+        (Some(case), None) => exporter.export_case(package, case, path, &options)?,
+        (Some(case), Some(redactor)) => {
+            exporter.export_case_redacted(package, case, path, &options, &redactor)?;
+        }
+    }
+    Ok(())
+}
+
+/// Export a package or test case in a format.
+fn export_as(
+    format: EaExportFormat,
+    package: &mut EvidencePackage,
+    case: Option<Uuid>,
+    path: PathBuf,
+) -> Result<(), FfiError> {
+    let id = match format {
+        EA_EXPORT_FORMAT_HTML => "html",
+        EA_EXPORT_FORMAT_EXCEL => "excel",
+        EA_EXPORT_FORMAT_ZIP_OF_FILES => "zip-of-files",
+        _ => {
+            return Err(FfiError::InvalidArgument(format!(
+                "{format} isn't a valid export format"
+            )));
+        }
+    };
+    let mut exporter = ExporterRegistry::default()
+        .create(id)
+        .ok_or_else(|| FfiError::InvalidArgument(format!("{id} exports aren't available")))?;
+    export(exporter.as_mut(), package, case, path)
+}
+
+/// Create a builder for evidence of a kind from raw data.
+fn evidence_builder(
+    kind: EaEvidenceKind,
+    data: Vec<u8>,
+    filename: Option<&str>,
+) -> Result<EvidenceBuilder, FfiError> {
+    /// Read the data as text.
+    fn text(data: Vec<u8>) -> Result<String, FfiError> {
+        String::from_utf8(data).map_err(|_| FfiError::InvalidUtf8("data"))
+    }
+
+    Ok(match kind {
+        EA_EVIDENCE_KIND_TEXT => EvidenceBuilder::text(text(data)?),
+        EA_EVIDENCE_KIND_RICH_TEXT => EvidenceBuilder::rich_text(text(data)?),
+        EA_EVIDENCE_KIND_IMAGE => EvidenceBuilder::screenshot(data),
+        EA_EVIDENCE_KIND_FILE => {
+            EvidenceBuilder::file_data(filename.ok_or(FfiError::NullArgument("filename"))?, data)
+        }
+        EA_EVIDENCE_KIND_HTTP => EvidenceBuilder::http_exchange(&HttpExchange::parse(&text(data)?)),
+        EA_EVIDENCE_KIND_LOG => {
+            EvidenceBuilder::structured(&Log::parse(&text(data)?, LogFormat::Auto))
+        }
+        EA_EVIDENCE_KIND_VIDEO => EvidenceBuilder::video(data),
+        _ => {
+            return Err(FfiError::InvalidArgument(format!(
+                "{kind} isn't a valid evidence kind"
+            )));
+        }
+    })
+}
+
+/// Add evidence built from raw data to a test case.
+fn add_evidence(
+    package: &mut EvidencePackage,
+    id: Uuid,
+    builder: EvidenceBuilder,
+    caption: Option<&str>,
+) -> Result<(), FfiError> {
+    // Check the test case exists before registering any media
+    test_case(package, id)?;
+    let builder = match caption {
+        Some(caption) => builder.caption(caption),
+        None => builder,
+    };
+    let evidence = builder.build(package)?;
+    test_case(package, id)?.evidence_mut().push(evidence);
+    Ok(())
+}
+
+/// Get the version of this library, such as `1.5.0`. The string is statically allocated.
+#[unsafe(no_mangle)]
+pub extern "C" fn ea_version() -> *const c_char {
+    concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr().cast()
+}
+
+/// Get a message describing the last error raised on this thread, or NULL if the last call
+/// succeeded. The string is valid until the next call on this thread.
+#[unsafe(no_mangle)]
+pub extern "C" fn ea_last_error_message() -> *const c_char {
+    LAST_ERROR.with_borrow(|last| {
+        last.as_ref()
+            .map_or(ptr::null(), |message| message.as_ptr())
+    })
+}
+
+/// Create a new package with no authors, replacing any file at `path`. On success, `*out` is
+/// set to the package, which must be closed with [`ea_package_close`].
+///
+/// # Safety
+///
+/// `path` and `title` must be NUL-terminated strings, and `out` must be valid to write a
+/// pointer to.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ea_package_create(
+    path: *const c_char,
+    title: *const c_char,
+    out: *mut *mut EaPackage,
+) -> EaError {
+    ffi_call(|| {
+        // SAFETY: upheld by the caller
+        let path = unsafe { str_arg(path, "path") }?;
+        // SAFETY: upheld by the caller
+        let title = unsafe { str_arg(title, "title") }?;
+        if out.is_null() {
+            return Err(FfiError::NullArgument("out"));
+        }
+        let package = EvidencePackage::new(PathBuf::from(path), title.to_string(), vec![])?;
+        // SAFETY: checked for NULL above, and otherwise upheld by the caller
+        unsafe { *out = Box::into_raw(Box::new(EaPackage { package })) };
+        Ok(())
+    })
+}
+
+/// Open an existing package. On success, `*out` is set to the package, which must be closed
+/// with [`ea_package_close`].
+///
+/// # Safety
+///
+/// `path` must be a NUL-terminated string, and `out` must be valid to write a pointer to.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ea_package_open(path: *const c_char, out: *mut *mut EaPackage) -> EaError {
+    ffi_call(|| {
+        // SAFETY: upheld by the caller
+        let path = unsafe { str_arg(path, "path") }?;
+        if out.is_null() {
+            return Err(FfiError::NullArgument("out"));
+        }
+        let package = EvidencePackage::open(PathBuf::from(path))?;
+        // SAFETY: checked for NULL above, and otherwise upheld by the caller
+        unsafe { *out = Box::into_raw(Box::new(EaPackage { package })) };
+        Ok(())
+    })
+}
+
+/// Save a package to disk.
+///
+/// # Safety
+///
+/// `package` must be an open package.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ea_package_save(package: *mut EaPackage) -> EaError {
+    ffi_call(|| {
+        // SAFETY: upheld by the caller
+        unsafe { package_arg(package) }?.save()?;
+        Ok(())
+    })
+}
+
+/// Close a package without saving it, releasing its lock. Passing NULL does nothing.
+///
+/// # Safety
+///
+/// `package` must be NULL or an open package, which mustn't be used again.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ea_package_close(package: *mut EaPackage) {
+    if !package.is_null() {
+        // SAFETY: upheld by the caller
+        drop(unsafe { Box::from_raw(package) });
+    }
+}
+
+/// Add an author to a package. `email` may be NULL.
+///
+/// # Safety
+///
+/// `package` must be an open package, `name` must be a NUL-terminated string and `email` must
+/// be NULL or a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ea_package_add_author(
+    package: *mut EaPackage,
+    name: *const c_char,
+    email: *const c_char,
+) -> EaError {
+    ffi_call(|| {
+        // SAFETY: upheld by the caller
+        let package = unsafe { package_arg(package) }?;
+        // SAFETY: upheld by the caller
+        let name = unsafe { str_arg(name, "name") }?;
+        // SAFETY: upheld by the caller
+        let email = unsafe { opt_str_arg(email, "email") }?;
+        let author = match email {
+            Some(email) => Author::new_with_email(name, email),
+            None => Author::new(name),
+        };
+        package.metadata_mut().authors_mut().push(author);
+        Ok(())
+    })
+}
+
+/// Get how many test cases are in a package.
+///
+/// # Safety
+///
+/// `package` must be an open package, and `count_out` must be valid to write to.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ea_package_test_case_count(
+    package: *mut EaPackage,
+    count_out: *mut usize,
+) -> EaError {
+    ffi_call(|| {
+        // SAFETY: upheld by the caller
+        let package = unsafe { package_arg(package) }?;
+        if count_out.is_null() {
+            return Err(FfiError::NullArgument("count_out"));
+        }
+        let count = package.test_case_iter()?.count();
+        // SAFETY: checked for NULL above, and otherwise upheld by the caller
+        unsafe { *count_out = count };
+        Ok(())
+    })
+}
+
+/// Get the ID of the test case at a zero-based index in a package, writing it to `id_out`.
+///
+/// # Safety
+///
+/// `package` must be an open package, and `id_out` must point to at least [`EA_ID_LENGTH`]
+/// writable bytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ea_package_test_case_id(
+    package: *mut EaPackage,
+    index: usize,
+    id_out: *mut c_char,
+) -> EaError {
+    ffi_call(|| {
+        // SAFETY: upheld by the caller
+        let package = unsafe { package_arg(package) }?;
+        let id = *package
+            .test_case_iter()?
+            .nth(index)
+            .ok_or_else(|| {
+                FfiError::InvalidArgument(format!("there is no test case at index {index}"))
+            })?
+            .id();
+        // SAFETY: upheld by the caller
+        unsafe { write_id(id_out, id) }
+    })
+}
+
+/// Export a package, redacting secrets if the package's settings ask for it.
+///
+/// # Safety
+///
+/// `package` must be an open package, and `path` must be a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ea_package_export(
+    package: *mut EaPackage,
+    format: EaExportFormat,
+    path: *const c_char,
+) -> EaError {
+    ffi_call(|| {
+        // SAFETY: upheld by the caller
+        let package = unsafe { package_arg(package) }?;
+        // SAFETY: upheld by the caller
+        let path = unsafe { str_arg(path, "path") }?;
+        export_as(format, package, None, PathBuf::from(path))
+    })
+}
+
+/// Create a test case, executed now, writing its ID to `id_out`.
+///
+/// # Safety
+///
+/// `package` must be an open package, `title` must be a NUL-terminated string, and `id_out`
+/// must point to at least [`EA_ID_LENGTH`] writable bytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ea_test_case_create(
+    package: *mut EaPackage,
+    title: *const c_char,
+    id_out: *mut c_char,
+) -> EaError {
+    ffi_call(|| {
+        // SAFETY: upheld by the caller
+        let package = unsafe { package_arg(package) }?;
+        // SAFETY: upheld by the caller
+        let title = unsafe { str_arg(title, "title") }?;
+        if id_out.is_null() {
+            return Err(FfiError::NullArgument("id_out"));
+        }
+        let id = *package.create_test_case(title)?.id();
+        // SAFETY: upheld by the caller
+        unsafe { write_id(id_out, id) }
+    })
+}
+
+/// Set the status of a test case.
+///
+/// # Safety
+///
+/// `package` must be an open package, and `test_case` must be a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ea_test_case_set_status(
+    package: *mut EaPackage,
+    test_case: *const c_char,
+    status: EaStatus,
+) -> EaError {
+    ffi_call(|| {
+        // SAFETY: upheld by the caller
+        let package = unsafe { package_arg(package) }?;
+        // SAFETY: upheld by the caller
+        let id = unsafe { test_case_id_arg(test_case) }?;
+        let status = match status {
+            EA_STATUS_NONE => None,
+            EA_STATUS_PASS => Some(TestCasePassStatus::Pass),
+            EA_STATUS_FAIL => Some(TestCasePassStatus::Fail),
+            _ => {
+                return Err(FfiError::InvalidArgument(format!(
+                    "{status} isn't a valid status"
+                )));
+            }
+        };
+        self::test_case(package, id)?
+            .metadata_mut()
+            .set_passed(status);
+        Ok(())
+    })
+}
+
+/// Set the value of a custom metadata field of a test case, or remove it if `value` is NULL.
+///
+/// # Safety
+///
+/// `package` must be an open package, `test_case` and `field` must be NUL-terminated strings,
+/// and `value` must be NULL or a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ea_test_case_set_custom_field(
+    package: *mut EaPackage,
+    test_case: *const c_char,
+    field: *const c_char,
+    value: *const c_char,
+) -> EaError {
+    ffi_call(|| {
+        // SAFETY: upheld by the caller
+        let package = unsafe { package_arg(package) }?;
+        // SAFETY: upheld by the caller
+        let id = unsafe { test_case_id_arg(test_case) }?;
+        // SAFETY: upheld by the caller
+        let field = unsafe { str_arg(field, "field") }?;
+        // SAFETY: upheld by the caller
+        let value = unsafe { opt_str_arg(value, "value") }?;
+        let custom = self::test_case(package, id)?.metadata_mut().custom_mut();
+        match value {
+            Some(value) => {
+                custom.insert(field.to_string(), value.to_string());
+            }
+            None => {
+                custom.remove(field);
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Add evidence to a test case from `length` bytes of data. `filename` is required for file
+/// evidence and ignored otherwise. `caption` may be NULL.
+///
+/// # Safety
+///
+/// `package` must be an open package, `test_case` must be a NUL-terminated string, `data` must
+/// point to at least `length` readable bytes (or may be NULL if `length` is 0), and `filename`
+/// and `caption` must be NULL or NUL-terminated strings.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ea_evidence_add_bytes(
+    package: *mut EaPackage,
+    test_case: *const c_char,
+    kind: EaEvidenceKind,
+    data: *const u8,
+    length: usize,
+    filename: *const c_char,
+    caption: *const c_char,
+) -> EaError {
+    ffi_call(|| {
+        // SAFETY: upheld by the caller
+        let package = unsafe { package_arg(package) }?;
+        // SAFETY: upheld by the caller
+        let id = unsafe { test_case_id_arg(test_case) }?;
+        let data = if length == 0 {
+            vec![]
+        } else if data.is_null() {
+            return Err(FfiError::NullArgument("data"));
+        } else {
+            // SAFETY: checked for NULL above, and otherwise upheld by the caller
+            unsafe { slice::from_raw_parts(data, length) }.to_vec()
+        };
+        // SAFETY: upheld by the caller
+        let filename = unsafe { opt_str_arg(filename, "filename") }?;
+        // SAFETY: upheld by the caller
+        let caption = unsafe { opt_str_arg(caption, "caption") }?;
+        let builder = evidence_builder(kind, data, filename)?;
+        add_evidence(package, id, builder, caption)
+    })
+}
+
+/// Add evidence to a test case from a file. `caption` may be NULL.
+///
+/// # Safety
+///
+/// `package` must be an open package, `test_case` and `path` must be NUL-terminated strings,
+/// and `caption` must be NULL or a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ea_evidence_add_file(
+    package: *mut EaPackage,
+    test_case: *const c_char,
+    kind: EaEvidenceKind,
+    path: *const c_char,
+    caption: *const c_char,
+) -> EaError {
+    ffi_call(|| {
+        // SAFETY: upheld by the caller
+        let package = unsafe { package_arg(package) }?;
+        // SAFETY: upheld by the caller
+        let id = unsafe { test_case_id_arg(test_case) }?;
+        // SAFETY: upheld by the caller
+        let path = Path::new(unsafe { str_arg(path, "path") }?);
+        // SAFETY: upheld by the caller
+        let caption = unsafe { opt_str_arg(caption, "caption") }?;
+        let data = fs::read(path).map_err(evidenceangel::Error::from)?;
+        let filename = path.file_name().map(|name| name.to_string_lossy());
+        let builder = evidence_builder(kind, data, filename.as_deref())?;
+        add_evidence(package, id, builder, caption)
+    })
+}
+
+/// Export a test case, redacting secrets if the package's settings ask for it.
+///
+/// # Safety
+///
+/// `package` must be an open package, and `test_case` and `path` must be NUL-terminated
+/// strings.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ea_test_case_export(
+    package: *mut EaPackage,
+    test_case: *const c_char,
+    format: EaExportFormat,
+    path: *const c_char,
+) -> EaError {
+    ffi_call(|| {
+        // SAFETY: upheld by the caller
+        let package = unsafe { package_arg(package) }?;
+        // SAFETY: upheld by the caller
+        let id = unsafe { test_case_id_arg(test_case) }?;
+        // SAFETY: upheld by the caller
+        let path = unsafe { str_arg(path, "path") }?;
+        self::test_case(package, id)?;
+        export_as(format, package, Some(id), PathBuf::from(path))
+    })
+}
@@ -0,0 +1,161 @@
+/*
+ * Exercises the EvidenceAngel C API. This is built and run by tests/c_api.rs, which passes a
+ * directory to write packages and exports into.
+ */
+
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "evidenceangel.h"
+
+static int failures = 0;
+
+#define CHECK(expr)                                                                           \
+    do {                                                                                      \
+        if (!(expr)) {                                                                        \
+            const char *message = ea_last_error_message();                                   \
+            fprintf(stderr, "%s:%d: check failed: %s (%s)\n", __FILE__, __LINE__, #expr,      \
+                    message ? message : "no error message");                                  \
+            failures++;                                                                       \
+        }                                                                                     \
+    } while (0)
+
+#define CHECK_OK(expr) CHECK((expr) == EA_ERROR_OK)
+
+/* A 1x1 green PNG. */
+static const uint8_t PNG[] = {
+    0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a, 0x00, 0x00, 0x00, 0x0d, 0x49, 0x48,
+    0x44, 0x52, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x08, 0x02, 0x00, 0x00,
+    0x00, 0x90, 0x77, 0x53, 0xde, 0x00, 0x00, 0x00, 0x0c, 0x49, 0x44, 0x41, 0x54, 0x78,
+    0x9c, 0x63, 0xf8, 0xcf, 0xc0, 0x00, 0x00, 0x03, 0x01, 0x01, 0x00, 0xc9, 0xfe, 0x92,
+    0xef, 0x00, 0x00, 0x00, 0x00, 0x49, 0x45, 0x4e, 0x44, 0xae, 0x42, 0x60, 0x82,
+};
+
+static const char LOG[] = "2024-05-01T10:00:00Z INFO Service started\n"
+                          "2024-05-01T10:00:01Z ERROR Connection refused\n";
+
+static const char HTTP[] = "GET /health HTTP/1.1\r\nHost: localhost\r\n\r\n"
+                           "\x1e"
+                           "HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\n\r\nOK";
+
+static void join(char *out, size_t size, const char *dir, const char *name) {
+    snprintf(out, size, "%s/%s", dir, name);
+}
+
+int main(int argc, char **argv) {
+    if (argc != 2) {
+        fprintf(stderr, "usage: %s <output directory>\n", argv[0]);
+        return 2;
+    }
+    const char *dir = argv[1];
+    char package_path[1024], html_path[1024], excel_path[1024], zip_path[1024],
+        case_path[1024], notes_path[1024];
+    join(package_path, sizeof package_path, dir, "package.evp");
+    join(html_path, sizeof html_path, dir, "package.html");
+    join(excel_path, sizeof excel_path, dir, "package.xlsx");
+    join(zip_path, sizeof zip_path, dir, "package.zip");
+    join(case_path, sizeof case_path, dir, "case.html");
+    join(notes_path, sizeof notes_path, dir, "notes.txt");
+
+    CHECK(ea_version() != NULL && strlen(ea_version()) > 0);
+
+    /* Create a package with a test case holding each kind of evidence */
+    EaPackage *package = NULL;
+    CHECK_OK(ea_package_create(package_path, "C API tests", &package));
+    CHECK(package != NULL);
+    CHECK(ea_last_error_message() == NULL);
+    CHECK_OK(ea_package_add_author(package, "Automation", NULL));
+    CHECK_OK(ea_package_add_author(package, "Tester", "tester@example.com"));
+
+    char id[EA_ID_LENGTH];
+    CHECK_OK(ea_test_case_create(package, "Health check", id));
+    CHECK(strlen(id) == EA_ID_LENGTH - 1);
+    CHECK_OK(ea_test_case_set_status(package, id, EA_STATUS_PASS));
+    CHECK_OK(ea_test_case_set_custom_field(package, id, "build", "1234"));
+    CHECK_OK(ea_test_case_set_custom_field(package, id, "build", NULL));
+
+    const char *text = "The service reported it is healthy";
+    CHECK_OK(ea_evidence_add_bytes(package, id, EA_EVIDENCE_KIND_TEXT, (const uint8_t *)text,
+                                   strlen(text), NULL, "Health"));
+    const char *rich = "# Summary\n\nAll **good**.";
+    CHECK_OK(ea_evidence_add_bytes(package, id, EA_EVIDENCE_KIND_RICH_TEXT,
+                                   (const uint8_t *)rich, strlen(rich), NULL, NULL));
+    CHECK_OK(ea_evidence_add_bytes(package, id, EA_EVIDENCE_KIND_IMAGE, PNG, sizeof PNG, NULL,
+                                   "Screenshot"));
+    CHECK_OK(ea_evidence_add_bytes(package, id, EA_EVIDENCE_KIND_LOG, (const uint8_t *)LOG,
+                                   strlen(LOG), NULL, "Service log"));
+    CHECK_OK(ea_evidence_add_bytes(package, id, EA_EVIDENCE_KIND_HTTP, (const uint8_t *)HTTP,
+                                   strlen(HTTP), NULL, NULL));
+    CHECK_OK(ea_evidence_add_bytes(package, id, EA_EVIDENCE_KIND_FILE, (const uint8_t *)text,
+                                   strlen(text), "output.txt", NULL));
+
+    FILE *notes = fopen(notes_path, "w");
+    CHECK(notes != NULL);
+    if (notes) {
+        fputs("Notes from the test run\n", notes);
+        fclose(notes);
+    }
+    CHECK_OK(ea_evidence_add_file(package, id, EA_EVIDENCE_KIND_FILE, notes_path, "Notes"));
+
+    char failing_id[EA_ID_LENGTH];
+    CHECK_OK(ea_test_case_create(package, "Login", failing_id));
+    CHECK_OK(ea_test_case_set_status(package, failing_id, EA_STATUS_FAIL));
+
+    CHECK_OK(ea_package_save(package));
+
+    /* Export in every format */
+    CHECK_OK(ea_package_export(package, EA_EXPORT_FORMAT_HTML, html_path));
+    CHECK_OK(ea_package_export(package, EA_EXPORT_FORMAT_EXCEL, excel_path));
+    CHECK_OK(ea_package_export(package, EA_EXPORT_FORMAT_ZIP_OF_FILES, zip_path));
+    CHECK_OK(ea_test_case_export(package, id, EA_EXPORT_FORMAT_HTML, case_path));
+
+    /* Errors are reported with a code and a message */
+    CHECK(ea_evidence_add_bytes(package, id, EA_EVIDENCE_KIND_IMAGE,
+                                (const uint8_t *)"not an image", 12, NULL,
+                                NULL) == EA_ERROR_INVALID_EVIDENCE_DATA);
+    CHECK(ea_last_error_message() != NULL);
+    CHECK(ea_evidence_add_bytes(package, id, EA_EVIDENCE_KIND_FILE, (const uint8_t *)text,
+                                strlen(text), NULL, NULL) == EA_ERROR_NULL_ARGUMENT);
+    CHECK(ea_evidence_add_bytes(package, id, 99, (const uint8_t *)text, strlen(text), NULL,
+                                NULL) == EA_ERROR_INVALID_ARGUMENT);
+    CHECK(ea_test_case_set_status(package, "not an id", EA_STATUS_PASS) ==
+          EA_ERROR_INVALID_ARGUMENT);
+    CHECK(ea_test_case_set_status(package, "00000000-0000-0000-0000-000000000000",
+                                  EA_STATUS_PASS) == EA_ERROR_DOESNT_EXIST);
+    CHECK(ea_package_export(package, 99, html_path) == EA_ERROR_INVALID_ARGUMENT);
+    CHECK(ea_package_save(NULL) == EA_ERROR_NULL_ARGUMENT);
+
+    /* The package is locked whilst it is open */
+    EaPackage *locked = NULL;
+    CHECK(ea_package_open(package_path, &locked) == EA_ERROR_LOCK_NOT_OBTAINED);
+    CHECK(locked == NULL);
+
+    ea_package_close(package);
+    ea_package_close(NULL);
+
+    /* Reopen the package and check it was saved */
+    EaPackage *reopened = NULL;
+    CHECK_OK(ea_package_open(package_path, &reopened));
+    size_t count = 0;
+    CHECK_OK(ea_package_test_case_count(reopened, &count));
+    CHECK(count == 2);
+    char first_id[EA_ID_LENGTH];
+    CHECK_OK(ea_package_test_case_id(reopened, 0, first_id));
+    CHECK(strcmp(first_id, id) == 0);
+    CHECK(ea_package_test_case_id(reopened, 2, first_id) == EA_ERROR_INVALID_ARGUMENT);
+    ea_package_close(reopened);
+
+    char missing_path[1024];
+    join(missing_path, sizeof missing_path, dir, "missing.evp");
+    EaPackage *missing = NULL;
+    CHECK(ea_package_open(missing_path, &missing) != EA_ERROR_OK);
+    CHECK(missing == NULL);
+
+    if (failures > 0) {
+        fprintf(stderr, "%d checks failed\n", failures);
+        return 1;
+    }
+    printf("all checks passed\n");
+    return 0;
+}
@@ -0,0 +1,49 @@
+//! Build the C test program against the library and run it.
+
+#![cfg(unix)]
+
+use std::{env, path::PathBuf, process::Command};
+
+#[test]
+fn c_test_program() {
+    let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    // Test binaries are built into `target/<profile>/deps`, next to the library's own directory
+    let lib_dir = env::current_exe()
+        .unwrap()
+        .parent()
+        .and_then(|deps| deps.parent())
+        .unwrap()
+        .to_path_buf();
+    let out_dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("c_api");
+    let _ = std::fs::remove_dir_all(&out_dir);
+    std::fs::create_dir_all(&out_dir).unwrap();
+    let program = out_dir.join("test_ffi");
+
+    let compiler = env::var("CC").unwrap_or_else(|_| "cc".to_string());
+    let status = Command::new(compiler)
+        .arg("-std=c99")
+        .arg("-Wall")
+        .arg("-Werror")
+        .arg("-I")
+        .arg(manifest_dir.join("include"))
+        .arg(manifest_dir.join("tests/c/test_ffi.c"))
+        .arg("-o")
+        .arg(&program)
+        .arg("-L")
+        .arg(&lib_dir)
+        .arg(format!("-Wl,-rpath,{}", lib_dir.display()))
+        .arg("-levidenceangel_ffi")
+        .status()
+        .expect("failed to run the C compiler");
+    assert!(status.success(), "failed to compile the C test program");
+
+    let status = Command::new(&program)
+        .arg(&out_dir)
+        .status()
+        .expect("failed to run the C test program");
+    assert!(status.success(), "the C test program failed");
+
+    for export in ["package.html", "package.xlsx", "package.zip", "case.html"] {
+        assert!(out_dir.join(export).exists(), "{export} wasn't exported");
+    }
+}
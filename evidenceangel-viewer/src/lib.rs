@@ -0,0 +1,26 @@
+#![deny(missing_docs)]
+#![deny(clippy::missing_docs_in_private_items)]
+#![deny(unsafe_code)]
+#![warn(clippy::pedantic)]
+
+//! # `EvidenceAngel` Viewer
+//!
+//! Renders evidence packages in a web browser, entirely client-side. This is built to
+//! WebAssembly and loaded by the page in `www/`.
+
+use evidenceangel::{
+    EvidencePackage,
+    exporters::{ExportOptions, html::HtmlExporter},
+};
+use wasm_bindgen::prelude::*;
+
+/// Render an evidence package as an HTML document, the same as if it had been exported to HTML.
+///
+/// # Errors
+///
+/// Returns an error if the data isn't a valid evidence package.
+#[wasm_bindgen(js_name = renderPackage)]
+pub fn render_package(data: Vec<u8>) -> Result<String, JsError> {
+    let mut package = EvidencePackage::open_from_bytes(data)?;
+    Ok(HtmlExporter.render_package(&mut package, &ExportOptions::default())?)
+}
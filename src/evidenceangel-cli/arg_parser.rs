@@ -5,7 +5,9 @@ use clap_verbosity_flag::Verbosity;
 use getset::Getters;
 
 use crate::{
-    export::ExportSubcommand, package::PackageSubcommand, test_cases::TestCasesSubcommand,
+    export::ExportSubcommand, package::PackageSubcommand, redaction::RedactionSubcommand,
+    requirements::RequirementsSubcommand, templates::TemplatesSubcommand,
+    test_cases::TestCasesSubcommand,
 };
 
 /// The command line arguments for this tool
@@ -66,4 +68,22 @@ pub enum Command {
         #[command(subcommand)]
         command: ExportSubcommand,
     },
+    /// Configure and apply redaction of secrets from evidence
+    Redaction {
+        /// The operation to perform on redaction settings or a package
+        #[command(subcommand)]
+        command: RedactionSubcommand,
+    },
+    /// Work with templates for creating test cases
+    Templates {
+        /// The operation to perform on templates
+        #[command(subcommand)]
+        command: TemplatesSubcommand,
+    },
+    /// Work with requirements and which test cases cover them
+    Requirements {
+        /// The operation to perform on requirements
+        #[command(subcommand)]
+        command: RequirementsSubcommand,
+    },
 }
@@ -1,28 +1,48 @@
-use std::{fmt, path::PathBuf, rc::Rc};
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
 
-use clap::Subcommand;
+use clap::{Args, Subcommand, ValueEnum};
+use colored::Colorize;
 use evidenceangel::{
-    EvidencePackage,
+    EvidenceKind, EvidencePackage, RedactionReport, SecretRedactor, TestCasePassStatus,
     exporters::{
-        Exporter, excel::ExcelExporter, html::HtmlExporter, zip_of_files::ZipOfFilesExporter,
+        ExportFormat, ExportOptionKind, ExportOptionValue, ExportOptions, Exporter,
+        ExporterRegistry, TestCaseOrder, html::HtmlExporter,
     },
 };
 use schemars::JsonSchema;
 use serde::Serialize;
 
-use crate::result::{CliData, CliError};
+use crate::{
+    redaction::CliRedactionReport,
+    result::{CliData, CliError},
+    test_cases::match_test_case,
+};
 
 /// Subcommands to work on packages
 #[derive(Subcommand, Clone)]
 pub enum ExportSubcommand {
+    /// List the formats that can be exported to, and the options each understands.
+    Formats,
+
     /// Export to another format.
     Package {
-        /// The format to export to. Permitted values are "html" and "excel".
+        /// The format to export to. Run `export formats` to list the formats available.
         #[arg(index = 1)]
         format: String,
-        /// The target file to write.
+        /// The target file to write. Excel workbooks link to videos copied into a folder
+        /// beside this file, named after it, unless `--option embed-only` is given.
         #[arg(index = 2)]
         target: PathBuf,
+        /// Which test cases to export, and in what order.
+        #[command(flatten)]
+        filter: ExportFilterArgs,
+        /// Options for the export.
+        #[command(flatten)]
+        options: ExportArgs,
     },
 
     /// Export a test case to another format.
@@ -30,34 +50,355 @@ pub enum ExportSubcommand {
         /// The one-based index of the test case to delete, or enough of the title to uniquely match against one test case.
         #[arg(index = 1)]
         case: String,
-        /// The format to export to. Permitted values are "html", "excel" and "zip-of-files".
+        /// The format to export to. Run `export formats` to list the formats available.
         #[arg(index = 2)]
         format: String,
-        /// The target file to write.
+        /// The target file to write. Excel workbooks link to videos copied into a folder
+        /// beside this file, named after it, unless `--option embed-only` is given.
         #[arg(index = 3)]
         target: PathBuf,
+        /// Options for the export.
+        #[command(flatten)]
+        options: ExportArgs,
     },
 }
 
+impl ExportSubcommand {
+    /// Whether this subcommand only lists export formats, so doesn't need a package.
+    pub fn is_formats(&self) -> bool {
+        matches!(self, Self::Formats)
+    }
+}
+
+/// Options for an export
+#[derive(Args, Clone)]
+#[allow(
+    clippy::struct_excessive_bools,
+    reason = "each bool is an independent command line flag"
+)]
+pub struct ExportArgs {
+    /// When exporting to HTML, embed image thumbnails that link to the full size image. This is
+    /// the same as `--option thumbnails`.
+    #[arg(long)]
+    thumbnails: bool,
+    /// Set an option understood by the format, as `ID` to turn on a flag or `ID=VALUE`. Run
+    /// `export formats` to list the options of each format.
+    #[arg(short = 'o', long = "option", value_name = "ID[=VALUE]")]
+    options: Vec<String>,
+    /// Redact secrets from the exported evidence. The package itself isn't changed. This is
+    /// always done if redaction on export is turned on.
+    #[arg(long)]
+    redact: bool,
+    /// Leave out evidence of a kind, such as files or HTTP traces. This can be given more than
+    /// once.
+    #[arg(long, value_name = "KIND")]
+    exclude_evidence: Vec<ExportEvidenceKind>,
+    /// Leave out the authors of the package.
+    #[arg(long)]
+    omit_authors: bool,
+    /// Leave out the email addresses of the authors of the package.
+    #[arg(long)]
+    omit_emails: bool,
+}
+
+/// Which test cases to export from a package, and in what order
+#[derive(Args, Clone)]
+pub struct ExportFilterArgs {
+    /// Only export a test case, by its one-based index or enough of its title to uniquely
+    /// match it. This can be given more than once.
+    #[arg(long = "test-case", value_name = "CASE")]
+    test_cases: Vec<String>,
+    /// Only export test cases with a status. This can be given more than once.
+    #[arg(long = "status", value_name = "STATUS")]
+    statuses: Vec<ExportStatus>,
+    /// Only export test cases with a value of a custom metadata field, as `ID=VALUE`. This can
+    /// be given more than once.
+    #[arg(long = "field", value_name = "ID=VALUE")]
+    custom_fields: Vec<String>,
+    /// Only export test cases executed at or after a date and time.
+    #[arg(long)]
+    executed_after: Option<String>,
+    /// Only export test cases executed at or before a date and time.
+    #[arg(long)]
+    executed_before: Option<String>,
+    /// The order to export test cases in.
+    #[arg(long, value_enum, default_value_t = ExportOrder::Package)]
+    order: ExportOrder,
+}
+
+/// A status of test cases to export
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+enum ExportStatus {
+    /// Test cases that passed
+    Pass,
+    /// Test cases that failed
+    Fail,
+    /// Test cases that haven't been run
+    None,
+}
+
+/// The order to export test cases in
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+enum ExportOrder {
+    /// The order of the test cases in the package
+    Package,
+    /// Alphabetically by title
+    Title,
+    /// From the earliest executed to the latest
+    ExecutionTime,
+    /// Failed test cases first, then those that haven't been run, then those that passed
+    Status,
+}
+
+/// A kind of evidence to leave out of an export
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+enum ExportEvidenceKind {
+    /// Text
+    Text,
+    /// Rich text
+    RichText,
+    /// Images
+    Image,
+    /// Attached files
+    File,
+    /// HTTP requests and responses
+    Http,
+    /// Logs
+    Log,
+    /// Tables
+    Table,
+    /// Diffs
+    Diff,
+    /// Commands and their output
+    Command,
+    /// SQL queries and their results
+    Sql,
+    /// Links
+    Link,
+    /// Videos
+    Video,
+}
+
+impl From<ExportEvidenceKind> for EvidenceKind {
+    fn from(kind: ExportEvidenceKind) -> Self {
+        match kind {
+            ExportEvidenceKind::Text => EvidenceKind::Text,
+            ExportEvidenceKind::RichText => EvidenceKind::RichText,
+            ExportEvidenceKind::Image => EvidenceKind::Image,
+            ExportEvidenceKind::File => EvidenceKind::File,
+            ExportEvidenceKind::Http => EvidenceKind::Http,
+            ExportEvidenceKind::Log => EvidenceKind::Log,
+            ExportEvidenceKind::Table => EvidenceKind::Table,
+            ExportEvidenceKind::Diff => EvidenceKind::Diff,
+            ExportEvidenceKind::Command => EvidenceKind::Command,
+            ExportEvidenceKind::Sql => EvidenceKind::Sql,
+            ExportEvidenceKind::Link => EvidenceKind::Link,
+            ExportEvidenceKind::Video => EvidenceKind::Video,
+        }
+    }
+}
+
+impl ExportFilterArgs {
+    /// Set which test cases to export, and in what order, on export options.
+    fn apply(
+        &self,
+        package: &mut EvidencePackage,
+        options: &mut ExportOptions,
+    ) -> Result<(), CliError> {
+        let filter = options.filter_mut();
+        if !self.test_cases.is_empty() {
+            let mut ids = vec![];
+            for case in &self.test_cases {
+                let id = match_test_case(package, case)
+                    .ok_or_else(|| CliError::CannotMatchTestCase(case.clone()))?;
+                ids.push(id);
+            }
+            filter.set_test_cases(Some(ids));
+        }
+        if !self.statuses.is_empty() {
+            filter.set_statuses(Some(
+                self.statuses
+                    .iter()
+                    .map(|status| match status {
+                        ExportStatus::Pass => Some(TestCasePassStatus::Pass),
+                        ExportStatus::Fail => Some(TestCasePassStatus::Fail),
+                        ExportStatus::None => None,
+                    })
+                    .collect(),
+            ));
+        }
+        for field in &self.custom_fields {
+            let (id, value) = field.split_once('=').ok_or(CliError::InvalidCustomField)?;
+            filter
+                .custom_fields_mut()
+                .insert(id.to_string(), value.to_string());
+        }
+        if let Some(after) = &self.executed_after {
+            let after = parse_datetime::parse_datetime(after)
+                .map_err(|_| CliError::InvalidExecutionDateTime)?;
+            filter.set_executed_after(Some(after));
+        }
+        if let Some(before) = &self.executed_before {
+            let before = parse_datetime::parse_datetime(before)
+                .map_err(|_| CliError::InvalidExecutionDateTime)?;
+            filter.set_executed_before(Some(before));
+        }
+        options.set_order(match self.order {
+            ExportOrder::Package => TestCaseOrder::Package,
+            ExportOrder::Title => TestCaseOrder::Title,
+            ExportOrder::ExecutionTime => TestCaseOrder::ExecutionTime,
+            ExportOrder::Status => TestCaseOrder::Status,
+        });
+        Ok(())
+    }
+}
+
+impl ExportArgs {
+    /// Get the options to pass to an exporter to a format.
+    fn export_options(&self, format: &ExportFormat) -> Result<ExportOptions, CliError> {
+        let mut options = ExportOptions::new();
+        options.set_excluded_evidence(
+            self.exclude_evidence
+                .iter()
+                .map(|kind| EvidenceKind::from(*kind))
+                .collect(),
+        );
+        options.set_omit_authors(self.omit_authors);
+        options.set_omit_author_emails(self.omit_emails);
+        if self.thumbnails {
+            options.set(HtmlExporter::THUMBNAILS, ExportOptionValue::Flag(true));
+        }
+        for option in &self.options {
+            let (id, value) = option.split_once('=').unwrap_or((option, ""));
+            let value = format
+                .option(id)
+                .and_then(|o| o.kind().parse(value))
+                .ok_or_else(|| CliError::InvalidExportOption(option.clone()))?;
+            options.set(id, value);
+        }
+        Ok(options)
+    }
+}
+
+/// The formats that can be exported to, for display or JSON serialization
+#[derive(Serialize, JsonSchema)]
+pub struct CliExportFormats {
+    /// The formats
+    formats: Vec<CliExportFormat>,
+}
+
+/// A format that can be exported to
+#[derive(Serialize, JsonSchema)]
+struct CliExportFormat {
+    /// The ID of the format, used to export to it
+    id: String,
+    /// The name of the format
+    name: String,
+    /// The file extension suggested for the format
+    extension: String,
+    /// The options understood by the format
+    options: Vec<CliExportOption>,
+}
+
+/// An option understood by an export format
+#[derive(Serialize, JsonSchema)]
+struct CliExportOption {
+    /// The ID of the option, used to set it
+    id: String,
+    /// The name of the option
+    name: String,
+    /// A description of what the option does
+    description: String,
+    /// The kind of value the option takes
+    kind: CliExportOptionKind,
+}
+
+/// The kind of value an export option takes
+#[derive(Serialize, JsonSchema)]
+enum CliExportOptionKind {
+    /// The option is a flag, either on or off
+    Flag,
+    /// The option takes text
+    Text,
+}
+
+impl From<&ExporterRegistry> for CliExportFormats {
+    fn from(registry: &ExporterRegistry) -> Self {
+        Self {
+            formats: registry
+                .formats()
+                .iter()
+                .map(|format| CliExportFormat {
+                    id: format.id().clone(),
+                    name: format.name().clone(),
+                    extension: format.extension().clone(),
+                    options: format
+                        .options()
+                        .iter()
+                        .map(|option| CliExportOption {
+                            id: option.id().clone(),
+                            name: option.name().clone(),
+                            description: option.description().clone(),
+                            kind: match option.kind() {
+                                ExportOptionKind::Flag => CliExportOptionKind::Flag,
+                                ExportOptionKind::Text => CliExportOptionKind::Text,
+                            },
+                        })
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl fmt::Display for CliExportFormats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "📤 {}", "Export Formats".bold())?;
+        for (idx, format) in self.formats.iter().enumerate() {
+            let last = idx == self.formats.len() - 1;
+            writeln!(
+                f,
+                "  {} {} {} {}",
+                if last { "╰" } else { "├" },
+                format.id.blue(),
+                format.name,
+                format!("({})", format.extension).dimmed()
+            )?;
+            for (idx, option) in format.options.iter().enumerate() {
+                writeln!(
+                    f,
+                    "  {}   {} {} {}",
+                    if last { " " } else { "│" },
+                    if idx == format.options.len() - 1 {
+                        "╰"
+                    } else {
+                        "├"
+                    },
+                    match option.kind {
+                        CliExportOptionKind::Flag => format!("--option {}", option.id),
+                        CliExportOptionKind::Text => format!("--option {}=VALUE", option.id),
+                    }
+                    .bold(),
+                    option.description.dimmed()
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
 #[derive(Serialize, JsonSchema)]
 pub struct CliExportResult {
-    /// The format that has been exported to
-    format: ExportFormat,
+    /// The ID of the format that has been exported to
+    format: String,
+    /// The name of the format that has been exported to
+    format_name: String,
     /// The amount of data exported
     scope: ExportScope,
     /// The path that has been exported to
     path: PathBuf,
-}
-
-/// The export format
-#[derive(Serialize, JsonSchema)]
-enum ExportFormat {
-    /// The export was to an Excel workbook
-    Excel,
-    /// The export was to HTML
-    Html,
-    /// The export was a ZIP of files
-    ZipOfFiles,
+    /// The secrets redacted from the export, if redaction was applied
+    redactions: Option<CliRedactionReport>,
 }
 
 /// The scope of data exported
@@ -77,59 +418,124 @@ impl fmt::Display for CliExportResult {
         writeln!(
             f,
             "Exported {} to {}",
-            match self.format {
-                ExportFormat::Excel => "Excel",
-                ExportFormat::Html => "HTML",
-                ExportFormat::ZipOfFiles => "ZIP of files",
-            },
+            self.format_name,
             self.path.display()
-        )
+        )?;
+        if let Some(redactions) = &self.redactions {
+            redactions.fmt(f)?;
+        }
+        Ok(())
     }
 }
 
+/// Get a redactor if secrets should be redacted from an export of a package, either because it
+/// was asked for or because the package's settings turn it on.
+fn export_redactor(
+    package: &EvidencePackage,
+    redact: bool,
+) -> Result<Option<SecretRedactor>, CliError> {
+    let settings = crate::redaction::effective_settings(package)?;
+    if redact || *settings.redact_on_export() {
+        crate::redaction::redactor(&settings).map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+/// Export a package, or one test case from it, redacting secrets if a redactor is given.
+fn export_with(
+    exporter: &mut dyn Exporter,
+    package: &mut EvidencePackage,
+    case: Option<uuid::Uuid>,
+    target: PathBuf,
+    options: &ExportOptions,
+    redactor: Option<&SecretRedactor>,
+) -> evidenceangel::Result<Option<RedactionReport>> {
+    match (case, redactor) {
+        (None, None) => exporter
+            .export_package(package, target, options)
+            .map(|()| None),
+        (Some(case), None) => exporter
+            .export_case(package, case, target, options)
+            .map(|()| None),
+        (None, Some(redactor)) => exporter
+            .export_package_redacted(package, target, options, redactor)
+            .map(Some),
+        (Some(case), Some(redactor)) => exporter
+            .export_case_redacted(package, case, target, options, redactor)
+            .map(Some),
+    }
+}
+
+/// Export a package, or one test case from it, to a format with the options given.
+fn export(
+    package: &mut EvidencePackage,
+    case: Option<(uuid::Uuid, String)>,
+    format: &str,
+    target: &Path,
+    args: &ExportArgs,
+    filter: Option<&ExportFilterArgs>,
+) -> CliData {
+    let registry = ExporterRegistry::default();
+    let Some(format) = registry.get(format) else {
+        return CliError::InvalidExportFormat(format.to_string()).into();
+    };
+    let mut options = match args.export_options(format) {
+        Ok(options) => options,
+        Err(e) => return e.into(),
+    };
+    if let Some(filter) = filter
+        && let Err(e) = filter.apply(package, &mut options)
+    {
+        return e.into();
+    }
+    let redactor = match export_redactor(package, args.redact) {
+        Ok(redactor) => redactor,
+        Err(e) => return e.into(),
+    };
+
+    let (case_id, scope) = match case {
+        Some((id, title)) => (Some(id), ExportScope::TestCase { title }),
+        None => (None, ExportScope::Package),
+    };
+    let redactions = match export_with(
+        format.create().as_mut(),
+        package,
+        case_id,
+        target.to_path_buf(),
+        &options,
+        redactor.as_ref(),
+    ) {
+        Ok(report) => report,
+        Err(e) => return CliError::FailedToExport(Rc::new(e)).into(),
+    };
+
+    CliData::ExportResult(CliExportResult {
+        format: format.id().clone(),
+        format_name: format.name().clone(),
+        scope,
+        path: target.to_path_buf(),
+        redactions: redactions.map(|report| CliRedactionReport::new(&report, true)),
+    })
+}
+
+/// List the formats that can be exported to.
+pub fn formats() -> CliData {
+    CliData::ExportFormats(CliExportFormats::from(&ExporterRegistry::default()))
+}
+
 /// Process the export subcommand
 pub fn process(path: PathBuf, command: &ExportSubcommand) -> CliData {
     match command {
-        ExportSubcommand::Package { format, target } => match EvidencePackage::open(path) {
-            Ok(mut package) => match format.to_ascii_lowercase().as_str() {
-                "excel" => {
-                    let mut exporter = ExcelExporter;
-                    if let Err(e) = exporter.export_package(&mut package, target.clone()) {
-                        return CliError::FailedToExport(Rc::new(e)).into();
-                    }
-
-                    CliData::ExportResult(CliExportResult {
-                        format: ExportFormat::Excel,
-                        scope: ExportScope::Package,
-                        path: target.clone(),
-                    })
-                }
-                "html" => {
-                    let mut exporter = HtmlExporter;
-                    if let Err(e) = exporter.export_package(&mut package, target.clone()) {
-                        return CliError::FailedToExport(Rc::new(e)).into();
-                    }
+        ExportSubcommand::Formats => formats(),
 
-                    CliData::ExportResult(CliExportResult {
-                        format: ExportFormat::Html,
-                        scope: ExportScope::Package,
-                        path: target.clone(),
-                    })
-                }
-                "zip-of-files" => {
-                    let mut exporter = ZipOfFilesExporter;
-                    if let Err(e) = exporter.export_package(&mut package, target.clone()) {
-                        return CliError::FailedToExport(Rc::new(e)).into();
-                    }
-
-                    CliData::ExportResult(CliExportResult {
-                        format: ExportFormat::ZipOfFiles,
-                        scope: ExportScope::Package,
-                        path: target.clone(),
-                    })
-                }
-                _ => CliError::InvalidExportFormat(format.clone()).into(),
-            },
+        ExportSubcommand::Package {
+            format,
+            target,
+            filter,
+            options,
+        } => match EvidencePackage::open(path) {
+            Ok(mut package) => export(&mut package, None, format, target, options, Some(filter)),
             Err(e) => CliError::FailedToReadPackage(Rc::new(e)).into(),
         },
 
@@ -137,6 +543,7 @@ pub fn process(path: PathBuf, command: &ExportSubcommand) -> CliData {
             case,
             format,
             target,
+            options,
         } => match EvidencePackage::open(path) {
             Ok(mut package) => {
                 // match against a test case
@@ -178,50 +585,8 @@ pub fn process(path: PathBuf, command: &ExportSubcommand) -> CliData {
                 if case_id.is_none() {
                     return CliError::CannotMatchTestCase(case.clone()).into();
                 }
-                let (case_id, case_title) = case_id.unwrap();
-
-                match format.to_ascii_lowercase().as_str() {
-                    "excel" => {
-                        let mut exporter = ExcelExporter;
-                        if let Err(e) = exporter.export_case(&mut package, case_id, target.clone())
-                        {
-                            return CliError::FailedToExport(Rc::new(e)).into();
-                        }
-
-                        CliData::ExportResult(CliExportResult {
-                            format: ExportFormat::Excel,
-                            scope: ExportScope::TestCase { title: case_title },
-                            path: target.clone(),
-                        })
-                    }
-                    "html" => {
-                        let mut exporter = HtmlExporter;
-                        if let Err(e) = exporter.export_case(&mut package, case_id, target.clone())
-                        {
-                            return CliError::FailedToExport(Rc::new(e)).into();
-                        }
-
-                        CliData::ExportResult(CliExportResult {
-                            format: ExportFormat::Html,
-                            scope: ExportScope::TestCase { title: case_title },
-                            path: target.clone(),
-                        })
-                    }
-                    "zip-of-files" => {
-                        let mut exporter = ZipOfFilesExporter;
-                        if let Err(e) = exporter.export_case(&mut package, case_id, target.clone())
-                        {
-                            return CliError::FailedToExport(Rc::new(e)).into();
-                        }
-
-                        CliData::ExportResult(CliExportResult {
-                            format: ExportFormat::ZipOfFiles,
-                            scope: ExportScope::TestCase { title: case_title },
-                            path: target.clone(),
-                        })
-                    }
-                    _ => CliError::InvalidExportFormat(format.clone()).into(),
-                }
+
+                export(&mut package, case_id, format, target, options, None)
             }
             Err(e) => CliError::FailedToReadPackage(Rc::new(e)).into(),
         },
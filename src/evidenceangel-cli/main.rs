@@ -27,8 +27,14 @@ mod arg_parser;
 mod export;
 /// Module containing functionality for working with packages.
 mod package;
+/// Module containing functionality for redacting secrets.
+mod redaction;
+/// Module containing functionality for working with requirements and their traceability.
+mod requirements;
 /// Module containing serializable and presentable result data.
 mod result;
+/// Module containing functionality for working with test case templates.
+mod templates;
 /// Module containing functionality for working with test cases.
 mod test_cases;
 
@@ -69,13 +75,39 @@ fn main() {
         _ => (),
     }
 
+    // Redaction can work on user settings without a package
+    if let Command::Redaction { command } = args.command() {
+        redaction::process(args.file().clone(), command).output(&args);
+        return;
+    }
+
+    // Templates can be for all packages the user works with
+    if let Command::Templates { command } = args.command() {
+        templates::process(args.file().clone(), command).output(&args);
+        return;
+    }
+
+    // Export formats can be listed without a package
+    if let Command::Export { command } = args.command()
+        && command.is_formats()
+    {
+        export::formats().output(&args);
+        return;
+    }
+
     // Now handle the rest...
     let path = args.file().clone().unwrap();
     let result: CliData = match args.command() {
-        Command::ShellCompletions { .. } | Command::JsonSchema => unreachable!(),
+        Command::ShellCompletions { .. }
+        | Command::JsonSchema
+        | Command::Redaction { .. }
+        | Command::Templates { .. } => {
+            unreachable!()
+        }
         Command::Package { command } => package::process(path, command),
         Command::TestCases { command } => test_cases::process(path, command),
         Command::Export { command } => export::process(path, command),
+        Command::Requirements { command } => requirements::process(path, command),
     };
 
     result.output(&args);
@@ -89,6 +121,9 @@ fn validate_cli_combinations(args: &Args) -> Option<CliData> {
                 return Some(CliError::CannotBeSerialized.into());
             }
         }
+        Command::Redaction { command } if command.is_user() => (),
+        Command::Templates { command } if command.is_user() => (),
+        Command::Export { command } if command.is_formats() => (),
         _ => {
             if args.file().is_none() {
                 return Some(CliError::MissingFile.into());
@@ -3,7 +3,10 @@ use std::{collections::HashMap, fmt, path::PathBuf, rc::Rc};
 use chrono::FixedOffset;
 use clap::Subcommand;
 use colored::Colorize;
-use evidenceangel::{Author, EvidencePackage};
+use evidenceangel::{
+    Author, CompressionAlgorithm, CompressionPolicy, EvidencePackage, PackageStatistics,
+    StatusCounts, StorageReport,
+};
 use schemars::JsonSchema;
 use serde::Serialize;
 
@@ -30,6 +33,9 @@ pub enum PackageSubcommand {
     /// Read the data from a package.
     Read,
 
+    /// Show statistics summarising the test cases and evidence in a package.
+    Stats,
+
     /// Update the details of this package
     Update {
         /// The new title of the package.
@@ -80,6 +86,19 @@ pub enum PackageSubcommand {
         #[arg(index = 1)]
         field: String,
     },
+
+    /// Compact this package, storing media that is already compressed as-is and recompressing
+    /// everything else. The compression chosen is remembered by the package and used whenever
+    /// it is saved.
+    Compact {
+        /// Compress with zstd instead of deflate. Note that not every ZIP tool can read zstd.
+        #[arg(short, long)]
+        zstd: bool,
+
+        /// The compression level. Deflate accepts levels 0 to 9, and zstd accepts levels 1 to 22.
+        #[arg(short, long)]
+        level: Option<i64>,
+    },
 }
 
 /// A package, for display or JSON serialization
@@ -214,6 +233,359 @@ struct PackageTestCase {
     executed_at: chrono::DateTime<FixedOffset>,
 }
 
+/// The result of compacting a package, for display or JSON serialization
+#[derive(Serialize, JsonSchema)]
+pub struct CliCompactResult {
+    /// The size of the package file before compacting, in bytes
+    size_before: u64,
+    /// The size of the package file after compacting, in bytes
+    size_after: u64,
+    /// The size of evidence in each test case, in bytes
+    test_cases: Vec<CliTestCaseStorage>,
+    /// The size of evidence of each kind, in bytes
+    evidence_kinds: HashMap<String, u64>,
+    /// The media within this package, largest first
+    media: Vec<CliMediaStorage>,
+    /// The number of bytes saved by only storing media referenced multiple times once
+    dedup_savings: u64,
+}
+
+impl CliCompactResult {
+    /// Create a new compaction result from a storage report
+    fn new(size_before: u64, size_after: u64, report: &StorageReport) -> Self {
+        Self {
+            size_before,
+            size_after,
+            test_cases: report
+                .test_cases()
+                .iter()
+                .map(|tc| CliTestCaseStorage {
+                    title: tc.title().clone(),
+                    size: tc.size(),
+                })
+                .collect(),
+            evidence_kinds: report
+                .evidence_kinds()
+                .iter()
+                .map(|(kind, size)| (format!("{kind:?}"), *size))
+                .collect(),
+            media: report
+                .media()
+                .iter()
+                .map(|m| CliMediaStorage {
+                    hash: m.hash().clone(),
+                    mime_type: m.mime_type().clone(),
+                    size: m.size(),
+                    compressed_size: m.compressed_size(),
+                    references: m.references(),
+                })
+                .collect(),
+            dedup_savings: *report.dedup_savings(),
+        }
+    }
+}
+
+impl fmt::Display for CliCompactResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "🗜  Compacted package from {} to {}",
+            format_bytes(self.size_before).bold(),
+            format_bytes(self.size_after).bold().green(),
+        )?;
+
+        writeln!(f, "\nTest Cases:")?;
+        for (idx, test_case) in self.test_cases.iter().enumerate() {
+            let ch = if idx == self.test_cases.len() - 1 {
+                "╰"
+            } else {
+                "├"
+            };
+            writeln!(
+                f,
+                "  {} {} {} {}",
+                ch,
+                format!("[#{}]", idx + 1).blue(),
+                test_case.title,
+                format!("({})", format_bytes(test_case.size)).magenta(),
+            )?;
+        }
+
+        writeln!(f, "\nEvidence Kinds:")?;
+        let mut sorted_kinds = self.evidence_kinds.iter().collect::<Vec<_>>();
+        sorted_kinds.sort_by(|(_, a), (_, b)| b.cmp(a));
+        for (idx, (kind, size)) in sorted_kinds.iter().enumerate() {
+            let ch = if idx == sorted_kinds.len() - 1 {
+                "╰"
+            } else {
+                "├"
+            };
+            writeln!(
+                f,
+                "  {} {} {}",
+                ch,
+                kind,
+                format!("({})", format_bytes(**size)).magenta()
+            )?;
+        }
+
+        writeln!(f, "\nLargest Media:")?;
+        let largest_media = self.media.iter().take(5).collect::<Vec<_>>();
+        for (idx, media) in largest_media.iter().enumerate() {
+            let ch = if idx == largest_media.len() - 1 {
+                "╰"
+            } else {
+                "├"
+            };
+            writeln!(
+                f,
+                "  {} {} {} {} {}",
+                ch,
+                media.hash.chars().take(12).collect::<String>().blue(),
+                media.mime_type,
+                format!("({})", format_bytes(media.size)).magenta(),
+                format!("×{}", media.references).dimmed(),
+            )?;
+        }
+
+        writeln!(
+            f,
+            "\nDeduplication saved {}",
+            format_bytes(self.dedup_savings).bold()
+        )?;
+
+        Ok(())
+    }
+}
+
+/// The storage used by a test case
+#[derive(Serialize, JsonSchema)]
+struct CliTestCaseStorage {
+    /// The title of the test case
+    title: String,
+    /// The size of the evidence in this test case, in bytes
+    size: u64,
+}
+
+/// The storage used by a media file
+#[derive(Serialize, JsonSchema)]
+struct CliMediaStorage {
+    /// The SHA256 hash of the media
+    hash: String,
+    /// The MIME type of the media
+    mime_type: String,
+    /// The uncompressed size of the media, in bytes
+    size: u64,
+    /// The size of the media as stored in the package, in bytes
+    compressed_size: Option<u64>,
+    /// The number of pieces of evidence referencing this media
+    references: usize,
+}
+
+/// Statistics summarising a package, for display or JSON serialization
+#[derive(Serialize, JsonSchema)]
+pub struct CliPackageStatistics {
+    /// The number of test cases
+    test_cases: usize,
+    /// The number of test cases with each status
+    statuses: CliStatusCounts,
+    /// The proportion of test cases with a status that passed, from 0 to 1
+    pass_rate: Option<f64>,
+    /// The number of test cases with each value of each custom metadata field, by field ID and
+    /// then by value
+    custom_fields: HashMap<String, HashMap<String, CliStatusCounts>>,
+    /// The number of test cases by each author of the package
+    authors: HashMap<String, CliStatusCounts>,
+    /// The number of pieces of evidence
+    evidence: usize,
+    /// The number of pieces of evidence of each kind
+    evidence_kinds: HashMap<String, usize>,
+    /// The number of media files
+    media_count: usize,
+    /// The total size of all media, in bytes
+    media_size: u64,
+    /// The size of the largest media file, in bytes
+    largest_media_size: Option<u64>,
+    /// When the earliest test case was executed
+    first_executed: Option<chrono::DateTime<FixedOffset>>,
+    /// When the latest test case was executed
+    last_executed: Option<chrono::DateTime<FixedOffset>>,
+}
+
+impl From<&PackageStatistics> for CliPackageStatistics {
+    fn from(statistics: &PackageStatistics) -> Self {
+        Self {
+            test_cases: statistics.test_cases(),
+            statuses: statistics.statuses().into(),
+            pass_rate: statistics.pass_rate(),
+            custom_fields: statistics
+                .custom_fields()
+                .iter()
+                .map(|(field, values)| {
+                    (
+                        field.clone(),
+                        values
+                            .iter()
+                            .map(|(value, counts)| (value.clone(), (*counts).into()))
+                            .collect(),
+                    )
+                })
+                .collect(),
+            authors: statistics
+                .authors()
+                .iter()
+                .map(|(author, counts)| (author.clone(), (*counts).into()))
+                .collect(),
+            evidence: statistics.evidence(),
+            evidence_kinds: statistics
+                .evidence_kinds()
+                .iter()
+                .map(|(kind, count)| (format!("{kind:?}"), *count))
+                .collect(),
+            media_count: statistics.media_count(),
+            media_size: statistics.media_size(),
+            largest_media_size: statistics.largest_media_size(),
+            first_executed: statistics.first_executed(),
+            last_executed: statistics.last_executed(),
+        }
+    }
+}
+
+impl fmt::Display for CliPackageStatistics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "📊 {}", "Statistics".bold())?;
+        writeln!(f, "  {} test cases: {}", self.test_cases, self.statuses)?;
+        if let Some(pass_rate) = self.pass_rate {
+            writeln!(
+                f,
+                "  {} pass rate",
+                format!("{:.1}%", pass_rate * 100.0).bold()
+            )?;
+        }
+        if let (Some(first), Some(last)) = (self.first_executed, self.last_executed) {
+            writeln!(
+                f,
+                "  Executed from {} to {}",
+                first.to_string().magenta(),
+                last.to_string().magenta()
+            )?;
+        }
+
+        writeln!(f, "\nAuthors:")?;
+        let mut sorted_authors = self.authors.iter().collect::<Vec<_>>();
+        sorted_authors.sort_by_key(|(author, _)| *author);
+        for (idx, (author, counts)) in sorted_authors.iter().enumerate() {
+            let ch = if idx == sorted_authors.len() - 1 {
+                "╰"
+            } else {
+                "├"
+            };
+            writeln!(f, "  {ch} {author} {}", format!("({counts})").dimmed())?;
+        }
+
+        let mut sorted_fields = self.custom_fields.iter().collect::<Vec<_>>();
+        sorted_fields.sort_by_key(|(field, _)| *field);
+        for (field, values) in sorted_fields {
+            writeln!(
+                f,
+                "\nCustom Metadata Field {}:",
+                format!("[{field}]").blue()
+            )?;
+            let mut sorted_values = values.iter().collect::<Vec<_>>();
+            sorted_values.sort_by_key(|(value, _)| *value);
+            for (idx, (value, counts)) in sorted_values.iter().enumerate() {
+                let ch = if idx == sorted_values.len() - 1 {
+                    "╰"
+                } else {
+                    "├"
+                };
+                writeln!(f, "  {ch} {value} {}", format!("({counts})").dimmed())?;
+            }
+        }
+
+        writeln!(f, "\nEvidence ({}):", self.evidence)?;
+        let mut sorted_kinds = self.evidence_kinds.iter().collect::<Vec<_>>();
+        sorted_kinds.sort_by(|(a_kind, a), (b_kind, b)| b.cmp(a).then(a_kind.cmp(b_kind)));
+        for (idx, (kind, count)) in sorted_kinds.iter().enumerate() {
+            let ch = if idx == sorted_kinds.len() - 1 {
+                "╰"
+            } else {
+                "├"
+            };
+            writeln!(f, "  {ch} {kind} {}", format!("×{count}").magenta())?;
+        }
+
+        write!(
+            f,
+            "\nMedia: {} files, {}",
+            self.media_count,
+            format_bytes(self.media_size).bold()
+        )?;
+        if let Some(largest) = self.largest_media_size {
+            write!(
+                f,
+                " {}",
+                format!("(largest {})", format_bytes(largest)).dimmed()
+            )?;
+        }
+        writeln!(f)?;
+
+        Ok(())
+    }
+}
+
+/// The number of test cases with each status
+#[derive(Serialize, JsonSchema)]
+struct CliStatusCounts {
+    /// The number of test cases that passed
+    passed: usize,
+    /// The number of test cases that failed
+    failed: usize,
+    /// The number of test cases without a status
+    not_run: usize,
+}
+
+impl From<StatusCounts> for CliStatusCounts {
+    fn from(counts: StatusCounts) -> Self {
+        Self {
+            passed: counts.passed(),
+            failed: counts.failed(),
+            not_run: counts.not_run(),
+        }
+    }
+}
+
+impl fmt::Display for CliStatusCounts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} passed, {} failed, {} not run",
+            self.passed.to_string().green(),
+            self.failed.to_string().red(),
+            self.not_run
+        )
+    }
+}
+
+/// Format a number of bytes in a human readable form
+#[allow(
+    clippy::cast_precision_loss,
+    reason = "precision is not needed for display"
+)]
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1} {}", UNITS[unit])
+}
+
 /// Process the package subcommand
 pub fn process(path: PathBuf, command: &PackageSubcommand) -> CliData {
     match command {
@@ -321,6 +693,14 @@ pub fn process(path: PathBuf, command: &PackageSubcommand) -> CliData {
             Err(e) => CliError::FailedToReadPackage(Rc::new(e)).into(),
         },
 
+        PackageSubcommand::Stats => match EvidencePackage::open(path) {
+            Ok(mut package) => match package.statistics() {
+                Ok(statistics) => CliData::Statistics(CliPackageStatistics::from(&statistics)),
+                Err(e) => CliError::FailedToReadPackage(Rc::new(e)).into(),
+            },
+            Err(e) => CliError::FailedToReadPackage(Rc::new(e)).into(),
+        },
+
         PackageSubcommand::Update { title, description } => match EvidencePackage::open(path) {
             Ok(mut package) => {
                 if let Some(title) = title {
@@ -593,5 +973,39 @@ pub fn process(path: PathBuf, command: &PackageSubcommand) -> CliData {
                 Err(e) => CliError::FailedToReadPackage(Rc::new(e)).into(),
             }
         }
+
+        PackageSubcommand::Compact { zstd, level } => {
+            let size_before = std::fs::metadata(&path).map_or(0, |m| m.len());
+            match EvidencePackage::open(path.clone()) {
+                Ok(mut package) => {
+                    let algorithm = if *zstd {
+                        CompressionAlgorithm::Zstd
+                    } else {
+                        CompressionAlgorithm::Deflate
+                    };
+                    match CompressionPolicy::new(algorithm, *level) {
+                        Ok(policy) => {
+                            package.set_compression_policy(policy);
+                        }
+                        Err(e) => return CliError::InvalidCompressionLevel(Rc::new(e)).into(),
+                    }
+
+                    if let Err(e) = package.save() {
+                        return CliError::FailedToSavePackage(Rc::new(e)).into();
+                    }
+                    let size_after = std::fs::metadata(&path).map_or(0, |m| m.len());
+
+                    match package.storage_report() {
+                        Ok(report) => CliData::CompactResult(CliCompactResult::new(
+                            size_before,
+                            size_after,
+                            &report,
+                        )),
+                        Err(e) => CliError::FailedToReadPackage(Rc::new(e)).into(),
+                    }
+                }
+                Err(e) => CliError::FailedToReadPackage(Rc::new(e)).into(),
+            }
+        }
     }
 }
@@ -0,0 +1,422 @@
+use std::{fmt, path::PathBuf, rc::Rc};
+
+use clap::Subcommand;
+use colored::Colorize;
+use directories::ProjectDirs;
+use evidenceangel::{
+    EvidencePackage, RedactionReport, RedactionSettings, SecretRedactor, SecretRule,
+};
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::result::{CliData, CliError};
+
+/// Subcommands to configure and apply secret redaction
+#[derive(Subcommand, Clone)]
+pub enum RedactionSubcommand {
+    /// Show how secrets are redacted.
+    Show {
+        /// Show the settings for all packages you work with, rather than this package.
+        #[arg(short, long)]
+        user: bool,
+    },
+
+    /// Change how secrets are redacted.
+    Set {
+        /// Whether the built-in rules are used, which find authorization and cookie headers,
+        /// bearer tokens, API keys, passwords and common access keys.
+        #[arg(long)]
+        builtin_rules: Option<bool>,
+
+        /// Whether secrets are redacted from evidence as it is added.
+        #[arg(long)]
+        on_add: Option<bool>,
+
+        /// Whether secrets are redacted from evidence when it is exported.
+        #[arg(long)]
+        on_export: Option<bool>,
+
+        /// Change the settings for all packages you work with, rather than this package.
+        #[arg(short, long)]
+        user: bool,
+    },
+
+    /// Add a rule for finding secrets, replacing any rule with the same name.
+    AddRule {
+        /// A short name describing what this rule finds.
+        #[arg(index = 1)]
+        name: String,
+
+        /// The regular expression to search for. If it has a capture group named `secret`, only
+        /// that group is redacted, such as `(?i)x-session: (?P<secret>.+)`.
+        #[arg(index = 2)]
+        pattern: String,
+
+        /// Add the rule for all packages you work with, rather than this package.
+        #[arg(short, long)]
+        user: bool,
+    },
+
+    /// Delete a rule for finding secrets.
+    DeleteRule {
+        /// The name of the rule to delete.
+        #[arg(index = 1)]
+        name: String,
+
+        /// Delete the rule from the settings for all packages you work with, rather than this
+        /// package.
+        #[arg(short, long)]
+        user: bool,
+    },
+
+    /// Redact secrets from all evidence in this package now.
+    Apply {
+        /// Report what would be redacted without changing the package.
+        #[arg(short = 'n', long)]
+        dry_run: bool,
+    },
+}
+
+impl RedactionSubcommand {
+    /// Whether this subcommand works on the user's settings, so doesn't need a package.
+    pub fn is_user(&self) -> bool {
+        match self {
+            Self::Show { user }
+            | Self::Set { user, .. }
+            | Self::AddRule { user, .. }
+            | Self::DeleteRule { user, .. } => *user,
+            Self::Apply { .. } => false,
+        }
+    }
+}
+
+/// Redaction settings, for display or JSON serialization
+#[derive(Serialize, JsonSchema)]
+pub struct CliRedactionSettings {
+    /// What these settings apply to
+    scope: RedactionScope,
+    /// Whether the built-in rules are used
+    builtin_rules: bool,
+    /// Whether secrets are redacted from evidence as it is added
+    redact_on_add: bool,
+    /// Whether secrets are redacted from evidence when it is exported
+    redact_on_export: bool,
+    /// Rules used in addition to the built-in rules
+    rules: Vec<CliSecretRule>,
+}
+
+/// What redaction settings apply to
+#[derive(Serialize, JsonSchema)]
+enum RedactionScope {
+    /// The settings apply to one package
+    Package,
+    /// The settings apply to all packages the user works with
+    User,
+}
+
+/// A rule for finding secrets
+#[derive(Serialize, JsonSchema)]
+struct CliSecretRule {
+    /// The name of the rule
+    name: String,
+    /// The regular expression the rule searches for
+    pattern: String,
+}
+
+impl CliRedactionSettings {
+    /// Create redaction settings for display on screen or via JSON
+    fn new(settings: &RedactionSettings, user: bool) -> Self {
+        Self {
+            scope: if user {
+                RedactionScope::User
+            } else {
+                RedactionScope::Package
+            },
+            builtin_rules: *settings.builtin_rules(),
+            redact_on_add: *settings.redact_on_add(),
+            redact_on_export: *settings.redact_on_export(),
+            rules: settings
+                .rules()
+                .iter()
+                .map(|rule| CliSecretRule {
+                    name: rule.name().clone(),
+                    pattern: rule.pattern().clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl fmt::Display for CliRedactionSettings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        /// Describe whether a setting is on
+        fn on_off(value: bool) -> colored::ColoredString {
+            if value { "on".green() } else { "off".red() }
+        }
+
+        writeln!(
+            f,
+            "🔒 {}",
+            if let RedactionScope::User = self.scope {
+                "Redaction for all packages".bold()
+            } else {
+                "Redaction for this package".bold()
+            }
+        )?;
+        writeln!(f, "  Built-in rules:   {}", on_off(self.builtin_rules))?;
+        writeln!(f, "  Redact on add:    {}", on_off(self.redact_on_add))?;
+        writeln!(f, "  Redact on export: {}", on_off(self.redact_on_export))?;
+
+        writeln!(f, "\nRules:")?;
+        if self.rules.is_empty() {
+            writeln!(f, "  {}", "No rules".dimmed())?;
+        }
+        for (idx, rule) in self.rules.iter().enumerate() {
+            let ch = if idx == self.rules.len() - 1 {
+                "╰"
+            } else {
+                "├"
+            };
+            writeln!(f, "  {} {} {}", ch, rule.name.blue(), rule.pattern)?;
+        }
+        Ok(())
+    }
+}
+
+/// A report of secrets that were redacted, for display or JSON serialization
+#[derive(Serialize, JsonSchema)]
+pub struct CliRedactionReport {
+    /// Whether the secrets were redacted, or only found
+    applied: bool,
+    /// The total number of secrets
+    total: usize,
+    /// The secrets found by each rule in each piece of evidence
+    entries: Vec<CliRedactionReportEntry>,
+}
+
+/// The number of secrets one rule found in a piece of evidence
+#[derive(Serialize, JsonSchema)]
+struct CliRedactionReportEntry {
+    /// The title of the test case containing the evidence
+    test_case: String,
+    /// The one-based index of the evidence in its test case
+    evidence: usize,
+    /// The name of the rule that found the secrets
+    rule: String,
+    /// The number of secrets found
+    count: usize,
+}
+
+impl CliRedactionReport {
+    /// Create a redaction report for display on screen or via JSON
+    pub fn new(report: &RedactionReport, applied: bool) -> Self {
+        Self {
+            applied,
+            total: report.total(),
+            entries: report
+                .entries()
+                .iter()
+                .map(|entry| CliRedactionReportEntry {
+                    test_case: entry.test_case_title().clone(),
+                    evidence: entry.evidence() + 1,
+                    rule: entry.rule().clone(),
+                    count: entry.count(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl fmt::Display for CliRedactionReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.total == 0 {
+            return writeln!(f, "🔒 {}", "No secrets found".green());
+        }
+        writeln!(
+            f,
+            "🔒 {} {} {}",
+            if self.applied { "Redacted" } else { "Found" },
+            self.total.to_string().bold(),
+            if self.total == 1 { "secret" } else { "secrets" },
+        )?;
+        for (idx, entry) in self.entries.iter().enumerate() {
+            let ch = if idx == self.entries.len() - 1 {
+                "╰"
+            } else {
+                "├"
+            };
+            writeln!(
+                f,
+                "  {} {} {} {} {}",
+                ch,
+                entry.test_case,
+                format!("[#{}]", entry.evidence).blue(),
+                entry.rule,
+                format!("×{}", entry.count).dimmed(),
+            )?;
+        }
+        if !self.applied {
+            writeln!(f, "{}", "Dry run, nothing was changed.".dimmed())?;
+        }
+        Ok(())
+    }
+}
+
+/// Get the path of the file storing the redaction settings for all packages the user works with.
+fn user_settings_path() -> Result<PathBuf, CliError> {
+    ProjectDirs::from("uk.hpkns", "AngelSuite", "EvidenceAngel")
+        .map(|dirs| dirs.config_dir().join("redaction.json"))
+        .ok_or(CliError::NoUserSettingsDirectory)
+}
+
+/// Read the redaction settings for all packages the user works with.
+fn user_settings() -> Result<RedactionSettings, CliError> {
+    RedactionSettings::load(&user_settings_path()?)
+        .map_err(|e| CliError::FailedToAccessUserSettings(Rc::new(e)))
+}
+
+/// Get the redaction settings that apply to a package, combining the user's settings with the
+/// package's own.
+pub fn effective_settings(package: &EvidencePackage) -> Result<RedactionSettings, CliError> {
+    let user = user_settings()?;
+    Ok(match package.metadata().redaction() {
+        Some(settings) => user.combined_with(settings),
+        None => user,
+    })
+}
+
+/// Create a redactor from some settings.
+pub fn redactor(settings: &RedactionSettings) -> Result<SecretRedactor, CliError> {
+    SecretRedactor::new(settings).map_err(|e| CliError::InvalidSecretRule(Rc::new(e)))
+}
+
+/// Change either the user's settings or a package's settings, then return them for display.
+fn modify_settings<F>(path: Option<PathBuf>, user: bool, modify: F) -> CliData
+where
+    F: FnOnce(&mut RedactionSettings) -> Result<(), CliError>,
+{
+    if user {
+        let mut settings = match user_settings() {
+            Ok(settings) => settings,
+            Err(e) => return e.into(),
+        };
+        if let Err(e) = modify(&mut settings) {
+            return e.into();
+        }
+        let result = user_settings_path().and_then(|path| {
+            settings
+                .save(&path)
+                .map_err(|e| CliError::FailedToAccessUserSettings(Rc::new(e)))
+        });
+        if let Err(e) = result {
+            return e.into();
+        }
+        CliData::RedactionSettings(CliRedactionSettings::new(&settings, true))
+    } else {
+        let Some(path) = path else {
+            return CliError::MissingFile.into();
+        };
+        match EvidencePackage::open(path) {
+            Ok(mut package) => {
+                if let Err(e) = modify(package.metadata_mut().redaction_mut()) {
+                    return e.into();
+                }
+                if let Err(e) = package.save() {
+                    return CliError::FailedToSavePackage(Rc::new(e)).into();
+                }
+                CliData::RedactionSettings(CliRedactionSettings::new(
+                    package.metadata().redaction().as_ref().unwrap(),
+                    false,
+                ))
+            }
+            Err(e) => CliError::FailedToReadPackage(Rc::new(e)).into(),
+        }
+    }
+}
+
+/// Process the redaction subcommand
+pub fn process(path: Option<PathBuf>, command: &RedactionSubcommand) -> CliData {
+    match command {
+        RedactionSubcommand::Show { user } => {
+            if *user {
+                return match user_settings() {
+                    Ok(settings) => {
+                        CliData::RedactionSettings(CliRedactionSettings::new(&settings, true))
+                    }
+                    Err(e) => e.into(),
+                };
+            }
+            let Some(path) = path else {
+                return CliError::MissingFile.into();
+            };
+            match EvidencePackage::open(path) {
+                Ok(package) => CliData::RedactionSettings(CliRedactionSettings::new(
+                    &package.metadata().redaction().clone().unwrap_or_default(),
+                    false,
+                )),
+                Err(e) => CliError::FailedToReadPackage(Rc::new(e)).into(),
+            }
+        }
+        RedactionSubcommand::Set {
+            builtin_rules,
+            on_add,
+            on_export,
+            user,
+        } => modify_settings(path, *user, |settings| {
+            if let Some(builtin_rules) = builtin_rules {
+                settings.set_builtin_rules(*builtin_rules);
+            }
+            if let Some(on_add) = on_add {
+                settings.set_redact_on_add(*on_add);
+            }
+            if let Some(on_export) = on_export {
+                settings.set_redact_on_export(*on_export);
+            }
+            Ok(())
+        }),
+        RedactionSubcommand::AddRule {
+            name,
+            pattern,
+            user,
+        } => modify_settings(path, *user, |settings| {
+            let rule = SecretRule::new(name.clone(), pattern.clone())
+                .map_err(|e| CliError::InvalidSecretRule(Rc::new(e)))?;
+            settings.insert_rule(rule);
+            Ok(())
+        }),
+        RedactionSubcommand::DeleteRule { name, user } => {
+            modify_settings(path, *user, |settings| {
+                if settings.remove_rule(name) {
+                    Ok(())
+                } else {
+                    Err(CliError::SecretRuleNotFound(name.clone()))
+                }
+            })
+        }
+        RedactionSubcommand::Apply { dry_run } => {
+            let Some(path) = path else {
+                return CliError::MissingFile.into();
+            };
+            match EvidencePackage::open(path) {
+                Ok(mut package) => {
+                    let redactor = match effective_settings(&package)
+                        .and_then(|settings| redactor(&settings))
+                    {
+                        Ok(redactor) => redactor,
+                        Err(e) => return e.into(),
+                    };
+                    // SAFETY: redacting cannot currently fail
+                    let report = redactor.redact_package(&mut package).unwrap();
+                    if !dry_run
+                        && !report.is_empty()
+                        && let Err(e) = package.save()
+                    {
+                        return CliError::FailedToSavePackage(Rc::new(e)).into();
+                    }
+                    CliData::RedactionReport(CliRedactionReport::new(&report, !dry_run))
+                }
+                Err(e) => CliError::FailedToReadPackage(Rc::new(e)).into(),
+            }
+        }
+    }
+}
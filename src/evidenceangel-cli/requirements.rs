@@ -0,0 +1,377 @@
+use std::{fmt, path::PathBuf, rc::Rc};
+
+use clap::Subcommand;
+use colored::Colorize;
+use evidenceangel::{CoverageStatus, EvidencePackage, Requirement, TestCasePassStatus};
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::{
+    result::{CliData, CliError},
+    test_cases::{CliTestCasePassStatus, modify_test_case},
+};
+
+/// Subcommands to work with requirements and their traceability to test cases
+#[derive(Subcommand, Clone)]
+pub enum RequirementsSubcommand {
+    /// List the requirements in the package's catalogue.
+    List,
+
+    /// Add a requirement to the package's catalogue, or update it if it already exists.
+    Add {
+        /// The ID of the requirement, such as `REQ-12`.
+        #[arg(index = 1)]
+        id: String,
+
+        /// The title of the requirement.
+        #[arg(short, long)]
+        title: Option<String>,
+
+        /// A link to the requirement in another system.
+        #[arg(short, long)]
+        link: Option<String>,
+    },
+
+    /// Delete a requirement from the package's catalogue. Test cases still reference it.
+    Delete {
+        /// The ID of the requirement.
+        #[arg(index = 1)]
+        id: String,
+    },
+
+    /// Record that a test case covers a requirement.
+    Link {
+        /// The one-based index of the test case, or enough of the title to uniquely match
+        /// against one test case.
+        #[arg(index = 1)]
+        case: String,
+
+        /// The ID of the requirement.
+        #[arg(index = 2)]
+        id: String,
+
+        /// The title of the requirement, if it isn't in the package's catalogue.
+        #[arg(short, long)]
+        title: Option<String>,
+
+        /// A link to the requirement, if it isn't in the package's catalogue.
+        #[arg(short, long)]
+        link: Option<String>,
+    },
+
+    /// Record that a test case no longer covers a requirement.
+    Unlink {
+        /// The one-based index of the test case, or enough of the title to uniquely match
+        /// against one test case.
+        #[arg(index = 1)]
+        case: String,
+
+        /// The ID of the requirement.
+        #[arg(index = 2)]
+        id: String,
+    },
+
+    /// Show which test cases cover each requirement, and highlight requirements that are
+    /// uncovered or failing.
+    Matrix,
+}
+
+/// The requirements in a package's catalogue, for display or JSON serialization
+#[derive(Serialize, JsonSchema)]
+pub struct CliRequirements {
+    /// The requirements
+    requirements: Vec<CliRequirement>,
+}
+
+/// A requirement
+#[derive(Serialize, JsonSchema)]
+struct CliRequirement {
+    /// The ID of the requirement
+    id: String,
+    /// The title of the requirement
+    title: Option<String>,
+    /// A link to the requirement
+    link: Option<String>,
+}
+
+impl From<&Requirement> for CliRequirement {
+    fn from(requirement: &Requirement) -> Self {
+        Self {
+            id: requirement.id().clone(),
+            title: requirement.title().clone(),
+            link: requirement.link().clone(),
+        }
+    }
+}
+
+impl CliRequirements {
+    /// Create a list of the requirements in a package for display on screen or via JSON.
+    fn new(package: &EvidencePackage) -> Self {
+        Self {
+            requirements: package
+                .metadata()
+                .requirements()
+                .iter()
+                .flatten()
+                .map(CliRequirement::from)
+                .collect(),
+        }
+    }
+}
+
+impl fmt::Display for CliRequirements {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "📌 {}", "Requirements".bold())?;
+        if self.requirements.is_empty() {
+            writeln!(f, "  {}", "No requirements".dimmed())?;
+        }
+        for (idx, requirement) in self.requirements.iter().enumerate() {
+            let ch = if idx == self.requirements.len() - 1 {
+                "╰"
+            } else {
+                "├"
+            };
+            write!(f, "  {ch} {}", requirement.id.blue())?;
+            if let Some(title) = &requirement.title {
+                write!(f, " {title}")?;
+            }
+            if let Some(link) = &requirement.link {
+                write!(f, " {}", link.dimmed())?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// The requirements in a package and the test cases covering them, for display or JSON
+/// serialization
+#[derive(Serialize, JsonSchema)]
+pub struct CliTraceabilityMatrix {
+    /// The requirements and their coverage
+    requirements: Vec<CliRequirementCoverage>,
+    /// The titles of test cases that don't cover any requirements
+    untraced_test_cases: Vec<String>,
+}
+
+/// A requirement and the test cases covering it
+#[derive(Serialize, JsonSchema)]
+struct CliRequirementCoverage {
+    /// The requirement
+    #[serde(flatten)]
+    requirement: CliRequirement,
+    /// Whether the requirement is in the package's catalogue, rather than only referenced by
+    /// test cases
+    catalogued: bool,
+    /// How well the requirement is covered
+    status: CliCoverageStatus,
+    /// The test cases covering the requirement
+    test_cases: Vec<CliTracedTestCase>,
+}
+
+/// How well a requirement is covered
+#[derive(Serialize, JsonSchema)]
+#[serde(tag = "type")]
+enum CliCoverageStatus {
+    /// No test cases cover the requirement
+    Uncovered,
+    /// A test case covering the requirement failed
+    Failing,
+    /// Not all test cases covering the requirement have a status
+    NotRun,
+    /// Every test case covering the requirement passed
+    Passing,
+}
+
+/// A test case covering a requirement
+#[derive(Serialize, JsonSchema)]
+struct CliTracedTestCase {
+    /// The one-based index of the test case
+    index: usize,
+    /// The title of the test case
+    title: String,
+    /// The status of the test case
+    status: CliTestCasePassStatus,
+}
+
+impl CliTraceabilityMatrix {
+    /// Create a traceability matrix for display on screen or via JSON.
+    fn new(package: &EvidencePackage) -> Result<Self, CliError> {
+        let matrix = package
+            .traceability_matrix()
+            .map_err(|e| CliError::FailedToReadPackage(Rc::new(e)))?;
+        let requirements = matrix
+            .requirements()
+            .iter()
+            .map(|coverage| CliRequirementCoverage {
+                requirement: CliRequirement::from(coverage.requirement()),
+                catalogued: *coverage.catalogued(),
+                status: match coverage.status() {
+                    CoverageStatus::Uncovered => CliCoverageStatus::Uncovered,
+                    CoverageStatus::Failing => CliCoverageStatus::Failing,
+                    CoverageStatus::NotRun => CliCoverageStatus::NotRun,
+                    CoverageStatus::Passing => CliCoverageStatus::Passing,
+                },
+                test_cases: matrix
+                    .test_cases()
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, tc)| coverage.is_covered_by(tc.id()))
+                    .map(|(idx, tc)| CliTracedTestCase {
+                        index: idx + 1,
+                        title: tc.title().clone(),
+                        status: match tc.status() {
+                            None => CliTestCasePassStatus::None,
+                            Some(TestCasePassStatus::Pass) => CliTestCasePassStatus::Pass,
+                            Some(TestCasePassStatus::Fail) => CliTestCasePassStatus::Fail,
+                        },
+                    })
+                    .collect(),
+            })
+            .collect();
+        Ok(Self {
+            requirements,
+            untraced_test_cases: matrix
+                .untraced_test_cases()
+                .map(|tc| tc.title().clone())
+                .collect(),
+        })
+    }
+}
+
+impl fmt::Display for CliTraceabilityMatrix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "🔗 {}", "Traceability".bold())?;
+        if self.requirements.is_empty() {
+            writeln!(f, "  {}", "No requirements".dimmed())?;
+        }
+        for (idx, coverage) in self.requirements.iter().enumerate() {
+            let last = idx == self.requirements.len() - 1;
+            write!(
+                f,
+                "  {} {}",
+                if last { "╰" } else { "├" },
+                coverage.requirement.id.blue()
+            )?;
+            if let Some(title) = &coverage.requirement.title {
+                write!(f, " {title}")?;
+            }
+            writeln!(
+                f,
+                " {}{}",
+                match coverage.status {
+                    CliCoverageStatus::Uncovered => "⚠ Uncovered".yellow(),
+                    CliCoverageStatus::Failing => "❌ Failing".red(),
+                    CliCoverageStatus::NotRun => "Not run".dimmed(),
+                    CliCoverageStatus::Passing => "✅ Passing".green(),
+                },
+                if coverage.catalogued {
+                    String::new()
+                } else {
+                    format!(" {}", "(not in catalogue)".dimmed())
+                }
+            )?;
+            let cont = if last { " " } else { "│" };
+            for test_case in &coverage.test_cases {
+                writeln!(
+                    f,
+                    "  {cont}   {} {}{}",
+                    format!("{}.", test_case.index).dimmed(),
+                    test_case.title,
+                    match test_case.status {
+                        CliTestCasePassStatus::None => String::new(),
+                        CliTestCasePassStatus::Pass => format!(" {}", "✅".green()),
+                        CliTestCasePassStatus::Fail => format!(" {}", "❌".red()),
+                    }
+                )?;
+            }
+        }
+        if !self.untraced_test_cases.is_empty() {
+            writeln!(
+                f,
+                "\n  {} {}",
+                "Test cases without requirements:".bold(),
+                self.untraced_test_cases.join(", ")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Open a package, change its requirements catalogue and save it, then return the catalogue for
+/// display.
+fn modify_catalogue<F>(path: PathBuf, modify: F) -> CliData
+where
+    F: FnOnce(&mut Vec<Requirement>) -> Result<(), CliError>,
+{
+    match EvidencePackage::open(path) {
+        Ok(mut package) => {
+            if let Err(e) = modify(package.metadata_mut().requirements_mut()) {
+                return e.into();
+            }
+            if let Err(e) = package.save() {
+                return CliError::FailedToSavePackage(Rc::new(e)).into();
+            }
+            CliData::Requirements(CliRequirements::new(&package))
+        }
+        Err(e) => CliError::FailedToReadPackage(Rc::new(e)).into(),
+    }
+}
+
+/// Process the requirements subcommand
+pub fn process(path: PathBuf, command: &RequirementsSubcommand) -> CliData {
+    match command {
+        RequirementsSubcommand::List => match EvidencePackage::open(path) {
+            Ok(package) => CliData::Requirements(CliRequirements::new(&package)),
+            Err(e) => CliError::FailedToReadPackage(Rc::new(e)).into(),
+        },
+        RequirementsSubcommand::Add { id, title, link } => modify_catalogue(path, |catalogue| {
+            let requirement = Requirement::new_with_details(id, title.clone(), link.clone());
+            if let Some(existing) = catalogue.iter_mut().find(|r| r.id() == id) {
+                *existing = requirement;
+            } else {
+                catalogue.push(requirement);
+            }
+            Ok(())
+        }),
+        RequirementsSubcommand::Delete { id } => modify_catalogue(path, |catalogue| {
+            let idx = catalogue
+                .iter()
+                .position(|r| r.id() == id)
+                .ok_or_else(|| CliError::RequirementNotFound(id.clone()))?;
+            catalogue.remove(idx);
+            Ok(())
+        }),
+        RequirementsSubcommand::Link {
+            case,
+            id,
+            title,
+            link,
+        } => modify_test_case(path, case, |test_case| {
+            let requirement = Requirement::new_with_details(id, title.clone(), link.clone());
+            let requirements = test_case.metadata_mut().requirements_mut();
+            if let Some(existing) = requirements.iter_mut().find(|r| r.id() == id) {
+                *existing = requirement;
+            } else {
+                requirements.push(requirement);
+            }
+            Ok(())
+        }),
+        RequirementsSubcommand::Unlink { case, id } => modify_test_case(path, case, |test_case| {
+            let requirements = test_case.metadata_mut().requirements_mut();
+            let idx = requirements
+                .iter()
+                .position(|r| r.id() == id)
+                .ok_or_else(|| CliError::RequirementNotFound(id.clone()))?;
+            requirements.remove(idx);
+            Ok(())
+        }),
+        RequirementsSubcommand::Matrix => match EvidencePackage::open(path) {
+            Ok(package) => match CliTraceabilityMatrix::new(&package) {
+                Ok(matrix) => CliData::TraceabilityMatrix(matrix),
+                Err(e) => e.into(),
+            },
+            Err(e) => CliError::FailedToReadPackage(Rc::new(e)).into(),
+        },
+    }
+}
@@ -4,7 +4,14 @@ use colored::Colorize;
 use schemars::{JsonSchema, schema_for};
 use serde::Serialize;
 
-use crate::{export::CliExportResult, package::CliPackage, test_cases::CliTestCase};
+use crate::{
+    export::{CliExportFormats, CliExportResult},
+    package::{CliCompactResult, CliPackage, CliPackageStatistics},
+    redaction::{CliRedactionReport, CliRedactionSettings},
+    requirements::{CliRequirements, CliTraceabilityMatrix},
+    templates::CliTemplates,
+    test_cases::CliTestCase,
+};
 
 use super::error::{CliError, CliErrorContainer};
 
@@ -22,6 +29,22 @@ pub enum CliData {
     TestCase(CliTestCase),
     /// A result of an export job.
     ExportResult(CliExportResult),
+    /// The formats that can be exported to.
+    ExportFormats(CliExportFormats),
+    /// A result of compacting a package.
+    CompactResult(CliCompactResult),
+    /// Statistics summarising a package.
+    Statistics(CliPackageStatistics),
+    /// Settings for redacting secrets.
+    RedactionSettings(CliRedactionSettings),
+    /// A report of secrets that were redacted.
+    RedactionReport(CliRedactionReport),
+    /// Templates for creating test cases.
+    Templates(CliTemplates),
+    /// The requirements in a package.
+    Requirements(CliRequirements),
+    /// The requirements in a package and the test cases covering them.
+    TraceabilityMatrix(CliTraceabilityMatrix),
 }
 
 impl CliData {
@@ -39,6 +62,14 @@ impl fmt::Display for CliData {
             CliData::Package(p) => p.fmt(f),
             CliData::TestCase(t) => t.fmt(f),
             CliData::ExportResult(e) => e.fmt(f),
+            CliData::ExportFormats(e) => e.fmt(f),
+            CliData::CompactResult(c) => c.fmt(f),
+            CliData::Statistics(s) => s.fmt(f),
+            CliData::RedactionSettings(r) => r.fmt(f),
+            CliData::RedactionReport(r) => r.fmt(f),
+            CliData::Templates(t) => t.fmt(f),
+            CliData::Requirements(r) => r.fmt(f),
+            CliData::TraceabilityMatrix(m) => m.fmt(f),
         }
     }
 }
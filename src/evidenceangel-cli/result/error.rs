@@ -8,9 +8,10 @@ use thiserror::Error;
 /// An error from the CLI tool
 #[derive(Error, Debug, Clone)]
 pub enum CliError {
-    /// --file is mandated for all subcommands except a `shell-completions` and `json-schema`
+    /// --file is mandated for all subcommands except a `shell-completions`, `json-schema` and
+    /// those working on user settings
     #[error(
-        "all subcommands except `shell-completions` and `json-schema` require --file to be specified"
+        "all subcommands except `shell-completions`, `json-schema` and those with --user require --file to be specified"
     )]
     MissingFile,
 
@@ -27,9 +28,13 @@ pub enum CliError {
     FailedToReadPackage(Rc<evidenceangel::Error>),
 
     /// invalid export format specified
-    #[error("invalid export format `{0}`")]
+    #[error("invalid export format `{0}`, run `export formats` to list the formats available")]
     InvalidExportFormat(String),
 
+    /// an export option isn't understood by the format, or its value is invalid
+    #[error("invalid export option `{0}`, run `export formats` to list the options of each format")]
+    InvalidExportOption(String),
+
     /// failed to export to file
     #[error("failed to export: {0}")]
     FailedToExport(Rc<evidenceangel::Error>),
@@ -56,6 +61,10 @@ pub enum CliError {
     #[error("invalid image provided")]
     InvalidImage,
 
+    /// invalid video provided
+    #[error("invalid video provided, only MP4 and WebM videos are supported")]
+    InvalidVideo,
+
     /// couldn't add media to package
     #[error("couldn't add media to package")]
     CouldntAddMedia,
@@ -63,6 +72,100 @@ pub enum CliError {
     /// the custom metadata field you reference doesn't exist
     #[error("the custom metadata field you reference doesn't exist")]
     InvalidCustomField,
+
+    /// invalid log format specified
+    #[error("invalid log format `{0}`")]
+    InvalidLogFormat(String),
+
+    /// invalid table format specified
+    #[error("invalid table format `{0}`")]
+    InvalidTableFormat(String),
+
+    /// the table provided couldn't be parsed
+    #[error("the table provided couldn't be parsed: {0}")]
+    InvalidTable(String),
+
+    /// invalid diff format specified
+    #[error("invalid diff format `{0}`")]
+    InvalidDiffFormat(String),
+
+    /// the content to compare couldn't be parsed
+    #[error("the content to compare couldn't be parsed: {0}")]
+    InvalidDiff(String),
+
+    /// the command couldn't be executed
+    #[error("the command couldn't be executed: {0}")]
+    FailedToExecuteCommand(String),
+
+    /// the link provided isn't valid
+    #[error("invalid link: {0}")]
+    InvalidLink(String),
+
+    /// invalid link system specified
+    #[error("invalid link system `{0}`")]
+    InvalidLinkSystem(String),
+
+    /// the HAR file provided couldn't be parsed
+    #[error("the HAR file provided couldn't be parsed: {0}")]
+    InvalidHar(String),
+
+    /// the HAR entry filter isn't a valid regular expression
+    #[error("invalid HAR entry filter: {0}")]
+    InvalidHarFilter(String),
+
+    /// no entries in the HAR file matched the filter
+    #[error("no entries in the HAR file matched the filter")]
+    NoMatchingHarEntries,
+
+    /// more than one entry in the HAR file matched when only one can be used
+    #[error("{0} entries in the HAR file matched, but only one can be used here")]
+    TooManyHarEntries(usize),
+
+    /// a secret redaction rule isn't valid
+    #[error("{0}")]
+    InvalidSecretRule(Rc<evidenceangel::Error>),
+
+    /// the secret redaction rule you reference doesn't exist
+    #[error("there is no secret redaction rule named `{0}`")]
+    SecretRuleNotFound(String),
+
+    /// the user's redaction settings couldn't be read or written
+    #[error("failed to access user redaction settings: {0}")]
+    FailedToAccessUserSettings(Rc<evidenceangel::Error>),
+
+    /// no step matches the index you provided
+    #[error("the value `{0}` does not match a one-based index of a step")]
+    CannotMatchStep(usize),
+
+    /// invalid step status specified
+    #[error(
+        "invalid step status `{0}`, expected \"pass\", \"fail\", \"blocked\", \"skipped\" or \"none\""
+    )]
+    InvalidStepStatus(String),
+
+    /// a test case template isn't valid
+    #[error("{0}")]
+    InvalidTemplate(Rc<evidenceangel::Error>),
+
+    /// the template you reference doesn't exist
+    #[error("there is no template named `{0}`")]
+    TemplateNotFound(String),
+
+    /// the user's templates couldn't be read or written
+    #[error("failed to access user templates: {0}")]
+    FailedToAccessUserTemplates(Rc<evidenceangel::Error>),
+
+    /// the requirement you reference doesn't exist
+    #[error("there is no requirement `{0}`")]
+    RequirementNotFound(String),
+
+    /// the compression level isn't accepted by the compression algorithm
+    #[error("{0}")]
+    InvalidCompressionLevel(Rc<evidenceangel::Error>),
+
+    /// there is nowhere to store user settings on this system
+    #[error("couldn't find where to store user settings on this system")]
+    NoUserSettingsDirectory,
 }
 
 /// Get the name of the [`CliError`] variant, without any args, as a [`String`].
@@ -73,14 +176,39 @@ fn get_error_name(error: &CliError) -> &'static str {
         CliError::FailedToSavePackage(_) => "FailedToCreatePackage",
         CliError::FailedToReadPackage(_) => "FailedToReadPackage",
         CliError::InvalidExportFormat(_) => "InvalidExportFormat",
+        CliError::InvalidExportOption(_) => "InvalidExportOption",
         CliError::FailedToExport(_) => "FailedToExport",
         CliError::CannotMatchTestCase(_) => "CannotMatchTestCase",
         CliError::CannotMatchEvidence(_) => "CannotMatchEvidence",
         CliError::InvalidExecutionDateTime => "InvalidExecutionDateTime",
         CliError::FailedToReadFile => "FailedToReadFile",
         CliError::InvalidImage => "InvalidImage",
+        CliError::InvalidVideo => "InvalidVideo",
         CliError::CouldntAddMedia => "CouldntAddMedia",
         CliError::InvalidCustomField => "InvalidCustomField",
+        CliError::InvalidLogFormat(_) => "InvalidLogFormat",
+        CliError::InvalidTableFormat(_) => "InvalidTableFormat",
+        CliError::InvalidTable(_) => "InvalidTable",
+        CliError::InvalidDiffFormat(_) => "InvalidDiffFormat",
+        CliError::InvalidDiff(_) => "InvalidDiff",
+        CliError::FailedToExecuteCommand(_) => "FailedToExecuteCommand",
+        CliError::InvalidLink(_) => "InvalidLink",
+        CliError::InvalidLinkSystem(_) => "InvalidLinkSystem",
+        CliError::InvalidHar(_) => "InvalidHar",
+        CliError::InvalidHarFilter(_) => "InvalidHarFilter",
+        CliError::NoMatchingHarEntries => "NoMatchingHarEntries",
+        CliError::TooManyHarEntries(_) => "TooManyHarEntries",
+        CliError::InvalidSecretRule(_) => "InvalidSecretRule",
+        CliError::SecretRuleNotFound(_) => "SecretRuleNotFound",
+        CliError::FailedToAccessUserSettings(_) => "FailedToAccessUserSettings",
+        CliError::NoUserSettingsDirectory => "NoUserSettingsDirectory",
+        CliError::CannotMatchStep(_) => "CannotMatchStep",
+        CliError::InvalidStepStatus(_) => "InvalidStepStatus",
+        CliError::InvalidTemplate(_) => "InvalidTemplate",
+        CliError::TemplateNotFound(_) => "TemplateNotFound",
+        CliError::FailedToAccessUserTemplates(_) => "FailedToAccessUserTemplates",
+        CliError::RequirementNotFound(_) => "RequirementNotFound",
+        CliError::InvalidCompressionLevel(_) => "InvalidCompressionLevel",
     }
 }
 
@@ -0,0 +1,326 @@
+use std::{fmt, path::PathBuf, rc::Rc};
+
+use clap::Subcommand;
+use colored::Colorize;
+use directories::ProjectDirs;
+use evidenceangel::{EvidencePackage, TemplateLibrary, TestCaseTemplate};
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::{
+    result::{CliData, CliError},
+    test_cases::match_test_case,
+};
+
+/// Subcommands to work with test case templates
+#[derive(Subcommand, Clone)]
+pub enum TemplatesSubcommand {
+    /// List the templates that test cases can be created from.
+    List {
+        /// Only list the templates for all packages you work with, rather than also this
+        /// package's.
+        #[arg(short, long)]
+        user: bool,
+    },
+
+    /// Create a template from an existing test case, taking its custom metadata and evidence.
+    /// This replaces any template with the same name. Evidence stored as media, such as images
+    /// and files, isn't included.
+    Create {
+        /// The one-based index of the test case to create the template from, or enough of the
+        /// title to uniquely match against one test case.
+        #[arg(index = 1)]
+        case: String,
+
+        /// The name of the new template.
+        #[arg(index = 2)]
+        name: String,
+
+        /// The pattern for titles of test cases created from this template. This may contain
+        /// `{n}`, `{date}`, `{time}` and `{template}`, which are replaced with the position of the
+        /// new test case, the date and time it was executed and the template's name.
+        #[arg(short, long, default_value = "{template} {n}")]
+        title: String,
+
+        /// A description of when the template should be used.
+        #[arg(short, long)]
+        description: Option<String>,
+
+        /// Store the template for all packages you work with, rather than in this package.
+        #[arg(short, long)]
+        user: bool,
+    },
+
+    /// Delete a template.
+    Delete {
+        /// The name of the template to delete.
+        #[arg(index = 1)]
+        name: String,
+
+        /// Delete the template from those for all packages you work with, rather than from this
+        /// package.
+        #[arg(short, long)]
+        user: bool,
+    },
+}
+
+impl TemplatesSubcommand {
+    /// Whether this subcommand works on the user's templates, so doesn't need a package.
+    pub fn is_user(&self) -> bool {
+        match self {
+            Self::List { user } | Self::Delete { user, .. } => *user,
+            Self::Create { .. } => false,
+        }
+    }
+}
+
+/// Templates that test cases can be created from, for display or JSON serialization
+#[derive(Serialize, JsonSchema)]
+pub struct CliTemplates {
+    /// The templates
+    templates: Vec<CliTemplate>,
+}
+
+/// A template for creating test cases
+#[derive(Serialize, JsonSchema)]
+struct CliTemplate {
+    /// The name of the template
+    name: String,
+    /// Where the template is stored
+    scope: TemplateScope,
+    /// A description of when the template should be used
+    description: Option<String>,
+    /// The pattern for titles of test cases created from the template
+    title: String,
+    /// The number of custom metadata fields the template gives default values for
+    custom_fields: usize,
+    /// The number of pieces of evidence new test cases start with
+    evidence: usize,
+}
+
+/// Where a template is stored
+#[derive(Serialize, JsonSchema)]
+enum TemplateScope {
+    /// The template is stored in one package
+    Package,
+    /// The template is available to all packages the user works with
+    User,
+}
+
+impl CliTemplates {
+    /// Create a list of templates for display on screen or via JSON. Package templates take
+    /// precedence over user templates with the same name.
+    fn new(package: Option<&TemplateLibrary>, user: Option<&TemplateLibrary>) -> Self {
+        /// Convert a template for display
+        fn convert(template: &TestCaseTemplate, scope: TemplateScope) -> CliTemplate {
+            CliTemplate {
+                name: template.name().clone(),
+                scope,
+                description: template.description().clone(),
+                title: template.title().clone(),
+                custom_fields: template.custom_metadata().len(),
+                evidence: template.evidence().len(),
+            }
+        }
+
+        let mut templates: Vec<CliTemplate> = package
+            .into_iter()
+            .flat_map(TemplateLibrary::iter)
+            .map(|t| convert(t, TemplateScope::Package))
+            .collect();
+        for template in user.into_iter().flat_map(TemplateLibrary::iter) {
+            if !templates.iter().any(|t| &t.name == template.name()) {
+                templates.push(convert(template, TemplateScope::User));
+            }
+        }
+        Self { templates }
+    }
+}
+
+impl fmt::Display for CliTemplates {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "📋 {}", "Templates".bold())?;
+        if self.templates.is_empty() {
+            writeln!(f, "  {}", "No templates".dimmed())?;
+        }
+        for (idx, template) in self.templates.iter().enumerate() {
+            let ch = if idx == self.templates.len() - 1 {
+                "╰"
+            } else {
+                "├"
+            };
+            writeln!(
+                f,
+                "  {} {} {} {}",
+                ch,
+                template.name.blue(),
+                template.title,
+                if let TemplateScope::User = template.scope {
+                    "(user)".dimmed()
+                } else {
+                    "(package)".dimmed()
+                },
+            )?;
+            let cont = if idx == self.templates.len() - 1 {
+                " "
+            } else {
+                "│"
+            };
+            if let Some(description) = &template.description {
+                writeln!(f, "  {cont}   {description}")?;
+            }
+            writeln!(
+                f,
+                "  {cont}   {}",
+                format!(
+                    "{} custom {}, {} evidence",
+                    template.custom_fields,
+                    if template.custom_fields == 1 {
+                        "field"
+                    } else {
+                        "fields"
+                    },
+                    template.evidence,
+                )
+                .dimmed()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Get the path of the file storing the templates for all packages the user works with.
+fn user_library_path() -> Result<PathBuf, CliError> {
+    ProjectDirs::from("uk.hpkns", "AngelSuite", "EvidenceAngel")
+        .map(|dirs| dirs.config_dir().join("templates.json"))
+        .ok_or(CliError::NoUserSettingsDirectory)
+}
+
+/// Read the templates for all packages the user works with.
+fn user_library() -> Result<TemplateLibrary, CliError> {
+    TemplateLibrary::load(&user_library_path()?)
+        .map_err(|e| CliError::FailedToAccessUserTemplates(Rc::new(e)))
+}
+
+/// Find a template by name, first in a package, then in the user's templates.
+pub fn find_template(package: &EvidencePackage, name: &str) -> Result<TestCaseTemplate, CliError> {
+    if let Some(template) = package
+        .metadata()
+        .templates()
+        .as_ref()
+        .and_then(|templates| templates.get(name))
+    {
+        return Ok(template.clone());
+    }
+    user_library()?
+        .get(name)
+        .cloned()
+        .ok_or_else(|| CliError::TemplateNotFound(name.to_string()))
+}
+
+/// Change either the user's templates or a package's templates, then return them for display.
+fn modify_library<F>(path: Option<PathBuf>, user: bool, modify: F) -> CliData
+where
+    F: FnOnce(Option<&mut EvidencePackage>, &mut TemplateLibrary) -> Result<(), CliError>,
+{
+    if user {
+        let mut library = match user_library() {
+            Ok(library) => library,
+            Err(e) => return e.into(),
+        };
+        let result = match path.map(EvidencePackage::open) {
+            Some(Ok(mut package)) => modify(Some(&mut package), &mut library),
+            Some(Err(e)) => return CliError::FailedToReadPackage(Rc::new(e)).into(),
+            None => modify(None, &mut library),
+        };
+        if let Err(e) = result {
+            return e.into();
+        }
+        let result = user_library_path().and_then(|path| {
+            library
+                .save(&path)
+                .map_err(|e| CliError::FailedToAccessUserTemplates(Rc::new(e)))
+        });
+        if let Err(e) = result {
+            return e.into();
+        }
+        CliData::Templates(CliTemplates::new(None, Some(&library)))
+    } else {
+        let Some(path) = path else {
+            return CliError::MissingFile.into();
+        };
+        match EvidencePackage::open(path) {
+            Ok(mut package) => {
+                let mut library = package.metadata().templates().clone().unwrap_or_default();
+                if let Err(e) = modify(Some(&mut package), &mut library) {
+                    return e.into();
+                }
+                *package.metadata_mut().templates_mut() = library;
+                if let Err(e) = package.save() {
+                    return CliError::FailedToSavePackage(Rc::new(e)).into();
+                }
+                CliData::Templates(CliTemplates::new(
+                    package.metadata().templates().as_ref(),
+                    None,
+                ))
+            }
+            Err(e) => CliError::FailedToReadPackage(Rc::new(e)).into(),
+        }
+    }
+}
+
+/// Process the templates subcommand
+pub fn process(path: Option<PathBuf>, command: &TemplatesSubcommand) -> CliData {
+    match command {
+        TemplatesSubcommand::List { user } => {
+            let library = match user_library() {
+                Ok(library) => library,
+                Err(e) => return e.into(),
+            };
+            if *user {
+                return CliData::Templates(CliTemplates::new(None, Some(&library)));
+            }
+            let Some(path) = path else {
+                return CliError::MissingFile.into();
+            };
+            match EvidencePackage::open(path) {
+                Ok(package) => CliData::Templates(CliTemplates::new(
+                    package.metadata().templates().as_ref(),
+                    Some(&library),
+                )),
+                Err(e) => CliError::FailedToReadPackage(Rc::new(e)).into(),
+            }
+        }
+        TemplatesSubcommand::Create {
+            case,
+            name,
+            title,
+            description,
+            user,
+        } => {
+            if path.is_none() {
+                return CliError::MissingFile.into();
+            }
+            modify_library(path, *user, |package, library| {
+                // SAFETY: a package is always opened as a path is present
+                let package = package.unwrap();
+                let case_id = match_test_case(package, case)
+                    .ok_or_else(|| CliError::CannotMatchTestCase(case.clone()))?;
+                // SAFETY: test case matched above
+                let test_case = package.test_case(case_id).unwrap().unwrap();
+                let mut template = TestCaseTemplate::from_test_case(name, title, test_case);
+                template.set_description(description.clone());
+                library.insert(template);
+                Ok(())
+            })
+        }
+        TemplatesSubcommand::Delete { name, user } => {
+            modify_library(path, *user, |_package, library| {
+                library
+                    .remove(name)
+                    .map(|_| ())
+                    .ok_or_else(|| CliError::TemplateNotFound(name.clone()))
+            })
+        }
+    }
+}
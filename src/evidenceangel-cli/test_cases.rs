@@ -1,9 +1,13 @@
 use std::{
+    collections::BTreeMap,
+    env,
     fmt::{self, Write},
     fs,
     io::{self, Cursor, Read},
     path::PathBuf,
+    process,
     rc::Rc,
+    time::{Duration, Instant},
 };
 
 use angelmark::{AngelmarkLine, AngelmarkTableAlignment, parse_angelmark};
@@ -11,8 +15,12 @@ use chrono::FixedOffset;
 use clap::{Subcommand, ValueEnum};
 use colored::Colorize;
 use evidenceangel::{
-    Evidence, EvidenceData, EvidenceKind, EvidencePackage, MediaFile, TestCasePassStatus,
+    CommandExecution, Diff, DiffChange, DiffFormat, Evidence, EvidenceData, EvidenceKind,
+    EvidencePackage, HttpExchange, HttpHeader, Link, LinkSystem, Log, LogFormat, MediaFile,
+    SqlQuery, SqlToken, StepStatus, StructuredEvidence, Table, TableFormat, TestCase,
+    TestCasePassStatus, TestStep, parse_har,
 };
+use regex::Regex;
 use schemars::JsonSchema;
 use serde::Serialize;
 use uuid::Uuid;
@@ -24,9 +32,14 @@ use crate::result::{CliData, CliError};
 pub enum TestCasesSubcommand {
     /// Create a new test case.
     Create {
-        /// The title of the new test case.
-        #[arg(index = 1)]
-        title: String,
+        /// The title of the new test case. This can be left out when creating from a template, to
+        /// use the template's title.
+        #[arg(index = 1, required_unless_present = "template")]
+        title: Option<String>,
+        /// The name of a template to create the test case from, either from this package or from
+        /// the templates for all packages you work with.
+        #[arg(short, long)]
+        template: Option<String>,
         /// The execution time of the new test case.
         #[arg(short, long)]
         executed_at: Option<String>,
@@ -126,6 +139,59 @@ pub enum TestCasesSubcommand {
         #[arg(index = 2)]
         evidence_id: usize,
     },
+    /// Add a step to a scripted test case.
+    AddStep {
+        /// The one-based index of the test case to add a step to, or enough of the title to uniquely match against one test case.
+        #[arg(index = 1)]
+        case: String,
+        /// What the tester should do.
+        #[arg(index = 2)]
+        action: String,
+        /// What should happen.
+        #[arg(short, long)]
+        expected: Option<String>,
+        /// The one-based position to insert the step at. Defaults to the end.
+        #[arg(short, long)]
+        position: Option<usize>,
+        /// The one-based indexes of evidence to attach to the step, separated by commas.
+        #[arg(long, value_delimiter = ',')]
+        evidence: Vec<usize>,
+    },
+    /// Update a step of a test case. If the step's status changes and the test case's status can
+    /// be worked out from its steps, the test case's status is updated to match.
+    UpdateStep {
+        /// The one-based index of the test case to update, or enough of the title to uniquely match against one test case.
+        #[arg(index = 1)]
+        case: String,
+        /// The one-based index of the step to update.
+        #[arg(index = 2)]
+        step: usize,
+        /// What the tester should do.
+        #[arg(long)]
+        action: Option<String>,
+        /// What should happen.
+        #[arg(short, long)]
+        expected: Option<String>,
+        /// What actually happened.
+        #[arg(short, long)]
+        actual: Option<String>,
+        /// The result of the step, "pass", "fail", "blocked", "skipped" or "none".
+        #[arg(short, long)]
+        status: Option<String>,
+        /// The one-based indexes of evidence attached to the step, separated by commas. This
+        /// replaces any evidence already attached.
+        #[arg(long, value_delimiter = ',', num_args = 0..)]
+        evidence: Option<Vec<usize>>,
+    },
+    /// Delete a step from a test case. Evidence attached to the step isn't deleted.
+    DeleteStep {
+        /// The one-based index of the test case to delete a step from, or enough of the title to uniquely match against one test case.
+        #[arg(index = 1)]
+        case: String,
+        /// The one-based index of the step to delete.
+        #[arg(index = 2)]
+        step: usize,
+    },
 }
 
 #[derive(Subcommand, Clone)]
@@ -159,6 +225,17 @@ pub enum EvidenceValue {
         /// An optional caption
         #[arg(index = 2)]
         caption: Option<String>,
+        /// How long the exchange took, in milliseconds
+        #[arg(long, conflicts_with = "har")]
+        duration_ms: Option<u64>,
+        /// Import the exchanges recorded in a HAR file, such as one exported from browser
+        /// developer tools, instead of reading the text. Each exchange is added as a separate
+        /// piece of evidence, captioned with its timings after the caption if one is given.
+        #[arg(long)]
+        har: Option<PathBuf>,
+        /// Only import exchanges from the HAR file with a URL matching this regular expression
+        #[arg(long, requires = "har")]
+        filter: Option<String>,
     },
     /// A file
     File {
@@ -169,6 +246,129 @@ pub enum EvidenceValue {
         #[arg(index = 2)]
         caption: Option<String>,
     },
+    /// A log, parsed into timestamped entries with levels and sources
+    Log {
+        /// The log file to add, or `-` to read from stdin.
+        #[arg(index = 1, default_value = "-")]
+        path: String,
+        /// An optional caption
+        #[arg(index = 2)]
+        caption: Option<String>,
+        /// The format of the log, "auto", "plain", "jsonl" or "syslog".
+        #[arg(short, long, default_value = "auto")]
+        format: String,
+    },
+    /// A video or screen recording, in MP4 or WebM format
+    Video {
+        /// The video to add as evidence
+        #[arg(index = 1)]
+        video: PathBuf,
+        /// An optional caption
+        #[arg(index = 2)]
+        caption: Option<String>,
+        /// A PNG or JPEG image to show before the video is played
+        #[arg(short, long)]
+        poster: Option<PathBuf>,
+    },
+    /// A table of data, with column headers on the first row
+    Table {
+        /// The table file to add, or `-` to read from stdin.
+        #[arg(index = 1, default_value = "-")]
+        path: String,
+        /// An optional caption
+        #[arg(index = 2)]
+        caption: Option<String>,
+        /// The format of the table, "auto", "csv", "tsv" or "json".
+        #[arg(short, long, default_value = "auto")]
+        format: String,
+    },
+    /// A comparison of expected and actual content, shown as a diff
+    Diff {
+        /// The file containing the expected content
+        #[arg(index = 1)]
+        expected: PathBuf,
+        /// The file containing the actual content
+        #[arg(index = 2)]
+        actual: PathBuf,
+        /// An optional caption
+        #[arg(index = 3)]
+        caption: Option<String>,
+        /// The format of the content, "auto", "text" or "json".
+        #[arg(short, long, default_value = "auto")]
+        format: String,
+    },
+    /// Execute a command, recording its output, exit code and duration
+    Command {
+        /// The command to execute and its arguments, after `--`
+        #[arg(
+            index = 1,
+            required = true,
+            trailing_var_arg = true,
+            allow_hyphen_values = true
+        )]
+        command: Vec<String>,
+        /// An optional caption
+        #[arg(short, long)]
+        caption: Option<String>,
+        /// An environment variable to record, either `NAME` to record its current value, or
+        /// `NAME=VALUE` to set it for the command. Can be provided multiple times.
+        #[arg(short, long = "env")]
+        environment: Vec<String>,
+    },
+    /// A link to a defect, ticket, build pipeline or documentation
+    Link {
+        /// The URL to link to
+        #[arg(index = 1)]
+        url: String,
+        /// An optional caption
+        #[arg(index = 2)]
+        caption: Option<String>,
+        /// A title describing what is being linked to
+        #[arg(short, long)]
+        title: Option<String>,
+        /// The type of system being linked to, "defect", "ticket", "build", "documentation" or
+        /// "other".
+        #[arg(short, long)]
+        system: Option<String>,
+    },
+    /// A SQL query, and optionally the rows it returned
+    Sql {
+        /// The file containing the query, or `-` to read from stdin.
+        #[arg(index = 1, default_value = "-")]
+        query: String,
+        /// An optional caption
+        #[arg(index = 2)]
+        caption: Option<String>,
+        /// A label identifying the database the query was executed against
+        #[arg(short, long)]
+        database: Option<String>,
+        /// A table file containing the rows returned by the query
+        #[arg(short, long)]
+        result: Option<PathBuf>,
+        /// The format of the result, "auto", "csv", "tsv" or "json".
+        #[arg(short, long, default_value = "auto")]
+        format: String,
+        /// The number of rows returned or affected, if different to the number of rows in the
+        /// result
+        #[arg(long)]
+        row_count: Option<u64>,
+        /// How long the query took to execute, in milliseconds
+        #[arg(long)]
+        duration_ms: Option<u64>,
+    },
+}
+
+/// Quote an argument so that a command line can be copied into a shell.
+fn quote_argument(arg: &str) -> String {
+    if !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./=:,+@%".contains(c))
+    {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
 }
 
 /// Whether to position before or after
@@ -191,10 +391,61 @@ pub struct CliTestCase {
     status: CliTestCasePassStatus,
     /// Custom fields
     custom_fields: Vec<CliCustomField>,
+    /// The IDs of the requirements the test case covers
+    requirements: Vec<String>,
+    /// The steps of the test case
+    steps: Vec<CliTestStep>,
     /// The evidence in the test case
     evidence: Vec<CliEvidence>,
 }
 
+impl CliTestCase {
+    /// Create a test case for display on screen or via JSON
+    fn new(package: &mut EvidencePackage, case_id: Uuid) -> Self {
+        // SAFETY: callers only pass test cases that exist
+        let test_case = package.test_case(case_id).unwrap().unwrap().clone();
+        let fields = package
+            .metadata()
+            .custom_test_case_metadata()
+            .clone()
+            .unwrap_or_default();
+        Self {
+            name: test_case.metadata().title().clone(),
+            executed_at: *test_case.metadata().execution_datetime(),
+            status: match test_case.metadata().passed() {
+                None => CliTestCasePassStatus::None,
+                Some(TestCasePassStatus::Pass) => CliTestCasePassStatus::Pass,
+                Some(TestCasePassStatus::Fail) => CliTestCasePassStatus::Fail,
+            },
+            custom_fields: test_case.metadata().custom().as_ref().map_or(vec![], |m| {
+                m.iter()
+                    .filter_map(|(key, val)| {
+                        fields.get(key).map(|field| CliCustomField {
+                            id: key.clone(),
+                            name: field.name().clone(),
+                            description: field.description().clone(),
+                            value: val.clone(),
+                        })
+                    })
+                    .collect()
+            }),
+            requirements: test_case
+                .metadata()
+                .requirements()
+                .iter()
+                .flatten()
+                .map(|r| r.id().clone())
+                .collect(),
+            steps: test_case.steps().iter().map(CliTestStep::from).collect(),
+            evidence: test_case
+                .evidence()
+                .iter()
+                .map(|ev| CliEvidence::from_evidence(ev, package))
+                .collect(),
+        }
+    }
+}
+
 impl fmt::Display for CliTestCase {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         writeln!(f, "🧪 {}", self.name.bold())?;
@@ -204,12 +455,55 @@ impl fmt::Display for CliTestCase {
             CliTestCasePassStatus::Pass => writeln!(f, "  ✅ {}", "Passed".green())?,
             CliTestCasePassStatus::Fail => writeln!(f, "  ❌ {}", "Failed".red())?,
         }
+        if !self.requirements.is_empty() {
+            writeln!(
+                f,
+                "  {} {}",
+                "Requirements:".bold(),
+                self.requirements.join(", ").blue()
+            )?;
+        }
         writeln!(f, "  {}:", "Custom fields".bold())?;
         for field in &self.custom_fields {
             writeln!(f, "  {field}")?;
         }
         writeln!(f)?;
 
+        for (idx, step) in self.steps.iter().enumerate() {
+            writeln!(
+                f,
+                "{} {}{}",
+                format!("[Step {}]", idx + 1).blue(),
+                match step.status {
+                    CliStepStatus::None => String::new(),
+                    CliStepStatus::Pass => format!("{} ", "✅".green()),
+                    CliStepStatus::Fail => format!("{} ", "❌".red()),
+                    CliStepStatus::Blocked => format!("{} ", "⛔ Blocked".yellow()),
+                    CliStepStatus::Skipped => format!("{} ", "⏭ Skipped".dimmed()),
+                },
+                step.action.bold()
+            )?;
+            if let Some(expected) = &step.expected {
+                writeln!(f, "  {} {expected}", "Expected:".dimmed())?;
+            }
+            if let Some(actual) = &step.actual {
+                writeln!(f, "  {} {actual}", "Actual:".dimmed())?;
+            }
+            if !step.evidence.is_empty() {
+                writeln!(
+                    f,
+                    "  {} {}",
+                    "Evidence:".dimmed(),
+                    step.evidence
+                        .iter()
+                        .map(|i| format!("#{i}"))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )?;
+            }
+            writeln!(f)?;
+        }
+
         for (idx, ev) in self.evidence.iter().enumerate() {
             writeln!(
                 f,
@@ -300,13 +594,151 @@ impl fmt::Display for CliTestCase {
                             "Invalid rich text".italic().red().to_string()
                         }
                     }
-                    CliEvidence::Http => "HTTP request".magenta().to_string(),
+                    CliEvidence::Http {
+                        request,
+                        response,
+                        duration_seconds,
+                    } => {
+                        let mut text = request.to_string();
+                        if let Some(response) = response {
+                            let _ = write!(text, "\n\n{response}");
+                        }
+                        if let Some(duration) = duration_seconds {
+                            let _ = write!(text, "\n{}", format!("{duration:.3}s").dimmed());
+                        }
+                        text
+                    }
                     CliEvidence::Image => "Image".magenta().to_string(),
                     CliEvidence::File { original_filename } => format!(
                         "{} {}",
                         "File".magenta(),
                         original_filename.clone().unwrap_or_default()
                     ),
+                    CliEvidence::Video {
+                        original_filename,
+                        duration_seconds,
+                        has_poster,
+                    } => {
+                        let mut text = format!(
+                            "{} {}",
+                            "Video".magenta(),
+                            original_filename.clone().unwrap_or_default()
+                        );
+                        if let Some(duration) = duration_seconds
+                            .and_then(|duration| Duration::try_from_secs_f64(duration).ok())
+                        {
+                            let seconds = duration.as_secs();
+                            let _ = write!(text, " ({}:{:02})", seconds / 60, seconds % 60);
+                        }
+                        if *has_poster {
+                            let _ = write!(text, " {}", "with poster".dimmed());
+                        }
+                        text
+                    }
+                    CliEvidence::Log { entries } => entries
+                        .iter()
+                        .fold(String::new(), |mut output, entry| {
+                            let _ = writeln!(output, "{entry}");
+                            output
+                        })
+                        .trim_end()
+                        .to_string(),
+                    CliEvidence::Command {
+                        command,
+                        working_directory,
+                        environment,
+                        exit_code,
+                        duration_seconds,
+                        stdout,
+                        stderr,
+                    } => {
+                        let mut text = String::new();
+                        if let Some(working_directory) = working_directory {
+                            let _ = write!(text, "{} ", working_directory.blue());
+                        }
+                        let _ = write!(text, "{} {}", "$".green(), command.bold());
+                        for (key, value) in environment {
+                            let _ = write!(text, "\n{}", format!("{key}={value}").dimmed());
+                        }
+                        for output in [stdout, stderr] {
+                            if !output.is_empty() {
+                                let _ = write!(text, "\n{}", output.trim_end());
+                            }
+                        }
+                        let mut status = vec![];
+                        if let Some(exit_code) = exit_code {
+                            let exit = format!("exit code {exit_code}");
+                            status.push(if *exit_code == 0 {
+                                exit.green().to_string()
+                            } else {
+                                exit.red().bold().to_string()
+                            });
+                        }
+                        if let Some(duration) = duration_seconds {
+                            status.push(format!("{duration:.3}s").dimmed().to_string());
+                        }
+                        if !status.is_empty() {
+                            let _ = write!(text, "\n{}", status.join(" "));
+                        }
+                        text
+                    }
+                    CliEvidence::Diff { lines } => lines
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                    CliEvidence::Table { columns, rows } => format_table(columns, rows),
+                    CliEvidence::Link { url, title, system } => {
+                        let mut text = String::new();
+                        if let Some(system) = system {
+                            let _ = write!(text, "{} ", system.magenta());
+                        }
+                        if let Some(title) = title {
+                            let _ = write!(text, "{} ", title.bold());
+                        }
+                        let _ = write!(text, "{}", url.blue().underline());
+                        text
+                    }
+                    CliEvidence::Sql {
+                        query,
+                        database,
+                        row_count,
+                        duration_seconds,
+                        columns,
+                        rows,
+                    } => {
+                        let mut text = SqlQuery::new(query.clone())
+                            .tokens()
+                            .into_iter()
+                            .map(|(token, text)| match token {
+                                SqlToken::Keyword => text.blue().bold().to_string(),
+                                SqlToken::String => text.red().to_string(),
+                                SqlToken::Number => text.green().to_string(),
+                                SqlToken::Comment => text.dimmed().italic().to_string(),
+                                SqlToken::Parameter => text.magenta().to_string(),
+                                SqlToken::Identifier | SqlToken::Other => text.to_string(),
+                            })
+                            .collect::<String>()
+                            .trim_end()
+                            .to_string();
+                        let mut details = vec![];
+                        if let Some(database) = database {
+                            details.push(database.bold().to_string());
+                        }
+                        details.push(if *row_count == 1 {
+                            "1 row".to_string()
+                        } else {
+                            format!("{row_count} rows")
+                        });
+                        if let Some(duration) = duration_seconds {
+                            details.push(format!("{duration:.3}s"));
+                        }
+                        let _ = write!(text, "\n{}", details.join(" · ").dimmed());
+                        if !columns.is_empty() {
+                            let _ = write!(text, "\n{}", format_table(columns, rows));
+                        }
+                        text
+                    }
                 }
             )?;
         }
@@ -327,6 +759,55 @@ pub enum CliTestCasePassStatus {
     None,
 }
 
+/// A step of a test case
+#[derive(Serialize, JsonSchema)]
+struct CliTestStep {
+    /// What the tester should do
+    action: String,
+    /// What should happen
+    expected: Option<String>,
+    /// What actually happened
+    actual: Option<String>,
+    /// The result of the step
+    status: CliStepStatus,
+    /// The one-based indexes of the evidence attached to the step
+    evidence: Vec<usize>,
+}
+
+impl From<&TestStep> for CliTestStep {
+    fn from(step: &TestStep) -> Self {
+        Self {
+            action: step.action().clone(),
+            expected: step.expected().clone(),
+            actual: step.actual().clone(),
+            status: match step.status() {
+                None => CliStepStatus::None,
+                Some(StepStatus::Pass) => CliStepStatus::Pass,
+                Some(StepStatus::Fail) => CliStepStatus::Fail,
+                Some(StepStatus::Blocked) => CliStepStatus::Blocked,
+                Some(StepStatus::Skipped) => CliStepStatus::Skipped,
+            },
+            evidence: step.evidence().iter().map(|i| i + 1).collect(),
+        }
+    }
+}
+
+/// Possible results of a step
+#[derive(Serialize, JsonSchema)]
+#[serde(tag = "type")]
+enum CliStepStatus {
+    /// The step passed
+    Pass,
+    /// The step failed
+    Fail,
+    /// The step couldn't be run
+    Blocked,
+    /// The step was deliberately not run
+    Skipped,
+    /// The step hasn't been run
+    None,
+}
+
 /// A custom metadata field
 #[derive(Serialize, JsonSchema)]
 pub struct CliCustomField {
@@ -361,7 +842,14 @@ pub enum CliEvidence {
         data: String,
     },
     /// An HTTP request and response
-    Http,
+    Http {
+        /// The request that was sent
+        request: CliHttpMessage,
+        /// The response that was received, if there was one
+        response: Option<CliHttpMessage>,
+        /// How long the exchange took in seconds, if known
+        duration_seconds: Option<f64>,
+    },
     /// An image
     Image,
     /// File evidence
@@ -369,11 +857,359 @@ pub enum CliEvidence {
         /// The original filename of this evidence, if available.
         original_filename: Option<String>,
     },
+    /// Log evidence
+    Log {
+        /// The entries in this log
+        entries: Vec<CliLogEntry>,
+    },
+    /// Video evidence
+    Video {
+        /// The original filename of this video, if available.
+        original_filename: Option<String>,
+        /// The duration of this video in seconds, if known.
+        duration_seconds: Option<f64>,
+        /// Whether this video has a poster frame.
+        has_poster: bool,
+    },
+    /// Command execution evidence
+    Command {
+        /// The command line that was executed
+        command: String,
+        /// The directory the command was executed in, if known
+        working_directory: Option<String>,
+        /// The environment variables recorded for the command
+        environment: BTreeMap<String, String>,
+        /// The code the command exited with, if known
+        exit_code: Option<i32>,
+        /// How long the command took in seconds, if known
+        duration_seconds: Option<f64>,
+        /// The standard output of the command
+        stdout: String,
+        /// The standard error of the command
+        stderr: String,
+    },
+    /// Diff evidence
+    Diff {
+        /// The lines of the unified diff
+        lines: Vec<CliDiffLine>,
+    },
+    /// Table evidence
+    Table {
+        /// The column headers of this table
+        columns: Vec<String>,
+        /// The rows of this table, with each cell as text
+        rows: Vec<Vec<String>>,
+    },
+    /// Link evidence
+    Link {
+        /// The URL linked to
+        url: String,
+        /// The title of the link, if it has one
+        title: Option<String>,
+        /// The type of system linked to, if known
+        system: Option<String>,
+    },
+    /// SQL query evidence
+    Sql {
+        /// The text of the query
+        query: String,
+        /// The label of the database the query was executed against, if known
+        database: Option<String>,
+        /// The number of rows returned or affected by the query
+        row_count: u64,
+        /// How long the query took in seconds, if known
+        duration_seconds: Option<f64>,
+        /// The column headers of the result
+        columns: Vec<String>,
+        /// The rows of the result, with each cell as text
+        rows: Vec<Vec<String>>,
+    },
+}
+
+impl CliEvidence {
+    /// Convert a piece of [`Evidence`] to its CLI representation.
+    fn from_evidence(ev: &Evidence, package: &mut EvidencePackage) -> Self {
+        match ev.kind() {
+            EvidenceKind::Text => CliEvidence::Text {
+                data: String::from_utf8(ev.value().get_data(package).unwrap()).unwrap(),
+            },
+            EvidenceKind::RichText => CliEvidence::RichText {
+                data: String::from_utf8(ev.value().get_data(package).unwrap()).unwrap(),
+            },
+            EvidenceKind::Image => CliEvidence::Image,
+            EvidenceKind::Http => {
+                let exchange = HttpExchange::from_evidence(ev, package).unwrap_or_default();
+                let request = exchange.request();
+                CliEvidence::Http {
+                    request: CliHttpMessage {
+                        start_line: request.start_line(),
+                        status: None,
+                        headers: request.headers().iter().map(CliHttpHeader::from).collect(),
+                        body: request.pretty_body(),
+                    },
+                    response: exchange.response().as_ref().map(|response| CliHttpMessage {
+                        start_line: response.start_line(),
+                        status: response.status(),
+                        headers: response.headers().iter().map(CliHttpHeader::from).collect(),
+                        body: response.pretty_body(),
+                    }),
+                    duration_seconds: exchange.duration().map(|duration| duration.as_secs_f64()),
+                }
+            }
+            EvidenceKind::File => CliEvidence::File {
+                original_filename: ev.original_filename().clone(),
+            },
+            EvidenceKind::Log => CliEvidence::Log {
+                entries: Log::from_evidence(ev, package)
+                    .map(|log| {
+                        log.entries()
+                            .iter()
+                            .map(|entry| CliLogEntry {
+                                timestamp: entry.timestamp(),
+                                level: entry.level().map(|level| level.to_string()),
+                                source: entry.source().clone(),
+                                message: entry.message().clone(),
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+            },
+            EvidenceKind::Video => {
+                let media = match ev.value() {
+                    EvidenceData::Media { hash } => package.get_media(hash).ok().flatten(),
+                    _ => None,
+                };
+                CliEvidence::Video {
+                    original_filename: media.and_then(|media| media.original_filename().clone()),
+                    duration_seconds: media
+                        .and_then(MediaFile::video_duration)
+                        .map(|duration| duration.as_secs_f64()),
+                    has_poster: ev.poster().is_some(),
+                }
+            }
+            EvidenceKind::Command => {
+                let command = CommandExecution::from_evidence(ev, package).unwrap_or_default();
+                CliEvidence::Command {
+                    command: command.command().clone(),
+                    working_directory: command.working_directory().clone(),
+                    environment: command.environment().clone(),
+                    exit_code: command.exit_code(),
+                    duration_seconds: command.duration().map(|duration| duration.as_secs_f64()),
+                    stdout: command.stdout().clone(),
+                    stderr: command.stderr().clone(),
+                }
+            }
+            EvidenceKind::Diff => CliEvidence::Diff {
+                lines: Diff::from_evidence(ev, package)
+                    .map(|diff| {
+                        diff.lines()
+                            .iter()
+                            .map(|line| CliDiffLine {
+                                change: match line.change() {
+                                    DiffChange::Unchanged => CliDiffChange::Unchanged,
+                                    DiffChange::Removed => CliDiffChange::Removed,
+                                    DiffChange::Added => CliDiffChange::Added,
+                                },
+                                text: line.text().clone(),
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+            },
+            EvidenceKind::Table => {
+                let table = Table::from_evidence(ev, package).unwrap_or_default();
+                CliEvidence::Table {
+                    columns: table.columns().clone(),
+                    rows: table
+                        .rows()
+                        .iter()
+                        .map(|row| row.iter().map(ToString::to_string).collect())
+                        .collect(),
+                }
+            }
+            EvidenceKind::Link => {
+                let link = Link::from_evidence(ev, package).unwrap_or_default();
+                CliEvidence::Link {
+                    url: link.url().clone(),
+                    title: link.title().clone(),
+                    system: link.system().map(|system| system.to_string()),
+                }
+            }
+            EvidenceKind::Sql => {
+                let query = SqlQuery::from_evidence(ev, package).unwrap_or_default();
+                CliEvidence::Sql {
+                    query: query.query().clone(),
+                    database: query.database().clone(),
+                    row_count: query.effective_row_count(),
+                    duration_seconds: query.duration().map(|duration| duration.as_secs_f64()),
+                    columns: query.result().columns().clone(),
+                    rows: query
+                        .result()
+                        .rows()
+                        .iter()
+                        .map(|row| row.iter().map(ToString::to_string).collect())
+                        .collect(),
+                }
+            }
+        }
+    }
+}
+
+/// Format a table as aligned columns, with the column headers in bold.
+fn format_table(columns: &[String], rows: &[Vec<String>]) -> String {
+    // Pad each column to the width of its widest value
+    let mut widths = columns
+        .iter()
+        .map(|column| column.chars().count())
+        .collect::<Vec<_>>();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let pad =
+        |text: &str, width: usize| format!("{text}{}", " ".repeat(width - text.chars().count()));
+
+    let mut text = columns
+        .iter()
+        .zip(&widths)
+        .map(|(column, width)| pad(column, *width).bold().to_string())
+        .collect::<Vec<_>>()
+        .join("  ");
+    for row in rows {
+        text.push('\n');
+        text.push_str(
+            &row.iter()
+                .zip(&widths)
+                .map(|(cell, width)| pad(cell, *width))
+                .collect::<Vec<_>>()
+                .join("  "),
+        );
+    }
+    text
+}
+
+/// A line of diff evidence
+#[derive(Serialize, JsonSchema)]
+pub struct CliDiffLine {
+    /// How this line changed
+    change: CliDiffChange,
+    /// The text of this line
+    text: String,
+}
+
+/// How a line of diff evidence changed
+#[derive(Serialize, JsonSchema)]
+pub enum CliDiffChange {
+    /// The line is the same in the expected and actual content
+    Unchanged,
+    /// The line was expected but is missing
+    Removed,
+    /// The line wasn't expected
+    Added,
+}
+
+impl fmt::Display for CliDiffLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.change {
+            CliDiffChange::Unchanged => write!(f, "  {}", self.text),
+            CliDiffChange::Removed => write!(f, "{}", format!("- {}", self.text).red()),
+            CliDiffChange::Added => write!(f, "{}", format!("+ {}", self.text).green()),
+        }
+    }
+}
+
+/// An entry in log evidence
+#[derive(Serialize, JsonSchema)]
+pub struct CliLogEntry {
+    /// The time this entry was written, if known
+    timestamp: Option<chrono::DateTime<FixedOffset>>,
+    /// The level of this entry, if known
+    level: Option<String>,
+    /// The component that wrote this entry, if known
+    source: Option<String>,
+    /// The message of this entry
+    message: String,
+}
+
+impl fmt::Display for CliLogEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(timestamp) = &self.timestamp {
+            write!(
+                f,
+                "{} ",
+                timestamp.format("%Y-%m-%d %H:%M:%S").to_string().dimmed()
+            )?;
+        }
+        if let Some(level) = &self.level {
+            let level = format!("{level:5}");
+            let level = match level.trim() {
+                "TRACE" | "DEBUG" => level.dimmed(),
+                "WARN" => level.yellow(),
+                "ERROR" | "FATAL" => level.red().bold(),
+                _ => level.green(),
+            };
+            write!(f, "{level} ")?;
+        }
+        if let Some(source) = &self.source {
+            write!(f, "{}: ", source.cyan())?;
+        }
+        write!(f, "{}", self.message)
+    }
+}
+
+/// One side of an HTTP exchange
+#[derive(Serialize, JsonSchema)]
+pub struct CliHttpMessage {
+    /// The first line of the message, such as `GET / HTTP/1.1` or `HTTP/1.1 200 OK`
+    start_line: String,
+    /// The status code of a response, if known
+    status: Option<u16>,
+    /// The headers of the message, in order
+    headers: Vec<CliHttpHeader>,
+    /// The body of the message, reformatted if it is JSON or XML
+    body: String,
+}
+
+impl fmt::Display for CliHttpMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let start_line = match self.status {
+            Some(status) if (200..400).contains(&status) => self.start_line.green().bold(),
+            Some(_) => self.start_line.red().bold(),
+            None => self.start_line.bold(),
+        };
+        write!(f, "{start_line}")?;
+        for header in &self.headers {
+            write!(f, "\n{}: {}", header.name.cyan(), header.value)?;
+        }
+        if !self.body.is_empty() {
+            write!(f, "\n\n{}", self.body.trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+/// A header of an HTTP message
+#[derive(Serialize, JsonSchema)]
+pub struct CliHttpHeader {
+    /// The name of the header
+    name: String,
+    /// The value of the header
+    value: String,
+}
+
+impl From<&HttpHeader> for CliHttpHeader {
+    fn from(header: &HttpHeader) -> Self {
+        Self {
+            name: header.name().clone(),
+            value: header.value().clone(),
+        }
+    }
 }
 
 /// Match a test case by a string, either a number (id) of the test case, or a
 /// partial text match to the title
-fn match_test_case(package: &mut EvidencePackage, case: &str) -> Option<Uuid> {
+pub fn match_test_case(package: &mut EvidencePackage, case: &str) -> Option<Uuid> {
     let test_cases: Vec<_> = package
         .test_case_iter()
         .unwrap()
@@ -411,6 +1247,46 @@ fn match_test_case(package: &mut EvidencePackage, case: &str) -> Option<Uuid> {
     }
 }
 
+/// Read the exchanges from the HAR file of an [`EvidenceValue::Http`], keeping those with URLs
+/// matching its filter, and convert each to an [`Evidence`] struct from EvidenceAngel.
+fn evidence_from_har(evidence_value: &EvidenceValue) -> Result<Vec<Evidence>, CliError> {
+    let EvidenceValue::Http {
+        caption,
+        har: Some(har),
+        filter,
+        ..
+    } = evidence_value
+    else {
+        unreachable!("only called for HTTP evidence imported from a HAR file");
+    };
+    let filter = filter
+        .as_ref()
+        .map(|filter| Regex::new(filter))
+        .transpose()
+        .map_err(|e| CliError::InvalidHarFilter(e.to_string()))?;
+    let text = fs::read_to_string(har).map_err(|_| CliError::FailedToReadFile)?;
+    let evidence = parse_har(&text)
+        .map_err(|e| CliError::InvalidHar(e.to_string()))?
+        .into_iter()
+        .filter(|entry| {
+            filter
+                .as_ref()
+                .is_none_or(|filter| filter.is_match(entry.url()))
+        })
+        .map(|entry| {
+            let mut evidence = entry.to_evidence();
+            if let Some(caption) = caption {
+                evidence.set_caption(Some(format!("{caption}: {}", entry.summary())));
+            }
+            evidence
+        })
+        .collect::<Vec<_>>();
+    if evidence.is_empty() {
+        return Err(CliError::NoMatchingHarEntries);
+    }
+    Ok(evidence)
+}
+
 /// Convert an [`EvidenceValue`] from the CLI args to an [`Evidence`] struct
 /// from EvidenceAngel.
 fn evidence_from_evidence_value(
@@ -445,25 +1321,38 @@ fn evidence_from_evidence_value(
             ))
         }
         EvidenceValue::Image { image, caption } => {
-            let media: MediaFile = fs::read(image)
+            let mut media: MediaFile = fs::read(&image)
                 .map_err(|_| CliError::FailedToReadFile)?
                 .into();
+            media.set_original_filename(image.file_name().map(|s| s.to_string_lossy().to_string()));
             let hash = media.hash();
-            if let Some(mime) = media.mime_type() {
-                if !["image/png", "image/jpeg"].contains(&mime.mime_type()) {
-                    return Err(CliError::InvalidImage);
-                }
-            } else {
+            if !["image/png", "image/jpeg"].contains(&media.media_type()) {
                 return Err(CliError::InvalidImage);
             }
             package
                 .add_media(media)
                 .map_err(|_| CliError::CouldntAddMedia)?;
+            // Generate the thumbnail now so that it is stored when the package is saved
+            package
+                .get_thumbnail(&hash)
+                .map_err(|_| CliError::CouldntAddMedia)?;
             let mut evidence = Evidence::new(EvidenceKind::Image, EvidenceData::Media { hash });
             evidence.set_caption(caption.clone());
             Ok(evidence)
         }
-        EvidenceValue::Http { value, caption } => {
+        EvidenceValue::Http { har: Some(_), .. } => {
+            let mut evidence = evidence_from_har(evidence_value)?;
+            if evidence.len() > 1 {
+                return Err(CliError::TooManyHarEntries(evidence.len()));
+            }
+            Ok(evidence.remove(0))
+        }
+        EvidenceValue::Http {
+            value,
+            caption,
+            duration_ms,
+            ..
+        } => {
             let mut buf = vec![];
             if value == "-" {
                 io::stdin()
@@ -472,15 +1361,21 @@ fn evidence_from_evidence_value(
             } else {
                 buf = value.into_bytes();
             }
-            let mut evidence =
-                Evidence::new(EvidenceKind::Http, EvidenceData::Base64 { data: buf });
+            let mut evidence = if let Some(duration_ms) = duration_ms {
+                let mut exchange = HttpExchange::parse(&String::from_utf8_lossy(&buf));
+                exchange.set_duration(Some(Duration::from_millis(duration_ms)));
+                exchange.to_evidence()
+            } else {
+                Evidence::new(EvidenceKind::Http, EvidenceData::Base64 { data: buf })
+            };
             evidence.set_caption(caption.clone());
             Ok(evidence)
         }
         EvidenceValue::File { path, caption } => {
-            let media: MediaFile = fs::read(&path)
+            let mut media: MediaFile = fs::read(&path)
                 .map_err(|_| CliError::FailedToReadFile)?
                 .into();
+            media.set_original_filename(path.file_name().map(|s| s.to_string_lossy().to_string()));
             let hash = media.hash();
             package
                 .add_media(media)
@@ -491,6 +1386,224 @@ fn evidence_from_evidence_value(
                 .set_original_filename(path.file_name().map(|s| s.to_string_lossy().to_string()));
             Ok(evidence)
         }
+        EvidenceValue::Log {
+            path,
+            caption,
+            format,
+        } => {
+            let format: LogFormat = format
+                .parse()
+                .map_err(|()| CliError::InvalidLogFormat(format.clone()))?;
+            let mut buf = vec![];
+            if path == "-" {
+                io::stdin()
+                    .read_to_end(&mut buf)
+                    .expect("failed to read stdin");
+            } else {
+                buf = fs::read(&path).map_err(|_| CliError::FailedToReadFile)?;
+            }
+            let log = Log::parse(&String::from_utf8_lossy(&buf), format);
+            let mut evidence = log.to_evidence();
+            evidence.set_caption(caption.clone());
+            Ok(evidence)
+        }
+        EvidenceValue::Video {
+            video,
+            caption,
+            poster,
+        } => {
+            let mut media: MediaFile = fs::read(&video)
+                .map_err(|_| CliError::FailedToReadFile)?
+                .into();
+            media.set_original_filename(video.file_name().map(|s| s.to_string_lossy().to_string()));
+            if !media.is_supported_video() {
+                return Err(CliError::InvalidVideo);
+            }
+            let hash = media.hash();
+            package
+                .add_media(media)
+                .map_err(|_| CliError::CouldntAddMedia)?;
+
+            let poster_hash = if let Some(poster) = poster {
+                let mut media: MediaFile = fs::read(&poster)
+                    .map_err(|_| CliError::FailedToReadFile)?
+                    .into();
+                media.set_original_filename(
+                    poster.file_name().map(|s| s.to_string_lossy().to_string()),
+                );
+                if !["image/png", "image/jpeg"].contains(&media.media_type()) {
+                    return Err(CliError::InvalidImage);
+                }
+                let hash = media.hash();
+                package
+                    .add_media(media)
+                    .map_err(|_| CliError::CouldntAddMedia)?;
+                Some(hash)
+            } else {
+                None
+            };
+
+            let mut evidence = Evidence::new(EvidenceKind::Video, EvidenceData::Media { hash });
+            evidence.set_caption(caption.clone());
+            evidence.set_poster(poster_hash);
+            Ok(evidence)
+        }
+        EvidenceValue::Command {
+            command,
+            caption,
+            environment,
+        } => {
+            let mut execution = CommandExecution::new(
+                command
+                    .iter()
+                    .map(|arg| quote_argument(arg))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            );
+            let mut process = process::Command::new(&command[0]);
+            process.args(&command[1..]).stdin(process::Stdio::null());
+            if let Ok(working_directory) = env::current_dir() {
+                execution.set_working_directory(Some(working_directory.display().to_string()));
+            }
+            for variable in environment {
+                if let Some((key, value)) = variable.split_once('=') {
+                    process.env(key, value);
+                    execution
+                        .environment_mut()
+                        .insert(key.to_string(), value.to_string());
+                } else if let Ok(value) = env::var(&variable) {
+                    execution.environment_mut().insert(variable, value);
+                }
+            }
+
+            let started = Instant::now();
+            let output = process
+                .output()
+                .map_err(|e| CliError::FailedToExecuteCommand(e.to_string()))?;
+            execution.set_duration(Some(started.elapsed()));
+            execution.set_exit_code(output.status.code());
+            execution.set_stdout(String::from_utf8_lossy(&output.stdout).to_string());
+            execution.set_stderr(String::from_utf8_lossy(&output.stderr).to_string());
+
+            let mut evidence = execution.to_evidence();
+            evidence.set_caption(caption.clone());
+            Ok(evidence)
+        }
+        EvidenceValue::Diff {
+            expected,
+            actual,
+            caption,
+            format,
+        } => {
+            let format: DiffFormat = format
+                .parse()
+                .map_err(|()| CliError::InvalidDiffFormat(format.clone()))?;
+            let expected = fs::read(&expected).map_err(|_| CliError::FailedToReadFile)?;
+            let actual = fs::read(&actual).map_err(|_| CliError::FailedToReadFile)?;
+            let diff = Diff::parse(
+                &String::from_utf8_lossy(&expected),
+                &String::from_utf8_lossy(&actual),
+                format,
+            )
+            .map_err(|e| match e {
+                evidenceangel::Error::InvalidEvidenceData(message) => {
+                    CliError::InvalidDiff(message)
+                }
+                e => CliError::InvalidDiff(e.to_string()),
+            })?;
+            let mut evidence = diff.to_evidence();
+            evidence.set_caption(caption.clone());
+            Ok(evidence)
+        }
+        EvidenceValue::Table {
+            path,
+            caption,
+            format,
+        } => {
+            let format: TableFormat = format
+                .parse()
+                .map_err(|()| CliError::InvalidTableFormat(format.clone()))?;
+            let mut buf = vec![];
+            if path == "-" {
+                io::stdin()
+                    .read_to_end(&mut buf)
+                    .expect("failed to read stdin");
+            } else {
+                buf = fs::read(&path).map_err(|_| CliError::FailedToReadFile)?;
+            }
+            let table =
+                Table::parse(&String::from_utf8_lossy(&buf), format).map_err(|e| match e {
+                    evidenceangel::Error::InvalidEvidenceData(message) => {
+                        CliError::InvalidTable(message)
+                    }
+                    e => CliError::InvalidTable(e.to_string()),
+                })?;
+            let mut evidence = table.to_evidence();
+            evidence.set_caption(caption.clone());
+            Ok(evidence)
+        }
+        EvidenceValue::Link {
+            url,
+            caption,
+            title,
+            system,
+        } => {
+            let mut link = Link::new(url.clone()).map_err(|e| match e {
+                evidenceangel::Error::InvalidEvidenceData(message) => {
+                    CliError::InvalidLink(message)
+                }
+                e => CliError::InvalidLink(e.to_string()),
+            })?;
+            link.set_title(title.clone());
+            if let Some(system) = system {
+                let system: LinkSystem = system
+                    .parse()
+                    .map_err(|()| CliError::InvalidLinkSystem(system.clone()))?;
+                link.set_system(Some(system));
+            }
+            let mut evidence = link.to_evidence();
+            evidence.set_caption(caption.clone());
+            Ok(evidence)
+        }
+        EvidenceValue::Sql {
+            query,
+            caption,
+            database,
+            result,
+            format,
+            row_count,
+            duration_ms,
+        } => {
+            let mut buf = vec![];
+            if query == "-" {
+                io::stdin()
+                    .read_to_end(&mut buf)
+                    .expect("failed to read stdin");
+            } else {
+                buf = fs::read(query).map_err(|_| CliError::FailedToReadFile)?;
+            }
+            let mut sql = SqlQuery::new(String::from_utf8_lossy(&buf));
+            sql.set_database(database.clone());
+            sql.set_row_count(row_count);
+            sql.set_duration(duration_ms.map(Duration::from_millis));
+            if let Some(result) = result {
+                let format: TableFormat = format
+                    .parse()
+                    .map_err(|()| CliError::InvalidTableFormat(format.clone()))?;
+                let buf = fs::read(result).map_err(|_| CliError::FailedToReadFile)?;
+                let table =
+                    Table::parse(&String::from_utf8_lossy(&buf), format).map_err(|e| match e {
+                        evidenceangel::Error::InvalidEvidenceData(message) => {
+                            CliError::InvalidTable(message)
+                        }
+                        e => CliError::InvalidTable(e.to_string()),
+                    })?;
+                sql.set_result(table);
+            }
+            let mut evidence = sql.to_evidence();
+            evidence.set_caption(caption.clone());
+            Ok(evidence)
+        }
     }
 }
 
@@ -499,25 +1612,37 @@ pub fn process(path: PathBuf, command: &TestCasesSubcommand) -> CliData {
     match command {
         TestCasesSubcommand::Create {
             title,
+            template,
             executed_at,
             status,
         } => match EvidencePackage::open(path) {
             Ok(mut package) => {
-                let case = {
-                    if let Some(executed_at) = executed_at {
-                        match parse_datetime::parse_datetime(executed_at) {
-                            Ok(dt) => Ok(package.create_test_case_at(title.clone(), dt).unwrap()),
-                            Err(_) => Err(CliError::InvalidExecutionDateTime),
-                        }
-                    } else {
-                        Ok(package.create_test_case(title.clone()).unwrap())
+                let at = if let Some(executed_at) = executed_at {
+                    match parse_datetime::parse_datetime(executed_at) {
+                        Ok(dt) => dt,
+                        Err(_) => return CliError::InvalidExecutionDateTime.into(),
                     }
+                } else {
+                    chrono::Local::now().fixed_offset()
                 };
-                if let Err(e) = &case {
-                    return e.clone().into();
+                let case = if let Some(template) = template {
+                    let template = match crate::templates::find_template(&package, template) {
+                        Ok(template) => template,
+                        Err(e) => return e.into(),
+                    };
+                    match package.create_test_case_from_template_at(&template, at) {
+                        Ok(case) => case,
+                        Err(e) => return CliError::InvalidTemplate(Rc::new(e)).into(),
+                    }
+                } else {
+                    // SAFETY: clap requires a title if there is no template
+                    package
+                        .create_test_case_at(title.clone().unwrap(), at)
+                        .unwrap()
+                };
+                if let Some(title) = title {
+                    case.metadata_mut().set_title(title.clone());
                 }
-                // SAFETY: checked line above
-                let case = case.unwrap();
                 if let Some(status) = status {
                     let status = if status.eq_ignore_ascii_case("pass") {
                         Some(TestCasePassStatus::Pass)
@@ -564,24 +1689,18 @@ pub fn process(path: PathBuf, command: &TestCasesSubcommand) -> CliData {
                             })
                             .collect::<Vec<_>>()
                     }),
+                    requirements: test_case
+                        .metadata()
+                        .requirements()
+                        .iter()
+                        .flatten()
+                        .map(|r| r.id().clone())
+                        .collect(),
+                    steps: test_case.steps().iter().map(CliTestStep::from).collect(),
                     evidence: test_case
                         .evidence()
                         .iter()
-                        .map(|ev| match ev.kind() {
-                            EvidenceKind::Text => CliEvidence::Text {
-                                data: String::from_utf8(ev.value().get_data(&mut package).unwrap())
-                                    .unwrap(),
-                            },
-                            EvidenceKind::RichText => CliEvidence::RichText {
-                                data: String::from_utf8(ev.value().get_data(&mut package).unwrap())
-                                    .unwrap(),
-                            },
-                            EvidenceKind::Image => CliEvidence::Image,
-                            EvidenceKind::Http => CliEvidence::Http,
-                            EvidenceKind::File => CliEvidence::File {
-                                original_filename: ev.original_filename().clone(),
-                            },
-                        })
+                        .map(|ev| CliEvidence::from_evidence(ev, &mut package))
                         .collect(),
                 })
             }
@@ -624,24 +1743,18 @@ pub fn process(path: PathBuf, command: &TestCasesSubcommand) -> CliData {
                             })
                             .collect::<Vec<_>>()
                     }),
+                    requirements: test_case
+                        .metadata()
+                        .requirements()
+                        .iter()
+                        .flatten()
+                        .map(|r| r.id().clone())
+                        .collect(),
+                    steps: test_case.steps().iter().map(CliTestStep::from).collect(),
                     evidence: test_case
                         .evidence()
                         .iter()
-                        .map(|ev| match ev.kind() {
-                            EvidenceKind::Text => CliEvidence::Text {
-                                data: String::from_utf8(ev.value().get_data(&mut package).unwrap())
-                                    .unwrap(),
-                            },
-                            EvidenceKind::RichText => CliEvidence::RichText {
-                                data: String::from_utf8(ev.value().get_data(&mut package).unwrap())
-                                    .unwrap(),
-                            },
-                            EvidenceKind::Image => CliEvidence::Image,
-                            EvidenceKind::Http => CliEvidence::Http,
-                            EvidenceKind::File => CliEvidence::File {
-                                original_filename: ev.original_filename().clone(),
-                            },
-                        })
+                        .map(|ev| CliEvidence::from_evidence(ev, &mut package))
                         .collect(),
                 })
             }
@@ -718,24 +1831,18 @@ pub fn process(path: PathBuf, command: &TestCasesSubcommand) -> CliData {
                             })
                             .collect::<Vec<_>>()
                     }),
+                    requirements: test_case
+                        .metadata()
+                        .requirements()
+                        .iter()
+                        .flatten()
+                        .map(|r| r.id().clone())
+                        .collect(),
+                    steps: test_case.steps().iter().map(CliTestStep::from).collect(),
                     evidence: test_case
                         .evidence()
                         .iter()
-                        .map(|ev| match ev.kind() {
-                            EvidenceKind::Text => CliEvidence::Text {
-                                data: String::from_utf8(ev.value().get_data(&mut package).unwrap())
-                                    .unwrap(),
-                            },
-                            EvidenceKind::RichText => CliEvidence::RichText {
-                                data: String::from_utf8(ev.value().get_data(&mut package).unwrap())
-                                    .unwrap(),
-                            },
-                            EvidenceKind::Image => CliEvidence::Image,
-                            EvidenceKind::Http => CliEvidence::Http,
-                            EvidenceKind::File => CliEvidence::File {
-                                original_filename: ev.original_filename().clone(),
-                            },
-                        })
+                        .map(|ev| CliEvidence::from_evidence(ev, &mut package))
                         .collect(),
                 })
             }
@@ -817,28 +1924,18 @@ pub fn process(path: PathBuf, command: &TestCasesSubcommand) -> CliData {
                                 })
                                 .collect::<Vec<_>>()
                         }),
+                        requirements: test_case
+                            .metadata()
+                            .requirements()
+                            .iter()
+                            .flatten()
+                            .map(|r| r.id().clone())
+                            .collect(),
+                        steps: test_case.steps().iter().map(CliTestStep::from).collect(),
                         evidence: test_case
                             .evidence()
                             .iter()
-                            .map(|ev| match ev.kind() {
-                                EvidenceKind::Text => CliEvidence::Text {
-                                    data: String::from_utf8(
-                                        ev.value().get_data(&mut package).unwrap(),
-                                    )
-                                    .unwrap(),
-                                },
-                                EvidenceKind::RichText => CliEvidence::RichText {
-                                    data: String::from_utf8(
-                                        ev.value().get_data(&mut package).unwrap(),
-                                    )
-                                    .unwrap(),
-                                },
-                                EvidenceKind::Image => CliEvidence::Image,
-                                EvidenceKind::Http => CliEvidence::Http,
-                                EvidenceKind::File => CliEvidence::File {
-                                    original_filename: ev.original_filename().clone(),
-                                },
-                            })
+                            .map(|ev| CliEvidence::from_evidence(ev, &mut package))
                             .collect(),
                     })
                 }
@@ -908,10 +2005,34 @@ pub fn process(path: PathBuf, command: &TestCasesSubcommand) -> CliData {
                 }
                 let case_id = case_id.unwrap();
 
-                match evidence_from_evidence_value(evidence_value, &mut package) {
-                    Ok(ev) => {
+                let evidence = match evidence_value {
+                    EvidenceValue::Http { har: Some(_), .. } => evidence_from_har(evidence_value),
+                    _ => evidence_from_evidence_value(evidence_value, &mut package)
+                        .map(|ev| vec![ev]),
+                };
+                let redaction = match crate::redaction::effective_settings(&package) {
+                    Ok(settings) if *settings.redact_on_add() => {
+                        match crate::redaction::redactor(&settings) {
+                            Ok(redactor) => Some(redactor),
+                            Err(e) => return e.into(),
+                        }
+                    }
+                    Ok(_) => None,
+                    Err(e) => return e.into(),
+                };
+                match evidence {
+                    Ok(mut evidence) => {
+                        if let Some(redactor) = redaction {
+                            let redacted: usize = evidence
+                                .iter_mut()
+                                .map(|ev| redactor.redact_evidence(ev).len())
+                                .sum();
+                            if redacted > 0 {
+                                tracing::info!("Redacted {redacted} secrets from new evidence");
+                            }
+                        }
                         let test_case = package.test_case_mut(case_id).unwrap().unwrap();
-                        test_case.evidence_mut().push(ev);
+                        test_case.evidence_mut().extend(evidence);
                     }
                     Err(e) => return e.into(),
                 }
@@ -950,24 +2071,18 @@ pub fn process(path: PathBuf, command: &TestCasesSubcommand) -> CliData {
                             })
                             .collect::<Vec<_>>()
                     }),
+                    requirements: test_case
+                        .metadata()
+                        .requirements()
+                        .iter()
+                        .flatten()
+                        .map(|r| r.id().clone())
+                        .collect(),
+                    steps: test_case.steps().iter().map(CliTestStep::from).collect(),
                     evidence: test_case
                         .evidence()
                         .iter()
-                        .map(|ev| match ev.kind() {
-                            EvidenceKind::Text => CliEvidence::Text {
-                                data: String::from_utf8(ev.value().get_data(&mut package).unwrap())
-                                    .unwrap(),
-                            },
-                            EvidenceKind::RichText => CliEvidence::RichText {
-                                data: String::from_utf8(ev.value().get_data(&mut package).unwrap())
-                                    .unwrap(),
-                            },
-                            EvidenceKind::Image => CliEvidence::Image,
-                            EvidenceKind::Http => CliEvidence::Http,
-                            EvidenceKind::File => CliEvidence::File {
-                                original_filename: ev.original_filename().clone(),
-                            },
-                        })
+                        .map(|ev| CliEvidence::from_evidence(ev, &mut package))
                         .collect(),
                 })
             }
@@ -1062,24 +2177,18 @@ pub fn process(path: PathBuf, command: &TestCasesSubcommand) -> CliData {
                             })
                             .collect::<Vec<_>>()
                     }),
+                    requirements: test_case
+                        .metadata()
+                        .requirements()
+                        .iter()
+                        .flatten()
+                        .map(|r| r.id().clone())
+                        .collect(),
+                    steps: test_case.steps().iter().map(CliTestStep::from).collect(),
                     evidence: test_case
                         .evidence()
                         .iter()
-                        .map(|ev| match ev.kind() {
-                            EvidenceKind::Text => CliEvidence::Text {
-                                data: String::from_utf8(ev.value().get_data(&mut package).unwrap())
-                                    .unwrap(),
-                            },
-                            EvidenceKind::RichText => CliEvidence::RichText {
-                                data: String::from_utf8(ev.value().get_data(&mut package).unwrap())
-                                    .unwrap(),
-                            },
-                            EvidenceKind::Image => CliEvidence::Image,
-                            EvidenceKind::Http => CliEvidence::Http,
-                            EvidenceKind::File => CliEvidence::File {
-                                original_filename: ev.original_filename().clone(),
-                            },
-                        })
+                        .map(|ev| CliEvidence::from_evidence(ev, &mut package))
                         .collect(),
                 })
             }
@@ -1100,7 +2209,7 @@ pub fn process(path: PathBuf, command: &TestCasesSubcommand) -> CliData {
                             return CliError::CannotMatchEvidence(*evidence_id).into();
                         }
                         let evidence_id = *evidence_id - 1;
-                        test_case.evidence_mut().remove(evidence_id);
+                        test_case.remove_evidence(evidence_id);
                     }
                     if let Err(e) = package.save() {
                         return CliError::FailedToSavePackage(Rc::new(e)).into();
@@ -1136,33 +2245,136 @@ pub fn process(path: PathBuf, command: &TestCasesSubcommand) -> CliData {
                                 })
                                 .collect::<Vec<_>>()
                         }),
+                        requirements: test_case
+                            .metadata()
+                            .requirements()
+                            .iter()
+                            .flatten()
+                            .map(|r| r.id().clone())
+                            .collect(),
+                        steps: test_case.steps().iter().map(CliTestStep::from).collect(),
                         evidence: test_case
                             .evidence()
                             .iter()
-                            .map(|ev| match ev.kind() {
-                                EvidenceKind::Text => CliEvidence::Text {
-                                    data: String::from_utf8(
-                                        ev.value().get_data(&mut package).unwrap(),
-                                    )
-                                    .unwrap(),
-                                },
-                                EvidenceKind::RichText => CliEvidence::RichText {
-                                    data: String::from_utf8(
-                                        ev.value().get_data(&mut package).unwrap(),
-                                    )
-                                    .unwrap(),
-                                },
-                                EvidenceKind::Image => CliEvidence::Image,
-                                EvidenceKind::Http => CliEvidence::Http,
-                                EvidenceKind::File => CliEvidence::File {
-                                    original_filename: ev.original_filename().clone(),
-                                },
-                            })
+                            .map(|ev| CliEvidence::from_evidence(ev, &mut package))
                             .collect(),
                     })
                 }
                 Err(e) => CliError::FailedToReadPackage(Rc::new(e)).into(),
             }
         }
+        TestCasesSubcommand::AddStep {
+            case,
+            action,
+            expected,
+            position,
+            evidence,
+        } => modify_test_case(path, case, |test_case| {
+            let mut step = TestStep::new(action.clone());
+            step.set_expected(expected.clone());
+            for index in evidence {
+                step.attach_evidence(evidence_index(test_case, *index)?);
+            }
+            let position = position.map_or(test_case.steps().len(), |p| {
+                p.saturating_sub(1).min(test_case.steps().len())
+            });
+            test_case.steps_mut().insert(position, step);
+            Ok(())
+        }),
+        TestCasesSubcommand::UpdateStep {
+            case,
+            step,
+            action,
+            expected,
+            actual,
+            status,
+            evidence,
+        } => modify_test_case(path, case, |test_case| {
+            let evidence = evidence
+                .as_ref()
+                .map(|evidence| {
+                    evidence
+                        .iter()
+                        .map(|index| evidence_index(test_case, *index))
+                        .collect::<Result<Vec<_>, _>>()
+                })
+                .transpose()?;
+            let status = match status.as_deref() {
+                None => None,
+                Some(s) if s.eq_ignore_ascii_case("none") => Some(None),
+                Some(s) => Some(Some(
+                    s.parse::<StepStatus>()
+                        .map_err(|_| CliError::InvalidStepStatus(s.to_string()))?,
+                )),
+            };
+            let the_step = step
+                .checked_sub(1)
+                .and_then(|idx| test_case.steps_mut().get_mut(idx))
+                .ok_or(CliError::CannotMatchStep(*step))?;
+            if let Some(action) = action {
+                the_step.set_action(action.clone());
+            }
+            if let Some(expected) = expected {
+                the_step.set_expected(Some(expected.clone()).filter(|e| !e.is_empty()));
+            }
+            if let Some(actual) = actual {
+                the_step.set_actual(Some(actual.clone()).filter(|a| !a.is_empty()));
+            }
+            if let Some(evidence) = evidence {
+                the_step.evidence_mut().clear();
+                for index in evidence {
+                    the_step.attach_evidence(index);
+                }
+            }
+            if let Some(status) = status {
+                the_step.set_status(status);
+                test_case.update_status_from_steps();
+            }
+            Ok(())
+        }),
+        TestCasesSubcommand::DeleteStep { case, step } => {
+            modify_test_case(path, case, |test_case| {
+                if *step < 1 || *step > test_case.steps().len() {
+                    return Err(CliError::CannotMatchStep(*step));
+                }
+                test_case.steps_mut().remove(*step - 1);
+                Ok(())
+            })
+        }
+    }
+}
+
+/// Convert a one-based index of evidence in a test case, as given by the user, to a zero-based
+/// index, checking the evidence exists.
+fn evidence_index(test_case: &TestCase, index: usize) -> Result<usize, CliError> {
+    if index < 1 || index > test_case.evidence().len() {
+        Err(CliError::CannotMatchEvidence(index))
+    } else {
+        Ok(index - 1)
+    }
+}
+
+/// Open a package, change a test case within it and save it, then return the test case for
+/// display.
+pub fn modify_test_case<F>(path: PathBuf, case: &str, modify: F) -> CliData
+where
+    F: FnOnce(&mut TestCase) -> Result<(), CliError>,
+{
+    match EvidencePackage::open(path) {
+        Ok(mut package) => {
+            let Some(case_id) = match_test_case(&mut package, case) else {
+                return CliError::CannotMatchTestCase(case.to_string()).into();
+            };
+            // SAFETY: test case matched above
+            let test_case = package.test_case_mut(case_id).unwrap().unwrap();
+            if let Err(e) = modify(test_case) {
+                return e.into();
+            }
+            if let Err(e) = package.save() {
+                return CliError::FailedToSavePackage(Rc::new(e)).into();
+            }
+            CliData::TestCase(CliTestCase::new(&mut package, case_id))
+        }
+        Err(e) => CliError::FailedToReadPackage(Rc::new(e)).into(),
     }
 }
@@ -2,10 +2,9 @@ use std::{path::PathBuf, sync::Arc};
 
 use adw::prelude::*;
 use evidenceangel::{
-    Author, Evidence, EvidenceData, EvidenceKind, EvidencePackage, MediaFile, TestCasePassStatus,
-    exporters::{
-        Exporter, excel::ExcelExporter, html::HtmlExporter, zip_of_files::ZipOfFilesExporter,
-    },
+    Author, Evidence, EvidenceData, EvidenceKind, EvidencePackage, MediaFile, RedactionReport,
+    RedactionSettings, Requirement, SecretRedactor, TestCasePassStatus, TestCaseTemplate, TestStep,
+    exporters::{ExportOptions, Exporter, ExporterRegistry},
 };
 #[allow(unused)]
 use gtk::prelude::*;
@@ -37,11 +36,17 @@ use crate::{
         error::*,
         export::*,
         new_author::*,
+        redaction::{RedactionDialogModel, RedactionInput, RedactionOutput},
+        templates::{
+            SaveTemplateDialogModel, SaveTemplateInput, SaveTemplateOutput, TemplatesDialogInit,
+            TemplatesDialogModel, TemplatesInput, TemplatesOutput,
+        },
     },
     evidence_factory::{EvidenceFactoryInit, EvidenceFactoryModel, EvidenceFactoryOutput},
     filter, lang, lang_args,
     nav_factory::{NavFactoryInit, NavFactoryInput, NavFactoryModel, NavFactoryOutput},
-    util::{BoxedEvidenceJson, BoxedTestCaseById},
+    step_factory::{StepFactoryModel, StepFactoryOutput},
+    util::{self, BoxedEvidenceJson, BoxedTestCaseById},
 };
 
 relm4::new_action_group!(MenuActionGroup, "menu");
@@ -53,6 +58,8 @@ relm4::new_stateless_action!(AboutAction, MenuActionGroup, "about");
 relm4::new_stateless_action!(PasteEvidenceAction, MenuActionGroup, "paste-evidence");
 relm4::new_stateless_action!(ExportPackageAction, MenuActionGroup, "export-package");
 relm4::new_stateless_action!(ExportTestCaseAction, MenuActionGroup, "export-test-case");
+relm4::new_stateless_action!(RedactionAction, MenuActionGroup, "redaction");
+relm4::new_stateless_action!(NewFromTemplateAction, MenuActionGroup, "new-from-template");
 
 relm4::new_action_group!(AddEvidenceActionGroup, "add-evidence");
 relm4::new_stateless_action!(AddEvidenceTextAction, AddEvidenceActionGroup, "text");
@@ -62,27 +69,50 @@ relm4::new_stateless_action!(
     "rich-text"
 );
 relm4::new_stateless_action!(AddEvidenceHttpAction, AddEvidenceActionGroup, "http");
+relm4::new_stateless_action!(AddEvidenceHarAction, AddEvidenceActionGroup, "har");
 relm4::new_stateless_action!(AddEvidenceImageAction, AddEvidenceActionGroup, "image");
 relm4::new_stateless_action!(AddEvidenceFileAction, AddEvidenceActionGroup, "file");
+relm4::new_stateless_action!(AddEvidenceLogAction, AddEvidenceActionGroup, "log");
+relm4::new_stateless_action!(AddEvidenceTableAction, AddEvidenceActionGroup, "table");
+relm4::new_stateless_action!(AddEvidenceDiffAction, AddEvidenceActionGroup, "diff");
+relm4::new_stateless_action!(AddEvidenceCommandAction, AddEvidenceActionGroup, "command");
+relm4::new_stateless_action!(AddEvidenceSqlAction, AddEvidenceActionGroup, "sql");
+relm4::new_stateless_action!(AddEvidenceLinkAction, AddEvidenceActionGroup, "link");
+relm4::new_stateless_action!(AddEvidenceVideoAction, AddEvidenceActionGroup, "video");
 
 pub struct AppModel {
     open_package: Option<Arc<RwLock<EvidencePackage>>>,
     open_path: Option<PathBuf>,
     open_case: OpenCase,
     needs_saving: bool,
+    /// Where evidence being moved has been inserted, until the original is deleted
+    moved_evidence_to: Option<usize>,
 
     action_save: RelmAction<SaveAction>,
     action_close: RelmAction<CloseAction>,
     action_export_package: RelmAction<ExportPackageAction>,
     action_export_test_case: RelmAction<ExportTestCaseAction>,
+    action_redaction: RelmAction<RedactionAction>,
+    action_new_from_template: RelmAction<NewFromTemplateAction>,
     action_paste_evidence: RelmAction<PasteEvidenceAction>,
 
     latest_new_author_dlg: Option<Controller<NewAuthorDialogModel>>,
     latest_new_custom_metadata_dlg: Option<Controller<CustomMetadataDialogModel>>,
     latest_add_evidence_image_dlg: Option<Controller<AddImageEvidenceDialogModel>>,
     latest_add_evidence_file_dlg: Option<Controller<AddFileEvidenceDialogModel>>,
+    latest_add_evidence_log_dlg: Option<Controller<AddLogEvidenceDialogModel>>,
+    latest_add_evidence_table_dlg: Option<Controller<AddTableEvidenceDialogModel>>,
+    latest_add_evidence_diff_dlg: Option<Controller<AddDiffEvidenceDialogModel>>,
+    latest_add_evidence_command_dlg: Option<Controller<AddCommandEvidenceDialogModel>>,
+    latest_add_evidence_sql_dlg: Option<Controller<AddSqlEvidenceDialogModel>>,
+    latest_add_evidence_link_dlg: Option<Controller<AddLinkEvidenceDialogModel>>,
+    latest_add_evidence_har_dlg: Option<Controller<AddHarEvidenceDialogModel>>,
+    latest_add_evidence_video_dlg: Option<Controller<AddVideoEvidenceDialogModel>>,
     latest_error_dlg: Option<Controller<ErrorDialogModel>>,
     latest_export_dlg: Option<Controller<ExportDialogModel>>,
+    latest_redaction_dlg: Option<Controller<RedactionDialogModel>>,
+    latest_templates_dlg: Option<Controller<TemplatesDialogModel>>,
+    latest_save_template_dlg: Option<Controller<SaveTemplateDialogModel>>,
     latest_delete_toasts: Vec<adw::Toast>,
 
     test_case_nav_factory: FactoryVecDeque<NavFactoryModel>,
@@ -90,6 +120,7 @@ pub struct AppModel {
     test_evidence_factory: FactoryVecDeque<EvidenceFactoryModel>,
     custom_metadata_factory: FactoryVecDeque<CustomMetadataFactoryModel>,
     custom_metadata_editor_factory: FactoryVecDeque<CustomMetadataEditorFactoryModel>,
+    test_step_factory: FactoryVecDeque<StepFactoryModel>,
 }
 
 impl AppModel {
@@ -110,6 +141,8 @@ impl AppModel {
         self.action_save.set_enabled(true);
         self.action_close.set_enabled(true);
         self.action_export_package.set_enabled(true);
+        self.action_redaction.set_enabled(true);
+        self.action_new_from_template.set_enabled(true);
         self.update_nav_menu()?;
         Ok(())
     }
@@ -123,6 +156,8 @@ impl AppModel {
         self.action_save.set_enabled(true);
         self.action_close.set_enabled(true);
         self.action_export_package.set_enabled(true);
+        self.action_redaction.set_enabled(true);
+        self.action_new_from_template.set_enabled(true);
         self.update_nav_menu()?;
         Ok(())
     }
@@ -134,6 +169,8 @@ impl AppModel {
         self.action_save.set_enabled(false);
         self.action_close.set_enabled(false);
         self.action_export_package.set_enabled(false);
+        self.action_redaction.set_enabled(false);
+        self.action_new_from_template.set_enabled(false);
         tracing::debug!("Package closed.");
     }
 
@@ -166,6 +203,20 @@ impl AppModel {
         Ok(())
     }
 
+    /// Update the step editor with the steps of the open test case.
+    fn update_steps(&mut self) {
+        let mut steps = self.test_step_factory.guard();
+        steps.clear();
+        if let OpenCase::Case { id, .. } = &self.open_case
+            && let Some(pkg) = self.open_package.as_ref()
+            && let Some(tc) = pkg.read().test_case(*id).ok().flatten()
+        {
+            for step in tc.steps() {
+                steps.push_back(step.clone());
+            }
+        }
+    }
+
     fn create_needs_saving_dialog(transient_for: &impl IsA<gtk::Window>) -> adw::MessageDialog {
         let dialog = adw::MessageDialog::builder()
             .transient_for(transient_for)
@@ -221,6 +272,12 @@ pub enum AppInput {
     },
     DeleteCase(Uuid),
     CreateCaseAndSelect,
+    CreateCaseFromTemplate,
+    _CreateCaseFromTemplate(TestCaseTemplate),
+    _DeleteTemplate {
+        name: String,
+        user: bool,
+    },
     SetMetadataTitle(String),
     SetMetadataDescription(String),
     CreateAuthor,
@@ -229,6 +286,7 @@ pub enum AppInput {
 
     SetTestCaseTitle(String),
     SetTestCaseStatus(u32),
+    SetTestCaseRequirements(String),
     CreateCustomMetadataField,
     _CreateCustomMetadataField {
         key: Option<String>,
@@ -257,6 +315,13 @@ pub enum AppInput {
     MoveSelectedCaseUp,
     MoveSelectedCaseDown,
     DuplicateCase,
+    SaveCaseAsTemplate,
+    _SaveCaseAsTemplate {
+        name: String,
+        title: String,
+        description: Option<String>,
+        user: bool,
+    },
     DeleteSelectedCase,
     _DeleteSelectedCase,
     AddTextEvidence,
@@ -265,12 +330,26 @@ pub enum AppInput {
     AddImageEvidence,
     #[allow(dead_code)]
     AddFileEvidence,
+    AddLogEvidence,
+    AddTableEvidence,
+    AddDiffEvidence,
+    AddCommandEvidence,
+    AddSqlEvidence,
+    AddLinkEvidence,
+    AddHarEvidence,
+    AddVideoEvidence,
     _AddEvidence(Evidence, Option<usize>),
     /// `InsertEvidenceAt` MUST NOT update the interface.
     InsertEvidenceAt(usize, Evidence),
     ReplaceEvidenceAt(DynamicIndex, Evidence),
     DeleteEvidenceAt(DynamicIndex, bool),
     _AddMedia(MediaFile),
+    AddStep,
+    UpdateStep {
+        index: DynamicIndex,
+        step: TestStep,
+    },
+    DeleteStep(DynamicIndex),
     /// Show an error dialog.
     ShowError {
         title: String,
@@ -278,9 +357,12 @@ pub enum AppInput {
     },
 
     ExportPackage,
-    _ExportPackage(String, PathBuf),
+    _ExportPackage(String, PathBuf, bool, ExportOptions),
     ExportTestCase,
-    _ExportTestCase(String, PathBuf),
+    _ExportTestCase(String, PathBuf, bool, ExportOptions),
+    OpenRedactionSettings,
+    _SetRedactionSettings(RedactionSettings),
+    _RedactPackage,
     PasteEvidence,
     ShowToast(String),
     ReinstatePaste,
@@ -594,6 +676,12 @@ impl Component for AppModel {
 
                                                                 connect_clicked => AppInput::DuplicateCase,
                                                             },
+                                                            gtk::Button {
+                                                                set_label: &lang::lookup("test-case-save-as-template"),
+                                                                add_css_class: "flat",
+
+                                                                connect_clicked => AppInput::SaveCaseAsTemplate,
+                                                            },
                                                             gtk::Button {
                                                                 set_label: &lang::lookup("nav-delete-case"),
                                                                 add_css_class: "flat",
@@ -667,6 +755,16 @@ impl Component for AppModel {
                                                         sender.input(AppInput::SetTestCaseStatus(entry.selected()));
                                                     } @case_status_changed_handler
                                                 },
+
+                                                #[name = "test_requirements"]
+                                                adw::EntryRow {
+                                                    set_title: &lang::lookup("test-requirements"),
+                                                    set_tooltip: &lang::lookup("test-requirements-tooltip"),
+
+                                                    connect_changed[sender] => move |entry| {
+                                                        sender.input(AppInput::SetTestCaseRequirements(entry.text().to_string()));
+                                                    } @case_requirements_changed_handler
+                                                },
                                             },
 
                                             // Custom metadata
@@ -678,6 +776,24 @@ impl Component for AppModel {
                                                 set_visible: !model.custom_metadata_factory.is_empty(),
                                             },
 
+                                            // Steps
+                                            #[local_ref]
+                                            steps_list -> adw::PreferencesGroup {
+                                                set_title: &lang::lookup("steps-title"),
+                                                set_description: Some(&lang::lookup("steps-description")),
+                                                set_margin_top: 16,
+                                                #[wrap(Some)]
+                                                set_header_suffix = &adw::Bin {
+                                                    gtk::Button {
+                                                        set_icon_name: relm4_icons::icon_names::PLUS,
+                                                        set_tooltip: &lang::lookup("step-add"),
+                                                        add_css_class: "flat",
+
+                                                        connect_clicked => AppInput::AddStep,
+                                                    }
+                                                },
+                                            },
+
                                             // Test Case Screen
                                             #[local_ref]
                                             evidence_list -> gtk::Box {
@@ -703,7 +819,8 @@ impl Component for AppModel {
                                                         if let Ok(data) = val.get::<BoxedEvidenceJson>() {
                                                             let ev = data.inner();
                                                             tracing::debug!("Dropped data: {ev:?}");
-                                                            sender.input(AppInput::_AddEvidence(ev, None));
+                                                            // This is only triggered by a move, so is always followed by a delete
+                                                            sender.input(AppInput::InsertEvidenceAt(usize::MAX, ev));
                                                             return true;
                                                         }
                                                         false
@@ -747,12 +864,16 @@ impl Component for AppModel {
             &lang::lookup("header-save") => SaveAction,
             &lang::lookup("header-close") => CloseAction,
             section! {
+                &lang::lookup("header-new-from-template") => NewFromTemplateAction,
                 &lang::lookup("header-paste-evidence") => PasteEvidenceAction,
             },
             section! {
                 &lang::lookup("header-export-package") => ExportPackageAction,
                 &lang::lookup("header-export-test-case") => ExportTestCaseAction,
             },
+            section! {
+                &lang::lookup("header-redaction") => RedactionAction,
+            },
             section! {
                 &lang::lookup("header-about") => AboutAction,
             },
@@ -761,8 +882,16 @@ impl Component for AppModel {
             &lang::lookup("evidence-text") => AddEvidenceTextAction,
             &lang::lookup("evidence-richtext") => AddEvidenceRichTextAction,
             &lang::lookup("evidence-http") => AddEvidenceHttpAction,
+            &lang::lookup("evidence-har") => AddEvidenceHarAction,
             &lang::lookup("evidence-image") => AddEvidenceImageAction,
             &lang::lookup("evidence-file") => AddEvidenceFileAction,
+            &lang::lookup("evidence-log") => AddEvidenceLogAction,
+            &lang::lookup("evidence-table") => AddEvidenceTableAction,
+            &lang::lookup("evidence-diff") => AddEvidenceDiffAction,
+            &lang::lookup("evidence-command") => AddEvidenceCommandAction,
+            &lang::lookup("evidence-sql") => AddEvidenceSqlAction,
+            &lang::lookup("evidence-link") => AddEvidenceLinkAction,
+            &lang::lookup("evidence-video") => AddEvidenceVideoAction,
         }
     }
 
@@ -824,6 +953,21 @@ impl Component for AppModel {
         relm4::main_application()
             .set_accelerators_for_action::<ExportTestCaseAction>(&["<primary><shift>E"]);
 
+        let sender_c = sender.clone();
+        let action_redaction: RelmAction<RedactionAction> = RelmAction::new_stateless(move |_| {
+            sender_c.input(AppInput::OpenRedactionSettings);
+        });
+        action_redaction.set_enabled(false);
+
+        let sender_c = sender.clone();
+        let action_new_from_template: RelmAction<NewFromTemplateAction> =
+            RelmAction::new_stateless(move |_| {
+                sender_c.input(AppInput::CreateCaseFromTemplate);
+            });
+        action_new_from_template.set_enabled(false);
+        relm4::main_application()
+            .set_accelerators_for_action::<NewFromTemplateAction>(&["<primary><shift>N"]);
+
         let sender_c = sender.clone();
         let action_about: RelmAction<AboutAction> = RelmAction::new_stateless(move |_| {
             sender_c.input(AppInput::OpenAboutDialog);
@@ -839,6 +983,8 @@ impl Component for AppModel {
         group.add_action(action_paste_evidence.clone());
         group.add_action(action_export_package.clone());
         group.add_action(action_export_test_case.clone());
+        group.add_action(action_redaction.clone());
+        group.add_action(action_new_from_template.clone());
         group.register_for_widget(&root);
 
         let sender_c = sender.clone();
@@ -871,12 +1017,68 @@ impl Component for AppModel {
                 sender_c.input(AppInput::AddFileEvidence);
             });
 
+        let sender_c = sender.clone();
+        let action_add_evidence_log: RelmAction<AddEvidenceLogAction> =
+            RelmAction::new_stateless(move |_| {
+                sender_c.input(AppInput::AddLogEvidence);
+            });
+
+        let sender_c = sender.clone();
+        let action_add_evidence_table: RelmAction<AddEvidenceTableAction> =
+            RelmAction::new_stateless(move |_| {
+                sender_c.input(AppInput::AddTableEvidence);
+            });
+
+        let sender_c = sender.clone();
+        let action_add_evidence_diff: RelmAction<AddEvidenceDiffAction> =
+            RelmAction::new_stateless(move |_| {
+                sender_c.input(AppInput::AddDiffEvidence);
+            });
+
+        let sender_c = sender.clone();
+        let action_add_evidence_command: RelmAction<AddEvidenceCommandAction> =
+            RelmAction::new_stateless(move |_| {
+                sender_c.input(AppInput::AddCommandEvidence);
+            });
+
+        let sender_c = sender.clone();
+        let action_add_evidence_sql: RelmAction<AddEvidenceSqlAction> =
+            RelmAction::new_stateless(move |_| {
+                sender_c.input(AppInput::AddSqlEvidence);
+            });
+
+        let sender_c = sender.clone();
+        let action_add_evidence_link: RelmAction<AddEvidenceLinkAction> =
+            RelmAction::new_stateless(move |_| {
+                sender_c.input(AppInput::AddLinkEvidence);
+            });
+
+        let sender_c = sender.clone();
+        let action_add_evidence_har: RelmAction<AddEvidenceHarAction> =
+            RelmAction::new_stateless(move |_| {
+                sender_c.input(AppInput::AddHarEvidence);
+            });
+
+        let sender_c = sender.clone();
+        let action_add_evidence_video: RelmAction<AddEvidenceVideoAction> =
+            RelmAction::new_stateless(move |_| {
+                sender_c.input(AppInput::AddVideoEvidence);
+            });
+
         let mut group = RelmActionGroup::<AddEvidenceActionGroup>::new();
         group.add_action(action_add_evidence_text);
         group.add_action(action_add_evidence_rich_text);
         group.add_action(action_add_evidence_http);
         group.add_action(action_add_evidence_image);
         group.add_action(action_add_evidence_file);
+        group.add_action(action_add_evidence_log);
+        group.add_action(action_add_evidence_table);
+        group.add_action(action_add_evidence_diff);
+        group.add_action(action_add_evidence_command);
+        group.add_action(action_add_evidence_sql);
+        group.add_action(action_add_evidence_link);
+        group.add_action(action_add_evidence_har);
+        group.add_action(action_add_evidence_video);
         group.register_for_widget(&root);
 
         let model = AppModel {
@@ -884,10 +1086,13 @@ impl Component for AppModel {
             open_path: None,
             open_case: OpenCase::Nothing,
             needs_saving: false,
+            moved_evidence_to: None,
 
             action_save,
             action_export_package,
             action_export_test_case,
+            action_redaction,
+            action_new_from_template,
             action_close,
             action_paste_evidence,
 
@@ -896,7 +1101,18 @@ impl Component for AppModel {
             latest_new_custom_metadata_dlg: None,
             latest_add_evidence_image_dlg: None,
             latest_add_evidence_file_dlg: None,
+            latest_add_evidence_log_dlg: None,
+            latest_add_evidence_table_dlg: None,
+            latest_add_evidence_diff_dlg: None,
+            latest_add_evidence_command_dlg: None,
+            latest_add_evidence_sql_dlg: None,
+            latest_add_evidence_link_dlg: None,
+            latest_add_evidence_har_dlg: None,
+            latest_add_evidence_video_dlg: None,
             latest_export_dlg: None,
+            latest_redaction_dlg: None,
+            latest_templates_dlg: None,
+            latest_save_template_dlg: None,
             latest_delete_toasts: vec![],
 
             test_case_nav_factory: FactoryVecDeque::builder().launch_default().forward(
@@ -972,12 +1188,22 @@ impl Component for AppModel {
                     }
                 },
             ),
+            test_step_factory: FactoryVecDeque::builder().launch_default().forward(
+                sender.input_sender(),
+                |output| match output {
+                    StepFactoryOutput::UpdateStep { index, step } => {
+                        AppInput::UpdateStep { index, step }
+                    }
+                    StepFactoryOutput::DeleteStep(index) => AppInput::DeleteStep(index),
+                },
+            ),
         };
 
         let test_case_list = model.test_case_nav_factory.widget();
         let authors_list = model.authors_factory.widget();
         let custom_metadata_list = model.custom_metadata_factory.widget();
         let custom_metadata_editor_list = model.custom_metadata_editor_factory.widget();
+        let steps_list = model.test_step_factory.widget();
         let evidence_list = model.test_evidence_factory.widget();
         let widgets = view_output!();
         if cfg!(debug_assertions) {
@@ -1299,6 +1525,21 @@ impl Component for AppModel {
                                 widgets
                                     .test_status
                                     .unblock_signal(&widgets.case_status_changed_handler);
+                                widgets
+                                    .test_requirements
+                                    .block_signal(&widgets.case_requirements_changed_handler);
+                                widgets.test_requirements.set_text(
+                                    &tc.metadata()
+                                        .requirements()
+                                        .iter()
+                                        .flatten()
+                                        .map(|r| r.id().as_str())
+                                        .collect::<Vec<_>>()
+                                        .join(", "),
+                                );
+                                widgets
+                                    .test_requirements
+                                    .unblock_signal(&widgets.case_requirements_changed_handler);
 
                                 let mut custom_metadata = self.custom_metadata_factory.guard();
                                 custom_metadata.clear();
@@ -1338,6 +1579,8 @@ impl Component for AppModel {
                         for ev in new_evidence {
                             evidence.push_back(ev);
                         }
+                        drop(evidence);
+                        self.update_steps();
                     }
                     OpenCase::Nothing => (),
                 }
@@ -1440,6 +1683,162 @@ impl Component for AppModel {
                     widgets.nav_scrolled_window.set_vadjustment(Some(&adj));
                 }
             }
+            AppInput::CreateCaseFromTemplate => {
+                if let Some(pkg) = self.get_package() {
+                    let package = pkg
+                        .read()
+                        .metadata()
+                        .templates()
+                        .clone()
+                        .unwrap_or_default();
+                    let templates_dlg = TemplatesDialogModel::builder()
+                        .launch(TemplatesDialogInit {
+                            package,
+                            user: util::user_templates(),
+                        })
+                        .forward(sender.input_sender(), |msg| match msg {
+                            TemplatesOutput::CreateFromTemplate(template) => {
+                                AppInput::_CreateCaseFromTemplate(template)
+                            }
+                            TemplatesOutput::DeleteTemplate { name, user } => {
+                                AppInput::_DeleteTemplate { name, user }
+                            }
+                        });
+                    templates_dlg.emit(TemplatesInput::Present(root.clone()));
+                    self.latest_templates_dlg = Some(templates_dlg);
+                }
+            }
+            AppInput::_CreateCaseFromTemplate(template) => {
+                if let Some(pkg) = self.get_package() {
+                    let primary_field = {
+                        let pkg = pkg.read();
+                        pkg.metadata()
+                            .custom_test_case_metadata()
+                            .clone()
+                            .and_then(|m| m.into_iter().find(|(_k, v)| *v.primary()).clone())
+                    };
+                    let mut pkg = pkg.write();
+                    let case = match pkg.create_test_case_from_template(&template) {
+                        Ok(case) => case,
+                        Err(e) => {
+                            sender.input(AppInput::ShowError {
+                                title: lang::lookup("template-failed-title"),
+                                message: e.to_string(),
+                            });
+                            return;
+                        }
+                    };
+                    let case_id = *case.id();
+
+                    // Add case to navigation
+                    let mut test_case_data = self.test_case_nav_factory.guard();
+                    test_case_data.push_back(NavFactoryInit {
+                        id: case_id,
+                        name: case.metadata().title().clone(),
+                        status: *case.metadata().passed(),
+                        primary_custom_value: primary_field.map(|(k, _f)| {
+                            case.metadata()
+                                .custom()
+                                .as_ref()
+                                .and_then(|m| m.get(&k).cloned())
+                                .unwrap_or_default()
+                        }),
+                    });
+                    self.needs_saving = true;
+
+                    // Switch to case
+                    sender.input(AppInput::NavigateTo(OpenCase::Case {
+                        // index will be calculated by NavigateTo
+                        index: 0,
+                        id: case_id,
+                    }));
+
+                    // Move to bottom of list
+                    let adj = widgets.nav_scrolled_window.vadjustment();
+                    adj.set_value(adj.upper());
+                    widgets.nav_scrolled_window.set_vadjustment(Some(&adj));
+                }
+            }
+            AppInput::_DeleteTemplate { name, user } => {
+                if user {
+                    let mut templates = util::user_templates();
+                    templates.remove(&name);
+                    if let Err(e) = util::save_user_templates(&templates) {
+                        sender.input(AppInput::ShowError {
+                            title: lang::lookup("template-failed-title"),
+                            message: e.to_string(),
+                        });
+                    }
+                } else if let Some(pkg) = self.get_package() {
+                    pkg.write().metadata_mut().templates_mut().remove(&name);
+                    self.needs_saving = true;
+                }
+            }
+            AppInput::SaveCaseAsTemplate => {
+                if let OpenCase::Case { id, .. } = &self.open_case
+                    && let Some(pkg) = self.get_package()
+                {
+                    let name = pkg
+                        .read()
+                        .test_case(*id)
+                        .ok()
+                        .flatten()
+                        .map(|case| case.metadata().title().clone())
+                        .unwrap_or_default();
+                    let save_template_dlg = SaveTemplateDialogModel::builder()
+                        .launch(name)
+                        .forward(sender.input_sender(), |msg| match msg {
+                            SaveTemplateOutput::Save {
+                                name,
+                                title,
+                                description,
+                                user,
+                            } => AppInput::_SaveCaseAsTemplate {
+                                name,
+                                title,
+                                description,
+                                user,
+                            },
+                        });
+                    save_template_dlg.emit(SaveTemplateInput::Present(root.clone()));
+                    self.latest_save_template_dlg = Some(save_template_dlg);
+                }
+            }
+            AppInput::_SaveCaseAsTemplate {
+                name,
+                title,
+                description,
+                user,
+            } => {
+                if let OpenCase::Case { id, .. } = &self.open_case
+                    && let Some(pkg) = self.get_package()
+                {
+                    let template = {
+                        let pkg = pkg.read();
+                        let Some(case) = pkg.test_case(*id).ok().flatten() else {
+                            return;
+                        };
+                        let mut template = TestCaseTemplate::from_test_case(name, title, case);
+                        template.set_description(description);
+                        template
+                    };
+                    if user {
+                        let mut templates = util::user_templates();
+                        templates.insert(template);
+                        if let Err(e) = util::save_user_templates(&templates) {
+                            sender.input(AppInput::ShowError {
+                                title: lang::lookup("template-failed-title"),
+                                message: e.to_string(),
+                            });
+                            return;
+                        }
+                    } else {
+                        pkg.write().metadata_mut().templates_mut().insert(template);
+                        self.needs_saving = true;
+                    }
+                    sender.input(AppInput::ShowToast(lang::lookup("toast-template-saved")));
+                }
+            }
             AppInput::SetMetadataTitle(new_title) => {
                 if new_title.trim().is_empty() {
                     widgets.metadata_title.add_css_class("error");
@@ -1654,6 +2053,30 @@ impl Component for AppModel {
                     }
                 }
             }
+            AppInput::SetTestCaseRequirements(text) => {
+                if let OpenCase::Case { id, .. } = &self.open_case
+                    && let Some(pkg) = self.get_package()
+                    && let Some(tc) = pkg.write().test_case_mut(*id).ok().flatten()
+                {
+                    let requirements = tc.metadata_mut().requirements_mut();
+                    let mut new_requirements: Vec<Requirement> = vec![];
+                    for id in text.split(',').map(str::trim).filter(|id| !id.is_empty()) {
+                        if new_requirements.iter().any(|r| r.id() == id) {
+                            continue;
+                        }
+                        // Keep the details of requirements that are still covered
+                        new_requirements.push(
+                            requirements
+                                .iter()
+                                .find(|r| r.id() == id)
+                                .cloned()
+                                .unwrap_or_else(|| Requirement::new(id)),
+                        );
+                    }
+                    *requirements = new_requirements;
+                    self.needs_saving = true;
+                }
+            }
             AppInput::SetCustomMetadataValue { key, new_value } => {
                 if let OpenCase::Case { index, id, .. } = &self.open_case {
                     if let Some(pkg) = self.get_package() {
@@ -1962,22 +2385,147 @@ impl Component for AppModel {
                 self.latest_add_evidence_file_dlg = Some(add_evidence_file_dlg);
                 self.action_paste_evidence.set_enabled(false);
             }
+            AppInput::AddLogEvidence => {
+                let add_evidence_log_dlg = AddLogEvidenceDialogModel::builder().launch(()).forward(
+                    sender.input_sender(),
+                    |msg| match msg {
+                        AddEvidenceOutput::AddEvidence(ev) => AppInput::_AddEvidence(ev, None),
+                        AddEvidenceOutput::Error { title, message } => {
+                            AppInput::ShowError { title, message }
+                        }
+                        AddEvidenceOutput::Closed => AppInput::ReinstatePaste,
+                    },
+                );
+                add_evidence_log_dlg.emit(AddEvidenceInput::Present(root.clone()));
+                self.latest_add_evidence_log_dlg = Some(add_evidence_log_dlg);
+                self.action_paste_evidence.set_enabled(false);
+            }
+            AppInput::AddTableEvidence => {
+                let add_evidence_table_dlg = AddTableEvidenceDialogModel::builder()
+                    .launch(())
+                    .forward(sender.input_sender(), |msg| match msg {
+                        AddEvidenceOutput::AddEvidence(ev) => AppInput::_AddEvidence(ev, None),
+                        AddEvidenceOutput::Error { title, message } => {
+                            AppInput::ShowError { title, message }
+                        }
+                        AddEvidenceOutput::Closed => AppInput::ReinstatePaste,
+                    });
+                add_evidence_table_dlg.emit(AddEvidenceInput::Present(root.clone()));
+                self.latest_add_evidence_table_dlg = Some(add_evidence_table_dlg);
+                self.action_paste_evidence.set_enabled(false);
+            }
+            AppInput::AddDiffEvidence => {
+                let add_evidence_diff_dlg = AddDiffEvidenceDialogModel::builder()
+                    .launch(())
+                    .forward(sender.input_sender(), |msg| match msg {
+                        AddEvidenceOutput::AddEvidence(ev) => AppInput::_AddEvidence(ev, None),
+                        AddEvidenceOutput::Error { title, message } => {
+                            AppInput::ShowError { title, message }
+                        }
+                        AddEvidenceOutput::Closed => AppInput::ReinstatePaste,
+                    });
+                add_evidence_diff_dlg.emit(AddDiffEvidenceInput::Present(root.clone()));
+                self.latest_add_evidence_diff_dlg = Some(add_evidence_diff_dlg);
+                self.action_paste_evidence.set_enabled(false);
+            }
+            AppInput::AddCommandEvidence => {
+                let add_evidence_command_dlg = AddCommandEvidenceDialogModel::builder()
+                    .launch(())
+                    .forward(sender.input_sender(), |msg| match msg {
+                        AddEvidenceOutput::AddEvidence(ev) => AppInput::_AddEvidence(ev, None),
+                        AddEvidenceOutput::Error { title, message } => {
+                            AppInput::ShowError { title, message }
+                        }
+                        AddEvidenceOutput::Closed => AppInput::ReinstatePaste,
+                    });
+                add_evidence_command_dlg.emit(AddCommandEvidenceInput::Present(root.clone()));
+                self.latest_add_evidence_command_dlg = Some(add_evidence_command_dlg);
+                self.action_paste_evidence.set_enabled(false);
+            }
+            AppInput::AddSqlEvidence => {
+                let add_evidence_sql_dlg = AddSqlEvidenceDialogModel::builder().launch(()).forward(
+                    sender.input_sender(),
+                    |msg| match msg {
+                        AddEvidenceOutput::AddEvidence(ev) => AppInput::_AddEvidence(ev, None),
+                        AddEvidenceOutput::Error { title, message } => {
+                            AppInput::ShowError { title, message }
+                        }
+                        AddEvidenceOutput::Closed => AppInput::ReinstatePaste,
+                    },
+                );
+                add_evidence_sql_dlg.emit(AddEvidenceInput::Present(root.clone()));
+                self.latest_add_evidence_sql_dlg = Some(add_evidence_sql_dlg);
+                self.action_paste_evidence.set_enabled(false);
+            }
+            AppInput::AddLinkEvidence => {
+                let add_evidence_link_dlg = AddLinkEvidenceDialogModel::builder()
+                    .launch(())
+                    .forward(sender.input_sender(), |msg| match msg {
+                        AddEvidenceOutput::AddEvidence(ev) => AppInput::_AddEvidence(ev, None),
+                        AddEvidenceOutput::Error { title, message } => {
+                            AppInput::ShowError { title, message }
+                        }
+                        AddEvidenceOutput::Closed => AppInput::ReinstatePaste,
+                    });
+                add_evidence_link_dlg.emit(AddEvidenceInput::Present(root.clone()));
+                self.latest_add_evidence_link_dlg = Some(add_evidence_link_dlg);
+                self.action_paste_evidence.set_enabled(false);
+            }
+            AppInput::AddHarEvidence => {
+                let add_evidence_har_dlg = AddHarEvidenceDialogModel::builder().launch(()).forward(
+                    sender.input_sender(),
+                    |msg| match msg {
+                        AddEvidenceOutput::AddEvidence(ev) => AppInput::_AddEvidence(ev, None),
+                        AddEvidenceOutput::Error { title, message } => {
+                            AppInput::ShowError { title, message }
+                        }
+                        AddEvidenceOutput::Closed => AppInput::ReinstatePaste,
+                    },
+                );
+                add_evidence_har_dlg.emit(AddHarEvidenceInput::Present(root.clone()));
+                self.latest_add_evidence_har_dlg = Some(add_evidence_har_dlg);
+                self.action_paste_evidence.set_enabled(false);
+            }
+            AppInput::AddVideoEvidence => {
+                let add_evidence_video_dlg = AddVideoEvidenceDialogModel::builder()
+                    .launch(self.get_package().unwrap())
+                    .forward(sender.input_sender(), |msg| match msg {
+                        AddEvidenceOutput::AddEvidence(ev) => AppInput::_AddEvidence(ev, None),
+                        AddEvidenceOutput::Error { title, message } => {
+                            AppInput::ShowError { title, message }
+                        }
+                        AddEvidenceOutput::Closed => AppInput::ReinstatePaste,
+                    });
+                add_evidence_video_dlg.emit(AddVideoEvidenceInput::Present(root.clone()));
+                self.latest_add_evidence_video_dlg = Some(add_evidence_video_dlg);
+                self.action_paste_evidence.set_enabled(false);
+            }
             AppInput::ReinstatePaste => self.action_paste_evidence.set_enabled(true),
-            AppInput::_AddEvidence(ev, maybe_pos) => {
+            AppInput::_AddEvidence(mut ev, maybe_pos) => {
                 if let Some(pkg) = self.get_package() {
                     if let OpenCase::Case { id, .. } = &self.open_case {
+                        let settings = util::redaction_settings(&pkg.read());
+                        if *settings.redact_on_add() {
+                            match SecretRedactor::new(&settings) {
+                                Ok(redactor) => {
+                                    let redacted = redactor.redact_evidence(&mut ev).len();
+                                    if redacted > 0 {
+                                        sender.input(AppInput::ShowToast(lang::lookup_with_args(
+                                            "toast-secrets-redacted",
+                                            &lang_args!("count", redacted),
+                                        )));
+                                    }
+                                }
+                                Err(e) => tracing::warn!("Failed to redact evidence: {e}"),
+                            }
+                        }
                         {
                             let mut pkg_guard = pkg.write();
-                            let evidence = pkg_guard
-                                .test_case_mut(*id)
-                                .ok()
-                                .flatten()
-                                .unwrap()
-                                .evidence_mut();
+                            let test_case = pkg_guard.test_case_mut(*id).ok().flatten().unwrap();
                             if let Some(pos) = &maybe_pos {
-                                evidence.insert(*pos, ev.clone());
+                                test_case.insert_evidence(*pos, ev.clone());
                             } else {
-                                evidence.push(ev.clone());
+                                test_case.evidence_mut().push(ev.clone());
                             }
                         }
                         self.needs_saving = true;
@@ -2011,16 +2559,12 @@ impl Component for AppModel {
                         let at = {
                             // This block prevents a panic when only one item is present
                             let mut pkg_w = pkg.write();
-                            let evidence = pkg_w
-                                .test_case_mut(*id)
-                                .ok()
-                                .flatten()
-                                .unwrap()
-                                .evidence_mut();
-                            let at = at.min(evidence.len());
-                            evidence.insert(at, ev.clone());
+                            let test_case = pkg_w.test_case_mut(*id).ok().flatten().unwrap();
+                            let at = at.min(test_case.evidence().len());
+                            test_case.insert_evidence(at, ev.clone());
                             at
                         };
+                        self.moved_evidence_to = Some(at);
                         self.needs_saving = true;
                         // update evidence
                         let mut tef = self.test_evidence_factory.guard();
@@ -2055,19 +2599,26 @@ impl Component for AppModel {
             AppInput::DeleteEvidenceAt(at, user_triggered) => {
                 if let Some(pkg) = self.get_package() {
                     if let OpenCase::Case { id, .. } = &self.open_case {
-                        let mut pkg = pkg.write();
-                        let evidence = pkg
-                            .test_case_mut(*id)
-                            .ok()
-                            .flatten()
-                            .unwrap()
-                            .evidence_mut();
-                        let ev = evidence.remove(at.current_index());
+                        let mut pkg_w = pkg.write();
+                        let test_case = pkg_w.test_case_mut(*id).ok().flatten().unwrap();
+                        // Steps follow evidence that is being moved, rather than losing it
+                        if let Some(to) = self.moved_evidence_to.take()
+                            && !user_triggered
+                        {
+                            for step in test_case.steps_mut() {
+                                if step.evidence().contains(&at.current_index()) {
+                                    step.attach_evidence(to);
+                                }
+                            }
+                        }
+                        let ev = test_case.remove_evidence(at.current_index()).unwrap();
+                        drop(pkg_w);
                         self.needs_saving = true;
                         // update evidence
                         let mut tef = self.test_evidence_factory.guard();
                         let index = at.current_index();
                         tef.remove(at.current_index());
+                        drop(tef);
 
                         if user_triggered {
                             let toast = adw::Toast::new(&lang::lookup("toast-evidence-deleted"));
@@ -2082,11 +2633,69 @@ impl Component for AppModel {
                             self.latest_delete_toasts.push(toast);
                         }
 
+                        self.update_steps();
+
                         // Fix for #73
                         widgets.test_case_scrolled.grab_focus();
                     }
                 }
             }
+            AppInput::AddStep => {
+                if let OpenCase::Case { id, .. } = &self.open_case
+                    && let Some(pkg) = self.get_package()
+                    && let Some(tc) = pkg.write().test_case_mut(*id).ok().flatten()
+                {
+                    let step = TestStep::new(lang::lookup("step-default-action"));
+                    tc.steps_mut().push(step.clone());
+                    self.needs_saving = true;
+                    self.test_step_factory.guard().push_back(step);
+                }
+            }
+            AppInput::UpdateStep { index, step } => {
+                if let OpenCase::Case {
+                    index: case_index,
+                    id,
+                } = &self.open_case
+                    && let Some(pkg) = self.get_package()
+                    && let Some(tc) = pkg.write().test_case_mut(*id).ok().flatten()
+                    && let Some(existing) = tc.steps_mut().get_mut(index.current_index())
+                {
+                    let status_changed = existing.status() != step.status();
+                    *existing = step;
+                    self.needs_saving = true;
+
+                    if status_changed {
+                        tc.update_status_from_steps();
+                        let status = *tc.metadata().passed();
+                        widgets
+                            .test_status
+                            .block_signal(&widgets.case_status_changed_handler);
+                        widgets.test_status.set_selected(match status {
+                            None => 0,
+                            Some(TestCasePassStatus::Pass) => 1,
+                            Some(TestCasePassStatus::Fail) => 2,
+                        });
+                        widgets
+                            .test_status
+                            .unblock_signal(&widgets.case_status_changed_handler);
+                        self.test_case_nav_factory
+                            .send(*case_index, NavFactoryInput::UpdateStatus(status));
+                    }
+                }
+            }
+            AppInput::DeleteStep(index) => {
+                if let OpenCase::Case { id, .. } = &self.open_case {
+                    if let Some(pkg) = self.get_package()
+                        && let Some(tc) = pkg.write().test_case_mut(*id).ok().flatten()
+                        && index.current_index() < tc.steps().len()
+                    {
+                        tc.steps_mut().remove(index.current_index());
+                        self.needs_saving = true;
+                    }
+                    // Rebuild so the remaining steps are renumbered
+                    self.update_steps();
+                }
+            }
             AppInput::_AddMedia(media) => {
                 if let Some(pkg) = self.get_package() {
                     // unwraps here cannot fail
@@ -2107,29 +2716,47 @@ impl Component for AppModel {
                 if self.open_package.is_none() {
                     return;
                 }
+                let pkg = self.open_package.as_ref().unwrap().read();
+                let test_cases = pkg
+                    .test_case_iter()
+                    .map(|cases| {
+                        cases
+                            .map(|case| (*case.id(), case.metadata().title().clone()))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                let mut custom_fields: Vec<_> = pkg
+                    .metadata()
+                    .custom_test_case_metadata()
+                    .iter()
+                    .flatten()
+                    .map(|(id, field)| (id.clone(), field.name().clone()))
+                    .collect();
+                custom_fields.sort();
                 let needs_saving = self.needs_saving;
                 let export_dlg = ExportDialogModel::builder()
                     .launch(ExportDialogInit {
-                        package_name: self
-                            .open_package
-                            .as_ref()
-                            .unwrap()
-                            .read()
-                            .metadata()
-                            .title()
-                            .clone(),
+                        package_name: pkg.metadata().title().clone(),
                         package_path: self.open_path.clone().unwrap(),
                         test_case_name: None,
                         needs_saving,
+                        redact: *util::redaction_settings(&pkg).redact_on_export(),
+                        test_cases,
+                        custom_fields,
                     })
                     .forward(sender.input_sender(), move |msg| match msg {
-                        ExportOutput::Export { format, path } => {
+                        ExportOutput::Export {
+                            format,
+                            path,
+                            redact,
+                            options,
+                        } => {
                             if needs_saving {
                                 AppInput::SaveFileThen(Box::new(AppInput::_ExportPackage(
-                                    format, path,
+                                    format, path, redact, options,
                                 )))
                             } else {
-                                AppInput::_ExportPackage(format, path)
+                                AppInput::_ExportPackage(format, path, redact, options)
                             }
                         }
                     });
@@ -2155,15 +2782,23 @@ impl Component for AppModel {
                             package_path: self.open_path.clone().unwrap(),
                             test_case_name: Some(case_name),
                             needs_saving,
+                            redact: *util::redaction_settings(&pkg).redact_on_export(),
+                            test_cases: vec![],
+                            custom_fields: vec![],
                         })
                         .forward(sender.input_sender(), move |msg| match msg {
-                            ExportOutput::Export { format, path } => {
+                            ExportOutput::Export {
+                                format,
+                                path,
+                                redact,
+                                options,
+                            } => {
                                 if needs_saving {
                                     AppInput::SaveFileThen(Box::new(AppInput::_ExportTestCase(
-                                        format, path,
+                                        format, path, redact, options,
                                     )))
                                 } else {
-                                    AppInput::_ExportTestCase(format, path)
+                                    AppInput::_ExportTestCase(format, path, redact, options)
                                 }
                             }
                         });
@@ -2171,40 +2806,53 @@ impl Component for AppModel {
                     self.latest_export_dlg = Some(export_dlg);
                 }
             }
-            AppInput::_ExportPackage(format, path) => {
+            AppInput::_ExportPackage(format, path, redact, options) => {
                 if let Some(pkg) = &self.open_package {
                     let mut pkg = pkg.write();
-                    if let Err(e) = match format.as_str() {
-                        "html document" => HtmlExporter.export_package(&mut pkg, path.clone()),
-                        "excel workbook" => ExcelExporter.export_package(&mut pkg, path.clone()),
-                        "zip archive of files" => {
-                            ZipOfFilesExporter.export_package(&mut pkg, path.clone())
-                        }
-                        _ => {
+                    let redactor = if redact {
+                        SecretRedactor::new(&util::redaction_settings(&pkg)).map(Some)
+                    } else {
+                        Ok(None)
+                    };
+                    let result = redactor.and_then(|redactor| {
+                        if let Some(mut exporter) = ExporterRegistry::default().create(&format) {
+                            export_with(
+                                exporter.as_mut(),
+                                &mut pkg,
+                                None,
+                                path.clone(),
+                                &options,
+                                redactor.as_ref(),
+                            )
+                        } else {
                             tracing::error!("Invalid format specified.");
-                            Ok(())
+                            Ok(None)
+                        }
+                    });
+                    match result {
+                        Err(e) => {
+                            // Show error dialog
+                            let error_dlg = ErrorDialogModel::builder()
+                                .launch(ErrorDialogInit {
+                                    title: Box::new(lang::lookup("export-error-failed-title")),
+                                    body: Box::new(lang::lookup_with_args(
+                                        "export-error-failed-message",
+                                        &lang_args!("error", e.to_string()),
+                                    )),
+                                })
+                                .forward(sender.input_sender(), |msg| match msg {});
+                            error_dlg.emit(ErrorDialogInput::Present(root.clone()));
+                            self.latest_error_dlg = Some(error_dlg);
+                        }
+                        Ok(report) => {
+                            let toast = adw::Toast::new(&export_complete_message(report.as_ref()));
+                            toast.set_timeout(5);
+                            toast.set_button_label(Some(&lang::lookup("header-open")));
+                            toast.connect_button_clicked(move |_| {
+                                open::that_in_background(path.clone());
+                            });
+                            widgets.toast_target.add_toast(toast);
                         }
-                    } {
-                        // Show error dialog
-                        let error_dlg = ErrorDialogModel::builder()
-                            .launch(ErrorDialogInit {
-                                title: Box::new(lang::lookup("export-error-failed-title")),
-                                body: Box::new(lang::lookup_with_args(
-                                    "export-error-failed-message",
-                                    &lang_args!("error", e.to_string()),
-                                )),
-                            })
-                            .forward(sender.input_sender(), |msg| match msg {});
-                        error_dlg.emit(ErrorDialogInput::Present(root.clone()));
-                        self.latest_error_dlg = Some(error_dlg);
-                    } else {
-                        let toast = adw::Toast::new(&lang::lookup("toast-export-complete"));
-                        toast.set_timeout(5);
-                        toast.set_button_label(Some(&lang::lookup("header-open")));
-                        toast.connect_button_clicked(move |_| {
-                            open::that_in_background(path.clone());
-                        });
-                        widgets.toast_target.add_toast(toast);
                     }
                 } else {
                     // Show error dialog
@@ -2218,26 +2866,33 @@ impl Component for AppModel {
                     self.latest_error_dlg = Some(error_dlg);
                 }
             }
-            AppInput::_ExportTestCase(format, path) => {
+            AppInput::_ExportTestCase(format, path, redact, options) => {
                 if let Some(pkg) = &self.open_package {
                     let mut pkg = pkg.write();
 
                     if let OpenCase::Case { id, .. } = &self.open_case {
-                        if let Err(e) = match format.as_str() {
-                            "html document" => {
-                                HtmlExporter.export_case(&mut pkg, *id, path.clone())
-                            }
-                            "excel workbook" => {
-                                ExcelExporter.export_case(&mut pkg, *id, path.clone())
-                            }
-                            "zip archive of files" => {
-                                ZipOfFilesExporter.export_case(&mut pkg, *id, path.clone())
-                            }
-                            _ => {
+                        let redactor = if redact {
+                            SecretRedactor::new(&util::redaction_settings(&pkg)).map(Some)
+                        } else {
+                            Ok(None)
+                        };
+                        let result = redactor.and_then(|redactor| {
+                            if let Some(mut exporter) = ExporterRegistry::default().create(&format)
+                            {
+                                export_with(
+                                    exporter.as_mut(),
+                                    &mut pkg,
+                                    Some(*id),
+                                    path.clone(),
+                                    &options,
+                                    redactor.as_ref(),
+                                )
+                            } else {
                                 tracing::error!("Invalid format specified.");
-                                Ok(())
+                                Ok(None)
                             }
-                        } {
+                        });
+                        if let Err(e) = result {
                             // Show error dialog
                             let error_dlg = ErrorDialogModel::builder()
                                 .launch(ErrorDialogInit {
@@ -2250,8 +2905,8 @@ impl Component for AppModel {
                                 .forward(sender.input_sender(), |msg| match msg {});
                             error_dlg.emit(ErrorDialogInput::Present(root.clone()));
                             self.latest_error_dlg = Some(error_dlg);
-                        } else {
-                            let toast = adw::Toast::new(&lang::lookup("toast-export-complete"));
+                        } else if let Ok(report) = result {
+                            let toast = adw::Toast::new(&export_complete_message(report.as_ref()));
                             toast.set_timeout(5);
                             toast.set_button_label(Some(&lang::lookup("header-open")));
                             toast.connect_button_clicked(move |_| {
@@ -2365,11 +3020,104 @@ impl Component for AppModel {
                 toast.set_timeout(3);
                 widgets.toast_target.add_toast(toast);
             }
+            AppInput::OpenRedactionSettings => {
+                if let Some(pkg) = self.get_package() {
+                    let settings = pkg
+                        .read()
+                        .metadata()
+                        .redaction()
+                        .clone()
+                        .unwrap_or_default();
+                    let redaction_dlg = RedactionDialogModel::builder().launch(settings).forward(
+                        sender.input_sender(),
+                        |msg| match msg {
+                            RedactionOutput::SetSettings(settings) => {
+                                AppInput::_SetRedactionSettings(settings)
+                            }
+                            RedactionOutput::RedactNow => AppInput::_RedactPackage,
+                        },
+                    );
+                    redaction_dlg.emit(RedactionInput::Present(root.clone()));
+                    self.latest_redaction_dlg = Some(redaction_dlg);
+                }
+            }
+            AppInput::_SetRedactionSettings(settings) => {
+                if let Some(pkg) = self.get_package() {
+                    pkg.write().metadata_mut().set_redaction(Some(settings));
+                    self.needs_saving = true;
+                }
+            }
+            AppInput::_RedactPackage => {
+                if let Some(pkg) = self.get_package() {
+                    let result = {
+                        let mut pkg = pkg.write();
+                        SecretRedactor::new(&util::redaction_settings(&pkg))
+                            .and_then(|redactor| redactor.redact_package(&mut pkg))
+                    };
+                    match result {
+                        Ok(report) => {
+                            if !report.is_empty() {
+                                self.needs_saving = true;
+                                // Show the redacted evidence
+                                sender.input(AppInput::NavigateTo(self.open_case));
+                            }
+                            sender.input(AppInput::ShowToast(if report.is_empty() {
+                                lang::lookup("toast-no-secrets")
+                            } else {
+                                lang::lookup_with_args(
+                                    "toast-secrets-redacted",
+                                    &lang_args!("count", report.total()),
+                                )
+                            }));
+                        }
+                        Err(e) => sender.input(AppInput::ShowError {
+                            title: lang::lookup("redaction-failed-title"),
+                            message: e.to_string(),
+                        }),
+                    }
+                }
+            }
         }
         self.update_view(widgets, sender);
     }
 }
 
+/// Export a package, or one test case from it, redacting secrets if a redactor is given.
+fn export_with(
+    exporter: &mut dyn Exporter,
+    package: &mut EvidencePackage,
+    case: Option<Uuid>,
+    path: PathBuf,
+    options: &ExportOptions,
+    redactor: Option<&SecretRedactor>,
+) -> evidenceangel::Result<Option<RedactionReport>> {
+    match (case, redactor) {
+        (None, None) => exporter
+            .export_package(package, path, options)
+            .map(|()| None),
+        (Some(case), None) => exporter
+            .export_case(package, case, path, options)
+            .map(|()| None),
+        (None, Some(redactor)) => exporter
+            .export_package_redacted(package, path, options, redactor)
+            .map(Some),
+        (Some(case), Some(redactor)) => exporter
+            .export_case_redacted(package, case, path, options, redactor)
+            .map(Some),
+    }
+}
+
+/// The message shown once an export is complete, including how many secrets were redacted.
+fn export_complete_message(report: Option<&RedactionReport>) -> String {
+    match report {
+        Some(report) if !report.is_empty() => lang::lookup_with_args(
+            "toast-export-complete-redacted",
+            &lang_args!("count", report.total()),
+        ),
+        _ => lang::lookup("toast-export-complete"),
+    }
+}
+
 #[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
 enum OpenCase {
     #[default]
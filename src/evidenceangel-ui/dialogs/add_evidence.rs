@@ -5,7 +5,11 @@ use std::{
 };
 
 use adw::prelude::*;
-use evidenceangel::{Evidence, EvidenceData, EvidenceKind, EvidencePackage, MediaFile};
+use evidenceangel::{
+    CommandExecution, Diff, DiffFormat, Evidence, EvidenceData, EvidenceKind, EvidencePackage,
+    HarEntry, Link, LinkSystem, Log, LogFormat, MediaFile, SqlQuery, StructuredEvidence, Table,
+    TableFormat, parse_har,
+};
 use parking_lot::RwLock;
 use relm4::{
     Component, ComponentParts, ComponentSender, RelmWidgetExt,
@@ -118,11 +122,12 @@ impl Component for AddImageEvidenceDialogModel {
             AddEvidenceInput::_AddEvidence => {
                 let path = widgets.file_row.text().to_string();
                 // Read file data
+                let path_c = path.clone();
                 let read_data = move || {
                     use std::fs::File;
 
                     let mut buf = vec![];
-                    let mut br = BufReader::new(File::open(path)?);
+                    let mut br = BufReader::new(File::open(path_c)?);
                     br.read_to_end(&mut buf)?;
 
                     Ok(buf)
@@ -144,7 +149,12 @@ impl Component for AddImageEvidenceDialogModel {
 
                 // Add media to package
                 let mut pkg = self.package.write();
-                let media = MediaFile::from(data);
+                let mut media = MediaFile::from(data);
+                media.set_original_filename(
+                    PathBuf::from(&path)
+                        .file_name()
+                        .map(|s| s.to_string_lossy().to_string()),
+                );
                 let hash = media.hash();
                 if let Err(e) = pkg.add_media(media) {
                     sender
@@ -312,7 +322,12 @@ impl Component for AddFileEvidenceDialogModel {
 
                 // Add media to package
                 let mut pkg = self.package.write();
-                let media = MediaFile::from(data);
+                let mut media = MediaFile::from(data);
+                media.set_original_filename(
+                    PathBuf::from(&path)
+                        .file_name()
+                        .map(|s| s.to_string_lossy().to_string()),
+                );
                 let hash = media.hash();
                 if let Err(e) = pkg.add_media(media) {
                     sender
@@ -368,3 +383,1493 @@ impl Component for AddFileEvidenceDialogModel {
         self.update_view(widgets, sender);
     }
 }
+
+/// The formats a log can be read as, in the order they are listed.
+const LOG_FORMATS: &[(&str, LogFormat)] = &[
+    ("add-evidence-log-format-auto", LogFormat::Auto),
+    ("add-evidence-log-format-plain", LogFormat::Plain),
+    ("add-evidence-log-format-jsonl", LogFormat::JsonLines),
+    ("add-evidence-log-format-syslog", LogFormat::Syslog),
+];
+
+pub struct AddLogEvidenceDialogModel {}
+
+#[relm4::component(pub)]
+impl Component for AddLogEvidenceDialogModel {
+    type Input = AddEvidenceInput;
+    type Output = AddEvidenceOutput;
+    type CommandOutput = ();
+    type Init = ();
+
+    view! {
+        #[root]
+        adw::Dialog {
+            connect_closed[sender] => move |_dlg| {
+                let _ = sender.output(AddEvidenceOutput::Closed);
+            },
+
+            #[wrap(Some)]
+            set_child = &adw::ToolbarView {
+                add_top_bar = &adw::HeaderBar {
+                    #[wrap(Some)]
+                    set_title_widget = &adw::WindowTitle {
+                        set_title: &lang::lookup("add-evidence-title"),
+                    }
+                },
+                set_width_request: 400,
+
+                gtk::Box {
+                    set_orientation: gtk::Orientation::Vertical,
+                    set_spacing: 8,
+                    set_margin_all: 16,
+
+                    adw::PreferencesGroup {
+                        #[name = "file_row"]
+                        adw::EntryRow {
+                            set_title: &lang::lookup("add-evidence-log-label"),
+                            add_suffix = &gtk::Button {
+                                set_icon_name: relm4_icons::icon_names::FOLDER_OPEN_FILLED,
+                                set_tooltip: &lang::lookup("select"),
+                                add_css_class: "flat",
+                                connect_clicked => AddEvidenceInput::_SelectFile,
+                            },
+                            connect_entry_activated => AddEvidenceInput::_AddEvidence,
+                        },
+                        #[name = "format_row"]
+                        adw::ComboRow {
+                            set_title: &lang::lookup("add-evidence-log-format-label"),
+                            set_model: Some(&gtk::StringList::new(&format_names)),
+                        },
+                        #[name = "caption_entry"]
+                        adw::EntryRow {
+                            set_title: &lang::lookup("add-evidence-log-caption-label"),
+                            connect_entry_activated => AddEvidenceInput::_AddEvidence,
+                        },
+                    },
+                    gtk::Button {
+                        set_label: &lang::lookup("add-evidence-submit"),
+                        add_css_class: "pill",
+                        add_css_class: "suggested-action",
+                        set_halign: gtk::Align::Center,
+
+                        connect_clicked => AddEvidenceInput::_AddEvidence,
+                    }
+                }
+            }
+        }
+    }
+
+    fn init(
+        _init: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let model = AddLogEvidenceDialogModel {};
+        let format_names = LOG_FORMATS
+            .iter()
+            .map(|(key, _)| lang::lookup(key))
+            .collect::<Vec<_>>();
+        let format_names = format_names.iter().map(String::as_str).collect::<Vec<_>>();
+        let widgets = view_output!();
+        ComponentParts { model, widgets }
+    }
+
+    fn update_with_view(
+        &mut self,
+        widgets: &mut Self::Widgets,
+        message: Self::Input,
+        sender: ComponentSender<Self>,
+        root: &Self::Root,
+    ) {
+        match message {
+            AddEvidenceInput::Present(window) => {
+                root.present(Some(&window));
+            }
+            AddEvidenceInput::_AddEvidence => {
+                let path = widgets.file_row.text().to_string();
+                let text = match std::fs::read(&path) {
+                    Ok(data) => String::from_utf8_lossy(&data).to_string(),
+                    Err(e) => {
+                        sender
+                            .output(AddEvidenceOutput::Error {
+                                title: lang::lookup("add-evidence-log-failed"),
+                                message: lang::lookup_with_args(
+                                    "add-evidence-log-failed-message",
+                                    &lang_args!("error", e.to_string()),
+                                ),
+                            })
+                            .unwrap();
+                        return;
+                    }
+                };
+
+                let format = LOG_FORMATS
+                    .get(widgets.format_row.selected() as usize)
+                    .map_or(LogFormat::Auto, |(_, format)| *format);
+                let mut ev = Log::parse(&text, format).to_evidence();
+                let caption_text = widgets.caption_entry.text().to_string();
+                if !caption_text.trim().is_empty() {
+                    ev.set_caption(Some(caption_text.trim().to_string()));
+                }
+                let _ = sender.output(AddEvidenceOutput::AddEvidence(ev));
+                root.close();
+            }
+            AddEvidenceInput::_SelectFile => {
+                // Open file selector
+                let dialog = gtk::FileDialog::builder()
+                    .modal(true)
+                    .title(lang::lookup("header-open"))
+                    .build();
+
+                let sender_c = sender.clone();
+                dialog.open(
+                    Some(&root.toplevel_window().unwrap()),
+                    Some(&relm4::gtk::gio::Cancellable::new()),
+                    move |res| {
+                        if let Ok(file) = res {
+                            let path = file.path().unwrap();
+                            sender_c.input(AddEvidenceInput::_FileSelected(path));
+                        }
+                    },
+                );
+            }
+            AddEvidenceInput::_FileSelected(path) => {
+                widgets.file_row.set_text(path.to_str().unwrap_or_default());
+            }
+        }
+        self.update_view(widgets, sender);
+    }
+}
+
+/// The formats a table can be read as, in the order they are listed.
+const TABLE_FORMATS: &[(&str, TableFormat)] = &[
+    ("add-evidence-table-format-auto", TableFormat::Auto),
+    ("add-evidence-table-format-csv", TableFormat::Csv),
+    ("add-evidence-table-format-tsv", TableFormat::Tsv),
+    ("add-evidence-table-format-json", TableFormat::Json),
+];
+
+pub struct AddTableEvidenceDialogModel {}
+
+#[relm4::component(pub)]
+impl Component for AddTableEvidenceDialogModel {
+    type Input = AddEvidenceInput;
+    type Output = AddEvidenceOutput;
+    type CommandOutput = ();
+    type Init = ();
+
+    view! {
+        #[root]
+        adw::Dialog {
+            connect_closed[sender] => move |_dlg| {
+                let _ = sender.output(AddEvidenceOutput::Closed);
+            },
+
+            #[wrap(Some)]
+            set_child = &adw::ToolbarView {
+                add_top_bar = &adw::HeaderBar {
+                    #[wrap(Some)]
+                    set_title_widget = &adw::WindowTitle {
+                        set_title: &lang::lookup("add-evidence-title"),
+                    }
+                },
+                set_width_request: 400,
+
+                gtk::Box {
+                    set_orientation: gtk::Orientation::Vertical,
+                    set_spacing: 8,
+                    set_margin_all: 16,
+
+                    adw::PreferencesGroup {
+                        #[name = "file_row"]
+                        adw::EntryRow {
+                            set_title: &lang::lookup("add-evidence-table-label"),
+                            add_suffix = &gtk::Button {
+                                set_icon_name: relm4_icons::icon_names::FOLDER_OPEN_FILLED,
+                                set_tooltip: &lang::lookup("select"),
+                                add_css_class: "flat",
+                                connect_clicked => AddEvidenceInput::_SelectFile,
+                            },
+                            connect_entry_activated => AddEvidenceInput::_AddEvidence,
+                        },
+                        #[name = "format_row"]
+                        adw::ComboRow {
+                            set_title: &lang::lookup("add-evidence-table-format-label"),
+                            set_model: Some(&gtk::StringList::new(&format_names)),
+                        },
+                        #[name = "caption_entry"]
+                        adw::EntryRow {
+                            set_title: &lang::lookup("add-evidence-table-caption-label"),
+                            connect_entry_activated => AddEvidenceInput::_AddEvidence,
+                        },
+                    },
+                    gtk::Button {
+                        set_label: &lang::lookup("add-evidence-submit"),
+                        add_css_class: "pill",
+                        add_css_class: "suggested-action",
+                        set_halign: gtk::Align::Center,
+
+                        connect_clicked => AddEvidenceInput::_AddEvidence,
+                    }
+                }
+            }
+        }
+    }
+
+    fn init(
+        _init: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let model = AddTableEvidenceDialogModel {};
+        let format_names = TABLE_FORMATS
+            .iter()
+            .map(|(key, _)| lang::lookup(key))
+            .collect::<Vec<_>>();
+        let format_names = format_names.iter().map(String::as_str).collect::<Vec<_>>();
+        let widgets = view_output!();
+        ComponentParts { model, widgets }
+    }
+
+    fn update_with_view(
+        &mut self,
+        widgets: &mut Self::Widgets,
+        message: Self::Input,
+        sender: ComponentSender<Self>,
+        root: &Self::Root,
+    ) {
+        match message {
+            AddEvidenceInput::Present(window) => {
+                root.present(Some(&window));
+            }
+            AddEvidenceInput::_AddEvidence => {
+                let path = widgets.file_row.text().to_string();
+                let text = match std::fs::read(&path) {
+                    Ok(data) => String::from_utf8_lossy(&data).to_string(),
+                    Err(e) => {
+                        sender
+                            .output(AddEvidenceOutput::Error {
+                                title: lang::lookup("add-evidence-table-failed"),
+                                message: lang::lookup_with_args(
+                                    "add-evidence-table-failed-message",
+                                    &lang_args!("error", e.to_string()),
+                                ),
+                            })
+                            .unwrap();
+                        return;
+                    }
+                };
+
+                let format = TABLE_FORMATS
+                    .get(widgets.format_row.selected() as usize)
+                    .map_or(TableFormat::Auto, |(_, format)| *format);
+                let mut ev = match Table::parse(&text, format) {
+                    Ok(table) => table.to_evidence(),
+                    Err(e) => {
+                        sender
+                            .output(AddEvidenceOutput::Error {
+                                title: lang::lookup("add-evidence-table-failed"),
+                                message: lang::lookup_with_args(
+                                    "add-evidence-table-failed-message",
+                                    &lang_args!("error", e.to_string()),
+                                ),
+                            })
+                            .unwrap();
+                        return;
+                    }
+                };
+                let caption_text = widgets.caption_entry.text().to_string();
+                if !caption_text.trim().is_empty() {
+                    ev.set_caption(Some(caption_text.trim().to_string()));
+                }
+                let _ = sender.output(AddEvidenceOutput::AddEvidence(ev));
+                root.close();
+            }
+            AddEvidenceInput::_SelectFile => {
+                // Open file selector
+                let dialog = gtk::FileDialog::builder()
+                    .modal(true)
+                    .title(lang::lookup("header-open"))
+                    .build();
+
+                let sender_c = sender.clone();
+                dialog.open(
+                    Some(&root.toplevel_window().unwrap()),
+                    Some(&relm4::gtk::gio::Cancellable::new()),
+                    move |res| {
+                        if let Ok(file) = res {
+                            let path = file.path().unwrap();
+                            sender_c.input(AddEvidenceInput::_FileSelected(path));
+                        }
+                    },
+                );
+            }
+            AddEvidenceInput::_FileSelected(path) => {
+                widgets.file_row.set_text(path.to_str().unwrap_or_default());
+            }
+        }
+        self.update_view(widgets, sender);
+    }
+}
+
+#[derive(Debug)]
+pub enum AddVideoEvidenceInput {
+    Present(ApplicationWindow),
+    _AddEvidence,
+    _SelectFile,
+    _FileSelected(PathBuf),
+    _SelectPoster,
+    _PosterSelected(PathBuf),
+}
+
+pub struct AddVideoEvidenceDialogModel {
+    package: Arc<RwLock<EvidencePackage>>,
+}
+
+impl AddVideoEvidenceDialogModel {
+    /// Read a file and add it to the package as media, returning its hash.
+    fn add_media<F>(&self, path: &str, is_valid: F) -> Result<String, String>
+    where
+        F: FnOnce(&MediaFile) -> bool,
+    {
+        let data = std::fs::read(path).map_err(|e| e.to_string())?;
+        let mut media = MediaFile::from(data);
+        media.set_original_filename(
+            PathBuf::from(path)
+                .file_name()
+                .map(|s| s.to_string_lossy().to_string()),
+        );
+        if !is_valid(&media) {
+            return Err(lang::lookup("add-evidence-video-invalid"));
+        }
+        let hash = media.hash();
+        self.package
+            .write()
+            .add_media(media)
+            .map_err(|e| e.to_string())?;
+        Ok(hash)
+    }
+}
+
+#[relm4::component(pub)]
+impl Component for AddVideoEvidenceDialogModel {
+    type Input = AddVideoEvidenceInput;
+    type Output = AddEvidenceOutput;
+    type CommandOutput = ();
+    type Init = Arc<RwLock<EvidencePackage>>;
+
+    view! {
+        #[root]
+        adw::Dialog {
+            connect_closed[sender] => move |_dlg| {
+                let _ = sender.output(AddEvidenceOutput::Closed);
+            },
+
+            #[wrap(Some)]
+            set_child = &adw::ToolbarView {
+                add_top_bar = &adw::HeaderBar {
+                    #[wrap(Some)]
+                    set_title_widget = &adw::WindowTitle {
+                        set_title: &lang::lookup("add-evidence-title"),
+                    }
+                },
+                set_width_request: 400,
+
+                gtk::Box {
+                    set_orientation: gtk::Orientation::Vertical,
+                    set_spacing: 8,
+                    set_margin_all: 16,
+
+                    adw::PreferencesGroup {
+                        #[name = "file_row"]
+                        adw::EntryRow {
+                            set_title: &lang::lookup("add-evidence-video-label"),
+                            add_suffix = &gtk::Button {
+                                set_icon_name: relm4_icons::icon_names::FOLDER_OPEN_FILLED,
+                                set_tooltip: &lang::lookup("select"),
+                                add_css_class: "flat",
+                                connect_clicked => AddVideoEvidenceInput::_SelectFile,
+                            },
+                            connect_entry_activated => AddVideoEvidenceInput::_AddEvidence,
+                        },
+                        #[name = "poster_row"]
+                        adw::EntryRow {
+                            set_title: &lang::lookup("add-evidence-video-poster-label"),
+                            add_suffix = &gtk::Button {
+                                set_icon_name: relm4_icons::icon_names::FOLDER_OPEN_FILLED,
+                                set_tooltip: &lang::lookup("select"),
+                                add_css_class: "flat",
+                                connect_clicked => AddVideoEvidenceInput::_SelectPoster,
+                            },
+                            connect_entry_activated => AddVideoEvidenceInput::_AddEvidence,
+                        },
+                        #[name = "caption_entry"]
+                        adw::EntryRow {
+                            set_title: &lang::lookup("add-evidence-video-caption-label"),
+                            connect_entry_activated => AddVideoEvidenceInput::_AddEvidence,
+                        },
+                    },
+                    gtk::Button {
+                        set_label: &lang::lookup("add-evidence-submit"),
+                        add_css_class: "pill",
+                        add_css_class: "suggested-action",
+                        set_halign: gtk::Align::Center,
+
+                        connect_clicked => AddVideoEvidenceInput::_AddEvidence,
+                    }
+                }
+            }
+        }
+    }
+
+    fn init(
+        init: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let model = AddVideoEvidenceDialogModel { package: init };
+        let widgets = view_output!();
+        ComponentParts { model, widgets }
+    }
+
+    fn update_with_view(
+        &mut self,
+        widgets: &mut Self::Widgets,
+        message: Self::Input,
+        sender: ComponentSender<Self>,
+        root: &Self::Root,
+    ) {
+        match message {
+            AddVideoEvidenceInput::Present(window) => {
+                root.present(Some(&window));
+            }
+            AddVideoEvidenceInput::_AddEvidence => {
+                let path = widgets.file_row.text().to_string();
+                let poster_path = widgets.poster_row.text().to_string();
+                let hashes = self
+                    .add_media(&path, MediaFile::is_supported_video)
+                    .and_then(|hash| {
+                        if poster_path.trim().is_empty() {
+                            return Ok((hash, None));
+                        }
+                        let poster_hash = self.add_media(&poster_path, |media| {
+                            ["image/png", "image/jpeg"].contains(&media.media_type())
+                        })?;
+                        Ok((hash, Some(poster_hash)))
+                    });
+                let (hash, poster_hash) = match hashes {
+                    Ok(hashes) => hashes,
+                    Err(e) => {
+                        sender
+                            .output(AddEvidenceOutput::Error {
+                                title: lang::lookup("add-evidence-video-failed"),
+                                message: lang::lookup_with_args(
+                                    "add-evidence-video-failed-message",
+                                    &lang_args!("error", e),
+                                ),
+                            })
+                            .unwrap();
+                        return;
+                    }
+                };
+
+                let mut ev = Evidence::new(EvidenceKind::Video, EvidenceData::Media { hash });
+                ev.set_poster(poster_hash);
+                let caption_text = widgets.caption_entry.text().to_string();
+                if !caption_text.trim().is_empty() {
+                    ev.set_caption(Some(caption_text.trim().to_string()));
+                }
+                let _ = sender.output(AddEvidenceOutput::AddEvidence(ev));
+                root.close();
+            }
+            AddVideoEvidenceInput::_SelectFile | AddVideoEvidenceInput::_SelectPoster => {
+                let is_poster = matches!(message, AddVideoEvidenceInput::_SelectPoster);
+                // Open file selector
+                let dialog = gtk::FileDialog::builder()
+                    .modal(true)
+                    .title(lang::lookup("header-open"))
+                    .filters(&filter::filter_list(vec![if is_poster {
+                        filter::images()
+                    } else {
+                        filter::videos()
+                    }]))
+                    .build();
+
+                let sender_c = sender.clone();
+                dialog.open(
+                    Some(&root.toplevel_window().unwrap()),
+                    Some(&relm4::gtk::gio::Cancellable::new()),
+                    move |res| {
+                        if let Ok(file) = res {
+                            let path = file.path().unwrap();
+                            sender_c.input(if is_poster {
+                                AddVideoEvidenceInput::_PosterSelected(path)
+                            } else {
+                                AddVideoEvidenceInput::_FileSelected(path)
+                            });
+                        }
+                    },
+                );
+            }
+            AddVideoEvidenceInput::_FileSelected(path) => {
+                widgets.file_row.set_text(path.to_str().unwrap_or_default());
+            }
+            AddVideoEvidenceInput::_PosterSelected(path) => {
+                widgets
+                    .poster_row
+                    .set_text(path.to_str().unwrap_or_default());
+            }
+        }
+        self.update_view(widgets, sender);
+    }
+}
+
+/// The formats diffed content can be read as, in the order they are listed.
+const DIFF_FORMATS: &[(&str, DiffFormat)] = &[
+    ("add-evidence-diff-format-auto", DiffFormat::Auto),
+    ("add-evidence-diff-format-text", DiffFormat::Text),
+    ("add-evidence-diff-format-json", DiffFormat::Json),
+];
+
+#[derive(Debug)]
+pub enum AddDiffEvidenceInput {
+    Present(ApplicationWindow),
+    _AddEvidence,
+    _SelectExpected,
+    _ExpectedSelected(PathBuf),
+    _SelectActual,
+    _ActualSelected(PathBuf),
+}
+
+pub struct AddDiffEvidenceDialogModel {}
+
+#[relm4::component(pub)]
+impl Component for AddDiffEvidenceDialogModel {
+    type Input = AddDiffEvidenceInput;
+    type Output = AddEvidenceOutput;
+    type CommandOutput = ();
+    type Init = ();
+
+    view! {
+        #[root]
+        adw::Dialog {
+            connect_closed[sender] => move |_dlg| {
+                let _ = sender.output(AddEvidenceOutput::Closed);
+            },
+
+            #[wrap(Some)]
+            set_child = &adw::ToolbarView {
+                add_top_bar = &adw::HeaderBar {
+                    #[wrap(Some)]
+                    set_title_widget = &adw::WindowTitle {
+                        set_title: &lang::lookup("add-evidence-title"),
+                    }
+                },
+                set_width_request: 400,
+
+                gtk::Box {
+                    set_orientation: gtk::Orientation::Vertical,
+                    set_spacing: 8,
+                    set_margin_all: 16,
+
+                    adw::PreferencesGroup {
+                        #[name = "expected_row"]
+                        adw::EntryRow {
+                            set_title: &lang::lookup("add-evidence-diff-expected-label"),
+                            add_suffix = &gtk::Button {
+                                set_icon_name: relm4_icons::icon_names::FOLDER_OPEN_FILLED,
+                                set_tooltip: &lang::lookup("select"),
+                                add_css_class: "flat",
+                                connect_clicked => AddDiffEvidenceInput::_SelectExpected,
+                            },
+                            connect_entry_activated => AddDiffEvidenceInput::_AddEvidence,
+                        },
+                        #[name = "actual_row"]
+                        adw::EntryRow {
+                            set_title: &lang::lookup("add-evidence-diff-actual-label"),
+                            add_suffix = &gtk::Button {
+                                set_icon_name: relm4_icons::icon_names::FOLDER_OPEN_FILLED,
+                                set_tooltip: &lang::lookup("select"),
+                                add_css_class: "flat",
+                                connect_clicked => AddDiffEvidenceInput::_SelectActual,
+                            },
+                            connect_entry_activated => AddDiffEvidenceInput::_AddEvidence,
+                        },
+                        #[name = "format_row"]
+                        adw::ComboRow {
+                            set_title: &lang::lookup("add-evidence-diff-format-label"),
+                            set_model: Some(&gtk::StringList::new(&format_names)),
+                        },
+                        #[name = "caption_entry"]
+                        adw::EntryRow {
+                            set_title: &lang::lookup("add-evidence-diff-caption-label"),
+                            connect_entry_activated => AddDiffEvidenceInput::_AddEvidence,
+                        },
+                    },
+                    gtk::Button {
+                        set_label: &lang::lookup("add-evidence-submit"),
+                        add_css_class: "pill",
+                        add_css_class: "suggested-action",
+                        set_halign: gtk::Align::Center,
+
+                        connect_clicked => AddDiffEvidenceInput::_AddEvidence,
+                    }
+                }
+            }
+        }
+    }
+
+    fn init(
+        _init: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let model = AddDiffEvidenceDialogModel {};
+        let format_names = DIFF_FORMATS
+            .iter()
+            .map(|(key, _)| lang::lookup(key))
+            .collect::<Vec<_>>();
+        let format_names = format_names.iter().map(String::as_str).collect::<Vec<_>>();
+        let widgets = view_output!();
+        ComponentParts { model, widgets }
+    }
+
+    fn update_with_view(
+        &mut self,
+        widgets: &mut Self::Widgets,
+        message: Self::Input,
+        sender: ComponentSender<Self>,
+        root: &Self::Root,
+    ) {
+        match message {
+            AddDiffEvidenceInput::Present(window) => {
+                root.present(Some(&window));
+            }
+            AddDiffEvidenceInput::_AddEvidence => {
+                let format = DIFF_FORMATS
+                    .get(widgets.format_row.selected() as usize)
+                    .map_or(DiffFormat::Auto, |(_, format)| *format);
+                let read = |path: String| {
+                    std::fs::read(path)
+                        .map(|data| String::from_utf8_lossy(&data).to_string())
+                        .map_err(|e| e.to_string())
+                };
+                let diff = read(widgets.expected_row.text().to_string()).and_then(|expected| {
+                    let actual = read(widgets.actual_row.text().to_string())?;
+                    Diff::parse(&expected, &actual, format).map_err(|e| e.to_string())
+                });
+                let mut ev = match diff {
+                    Ok(diff) => diff.to_evidence(),
+                    Err(e) => {
+                        sender
+                            .output(AddEvidenceOutput::Error {
+                                title: lang::lookup("add-evidence-diff-failed"),
+                                message: lang::lookup_with_args(
+                                    "add-evidence-diff-failed-message",
+                                    &lang_args!("error", e),
+                                ),
+                            })
+                            .unwrap();
+                        return;
+                    }
+                };
+                let caption_text = widgets.caption_entry.text().to_string();
+                if !caption_text.trim().is_empty() {
+                    ev.set_caption(Some(caption_text.trim().to_string()));
+                }
+                let _ = sender.output(AddEvidenceOutput::AddEvidence(ev));
+                root.close();
+            }
+            AddDiffEvidenceInput::_SelectExpected | AddDiffEvidenceInput::_SelectActual => {
+                let is_expected = matches!(message, AddDiffEvidenceInput::_SelectExpected);
+                // Open file selector
+                let dialog = gtk::FileDialog::builder()
+                    .modal(true)
+                    .title(lang::lookup("header-open"))
+                    .build();
+
+                let sender_c = sender.clone();
+                dialog.open(
+                    Some(&root.toplevel_window().unwrap()),
+                    Some(&relm4::gtk::gio::Cancellable::new()),
+                    move |res| {
+                        if let Ok(file) = res {
+                            let path = file.path().unwrap();
+                            sender_c.input(if is_expected {
+                                AddDiffEvidenceInput::_ExpectedSelected(path)
+                            } else {
+                                AddDiffEvidenceInput::_ActualSelected(path)
+                            });
+                        }
+                    },
+                );
+            }
+            AddDiffEvidenceInput::_ExpectedSelected(path) => {
+                widgets
+                    .expected_row
+                    .set_text(path.to_str().unwrap_or_default());
+            }
+            AddDiffEvidenceInput::_ActualSelected(path) => {
+                widgets
+                    .actual_row
+                    .set_text(path.to_str().unwrap_or_default());
+            }
+        }
+        self.update_view(widgets, sender);
+    }
+}
+
+#[derive(Debug)]
+pub enum AddCommandEvidenceInput {
+    Present(ApplicationWindow),
+    _AddEvidence,
+}
+
+pub struct AddCommandEvidenceDialogModel {}
+
+#[relm4::component(pub)]
+impl Component for AddCommandEvidenceDialogModel {
+    type Input = AddCommandEvidenceInput;
+    type Output = AddEvidenceOutput;
+    type CommandOutput = ();
+    type Init = ();
+
+    view! {
+        #[root]
+        adw::Dialog {
+            connect_closed[sender] => move |_dlg| {
+                let _ = sender.output(AddEvidenceOutput::Closed);
+            },
+
+            #[wrap(Some)]
+            set_child = &adw::ToolbarView {
+                add_top_bar = &adw::HeaderBar {
+                    #[wrap(Some)]
+                    set_title_widget = &adw::WindowTitle {
+                        set_title: &lang::lookup("add-evidence-title"),
+                    }
+                },
+                set_width_request: 500,
+
+                gtk::Box {
+                    set_orientation: gtk::Orientation::Vertical,
+                    set_spacing: 8,
+                    set_margin_all: 16,
+
+                    adw::PreferencesGroup {
+                        #[name = "command_row"]
+                        adw::EntryRow {
+                            set_title: &lang::lookup("add-evidence-command-label"),
+                            add_css_class: "monospace",
+                        },
+                        #[name = "directory_row"]
+                        adw::EntryRow {
+                            set_title: &lang::lookup("add-evidence-command-directory-label"),
+                        },
+                        #[name = "exit_code_row"]
+                        adw::EntryRow {
+                            set_title: &lang::lookup("add-evidence-command-exit-code-label"),
+                            set_input_purpose: gtk::InputPurpose::Number,
+                        },
+                        #[name = "caption_entry"]
+                        adw::EntryRow {
+                            set_title: &lang::lookup("add-evidence-command-caption-label"),
+                        },
+                    },
+                    gtk::Label {
+                        set_label: &lang::lookup("add-evidence-command-stdout-label"),
+                        add_css_class: "heading",
+                        set_xalign: 0.0,
+                    },
+                    gtk::ScrolledWindow {
+                        set_height_request: 120,
+                        add_css_class: "card",
+
+                        #[name = "stdout_view"]
+                        gtk::TextView {
+                            set_monospace: true,
+                            set_top_margin: 4,
+                            set_bottom_margin: 4,
+                            set_left_margin: 4,
+                            set_right_margin: 4,
+                        },
+                    },
+                    gtk::Label {
+                        set_label: &lang::lookup("add-evidence-command-stderr-label"),
+                        add_css_class: "heading",
+                        set_xalign: 0.0,
+                    },
+                    gtk::ScrolledWindow {
+                        set_height_request: 80,
+                        add_css_class: "card",
+
+                        #[name = "stderr_view"]
+                        gtk::TextView {
+                            set_monospace: true,
+                            set_top_margin: 4,
+                            set_bottom_margin: 4,
+                            set_left_margin: 4,
+                            set_right_margin: 4,
+                        },
+                    },
+                    gtk::Button {
+                        set_label: &lang::lookup("add-evidence-submit"),
+                        add_css_class: "pill",
+                        add_css_class: "suggested-action",
+                        set_halign: gtk::Align::Center,
+
+                        connect_clicked => AddCommandEvidenceInput::_AddEvidence,
+                    }
+                }
+            }
+        }
+    }
+
+    fn init(
+        _init: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let model = AddCommandEvidenceDialogModel {};
+        let widgets = view_output!();
+        ComponentParts { model, widgets }
+    }
+
+    fn update_with_view(
+        &mut self,
+        widgets: &mut Self::Widgets,
+        message: Self::Input,
+        sender: ComponentSender<Self>,
+        root: &Self::Root,
+    ) {
+        match message {
+            AddCommandEvidenceInput::Present(window) => {
+                root.present(Some(&window));
+            }
+            AddCommandEvidenceInput::_AddEvidence => {
+                let exit_code_text = widgets.exit_code_row.text().trim().to_string();
+                let exit_code = if exit_code_text.is_empty() {
+                    None
+                } else if let Ok(exit_code) = exit_code_text.parse() {
+                    Some(exit_code)
+                } else {
+                    sender
+                        .output(AddEvidenceOutput::Error {
+                            title: lang::lookup("add-evidence-command-failed"),
+                            message: lang::lookup("add-evidence-command-invalid-exit-code"),
+                        })
+                        .unwrap();
+                    return;
+                };
+
+                let text_of = |view: &gtk::TextView| {
+                    let buffer = view.buffer();
+                    buffer
+                        .text(&buffer.start_iter(), &buffer.end_iter(), false)
+                        .to_string()
+                };
+                let mut command =
+                    CommandExecution::new(widgets.command_row.text().trim().to_string());
+                let directory = widgets.directory_row.text().trim().to_string();
+                if !directory.is_empty() {
+                    command.set_working_directory(Some(directory));
+                }
+                command.set_exit_code(exit_code);
+                command.set_stdout(text_of(&widgets.stdout_view));
+                command.set_stderr(text_of(&widgets.stderr_view));
+
+                let mut ev = command.to_evidence();
+                let caption_text = widgets.caption_entry.text().to_string();
+                if !caption_text.trim().is_empty() {
+                    ev.set_caption(Some(caption_text.trim().to_string()));
+                }
+                let _ = sender.output(AddEvidenceOutput::AddEvidence(ev));
+                root.close();
+            }
+        }
+        self.update_view(widgets, sender);
+    }
+}
+
+pub struct AddSqlEvidenceDialogModel {}
+
+#[relm4::component(pub)]
+impl Component for AddSqlEvidenceDialogModel {
+    type Input = AddEvidenceInput;
+    type Output = AddEvidenceOutput;
+    type CommandOutput = ();
+    type Init = ();
+
+    view! {
+        #[root]
+        adw::Dialog {
+            connect_closed[sender] => move |_dlg| {
+                let _ = sender.output(AddEvidenceOutput::Closed);
+            },
+
+            #[wrap(Some)]
+            set_child = &adw::ToolbarView {
+                add_top_bar = &adw::HeaderBar {
+                    #[wrap(Some)]
+                    set_title_widget = &adw::WindowTitle {
+                        set_title: &lang::lookup("add-evidence-title"),
+                    }
+                },
+                set_width_request: 500,
+
+                gtk::Box {
+                    set_orientation: gtk::Orientation::Vertical,
+                    set_spacing: 8,
+                    set_margin_all: 16,
+
+                    gtk::Label {
+                        set_label: &lang::lookup("add-evidence-sql-query-label"),
+                        add_css_class: "heading",
+                        set_xalign: 0.0,
+                    },
+                    gtk::ScrolledWindow {
+                        set_height_request: 120,
+                        add_css_class: "card",
+
+                        #[name = "query_view"]
+                        gtk::TextView {
+                            set_monospace: true,
+                            set_top_margin: 4,
+                            set_bottom_margin: 4,
+                            set_left_margin: 4,
+                            set_right_margin: 4,
+                        },
+                    },
+                    adw::PreferencesGroup {
+                        #[name = "database_row"]
+                        adw::EntryRow {
+                            set_title: &lang::lookup("add-evidence-sql-database-label"),
+                        },
+                        #[name = "file_row"]
+                        adw::EntryRow {
+                            set_title: &lang::lookup("add-evidence-sql-result-label"),
+                            add_suffix = &gtk::Button {
+                                set_icon_name: relm4_icons::icon_names::FOLDER_OPEN_FILLED,
+                                set_tooltip: &lang::lookup("select"),
+                                add_css_class: "flat",
+                                connect_clicked => AddEvidenceInput::_SelectFile,
+                            },
+                        },
+                        #[name = "format_row"]
+                        adw::ComboRow {
+                            set_title: &lang::lookup("add-evidence-table-format-label"),
+                            set_model: Some(&gtk::StringList::new(&format_names)),
+                        },
+                        #[name = "row_count_row"]
+                        adw::EntryRow {
+                            set_title: &lang::lookup("add-evidence-sql-row-count-label"),
+                            set_input_purpose: gtk::InputPurpose::Digits,
+                        },
+                        #[name = "duration_row"]
+                        adw::EntryRow {
+                            set_title: &lang::lookup("add-evidence-sql-duration-label"),
+                            set_input_purpose: gtk::InputPurpose::Digits,
+                        },
+                        #[name = "caption_entry"]
+                        adw::EntryRow {
+                            set_title: &lang::lookup("add-evidence-sql-caption-label"),
+                            connect_entry_activated => AddEvidenceInput::_AddEvidence,
+                        },
+                    },
+                    gtk::Button {
+                        set_label: &lang::lookup("add-evidence-submit"),
+                        add_css_class: "pill",
+                        add_css_class: "suggested-action",
+                        set_halign: gtk::Align::Center,
+
+                        connect_clicked => AddEvidenceInput::_AddEvidence,
+                    }
+                }
+            }
+        }
+    }
+
+    fn init(
+        _init: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let model = AddSqlEvidenceDialogModel {};
+        let format_names = TABLE_FORMATS
+            .iter()
+            .map(|(key, _)| lang::lookup(key))
+            .collect::<Vec<_>>();
+        let format_names = format_names.iter().map(String::as_str).collect::<Vec<_>>();
+        let widgets = view_output!();
+        ComponentParts { model, widgets }
+    }
+
+    fn update_with_view(
+        &mut self,
+        widgets: &mut Self::Widgets,
+        message: Self::Input,
+        sender: ComponentSender<Self>,
+        root: &Self::Root,
+    ) {
+        match message {
+            AddEvidenceInput::Present(window) => {
+                root.present(Some(&window));
+            }
+            AddEvidenceInput::_AddEvidence => {
+                /// Read an optional whole number from an entry, or `Err` if it isn't valid
+                fn optional_number(row: &adw::EntryRow) -> Result<Option<u64>, ()> {
+                    let text = row.text().trim().to_string();
+                    if text.is_empty() {
+                        Ok(None)
+                    } else {
+                        text.parse().map(Some).map_err(|_| ())
+                    }
+                }
+                let fail = |message: String| {
+                    sender
+                        .output(AddEvidenceOutput::Error {
+                            title: lang::lookup("add-evidence-sql-failed"),
+                            message,
+                        })
+                        .unwrap();
+                };
+                let (Ok(row_count), Ok(duration_ms)) = (
+                    optional_number(&widgets.row_count_row),
+                    optional_number(&widgets.duration_row),
+                ) else {
+                    fail(lang::lookup("add-evidence-sql-invalid-number"));
+                    return;
+                };
+
+                let buffer = widgets.query_view.buffer();
+                let mut query = SqlQuery::new(
+                    buffer
+                        .text(&buffer.start_iter(), &buffer.end_iter(), false)
+                        .to_string(),
+                );
+                let database = widgets.database_row.text().trim().to_string();
+                if !database.is_empty() {
+                    query.set_database(Some(database));
+                }
+                query.set_row_count(row_count);
+                query.set_duration(duration_ms.map(std::time::Duration::from_millis));
+
+                let path = widgets.file_row.text().to_string();
+                if !path.trim().is_empty() {
+                    let format = TABLE_FORMATS
+                        .get(widgets.format_row.selected() as usize)
+                        .map_or(TableFormat::Auto, |(_, format)| *format);
+                    let table = std::fs::read(&path)
+                        .map_err(|e| e.to_string())
+                        .and_then(|data| {
+                            Table::parse(&String::from_utf8_lossy(&data), format)
+                                .map_err(|e| e.to_string())
+                        });
+                    match table {
+                        Ok(table) => {
+                            query.set_result(table);
+                        }
+                        Err(e) => {
+                            fail(lang::lookup_with_args(
+                                "add-evidence-table-failed-message",
+                                &lang_args!("error", e),
+                            ));
+                            return;
+                        }
+                    }
+                }
+
+                let mut ev = query.to_evidence();
+                let caption_text = widgets.caption_entry.text().to_string();
+                if !caption_text.trim().is_empty() {
+                    ev.set_caption(Some(caption_text.trim().to_string()));
+                }
+                let _ = sender.output(AddEvidenceOutput::AddEvidence(ev));
+                root.close();
+            }
+            AddEvidenceInput::_SelectFile => {
+                // Open file selector
+                let dialog = gtk::FileDialog::builder()
+                    .modal(true)
+                    .title(lang::lookup("header-open"))
+                    .build();
+
+                let sender_c = sender.clone();
+                dialog.open(
+                    Some(&root.toplevel_window().unwrap()),
+                    Some(&relm4::gtk::gio::Cancellable::new()),
+                    move |res| {
+                        if let Ok(file) = res {
+                            let path = file.path().unwrap();
+                            sender_c.input(AddEvidenceInput::_FileSelected(path));
+                        }
+                    },
+                );
+            }
+            AddEvidenceInput::_FileSelected(path) => {
+                widgets.file_row.set_text(path.to_str().unwrap_or_default());
+            }
+        }
+        self.update_view(widgets, sender);
+    }
+}
+
+/// The types of system a link can refer to, in the order they are listed.
+const LINK_SYSTEMS: &[(&str, Option<LinkSystem>)] = &[
+    ("add-evidence-link-system-none", None),
+    ("evidence-link-system-defect", Some(LinkSystem::Defect)),
+    ("evidence-link-system-ticket", Some(LinkSystem::Ticket)),
+    ("evidence-link-system-build", Some(LinkSystem::Build)),
+    (
+        "evidence-link-system-documentation",
+        Some(LinkSystem::Documentation),
+    ),
+    ("evidence-link-system-other", Some(LinkSystem::Other)),
+];
+
+pub struct AddLinkEvidenceDialogModel {}
+
+#[relm4::component(pub)]
+impl Component for AddLinkEvidenceDialogModel {
+    type Input = AddEvidenceInput;
+    type Output = AddEvidenceOutput;
+    type CommandOutput = ();
+    type Init = ();
+
+    view! {
+        #[root]
+        adw::Dialog {
+            connect_closed[sender] => move |_dlg| {
+                let _ = sender.output(AddEvidenceOutput::Closed);
+            },
+
+            #[wrap(Some)]
+            set_child = &adw::ToolbarView {
+                add_top_bar = &adw::HeaderBar {
+                    #[wrap(Some)]
+                    set_title_widget = &adw::WindowTitle {
+                        set_title: &lang::lookup("add-evidence-title"),
+                    }
+                },
+                set_width_request: 400,
+
+                gtk::Box {
+                    set_orientation: gtk::Orientation::Vertical,
+                    set_spacing: 8,
+                    set_margin_all: 16,
+
+                    adw::PreferencesGroup {
+                        #[name = "url_row"]
+                        adw::EntryRow {
+                            set_title: &lang::lookup("add-evidence-link-url-label"),
+                            set_input_purpose: gtk::InputPurpose::Url,
+                            connect_entry_activated => AddEvidenceInput::_AddEvidence,
+                        },
+                        #[name = "title_row"]
+                        adw::EntryRow {
+                            set_title: &lang::lookup("add-evidence-link-title-label"),
+                            connect_entry_activated => AddEvidenceInput::_AddEvidence,
+                        },
+                        #[name = "system_row"]
+                        adw::ComboRow {
+                            set_title: &lang::lookup("add-evidence-link-system-label"),
+                            set_model: Some(&gtk::StringList::new(&system_names)),
+                        },
+                        #[name = "caption_entry"]
+                        adw::EntryRow {
+                            set_title: &lang::lookup("add-evidence-link-caption-label"),
+                            connect_entry_activated => AddEvidenceInput::_AddEvidence,
+                        },
+                    },
+                    gtk::Button {
+                        set_label: &lang::lookup("add-evidence-submit"),
+                        add_css_class: "pill",
+                        add_css_class: "suggested-action",
+                        set_halign: gtk::Align::Center,
+
+                        connect_clicked => AddEvidenceInput::_AddEvidence,
+                    }
+                }
+            }
+        }
+    }
+
+    fn init(
+        _init: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let model = AddLinkEvidenceDialogModel {};
+        let system_names = LINK_SYSTEMS
+            .iter()
+            .map(|(key, _)| lang::lookup(key))
+            .collect::<Vec<_>>();
+        let system_names = system_names.iter().map(String::as_str).collect::<Vec<_>>();
+        let widgets = view_output!();
+        ComponentParts { model, widgets }
+    }
+
+    fn update_with_view(
+        &mut self,
+        widgets: &mut Self::Widgets,
+        message: Self::Input,
+        sender: ComponentSender<Self>,
+        root: &Self::Root,
+    ) {
+        match message {
+            AddEvidenceInput::Present(window) => {
+                root.present(Some(&window));
+            }
+            AddEvidenceInput::_AddEvidence => {
+                let mut link = match Link::new(widgets.url_row.text().to_string()) {
+                    Ok(link) => link,
+                    Err(e) => {
+                        sender
+                            .output(AddEvidenceOutput::Error {
+                                title: lang::lookup("add-evidence-link-failed"),
+                                message: lang::lookup_with_args(
+                                    "add-evidence-link-failed-message",
+                                    &lang_args!("error", e.to_string()),
+                                ),
+                            })
+                            .unwrap();
+                        return;
+                    }
+                };
+                let title = widgets.title_row.text().trim().to_string();
+                if !title.is_empty() {
+                    link.set_title(Some(title));
+                }
+                link.set_system(
+                    LINK_SYSTEMS
+                        .get(widgets.system_row.selected() as usize)
+                        .and_then(|(_, system)| *system),
+                );
+
+                let mut ev = link.to_evidence();
+                let caption_text = widgets.caption_entry.text().to_string();
+                if !caption_text.trim().is_empty() {
+                    ev.set_caption(Some(caption_text.trim().to_string()));
+                }
+                let _ = sender.output(AddEvidenceOutput::AddEvidence(ev));
+                root.close();
+            }
+            AddEvidenceInput::_SelectFile | AddEvidenceInput::_FileSelected(_) => (),
+        }
+        self.update_view(widgets, sender);
+    }
+}
+
+#[derive(Debug)]
+pub enum AddHarEvidenceInput {
+    Present(ApplicationWindow),
+    _AddEvidence,
+    _SelectFile,
+    _FileSelected(PathBuf),
+    _LoadFile,
+    _FilterChanged,
+}
+
+pub struct AddHarEvidenceDialogModel {
+    /// The exchanges read from the HAR file, with the row showing each.
+    entries: Vec<(HarEntry, adw::ActionRow, gtk::CheckButton)>,
+}
+
+#[relm4::component(pub)]
+impl Component for AddHarEvidenceDialogModel {
+    type Input = AddHarEvidenceInput;
+    type Output = AddEvidenceOutput;
+    type CommandOutput = ();
+    type Init = ();
+
+    view! {
+        #[root]
+        adw::Dialog {
+            connect_closed[sender] => move |_dlg| {
+                let _ = sender.output(AddEvidenceOutput::Closed);
+            },
+
+            #[wrap(Some)]
+            set_child = &adw::ToolbarView {
+                add_top_bar = &adw::HeaderBar {
+                    #[wrap(Some)]
+                    set_title_widget = &adw::WindowTitle {
+                        set_title: &lang::lookup("add-evidence-title"),
+                    }
+                },
+                set_width_request: 600,
+
+                gtk::Box {
+                    set_orientation: gtk::Orientation::Vertical,
+                    set_spacing: 8,
+                    set_margin_all: 16,
+
+                    adw::PreferencesGroup {
+                        #[name = "file_row"]
+                        adw::EntryRow {
+                            set_title: &lang::lookup("add-evidence-har-label"),
+                            add_suffix = &gtk::Button {
+                                set_icon_name: relm4_icons::icon_names::FOLDER_OPEN_FILLED,
+                                set_tooltip: &lang::lookup("select"),
+                                add_css_class: "flat",
+                                connect_clicked => AddHarEvidenceInput::_SelectFile,
+                            },
+                            connect_entry_activated => AddHarEvidenceInput::_LoadFile,
+                        },
+                        #[name = "filter_row"]
+                        adw::EntryRow {
+                            set_title: &lang::lookup("add-evidence-har-filter-label"),
+                            connect_changed => AddHarEvidenceInput::_FilterChanged,
+                        },
+                    },
+                    gtk::ScrolledWindow {
+                        set_propagate_natural_height: true,
+                        set_max_content_height: 400,
+                        set_hscrollbar_policy: gtk::PolicyType::Never,
+
+                        #[name = "entry_list"]
+                        gtk::ListBox {
+                            add_css_class: "boxed-list",
+                            set_selection_mode: gtk::SelectionMode::None,
+                            #[wrap(Some)]
+                            set_placeholder = &gtk::Label {
+                                add_css_class: "dim-label",
+                                set_wrap: true,
+                                set_margin_all: 16,
+                                set_label: &lang::lookup("add-evidence-har-empty"),
+                            },
+                        },
+                    },
+                    gtk::Button {
+                        set_label: &lang::lookup("add-evidence-submit"),
+                        add_css_class: "pill",
+                        add_css_class: "suggested-action",
+                        set_halign: gtk::Align::Center,
+
+                        connect_clicked => AddHarEvidenceInput::_AddEvidence,
+                    }
+                }
+            }
+        }
+    }
+
+    fn init(
+        _init: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let model = AddHarEvidenceDialogModel { entries: vec![] };
+        let widgets = view_output!();
+        ComponentParts { model, widgets }
+    }
+
+    fn update_with_view(
+        &mut self,
+        widgets: &mut Self::Widgets,
+        message: Self::Input,
+        sender: ComponentSender<Self>,
+        root: &Self::Root,
+    ) {
+        match message {
+            AddHarEvidenceInput::Present(window) => {
+                root.present(Some(&window));
+            }
+            AddHarEvidenceInput::_AddEvidence => {
+                let selected = self
+                    .entries
+                    .iter()
+                    .filter(|(_, row, check)| row.is_visible() && check.is_active())
+                    .map(|(entry, _, _)| entry.to_evidence())
+                    .collect::<Vec<_>>();
+                if selected.is_empty() {
+                    sender
+                        .output(AddEvidenceOutput::Error {
+                            title: lang::lookup("add-evidence-har-failed"),
+                            message: lang::lookup("add-evidence-har-none-selected"),
+                        })
+                        .unwrap();
+                    return;
+                }
+                for ev in selected {
+                    let _ = sender.output(AddEvidenceOutput::AddEvidence(ev));
+                }
+                root.close();
+            }
+            AddHarEvidenceInput::_SelectFile => {
+                // Open file selector
+                let dialog = gtk::FileDialog::builder()
+                    .modal(true)
+                    .title(lang::lookup("header-open"))
+                    .build();
+
+                let sender_c = sender.clone();
+                dialog.open(
+                    Some(&root.toplevel_window().unwrap()),
+                    Some(&relm4::gtk::gio::Cancellable::new()),
+                    move |res| {
+                        if let Ok(file) = res {
+                            let path = file.path().unwrap();
+                            sender_c.input(AddHarEvidenceInput::_FileSelected(path));
+                        }
+                    },
+                );
+            }
+            AddHarEvidenceInput::_FileSelected(path) => {
+                widgets.file_row.set_text(path.to_str().unwrap_or_default());
+                sender.input(AddHarEvidenceInput::_LoadFile);
+            }
+            AddHarEvidenceInput::_LoadFile => {
+                let path = widgets.file_row.text().to_string();
+                let entries = std::fs::read(&path)
+                    .map_err(|e| e.to_string())
+                    .and_then(|data| {
+                        parse_har(&String::from_utf8_lossy(&data)).map_err(|e| e.to_string())
+                    });
+                let entries = match entries {
+                    Ok(entries) => entries,
+                    Err(e) => {
+                        sender
+                            .output(AddEvidenceOutput::Error {
+                                title: lang::lookup("add-evidence-har-failed"),
+                                message: lang::lookup_with_args(
+                                    "add-evidence-har-failed-message",
+                                    &lang_args!("error", e),
+                                ),
+                            })
+                            .unwrap();
+                        return;
+                    }
+                };
+
+                widgets.entry_list.remove_all();
+                self.entries = entries
+                    .into_iter()
+                    .map(|entry| {
+                        let check = gtk::CheckButton::new();
+                        check.set_active(true);
+                        let row = adw::ActionRow::new();
+                        row.set_use_markup(false);
+                        row.set_title(&format!(
+                            "{} {}",
+                            entry.exchange().request().method(),
+                            entry.url()
+                        ));
+                        row.set_subtitle(&entry.summary());
+                        row.add_prefix(&check);
+                        row.set_activatable_widget(Some(&check));
+                        widgets.entry_list.append(&row);
+                        (entry, row, check)
+                    })
+                    .collect();
+                sender.input(AddHarEvidenceInput::_FilterChanged);
+            }
+            AddHarEvidenceInput::_FilterChanged => {
+                let filter = widgets.filter_row.text().to_lowercase();
+                for (entry, row, _) in &self.entries {
+                    row.set_visible(entry.url().to_lowercase().contains(&filter));
+                }
+            }
+        }
+        self.update_view(widgets, sender);
+    }
+}
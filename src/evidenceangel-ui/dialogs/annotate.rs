@@ -0,0 +1,310 @@
+use std::sync::Arc;
+
+use adw::prelude::*;
+use evidenceangel::{
+    Annotation, AnnotationColor, Evidence, EvidencePackage, RedactionStyle, render_annotations,
+};
+use parking_lot::RwLock;
+use relm4::{Component, ComponentParts, ComponentSender, RelmWidgetExt, adw, gtk};
+
+use crate::lang;
+
+/// The tools available in the annotation editor, in the order they are listed.
+const TOOLS: &[&str] = &[
+    "annotate-tool-rectangle",
+    "annotate-tool-arrow",
+    "annotate-tool-text",
+    "annotate-tool-blur",
+    "annotate-tool-black-out",
+];
+
+#[derive(Debug)]
+pub enum AnnotateInput {
+    Present(gtk::Widget),
+    _DragBegin(f64, f64),
+    _DragEnd(f64, f64),
+    _Undo,
+    _Clear,
+    _Save,
+}
+
+#[derive(Debug)]
+pub enum AnnotateOutput {
+    UpdateEvidence(Evidence),
+}
+
+pub struct AnnotateDialogInit {
+    pub evidence: Evidence,
+    pub package: Arc<RwLock<EvidencePackage>>,
+}
+
+pub struct AnnotateDialogModel {
+    package: Arc<RwLock<EvidencePackage>>,
+    evidence: Evidence,
+    /// The image data without any annotations.
+    original: Vec<u8>,
+    /// The image with the current annotations rendered over it.
+    texture: Option<gtk::gdk::Texture>,
+    /// The start of the current drag, in image coordinates.
+    drag_start: Option<(f64, f64)>,
+}
+
+impl AnnotateDialogModel {
+    fn render(&mut self) {
+        let data = if self.evidence.annotations().is_empty() {
+            Ok(self.original.clone())
+        } else {
+            render_annotations(&self.original, self.evidence.annotations())
+        };
+        self.texture = data
+            .inspect_err(|e| tracing::error!("Failed to render annotations: {e}"))
+            .ok()
+            .and_then(|data| {
+                gtk::gdk::Texture::from_bytes(&gtk::glib::Bytes::from_owned(data)).ok()
+            });
+    }
+
+    /// Convert a point on the picture widget to a point on the image, accounting for the image
+    /// being scaled to fit and centered.
+    fn to_image_coords(&self, picture: &gtk::Picture, x: f64, y: f64) -> Option<(f64, f64)> {
+        let texture = self.texture.as_ref()?;
+        let (image_width, image_height) = (f64::from(texture.width()), f64::from(texture.height()));
+        let (widget_width, widget_height) =
+            (f64::from(picture.width()), f64::from(picture.height()));
+        let scale = (widget_width / image_width).min(widget_height / image_height);
+        let offset_x = (widget_width - image_width * scale) / 2.0;
+        let offset_y = (widget_height - image_height * scale) / 2.0;
+        Some((
+            ((x - offset_x) / scale).clamp(0.0, image_width),
+            ((y - offset_y) / scale).clamp(0.0, image_height),
+        ))
+    }
+}
+
+#[relm4::component(pub)]
+impl Component for AnnotateDialogModel {
+    type Input = AnnotateInput;
+    type Output = AnnotateOutput;
+    type CommandOutput = ();
+    type Init = AnnotateDialogInit;
+
+    view! {
+        #[root]
+        adw::Dialog {
+            set_content_width: 900,
+            set_content_height: 700,
+
+            #[wrap(Some)]
+            set_child = &adw::ToolbarView {
+                add_top_bar = &adw::HeaderBar {
+                    #[wrap(Some)]
+                    set_title_widget = &adw::WindowTitle {
+                        set_title: &lang::lookup("annotate-title"),
+                    },
+                },
+
+                gtk::Box {
+                    set_orientation: gtk::Orientation::Vertical,
+                    set_spacing: 8,
+                    set_margin_all: 16,
+
+                    gtk::Box {
+                        set_orientation: gtk::Orientation::Horizontal,
+                        set_spacing: 8,
+
+                        #[name = "tool"]
+                        gtk::DropDown {
+                            set_model: Some(&gtk::StringList::new(&tool_names)),
+                        },
+                        #[name = "color"]
+                        gtk::ColorDialogButton {
+                            set_dialog: &gtk::ColorDialog::new(),
+                            set_rgba: &gtk::gdk::RGBA::new(0.878, 0.106, 0.141, 1.0),
+                        },
+                        #[name = "text"]
+                        gtk::Entry {
+                            set_placeholder_text: Some(&lang::lookup("annotate-text-placeholder")),
+                            set_hexpand: true,
+                        },
+                        gtk::Button {
+                            set_label: &lang::lookup("annotate-undo"),
+                            connect_clicked => AnnotateInput::_Undo,
+                        },
+                        gtk::Button {
+                            set_label: &lang::lookup("annotate-clear"),
+                            connect_clicked => AnnotateInput::_Clear,
+                        },
+                    },
+
+                    #[name = "picture"]
+                    gtk::Picture {
+                        #[watch]
+                        set_paintable: model.texture.as_ref(),
+                        set_content_fit: gtk::ContentFit::Contain,
+                        set_can_shrink: true,
+                        set_hexpand: true,
+                        set_vexpand: true,
+
+                        add_controller = gtk::GestureDrag {
+                            connect_drag_begin[sender] => move |_gesture, x, y| {
+                                sender.input(AnnotateInput::_DragBegin(x, y));
+                            },
+                            connect_drag_end[sender] => move |_gesture, offset_x, offset_y| {
+                                sender.input(AnnotateInput::_DragEnd(offset_x, offset_y));
+                            },
+                        },
+                    },
+
+                    adw::PreferencesGroup {
+                        #[name = "burn_in"]
+                        adw::SwitchRow {
+                            set_title: &lang::lookup("annotate-burn-in"),
+                            set_subtitle: &lang::lookup("annotate-burn-in-subtitle"),
+                        },
+                    },
+
+                    gtk::Button {
+                        set_label: &lang::lookup("annotate-save"),
+                        add_css_class: "pill",
+                        add_css_class: "suggested-action",
+                        set_halign: gtk::Align::Center,
+
+                        connect_clicked => AnnotateInput::_Save,
+                    },
+                },
+            },
+        }
+    }
+
+    fn init(
+        init: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let AnnotateDialogInit { evidence, package } = init;
+        let original = evidence
+            .value()
+            .get_data(&mut package.write())
+            .unwrap_or_default();
+        let mut model = AnnotateDialogModel {
+            package,
+            evidence,
+            original,
+            texture: None,
+            drag_start: None,
+        };
+        model.render();
+        let tool_names = TOOLS.iter().map(lang::lookup).collect::<Vec<_>>();
+        let tool_names = tool_names.iter().map(String::as_str).collect::<Vec<_>>();
+        let widgets = view_output!();
+        ComponentParts { model, widgets }
+    }
+
+    fn update_with_view(
+        &mut self,
+        widgets: &mut Self::Widgets,
+        message: Self::Input,
+        sender: ComponentSender<Self>,
+        root: &Self::Root,
+    ) {
+        match message {
+            AnnotateInput::Present(parent) => {
+                root.present(Some(&parent));
+            }
+            AnnotateInput::_DragBegin(x, y) => {
+                self.drag_start = self.to_image_coords(&widgets.picture, x, y);
+            }
+            AnnotateInput::_DragEnd(offset_x, offset_y) => {
+                let Some(texture) = self.texture.as_ref() else {
+                    return;
+                };
+                let scale = (f64::from(widgets.picture.width()) / f64::from(texture.width()))
+                    .min(f64::from(widgets.picture.height()) / f64::from(texture.height()));
+                let Some((start_x, start_y)) = self.drag_start.take() else {
+                    return;
+                };
+                let end_x = (start_x + offset_x / scale).clamp(0.0, f64::from(texture.width()));
+                let end_y = (start_y + offset_y / scale).clamp(0.0, f64::from(texture.height()));
+
+                let (x, y) = (start_x.min(end_x) as u32, start_y.min(end_y) as u32);
+                let (width, height) = (
+                    (start_x - end_x).abs() as u32,
+                    (start_y - end_y).abs() as u32,
+                );
+                let rgba = widgets.color.rgba();
+                let color = AnnotationColor::new(
+                    (rgba.red() * 255.0) as u8,
+                    (rgba.green() * 255.0) as u8,
+                    (rgba.blue() * 255.0) as u8,
+                );
+
+                let annotation = match TOOLS.get(widgets.tool.selected() as usize).copied() {
+                    Some("annotate-tool-rectangle") => Annotation::Rectangle {
+                        x,
+                        y,
+                        width,
+                        height,
+                        color,
+                    },
+                    Some("annotate-tool-arrow") => Annotation::Arrow {
+                        from_x: start_x as u32,
+                        from_y: start_y as u32,
+                        to_x: end_x as u32,
+                        to_y: end_y as u32,
+                        color,
+                    },
+                    Some("annotate-tool-text") => {
+                        let text = widgets.text.text().to_string();
+                        if text.trim().is_empty() {
+                            return;
+                        }
+                        Annotation::Text {
+                            x: end_x as u32,
+                            y: end_y as u32,
+                            text,
+                            color,
+                        }
+                    }
+                    Some("annotate-tool-blur") => Annotation::Redaction {
+                        x,
+                        y,
+                        width,
+                        height,
+                        style: RedactionStyle::Blur,
+                    },
+                    _ => Annotation::Redaction {
+                        x,
+                        y,
+                        width,
+                        height,
+                        style: RedactionStyle::BlackOut,
+                    },
+                };
+                self.evidence.annotations_mut().push(annotation);
+                self.render();
+            }
+            AnnotateInput::_Undo => {
+                self.evidence.annotations_mut().pop();
+                self.render();
+            }
+            AnnotateInput::_Clear => {
+                self.evidence.annotations_mut().clear();
+                self.render();
+            }
+            AnnotateInput::_Save => {
+                if widgets.burn_in.is_active() {
+                    let mut pkg = self.package.write();
+                    if let Err(e) = self.evidence.burn_in_redactions(&mut pkg) {
+                        tracing::error!("Failed to burn in redactions: {e}");
+                        return;
+                    }
+                }
+                sender
+                    .output(AnnotateOutput::UpdateEvidence(self.evidence.clone()))
+                    .unwrap();
+                root.close();
+            }
+        }
+        self.update_view(widgets, sender);
+    }
+}
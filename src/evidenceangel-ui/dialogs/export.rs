@@ -1,20 +1,54 @@
 use std::{fs, path::PathBuf};
 
 use adw::prelude::*;
+use chrono::{DateTime, FixedOffset};
+use evidenceangel::{
+    EvidenceKind, TestCasePassStatus,
+    exporters::{
+        ExportFormat, ExportOptionKind, ExportOptionValue, ExportOptions, ExporterRegistry,
+        TestCaseOrder,
+    },
+};
 use relm4::{
     Component, ComponentParts, ComponentSender, RelmWidgetExt,
     adw::{self, ApplicationWindow},
     gtk::{self, StringList},
 };
 
+use uuid::Uuid;
+
 use crate::{lang, lang_args};
 
-const EXPORT_FORMATS: &[&str] = &["HTML Document", "Excel Workbook", "ZIP Archive of Files"];
-const EXPORT_EXTENSIONS: &[&str] = &["html", "xlsx", "zip"];
+/// The kinds of evidence that can be left out of an export, with the language key of their
+/// names.
+const EVIDENCE_KINDS: [(EvidenceKind, &str); 12] = [
+    (EvidenceKind::Text, "export-evidence-text"),
+    (EvidenceKind::RichText, "export-evidence-rich-text"),
+    (EvidenceKind::Image, "export-evidence-image"),
+    (EvidenceKind::Video, "export-evidence-video"),
+    (EvidenceKind::File, "export-evidence-file"),
+    (EvidenceKind::Http, "export-evidence-http"),
+    (EvidenceKind::Log, "export-evidence-log"),
+    (EvidenceKind::Table, "export-evidence-table"),
+    (EvidenceKind::Diff, "export-evidence-diff"),
+    (EvidenceKind::Command, "export-evidence-command"),
+    (EvidenceKind::Sql, "export-evidence-sql"),
+    (EvidenceKind::Link, "export-evidence-link"),
+];
+
+/// The orders test cases can be exported in, in the order they are listed.
+const ORDERS: [TestCaseOrder; 4] = [
+    TestCaseOrder::Package,
+    TestCaseOrder::Title,
+    TestCaseOrder::ExecutionTime,
+    TestCaseOrder::Status,
+];
 
 #[derive(Debug)]
 pub enum ExportInput {
     Present(ApplicationWindow),
+    _FormatChanged,
+    _CheckDates,
     _Export,
     _SelectFile,
     _FileSelected(PathBuf),
@@ -23,7 +57,12 @@ pub enum ExportInput {
 
 #[derive(Debug)]
 pub enum ExportOutput {
-    Export { format: String, path: PathBuf },
+    Export {
+        format: String,
+        path: PathBuf,
+        redact: bool,
+        options: ExportOptions,
+    },
 }
 
 #[derive(Debug)]
@@ -36,6 +75,13 @@ pub struct ExportDialogInit {
     pub test_case_name: Option<String>,
     /// Does the file need saving before exporting
     pub needs_saving: bool,
+    /// Should secrets be redacted by default
+    pub redact: bool,
+    /// The IDs and titles of the test cases that can be chosen from, in package order. Empty
+    /// when exporting a single test case.
+    pub test_cases: Vec<(Uuid, String)>,
+    /// The IDs and names of the custom metadata fields that test cases can be filtered by.
+    pub custom_fields: Vec<(String, String)>,
 }
 
 pub struct ExportDialogModel {
@@ -47,6 +93,171 @@ pub struct ExportDialogModel {
     test_case_name: Option<String>,
     /// Does the file need saving before exporting
     needs_saving: bool,
+    /// The formats that can be exported to
+    formats: Vec<ExportFormat>,
+    /// The rows for the options of the selected format
+    option_rows: Vec<(String, OptionRow)>,
+    /// The switches for including each test case
+    test_case_rows: Vec<(Uuid, adw::SwitchRow)>,
+    /// The entries for filtering by custom metadata fields
+    field_rows: Vec<(String, adw::EntryRow)>,
+    /// The switches for including each kind of evidence
+    evidence_rows: Vec<(EvidenceKind, adw::SwitchRow)>,
+}
+
+/// A row for setting an export option
+enum OptionRow {
+    /// A switch to turn a flag on or off
+    Flag(adw::SwitchRow),
+    /// An entry for some text
+    Text(adw::EntryRow),
+}
+
+impl OptionRow {
+    /// Get the widget of this row.
+    fn widget(&self) -> &gtk::Widget {
+        match self {
+            Self::Flag(row) => row.upcast_ref(),
+            Self::Text(row) => row.upcast_ref(),
+        }
+    }
+
+    /// Get the value set in this row, if any.
+    fn value(&self) -> Option<ExportOptionValue> {
+        match self {
+            Self::Flag(row) => Some(ExportOptionValue::Flag(row.is_active())),
+            Self::Text(row) => {
+                let text = row.text().to_string();
+                (!text.is_empty()).then_some(ExportOptionValue::Text(text))
+            }
+        }
+    }
+}
+
+impl ExportDialogModel {
+    /// Get the format currently selected.
+    fn selected_format(&self, widgets: &ExportDialogModelWidgets) -> &ExportFormat {
+        &self.formats[widgets.format_row.selected() as usize]
+    }
+
+    /// Get the extension of the format currently selected, without a leading dot.
+    fn selected_extension(&self, widgets: &ExportDialogModelWidgets) -> String {
+        self.selected_format(widgets)
+            .extension()
+            .trim_start_matches('.')
+            .to_string()
+    }
+
+    /// Replace the option rows with those for the format currently selected.
+    fn update_option_rows(&mut self, widgets: &ExportDialogModelWidgets) {
+        for (_, row) in self.option_rows.drain(..) {
+            widgets.options_group.remove(row.widget());
+        }
+        let format = &self.formats[widgets.format_row.selected() as usize];
+        for option in format.options() {
+            let row = match option.kind() {
+                ExportOptionKind::Flag => OptionRow::Flag(
+                    adw::SwitchRow::builder()
+                        .title(option.name())
+                        .subtitle(option.description())
+                        .build(),
+                ),
+                ExportOptionKind::Text => OptionRow::Text(
+                    adw::EntryRow::builder()
+                        .title(option.name())
+                        .tooltip_text(option.description())
+                        .build(),
+                ),
+            };
+            widgets.options_group.add(row.widget());
+            self.option_rows.push((option.id().clone(), row));
+        }
+        widgets
+            .options_group
+            .set_visible(!self.option_rows.is_empty());
+    }
+
+    /// Get the options set in the dialog. Dates that can't be parsed are ignored, so should be
+    /// checked first.
+    fn options(&self, widgets: &ExportDialogModelWidgets) -> ExportOptions {
+        let mut options = ExportOptions::new();
+        for (id, row) in &self.option_rows {
+            if let Some(value) = row.value() {
+                options.set(id.clone(), value);
+            }
+        }
+
+        if self.test_case_name.is_none() {
+            let filter = options.filter_mut();
+            if self.test_case_rows.iter().any(|(_, row)| !row.is_active()) {
+                filter.set_test_cases(Some(
+                    self.test_case_rows
+                        .iter()
+                        .filter(|(_, row)| row.is_active())
+                        .map(|(id, _)| *id)
+                        .collect(),
+                ));
+            }
+            let statuses = [
+                (&widgets.passed_row, Some(TestCasePassStatus::Pass)),
+                (&widgets.failed_row, Some(TestCasePassStatus::Fail)),
+                (&widgets.not_run_row, None),
+            ];
+            if statuses.iter().any(|(row, _)| !row.is_active()) {
+                filter.set_statuses(Some(
+                    statuses
+                        .into_iter()
+                        .filter(|(row, _)| row.is_active())
+                        .map(|(_, status)| status)
+                        .collect(),
+                ));
+            }
+            for (id, row) in &self.field_rows {
+                let value = row.text().to_string();
+                if !value.is_empty() {
+                    filter.custom_fields_mut().insert(id.clone(), value);
+                }
+            }
+            if let Some(Ok(after)) = parse_date(&widgets.executed_after_row) {
+                filter.set_executed_after(Some(after));
+            }
+            if let Some(Ok(before)) = parse_date(&widgets.executed_before_row) {
+                filter.set_executed_before(Some(before));
+            }
+            options.set_order(ORDERS[widgets.order_row.selected() as usize]);
+        }
+
+        for (kind, row) in &self.evidence_rows {
+            if !row.is_active() {
+                options.excluded_evidence_mut().insert(*kind);
+            }
+        }
+        options.set_omit_authors(widgets.omit_authors_row.is_active());
+        options.set_omit_author_emails(widgets.omit_emails_row.is_active());
+        options
+    }
+
+    /// Mark the date rows that can't be parsed, returning whether they are all valid.
+    fn check_dates(widgets: &ExportDialogModelWidgets) -> bool {
+        let mut valid = true;
+        for row in [&widgets.executed_after_row, &widgets.executed_before_row] {
+            if let Some(Err(_)) = parse_date(row) {
+                row.add_css_class("error");
+                valid = false;
+            } else {
+                row.remove_css_class("error");
+            }
+        }
+        valid
+    }
+}
+
+/// Parse the date and time in an entry, if one has been entered.
+fn parse_date(
+    row: &adw::EntryRow,
+) -> Option<Result<DateTime<FixedOffset>, parse_datetime::ParseDateTimeError>> {
+    let text = row.text();
+    (!text.trim().is_empty()).then(|| parse_datetime::parse_datetime(text.as_str()))
 }
 
 #[relm4::component(pub)]
@@ -73,43 +284,126 @@ impl Component for ExportDialogModel {
                 },
                 set_width_request: 400,
 
-                gtk::Box {
-                    set_orientation: gtk::Orientation::Vertical,
-                    set_spacing: 8,
-                    set_margin_all: 16,
-
-                    adw::PreferencesGroup {
-                        #[name = "format_row"]
-                        adw::ComboRow {
-                            set_title: &lang::lookup("export-format-label"),
-                            set_model: Some(&StringList::new(EXPORT_FORMATS)),
-                            connect_selected_notify => ExportInput::_CheckPathValidity,
+                gtk::ScrolledWindow {
+                    set_hscrollbar_policy: gtk::PolicyType::Never,
+                    set_propagate_natural_height: true,
+
+                    #[wrap(Some)]
+                    set_child = &gtk::Box {
+                        set_orientation: gtk::Orientation::Vertical,
+                        set_spacing: 8,
+                        set_margin_all: 16,
+
+                        adw::PreferencesGroup {
+                            #[name = "format_row"]
+                            adw::ComboRow {
+                                set_title: &lang::lookup("export-format-label"),
+                                set_model: Some(&StringList::new(&format_names)),
+                                connect_selected_notify => ExportInput::_FormatChanged,
+                            },
+                            #[name = "file_row"]
+                            adw::EntryRow {
+                                set_title: &lang::lookup("export-file-label"),
+                                add_suffix = &gtk::Button {
+                                    set_icon_name: relm4_icons::icon_names::FOLDER_OPEN_FILLED,
+                                    set_tooltip: &lang::lookup("select"),
+                                    add_css_class: "flat",
+                                    connect_clicked => ExportInput::_SelectFile,
+                                },
+                                connect_entry_activated => ExportInput::_Export,
+                                connect_changed => ExportInput::_CheckPathValidity @file_row_changed,
+                            },
+                            #[name = "redact_row"]
+                            adw::SwitchRow {
+                                set_title: &lang::lookup("export-redact"),
+                                set_subtitle: &lang::lookup("export-redact-subtitle"),
+                                set_active: redact,
+                            },
+                        },
+                        #[name = "options_group"]
+                        adw::PreferencesGroup {
+                            set_title: &lang::lookup("export-options"),
                         },
-                        #[name = "file_row"]
-                        adw::EntryRow {
-                            set_title: &lang::lookup("export-file-label"),
-                            add_suffix = &gtk::Button {
-                                set_icon_name: relm4_icons::icon_names::FOLDER_OPEN_FILLED,
-                                set_tooltip: &lang::lookup("select"),
-                                add_css_class: "flat",
-                                connect_clicked => ExportInput::_SelectFile,
+                        adw::PreferencesGroup {
+                            set_title: &lang::lookup("export-scope"),
+                            set_visible: model.test_case_name.is_none(),
+
+                            #[name = "test_cases_row"]
+                            adw::ExpanderRow {
+                                set_title: &lang::lookup("export-test-cases"),
+                                set_subtitle: &lang::lookup("export-test-cases-subtitle"),
+                            },
+                            #[name = "passed_row"]
+                            adw::SwitchRow {
+                                set_title: &lang::lookup("export-include-passed"),
+                                set_active: true,
+                            },
+                            #[name = "failed_row"]
+                            adw::SwitchRow {
+                                set_title: &lang::lookup("export-include-failed"),
+                                set_active: true,
+                            },
+                            #[name = "not_run_row"]
+                            adw::SwitchRow {
+                                set_title: &lang::lookup("export-include-not-run"),
+                                set_active: true,
+                            },
+                            #[name = "fields_row"]
+                            adw::ExpanderRow {
+                                set_title: &lang::lookup("export-fields"),
+                                set_subtitle: &lang::lookup("export-fields-subtitle"),
+                            },
+                            #[name = "executed_after_row"]
+                            adw::EntryRow {
+                                set_title: &lang::lookup("export-executed-after"),
+                                connect_changed => ExportInput::_CheckDates,
+                            },
+                            #[name = "executed_before_row"]
+                            adw::EntryRow {
+                                set_title: &lang::lookup("export-executed-before"),
+                                connect_changed => ExportInput::_CheckDates,
+                            },
+                            #[name = "order_row"]
+                            adw::ComboRow {
+                                set_title: &lang::lookup("export-order"),
+                                set_model: Some(&StringList::new(&[
+                                    &lang::lookup("export-order-package"),
+                                    &lang::lookup("export-order-title"),
+                                    &lang::lookup("export-order-execution-time"),
+                                    &lang::lookup("export-order-status"),
+                                ])),
                             },
-                            connect_entry_activated => ExportInput::_Export,
-                            connect_changed => ExportInput::_CheckPathValidity @file_row_changed,
                         },
-                    },
-                    #[name = "export_btn"]
-                    gtk::Button {
-                        set_label: &if needs_saving {
-                            lang::lookup("export-submit-save")
-                        } else {
-                            lang::lookup("export-submit")
+                        adw::PreferencesGroup {
+                            set_title: &lang::lookup("export-content"),
+
+                            #[name = "evidence_row"]
+                            adw::ExpanderRow {
+                                set_title: &lang::lookup("export-evidence"),
+                                set_subtitle: &lang::lookup("export-evidence-subtitle"),
+                            },
+                            #[name = "omit_authors_row"]
+                            adw::SwitchRow {
+                                set_title: &lang::lookup("export-omit-authors"),
+                            },
+                            #[name = "omit_emails_row"]
+                            adw::SwitchRow {
+                                set_title: &lang::lookup("export-omit-emails"),
+                            },
                         },
-                        add_css_class: "pill",
-                        add_css_class: "suggested-action",
-                        set_halign: gtk::Align::Center,
+                        #[name = "export_btn"]
+                        gtk::Button {
+                            set_label: &if needs_saving {
+                                lang::lookup("export-submit-save")
+                            } else {
+                                lang::lookup("export-submit")
+                            },
+                            add_css_class: "pill",
+                            add_css_class: "suggested-action",
+                            set_halign: gtk::Align::Center,
 
-                        connect_clicked => ExportInput::_Export,
+                            connect_clicked => ExportInput::_Export,
+                        }
                     }
                 }
             }
@@ -126,14 +420,53 @@ impl Component for ExportDialogModel {
             package_path,
             test_case_name,
             needs_saving,
+            redact,
+            test_cases,
+            custom_fields,
         } = init;
-        let model = Self {
+        let mut model = Self {
             package_name,
             package_directory: package_path.parent().unwrap().to_path_buf(),
             test_case_name,
             needs_saving,
+            formats: ExporterRegistry::default().formats().to_vec(),
+            option_rows: vec![],
+            test_case_rows: vec![],
+            field_rows: vec![],
+            evidence_rows: vec![],
         };
+        let format_names: Vec<&str> = model.formats.iter().map(|f| f.name().as_str()).collect();
         let widgets = view_output!();
+        model.update_option_rows(&widgets);
+
+        for (id, title) in test_cases {
+            let row = adw::SwitchRow::builder().title(title).active(true).build();
+            widgets.test_cases_row.add_row(&row);
+            model.test_case_rows.push((id, row));
+        }
+        widgets
+            .test_cases_row
+            .set_visible(!model.test_case_rows.is_empty());
+        for (id, name) in custom_fields {
+            let row = adw::EntryRow::builder().title(name).build();
+            widgets.fields_row.add_row(&row);
+            model.field_rows.push((id, row));
+        }
+        widgets.fields_row.set_visible(!model.field_rows.is_empty());
+        for (kind, key) in EVIDENCE_KINDS {
+            let row = adw::SwitchRow::builder()
+                .title(lang::lookup(key))
+                .active(true)
+                .build();
+            widgets.evidence_row.add_row(&row);
+            model.evidence_rows.push((kind, row));
+        }
+        widgets
+            .omit_authors_row
+            .bind_property("active", &widgets.omit_emails_row, "sensitive")
+            .invert_boolean()
+            .sync_create()
+            .build();
         ComponentParts { model, widgets }
     }
 
@@ -148,6 +481,13 @@ impl Component for ExportDialogModel {
             ExportInput::Present(window) => {
                 root.present(Some(&window));
             }
+            ExportInput::_FormatChanged => {
+                self.update_option_rows(widgets);
+                sender.input(ExportInput::_CheckPathValidity);
+            }
+            ExportInput::_CheckDates => {
+                Self::check_dates(widgets);
+            }
             ExportInput::_CheckPathValidity => {
                 let path = widgets.file_row.text().to_string();
                 if path.trim().is_empty() {
@@ -161,8 +501,8 @@ impl Component for ExportDialogModel {
                 let mut path = PathBuf::from(path);
 
                 // Update extension
-                let extension = EXPORT_EXTENSIONS[widgets.format_row.selected() as usize];
-                if !path_str.ends_with(extension) {
+                let extension = self.selected_extension(widgets);
+                if !path_str.ends_with(&extension) {
                     // If already ends with extension, assume at this stage the
                     // user is just typing something. Fix for #150.
                     path.set_extension(extension);
@@ -200,7 +540,7 @@ impl Component for ExportDialogModel {
             ExportInput::_Export => {
                 let path = widgets.file_row.text().to_string();
                 sender.input(ExportInput::_CheckPathValidity);
-                if path.trim().is_empty() {
+                if path.trim().is_empty() || !Self::check_dates(widgets) {
                     return;
                 }
                 let mut path = PathBuf::from(path);
@@ -210,11 +550,16 @@ impl Component for ExportDialogModel {
                     tracing::debug!("Making path relative to EVP: {path:?}");
                 }
                 // Update extension
-                let extension = EXPORT_EXTENSIONS[widgets.format_row.selected() as usize];
-                path.set_extension(extension);
+                path.set_extension(self.selected_extension(widgets));
 
-                let format = EXPORT_FORMATS[widgets.format_row.selected() as usize].to_lowercase();
-                let _ = sender.output(ExportOutput::Export { format, path });
+                let format = self.selected_format(widgets).id().clone();
+                let redact = widgets.redact_row.is_active();
+                let _ = sender.output(ExportOutput::Export {
+                    format,
+                    path,
+                    redact,
+                    options: self.options(widgets),
+                });
                 root.close();
             }
             ExportInput::_SelectFile => {
@@ -227,14 +572,14 @@ impl Component for ExportDialogModel {
                     .build();
 
                 let sender_c = sender.clone();
-                let extension = EXPORT_EXTENSIONS[widgets.format_row.selected() as usize];
+                let extension = self.selected_extension(widgets);
                 dialog.save(
                     Some(&root.toplevel_window().unwrap()),
                     Some(&relm4::gtk::gio::Cancellable::new()),
                     move |res| {
                         if let Ok(file) = res {
                             let mut path = file.path().unwrap();
-                            path.set_extension(extension);
+                            path.set_extension(&extension);
                             // Open this package
                             sender_c.input(ExportInput::_FileSelected(path));
                         }
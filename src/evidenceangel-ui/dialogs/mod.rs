@@ -1,5 +1,8 @@
 pub mod add_evidence;
+pub mod annotate;
 pub mod custom_metadata_field;
 pub mod error;
 pub mod export;
 pub mod new_author;
+pub mod redaction;
+pub mod templates;
@@ -0,0 +1,238 @@
+use adw::prelude::*;
+use evidenceangel::{RedactionSettings, SecretRule};
+use relm4::{
+    Component, ComponentParts, ComponentSender, RelmWidgetExt,
+    adw::{self, ApplicationWindow},
+    gtk,
+};
+
+use crate::lang;
+
+pub struct RedactionDialogModel {
+    /// The package's redaction settings, as they are being edited
+    settings: RedactionSettings,
+}
+
+#[derive(Debug)]
+pub enum RedactionInput {
+    Present(ApplicationWindow),
+    _SettingsChanged,
+    _AddRule,
+    _DeleteRule(String),
+    _CheckPattern,
+    _RedactNow,
+}
+
+#[derive(Debug)]
+pub enum RedactionOutput {
+    /// The package's redaction settings have been changed.
+    SetSettings(RedactionSettings),
+    /// Redact the evidence in the package now.
+    RedactNow,
+}
+
+#[relm4::component(pub)]
+impl Component for RedactionDialogModel {
+    type Input = RedactionInput;
+    type Output = RedactionOutput;
+    type CommandOutput = ();
+    type Init = RedactionSettings;
+
+    view! {
+        #[root]
+        adw::Dialog {
+            set_width_request: 500,
+
+            #[wrap(Some)]
+            set_child = &adw::ToolbarView {
+                add_top_bar = &adw::HeaderBar {
+                    #[wrap(Some)]
+                    set_title_widget = &adw::WindowTitle {
+                        set_title: &lang::lookup("redaction-title"),
+                    }
+                },
+
+                gtk::ScrolledWindow {
+                    set_propagate_natural_height: true,
+                    set_hscrollbar_policy: gtk::PolicyType::Never,
+
+                    gtk::Box {
+                        set_orientation: gtk::Orientation::Vertical,
+                        set_spacing: 16,
+                        set_margin_all: 16,
+
+                        adw::PreferencesGroup {
+                            #[name = "builtin_rules"]
+                            adw::SwitchRow {
+                                set_title: &lang::lookup("redaction-builtin-rules"),
+                                set_subtitle: &lang::lookup("redaction-builtin-rules-subtitle"),
+                                set_active: *model.settings.builtin_rules(),
+                                connect_active_notify => RedactionInput::_SettingsChanged,
+                            },
+                            #[name = "on_add"]
+                            adw::SwitchRow {
+                                set_title: &lang::lookup("redaction-on-add"),
+                                set_active: *model.settings.redact_on_add(),
+                                connect_active_notify => RedactionInput::_SettingsChanged,
+                            },
+                            #[name = "on_export"]
+                            adw::SwitchRow {
+                                set_title: &lang::lookup("redaction-on-export"),
+                                set_active: *model.settings.redact_on_export(),
+                                connect_active_notify => RedactionInput::_SettingsChanged,
+                            },
+                        },
+
+                        adw::PreferencesGroup {
+                            set_title: &lang::lookup("redaction-rules"),
+                            set_description: Some(&lang::lookup("redaction-rules-description")),
+
+                            #[name = "rules"]
+                            gtk::ListBox {
+                                add_css_class: "boxed-list",
+                                set_selection_mode: gtk::SelectionMode::None,
+                            },
+                        },
+
+                        adw::PreferencesGroup {
+                            #[name = "rule_name"]
+                            adw::EntryRow {
+                                set_title: &lang::lookup("redaction-rule-name"),
+                                connect_entry_activated => RedactionInput::_AddRule,
+                            },
+                            #[name = "rule_pattern"]
+                            adw::EntryRow {
+                                set_title: &lang::lookup("redaction-rule-pattern"),
+                                add_css_class: "monospace",
+                                connect_changed => RedactionInput::_CheckPattern,
+                                connect_entry_activated => RedactionInput::_AddRule,
+                                add_suffix = &gtk::Button {
+                                    set_icon_name: relm4_icons::icon_names::PLUS,
+                                    set_tooltip: &lang::lookup("redaction-rule-add"),
+                                    add_css_class: "flat",
+                                    connect_clicked => RedactionInput::_AddRule,
+                                },
+                            },
+                        },
+
+                        gtk::Button {
+                            set_label: &lang::lookup("redaction-apply"),
+                            add_css_class: "pill",
+                            add_css_class: "destructive-action",
+                            set_halign: gtk::Align::Center,
+
+                            connect_clicked => RedactionInput::_RedactNow,
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn init(
+        init: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let model = RedactionDialogModel { settings: init };
+        let widgets = view_output!();
+        model.update_rules(&widgets, &sender);
+        ComponentParts { model, widgets }
+    }
+
+    fn update_with_view(
+        &mut self,
+        widgets: &mut Self::Widgets,
+        message: Self::Input,
+        sender: ComponentSender<Self>,
+        root: &Self::Root,
+    ) {
+        match message {
+            RedactionInput::Present(window) => {
+                root.present(Some(&window));
+            }
+            RedactionInput::_SettingsChanged => {
+                self.settings
+                    .set_builtin_rules(widgets.builtin_rules.is_active());
+                self.settings.set_redact_on_add(widgets.on_add.is_active());
+                self.settings
+                    .set_redact_on_export(widgets.on_export.is_active());
+                let _ = sender.output(RedactionOutput::SetSettings(self.settings.clone()));
+            }
+            RedactionInput::_CheckPattern => {
+                let pattern = widgets.rule_pattern.text().to_string();
+                if pattern.is_empty() || SecretRule::new("", pattern).is_ok() {
+                    widgets.rule_pattern.remove_css_class("error");
+                } else {
+                    widgets.rule_pattern.add_css_class("error");
+                }
+            }
+            RedactionInput::_AddRule => {
+                let name = widgets.rule_name.text().trim().to_string();
+                let pattern = widgets.rule_pattern.text().to_string();
+                if name.is_empty() {
+                    widgets.rule_name.add_css_class("error");
+                    return;
+                }
+                widgets.rule_name.remove_css_class("error");
+                let Ok(rule) = SecretRule::new(name, pattern) else {
+                    widgets.rule_pattern.add_css_class("error");
+                    return;
+                };
+                self.settings.insert_rule(rule);
+                widgets.rule_name.set_text("");
+                widgets.rule_pattern.set_text("");
+                self.update_rules(widgets, &sender);
+                let _ = sender.output(RedactionOutput::SetSettings(self.settings.clone()));
+            }
+            RedactionInput::_DeleteRule(name) => {
+                if self.settings.remove_rule(&name) {
+                    self.update_rules(widgets, &sender);
+                    let _ = sender.output(RedactionOutput::SetSettings(self.settings.clone()));
+                }
+            }
+            RedactionInput::_RedactNow => {
+                let _ = sender.output(RedactionOutput::RedactNow);
+                root.close();
+            }
+        }
+        self.update_view(widgets, sender);
+    }
+}
+
+impl RedactionDialogModel {
+    /// Rebuild the list of custom rules.
+    fn update_rules(&self, widgets: &RedactionDialogModelWidgets, sender: &ComponentSender<Self>) {
+        widgets.rules.remove_all();
+        if self.settings.rules().is_empty() {
+            let row = adw::ActionRow::new();
+            row.set_title(&lang::lookup("redaction-no-rules"));
+            row.add_css_class("dim-label");
+            widgets.rules.append(&row);
+        }
+        for rule in self.settings.rules() {
+            let row = adw::ActionRow::new();
+            row.set_title(&glib_escape(rule.name()));
+            row.set_subtitle(&glib_escape(rule.pattern()));
+            row.set_subtitle_selectable(true);
+            row.add_css_class("property");
+
+            let delete = gtk::Button::from_icon_name(relm4_icons::icon_names::CROSS_LARGE);
+            delete.set_tooltip(&lang::lookup("redaction-rule-delete"));
+            delete.set_valign(gtk::Align::Center);
+            delete.add_css_class("flat");
+            let sender = sender.clone();
+            let name = rule.name().clone();
+            delete.connect_clicked(move |_| {
+                sender.input(RedactionInput::_DeleteRule(name.clone()));
+            });
+            row.add_suffix(&delete);
+            widgets.rules.append(&row);
+        }
+    }
+}
+
+/// Escape text for display in a row, as row titles are parsed as markup.
+fn glib_escape(text: &str) -> String {
+    gtk::glib::markup_escape_text(text).to_string()
+}
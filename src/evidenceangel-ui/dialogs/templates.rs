@@ -0,0 +1,330 @@
+use adw::prelude::*;
+use evidenceangel::{TemplateLibrary, TestCaseTemplate};
+use relm4::{
+    Component, ComponentParts, ComponentSender, RelmWidgetExt,
+    adw::{self, ApplicationWindow},
+    gtk,
+};
+
+use crate::lang;
+
+pub struct TemplatesDialogModel {
+    /// The templates stored in the package
+    package: TemplateLibrary,
+    /// The templates for all packages the user works with
+    user: TemplateLibrary,
+}
+
+pub struct TemplatesDialogInit {
+    pub package: TemplateLibrary,
+    pub user: TemplateLibrary,
+}
+
+#[derive(Debug)]
+pub enum TemplatesInput {
+    Present(ApplicationWindow),
+    _Create { name: String, user: bool },
+    _Delete { name: String, user: bool },
+}
+
+#[derive(Debug)]
+pub enum TemplatesOutput {
+    /// Create a new test case from a template.
+    CreateFromTemplate(TestCaseTemplate),
+    /// Delete a template, either from the package or from the user's templates.
+    DeleteTemplate { name: String, user: bool },
+}
+
+#[relm4::component(pub)]
+impl Component for TemplatesDialogModel {
+    type Input = TemplatesInput;
+    type Output = TemplatesOutput;
+    type CommandOutput = ();
+    type Init = TemplatesDialogInit;
+
+    view! {
+        #[root]
+        adw::Dialog {
+            set_width_request: 500,
+
+            #[wrap(Some)]
+            set_child = &adw::ToolbarView {
+                add_top_bar = &adw::HeaderBar {
+                    #[wrap(Some)]
+                    set_title_widget = &adw::WindowTitle {
+                        set_title: &lang::lookup("templates-title"),
+                    }
+                },
+
+                gtk::ScrolledWindow {
+                    set_propagate_natural_height: true,
+                    set_hscrollbar_policy: gtk::PolicyType::Never,
+
+                    gtk::Box {
+                        set_orientation: gtk::Orientation::Vertical,
+                        set_spacing: 16,
+                        set_margin_all: 16,
+
+                        adw::PreferencesGroup {
+                            set_title: &lang::lookup("templates-package"),
+
+                            #[name = "package_templates"]
+                            gtk::ListBox {
+                                add_css_class: "boxed-list",
+                                set_selection_mode: gtk::SelectionMode::None,
+                            },
+                        },
+
+                        adw::PreferencesGroup {
+                            set_title: &lang::lookup("templates-user"),
+                            set_description: Some(&lang::lookup("templates-user-description")),
+
+                            #[name = "user_templates"]
+                            gtk::ListBox {
+                                add_css_class: "boxed-list",
+                                set_selection_mode: gtk::SelectionMode::None,
+                            },
+                        },
+                    }
+                }
+            }
+        }
+    }
+
+    fn init(
+        init: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let model = TemplatesDialogModel {
+            package: init.package,
+            user: init.user,
+        };
+        let widgets = view_output!();
+        model.update_templates(&widgets, &sender);
+        ComponentParts { model, widgets }
+    }
+
+    fn update_with_view(
+        &mut self,
+        widgets: &mut Self::Widgets,
+        message: Self::Input,
+        sender: ComponentSender<Self>,
+        root: &Self::Root,
+    ) {
+        match message {
+            TemplatesInput::Present(window) => {
+                root.present(Some(&window));
+            }
+            TemplatesInput::_Create { name, user } => {
+                let library = if user { &self.user } else { &self.package };
+                if let Some(template) = library.get(&name) {
+                    let _ = sender.output(TemplatesOutput::CreateFromTemplate(template.clone()));
+                }
+                root.close();
+            }
+            TemplatesInput::_Delete { name, user } => {
+                let library = if user {
+                    &mut self.user
+                } else {
+                    &mut self.package
+                };
+                if library.remove(&name).is_some() {
+                    self.update_templates(widgets, &sender);
+                    let _ = sender.output(TemplatesOutput::DeleteTemplate { name, user });
+                }
+            }
+        }
+        self.update_view(widgets, sender);
+    }
+}
+
+impl TemplatesDialogModel {
+    /// Rebuild the lists of templates.
+    fn update_templates(
+        &self,
+        widgets: &TemplatesDialogModelWidgets,
+        sender: &ComponentSender<Self>,
+    ) {
+        for (list, library, user) in [
+            (&widgets.package_templates, &self.package, false),
+            (&widgets.user_templates, &self.user, true),
+        ] {
+            list.remove_all();
+            if library.is_empty() {
+                let row = adw::ActionRow::new();
+                row.set_title(&lang::lookup("templates-none"));
+                row.add_css_class("dim-label");
+                list.append(&row);
+            }
+            for template in library.iter() {
+                let row = adw::ActionRow::new();
+                row.set_title(&glib_escape(template.name()));
+                row.set_subtitle(&glib_escape(
+                    template.description().as_ref().unwrap_or(template.title()),
+                ));
+                row.set_activatable(true);
+                let sender_c = sender.clone();
+                let name = template.name().clone();
+                row.connect_activated(move |_| {
+                    sender_c.input(TemplatesInput::_Create {
+                        name: name.clone(),
+                        user,
+                    });
+                });
+
+                let delete = gtk::Button::from_icon_name(relm4_icons::icon_names::CROSS_LARGE);
+                delete.set_tooltip(&lang::lookup("templates-delete"));
+                delete.set_valign(gtk::Align::Center);
+                delete.add_css_class("flat");
+                let sender_c = sender.clone();
+                let name = template.name().clone();
+                delete.connect_clicked(move |_| {
+                    sender_c.input(TemplatesInput::_Delete {
+                        name: name.clone(),
+                        user,
+                    });
+                });
+                row.add_suffix(&delete);
+                list.append(&row);
+            }
+        }
+    }
+}
+
+pub struct SaveTemplateDialogModel {}
+
+#[derive(Debug)]
+pub enum SaveTemplateInput {
+    Present(ApplicationWindow),
+    _Save,
+}
+
+#[derive(Debug)]
+pub enum SaveTemplateOutput {
+    /// Save the open test case as a template.
+    Save {
+        name: String,
+        title: String,
+        description: Option<String>,
+        user: bool,
+    },
+}
+
+#[relm4::component(pub)]
+impl Component for SaveTemplateDialogModel {
+    type Input = SaveTemplateInput;
+    type Output = SaveTemplateOutput;
+    type CommandOutput = ();
+    /// The name the template starts with.
+    type Init = String;
+
+    view! {
+        #[root]
+        adw::Dialog {
+            set_width_request: 400,
+
+            #[wrap(Some)]
+            set_child = &adw::ToolbarView {
+                add_top_bar = &adw::HeaderBar {
+                    #[wrap(Some)]
+                    set_title_widget = &adw::WindowTitle {
+                        set_title: &lang::lookup("template-save-title"),
+                    }
+                },
+
+                gtk::Box {
+                    set_orientation: gtk::Orientation::Vertical,
+                    set_spacing: 8,
+                    set_margin_all: 16,
+
+                    adw::PreferencesGroup {
+                        set_description: Some(&lang::lookup("template-save-description")),
+
+                        #[name = "name"]
+                        adw::EntryRow {
+                            set_title: &lang::lookup("template-save-name"),
+                            set_text: &init,
+                            connect_entry_activated => SaveTemplateInput::_Save,
+                        },
+                        #[name = "title"]
+                        adw::EntryRow {
+                            set_title: &lang::lookup("template-save-case-title"),
+                            set_tooltip: &lang::lookup("template-save-case-title-tooltip"),
+                            set_text: "{template} {n}",
+                            connect_entry_activated => SaveTemplateInput::_Save,
+                        },
+                        #[name = "description"]
+                        adw::EntryRow {
+                            set_title: &lang::lookup("template-save-template-description"),
+                            connect_entry_activated => SaveTemplateInput::_Save,
+                        },
+                        #[name = "user"]
+                        adw::SwitchRow {
+                            set_title: &lang::lookup("template-save-user"),
+                        },
+                    },
+
+                    gtk::Button {
+                        set_label: &lang::lookup("template-save-submit"),
+                        add_css_class: "pill",
+                        add_css_class: "suggested-action",
+                        set_halign: gtk::Align::Center,
+
+                        connect_clicked => SaveTemplateInput::_Save,
+                    }
+                }
+            }
+        }
+    }
+
+    fn init(
+        init: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let model = SaveTemplateDialogModel {};
+        let widgets = view_output!();
+        ComponentParts { model, widgets }
+    }
+
+    fn update_with_view(
+        &mut self,
+        widgets: &mut Self::Widgets,
+        message: Self::Input,
+        sender: ComponentSender<Self>,
+        root: &Self::Root,
+    ) {
+        match message {
+            SaveTemplateInput::Present(window) => {
+                root.present(Some(&window));
+            }
+            SaveTemplateInput::_Save => {
+                let name = widgets.name.text().trim().to_string();
+                let title = widgets.title.text().trim().to_string();
+                let description = widgets.description.text().trim().to_string();
+                if name.is_empty() {
+                    widgets.name.add_css_class("error");
+                    return;
+                }
+                if title.is_empty() {
+                    widgets.title.add_css_class("error");
+                    return;
+                }
+                let _ = sender.output(SaveTemplateOutput::Save {
+                    name,
+                    title,
+                    description: (!description.is_empty()).then_some(description),
+                    user: widgets.user.is_active(),
+                });
+                root.close();
+            }
+        }
+        self.update_view(widgets, sender);
+    }
+}
+
+/// Escape text for display in a row, as row titles are parsed as markup.
+fn glib_escape(text: &str) -> String {
+    gtk::glib::markup_escape_text(text).to_string()
+}
@@ -0,0 +1,174 @@
+use evidenceangel::CommandExecution;
+use gtk::{glib::translate::IntoGlib, prelude::*};
+use relm4::{Component, ComponentParts, ComponentSender, gtk};
+
+use crate::{lang, lang_args};
+
+pub struct ComponentModel;
+
+#[derive(Debug)]
+pub enum ComponentInput {}
+
+#[derive(Debug)]
+pub enum ComponentOutput {}
+
+pub struct ComponentInit {
+    pub command: CommandExecution,
+}
+
+/// Append terminal output to a text buffer, converting colour and style escape codes to tags.
+fn append_ansi(buffer: &gtk::TextBuffer, text: &str) {
+    for span in evidenceangel::parse_ansi(text) {
+        let style = span.style();
+        if style.is_plain() {
+            buffer.insert(&mut buffer.end_iter(), span.text());
+            continue;
+        }
+
+        let tag = gtk::TextTag::new(None);
+        if let Some(color) = style.foreground() {
+            tag.set_foreground(Some(&color.to_hex()));
+        } else if style.dim() {
+            tag.set_foreground(Some("gray"));
+        }
+        if let Some(color) = style.background() {
+            tag.set_background(Some(&color.to_hex()));
+        }
+        if style.bold() {
+            tag.set_weight(gtk::pango::Weight::Bold.into_glib());
+        }
+        if style.italic() {
+            tag.set_style(gtk::pango::Style::Italic);
+        }
+        if style.underline() {
+            tag.set_underline(gtk::pango::Underline::Single);
+        }
+        buffer.tag_table().add(&tag);
+        buffer.insert_with_tags(&mut buffer.end_iter(), span.text(), &[&tag]);
+    }
+}
+
+/// Create a read-only view of some terminal output.
+fn output_view(text: &str) -> gtk::ScrolledWindow {
+    let view = gtk::TextView::new();
+    view.set_editable(false);
+    view.set_cursor_visible(false);
+    view.set_monospace(true);
+    view.set_wrap_mode(gtk::WrapMode::WordChar);
+    view.set_top_margin(4);
+    view.set_bottom_margin(4);
+    view.set_left_margin(4);
+    view.set_right_margin(4);
+    append_ansi(&view.buffer(), text.trim_end());
+
+    let scroll = gtk::ScrolledWindow::new();
+    scroll.set_child(Some(&view));
+    scroll.set_propagate_natural_height(true);
+    scroll.set_max_content_height(super::EVIDENCE_HEIGHT_REQUEST);
+    scroll.add_css_class("card");
+    scroll
+}
+
+#[relm4::component(pub)]
+impl Component for ComponentModel {
+    type CommandOutput = ();
+    type Input = ComponentInput;
+    type Output = ComponentOutput;
+    type Init = ComponentInit;
+
+    view! {
+        #[root]
+        gtk::Expander {
+            set_expanded: !init.command.succeeded(),
+            set_hexpand: true,
+
+            #[wrap(Some)]
+            set_label_widget = &gtk::Box {
+                set_orientation: gtk::Orientation::Horizontal,
+                set_spacing: 8,
+
+                gtk::Label {
+                    add_css_class: "monospace",
+                    set_ellipsize: gtk::pango::EllipsizeMode::End,
+                    set_label: &format!("$ {}", init.command.command()),
+                    set_tooltip_text: Some(init.command.command()),
+                },
+                gtk::Label {
+                    set_visible: init.command.exit_code().is_some(),
+                    add_css_class: if init.command.succeeded() { "success" } else { "error" },
+                    set_label: &init.command.exit_code().map(|exit_code| {
+                        lang::lookup_with_args(
+                            "evidence-command-exit-code",
+                            &lang_args!("code", exit_code),
+                        )
+                    }).unwrap_or_default(),
+                },
+                gtk::Label {
+                    set_visible: init.command.duration().is_some(),
+                    add_css_class: "dim-label",
+                    set_label: &init.command.duration().map(|duration| {
+                        format!("{:.3}s", duration.as_secs_f64())
+                    }).unwrap_or_default(),
+                },
+            },
+
+            #[name = "details"]
+            gtk::Box {
+                set_orientation: gtk::Orientation::Vertical,
+                set_spacing: 4,
+                set_margin_top: 4,
+
+                gtk::Label {
+                    set_visible: init.command.working_directory().is_some(),
+                    add_css_class: "dim-label",
+                    set_xalign: 0.0,
+                    set_ellipsize: gtk::pango::EllipsizeMode::Start,
+                    set_label: &init.command.working_directory().as_ref().map(|working_directory| {
+                        lang::lookup_with_args(
+                            "evidence-command-directory",
+                            &lang_args!("directory", working_directory.clone()),
+                        )
+                    }).unwrap_or_default(),
+                },
+                gtk::Label {
+                    set_visible: !init.command.environment().is_empty(),
+                    add_css_class: "dim-label",
+                    add_css_class: "monospace",
+                    set_xalign: 0.0,
+                    set_wrap: true,
+                    set_selectable: true,
+                    set_label: &init.command.environment()
+                        .iter()
+                        .map(|(key, value)| format!("{key}={value}"))
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                },
+            },
+        }
+    }
+
+    fn init(
+        init: Self::Init,
+        root: Self::Root,
+        _sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let model = ComponentModel;
+        let widgets = view_output!();
+
+        for (label, output) in [
+            ("evidence-command-stdout", init.command.stdout()),
+            ("evidence-command-stderr", init.command.stderr()),
+        ] {
+            if output.trim().is_empty() {
+                continue;
+            }
+            let heading = gtk::Label::new(Some(&lang::lookup(label)));
+            heading.add_css_class("heading");
+            heading.set_xalign(0.0);
+            widgets.details.append(&heading);
+            widgets.details.append(&output_view(output));
+        }
+
+        ComponentParts { model, widgets }
+    }
+}
@@ -0,0 +1,153 @@
+use evidenceangel::{Diff, DiffChange, DiffLine};
+use gtk::prelude::*;
+use relm4::{Component, ComponentParts, ComponentSender, gtk};
+
+use crate::lang;
+
+pub struct ComponentModel;
+
+#[derive(Debug)]
+pub enum ComponentInput {}
+
+#[derive(Debug)]
+pub enum ComponentOutput {}
+
+pub struct ComponentInit {
+    pub diff: Diff,
+}
+
+/// Add tags to the buffer of a diff view to highlight each kind of change.
+fn add_tags(view: &gtk::TextView) {
+    let tags = view.buffer().tag_table();
+    for (name, color) in [
+        ("removed", "rgba(224, 27, 36, 0.2)"),
+        ("added", "rgba(46, 194, 126, 0.2)"),
+        ("empty", "rgba(127, 127, 127, 0.1)"),
+    ] {
+        let tag = gtk::TextTag::new(Some(name));
+        tag.set_paragraph_background(Some(color));
+        tags.add(&tag);
+    }
+    let tag = gtk::TextTag::new(Some("marker"));
+    tag.set_foreground(Some("gray"));
+    tags.add(&tag);
+}
+
+/// Append a line to a diff view, highlighted according to how it changed.
+fn append_line(view: &gtk::TextView, marker: Option<&str>, line: Option<&DiffLine>) {
+    let buffer = view.buffer();
+    let tag = match line.map(DiffLine::change) {
+        Some(DiffChange::Unchanged) => None,
+        Some(DiffChange::Removed) => Some("removed"),
+        Some(DiffChange::Added) => Some("added"),
+        None => Some("empty"),
+    };
+    let start = buffer.end_iter().offset();
+    if buffer.char_count() > 0 {
+        buffer.insert(&mut buffer.end_iter(), "\n");
+    }
+    if let Some(marker) = marker {
+        buffer.insert_with_tags_by_name(&mut buffer.end_iter(), marker, &["marker"]);
+    }
+    buffer.insert(
+        &mut buffer.end_iter(),
+        line.map(|line| line.text().as_str()).unwrap_or_default(),
+    );
+    if let Some(tag) = tag {
+        buffer.apply_tag_by_name(tag, &buffer.iter_at_offset(start), &buffer.end_iter());
+    }
+}
+
+#[relm4::component(pub)]
+impl Component for ComponentModel {
+    type CommandOutput = ();
+    type Input = ComponentInput;
+    type Output = ComponentOutput;
+    type Init = ComponentInit;
+
+    view! {
+        #[root]
+        gtk::Box {
+            set_orientation: gtk::Orientation::Vertical,
+            set_spacing: 4,
+
+            gtk::StackSwitcher {
+                set_stack: Some(&stack),
+                set_halign: gtk::Align::Center,
+            },
+            #[name = "stack"]
+            gtk::Stack {
+                set_height_request: super::EVIDENCE_HEIGHT_REQUEST,
+                set_hexpand: true,
+
+                add_titled[Some("unified"), &lang::lookup("evidence-diff-unified")] = &gtk::ScrolledWindow {
+                    #[name = "unified"]
+                    gtk::TextView {
+                        set_editable: false,
+                        set_cursor_visible: false,
+                        set_monospace: true,
+                        set_left_margin: 4,
+                        set_right_margin: 4,
+                    },
+                },
+                add_titled[Some("side-by-side"), &lang::lookup("evidence-diff-side-by-side")] = &gtk::ScrolledWindow {
+                    gtk::Box {
+                        set_orientation: gtk::Orientation::Horizontal,
+                        set_homogeneous: true,
+                        set_spacing: 4,
+
+                        #[name = "expected"]
+                        gtk::TextView {
+                            set_editable: false,
+                            set_cursor_visible: false,
+                            set_monospace: true,
+                            set_left_margin: 4,
+                            set_right_margin: 4,
+                        },
+                        #[name = "actual"]
+                        gtk::TextView {
+                            set_editable: false,
+                            set_cursor_visible: false,
+                            set_monospace: true,
+                            set_left_margin: 4,
+                            set_right_margin: 4,
+                        },
+                    },
+                },
+            },
+            gtk::Label {
+                add_css_class: "dim-label",
+                add_css_class: "caption",
+                set_visible: !init.diff.has_differences(),
+                set_label: &lang::lookup("evidence-diff-identical"),
+            },
+        }
+    }
+
+    fn init(
+        init: Self::Init,
+        root: Self::Root,
+        _sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let model = ComponentModel;
+        let widgets = view_output!();
+
+        for view in [&widgets.unified, &widgets.expected, &widgets.actual] {
+            add_tags(view);
+        }
+        for line in init.diff.lines() {
+            let marker = match line.change() {
+                DiffChange::Unchanged => "  ",
+                DiffChange::Removed => "- ",
+                DiffChange::Added => "+ ",
+            };
+            append_line(&widgets.unified, Some(marker), Some(&line));
+        }
+        for (expected, actual) in init.diff.side_by_side() {
+            append_line(&widgets.expected, None, expected.as_ref());
+            append_line(&widgets.actual, None, actual.as_ref());
+        }
+
+        ComponentParts { model, widgets }
+    }
+}
@@ -14,6 +14,7 @@ use crate::{lang, lang_args};
 pub struct ComponentModel {
     package: Arc<RwLock<EvidencePackage>>,
     evidence: Evidence,
+    details: Option<String>,
     temp_files: Vec<TempDir>,
 }
 
@@ -64,6 +65,13 @@ impl Component for ComponentModel {
                     lang::lookup("test-evidence-file-unnamed")
                 },
             },
+            gtk::Label {
+                add_css_class: "dim-label",
+                add_css_class: "caption",
+                set_ellipsize: gtk::pango::EllipsizeMode::End,
+                set_visible: model.details.is_some(),
+                set_label: model.details.as_deref().unwrap_or_default(),
+            },
             gtk::Button {
                 set_label: &lang::lookup("expand-file"),
                 add_css_class: "flat",
@@ -83,6 +91,7 @@ impl Component for ComponentModel {
             package, evidence, ..
         } = init;
         let model = ComponentModel {
+            details: super::media_details(&evidence, &package),
             evidence,
             package,
             temp_files: vec![],
@@ -108,7 +117,8 @@ impl Component for ComponentModel {
                             .original_filename()
                             .clone()
                             .unwrap_or_else(|| {
-                                let maybe_extension = infer::get(&data).map(|ty| ty.extension());
+                                let maybe_extension =
+                                    super::media_extension(&self.evidence, &self.package);
                                 format!(
                                     "file{}{}",
                                     if maybe_extension.is_some() { "." } else { "" },
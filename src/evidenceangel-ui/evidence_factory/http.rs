@@ -1,9 +1,15 @@
+use std::time::Duration;
+
+use evidenceangel::{HttpHeader, HttpRequest, HttpResponse};
 use gtk::prelude::*;
-use relm4::{Component, ComponentParts, ComponentSender, gtk};
+use relm4::{Component, ComponentParts, ComponentSender, RelmWidgetExt, gtk};
 
 use crate::lang;
 
-pub struct ComponentModel {}
+pub struct ComponentModel {
+    request: String,
+    response: String,
+}
 
 #[derive(Debug)]
 pub enum ComponentInput {
@@ -35,6 +41,77 @@ pub enum ComponentOutput {
 pub struct ComponentInit {
     pub request: String,
     pub response: String,
+    pub duration: Option<Duration>,
+}
+
+/// Remove every child of a box, so that it can be filled again.
+fn clear(container: &gtk::Box) {
+    while let Some(child) = container.first_child() {
+        container.remove(&child);
+    }
+}
+
+/// Fill a box with the start line, headers and body of one side of an exchange.
+fn fill_message(
+    container: &gtk::Box,
+    start_line: &str,
+    status_class: Option<&str>,
+    headers: &[HttpHeader],
+    body: &str,
+) {
+    clear(container);
+
+    let start = gtk::Label::new(Some(start_line));
+    start.add_css_class("monospace");
+    start.add_css_class("heading");
+    if let Some(class) = status_class {
+        start.add_css_class(class);
+    }
+    start.set_xalign(0.0);
+    start.set_wrap(true);
+    start.set_wrap_mode(gtk::pango::WrapMode::WordChar);
+    start.set_selectable(true);
+    container.append(&start);
+
+    if !headers.is_empty() {
+        let grid = gtk::Grid::new();
+        grid.set_column_spacing(8);
+        grid.set_row_spacing(2);
+        for (row, header) in (0..).zip(headers) {
+            let name = gtk::Label::new(Some(header.name()));
+            name.add_css_class("monospace");
+            name.add_css_class("dim-label");
+            name.set_xalign(0.0);
+            name.set_yalign(0.0);
+            name.set_selectable(true);
+            grid.attach(&name, 0, row, 1, 1);
+
+            let value = gtk::Label::new(Some(header.value()));
+            value.add_css_class("monospace");
+            value.set_xalign(0.0);
+            value.set_hexpand(true);
+            value.set_wrap(true);
+            value.set_wrap_mode(gtk::pango::WrapMode::WordChar);
+            value.set_selectable(true);
+            grid.attach(&value, 1, row, 1, 1);
+        }
+        container.append(&grid);
+    }
+
+    if !body.is_empty() {
+        let view = gtk::TextView::new();
+        view.set_editable(false);
+        view.set_cursor_visible(false);
+        view.set_monospace(true);
+        view.set_wrap_mode(gtk::WrapMode::WordChar);
+        view.set_top_margin(4);
+        view.set_bottom_margin(4);
+        view.set_left_margin(4);
+        view.set_right_margin(4);
+        view.buffer().set_text(body);
+        view.add_css_class("card");
+        container.append(&view);
+    }
 }
 
 #[relm4::component(pub)]
@@ -47,57 +124,114 @@ impl Component for ComponentModel {
     view! {
         #[root]
         gtk::Box {
-            set_spacing: 8,
-
-            gtk::Frame {
-                set_height_request: super::EVIDENCE_HEIGHT_REQUEST,
-                set_label: Some(&lang::lookup("evidence-http-request")),
-
-                gtk::ScrolledWindow {
-                    set_hexpand: true,
-
-                    gtk::TextView {
-                        add_css_class: "monospace",
-                        set_left_margin: 8,
-                        set_right_margin: 8,
-                        set_top_margin: 8,
-                        set_bottom_margin: 8,
-                        set_halign: gtk::Align::Fill,
-                        set_valign: gtk::Align::Fill,
-
-                        #[name = "request_text_buffer"]
-                        #[wrap(Some)]
-                        set_buffer = &gtk::TextBuffer {
-                            set_text: &init.request,
-                            connect_changed => ComponentInput::Internal(ComponentInputInternal::RequestChanged),
-                        }
-                    }
-                }
+            set_orientation: gtk::Orientation::Vertical,
+            set_spacing: 4,
+
+            gtk::Box {
+                set_spacing: 8,
+
+                gtk::StackSwitcher {
+                    set_stack: Some(&stack),
+                },
+                gtk::Label {
+                    set_visible: init.duration.is_some(),
+                    add_css_class: "dim-label",
+                    set_label: &init.duration.map(|duration| {
+                        format!("{:.3}s", duration.as_secs_f64())
+                    }).unwrap_or_default(),
+                },
             },
-            gtk::Frame {
-                set_height_request: super::EVIDENCE_HEIGHT_REQUEST,
-                set_label: Some(&lang::lookup("evidence-http-response")),
-
-                gtk::ScrolledWindow {
-                    set_hexpand: true,
-
-                    gtk::TextView {
-                        add_css_class: "monospace",
-                        set_left_margin: 8,
-                        set_right_margin: 8,
-                        set_top_margin: 8,
-                        set_bottom_margin: 8,
-                        set_halign: gtk::Align::Fill,
-                        set_valign: gtk::Align::Fill,
-
-                        #[name = "response_text_buffer"]
-                        #[wrap(Some)]
-                        set_buffer = &gtk::TextBuffer {
-                            set_text: &init.response,
-                            connect_changed => ComponentInput::Internal(ComponentInputInternal::ResponseChanged),
+
+            #[name = "stack"]
+            gtk::Stack {
+                add_titled[Some("formatted"), &lang::lookup("evidence-http-formatted")] = &gtk::Box {
+                    set_spacing: 8,
+
+                    gtk::Frame {
+                        set_height_request: super::EVIDENCE_HEIGHT_REQUEST,
+                        set_label: Some(&lang::lookup("evidence-http-request")),
+
+                        gtk::ScrolledWindow {
+                            set_hexpand: true,
+
+                            #[name = "request_formatted"]
+                            gtk::Box {
+                                set_orientation: gtk::Orientation::Vertical,
+                                set_spacing: 8,
+                                set_margin_all: 8,
+                            }
+                        }
+                    },
+                    gtk::Frame {
+                        set_height_request: super::EVIDENCE_HEIGHT_REQUEST,
+                        set_label: Some(&lang::lookup("evidence-http-response")),
+
+                        gtk::ScrolledWindow {
+                            set_hexpand: true,
+
+                            #[name = "response_formatted"]
+                            gtk::Box {
+                                set_orientation: gtk::Orientation::Vertical,
+                                set_spacing: 8,
+                                set_margin_all: 8,
+                            }
+                        }
+                    },
+                },
+                add_titled[Some("raw"), &lang::lookup("evidence-http-raw")] = &gtk::Box {
+                    set_spacing: 8,
+
+                    gtk::Frame {
+                        set_height_request: super::EVIDENCE_HEIGHT_REQUEST,
+                        set_label: Some(&lang::lookup("evidence-http-request")),
+
+                        gtk::ScrolledWindow {
+                            set_hexpand: true,
+
+                            gtk::TextView {
+                                add_css_class: "monospace",
+                                set_left_margin: 8,
+                                set_right_margin: 8,
+                                set_top_margin: 8,
+                                set_bottom_margin: 8,
+                                set_halign: gtk::Align::Fill,
+                                set_valign: gtk::Align::Fill,
+
+                                #[name = "request_text_buffer"]
+                                #[wrap(Some)]
+                                set_buffer = &gtk::TextBuffer {
+                                    set_text: &init.request,
+                                    connect_changed => ComponentInput::Internal(ComponentInputInternal::RequestChanged),
+                                }
+                            }
                         }
-                    }
-                }
+                    },
+                    gtk::Frame {
+                        set_height_request: super::EVIDENCE_HEIGHT_REQUEST,
+                        set_label: Some(&lang::lookup("evidence-http-response")),
+
+                        gtk::ScrolledWindow {
+                            set_hexpand: true,
+
+                            gtk::TextView {
+                                add_css_class: "monospace",
+                                set_left_margin: 8,
+                                set_right_margin: 8,
+                                set_top_margin: 8,
+                                set_bottom_margin: 8,
+                                set_halign: gtk::Align::Fill,
+                                set_valign: gtk::Align::Fill,
+
+                                #[name = "response_text_buffer"]
+                                #[wrap(Some)]
+                                set_buffer = &gtk::TextBuffer {
+                                    set_text: &init.response,
+                                    connect_changed => ComponentInput::Internal(ComponentInputInternal::ResponseChanged),
+                                }
+                            }
+                        }
+                    },
+                },
             },
         }
     }
@@ -107,8 +241,16 @@ impl Component for ComponentModel {
         root: Self::Root,
         sender: ComponentSender<Self>,
     ) -> ComponentParts<Self> {
-        let model = ComponentModel {};
+        let model = ComponentModel {
+            request: init.request.clone(),
+            response: init.response.clone(),
+        };
         let widgets = view_output!();
+        model.update_formatted(&widgets);
+        // New evidence has nothing to format, so start by editing it
+        if init.request.trim().is_empty() {
+            widgets.stack.set_visible_child_name("raw");
+        }
         ComponentParts { model, widgets }
     }
 
@@ -125,6 +267,7 @@ impl Component for ComponentModel {
                 let new_text = buf
                     .text(&buf.start_iter(), &buf.end_iter(), false)
                     .to_string();
+                self.request.clone_from(&new_text);
                 let _ = sender.output(ComponentOutput::RequestChanged { new_text });
             }
             ComponentInput::Internal(ComponentInputInternal::ResponseChanged) => {
@@ -132,8 +275,45 @@ impl Component for ComponentModel {
                 let new_text = buf
                     .text(&buf.start_iter(), &buf.end_iter(), false)
                     .to_string();
+                self.response.clone_from(&new_text);
                 let _ = sender.output(ComponentOutput::ResponseChanged { new_text });
             }
         }
+        self.update_formatted(widgets);
+    }
+}
+
+impl ComponentModel {
+    /// Rebuild the formatted view from the current request and response.
+    fn update_formatted(&self, widgets: &ComponentModelWidgets) {
+        let request = HttpRequest::parse(&self.request);
+        fill_message(
+            &widgets.request_formatted,
+            &request.start_line(),
+            None,
+            request.headers(),
+            &request.pretty_body(),
+        );
+
+        if self.response.trim().is_empty() {
+            clear(&widgets.response_formatted);
+            let label = gtk::Label::new(Some(&lang::lookup("evidence-http-no-response")));
+            label.add_css_class("dim-label");
+            widgets.response_formatted.append(&label);
+        } else {
+            let response = HttpResponse::parse(&self.response);
+            let status_class = match response.status() {
+                Some(_) if response.is_success() => Some("success"),
+                Some(_) => Some("error"),
+                None => None,
+            };
+            fill_message(
+                &widgets.response_formatted,
+                &response.start_line(),
+                status_class,
+                response.headers(),
+                &response.pretty_body(),
+            );
+        }
     }
 }
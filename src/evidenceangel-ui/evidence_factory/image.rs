@@ -1,18 +1,50 @@
 use std::{fs, sync::Arc};
 
-use evidenceangel::{Evidence, EvidencePackage};
+use evidenceangel::{Evidence, EvidenceData, EvidencePackage};
 use gtk::prelude::*;
 use parking_lot::RwLock;
-use relm4::{Component, ComponentParts, ComponentSender, gtk};
+use relm4::{Component, ComponentController, ComponentParts, ComponentSender, Controller, gtk};
 use tempfile::TempDir;
 
-use crate::lang;
+use crate::{
+    dialogs::annotate::{AnnotateDialogInit, AnnotateDialogModel, AnnotateInput, AnnotateOutput},
+    lang,
+};
 
 pub struct ComponentModel {
     texture: Option<gtk::gdk::Texture>,
     package: Arc<RwLock<EvidencePackage>>,
     evidence: Evidence,
+    details: Option<String>,
     temp_files: Vec<TempDir>,
+    latest_annotate_dlg: Option<Controller<AnnotateDialogModel>>,
+}
+
+/// Load the texture to display for some image evidence. The thumbnail is preferred where
+/// available, to avoid decoding full size images, but can't be used if the image is annotated.
+fn load_texture(
+    evidence: &Evidence,
+    package: &Arc<RwLock<EvidencePackage>>,
+) -> Option<gtk::gdk::Texture> {
+    let mut pkg = package.write();
+    let data = if evidence.annotations().is_empty() {
+        match evidence.value() {
+            EvidenceData::Media { hash } => pkg
+                .get_thumbnail(hash)
+                .ok()
+                .flatten()
+                .map(|thumbnail| thumbnail.data().clone()),
+            _ => None,
+        }
+    } else {
+        evidence
+            .annotated_data(&mut pkg)
+            .inspect_err(|e| tracing::error!("Failed to render annotations: {e}"))
+            .ok()
+    }
+    .unwrap_or_else(|| evidence.data(&mut pkg));
+    let glib_bytes = gtk::glib::Bytes::from_owned(data);
+    gtk::gdk::Texture::from_bytes(&glib_bytes).ok()
 }
 
 #[derive(Debug)]
@@ -28,10 +60,15 @@ pub enum ComponentInput {
 #[derive(Debug)]
 enum ComponentInputInternal {
     Enlarge,
+    Annotate,
+    UpdateEvidence(Evidence),
 }
 
 #[derive(Debug)]
-pub enum ComponentOutput {}
+pub enum ComponentOutput {
+    /// The evidence has been changed, such as by being annotated.
+    EvidenceChanged(Evidence),
+}
 
 pub struct ComponentInit {
     pub evidence: Evidence,
@@ -52,16 +89,37 @@ impl Component for ComponentModel {
             set_spacing: 4,
 
             gtk::Picture {
+                #[watch]
                 set_paintable: model.texture.as_ref(),
                 set_hexpand: true,
                 set_height_request: super::EVIDENCE_HEIGHT_REQUEST,
             },
-            gtk::Button {
-                set_label: &lang::lookup("expand-image"),
-                add_css_class: "flat",
+            gtk::Label {
+                add_css_class: "dim-label",
+                add_css_class: "caption",
+                set_ellipsize: gtk::pango::EllipsizeMode::End,
+                #[watch]
+                set_visible: model.details.is_some(),
+                #[watch]
+                set_label: model.details.as_deref().unwrap_or_default(),
+            },
+            gtk::Box {
+                set_orientation: gtk::Orientation::Horizontal,
+                set_spacing: 4,
                 set_halign: gtk::Align::Center,
 
-                connect_clicked => ComponentInput::Internal(ComponentInputInternal::Enlarge),
+                gtk::Button {
+                    set_label: &lang::lookup("expand-image"),
+                    add_css_class: "flat",
+
+                    connect_clicked => ComponentInput::Internal(ComponentInputInternal::Enlarge),
+                },
+                gtk::Button {
+                    set_label: &lang::lookup("annotate-image"),
+                    add_css_class: "flat",
+
+                    connect_clicked => ComponentInput::Internal(ComponentInputInternal::Annotate),
+                },
             },
         }
     }
@@ -74,13 +132,13 @@ impl Component for ComponentModel {
         let ComponentInit {
             evidence, package, ..
         } = init;
-        let glib_bytes = gtk::glib::Bytes::from_owned(evidence.data(&mut package.write()));
-        let texture = gtk::gdk::Texture::from_bytes(&glib_bytes).ok();
         let model = ComponentModel {
-            texture,
+            texture: load_texture(&evidence, &package),
+            details: super::media_details(&evidence, &package),
             package,
             evidence,
             temp_files: vec![],
+            latest_annotate_dlg: None,
         };
         let widgets = view_output!();
         ComponentParts { model, widgets }
@@ -88,14 +146,39 @@ impl Component for ComponentModel {
 
     fn update_with_view(
         &mut self,
-        _widgets: &mut Self::Widgets,
+        widgets: &mut Self::Widgets,
         message: Self::Input,
-        _sender: ComponentSender<Self>,
-        _root: &Self::Root,
+        sender: ComponentSender<Self>,
+        root: &Self::Root,
     ) {
         match message {
+            ComponentInput::Internal(ComponentInputInternal::Annotate) => {
+                let annotate_dlg = AnnotateDialogModel::builder()
+                    .launch(AnnotateDialogInit {
+                        evidence: self.evidence.clone(),
+                        package: self.package.clone(),
+                    })
+                    .forward(sender.input_sender(), |msg| match msg {
+                        AnnotateOutput::UpdateEvidence(evidence) => ComponentInput::Internal(
+                            ComponentInputInternal::UpdateEvidence(evidence),
+                        ),
+                    });
+                annotate_dlg.emit(AnnotateInput::Present(root.clone().upcast()));
+                self.latest_annotate_dlg = Some(annotate_dlg);
+            }
+            ComponentInput::Internal(ComponentInputInternal::UpdateEvidence(evidence)) => {
+                self.evidence = evidence;
+                self.texture = load_texture(&self.evidence, &self.package);
+                self.details = super::media_details(&self.evidence, &self.package);
+                sender
+                    .output(ComponentOutput::EvidenceChanged(self.evidence.clone()))
+                    .unwrap();
+            }
             ComponentInput::Internal(ComponentInputInternal::Enlarge) => {
-                let data = self.evidence.data(&mut self.package.write());
+                let data = self
+                    .evidence
+                    .annotated_data(&mut self.package.write())
+                    .unwrap_or_else(|_| self.evidence.data(&mut self.package.write()));
 
                 // Create temporary directory
                 if let Ok(target_dir) = tempfile::tempdir() {
@@ -104,7 +187,8 @@ impl Component for ComponentModel {
                             .original_filename()
                             .clone()
                             .unwrap_or_else(|| {
-                                let maybe_extension = infer::get(&data).map(|ty| ty.extension());
+                                let maybe_extension =
+                                    super::media_extension(&self.evidence, &self.package);
                                 format!(
                                     "image{}{}",
                                     if maybe_extension.is_some() { "." } else { "" },
@@ -126,5 +210,6 @@ impl Component for ComponentModel {
                 }
             }
         }
+        self.update_view(widgets, sender);
     }
 }
@@ -0,0 +1,81 @@
+use adw::prelude::*;
+use evidenceangel::{Link, LinkSystem};
+use relm4::{Component, ComponentParts, ComponentSender, adw, gtk};
+
+use crate::lang;
+
+pub struct ComponentModel;
+
+#[derive(Debug)]
+pub enum ComponentInput {}
+
+#[derive(Debug)]
+pub enum ComponentOutput {}
+
+pub struct ComponentInit {
+    pub link: Link,
+}
+
+/// The key of the translated name of a type of system.
+fn system_key(system: LinkSystem) -> &'static str {
+    match system {
+        LinkSystem::Defect => "evidence-link-system-defect",
+        LinkSystem::Ticket => "evidence-link-system-ticket",
+        LinkSystem::Build => "evidence-link-system-build",
+        LinkSystem::Documentation => "evidence-link-system-documentation",
+        LinkSystem::Other => "evidence-link-system-other",
+    }
+}
+
+#[relm4::component(pub)]
+impl Component for ComponentModel {
+    type CommandOutput = ();
+    type Input = ComponentInput;
+    type Output = ComponentOutput;
+    type Init = ComponentInit;
+
+    view! {
+        #[root]
+        gtk::ListBox {
+            add_css_class: "boxed-list",
+            set_selection_mode: gtk::SelectionMode::None,
+
+            adw::ActionRow {
+                set_use_markup: false,
+                set_title: init.link.display_title(),
+                set_subtitle: if init.link.display_title() == init.link.url() {
+                    ""
+                } else {
+                    init.link.url()
+                },
+                set_subtitle_selectable: true,
+                set_tooltip_text: Some(init.link.url()),
+                set_activatable: init.link.is_safe_to_open(),
+                connect_activated[url = init.link.url().clone()] => move |_| {
+                    open::that_in_background(&url);
+                },
+
+                add_prefix = &gtk::Label {
+                    set_visible: init.link.system().is_some(),
+                    add_css_class: "caption-heading",
+                    add_css_class: "dim-label",
+                    set_label: &init.link.system().map(|system| lang::lookup(system_key(system))).unwrap_or_default(),
+                },
+                add_suffix = &gtk::Image {
+                    set_visible: init.link.is_safe_to_open(),
+                    set_icon_name: Some("adw-external-link-symbolic"),
+                },
+            },
+        }
+    }
+
+    fn init(
+        init: Self::Init,
+        root: Self::Root,
+        _sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let model = ComponentModel;
+        let widgets = view_output!();
+        ComponentParts { model, widgets }
+    }
+}
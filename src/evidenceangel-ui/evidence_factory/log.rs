@@ -0,0 +1,200 @@
+use std::{cell::RefCell, rc::Rc};
+
+use evidenceangel::{Log, LogEntry, LogLevel};
+use gtk::prelude::*;
+use relm4::{Component, ComponentParts, ComponentSender, gtk};
+
+use crate::{lang, lang_args};
+
+/// The current filter applied to the entries shown.
+#[derive(Default)]
+struct Filter {
+    min_level: Option<LogLevel>,
+    search: String,
+}
+
+pub struct ComponentModel {
+    filter: Rc<RefCell<Filter>>,
+}
+
+#[derive(Debug)]
+pub enum ComponentInput {
+    /// An internal message was triggered
+    #[allow(
+        private_interfaces,
+        reason = "These messages should only be produced by this component."
+    )]
+    Internal(ComponentInputInternal),
+}
+
+#[derive(Debug)]
+enum ComponentInputInternal {
+    SetMinLevel(u32),
+    SetSearch(String),
+}
+
+#[derive(Debug)]
+pub enum ComponentOutput {}
+
+pub struct ComponentInit {
+    pub log: Log,
+}
+
+/// Create a row of the list for a log entry.
+fn entry_row(entry: &LogEntry) -> gtk::Box {
+    let row = gtk::Box::new(gtk::Orientation::Horizontal, 8);
+    row.set_margin_top(2);
+    row.set_margin_bottom(2);
+    row.set_margin_start(4);
+    row.set_margin_end(4);
+
+    let timestamp = gtk::Label::new(
+        entry
+            .timestamp()
+            .map(|timestamp| timestamp.format("%Y-%m-%d %H:%M:%S").to_string())
+            .as_deref(),
+    );
+    timestamp.add_css_class("dim-label");
+    timestamp.add_css_class("monospace");
+    timestamp.set_valign(gtk::Align::Start);
+    row.append(&timestamp);
+
+    let level = gtk::Label::new(entry.level().map(|level| level.to_string()).as_deref());
+    level.add_css_class("monospace");
+    level.set_width_chars(5);
+    level.set_xalign(0.0);
+    level.set_valign(gtk::Align::Start);
+    match entry.level() {
+        Some(LogLevel::Trace | LogLevel::Debug) => level.add_css_class("dim-label"),
+        Some(LogLevel::Warn) => level.add_css_class("warning"),
+        Some(LogLevel::Error | LogLevel::Fatal) => level.add_css_class("error"),
+        _ => level.add_css_class("success"),
+    }
+    row.append(&level);
+
+    if let Some(source) = entry.source() {
+        let source = gtk::Label::new(Some(source));
+        source.add_css_class("dim-label");
+        source.set_valign(gtk::Align::Start);
+        row.append(&source);
+    }
+
+    let message = gtk::Label::new(Some(entry.message()));
+    message.add_css_class("monospace");
+    message.set_xalign(0.0);
+    message.set_hexpand(true);
+    message.set_wrap(true);
+    message.set_wrap_mode(gtk::pango::WrapMode::WordChar);
+    message.set_selectable(true);
+    row.append(&message);
+
+    row
+}
+
+#[relm4::component(pub)]
+impl Component for ComponentModel {
+    type CommandOutput = ();
+    type Input = ComponentInput;
+    type Output = ComponentOutput;
+    type Init = ComponentInit;
+
+    view! {
+        #[root]
+        gtk::Box {
+            set_orientation: gtk::Orientation::Vertical,
+            set_spacing: 4,
+
+            gtk::Box {
+                set_orientation: gtk::Orientation::Horizontal,
+                set_spacing: 8,
+
+                gtk::DropDown {
+                    set_model: Some(&gtk::StringList::new(&level_names)),
+                    connect_selected_notify[sender] => move |dropdown| {
+                        sender.input(ComponentInput::Internal(
+                            ComponentInputInternal::SetMinLevel(dropdown.selected()),
+                        ));
+                    },
+                },
+                gtk::SearchEntry {
+                    set_hexpand: true,
+                    set_placeholder_text: Some(&lang::lookup("evidence-log-filter")),
+                    connect_search_changed[sender] => move |entry| {
+                        sender.input(ComponentInput::Internal(
+                            ComponentInputInternal::SetSearch(entry.text().to_string()),
+                        ));
+                    },
+                },
+            },
+            gtk::ScrolledWindow {
+                set_height_request: super::EVIDENCE_HEIGHT_REQUEST,
+                set_hexpand: true,
+
+                #[name = "list"]
+                gtk::ListBox {
+                    set_selection_mode: gtk::SelectionMode::None,
+                    add_css_class: "boxed-list",
+                },
+            },
+            gtk::Label {
+                add_css_class: "dim-label",
+                add_css_class: "caption",
+                set_label: &lang::lookup_with_args(
+                    "evidence-log-entries",
+                    &lang_args!("count", init.log.entries().len()),
+                ),
+            },
+        }
+    }
+
+    fn init(
+        init: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let model = ComponentModel {
+            filter: Rc::new(RefCell::new(Filter::default())),
+        };
+        let level_names = std::iter::once(lang::lookup("evidence-log-all-levels"))
+            .chain(LogLevel::ALL.iter().map(ToString::to_string))
+            .collect::<Vec<_>>();
+        let level_names = level_names.iter().map(String::as_str).collect::<Vec<_>>();
+        let widgets = view_output!();
+
+        for entry in init.log.entries() {
+            widgets.list.append(&entry_row(entry));
+        }
+        let entries = Rc::new(init.log.entries().clone());
+        let filter = model.filter.clone();
+        widgets.list.set_filter_func(move |row| {
+            let filter = filter.borrow();
+            usize::try_from(row.index())
+                .ok()
+                .and_then(|index| entries.get(index))
+                .is_none_or(|entry| entry.matches(filter.min_level, &filter.search))
+        });
+
+        ComponentParts { model, widgets }
+    }
+
+    fn update_with_view(
+        &mut self,
+        widgets: &mut Self::Widgets,
+        message: Self::Input,
+        _sender: ComponentSender<Self>,
+        _root: &Self::Root,
+    ) {
+        match message {
+            ComponentInput::Internal(ComponentInputInternal::SetMinLevel(selected)) => {
+                // The first option shows all levels
+                self.filter.borrow_mut().min_level = (selected as usize)
+                    .checked_sub(1)
+                    .and_then(|index| LogLevel::ALL.get(index).copied());
+            }
+            ComponentInput::Internal(ComponentInputInternal::SetSearch(search)) => {
+                self.filter.borrow_mut().search = search;
+            }
+        }
+        widgets.list.invalidate_filter();
+    }
+}
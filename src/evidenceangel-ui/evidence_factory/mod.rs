@@ -1,7 +1,10 @@
 use std::{any::Any, sync::Arc};
 
 use adw::prelude::*;
-use evidenceangel::{Evidence, EvidenceData, EvidenceKind, EvidencePackage};
+use evidenceangel::{
+    CommandExecution, Diff, Evidence, EvidenceData, EvidenceKind, EvidencePackage, HTTP_SEPARATOR,
+    HttpExchange, Link, Log, SqlQuery, StructuredEvidence, Table,
+};
 #[allow(unused_imports)]
 use gtk::prelude::*;
 use parking_lot::RwLock;
@@ -12,17 +15,82 @@ use relm4::{
     prelude::{DynamicIndex, FactoryComponent},
 };
 
-use crate::lang;
 use crate::util::BoxedEvidenceJson;
+use crate::{lang, lang_args};
 
+mod command;
+mod diff;
 mod file;
 mod http;
 mod image;
+mod link;
+mod log;
 mod rich_text;
+mod sql;
+mod table;
 mod text;
+mod video;
 
 const EVIDENCE_HEIGHT_REQUEST: i32 = 300;
-const HTTP_SEPARATOR: char = '\x1e';
+
+/// Replace the request (`0`) or response (`1`) section of HTTP evidence, keeping the other
+/// sections as they are.
+fn replace_http_section(data: &str, index: usize, text: &str) -> String {
+    let mut sections = data.splitn(3, HTTP_SEPARATOR).collect::<Vec<_>>();
+    if sections.len() < 2 {
+        sections.resize(2, "");
+    }
+    sections[index] = text;
+    sections.join(&HTTP_SEPARATOR.to_string())
+}
+
+/// Describe the details recorded against the media referenced by some evidence, such as its
+/// type, size, dimensions, duration and capture time.
+fn media_details(evidence: &Evidence, package: &Arc<RwLock<EvidencePackage>>) -> Option<String> {
+    let EvidenceData::Media { hash } = evidence.value() else {
+        return None;
+    };
+    let mut pkg = package.write();
+    let media = pkg.get_media(hash).ok().flatten()?;
+
+    let mut details = vec![
+        media.media_type().to_string(),
+        gtk::glib::format_size(media.size()).to_string(),
+    ];
+    if let Some((width, height)) = media.image_dimensions() {
+        details.push(lang::lookup_with_args(
+            "media-details-dimensions",
+            &lang_args!("width", width, "height", height),
+        ));
+    }
+    if let Some(duration) = media.video_duration() {
+        let seconds = duration.as_secs();
+        details.push(lang::lookup_with_args(
+            "media-details-duration",
+            &lang_args!("duration", format!("{}:{:02}", seconds / 60, seconds % 60)),
+        ));
+    }
+    if let Some(captured_at) = media.captured_at() {
+        details.push(lang::lookup_with_args(
+            "media-details-captured",
+            &lang_args!("time", captured_at.format("%Y-%m-%d %H:%M:%S").to_string()),
+        ));
+    }
+    Some(details.join(" · "))
+}
+
+/// Determine a suitable file extension for the media referenced by some evidence.
+fn media_extension(evidence: &Evidence, package: &Arc<RwLock<EvidencePackage>>) -> Option<String> {
+    let EvidenceData::Media { hash } = evidence.value() else {
+        return None;
+    };
+    package
+        .write()
+        .get_media(hash)
+        .ok()
+        .flatten()
+        .and_then(evidenceangel::MediaFile::extension)
+}
 
 pub struct EvidenceFactoryModel {
     sub_component: Box<dyn Any>,
@@ -54,6 +122,8 @@ pub enum EvidenceFactoryInput {
     HttpSetResponse(String),
     /// Set the caption for this evidence.
     SetCaption(String),
+    /// Replace this evidence entirely, such as after it has been annotated.
+    ReplaceEvidence(Evidence),
     MoveUp,
     MoveDown,
     Delete,
@@ -234,7 +304,11 @@ impl FactoryComponent for EvidenceFactoryModel {
                         evidence: self.evidence.read().clone(),
                         package: self.package.clone(),
                     })
-                    .forward(sender.input_sender(), |msg| match msg {});
+                    .forward(sender.input_sender(), |msg| match msg {
+                        image::ComponentOutput::EvidenceChanged(evidence) => {
+                            EvidenceFactoryInput::ReplaceEvidence(evidence)
+                        }
+                    });
                 widgets.evidence_child.set_child(Some(component.widget()));
                 self.sub_component = Box::new(component);
             }
@@ -246,9 +320,14 @@ impl FactoryComponent for EvidenceFactoryModel {
                     .collect::<Vec<_>>();
                 let request = data_parts.first().cloned().unwrap_or_default();
                 let response = data_parts.get(1).cloned().unwrap_or_default();
+                let duration = HttpExchange::parse(&data).duration();
 
                 let component = http::ComponentModel::builder()
-                    .launch(http::ComponentInit { request, response })
+                    .launch(http::ComponentInit {
+                        request,
+                        response,
+                        duration,
+                    })
                     .forward(sender.input_sender(), |msg| match msg {
                         http::ComponentOutput::RequestChanged { new_text } => {
                             EvidenceFactoryInput::HttpSetRequest(new_text)
@@ -271,6 +350,80 @@ impl FactoryComponent for EvidenceFactoryModel {
                 widgets.evidence_child.set_child(Some(component.widget()));
                 self.sub_component = Box::new(component);
             }
+            EvidenceKind::Video => {
+                let component = video::ComponentModel::builder()
+                    .launch(video::ComponentInit {
+                        evidence: self.evidence.read().clone(),
+                        package: self.package.clone(),
+                    })
+                    .forward(sender.input_sender(), |msg| match msg {});
+                widgets.evidence_child.set_child(Some(component.widget()));
+                self.sub_component = Box::new(component);
+            }
+            EvidenceKind::Log => {
+                let log = Log::from_evidence(&self.evidence.read(), &mut self.package.write())
+                    .inspect_err(|e| tracing::error!("Failed to read log evidence: {e}"))
+                    .unwrap_or_default();
+                let component = log::ComponentModel::builder()
+                    .launch(log::ComponentInit { log })
+                    .forward(sender.input_sender(), |msg| match msg {});
+                widgets.evidence_child.set_child(Some(component.widget()));
+                self.sub_component = Box::new(component);
+            }
+            EvidenceKind::Command => {
+                let command = CommandExecution::from_evidence(
+                    &self.evidence.read(),
+                    &mut self.package.write(),
+                )
+                .inspect_err(|e| tracing::error!("Failed to read command evidence: {e}"))
+                .unwrap_or_default();
+                let component = command::ComponentModel::builder()
+                    .launch(command::ComponentInit { command })
+                    .forward(sender.input_sender(), |msg| match msg {});
+                widgets.evidence_child.set_child(Some(component.widget()));
+                self.sub_component = Box::new(component);
+            }
+            EvidenceKind::Link => {
+                let link = Link::from_evidence(&self.evidence.read(), &mut self.package.write())
+                    .inspect_err(|e| tracing::error!("Failed to read link evidence: {e}"))
+                    .unwrap_or_default();
+                let component = link::ComponentModel::builder()
+                    .launch(link::ComponentInit { link })
+                    .forward(sender.input_sender(), |msg| match msg {});
+                widgets.evidence_child.set_child(Some(component.widget()));
+                self.sub_component = Box::new(component);
+            }
+            EvidenceKind::Sql => {
+                let query =
+                    SqlQuery::from_evidence(&self.evidence.read(), &mut self.package.write())
+                        .inspect_err(|e| tracing::error!("Failed to read SQL evidence: {e}"))
+                        .unwrap_or_default();
+                let component = sql::ComponentModel::builder()
+                    .launch(sql::ComponentInit { query })
+                    .forward(sender.input_sender(), |msg| match msg {});
+                widgets.evidence_child.set_child(Some(component.widget()));
+                self.sub_component = Box::new(component);
+            }
+            EvidenceKind::Diff => {
+                let diff = Diff::from_evidence(&self.evidence.read(), &mut self.package.write())
+                    .inspect_err(|e| tracing::error!("Failed to read diff evidence: {e}"))
+                    .unwrap_or_default();
+                let component = diff::ComponentModel::builder()
+                    .launch(diff::ComponentInit { diff })
+                    .forward(sender.input_sender(), |msg| match msg {});
+                widgets.evidence_child.set_child(Some(component.widget()));
+                self.sub_component = Box::new(component);
+            }
+            EvidenceKind::Table => {
+                let table = Table::from_evidence(&self.evidence.read(), &mut self.package.write())
+                    .inspect_err(|e| tracing::error!("Failed to read table evidence: {e}"))
+                    .unwrap_or_default();
+                let component = table::ComponentModel::builder()
+                    .launch(table::ComponentInit { table })
+                    .forward(sender.input_sender(), |msg| match msg {});
+                widgets.evidence_child.set_child(Some(component.widget()));
+                self.sub_component = Box::new(component);
+            }
         };
 
         widgets
@@ -287,6 +440,15 @@ impl FactoryComponent for EvidenceFactoryModel {
                     ))
                     .unwrap();
             }
+            EvidenceFactoryInput::ReplaceEvidence(evidence) => {
+                *self.evidence.write() = evidence;
+                sender
+                    .output(EvidenceFactoryOutput::UpdateEvidence(
+                        self.index.clone(),
+                        self.evidence.read().clone(),
+                    ))
+                    .unwrap();
+            }
             EvidenceFactoryInput::TextSetText(new_text) => {
                 if ![EvidenceKind::Text, EvidenceKind::RichText]
                     .contains(self.evidence.read().kind())
@@ -309,32 +471,17 @@ impl FactoryComponent for EvidenceFactoryModel {
                     ))
                     .unwrap();
             }
-            EvidenceFactoryInput::HttpSetRequest(mut new_req) => {
+            EvidenceFactoryInput::HttpSetRequest(new_req) => {
                 if *self.evidence.read().kind() != EvidenceKind::Http {
                     return;
                 }
                 match self.evidence.write().value_mut() {
                     EvidenceData::Text { content } => {
-                        let data_parts = content
-                            .split(HTTP_SEPARATOR)
-                            .map(ToString::to_string)
-                            .collect::<Vec<_>>();
-                        let response = data_parts.get(1).cloned().unwrap_or_default();
-
-                        new_req.push(HTTP_SEPARATOR);
-                        new_req.push_str(&response);
-                        *content = new_req;
+                        *content = replace_http_section(content, 0, &new_req);
                     }
                     EvidenceData::Base64 { data } => {
-                        let data_parts = String::from_utf8_lossy(data)
-                            .split(HTTP_SEPARATOR)
-                            .map(ToString::to_string)
-                            .collect::<Vec<_>>();
-                        let response = data_parts.get(1).cloned().unwrap_or_default();
-
-                        new_req.push(HTTP_SEPARATOR);
-                        new_req.push_str(&response);
-                        *data = new_req.into_bytes();
+                        *data = replace_http_section(&String::from_utf8_lossy(data), 0, &new_req)
+                            .into_bytes();
                     }
                     EvidenceData::Media { .. } => panic!("cannot handle text of media type"),
                 }
@@ -351,26 +498,11 @@ impl FactoryComponent for EvidenceFactoryModel {
                 }
                 match self.evidence.write().value_mut() {
                     EvidenceData::Text { content } => {
-                        let data_parts = content
-                            .split(HTTP_SEPARATOR)
-                            .map(ToString::to_string)
-                            .collect::<Vec<_>>();
-                        let mut request = data_parts.first().cloned().unwrap_or_default();
-
-                        request.push(HTTP_SEPARATOR);
-                        request.push_str(&new_res);
-                        *content = request;
+                        *content = replace_http_section(content, 1, &new_res);
                     }
                     EvidenceData::Base64 { data } => {
-                        let data_parts = String::from_utf8_lossy(data)
-                            .split(HTTP_SEPARATOR)
-                            .map(ToString::to_string)
-                            .collect::<Vec<_>>();
-                        let mut request = data_parts.first().cloned().unwrap_or_default();
-
-                        request.push(HTTP_SEPARATOR);
-                        request.push_str(&new_res);
-                        *data = request.into_bytes();
+                        *data = replace_http_section(&String::from_utf8_lossy(data), 1, &new_res)
+                            .into_bytes();
                     }
                     EvidenceData::Media { .. } => panic!("cannot handle text of media type"),
                 }
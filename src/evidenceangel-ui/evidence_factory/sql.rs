@@ -0,0 +1,136 @@
+use evidenceangel::{SqlQuery, SqlToken};
+use gtk::{glib::translate::IntoGlib, prelude::*};
+use relm4::{Component, ComponentController, ComponentParts, ComponentSender, Controller, gtk};
+
+use crate::{lang, lang_args};
+
+use super::table;
+
+pub struct ComponentModel {
+    /// The table showing the rows returned by the query, if there are any
+    _result: Option<Controller<table::ComponentModel>>,
+}
+
+#[derive(Debug)]
+pub enum ComponentInput {}
+
+#[derive(Debug)]
+pub enum ComponentOutput {}
+
+pub struct ComponentInit {
+    pub query: SqlQuery,
+}
+
+/// Add tags to a text buffer to highlight each kind of token in a query.
+fn add_tags(buffer: &gtk::TextBuffer) {
+    let tags = buffer.tag_table();
+    for (name, color) in [
+        ("keyword", "#3584e4"),
+        ("string", "#e01b24"),
+        ("number", "#2ec27e"),
+        ("comment", "gray"),
+        ("parameter", "#9141ac"),
+    ] {
+        let tag = gtk::TextTag::new(Some(name));
+        tag.set_foreground(Some(color));
+        match name {
+            "keyword" => tag.set_weight(gtk::pango::Weight::Bold.into_glib()),
+            "comment" => tag.set_style(gtk::pango::Style::Italic),
+            _ => (),
+        }
+        tags.add(&tag);
+    }
+}
+
+#[relm4::component(pub)]
+impl Component for ComponentModel {
+    type CommandOutput = ();
+    type Input = ComponentInput;
+    type Output = ComponentOutput;
+    type Init = ComponentInit;
+
+    view! {
+        #[root]
+        gtk::Box {
+            set_orientation: gtk::Orientation::Vertical,
+            set_spacing: 4,
+
+            gtk::ScrolledWindow {
+                set_propagate_natural_height: true,
+                set_max_content_height: super::EVIDENCE_HEIGHT_REQUEST,
+                set_hexpand: true,
+                add_css_class: "card",
+
+                #[name = "query_view"]
+                gtk::TextView {
+                    set_editable: false,
+                    set_cursor_visible: false,
+                    set_monospace: true,
+                    set_wrap_mode: gtk::WrapMode::WordChar,
+                    set_top_margin: 4,
+                    set_bottom_margin: 4,
+                    set_left_margin: 4,
+                    set_right_margin: 4,
+                },
+            },
+            gtk::Label {
+                add_css_class: "dim-label",
+                add_css_class: "caption",
+                set_xalign: 0.0,
+                set_label: &details,
+            },
+        }
+    }
+
+    fn init(
+        init: Self::Init,
+        root: Self::Root,
+        _sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let mut details = vec![];
+        if let Some(database) = init.query.database() {
+            details.push(database.clone());
+        }
+        details.push(lang::lookup_with_args(
+            "evidence-sql-rows",
+            &lang_args!("count", init.query.effective_row_count()),
+        ));
+        if let Some(duration) = init.query.duration() {
+            details.push(format!("{:.3}s", duration.as_secs_f64()));
+        }
+        let details = details.join(" · ");
+        let widgets = view_output!();
+
+        let buffer = widgets.query_view.buffer();
+        add_tags(&buffer);
+        for (token, text) in init.query.tokens() {
+            let tag = match token {
+                SqlToken::Keyword => "keyword",
+                SqlToken::String => "string",
+                SqlToken::Number => "number",
+                SqlToken::Comment => "comment",
+                SqlToken::Parameter => "parameter",
+                SqlToken::Identifier | SqlToken::Other => {
+                    buffer.insert(&mut buffer.end_iter(), text);
+                    continue;
+                }
+            };
+            buffer.insert_with_tags_by_name(&mut buffer.end_iter(), text, &[tag]);
+        }
+
+        let result = if init.query.result().columns().is_empty() {
+            None
+        } else {
+            let result = table::ComponentModel::builder()
+                .launch(table::ComponentInit {
+                    table: init.query.result().clone(),
+                })
+                .detach();
+            root.append(result.widget());
+            Some(result)
+        };
+
+        let model = ComponentModel { _result: result };
+        ComponentParts { model, widgets }
+    }
+}
@@ -0,0 +1,140 @@
+use std::rc::Rc;
+
+use evidenceangel::{Table, TableCell};
+use gtk::{gio, glib, prelude::*};
+use relm4::{Component, ComponentParts, ComponentSender, gtk};
+
+use crate::{lang, lang_args};
+
+pub struct ComponentModel;
+
+#[derive(Debug)]
+pub enum ComponentInput {}
+
+#[derive(Debug)]
+pub enum ComponentOutput {}
+
+pub struct ComponentInit {
+    pub table: Table,
+}
+
+/// Get the cell at a column of the row held by a list item.
+fn cell_of<'a>(rows: &'a [Vec<TableCell>], item: &glib::Object, column: usize) -> &'a TableCell {
+    /// The cell returned when a row doesn't have enough cells
+    static EMPTY: TableCell = TableCell::Empty;
+
+    item.downcast_ref::<glib::BoxedAnyObject>()
+        .and_then(|boxed| rows.get(*boxed.borrow::<usize>()))
+        .and_then(|row| row.get(column))
+        .unwrap_or(&EMPTY)
+}
+
+/// Create a sortable column showing the cells at an index of each row.
+fn table_column(
+    title: &str,
+    rows: &Rc<Vec<Vec<TableCell>>>,
+    column: usize,
+) -> gtk::ColumnViewColumn {
+    let factory = gtk::SignalListItemFactory::new();
+    factory.connect_setup(|_, item| {
+        let label = gtk::Label::new(None);
+        label.set_xalign(0.0);
+        label.set_selectable(true);
+        if let Some(item) = item.downcast_ref::<gtk::ListItem>() {
+            item.set_child(Some(&label));
+        }
+    });
+    let bind_rows = rows.clone();
+    factory.connect_bind(move |_, item| {
+        let Some(item) = item.downcast_ref::<gtk::ListItem>() else {
+            return;
+        };
+        let (Some(row), Some(label)) = (item.item(), item.child().and_downcast::<gtk::Label>())
+        else {
+            return;
+        };
+        let cell = cell_of(&bind_rows, &row, column);
+        label.set_label(&cell.to_string());
+        label.set_xalign(if matches!(cell, TableCell::Number(_)) {
+            1.0
+        } else {
+            0.0
+        });
+    });
+
+    let sort_rows = rows.clone();
+    let sorter = gtk::CustomSorter::new(move |a, b| {
+        cell_of(&sort_rows, a, column)
+            .compare(cell_of(&sort_rows, b, column))
+            .into()
+    });
+
+    let column = gtk::ColumnViewColumn::new(Some(title), Some(factory));
+    column.set_sorter(Some(&sorter));
+    column.set_resizable(true);
+    column
+}
+
+#[relm4::component(pub)]
+impl Component for ComponentModel {
+    type CommandOutput = ();
+    type Input = ComponentInput;
+    type Output = ComponentOutput;
+    type Init = ComponentInit;
+
+    view! {
+        #[root]
+        gtk::Box {
+            set_orientation: gtk::Orientation::Vertical,
+            set_spacing: 4,
+
+            gtk::ScrolledWindow {
+                set_height_request: super::EVIDENCE_HEIGHT_REQUEST,
+                set_hexpand: true,
+
+                #[name = "column_view"]
+                gtk::ColumnView {
+                    add_css_class: "data-table",
+                    set_show_column_separators: true,
+                    set_show_row_separators: true,
+                },
+            },
+            gtk::Label {
+                add_css_class: "dim-label",
+                add_css_class: "caption",
+                set_label: &lang::lookup_with_args(
+                    "evidence-table-rows",
+                    &lang_args!("count", init.table.rows().len()),
+                ),
+            },
+        }
+    }
+
+    fn init(
+        init: Self::Init,
+        root: Self::Root,
+        _sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let model = ComponentModel;
+        let widgets = view_output!();
+
+        let rows = Rc::new(init.table.rows().clone());
+        for (index, title) in init.table.columns().iter().enumerate() {
+            widgets
+                .column_view
+                .append_column(&table_column(title, &rows, index));
+        }
+
+        // Rows are held by index, so that sorting doesn't need to copy any cells
+        let store = gio::ListStore::new::<glib::BoxedAnyObject>();
+        for index in 0..rows.len() {
+            store.append(&glib::BoxedAnyObject::new(index));
+        }
+        let sorted = gtk::SortListModel::new(Some(store), widgets.column_view.sorter());
+        widgets
+            .column_view
+            .set_model(Some(&gtk::NoSelection::new(Some(sorted))));
+
+        ComponentParts { model, widgets }
+    }
+}
@@ -0,0 +1,191 @@
+use std::{fs, path::PathBuf, sync::Arc};
+
+use evidenceangel::{Evidence, EvidencePackage};
+use gtk::prelude::*;
+use parking_lot::RwLock;
+use relm4::{Component, ComponentParts, ComponentSender, RelmWidgetExt, gtk};
+use tempfile::TempDir;
+
+use crate::lang;
+
+pub struct ComponentModel {
+    package: Arc<RwLock<EvidencePackage>>,
+    evidence: Evidence,
+    poster: Option<gtk::gdk::Texture>,
+    details: Option<String>,
+    /// The temporary copy of the video, once it has been written out to be played.
+    video_file: Option<PathBuf>,
+    temp_files: Vec<TempDir>,
+}
+
+impl ComponentModel {
+    /// Write the video to a temporary directory, returning the path to it. The video is only
+    /// written once, and reused after that.
+    fn video_file(&mut self) -> Option<PathBuf> {
+        if let Some(video_file) = &self.video_file {
+            return Some(video_file.clone());
+        }
+
+        let data = self.evidence.data(&mut self.package.write());
+        let target_dir = tempfile::tempdir()
+            .inspect_err(|e| tracing::error!("Failed to create temp dir! ({e})"))
+            .ok()?;
+        let maybe_extension = super::media_extension(&self.evidence, &self.package);
+        let target_file = target_dir.path().join(format!(
+            "video{}{}",
+            if maybe_extension.is_some() { "." } else { "" },
+            maybe_extension.unwrap_or_default()
+        ));
+        if let Err(e) = fs::write(&target_file, data) {
+            tracing::error!("Failed to write video data to temp file! ({e})");
+            return None;
+        }
+
+        self.temp_files.push(target_dir);
+        self.video_file = Some(target_file.clone());
+        Some(target_file)
+    }
+}
+
+/// Load the poster frame of some video evidence, if it has one.
+fn load_poster(
+    evidence: &Evidence,
+    package: &Arc<RwLock<EvidencePackage>>,
+) -> Option<gtk::gdk::Texture> {
+    let hash = evidence.poster().as_ref()?;
+    let data = package
+        .write()
+        .get_media(hash)
+        .ok()
+        .flatten()
+        .map(|media| media.data().clone())?;
+    gtk::gdk::Texture::from_bytes(&gtk::glib::Bytes::from_owned(data)).ok()
+}
+
+#[derive(Debug)]
+pub enum ComponentInput {
+    /// An internal message was triggered
+    #[allow(
+        private_interfaces,
+        reason = "These messages should only be produced by this component."
+    )]
+    Internal(ComponentInputInternal),
+}
+
+#[derive(Debug)]
+enum ComponentInputInternal {
+    Play,
+    Open,
+}
+
+#[derive(Debug)]
+pub enum ComponentOutput {}
+
+pub struct ComponentInit {
+    pub evidence: Evidence,
+    pub package: Arc<RwLock<EvidencePackage>>,
+}
+
+#[relm4::component(pub)]
+impl Component for ComponentModel {
+    type CommandOutput = ();
+    type Input = ComponentInput;
+    type Output = ComponentOutput;
+    type Init = ComponentInit;
+
+    view! {
+        #[root]
+        gtk::Box {
+            set_orientation: gtk::Orientation::Vertical,
+            set_spacing: 4,
+
+            #[name = "stack"]
+            gtk::Stack {
+                set_hexpand: true,
+                set_height_request: super::EVIDENCE_HEIGHT_REQUEST,
+
+                add_named[Some("poster")] = &gtk::Overlay {
+                    #[wrap(Some)]
+                    set_child = &gtk::Picture {
+                        set_paintable: model.poster.as_ref(),
+                    },
+                    add_overlay = &gtk::Button {
+                        set_icon_name: relm4_icons::icon_names::PLAY_LARGE,
+                        set_tooltip: &lang::lookup("play-video"),
+                        add_css_class: "circular",
+                        add_css_class: "osd",
+                        set_halign: gtk::Align::Center,
+                        set_valign: gtk::Align::Center,
+                        set_width_request: 64,
+                        set_height_request: 64,
+
+                        connect_clicked => ComponentInput::Internal(ComponentInputInternal::Play),
+                    },
+                },
+                #[name = "video"]
+                add_named[Some("video")] = &gtk::Video {
+                    set_autoplay: true,
+                },
+            },
+            gtk::Label {
+                add_css_class: "dim-label",
+                add_css_class: "caption",
+                set_ellipsize: gtk::pango::EllipsizeMode::End,
+                set_visible: model.details.is_some(),
+                set_label: model.details.as_deref().unwrap_or_default(),
+            },
+            gtk::Button {
+                set_label: &lang::lookup("expand-video"),
+                add_css_class: "flat",
+                set_halign: gtk::Align::Center,
+
+                connect_clicked => ComponentInput::Internal(ComponentInputInternal::Open),
+            },
+        }
+    }
+
+    fn init(
+        init: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let ComponentInit { evidence, package } = init;
+        let model = ComponentModel {
+            poster: load_poster(&evidence, &package),
+            details: super::media_details(&evidence, &package),
+            package,
+            evidence,
+            video_file: None,
+            temp_files: vec![],
+        };
+        let widgets = view_output!();
+        widgets.stack.set_visible_child_name("poster");
+        ComponentParts { model, widgets }
+    }
+
+    fn update_with_view(
+        &mut self,
+        widgets: &mut Self::Widgets,
+        message: Self::Input,
+        sender: ComponentSender<Self>,
+        _root: &Self::Root,
+    ) {
+        match message {
+            ComponentInput::Internal(ComponentInputInternal::Play) => {
+                if let Some(video_file) = self.video_file() {
+                    widgets.video.set_filename(Some(&video_file));
+                    widgets.stack.set_visible_child_name("video");
+                }
+            }
+            ComponentInput::Internal(ComponentInputInternal::Open) => {
+                if let Some(video_file) = self.video_file()
+                    && let Some(target_dir) = video_file.parent()
+                {
+                    // Trigger OS to open
+                    open::that_in_background(target_dir);
+                }
+            }
+        }
+        self.update_view(widgets, sender);
+    }
+}
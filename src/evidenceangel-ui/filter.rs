@@ -33,6 +33,15 @@ pub fn images() -> FileFilter {
     filter
 }
 
+/// Get a [`FileFilter`] tuned to videos.
+pub fn videos() -> FileFilter {
+    let filter = FileFilter::new();
+    filter.set_name(Some(&lang::lookup("filetype-videos")));
+    filter.add_suffix("mp4");
+    filter.add_suffix("webm");
+    filter
+}
+
 // Public part of the FileFilterListModel type.
 glib::wrapper! {
     pub struct FileFilterListModel(ObjectSubclass<InnerFileFilterListModel>) @implements gio::ListModel;
@@ -15,12 +15,15 @@ header-menu = Menu
 header-paste-evidence = Paste Evidence
 header-export-package = Export Package...
 header-export-test-case = Export Test Case...
+header-redaction = Redact Secrets...
+header-new-from-template = New Test Case from Template...
 
 paste-evidence-failed = Failed to paste.
 paste-evidence-wrong-type = Cannot paste this type of data.
 
 filetype-package = Evidence Package
 filetype-images = Images
+filetype-videos = Videos
 
 nothing-open = Nothing is Open
 nothing-open-package-description = Open or create a package from the menu.
@@ -28,6 +31,16 @@ nothing-open-case-description = Select a case from the left panel to view and ed
 
 toast-saved = Package saved.
 toast-export-complete = Export complete.
+toast-export-complete-redacted = { $count ->
+    [one] Export complete, with 1 secret redacted.
+   *[other] Export complete, with { $count } secrets redacted.
+}
+toast-secrets-redacted = { $count ->
+    [one] Redacted 1 secret.
+   *[other] Redacted { $count } secrets.
+}
+toast-no-secrets = No secrets found.
+toast-template-saved = Template saved.
 toast-name-too-long = Name too long. Please make it less than 30 characters.
 toast-name-cant-be-empty = Name cannot be empty.
 toast-evidence-deleted = Evidence deleted.
@@ -87,9 +100,35 @@ test-status-fail = Fail
 test-status-unset-display = Unset
 test-status-pass-display = ✅ Pass
 test-status-fail-display = ❌ Fail
+test-requirements = Requirements
+test-requirements-tooltip = The IDs of the requirements this test case covers, separated by commas
+steps-title = Steps
+steps-description = Setting the result of a step updates the status of the test case.
+step-add = Add Step
+step-default-action = New step
+step-delete = Delete Step
+step-action = Action
+step-expected = Expected Result
+step-actual = Actual Result
+step-status = Result
+step-status-unset = Not Run
+step-status-pass = Pass
+step-status-fail = Fail
+step-status-blocked = Blocked
+step-status-skipped = Skipped
+step-status-unset-display = Not run
+step-status-pass-display = ✅ Pass
+step-status-fail-display = ❌ Fail
+step-status-blocked-display = ⛔ Blocked
+step-status-skipped-display = ⏭ Skipped
+step-evidence = Evidence
+step-evidence-tooltip = The numbers of the evidence for this step, separated by commas
 test-evidence-caption = Caption
 test-evidence-file-unnamed = <b>Unnamed file</b>
 test-evidence-file-named = <b>File:</b> { $filename }
+media-details-dimensions = { $width } × { $height }
+media-details-captured = Captured { $time }
+media-details-duration = Duration { $duration }
 
 add-evidence-title = Add Evidence
 add-evidence-submit = Add
@@ -99,10 +138,87 @@ add-evidence-image-failed = Failed to Add Image
 add-evidence-image-failed-message = Failed to add image to package: { $error }
 expand-image = Show Image
 expand-file = Show File
+expand-video = Show Video
+play-video = Play
+annotate-image = Annotate
+annotate-title = Annotate Image
+annotate-tool-rectangle = Rectangle
+annotate-tool-arrow = Arrow
+annotate-tool-text = Text
+annotate-tool-blur = Blur
+annotate-tool-black-out = Black Out
+annotate-text-placeholder = Label text
+annotate-undo = Undo
+annotate-clear = Clear
+annotate-burn-in = Permanently Remove Redacted Content
+annotate-burn-in-subtitle = Replaces the original image in the package with a redacted copy
+annotate-save = Save
 add-evidence-file-label = File Path
 add-evidence-file-caption-label = File Caption (optional)
 add-evidence-file-failed = Failed to Add File
 add-evidence-file-failed-message = Failed to add file to package: { $error }
+add-evidence-log-label = Log File Path
+add-evidence-log-caption-label = Log Caption (optional)
+add-evidence-log-format-label = Format
+add-evidence-log-format-auto = Detect automatically
+add-evidence-log-format-plain = Plain text
+add-evidence-log-format-jsonl = JSON Lines
+add-evidence-log-format-syslog = Syslog
+add-evidence-log-failed = Failed to Add Log
+add-evidence-log-failed-message = Failed to read log file: { $error }
+add-evidence-table-label = Table File Path
+add-evidence-table-caption-label = Table Caption (optional)
+add-evidence-table-format-label = Format
+add-evidence-table-format-auto = Detect automatically
+add-evidence-table-format-csv = CSV
+add-evidence-table-format-tsv = TSV
+add-evidence-table-format-json = JSON
+add-evidence-table-failed = Failed to Add Table
+add-evidence-table-failed-message = Failed to read table: { $error }
+add-evidence-diff-expected-label = Expected Content File Path
+add-evidence-diff-actual-label = Actual Content File Path
+add-evidence-diff-caption-label = Comparison Caption (optional)
+add-evidence-diff-format-label = Format
+add-evidence-diff-format-auto = Detect automatically
+add-evidence-diff-format-text = Plain text
+add-evidence-diff-format-json = JSON
+add-evidence-diff-failed = Failed to Add Comparison
+add-evidence-diff-failed-message = Failed to compare content: { $error }
+add-evidence-command-label = Command Line
+add-evidence-command-directory-label = Working Directory (optional)
+add-evidence-command-exit-code-label = Exit Code (optional)
+add-evidence-command-caption-label = Command Caption (optional)
+add-evidence-command-stdout-label = Standard Output
+add-evidence-command-stderr-label = Standard Error
+add-evidence-command-failed = Failed to Add Command
+add-evidence-command-invalid-exit-code = The exit code must be a whole number.
+add-evidence-sql-query-label = Query
+add-evidence-sql-database-label = Database (optional)
+add-evidence-sql-result-label = Result File Path (optional)
+add-evidence-sql-row-count-label = Rows Returned or Affected (optional)
+add-evidence-sql-duration-label = Duration in Milliseconds (optional)
+add-evidence-sql-caption-label = Query Caption (optional)
+add-evidence-sql-failed = Failed to Add SQL Query
+add-evidence-sql-invalid-number = The row count and duration must be whole numbers.
+add-evidence-link-url-label = URL
+add-evidence-link-title-label = Title (optional)
+add-evidence-link-system-label = Links To
+add-evidence-link-system-none = Not specified
+add-evidence-link-caption-label = Link Caption (optional)
+add-evidence-link-failed = Failed to Add Link
+add-evidence-link-failed-message = Invalid link: { $error }
+add-evidence-har-label = HAR File Path
+add-evidence-har-filter-label = Filter by URL (optional)
+add-evidence-har-empty = Choose a HAR file to list the exchanges it recorded.
+add-evidence-har-failed = Failed to Import HAR File
+add-evidence-har-failed-message = Failed to read HAR file: { $error }
+add-evidence-har-none-selected = Select at least one exchange to import.
+add-evidence-video-label = Video Path
+add-evidence-video-poster-label = Poster Image Path (optional)
+add-evidence-video-caption-label = Video Caption (optional)
+add-evidence-video-invalid = Only MP4 and WebM videos, and PNG and JPEG poster images, are supported.
+add-evidence-video-failed = Failed to Add Video
+add-evidence-video-failed-message = Failed to add video to package: { $error }
 
 export-title = Export { $target }
 export-target-package = Entire Package
@@ -116,6 +232,72 @@ export-error-nothing-open-title = Nothing Open to Export
 export-error-nothing-open-message = Nothing is open to export.
 export-error-failed-title = Failed to Export
 export-error-failed-message = Failed to export: { $error }
+export-redact = Redact Secrets
+export-redact-subtitle = Mask passwords, tokens and other secrets in the exported file
+export-options = Format Options
+export-scope = Test Cases
+export-test-cases = Test Cases to Export
+export-test-cases-subtitle = Choose which test cases to include
+export-include-passed = Include Passed Test Cases
+export-include-failed = Include Failed Test Cases
+export-include-not-run = Include Test Cases Not Run
+export-fields = Custom Fields
+export-fields-subtitle = Only include test cases with these values
+export-executed-after = Executed On or After
+export-executed-before = Executed On or Before
+export-order = Order
+export-order-package = Package Order
+export-order-title = Title
+export-order-execution-time = Execution Time
+export-order-status = Failed First
+export-content = Content
+export-evidence = Evidence to Include
+export-evidence-subtitle = Leave out evidence such as files or HTTP requests
+export-evidence-text = Text
+export-evidence-rich-text = Rich Text
+export-evidence-image = Images
+export-evidence-video = Videos
+export-evidence-file = Files
+export-evidence-http = HTTP Requests
+export-evidence-log = Logs
+export-evidence-table = Tables
+export-evidence-diff = Comparisons
+export-evidence-command = Commands
+export-evidence-sql = SQL Queries
+export-evidence-link = Links
+export-omit-authors = Leave Out Authors
+export-omit-emails = Leave Out Author Email Addresses
+
+redaction-title = Secret Redaction
+redaction-builtin-rules = Built-in Rules
+redaction-builtin-rules-subtitle = Authorization and cookie headers, bearer tokens, API keys, passwords and private keys
+redaction-on-add = Redact When Adding Evidence
+redaction-on-export = Redact When Exporting
+redaction-rules = Custom Rules
+redaction-rules-description = Regular expressions matching secrets. Name a group "secret" to only redact part of each match.
+redaction-no-rules = No custom rules
+redaction-rule-name = Rule name
+redaction-rule-pattern = Pattern
+redaction-rule-add = Add Rule
+redaction-rule-delete = Delete Rule
+redaction-apply = Redact Package Now
+redaction-failed-title = Failed to Redact Secrets
+
+templates-title = New Test Case from Template
+templates-package = This Package
+templates-user = All Packages
+templates-user-description = Templates you can use in any package.
+templates-none = No templates
+templates-delete = Delete Template
+template-save-title = Save as Template
+template-save-description = New test cases created from this template will start with this test case's custom metadata and evidence, except images, videos and files.
+template-save-name = Template name
+template-save-case-title = Test case title
+template-save-case-title-tooltip = {"{"}n{"}"}, {"{"}date{"}"}, {"{"}time{"}"} and {"{"}template{"}"} are replaced with the position of the new test case, the date and time and the template name.
+template-save-template-description = Description
+template-save-user = Available in All Packages
+template-save-submit = Save
+template-failed-title = Failed to Use Template
 
 evidence-menu = Evidence Actions
 evidence-move-up = Move this Up
@@ -127,14 +309,52 @@ evidence-richtext = Rich Text
 evidence-http = HTTP Request
 evidence-http-request = Request
 evidence-http-response = Response
+evidence-http-formatted = Formatted
+evidence-http-raw = Raw
+evidence-http-no-response = No response
+evidence-har = HTTP Archive (HAR)
 evidence-image = Image
 evidence-file = File
+evidence-log = Log
+evidence-log-all-levels = All levels
+evidence-log-filter = Search log
+evidence-log-entries = { $count ->
+    [one] 1 entry
+   *[other] { $count } entries
+}
+evidence-table = Table
+evidence-table-rows = { $count ->
+    [one] 1 row
+   *[other] { $count } rows
+}
+evidence-diff = Comparison
+evidence-diff-unified = Unified
+evidence-diff-side-by-side = Side by Side
+evidence-diff-identical = Expected and actual content are identical.
+evidence-command = Command
+evidence-command-exit-code = Exit code { $code }
+evidence-command-directory = In { $directory }
+evidence-command-stdout = Standard Output
+evidence-command-stderr = Standard Error
+evidence-sql = SQL Query
+evidence-sql-rows = { $count ->
+    [one] 1 row
+   *[other] { $count } rows
+}
+evidence-link = Link
+evidence-link-system-defect = Defect
+evidence-link-system-ticket = Ticket
+evidence-link-system-build = Build
+evidence-link-system-documentation = Documentation
+evidence-link-system-other = Other
+evidence-video = Video
 
 test-case-menu = Actions
 test-case-move-up = Move this Up
 test-case-move-down = Move this Down
 test-case-duplicate = Duplicate
 test-case-duplicate-suffix = (Copy)
+test-case-save-as-template = Save as Template
 delete-case-title = Delete { $name }?
 delete-case-message = Do you really want to irreversibly delete test case { $name }?
 delete-case-affirm = Delete
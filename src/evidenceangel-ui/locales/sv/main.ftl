@@ -15,12 +15,15 @@ header-menu = Meny
 header-paste-evidence = Klistra in bevis
 header-export-package = Exportera Paket...
 header-export-test-case = Exportera Testfall...
+header-redaction = Maskera Hemligheter...
+header-new-from-template = Nytt Testfall från Mall...
 
 paste-evidence-failed = Det gick inte att klistra in.
 paste-evidence-wrong-type = Det går inte att klistra in den här typen av data.
 
 filetype-package = Bevispaket
 filetype-images = Bilder
+filetype-videos = Videor
 
 nothing-open = Inget öppet
 nothing-open-package-description = Öppna eller skapa ett paket från menyn.
@@ -28,6 +31,16 @@ nothing-open-case-description = Välj ett fall från den vänstra panelen för a
 
 toast-saved = Bevispaket sparade.
 toast-export-complete = Exporten är klar.
+toast-export-complete-redacted = { $count ->
+    [one] Exporten är klar, 1 hemlighet maskerades.
+   *[other] Exporten är klar, { $count } hemligheter maskerades.
+}
+toast-secrets-redacted = { $count ->
+    [one] Maskerade 1 hemlighet.
+   *[other] Maskerade { $count } hemligheter.
+}
+toast-no-secrets = Inga hemligheter hittades.
+toast-template-saved = Mall sparad.
 toast-name-too-long = Namnet är för långt. Vänligen gör det mindre än 30 tecken.
 toast-name-cant-be-empty = Namnet får inte vara tomt.
 toast-evidence-deleted = Bevis raderade.
@@ -87,9 +100,35 @@ test-status-fail = Misslyckar
 test-status-unset-display = Odefinerad
 test-status-pass-display = ✅ Passar
 test-status-fail-display = ❌ Misslyckar
+test-requirements = Krav
+test-requirements-tooltip = ID:n för kraven som detta testfall täcker, separerade med kommatecken
+steps-title = Steg
+steps-description = Att ange resultatet för ett steg uppdaterar testfallets status.
+step-add = Lägg till steg
+step-default-action = Nytt steg
+step-delete = Ta bort steg
+step-action = Åtgärd
+step-expected = Förväntat resultat
+step-actual = Faktiskt resultat
+step-status = Resultat
+step-status-unset = Ej körd
+step-status-pass = Passar
+step-status-fail = Misslyckar
+step-status-blocked = Blockerad
+step-status-skipped = Överhoppad
+step-status-unset-display = Ej körd
+step-status-pass-display = ✅ Passar
+step-status-fail-display = ❌ Misslyckar
+step-status-blocked-display = ⛔ Blockerad
+step-status-skipped-display = ⏭ Överhoppad
+step-evidence = Bevis
+step-evidence-tooltip = Numren på bevisen för detta steg, separerade med kommatecken
 test-evidence-caption = Rubrik
 test-evidence-file-unnamed = <b>Namnlös fil</b>
 test-evidence-file-named = <b>Fil:</b> { $filename }
+media-details-dimensions = { $width } × { $height }
+media-details-captured = Fångad { $time }
+media-details-duration = Längd { $duration }
 
 add-evidence-title = Lägg till Bevis
 add-evidence-submit = Lägg till
@@ -99,10 +138,87 @@ add-evidence-image-failed = Misslyckades att Lägg till Bild
 add-evidence-image-failed-message = Misslyckades att lägga till bild i paketet: { $error }
 expand-image = Visa bild
 expand-file = Visa fil
+expand-video = Visa video
+play-video = Spela upp
+annotate-image = Kommentera
+annotate-title = Kommentera Bild
+annotate-tool-rectangle = Rektangel
+annotate-tool-arrow = Pil
+annotate-tool-text = Text
+annotate-tool-blur = Suddig
+annotate-tool-black-out = Svärta
+annotate-text-placeholder = Etikettext
+annotate-undo = Ångra
+annotate-clear = Rensa
+annotate-burn-in = Ta bort Maskerat Innehåll Permanent
+annotate-burn-in-subtitle = Ersätter originalbilden i paketet med en maskerad kopia
+annotate-save = Spara
 add-evidence-file-label = Filsökväg
 add-evidence-file-caption-label = Filrubrik (valfritt)
 add-evidence-file-failed = Misslyckades att Lägg till fil
 add-evidence-file-failed-message = Misslyckades att lägga till fil i paketet: { $error }
+add-evidence-log-label = Sökväg till loggfil
+add-evidence-log-caption-label = Loggrubrik (valfritt)
+add-evidence-log-format-label = Format
+add-evidence-log-format-auto = Identifiera automatiskt
+add-evidence-log-format-plain = Vanlig text
+add-evidence-log-format-jsonl = JSON Lines
+add-evidence-log-format-syslog = Syslog
+add-evidence-log-failed = Misslyckades att Lägg till logg
+add-evidence-log-failed-message = Misslyckades att läsa loggfil: { $error }
+add-evidence-table-label = Sökväg till tabellfil
+add-evidence-table-caption-label = Tabellrubrik (valfritt)
+add-evidence-table-format-label = Format
+add-evidence-table-format-auto = Identifiera automatiskt
+add-evidence-table-format-csv = CSV
+add-evidence-table-format-tsv = TSV
+add-evidence-table-format-json = JSON
+add-evidence-table-failed = Misslyckades att Lägg till tabell
+add-evidence-table-failed-message = Misslyckades att läsa tabell: { $error }
+add-evidence-diff-expected-label = Sökväg till förväntat innehåll
+add-evidence-diff-actual-label = Sökväg till faktiskt innehåll
+add-evidence-diff-caption-label = Jämförelserubrik (valfritt)
+add-evidence-diff-format-label = Format
+add-evidence-diff-format-auto = Identifiera automatiskt
+add-evidence-diff-format-text = Vanlig text
+add-evidence-diff-format-json = JSON
+add-evidence-diff-failed = Misslyckades att Lägg till jämförelse
+add-evidence-diff-failed-message = Misslyckades att jämföra innehåll: { $error }
+add-evidence-command-label = Kommandorad
+add-evidence-command-directory-label = Arbetskatalog (valfritt)
+add-evidence-command-exit-code-label = Slutkod (valfritt)
+add-evidence-command-caption-label = Kommandorubrik (valfritt)
+add-evidence-command-stdout-label = Standardutdata
+add-evidence-command-stderr-label = Standardfel
+add-evidence-command-failed = Misslyckades att Lägg till kommando
+add-evidence-command-invalid-exit-code = Slutkoden måste vara ett heltal.
+add-evidence-sql-query-label = Fråga
+add-evidence-sql-database-label = Databas (valfritt)
+add-evidence-sql-result-label = Resultatfilens sökväg (valfritt)
+add-evidence-sql-row-count-label = Returnerade eller påverkade rader (valfritt)
+add-evidence-sql-duration-label = Varaktighet i millisekunder (valfritt)
+add-evidence-sql-caption-label = Frågerubrik (valfritt)
+add-evidence-sql-failed = Misslyckades att Lägg till SQL-fråga
+add-evidence-sql-invalid-number = Antalet rader och varaktigheten måste vara heltal.
+add-evidence-link-url-label = URL
+add-evidence-link-title-label = Titel (valfritt)
+add-evidence-link-system-label = Länkar till
+add-evidence-link-system-none = Inte angivet
+add-evidence-link-caption-label = Länkrubrik (valfritt)
+add-evidence-link-failed = Misslyckades att Lägg till länk
+add-evidence-link-failed-message = Ogiltig länk: { $error }
+add-evidence-har-label = Sökväg till HAR-fil
+add-evidence-har-filter-label = Filtrera efter URL (valfritt)
+add-evidence-har-empty = Välj en HAR-fil för att lista de utbyten den spelade in.
+add-evidence-har-failed = Misslyckades att Importera HAR-fil
+add-evidence-har-failed-message = Misslyckades att läsa HAR-fil: { $error }
+add-evidence-har-none-selected = Välj minst ett utbyte att importera.
+add-evidence-video-label = Videosökväg
+add-evidence-video-poster-label = Sökväg till affischbild (valfritt)
+add-evidence-video-caption-label = Videorubrik (valfritt)
+add-evidence-video-invalid = Endast MP4- och WebM-videor, och PNG- och JPEG-affischbilder, stöds.
+add-evidence-video-failed = Misslyckades att Lägg till video
+add-evidence-video-failed-message = Misslyckades att lägga till video i paketet: { $error }
 
 export-title = Exportera { $target }
 export-target-package = Hela Paketet
@@ -116,6 +232,72 @@ export-error-nothing-open-title = Inget öppet att exportera
 export-error-nothing-open-message = Inget är öppet att exportera.
 export-error-failed-title = Misslyckades att Exportera
 export-error-failed-message = Misslyckades att exportera: { $error }
+export-redact = Maskera Hemligheter
+export-redact-subtitle = Dölj lösenord, tokens och andra hemligheter i den exporterade filen
+export-options = Formatalternativ
+export-scope = Testfall
+export-test-cases = Testfall att Exportera
+export-test-cases-subtitle = Välj vilka testfall som ska inkluderas
+export-include-passed = Inkludera Godkända Testfall
+export-include-failed = Inkludera Underkända Testfall
+export-include-not-run = Inkludera Ej Körda Testfall
+export-fields = Anpassade Fält
+export-fields-subtitle = Inkludera endast testfall med dessa värden
+export-executed-after = Körd Tidigast
+export-executed-before = Körd Senast
+export-order = Ordning
+export-order-package = Paketets Ordning
+export-order-title = Titel
+export-order-execution-time = Körtid
+export-order-status = Underkända Först
+export-content = Innehåll
+export-evidence = Bevis att Inkludera
+export-evidence-subtitle = Utelämna bevis som filer eller HTTP-förfrågningar
+export-evidence-text = Text
+export-evidence-rich-text = Formaterad Text
+export-evidence-image = Bilder
+export-evidence-video = Videor
+export-evidence-file = Filer
+export-evidence-http = HTTP-förfrågningar
+export-evidence-log = Loggar
+export-evidence-table = Tabeller
+export-evidence-diff = Jämförelser
+export-evidence-command = Kommandon
+export-evidence-sql = SQL-frågor
+export-evidence-link = Länkar
+export-omit-authors = Utelämna Författare
+export-omit-emails = Utelämna Författarnas E-postadresser
+
+redaction-title = Maskering av Hemligheter
+redaction-builtin-rules = Inbyggda Regler
+redaction-builtin-rules-subtitle = Autentiserings- och cookie-huvuden, bearer-tokens, API-nycklar, lösenord och privata nycklar
+redaction-on-add = Maskera när Bevis Läggs till
+redaction-on-export = Maskera vid Export
+redaction-rules = Egna Regler
+redaction-rules-description = Reguljära uttryck som matchar hemligheter. Namnge en grupp "secret" för att bara maskera en del av varje matchning.
+redaction-no-rules = Inga egna regler
+redaction-rule-name = Regelnamn
+redaction-rule-pattern = Mönster
+redaction-rule-add = Lägg till Regel
+redaction-rule-delete = Ta bort Regel
+redaction-apply = Maskera Paketet Nu
+redaction-failed-title = Misslyckades att Maskera Hemligheter
+
+templates-title = Nytt Testfall från Mall
+templates-package = Det här Paketet
+templates-user = Alla Paket
+templates-user-description = Mallar som du kan använda i alla paket.
+templates-none = Inga mallar
+templates-delete = Ta bort Mall
+template-save-title = Spara som Mall
+template-save-description = Nya testfall som skapas från den här mallen börjar med det här testfallets anpassade metadata och bevis, förutom bilder, videor och filer.
+template-save-name = Mallnamn
+template-save-case-title = Testfallets titel
+template-save-case-title-tooltip = {"{"}n{"}"}, {"{"}date{"}"}, {"{"}time{"}"} och {"{"}template{"}"} ersätts med det nya testfallets position, datum och tid samt mallens namn.
+template-save-template-description = Beskrivning
+template-save-user = Tillgänglig i Alla Paket
+template-save-submit = Spara
+template-failed-title = Misslyckades att Använda Mall
 
 evidence-menu = Bevisåtgärder
 evidence-move-up = Flytta det här Upp
@@ -127,14 +309,52 @@ evidence-richtext = Rik Text
 evidence-http = HTTP-begäran
 evidence-http-request = Begäran
 evidence-http-response = Svar
+evidence-http-formatted = Formaterad
+evidence-http-raw = Rå
+evidence-http-no-response = Inget svar
+evidence-har = HTTP-arkiv (HAR)
 evidence-image = Bild
 evidence-file = Fil
+evidence-log = Logg
+evidence-log-all-levels = Alla nivåer
+evidence-log-filter = Sök i loggen
+evidence-log-entries = { $count ->
+    [one] 1 post
+   *[other] { $count } poster
+}
+evidence-table = Tabell
+evidence-table-rows = { $count ->
+    [one] 1 rad
+   *[other] { $count } rader
+}
+evidence-diff = Jämförelse
+evidence-diff-unified = Samlad
+evidence-diff-side-by-side = Sida vid sida
+evidence-diff-identical = Förväntat och faktiskt innehåll är identiska.
+evidence-command = Kommando
+evidence-command-exit-code = Slutkod { $code }
+evidence-command-directory = I { $directory }
+evidence-command-stdout = Standardutdata
+evidence-command-stderr = Standardfel
+evidence-sql = SQL-fråga
+evidence-sql-rows = { $count ->
+    [one] 1 rad
+   *[other] { $count } rader
+}
+evidence-link = Länk
+evidence-link-system-defect = Defekt
+evidence-link-system-ticket = Ärende
+evidence-link-system-build = Bygge
+evidence-link-system-documentation = Dokumentation
+evidence-link-system-other = Annat
+evidence-video = Video
 
 test-case-menu = Åtgärder
 test-case-move-up = Flytta det här Upp
 test-case-move-down = Flytta det här Ner
 test-case-duplicate = Duplicera
 test-case-duplicate-suffix = (Kopiera)
+test-case-save-as-template = Spara som Mall
 delete-case-title = Radera { $name }?
 delete-case-message = Vill du verkligen oåterkalleligt radera testfallet { $name }?
 delete-case-affirm = Radera
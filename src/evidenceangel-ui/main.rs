@@ -34,6 +34,7 @@ mod evidence_factory;
 mod filter;
 mod lang;
 mod nav_factory;
+mod step_factory;
 mod util;
 
 #[derive(Parser)]
@@ -0,0 +1,206 @@
+use adw::prelude::*;
+use evidenceangel::{StepStatus, TestStep};
+use relm4::{
+    FactorySender, RelmWidgetExt, adw,
+    factory::FactoryView,
+    gtk,
+    prelude::{DynamicIndex, FactoryComponent},
+};
+
+use crate::lang;
+
+pub struct StepFactoryModel {
+    index: DynamicIndex,
+    step: TestStep,
+}
+
+#[derive(Debug)]
+pub enum StepFactoryInput {
+    SetAction(String),
+    SetExpected(String),
+    SetActual(String),
+    SetStatus(u32),
+    /// Set the evidence attached to this step, as zero-based indexes.
+    SetEvidence(Vec<usize>),
+    DeleteSelf,
+}
+
+#[derive(Debug)]
+pub enum StepFactoryOutput {
+    UpdateStep { index: DynamicIndex, step: TestStep },
+    DeleteStep(DynamicIndex),
+}
+
+#[relm4::factory(pub)]
+impl FactoryComponent for StepFactoryModel {
+    type ParentWidget = adw::PreferencesGroup;
+    type Input = StepFactoryInput;
+    type Output = StepFactoryOutput;
+    type Init = TestStep;
+    type CommandOutput = ();
+
+    view! {
+        #[root]
+        adw::ExpanderRow {
+            set_use_markup: false,
+            #[watch]
+            set_title: &format!("{}. {}", self.index.current_index() + 1, self.step.action()),
+            #[watch]
+            set_subtitle: &status_label(*self.step.status()),
+
+            add_suffix = &gtk::Button {
+                set_icon_name: relm4_icons::icon_names::CROSS_LARGE,
+                set_tooltip: &lang::lookup("step-delete"),
+                set_valign: gtk::Align::Center,
+                add_css_class: "flat",
+
+                connect_clicked => StepFactoryInput::DeleteSelf,
+            },
+
+            add_row = &adw::EntryRow {
+                set_title: &lang::lookup("step-action"),
+                set_text: self.step.action(),
+
+                connect_changed[sender] => move |entry| {
+                    sender.input(StepFactoryInput::SetAction(entry.text().to_string()));
+                },
+            },
+            add_row = &adw::EntryRow {
+                set_title: &lang::lookup("step-expected"),
+                set_text: self.step.expected().as_deref().unwrap_or_default(),
+
+                connect_changed[sender] => move |entry| {
+                    sender.input(StepFactoryInput::SetExpected(entry.text().to_string()));
+                },
+            },
+            add_row = &adw::EntryRow {
+                set_title: &lang::lookup("step-actual"),
+                set_text: self.step.actual().as_deref().unwrap_or_default(),
+
+                connect_changed[sender] => move |entry| {
+                    sender.input(StepFactoryInput::SetActual(entry.text().to_string()));
+                },
+            },
+            add_row = &adw::ComboRow {
+                set_title: &lang::lookup("step-status"),
+                set_model: Some(&gtk::StringList::new(&[
+                    &lang::lookup("step-status-unset"),
+                    &lang::lookup("step-status-pass"),
+                    &lang::lookup("step-status-fail"),
+                    &lang::lookup("step-status-blocked"),
+                    &lang::lookup("step-status-skipped"),
+                ])),
+                set_selected: match self.step.status() {
+                    None => 0,
+                    Some(StepStatus::Pass) => 1,
+                    Some(StepStatus::Fail) => 2,
+                    Some(StepStatus::Blocked) => 3,
+                    Some(StepStatus::Skipped) => 4,
+                },
+
+                connect_selected_notify[sender] => move |entry| {
+                    sender.input(StepFactoryInput::SetStatus(entry.selected()));
+                },
+            },
+            add_row = &adw::EntryRow {
+                set_title: &lang::lookup("step-evidence"),
+                set_tooltip: &lang::lookup("step-evidence-tooltip"),
+                set_text: &self
+                    .step
+                    .evidence()
+                    .iter()
+                    .map(|i| (i + 1).to_string())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+
+                connect_changed[sender] => move |entry| {
+                    // Evidence is shown one-based, as it is numbered in exports
+                    let text = entry.text();
+                    let evidence = text
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|s| !s.is_empty())
+                        .map(|s| s.parse::<usize>().ok().and_then(|n| n.checked_sub(1)))
+                        .collect::<Option<Vec<_>>>();
+                    if let Some(evidence) = evidence {
+                        entry.remove_css_class("error");
+                        sender.input(StepFactoryInput::SetEvidence(evidence));
+                    } else {
+                        entry.add_css_class("error");
+                    }
+                },
+            },
+        }
+    }
+
+    fn init_model(init: Self::Init, index: &DynamicIndex, _sender: FactorySender<Self>) -> Self {
+        Self {
+            index: index.clone(),
+            step: init,
+        }
+    }
+
+    fn init_widgets(
+        &mut self,
+        _index: &DynamicIndex,
+        root: Self::Root,
+        _returned_widget: &<Self::ParentWidget as FactoryView>::ReturnedWidget,
+        sender: FactorySender<Self>,
+    ) -> Self::Widgets {
+        let widgets = view_output!();
+        widgets
+    }
+
+    fn update(&mut self, message: Self::Input, sender: FactorySender<Self>) {
+        match message {
+            StepFactoryInput::SetAction(action) => {
+                self.step.set_action(action);
+            }
+            StepFactoryInput::SetExpected(expected) => {
+                self.step
+                    .set_expected((!expected.is_empty()).then_some(expected));
+            }
+            StepFactoryInput::SetActual(actual) => {
+                self.step.set_actual((!actual.is_empty()).then_some(actual));
+            }
+            StepFactoryInput::SetStatus(status) => {
+                self.step.set_status(match status {
+                    1 => Some(StepStatus::Pass),
+                    2 => Some(StepStatus::Fail),
+                    3 => Some(StepStatus::Blocked),
+                    4 => Some(StepStatus::Skipped),
+                    _ => None,
+                });
+            }
+            StepFactoryInput::SetEvidence(evidence) => {
+                self.step.evidence_mut().clear();
+                for index in evidence {
+                    self.step.attach_evidence(index);
+                }
+            }
+            StepFactoryInput::DeleteSelf => {
+                sender
+                    .output(StepFactoryOutput::DeleteStep(self.index.clone()))
+                    .unwrap();
+                return;
+            }
+        }
+        sender
+            .output(StepFactoryOutput::UpdateStep {
+                index: self.index.clone(),
+                step: self.step.clone(),
+            })
+            .unwrap();
+    }
+}
+
+/// Get the text shown for the status of a step.
+fn status_label(status: Option<StepStatus>) -> String {
+    lang::lookup(match status {
+        None => "step-status-unset-display",
+        Some(StepStatus::Pass) => "step-status-pass-display",
+        Some(StepStatus::Fail) => "step-status-fail-display",
+        Some(StepStatus::Blocked) => "step-status-blocked-display",
+        Some(StepStatus::Skipped) => "step-status-skipped-display",
+    })
+}
@@ -1,4 +1,7 @@
-use evidenceangel::Evidence;
+use std::path::PathBuf;
+
+use directories::ProjectDirs;
+use evidenceangel::{Evidence, EvidencePackage, RedactionSettings, TemplateLibrary};
 use getset::Getters;
 use relm4::gtk::glib;
 use uuid::Uuid;
@@ -36,3 +39,51 @@ impl BoxedTestCaseById {
         self.data
     }
 }
+
+/// Get the redaction settings that apply to a package, combining the user's settings, which are
+/// shared with the CLI tool, with the package's own.
+pub fn redaction_settings(package: &EvidencePackage) -> RedactionSettings {
+    let user = ProjectDirs::from("uk.hpkns", "AngelSuite", "EvidenceAngel")
+        .map(|dirs| dirs.config_dir().join("redaction.json"))
+        .map(|path| {
+            RedactionSettings::load(&path).unwrap_or_else(|e| {
+                tracing::warn!("Failed to read user redaction settings: {e}");
+                RedactionSettings::default()
+            })
+        })
+        .unwrap_or_default();
+    match package.metadata().redaction() {
+        Some(settings) => user.combined_with(settings),
+        None => user,
+    }
+}
+
+/// Get the path of the file storing the templates for all packages the user works with, which are
+/// shared with the CLI tool.
+fn user_templates_path() -> Option<PathBuf> {
+    ProjectDirs::from("uk.hpkns", "AngelSuite", "EvidenceAngel")
+        .map(|dirs| dirs.config_dir().join("templates.json"))
+}
+
+/// Get the templates for all packages the user works with.
+pub fn user_templates() -> TemplateLibrary {
+    user_templates_path()
+        .map(|path| {
+            TemplateLibrary::load(&path).unwrap_or_else(|e| {
+                tracing::warn!("Failed to read user templates: {e}");
+                TemplateLibrary::default()
+            })
+        })
+        .unwrap_or_default()
+}
+
+/// Save the templates for all packages the user works with.
+pub fn save_user_templates(templates: &TemplateLibrary) -> evidenceangel::Result<()> {
+    let path = user_templates_path().ok_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            "couldn't find where to store user settings on this system",
+        )
+    })?;
+    templates.save(&path)
+}
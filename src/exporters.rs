@@ -2,7 +2,7 @@ use std::path::PathBuf;
 
 use uuid::Uuid;
 
-use crate::{EvidencePackage, Result};
+use crate::{EvidencePackage, RedactionReport, Result, SecretRedactor};
 
 /// Exporter for Excel files.
 #[cfg(feature = "exporter-excel")]
@@ -14,19 +14,40 @@ pub mod html;
 #[cfg(feature = "exporter-zip-of-files")]
 pub mod zip_of_files;
 
+/// Options passed to exporters
+mod options;
+pub use options::{
+    ExportOption, ExportOptionKind, ExportOptionValue, ExportOptions, TestCaseFilter, TestCaseOrder,
+};
+/// A registry of the formats that can be exported to
+mod registry;
+pub use registry::{ExportFormat, ExporterRegistry};
+
 /// Exporters can take an `EvidencePackage` and a target file path and export to other formats.
+///
+/// This trait is object safe, so exporters can be registered with an [`ExporterRegistry`] and
+/// used as `dyn Exporter`.
 pub trait Exporter {
     /// The name of this exporter.
-    fn export_name() -> String;
+    fn export_name(&self) -> String;
     /// The file extension to suggest when saving this file.
-    fn export_extension() -> String;
+    fn export_extension(&self) -> String;
+    /// The options this exporter understands. Other options passed to it are ignored.
+    fn export_options(&self) -> Vec<ExportOption> {
+        vec![]
+    }
 
     /// Export a package.
     ///
     /// # Errors
     ///
     /// Returns an error if the export failed for any reason.
-    fn export_package(&mut self, package: &mut EvidencePackage, path: PathBuf) -> Result<()>;
+    fn export_package(
+        &mut self,
+        package: &mut EvidencePackage,
+        path: PathBuf,
+        options: &ExportOptions,
+    ) -> Result<()>;
     /// Export a test case.
     ///
     /// # Errors
@@ -37,5 +58,49 @@ pub trait Exporter {
         package: &mut EvidencePackage,
         case: Uuid,
         path: PathBuf,
+        options: &ExportOptions,
     ) -> Result<()>;
+
+    /// Export a package with secrets redacted from its evidence, returning a report of what was
+    /// redacted. The package itself is left unchanged.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the export failed for any reason.
+    fn export_package_redacted(
+        &mut self,
+        package: &mut EvidencePackage,
+        path: PathBuf,
+        options: &ExportOptions,
+        redactor: &SecretRedactor,
+    ) -> Result<RedactionReport> {
+        redactor
+            .while_redacted(package, |package| {
+                self.export_package(package, path, options)
+            })
+            .map(|((), report)| report)
+    }
+    /// Export a test case with secrets redacted from its evidence, returning a report of what
+    /// was redacted. The package itself is left unchanged.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the export failed for any reason.
+    fn export_case_redacted(
+        &mut self,
+        package: &mut EvidencePackage,
+        case: Uuid,
+        path: PathBuf,
+        options: &ExportOptions,
+        redactor: &SecretRedactor,
+    ) -> Result<RedactionReport> {
+        redactor
+            .while_redacted(package, |package| {
+                self.export_case(package, case, path, options)
+            })
+            .map(|((), mut report)| {
+                report.retain_test_case(case);
+                report
+            })
+    }
 }
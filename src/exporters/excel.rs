@@ -1,50 +1,101 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
 use angelmark::{AngelmarkLine, AngelmarkTableAlignment, AngelmarkText, parse_angelmark};
-use rust_xlsxwriter::{Format, FormatAlign, FormatBorder, Image, Note, Workbook, Worksheet};
+use rust_xlsxwriter::{
+    Format, FormatAlign, FormatBorder, Image, Note, Table as ExcelTable,
+    TableColumn as ExcelTableColumn, Url, Workbook, Worksheet, XlsxError,
+};
 use uuid::Uuid;
 
-use crate::{EvidenceKind, EvidencePackage, TestCase, TestCasePassStatus};
+use crate::{
+    CommandExecution, CoverageStatus, Diff, DiffChange, DiffLine, EvidenceData, EvidenceKind,
+    EvidencePackage, HttpExchange, HttpHeader, Link, Log, LogLevel, MediaFile, PackageStatistics,
+    SqlQuery, SqlToken, StepStatus, StructuredEvidence, Table, TableCell, TestCase,
+    TestCasePassStatus, TraceabilityMatrix,
+};
 
-use super::Exporter;
+use super::{ExportOption, ExportOptionKind, ExportOptions, Exporter};
 
 /// An exporter to an Excel document.
+///
+/// Media that can't be embedded in a workbook, such as videos, is copied into a folder beside
+/// the workbook named after it (`<name> media` for `<name>.xlsx`) and linked to, unless the
+/// [`ExcelExporter::EMBED_ONLY`] flag is set. If the export fails, a folder created by it is
+/// removed again.
 #[derive(Default)]
 pub struct ExcelExporter;
 
+impl ExcelExporter {
+    /// The ID of the flag to only write the workbook, rather than also copying videos into a
+    /// folder beside it to link to. Videos are then shown by their poster frame and name only.
+    pub const EMBED_ONLY: &'static str = "embed-only";
+}
+
 impl Exporter for ExcelExporter {
-    fn export_name() -> String {
+    fn export_name(&self) -> String {
         "Excel Workbook".to_string()
     }
 
-    fn export_extension() -> String {
+    fn export_extension(&self) -> String {
         ".xlsx".to_string()
     }
 
+    fn export_options(&self) -> Vec<ExportOption> {
+        vec![ExportOption::new(
+            Self::EMBED_ONLY,
+            "Embed Only",
+            "Only write the workbook, rather than copying videos into a folder beside it",
+            ExportOptionKind::Flag,
+        )]
+    }
+
     fn export_package(
         &mut self,
         package: &mut EvidencePackage,
         path: std::path::PathBuf,
+        options: &ExportOptions,
     ) -> crate::Result<()> {
-        let mut workbook = Workbook::new();
-        workbook.read_only_recommended();
-
-        create_metadata_sheet(workbook.add_worksheet(), package)
-            .map_err(crate::Error::OtherExportError)?;
+        options.while_applied(package, None, |package| {
+            let mut workbook = Workbook::new();
+            workbook.read_only_recommended();
 
-        create_summary_sheet(workbook.add_worksheet(), package)
-            .map_err(crate::Error::OtherExportError)?;
+            create_metadata_sheet(workbook.add_worksheet(), package)
+                .map_err(crate::Error::OtherExportError)?;
 
-        let test_cases: Vec<&TestCase> = package.test_case_iter()?.collect();
-        for test_case in test_cases {
-            let worksheet = workbook.add_worksheet();
-            create_test_case_sheet(worksheet, package.clone(), test_case)
+            let statistics = package.statistics()?;
+            create_summary_sheet(workbook.add_worksheet(), package, &statistics)
                 .map_err(crate::Error::OtherExportError)?;
-        }
 
-        workbook
-            .save(path)
-            .map_err(|e| crate::Error::OtherExportError(e.into()))?;
+            let matrix = package.traceability_matrix()?;
+            if !matrix.is_empty() {
+                create_traceability_sheet(workbook.add_worksheet(), &matrix)
+                    .map_err(crate::Error::OtherExportError)?;
+            }
+
+            let media_dir = linked_media_dir(&path, options);
+            with_linked_media_dir(media_dir.as_deref(), || {
+                let test_cases: Vec<&TestCase> = package.test_case_iter()?.collect();
+                for test_case in test_cases {
+                    let worksheet = workbook.add_worksheet();
+                    create_test_case_sheet(
+                        worksheet,
+                        package.clone(),
+                        test_case,
+                        media_dir.as_deref(),
+                    )
+                    .map_err(crate::Error::OtherExportError)?;
+                }
+
+                workbook
+                    .save(&path)
+                    .map_err(|e| crate::Error::OtherExportError(e.into()))?;
 
-        Ok(())
+                Ok(())
+            })
+        })
     }
 
     fn export_case(
@@ -52,26 +103,76 @@ impl Exporter for ExcelExporter {
         package: &mut EvidencePackage,
         case: Uuid,
         path: std::path::PathBuf,
+        options: &ExportOptions,
     ) -> crate::Result<()> {
-        let mut workbook = Workbook::new();
+        options.while_applied(package, Some(case), |package| {
+            let mut workbook = Workbook::new();
 
-        let worksheet = workbook.add_worksheet();
-        let case = package
-            .test_case(case)?
-            .ok_or(crate::Error::OtherExportError(
-                "Test case not found!".into(),
-            ))?;
-        create_test_case_sheet(worksheet, package.clone(), case)
-            .map_err(crate::Error::OtherExportError)?;
+            let media_dir = linked_media_dir(&path, options);
+            let case = package
+                .test_case(case)?
+                .ok_or(crate::Error::OtherExportError(
+                    "Test case not found!".into(),
+                ))?;
+            with_linked_media_dir(media_dir.as_deref(), || {
+                let worksheet = workbook.add_worksheet();
+                create_test_case_sheet(worksheet, package.clone(), case, media_dir.as_deref())
+                    .map_err(crate::Error::OtherExportError)?;
 
-        workbook
-            .save(path)
-            .map_err(|e| crate::Error::OtherExportError(e.into()))?;
+                workbook
+                    .save(&path)
+                    .map_err(|e| crate::Error::OtherExportError(e.into()))?;
 
-        Ok(())
+                Ok(())
+            })
+        })
     }
 }
 
+/// Determine the directory that media which can't be embedded in the workbook, such as videos,
+/// is written to. This is alongside the workbook, named after it, or `None` if the
+/// [`ExcelExporter::EMBED_ONLY`] flag is set.
+fn linked_media_dir(path: &Path, options: &ExportOptions) -> Option<PathBuf> {
+    if options.flag(ExcelExporter::EMBED_ONLY) {
+        return None;
+    }
+    let stem = path
+        .file_stem()
+        .map_or_else(|| "export".to_string(), |s| s.to_string_lossy().to_string());
+    Some(path.with_file_name(format!("{stem} media")))
+}
+
+/// Run an export that may write to the linked media directory. If it fails, the directory is
+/// removed again, unless it already existed before the export started.
+fn with_linked_media_dir<F>(media_dir: Option<&Path>, export: F) -> crate::Result<()>
+where
+    F: FnOnce() -> crate::Result<()>,
+{
+    let existed = media_dir.is_some_and(Path::exists);
+    let result = export();
+    if result.is_err()
+        && !existed
+        && let Some(media_dir) = media_dir
+        && media_dir.exists()
+        && let Err(e) = fs::remove_dir_all(media_dir)
+    {
+        tracing::warn!("Failed to remove linked media directory after failed export: {e}");
+    }
+    result
+}
+
+/// Write media to the linked media directory, returning a link to it relative to the workbook.
+fn write_linked_media(media_dir: &Path, media: &MediaFile) -> std::io::Result<String> {
+    let filename = media.suggested_filename(Some(&media.hash()));
+    fs::create_dir_all(media_dir)?;
+    fs::write(media_dir.join(&filename), media.data())?;
+    let dir_name = media_dir
+        .file_name()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    Ok(format!("file:///{dir_name}/{filename}"))
+}
+
 /// Create the worksheet for the metadata
 fn create_metadata_sheet(
     worksheet: &mut Worksheet,
@@ -108,6 +209,7 @@ fn create_metadata_sheet(
 fn create_summary_sheet(
     worksheet: &mut Worksheet,
     package: &EvidencePackage,
+    statistics: &PackageStatistics,
 ) -> Result<(), Box<dyn std::error::Error>> {
     tracing::debug!("Creating excel sheet for summary");
     worksheet.set_name("Summary")?;
@@ -124,6 +226,10 @@ fn create_summary_sheet(
     worksheet.write_string_with_format(row, 1, "Summary", &title)?;
     row += 2;
 
+    // Write statistics
+    row = write_statistics(worksheet, row, package, statistics)?;
+    row += 1;
+
     // Write header row
     worksheet.write_string_with_format(row, 1, "Test Case", &bold_bordered)?;
     worksheet.write_string_with_format(row, 2, "Executed At", &bold_bordered)?;
@@ -178,11 +284,177 @@ fn create_summary_sheet(
     Ok(())
 }
 
+/// Write the statistics summarising a package to a worksheet, starting at a row. Returns the
+/// row after the statistics.
+fn write_statistics(
+    worksheet: &mut Worksheet,
+    mut row: u32,
+    package: &EvidencePackage,
+    statistics: &PackageStatistics,
+) -> Result<u32, Box<dyn std::error::Error>> {
+    let bold = Format::new().set_bold();
+    let bold_bordered = Format::new().set_bold().set_border(FormatBorder::Thin);
+    let bordered = Format::new().set_border(FormatBorder::Thin);
+    let percentage = Format::new().set_num_format("0.0%");
+
+    let statuses = statistics.statuses();
+    let figures = [
+        ("Test Cases", statistics.test_cases()),
+        ("Passed", statuses.passed()),
+        ("Failed", statuses.failed()),
+        ("Not Run", statuses.not_run()),
+        ("Evidence", statistics.evidence()),
+        ("Media Files", statistics.media_count()),
+    ];
+    for (label, value) in figures {
+        worksheet.write_string_with_format(row, 1, label, &bold)?;
+        worksheet.write_number(row, 2, u32::try_from(value)?)?;
+        row += 1;
+    }
+    if let Some(pass_rate) = statistics.pass_rate() {
+        worksheet.write_string_with_format(row, 1, "Pass Rate", &bold)?;
+        worksheet.write_number_with_format(row, 2, pass_rate, &percentage)?;
+        row += 1;
+    }
+    if let (Some(first), Some(last)) = (statistics.first_executed(), statistics.last_executed()) {
+        worksheet.write_string_with_format(row, 1, "Executed", &bold)?;
+        worksheet.write_string(
+            row,
+            2,
+            format!("{} to {}", first.to_rfc3339(), last.to_rfc3339()),
+        )?;
+        row += 1;
+    }
+
+    // Write the status of test cases by the value of each custom field
+    if let Some(fields) = package.metadata().custom_test_case_metadata() {
+        let mut fields = fields.iter().collect::<Vec<_>>();
+        fields.sort_by_key(|(key, _)| *key);
+        for (key, field) in fields {
+            let Some(values) = statistics.custom_fields().get(key) else {
+                continue;
+            };
+            row += 1;
+            worksheet.write_string_with_format(row, 1, field.name(), &bold_bordered)?;
+            for (col, header) in ["Passed", "Failed", "Not Run"].iter().enumerate() {
+                let col = u16::try_from(2 + col)?;
+                worksheet.write_string_with_format(row, col, *header, &bold_bordered)?;
+            }
+            row += 1;
+
+            let mut values = values.iter().collect::<Vec<_>>();
+            values.sort_by_key(|(value, _)| *value);
+            for (value, counts) in values {
+                worksheet.write_string_with_format(row, 1, value, &bordered)?;
+                for (col, count) in [counts.passed(), counts.failed(), counts.not_run()]
+                    .into_iter()
+                    .enumerate()
+                {
+                    let col = u16::try_from(2 + col)?;
+                    worksheet.write_number_with_format(
+                        row,
+                        col,
+                        u32::try_from(count)?,
+                        &bordered,
+                    )?;
+                }
+                row += 1;
+            }
+        }
+    }
+
+    Ok(row)
+}
+
+/// Create the worksheet for the traceability of requirements to test cases
+fn create_traceability_sheet(
+    worksheet: &mut Worksheet,
+    matrix: &TraceabilityMatrix,
+) -> Result<(), Box<dyn std::error::Error>> {
+    tracing::debug!("Creating excel sheet for traceability");
+    worksheet.set_name("Traceability")?;
+    worksheet.set_screen_gridlines(false);
+    worksheet.set_column_width(0, 3)?; // To appear tidy
+
+    let mut row = 1;
+
+    let title = Format::new().set_bold().set_font_size(14);
+    let bold_bordered = Format::new().set_bold().set_border(FormatBorder::Thin);
+    let bordered = Format::new().set_border(FormatBorder::Thin);
+    let centered = bordered.clone().set_align(FormatAlign::Center);
+    let uncovered = bordered.clone().set_background_color("#FEF7E0");
+    let failing = bordered.clone().set_background_color("#FCE8E6");
+    let passing = bordered.clone().set_background_color("#E6F4EA");
+
+    worksheet.write_string_with_format(row, 1, "Traceability", &title)?;
+    row += 2;
+
+    // Write header row
+    worksheet.write_string_with_format(row, 1, "Requirement", &bold_bordered)?;
+    worksheet.write_string_with_format(row, 2, "Title", &bold_bordered)?;
+    worksheet.write_string_with_format(row, 3, "Coverage", &bold_bordered)?;
+    for (idx, test_case) in matrix.test_cases().iter().enumerate() {
+        let col = u16::try_from(4 + idx)?;
+        worksheet.write_string_with_format(row, col, test_case.title(), &bold_bordered)?;
+    }
+    row += 1;
+
+    // Write data rows
+    for coverage in matrix.requirements() {
+        let requirement = coverage.requirement();
+        worksheet.write_string_with_format(row, 1, requirement.id(), &bordered)?;
+        if let Some(link) = requirement.link() {
+            let url = Url::new(link).set_text(requirement.id()).set_tip(link);
+            match worksheet.write_url_with_format(row, 1, url, &bordered) {
+                // Excel only supports some types of URL, so others are left as text
+                Ok(_)
+                | Err(
+                    XlsxError::UnknownUrlType(_)
+                    | XlsxError::ParameterError(_)
+                    | XlsxError::MaxUrlLengthExceeded,
+                ) => (),
+                Err(e) => return Err(e.into()),
+            }
+        }
+        worksheet.write_string_with_format(
+            row,
+            2,
+            requirement.title().as_deref().unwrap_or_default(),
+            &bordered,
+        )?;
+        let (status, format) = match coverage.status() {
+            CoverageStatus::Uncovered => ("Uncovered", &uncovered),
+            CoverageStatus::Failing => ("Failing", &failing),
+            CoverageStatus::NotRun => ("Not run", &bordered),
+            CoverageStatus::Passing => ("Passing", &passing),
+        };
+        worksheet.write_string_with_format(row, 3, status, format)?;
+        for (idx, test_case) in matrix.test_cases().iter().enumerate() {
+            let col = u16::try_from(4 + idx)?;
+            let mark = if coverage.is_covered_by(test_case.id()) {
+                match test_case.status() {
+                    None => "●",
+                    Some(TestCasePassStatus::Pass) => "✅",
+                    Some(TestCasePassStatus::Fail) => "❌",
+                }
+            } else {
+                ""
+            };
+            worksheet.write_string_with_format(row, col, mark, &centered)?;
+        }
+        row += 1;
+    }
+    worksheet.autofit();
+
+    Ok(())
+}
+
 /// Create the worksheet that holds the test case's information
 fn create_test_case_sheet(
     worksheet: &mut Worksheet,
     mut package: EvidencePackage,
     test_case: &TestCase,
+    media_dir: Option<&Path>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     tracing::debug!("Creating excel sheet for test case {}", test_case.id());
     worksheet.set_name(test_case.metadata().title())?;
@@ -239,6 +511,10 @@ fn create_test_case_sheet(
     }
     row += 1;
 
+    if !test_case.steps().is_empty() {
+        row = write_steps(worksheet, row, test_case)?;
+    }
+
     // Write evidence
     for evidence in test_case.evidence() {
         if let Some(caption) = evidence.caption() {
@@ -461,7 +737,7 @@ fn create_test_case_sheet(
                 }
             }
             EvidenceKind::Image => {
-                let data = evidence.value().get_data(&mut package)?;
+                let data = evidence.annotated_data(&mut package)?;
                 let image = Image::new_from_buffer(data.as_slice())?;
                 worksheet.insert_image(row, 1, &image)?;
 
@@ -472,14 +748,105 @@ fn create_test_case_sheet(
                 row += num_rows_to_skip;
             }
             EvidenceKind::Http => {
-                worksheet.write_string_with_format(row, 1, "HTTP Request", &bold)?;
+                let exchange = HttpExchange::from_evidence(evidence, &mut package)?;
+                row = write_http(worksheet, row, &exchange)?;
+            }
+            EvidenceKind::Log => {
+                let log = Log::from_evidence(evidence, &mut package)?;
+                row = write_log(worksheet, row, &log)?;
+            }
+            EvidenceKind::Table => {
+                let table = Table::from_evidence(evidence, &mut package)?;
+                row = write_table(worksheet, row, &table)?;
+            }
+            EvidenceKind::Diff => {
+                let diff = Diff::from_evidence(evidence, &mut package)?;
+                row = write_diff(worksheet, row, &diff)?;
+            }
+            EvidenceKind::Command => {
+                let command = CommandExecution::from_evidence(evidence, &mut package)?;
+                row = write_command(worksheet, row, &command)?;
+            }
+            EvidenceKind::Sql => {
+                let query = SqlQuery::from_evidence(evidence, &mut package)?;
+                row = write_sql(worksheet, row, &query)?;
+            }
+            EvidenceKind::Link => {
+                let link = Link::from_evidence(evidence, &mut package)?;
+                worksheet.write_string_with_format(
+                    row,
+                    1,
+                    link.system()
+                        .map_or("Link".to_string(), |system| system.to_string()),
+                    &bold,
+                )?;
+                let url = Url::new(link.url())
+                    .set_text(link.display_title())
+                    .set_tip(link.url());
+                match worksheet.write_url(row, 2, url) {
+                    Ok(_) => (),
+                    // Excel only supports some types of URL, so others are written as text
+                    Err(
+                        XlsxError::UnknownUrlType(_)
+                        | XlsxError::ParameterError(_)
+                        | XlsxError::MaxUrlLengthExceeded,
+                    ) => {
+                        worksheet.write_string(row, 2, link.display_title())?;
+                        if link.display_title() != link.url() {
+                            row += 1;
+                            worksheet.write_string(row, 2, link.url())?;
+                        }
+                    }
+                    Err(e) => return Err(e.into()),
+                }
                 row += 1;
-                let data = evidence.value().get_data(&mut package)?;
-                let text = String::from_utf8_lossy(data.as_slice());
-                for line in text.lines() {
-                    worksheet.write_string_with_format(row, 1, line, &file_data)?;
-                    row += 1;
+            }
+            EvidenceKind::Video => {
+                let media = match evidence.value() {
+                    EvidenceData::Media { hash } => package.get_media(hash)?.cloned(),
+                    _ => None,
+                };
+                let media = match media {
+                    Some(media) => media,
+                    None => MediaFile::from(evidence.value().get_data(&mut package)?),
+                };
+                let link = match media_dir {
+                    Some(media_dir) => Some(write_linked_media(media_dir, &media)?),
+                    None => None,
+                };
+
+                let poster = match evidence.poster() {
+                    Some(poster) => package.get_media(poster)?,
+                    None => None,
+                };
+                if let Some(poster) = poster {
+                    let mut image = Image::new_from_buffer(poster.data())?;
+                    if let Some(link) = &link {
+                        image = image.set_url(link.as_str())?;
+                    }
+                    worksheet.insert_image(row, 1, &image)?;
+
+                    // Calculate row offset
+                    let height_in = image.height() / image.height_dpi();
+                    let row_units_per_in = 4.87;
+                    let num_rows_to_skip = (height_in * row_units_per_in).ceil() as u32;
+                    row += num_rows_to_skip;
+                }
+
+                let mut text = media
+                    .original_filename()
+                    .clone()
+                    .unwrap_or_else(|| "Video".to_string());
+                if let Some(duration) = media.video_duration() {
+                    let seconds = duration.as_secs();
+                    text = format!("{text} ({}:{:02})", seconds / 60, seconds % 60);
                 }
+                if let Some(link) = &link {
+                    worksheet.write_url_with_text(row, 1, link.as_str(), text)?;
+                } else {
+                    worksheet.write_string(row, 1, text)?;
+                }
+                row += 1;
             }
             EvidenceKind::File => {
                 let data = evidence.value().get_data(&mut package)?;
@@ -517,6 +884,398 @@ fn create_test_case_sheet(
     Ok(())
 }
 
+/// Write a log as a table with filters, starting at the given row, returning the row after the
+/// table.
+fn write_log(worksheet: &mut Worksheet, mut row: u32, log: &Log) -> Result<u32, XlsxError> {
+    if log.entries().is_empty() {
+        return Ok(row);
+    }
+
+    let first_row = row;
+    let timestamp = Format::new().set_num_format("yyyy-mm-dd hh:mm:ss.000");
+    let message = Format::new().set_font_name("Courier New");
+    row += 1;
+    for entry in log.entries() {
+        if let Some(ts) = entry.timestamp() {
+            worksheet.write_with_format(row, 1, &ts.naive_local(), &timestamp)?;
+        }
+        if let Some(level) = entry.level() {
+            let level_format = match level {
+                LogLevel::Trace | LogLevel::Debug => Format::new().set_font_color("#808080"),
+                LogLevel::Info => Format::new(),
+                LogLevel::Warn => Format::new().set_font_color("#9C5700"),
+                LogLevel::Error | LogLevel::Fatal => Format::new().set_font_color("#9C0006"),
+            };
+            worksheet.write_string_with_format(row, 2, level.to_string(), &level_format)?;
+        }
+        if let Some(source) = entry.source() {
+            worksheet.write_string(row, 3, source)?;
+        }
+        worksheet.write_string_with_format(row, 4, entry.message(), &message)?;
+        row += 1;
+    }
+
+    let table = ExcelTable::new().set_columns(&[
+        ExcelTableColumn::new().set_header("Time"),
+        ExcelTableColumn::new().set_header("Level"),
+        ExcelTableColumn::new().set_header("Source"),
+        ExcelTableColumn::new().set_header("Message"),
+    ]);
+    worksheet.add_table(first_row, 1, row - 1, 4, &table)?;
+    Ok(row + 1)
+}
+
+/// Write a command execution as labelled cells, returning the next free row. Terminal escape codes
+/// are removed from the output.
+fn write_command(
+    worksheet: &mut Worksheet,
+    mut row: u32,
+    command: &CommandExecution,
+) -> Result<u32, XlsxError> {
+    let label = Format::new().set_bold();
+    let monospace = Format::new().set_font_name("Courier New");
+    let output = Format::new()
+        .set_font_name("Courier New")
+        .set_border_left(FormatBorder::Thick);
+
+    worksheet.write_string_with_format(row, 1, "Command", &label)?;
+    worksheet.write_string_with_format(row, 2, command.command(), &monospace)?;
+    row += 1;
+    if let Some(working_directory) = command.working_directory() {
+        worksheet.write_string_with_format(row, 1, "Directory", &label)?;
+        worksheet.write_string_with_format(row, 2, working_directory, &monospace)?;
+        row += 1;
+    }
+    for (index, (key, value)) in command.environment().iter().enumerate() {
+        if index == 0 {
+            worksheet.write_string_with_format(row, 1, "Environment", &label)?;
+        }
+        worksheet.write_string_with_format(row, 2, format!("{key}={value}"), &monospace)?;
+        row += 1;
+    }
+    if let Some(exit_code) = command.exit_code() {
+        let exit_format = if command.succeeded() {
+            Format::new().set_align(FormatAlign::Left)
+        } else {
+            Format::new()
+                .set_align(FormatAlign::Left)
+                .set_font_color("#9C0006")
+                .set_bold()
+        };
+        worksheet.write_string_with_format(row, 1, "Exit code", &label)?;
+        worksheet.write_number_with_format(row, 2, exit_code, &exit_format)?;
+        row += 1;
+    }
+    if let Some(duration) = command.duration() {
+        worksheet.write_string_with_format(row, 1, "Duration", &label)?;
+        worksheet.write_string(row, 2, format!("{:.3}s", duration.as_secs_f64()))?;
+        row += 1;
+    }
+
+    for (name, text) in [
+        ("Standard output", command.stdout()),
+        ("Standard error", command.stderr()),
+    ] {
+        if text.is_empty() {
+            continue;
+        }
+        worksheet.write_string_with_format(row, 1, name, &label)?;
+        row += 1;
+        for line in crate::strip_ansi(text).lines() {
+            worksheet.write_string_with_format(row, 1, line, &output)?;
+            row += 1;
+        }
+    }
+    Ok(row)
+}
+
+/// Write an HTTP exchange, with the start line, headers and body of the request followed by
+/// those of the response, returning the next free row.
+fn write_http(
+    worksheet: &mut Worksheet,
+    mut row: u32,
+    exchange: &HttpExchange,
+) -> Result<u32, XlsxError> {
+    /// Write the headers and body of one side of the exchange
+    fn write_message(
+        worksheet: &mut Worksheet,
+        mut row: u32,
+        headers: &[HttpHeader],
+        body: &str,
+    ) -> Result<u32, XlsxError> {
+        let name = Format::new().set_bold().set_font_name("Courier New");
+        let monospace = Format::new().set_font_name("Courier New");
+        let body_format = monospace.clone().set_border_left(FormatBorder::Thick);
+
+        for header in headers {
+            worksheet.write_string_with_format(row, 1, header.name(), &name)?;
+            worksheet.write_string_with_format(row, 2, header.value(), &monospace)?;
+            row += 1;
+        }
+        for line in body.lines() {
+            worksheet.write_string_with_format(row, 1, line, &body_format)?;
+            row += 1;
+        }
+        Ok(row)
+    }
+
+    let label = Format::new().set_bold();
+    let monospace = Format::new().set_font_name("Courier New");
+
+    let request = exchange.request();
+    worksheet.write_string_with_format(row, 1, "HTTP Request", &label)?;
+    worksheet.write_string_with_format(row, 2, request.start_line(), &monospace)?;
+    row += 1;
+    row = write_message(worksheet, row, request.headers(), &request.pretty_body())?;
+
+    if let Some(response) = exchange.response() {
+        let status = match response.status() {
+            Some(_) if response.is_success() => monospace.clone().set_font_color("#006100"),
+            Some(_) => monospace.clone().set_font_color("#9C0006").set_bold(),
+            None => monospace.clone(),
+        };
+        row += 1;
+        worksheet.write_string_with_format(row, 1, "HTTP Response", &label)?;
+        worksheet.write_string_with_format(row, 2, response.start_line(), &status)?;
+        row += 1;
+        row = write_message(worksheet, row, response.headers(), &response.pretty_body())?;
+    }
+
+    if let Some(duration) = exchange.duration() {
+        worksheet.write_string_with_format(row, 1, "Duration", &label)?;
+        worksheet.write_string(row, 2, format!("{:.3}s", duration.as_secs_f64()))?;
+        row += 1;
+    }
+    Ok(row)
+}
+
+/// Write a SQL query with syntax highlighting, one line per row, followed by its details and the
+/// rows it returned, returning the next free row.
+fn write_sql(worksheet: &mut Worksheet, mut row: u32, query: &SqlQuery) -> Result<u32, XlsxError> {
+    let label = Format::new().set_bold();
+    let plain = Format::new().set_font_name("Courier New");
+    let keyword = plain.clone().set_bold().set_font_color("#0000CC");
+    let string = plain.clone().set_font_color("#A31515");
+    let number = plain.clone().set_font_color("#098658");
+    let comment = plain.clone().set_italic().set_font_color("#808080");
+    let parameter = plain.clone().set_font_color("#AF00DB");
+
+    // Tokens can span lines, so split them into the segments of each line first
+    let mut lines: Vec<Vec<(&Format, &str)>> = vec![vec![]];
+    for (token, text) in query.tokens() {
+        let format = match token {
+            SqlToken::Keyword => &keyword,
+            SqlToken::String => &string,
+            SqlToken::Number => &number,
+            SqlToken::Comment => &comment,
+            SqlToken::Parameter => &parameter,
+            SqlToken::Identifier | SqlToken::Other => &plain,
+        };
+        for (index, part) in text.split('\n').enumerate() {
+            if index > 0 {
+                lines.push(vec![]);
+            }
+            let part = part.trim_end_matches('\r');
+            if !part.is_empty() {
+                lines
+                    .last_mut()
+                    .expect("there is always a line")
+                    .push((format, part));
+            }
+        }
+    }
+    for segments in &lines {
+        // Excel requires rich strings to have at least one segment
+        if !segments.is_empty() {
+            worksheet.write_rich_string(row, 1, segments)?;
+        }
+        row += 1;
+    }
+
+    if let Some(database) = query.database() {
+        worksheet.write_string_with_format(row, 1, "Database", &label)?;
+        worksheet.write_string(row, 2, database)?;
+        row += 1;
+    }
+    worksheet.write_string_with_format(row, 1, "Rows", &label)?;
+    worksheet.write_with_format(
+        row,
+        2,
+        query.effective_row_count(),
+        &Format::new().set_align(FormatAlign::Left),
+    )?;
+    row += 1;
+    if let Some(duration) = query.duration() {
+        worksheet.write_string_with_format(row, 1, "Duration", &label)?;
+        worksheet.write_string(row, 2, format!("{:.3}s", duration.as_secs_f64()))?;
+        row += 1;
+    }
+    row += 1;
+    write_table(worksheet, row, query.result())
+}
+
+/// Write a diff as two columns, with the expected content on the left and the actual content on
+/// the right, returning the next free row.
+fn write_diff(worksheet: &mut Worksheet, mut row: u32, diff: &Diff) -> Result<u32, XlsxError> {
+    /// The columns spanned by the expected content. These are roughly as wide as the next six.
+    const EXPECTED_COLS: (u16, u16) = (1, 4);
+    /// The columns spanned by the actual content.
+    const ACTUAL_COLS: (u16, u16) = (5, 10);
+
+    let header = Format::new()
+        .set_bold()
+        .set_border(FormatBorder::Thin)
+        .set_background_color("#F0F0F0");
+    worksheet.merge_range(
+        row,
+        EXPECTED_COLS.0,
+        row,
+        EXPECTED_COLS.1,
+        "Expected",
+        &header,
+    )?;
+    worksheet.merge_range(row, ACTUAL_COLS.0, row, ACTUAL_COLS.1, "Actual", &header)?;
+    row += 1;
+
+    let line_format = |line: Option<&DiffLine>| {
+        let format = Format::new()
+            .set_font_name("Courier New")
+            .set_border_left(FormatBorder::Thin)
+            .set_border_right(FormatBorder::Thin);
+        match line.map(DiffLine::change) {
+            Some(DiffChange::Unchanged) => format,
+            Some(DiffChange::Removed) => format.set_background_color("#FFEBE9"),
+            Some(DiffChange::Added) => format.set_background_color("#DAFBE1"),
+            None => format.set_background_color("#F6F8FA"),
+        }
+    };
+    for (expected, actual) in diff.side_by_side() {
+        worksheet.merge_range(
+            row,
+            EXPECTED_COLS.0,
+            row,
+            EXPECTED_COLS.1,
+            expected.as_ref().map_or("", |line| line.text()),
+            &line_format(expected.as_ref()),
+        )?;
+        worksheet.merge_range(
+            row,
+            ACTUAL_COLS.0,
+            row,
+            ACTUAL_COLS.1,
+            actual.as_ref().map_or("", |line| line.text()),
+            &line_format(actual.as_ref()),
+        )?;
+        row += 1;
+    }
+    Ok(row + 1)
+}
+
+/// Write the steps of a test case as a native Excel table, returning the next free row.
+fn write_steps(
+    worksheet: &mut Worksheet,
+    mut row: u32,
+    test_case: &TestCase,
+) -> Result<u32, XlsxError> {
+    let first_row = row;
+    row += 1;
+    for (idx, step) in test_case.steps().iter().enumerate() {
+        let status = match step.status() {
+            None => "",
+            Some(StepStatus::Pass) => "✅ Pass",
+            Some(StepStatus::Fail) => "❌ Fail",
+            Some(StepStatus::Blocked) => "⛔ Blocked",
+            Some(StepStatus::Skipped) => "Skipped",
+        };
+        let evidence = step
+            .evidence()
+            .iter()
+            .map(|index| format!("#{}", index + 1))
+            .collect::<Vec<_>>()
+            .join(", ");
+        worksheet.write_number(row, 1, u32::try_from(idx + 1).unwrap_or(u32::MAX))?;
+        worksheet.write_string(row, 2, step.action())?;
+        worksheet.write_string(row, 3, step.expected().as_deref().unwrap_or_default())?;
+        worksheet.write_string(row, 4, step.actual().as_deref().unwrap_or_default())?;
+        worksheet.write_string(row, 5, status)?;
+        worksheet.write_string(row, 6, evidence)?;
+        row += 1;
+    }
+
+    let columns = ["#", "Action", "Expected", "Actual", "Status", "Evidence"]
+        .into_iter()
+        .map(|header| ExcelTableColumn::new().set_header(header))
+        .collect::<Vec<_>>();
+    let excel_table = ExcelTable::new().set_columns(&columns);
+    worksheet.add_table(first_row, 1, row - 1, 6, &excel_table)?;
+    Ok(row + 1)
+}
+
+/// Write a table as a native Excel table with filters, returning the next free row.
+fn write_table(worksheet: &mut Worksheet, mut row: u32, table: &Table) -> Result<u32, XlsxError> {
+    if table.columns().is_empty() {
+        return Ok(row);
+    }
+
+    // Excel requires headers to be present and unique
+    let mut headers: Vec<String> = vec![];
+    for (index, column) in table.columns().iter().enumerate() {
+        let base = if column.trim().is_empty() {
+            format!("Column {}", index + 1)
+        } else {
+            column.clone()
+        };
+        let mut header = base.clone();
+        let mut suffix = 2;
+        while headers
+            .iter()
+            .any(|existing| existing.eq_ignore_ascii_case(&header))
+        {
+            header = format!("{base} {suffix}");
+            suffix += 1;
+        }
+        headers.push(header);
+    }
+    let last_col = u16::try_from(headers.len()).unwrap_or(u16::MAX);
+
+    if table.rows().is_empty() {
+        // Excel tables must have at least one data row, so just write the headers
+        let bold = Format::new().set_bold();
+        for (col, header) in (1..).zip(&headers) {
+            worksheet.write_string_with_format(row, col, header, &bold)?;
+        }
+        return Ok(row + 2);
+    }
+
+    let first_row = row;
+    row += 1;
+    for cells in table.rows() {
+        for (col, cell) in (1..).zip(cells) {
+            match cell {
+                TableCell::Empty => (),
+                TableCell::Boolean(value) => {
+                    worksheet.write_boolean(row, col, *value)?;
+                }
+                TableCell::Number(value) => {
+                    worksheet.write_number(row, col, *value)?;
+                }
+                TableCell::Text(value) => {
+                    worksheet.write_string(row, col, value)?;
+                }
+            }
+        }
+        row += 1;
+    }
+
+    let columns = headers
+        .iter()
+        .map(|header| ExcelTableColumn::new().set_header(header))
+        .collect::<Vec<_>>();
+    let excel_table = ExcelTable::new().set_columns(&columns);
+    worksheet.add_table(first_row, 1, row - 1, last_col, &excel_table)?;
+    Ok(row + 1)
+}
+
 /// Convert Angelmark to Excel format data
 fn angelmark_to_excel(angelmark: &AngelmarkText, format: Format) -> (Format, String) {
     match angelmark {
@@ -528,3 +1287,60 @@ fn angelmark_to_excel(angelmark: &AngelmarkText, format: Format) -> (Format, Str
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exporters::ExportOptionValue;
+
+    #[test]
+    fn linked_media_dir_is_named_after_workbook() {
+        let options = ExportOptions::new();
+        assert_eq!(
+            linked_media_dir(Path::new("/exports/report.xlsx"), &options),
+            Some(PathBuf::from("/exports/report media"))
+        );
+
+        let options =
+            ExportOptions::new().with(ExcelExporter::EMBED_ONLY, ExportOptionValue::Flag(true));
+        assert_eq!(
+            linked_media_dir(Path::new("/exports/report.xlsx"), &options),
+            None
+        );
+    }
+
+    #[test]
+    fn linked_media_dir_is_removed_on_failure() {
+        let media_dir = std::env::temp_dir().join(format!("evidenceangel-{}", Uuid::new_v4()));
+        let result = with_linked_media_dir(Some(&media_dir), || {
+            write_linked_media(&media_dir, &MediaFile::from(b"video".to_vec()))?;
+            Err(crate::Error::OtherExportError("failed".into()))
+        });
+        assert!(result.is_err());
+        assert!(!media_dir.exists());
+    }
+
+    #[test]
+    fn linked_media_dir_is_kept_on_success() {
+        let media_dir = std::env::temp_dir().join(format!("evidenceangel-{}", Uuid::new_v4()));
+        with_linked_media_dir(Some(&media_dir), || {
+            write_linked_media(&media_dir, &MediaFile::from(b"video".to_vec()))?;
+            Ok(())
+        })
+        .unwrap();
+        assert!(media_dir.exists());
+        fs::remove_dir_all(&media_dir).unwrap();
+    }
+
+    #[test]
+    fn existing_linked_media_dir_is_kept_on_failure() {
+        let media_dir = std::env::temp_dir().join(format!("evidenceangel-{}", Uuid::new_v4()));
+        fs::create_dir_all(&media_dir).unwrap();
+        let result = with_linked_media_dir(Some(&media_dir), || {
+            Err(crate::Error::OtherExportError("failed".into()))
+        });
+        assert!(result.is_err());
+        assert!(media_dir.exists());
+        fs::remove_dir_all(&media_dir).unwrap();
+    }
+}
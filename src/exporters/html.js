@@ -21,3 +21,113 @@ let tabAnchorMatch = () => {
 
 window.addEventListener("hashchange", tabAnchorMatch);
 window.addEventListener("DOMContentLoaded", tabAnchorMatch);
+
+// Browsers refuse to navigate to data URLs directly, so open full size images
+// linked from thumbnails as blobs instead.
+document.addEventListener("click", (event) => {
+    let link = event.target.closest("a.thumbnail");
+    if (link) {
+        event.preventDefault();
+        fetch(link.href)
+            .then((res) => res.blob())
+            .then((blob) => window.open(URL.createObjectURL(blob), "_blank"));
+    }
+});
+
+// Add level and text filters to logs.
+let addLogFilters = () => {
+    let levels = ["TRACE", "DEBUG", "INFO", "WARN", "ERROR", "FATAL"];
+    document.querySelectorAll(".log-container").forEach((container) => {
+        let filter = document.createElement("div");
+        filter.classList.add("log-filter");
+
+        let level = document.createElement("select");
+        level.add(new Option("All levels", "-1"));
+        levels.forEach((name, index) => level.add(new Option(`${name} and above`, index)));
+
+        let search = document.createElement("input");
+        search.type = "search";
+        search.placeholder = "Filter...";
+
+        let apply = () => {
+            let minLevel = parseInt(level.value);
+            let text = search.value.toLowerCase();
+            container.querySelectorAll(".log-entry").forEach((row) => {
+                let rowLevel = row.dataset.level === undefined ? -1 : parseInt(row.dataset.level);
+                let levelMatches = minLevel < 0 || rowLevel >= minLevel;
+                let textMatches = text === "" || row.textContent.toLowerCase().includes(text);
+                row.hidden = !(levelMatches && textMatches);
+            });
+        };
+        level.addEventListener("change", apply);
+        search.addEventListener("input", apply);
+
+        filter.append(level, search);
+        container.prepend(filter);
+    });
+};
+
+window.addEventListener("DOMContentLoaded", addLogFilters);
+
+// Sort tables by a column when its header is clicked.
+let addTableSorting = () => {
+    document.querySelectorAll(".data-table").forEach((table) => {
+        let headers = table.querySelectorAll("th");
+        headers.forEach((header, column) => {
+            header.addEventListener("click", () => {
+                let descending = header.dataset.sort === "ascending";
+                headers.forEach((h) => delete h.dataset.sort);
+                header.dataset.sort = descending ? "descending" : "ascending";
+
+                let body = table.querySelector("tbody");
+                let sortValue = (row) => {
+                    let cell = row.cells[column];
+                    return cell.dataset.sortValue === undefined
+                        ? cell.textContent.toLowerCase()
+                        : parseFloat(cell.dataset.sortValue);
+                };
+                let rows = Array.from(body.rows).sort((a, b) => {
+                    let [x, y] = [sortValue(a), sortValue(b)];
+                    // Numbers sort before text
+                    if (typeof x !== typeof y) {
+                        return typeof x === "number" ? -1 : 1;
+                    }
+                    return x < y ? -1 : x > y ? 1 : 0;
+                });
+                if (descending) {
+                    rows.reverse();
+                }
+                body.append(...rows);
+            });
+        });
+    });
+};
+
+window.addEventListener("DOMContentLoaded", addTableSorting);
+
+// Allow switching diffs between unified and side by side views.
+let addDiffViews = () => {
+    document.querySelectorAll(".diff-container").forEach((container) => {
+        let unified = container.querySelector(".diff-unified");
+        let sideBySide = container.querySelector(".diff-side-by-side");
+
+        let view = document.createElement("div");
+        view.classList.add("diff-view");
+        [
+            ["Unified", unified],
+            ["Side by side", sideBySide],
+        ].forEach(([label, table]) => {
+            let button = document.createElement("button");
+            button.type = "button";
+            button.textContent = label;
+            button.addEventListener("click", () => {
+                unified.hidden = table !== unified;
+                sideBySide.hidden = table !== sideBySide;
+            });
+            view.append(button);
+        });
+        container.prepend(view);
+    });
+};
+
+window.addEventListener("DOMContentLoaded", addDiffViews);
@@ -1,114 +1,192 @@
-use std::fs;
+use std::{fmt::Write, fs};
 
 use angelmark::{AngelmarkLine, AngelmarkTableAlignment, AngelmarkText, parse_angelmark};
 use base64::Engine;
 use build_html::{Html, HtmlContainer, HtmlElement, HtmlPage, HtmlTag};
 use uuid::Uuid;
 
-use crate::{EvidenceData, EvidenceKind, EvidencePackage, MediaFile, TestCase, TestCasePassStatus};
+use crate::{
+    CommandExecution, CoverageStatus, Diff, DiffChange, DiffLine, EvidenceData, EvidenceKind,
+    EvidencePackage, HttpExchange, HttpHeader, Link, Log, LogLevel, MediaFile, PackageStatistics,
+    SqlQuery, SqlToken, StepStatus, StructuredEvidence, Table, TableCell, TestCase,
+    TestCasePassStatus, TraceabilityMatrix,
+};
 
-use super::Exporter;
+use super::{ExportOption, ExportOptionKind, ExportOptions, Exporter};
 
 /// An exporter to HTML document.
 #[derive(Default)]
 pub struct HtmlExporter;
 
-impl Exporter for HtmlExporter {
-    fn export_name() -> String {
-        "HTML Document".to_string()
-    }
-
-    fn export_extension() -> String {
-        ".html".to_string()
-    }
+impl HtmlExporter {
+    /// The ID of the flag to embed thumbnails of images which link through to the full size
+    /// image, rather than displaying the full size image directly.
+    pub const THUMBNAILS: &'static str = "thumbnails";
 
-    fn export_package(
-        &mut self,
+    /// Render a package as an HTML document, as written by [`Exporter::export_package`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the package's media couldn't be read.
+    pub fn render_package(
+        &self,
         package: &mut EvidencePackage,
-        path: std::path::PathBuf,
-    ) -> crate::Result<()> {
-        let mut page = HtmlPage::new()
-            .with_title(html_escape::encode_text(package.metadata().title()))
-            .with_style(include_str!("html.css"))
-            .with_script_literal(include_str!("html.js"));
-
-        let title = HtmlElement::new(HtmlTag::Heading1)
-            .with_raw(html_escape::encode_text(package.metadata().title()));
-        page.add_html(title);
-
-        let mut authors = String::new();
-        for author in package.metadata().authors() {
-            if let Some(email) = author.email() {
-                authors.push_str(&format!("{} <{}>, ", author.name(), email));
-            } else {
-                authors.push_str(&format!("{}, ", author.name()));
-            }
-        }
-        authors.pop();
-        authors.pop();
+        options: &ExportOptions,
+    ) -> crate::Result<String> {
+        options.while_applied(package, None, |package| {
+            let use_thumbnails = options.flag(Self::THUMBNAILS);
+            let mut page = HtmlPage::new()
+                .with_title(html_escape::encode_text(package.metadata().title()))
+                .with_style(include_str!("html.css"))
+                .with_script_literal(include_str!("html.js"));
 
-        page.add_html(
-            HtmlElement::new(HtmlTag::ParagraphText)
-                .with_attribute("class", "authors")
-                .with_raw(html_escape::encode_text(&authors)),
-        );
+            let title = HtmlElement::new(HtmlTag::Heading1)
+                .with_raw(html_escape::encode_text(package.metadata().title()));
+            page.add_html(title);
 
-        if let Some(description) = package.metadata().description() {
-            page.add_html(
-                HtmlElement::new(HtmlTag::ParagraphText)
-                    .with_raw(html_escape::encode_text(description)),
-            );
-        }
+            let mut authors = String::new();
+            for author in package.metadata().authors() {
+                if let Some(email) = author.email() {
+                    authors.push_str(&format!("{} <{}>, ", author.name(), email));
+                } else {
+                    authors.push_str(&format!("{}, ", author.name()));
+                }
+            }
+            authors.pop();
+            authors.pop();
 
-        let test_cases: Vec<&TestCase> = package.test_case_iter()?.collect();
-        let mut first = true;
-        let mut test_case_elems = vec![];
-        let mut tab_container =
-            HtmlElement::new(HtmlTag::UnorderedList).with_attribute("class", "tabs");
-        for (idx, test_case) in test_cases.iter().enumerate() {
-            let mut tab_elem = HtmlElement::new(HtmlTag::ListElement)
-                .with_attribute("data-tab-index", idx)
-                .with_link(
-                    format!("#tab{idx}"),
-                    format!(
-                        "{}{}",
-                        match test_case.metadata().passed() {
-                            None => "",
-                            Some(TestCasePassStatus::Pass) => "✅&nbsp;",
-                            Some(TestCasePassStatus::Fail) => "❌&nbsp;",
-                        },
-                        test_case.metadata().title()
-                    ),
+            if !authors.is_empty() {
+                page.add_html(
+                    HtmlElement::new(HtmlTag::ParagraphText)
+                        .with_attribute("class", "authors")
+                        .with_raw(html_escape::encode_text(&authors)),
                 );
-            if first {
-                tab_elem.add_attribute("class", "selected");
             }
-            tab_container.add_html(tab_elem);
 
-            let elem = create_test_case_div(package.clone(), test_case)
-                .map_err(crate::Error::OtherExportError)?
-                .with_attribute("data-tab-index", idx)
-                .with_attribute(
-                    "class",
-                    format!(
-                        "tab-content {}",
-                        if first {
-                            first = false;
-                            "selected"
-                        } else {
-                            ""
-                        }
-                    ),
+            if let Some(description) = package.metadata().description() {
+                page.add_html(
+                    HtmlElement::new(HtmlTag::ParagraphText)
+                        .with_raw(html_escape::encode_text(description)),
                 );
-            test_case_elems.push(elem);
-        }
-        page.add_html(tab_container);
-        for elem in test_case_elems {
+            }
+
+            let statistics = package.statistics()?;
+            if statistics.test_cases() > 0 {
+                page.add_html(summary_to_html(package, &statistics));
+            }
+
+            let matrix = package.traceability_matrix()?;
+            if !matrix.is_empty() {
+                page.add_html(traceability_to_html(&matrix));
+            }
+
+            let test_cases: Vec<&TestCase> = package.test_case_iter()?.collect();
+            let mut first = true;
+            let mut test_case_elems = vec![];
+            let mut tab_container =
+                HtmlElement::new(HtmlTag::UnorderedList).with_attribute("class", "tabs");
+            for (idx, test_case) in test_cases.iter().enumerate() {
+                let mut tab_elem = HtmlElement::new(HtmlTag::ListElement)
+                    .with_attribute("data-tab-index", idx)
+                    .with_link(
+                        format!("#tab{idx}"),
+                        format!(
+                            "{}{}",
+                            match test_case.metadata().passed() {
+                                None => "",
+                                Some(TestCasePassStatus::Pass) => "✅&nbsp;",
+                                Some(TestCasePassStatus::Fail) => "❌&nbsp;",
+                            },
+                            html_escape::encode_text(test_case.metadata().title())
+                        ),
+                    );
+                if first {
+                    tab_elem.add_attribute("class", "selected");
+                }
+                tab_container.add_html(tab_elem);
+
+                let elem = create_test_case_div(package.clone(), test_case, use_thumbnails)
+                    .map_err(crate::Error::OtherExportError)?
+                    .with_attribute("data-tab-index", idx)
+                    .with_attribute(
+                        "class",
+                        format!(
+                            "tab-content {}",
+                            if first {
+                                first = false;
+                                "selected"
+                            } else {
+                                ""
+                            }
+                        ),
+                    );
+                test_case_elems.push(elem);
+            }
+            page.add_html(tab_container);
+            for elem in test_case_elems {
+                page.add_html(elem);
+            }
+
+            Ok(page.to_html_string())
+        })
+    }
+
+    /// Render a test case as an HTML document, as written by [`Exporter::export_case`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the test case doesn't exist, or its media couldn't be read.
+    pub fn render_case(
+        &self,
+        package: &mut EvidencePackage,
+        case: Uuid,
+        options: &ExportOptions,
+    ) -> crate::Result<String> {
+        options.while_applied(package, Some(case), |package| {
+            let mut page = HtmlPage::new()
+                .with_title(html_escape::encode_text(package.metadata().title()))
+                .with_style(include_str!("html.css"))
+                .with_script_literal(include_str!("html.js"));
+
+            let case = package
+                .test_case(case)?
+                .ok_or(crate::Error::OtherExportError(
+                    "Test case not found!".into(),
+                ))?;
+            let elem = create_test_case_div(package.clone(), case, options.flag(Self::THUMBNAILS))
+                .map_err(crate::Error::OtherExportError)?;
             page.add_html(elem);
-        }
 
-        fs::write(path, page.to_html_string())?;
+            Ok(page.to_html_string())
+        })
+    }
+}
 
+impl Exporter for HtmlExporter {
+    fn export_name(&self) -> String {
+        "HTML Document".to_string()
+    }
+
+    fn export_extension(&self) -> String {
+        ".html".to_string()
+    }
+
+    fn export_options(&self) -> Vec<ExportOption> {
+        vec![ExportOption::new(
+            Self::THUMBNAILS,
+            "Image Thumbnails",
+            "Embed image thumbnails that link to the full size image",
+            ExportOptionKind::Flag,
+        )]
+    }
+
+    fn export_package(
+        &mut self,
+        package: &mut EvidencePackage,
+        path: std::path::PathBuf,
+        options: &ExportOptions,
+    ) -> crate::Result<()> {
+        fs::write(path, self.render_package(package, options)?)?;
         Ok(())
     }
 
@@ -117,23 +195,9 @@ impl Exporter for HtmlExporter {
         package: &mut EvidencePackage,
         case: Uuid,
         path: std::path::PathBuf,
+        options: &ExportOptions,
     ) -> crate::Result<()> {
-        let mut page = HtmlPage::new()
-            .with_title(html_escape::encode_text(package.metadata().title()))
-            .with_style(include_str!("html.css"))
-            .with_script_literal(include_str!("html.js"));
-
-        let case = package
-            .test_case(case)?
-            .ok_or(crate::Error::OtherExportError(
-                "Test case not found!".into(),
-            ))?;
-        let elem =
-            create_test_case_div(package.clone(), case).map_err(crate::Error::OtherExportError)?;
-        page.add_html(elem);
-
-        fs::write(path, page.to_html_string())?;
-
+        fs::write(path, self.render_case(package, case, options)?)?;
         Ok(())
     }
 }
@@ -142,6 +206,7 @@ impl Exporter for HtmlExporter {
 fn create_test_case_div(
     mut package: EvidencePackage,
     test_case: &TestCase,
+    use_thumbnails: bool,
 ) -> Result<HtmlElement, Box<dyn std::error::Error>> {
     tracing::debug!("Creating HTML element for test case {}", test_case.id());
     let mut elem = HtmlElement::new(HtmlTag::Div);
@@ -199,8 +264,23 @@ fn create_test_case_div(
     }
     elem.add_html(meta_elem);
 
+    if !test_case.steps().is_empty() {
+        elem.add_html(steps_to_html(test_case));
+    }
+
     // Write evidence
-    for evidence in test_case.evidence() {
+    for (index, evidence) in test_case.evidence().iter().enumerate() {
+        // Give evidence attached to steps somewhere to link to
+        if test_case
+            .steps()
+            .iter()
+            .any(|step| step.evidence().contains(&index))
+        {
+            elem.add_html(
+                HtmlElement::new(HtmlTag::Span)
+                    .with_attribute("id", evidence_anchor(test_case, index)),
+            );
+        }
         if let Some(caption) = evidence.caption() {
             elem.add_html(
                 HtmlElement::new(HtmlTag::ParagraphText)
@@ -331,80 +411,152 @@ fn create_test_case_div(
                 }
             }
             EvidenceKind::Image => {
-                let data = evidence.value().get_data(&mut package)?;
+                let data = evidence.annotated_data(&mut package)?;
                 let media = MediaFile::from(data);
-                if let Some(mime) = media.mime_type() {
-                    let data = base64::prelude::BASE64_STANDARD_NO_PAD.encode(media.data());
+                let mime = media.media_type().to_string();
+                let data = base64::prelude::BASE64_STANDARD_NO_PAD.encode(media.data());
+                let src = format!("data:{mime};base64,{data}");
+
+                let thumbnail = match evidence.value() {
+                    // Thumbnails don't include annotations, so can't be used if any are present
+                    EvidenceData::Media { hash }
+                        if use_thumbnails && evidence.annotations().is_empty() =>
+                    {
+                        package
+                            .get_thumbnail(hash)?
+                            .filter(|thumbnail| thumbnail.size() < media.size())
+                            .cloned()
+                    }
+                    _ => None,
+                };
+                if let Some(thumbnail) = thumbnail {
+                    let thumbnail_mime = thumbnail.media_type().to_string();
+                    let thumbnail_data =
+                        base64::prelude::BASE64_STANDARD_NO_PAD.encode(thumbnail.data());
+                    elem.add_html(
+                        HtmlElement::new(HtmlTag::Link)
+                            .with_attribute("class", "thumbnail")
+                            .with_attribute(
+                                "href",
+                                html_escape::encode_double_quoted_attribute(&src),
+                            )
+                            .with_attribute("target", "_blank")
+                            .with_html(HtmlElement::new(HtmlTag::Image).with_attribute(
+                                "src",
+                                html_escape::encode_double_quoted_attribute(&format!(
+                                    "data:{thumbnail_mime};base64,{thumbnail_data}"
+                                )),
+                            )),
+                    );
+                } else {
                     elem.add_html(
-                        HtmlElement::new(HtmlTag::Image)
-                            .with_attribute("src", format!("data:{mime};base64,{data}")),
+                        HtmlElement::new(HtmlTag::Image).with_attribute(
+                            "src",
+                            html_escape::encode_double_quoted_attribute(&src),
+                        ),
                     );
                 }
             }
             EvidenceKind::Http => {
+                let exchange = HttpExchange::from_evidence(evidence, &mut package)?;
+                elem.add_html(http_to_html(&exchange));
+            }
+            EvidenceKind::Log => {
+                let log = Log::from_evidence(evidence, &mut package)?;
+                elem.add_html(log_to_html(&log));
+            }
+            EvidenceKind::Table => {
+                let table = Table::from_evidence(evidence, &mut package)?;
+                elem.add_html(table_to_html(&table));
+            }
+            EvidenceKind::Diff => {
+                let diff = Diff::from_evidence(evidence, &mut package)?;
+                elem.add_html(diff_to_html(&diff));
+            }
+            EvidenceKind::Command => {
+                let command = CommandExecution::from_evidence(evidence, &mut package)?;
+                elem.add_html(command_to_html(&command));
+            }
+            EvidenceKind::Sql => {
+                let query = SqlQuery::from_evidence(evidence, &mut package)?;
+                elem.add_html(sql_to_html(&query));
+            }
+            EvidenceKind::Link => {
+                let link = Link::from_evidence(evidence, &mut package)?;
+                elem.add_html(link_to_html(&link));
+            }
+            EvidenceKind::Video => {
                 let data = evidence.value().get_data(&mut package)?;
-                let data = String::from_utf8_lossy(data.as_slice());
-                let data_parts = data
-                    .split('\x1e')
-                    .map(std::string::ToString::to_string)
-                    .collect::<Vec<_>>();
-                let request = data_parts.first().cloned().unwrap_or_default();
-                let response = data_parts.get(1).cloned().unwrap_or_default();
+                let media = MediaFile::from(data);
+                let mime = media.media_type().to_string();
+                let data = base64::prelude::BASE64_STANDARD_NO_PAD.encode(media.data());
+                let src = format!("data:{mime};base64,{data}");
 
-                elem.add_html(
-                    HtmlElement::new(HtmlTag::Div)
-                        .with_attribute("class", "http-container")
-                        .with_html(
-                            HtmlElement::new(HtmlTag::Div)
-                                .with_attribute("class", "http-request")
-                                .with_html(
-                                    HtmlElement::new(HtmlTag::CodeText)
-                                        .with_preformatted(html_escape::encode_text(&request)),
-                                ),
-                        )
-                        .with_html(
-                            HtmlElement::new(HtmlTag::Div)
-                                .with_attribute("class", "http-response")
-                                .with_html(
-                                    HtmlElement::new(HtmlTag::CodeText)
-                                        .with_preformatted(html_escape::encode_text(&response)),
-                                ),
-                        ),
+                let mut video = HtmlElement::new(HtmlTag::Video)
+                    .with_attribute("controls", "")
+                    .with_attribute("preload", "metadata")
+                    .with_attribute("src", html_escape::encode_double_quoted_attribute(&src));
+                if let Some(poster) = evidence.poster()
+                    && let Some(poster) = package.get_media(poster)?
+                {
+                    let poster_mime = poster.media_type().to_string();
+                    let poster_data = base64::prelude::BASE64_STANDARD_NO_PAD.encode(poster.data());
+                    video.add_attribute(
+                        "poster",
+                        html_escape::encode_double_quoted_attribute(&format!(
+                            "data:{poster_mime};base64,{poster_data}"
+                        )),
+                    );
+                }
+                // Shown by browsers that can't play the video
+                video.add_html(
+                    HtmlElement::new(HtmlTag::Link)
+                        .with_attribute("href", html_escape::encode_double_quoted_attribute(&src))
+                        .with_attribute("download", "")
+                        .with_raw("Download video"),
                 );
+                elem.add_html(video);
             }
             EvidenceKind::File => {
                 let data = evidence.value().get_data(&mut package)?;
                 let data = base64::prelude::BASE64_STANDARD_NO_PAD.encode(data);
-                let mime = if let EvidenceData::Media { hash } = evidence.value() {
+                let (mime, filename) = if let EvidenceData::Media { hash } = evidence.value() {
                     if let Some(media) = package.get_media(hash).ok().flatten() {
-                        if let Some(mime) = media.mime_type() {
-                            mime.to_string()
-                        } else {
-                            "application/octet-stream".to_string()
-                        }
+                        (
+                            media.media_type().to_string(),
+                            Some(media.suggested_filename(evidence.original_filename().as_deref())),
+                        )
                     } else {
-                        "application/octet-stream".to_string()
+                        (
+                            "application/octet-stream".to_string(),
+                            evidence.original_filename().clone(),
+                        )
                     }
                 } else {
-                    "application/octet-stream".to_string()
+                    (
+                        "application/octet-stream".to_string(),
+                        evidence.original_filename().clone(),
+                    )
                 };
 
                 elem.add_html(
                     HtmlElement::new(HtmlTag::Div).with_html(
                         HtmlElement::new(HtmlTag::Link)
-                            .with_attribute("href", format!("data:{mime};base64,{data}"))
+                            .with_attribute(
+                                "href",
+                                html_escape::encode_double_quoted_attribute(&format!(
+                                    "data:{mime};base64,{data}"
+                                )),
+                            )
                             .with_attribute(
                                 "download",
-                                evidence
-                                    .original_filename()
-                                    .clone()
-                                    .unwrap_or(String::new()),
+                                html_escape::encode_double_quoted_attribute(
+                                    filename.as_deref().unwrap_or_default(),
+                                ),
                             )
-                            .with_raw(&if let Some(filename) = evidence.original_filename() {
-                                filename.clone()
-                            } else {
-                                "Unnamed file".to_string()
-                            }),
+                            .with_raw(html_escape::encode_text(
+                                &filename.unwrap_or_else(|| "Unnamed file".to_string()),
+                            )),
                     ),
                 );
             }
@@ -414,6 +566,737 @@ fn create_test_case_div(
     Ok(elem)
 }
 
+/// Convert terminal output to HTML, converting colour and style escape codes to styled spans.
+fn ansi_to_html(text: &str) -> String {
+    let mut html = String::new();
+    for span in crate::parse_ansi(text) {
+        let text = html_escape::encode_text(span.text());
+        let style = span.style();
+        if style.is_plain() {
+            html.push_str(&text);
+            continue;
+        }
+
+        let mut css = vec![];
+        if let Some(color) = style.foreground() {
+            css.push(format!("color: {}", color.to_hex()));
+        }
+        if let Some(color) = style.background() {
+            css.push(format!("background-color: {}", color.to_hex()));
+        }
+        if style.bold() {
+            css.push("font-weight: bold".to_string());
+        }
+        if style.dim() {
+            css.push("opacity: 0.7".to_string());
+        }
+        if style.italic() {
+            css.push("font-style: italic".to_string());
+        }
+        if style.underline() {
+            css.push("text-decoration: underline".to_string());
+        }
+        let _ = write!(html, "<span style=\"{}\">{text}</span>", css.join("; "));
+    }
+    html
+}
+
+/// Convert a command execution to a terminal-style block.
+fn command_to_html(command: &CommandExecution) -> HtmlElement {
+    let mut header = HtmlElement::new(HtmlTag::Div).with_attribute("class", "terminal-header");
+    if let Some(working_directory) = command.working_directory() {
+        header.add_html(
+            HtmlElement::new(HtmlTag::Span)
+                .with_attribute("class", "terminal-directory")
+                .with_raw(html_escape::encode_text(working_directory)),
+        );
+    }
+    header.add_html(
+        HtmlElement::new(HtmlTag::Span)
+            .with_attribute("class", "terminal-prompt")
+            .with_raw("$ "),
+    );
+    header.add_html(
+        HtmlElement::new(HtmlTag::Span)
+            .with_attribute("class", "terminal-command")
+            .with_raw(html_escape::encode_text(command.command())),
+    );
+
+    let mut terminal = HtmlElement::new(HtmlTag::Div)
+        .with_attribute("class", "terminal")
+        .with_html(header);
+
+    if !command.environment().is_empty() {
+        let mut environment =
+            HtmlElement::new(HtmlTag::Div).with_attribute("class", "terminal-environment");
+        for (key, value) in command.environment() {
+            environment.add_html(
+                HtmlElement::new(HtmlTag::Div)
+                    .with_raw(html_escape::encode_text(&format!("{key}={value}"))),
+            );
+        }
+        terminal.add_html(environment);
+    }
+
+    for (class, output) in [("stdout", command.stdout()), ("stderr", command.stderr())] {
+        if output.is_empty() {
+            continue;
+        }
+        terminal.add_html(
+            HtmlElement::new(HtmlTag::PreformattedText)
+                .with_attribute("class", format!("terminal-output terminal-{class}"))
+                .with_raw(ansi_to_html(output)),
+        );
+    }
+
+    let mut footer = HtmlElement::new(HtmlTag::Div).with_attribute("class", "terminal-footer");
+    if let Some(exit_code) = command.exit_code() {
+        footer.add_html(
+            HtmlElement::new(HtmlTag::Span)
+                .with_attribute(
+                    "class",
+                    if command.succeeded() {
+                        "terminal-exit success"
+                    } else {
+                        "terminal-exit failure"
+                    },
+                )
+                .with_raw(format!("Exit code {exit_code}")),
+        );
+    }
+    if let Some(duration) = command.duration() {
+        footer.add_html(
+            HtmlElement::new(HtmlTag::Span)
+                .with_attribute("class", "terminal-duration")
+                .with_raw(format!("{:.3}s", duration.as_secs_f64())),
+        );
+    }
+    terminal.with_html(footer)
+}
+
+/// Convert an HTTP exchange to HTML, with the request and response side by side. Each shows its
+/// start line, a table of headers and its body, which is reformatted if it is JSON or XML.
+fn http_to_html(exchange: &HttpExchange) -> HtmlElement {
+    /// Convert one side of the exchange to HTML
+    fn message_to_html(
+        class: &str,
+        start_line: HtmlElement,
+        headers: &[HttpHeader],
+        body: &str,
+    ) -> HtmlElement {
+        let mut message = HtmlElement::new(HtmlTag::Div)
+            .with_attribute("class", class)
+            .with_html(start_line);
+        if !headers.is_empty() {
+            let mut table =
+                HtmlElement::new(HtmlTag::Table).with_attribute("class", "http-headers");
+            for header in headers {
+                table.add_html(
+                    HtmlElement::new(HtmlTag::TableRow)
+                        .with_html(
+                            HtmlElement::new(HtmlTag::TableHeaderCell)
+                                .with_raw(html_escape::encode_text(header.name())),
+                        )
+                        .with_html(
+                            HtmlElement::new(HtmlTag::TableCell)
+                                .with_raw(html_escape::encode_text(header.value())),
+                        ),
+                );
+            }
+            message.add_html(table);
+        }
+        if !body.is_empty() {
+            message.add_html(
+                HtmlElement::new(HtmlTag::PreformattedText)
+                    .with_attribute("class", "http-body")
+                    .with_raw(html_escape::encode_text(body)),
+            );
+        }
+        message
+    }
+
+    let request = exchange.request();
+    let mut container = HtmlElement::new(HtmlTag::Div)
+        .with_attribute("class", "http-container")
+        .with_html(message_to_html(
+            "http-request",
+            HtmlElement::new(HtmlTag::Div)
+                .with_attribute("class", "http-start-line")
+                .with_raw(html_escape::encode_text(&request.start_line())),
+            request.headers(),
+            &request.pretty_body(),
+        ));
+    if let Some(response) = exchange.response() {
+        let status_class = match response.status() {
+            Some(_) if response.is_success() => "http-start-line http-success",
+            Some(_) => "http-start-line http-failure",
+            None => "http-start-line",
+        };
+        container.add_html(message_to_html(
+            "http-response",
+            HtmlElement::new(HtmlTag::Div)
+                .with_attribute("class", status_class)
+                .with_raw(html_escape::encode_text(&response.start_line())),
+            response.headers(),
+            &response.pretty_body(),
+        ));
+    }
+    if let Some(duration) = exchange.duration() {
+        container.add_html(
+            HtmlElement::new(HtmlTag::Div)
+                .with_attribute("class", "http-duration")
+                .with_raw(format!("{:.3}s", duration.as_secs_f64())),
+        );
+    }
+    container
+}
+
+/// Convert a link to a hyperlink, labelled with the type of system it refers to.
+fn link_to_html(link: &Link) -> HtmlElement {
+    let mut container = HtmlElement::new(HtmlTag::Div).with_attribute("class", "link");
+    if let Some(system) = link.system() {
+        container.add_html(
+            HtmlElement::new(HtmlTag::Span)
+                .with_attribute(
+                    "class",
+                    format!(
+                        "link-system link-system-{}",
+                        system.to_string().to_lowercase()
+                    ),
+                )
+                .with_raw(system),
+        );
+    }
+    if link.is_safe_to_open() {
+        container.add_html(
+            HtmlElement::new(HtmlTag::Link)
+                .with_attribute(
+                    "href",
+                    html_escape::encode_double_quoted_attribute(link.url()),
+                )
+                .with_attribute("target", "_blank")
+                .with_attribute("rel", "noopener noreferrer")
+                .with_raw(html_escape::encode_text(link.display_title())),
+        );
+    } else {
+        container.add_html(
+            HtmlElement::new(HtmlTag::Span)
+                .with_raw(html_escape::encode_text(link.display_title())),
+        );
+    }
+    if link.display_title() != link.url() {
+        container.add_html(
+            HtmlElement::new(HtmlTag::Span)
+                .with_attribute("class", "link-url")
+                .with_raw(html_escape::encode_text(link.url())),
+        );
+    }
+    container
+}
+
+/// Convert a SQL query to a highlighted code block, followed by the rows it returned.
+fn sql_to_html(query: &SqlQuery) -> HtmlElement {
+    let mut code = String::new();
+    for (token, text) in query.tokens() {
+        let text = html_escape::encode_text(text);
+        let class = match token {
+            SqlToken::Keyword => "sql-keyword",
+            SqlToken::Identifier => "sql-identifier",
+            SqlToken::String => "sql-string",
+            SqlToken::Number => "sql-number",
+            SqlToken::Comment => "sql-comment",
+            SqlToken::Parameter => "sql-parameter",
+            SqlToken::Other => {
+                code.push_str(&text);
+                continue;
+            }
+        };
+        let _ = write!(code, "<span class=\"{class}\">{text}</span>");
+    }
+
+    let mut details = vec![];
+    if let Some(database) = query.database() {
+        details.push(
+            HtmlElement::new(HtmlTag::Span)
+                .with_attribute("class", "sql-database")
+                .with_raw(html_escape::encode_text(database)),
+        );
+    }
+    let row_count = query.effective_row_count();
+    details.push(HtmlElement::new(HtmlTag::Span).with_raw(if row_count == 1 {
+        "1 row".to_string()
+    } else {
+        format!("{row_count} rows")
+    }));
+    if let Some(duration) = query.duration() {
+        details.push(
+            HtmlElement::new(HtmlTag::Span).with_raw(format!("{:.3}s", duration.as_secs_f64())),
+        );
+    }
+    let mut header = HtmlElement::new(HtmlTag::Div).with_attribute("class", "sql-details");
+    for detail in details {
+        header.add_html(detail);
+    }
+
+    let mut container = HtmlElement::new(HtmlTag::Div)
+        .with_attribute("class", "sql-query")
+        .with_html(
+            HtmlElement::new(HtmlTag::PreformattedText)
+                .with_attribute("class", "sql-code")
+                .with_raw(code),
+        )
+        .with_html(header);
+    if !query.result().columns().is_empty() {
+        container.add_html(table_to_html(query.result()));
+    }
+    container
+}
+
+/// Convert a diff to HTML. Both a unified and a side by side view are included, and the page
+/// script allows switching between them.
+fn diff_to_html(diff: &Diff) -> HtmlElement {
+    /// The class of a row or cell showing a line with this change
+    fn change_class(change: DiffChange) -> &'static str {
+        match change {
+            DiffChange::Unchanged => "diff-unchanged",
+            DiffChange::Removed => "diff-removed",
+            DiffChange::Added => "diff-added",
+        }
+    }
+
+    /// Create a cell containing a line number, if there is one
+    fn line_number(number: Option<usize>) -> HtmlElement {
+        HtmlElement::new(HtmlTag::TableCell)
+            .with_attribute("class", "diff-line-number")
+            .with_raw(number.map(|n| n.to_string()).unwrap_or_default())
+    }
+
+    /// Create a cell containing the text of a line, if there is one
+    fn line_text(line: Option<&DiffLine>) -> HtmlElement {
+        let mut cell = HtmlElement::new(HtmlTag::TableCell).with_attribute(
+            "class",
+            format!(
+                "diff-text {}",
+                line.map_or("diff-empty", |line| change_class(line.change()))
+            ),
+        );
+        cell.add_raw(
+            line.map(|line| html_escape::encode_text(line.text()).to_string())
+                .unwrap_or_default(),
+        );
+        cell
+    }
+
+    let mut unified = HtmlElement::new(HtmlTag::TableBody);
+    for line in diff.lines() {
+        let marker = match line.change() {
+            DiffChange::Unchanged => " ",
+            DiffChange::Removed => "-",
+            DiffChange::Added => "+",
+        };
+        unified.add_html(
+            HtmlElement::new(HtmlTag::TableRow)
+                .with_attribute("class", change_class(line.change()))
+                .with_html(line_number(line.expected_line()))
+                .with_html(line_number(line.actual_line()))
+                .with_html(
+                    HtmlElement::new(HtmlTag::TableCell)
+                        .with_attribute("class", "diff-marker")
+                        .with_raw(marker),
+                )
+                .with_html(line_text(Some(&line))),
+        );
+    }
+
+    let mut side_by_side = HtmlElement::new(HtmlTag::TableBody);
+    for (expected, actual) in diff.side_by_side() {
+        side_by_side.add_html(
+            HtmlElement::new(HtmlTag::TableRow)
+                .with_html(line_number(
+                    expected.as_ref().and_then(DiffLine::expected_line),
+                ))
+                .with_html(line_text(expected.as_ref()))
+                .with_html(line_number(actual.as_ref().and_then(DiffLine::actual_line)))
+                .with_html(line_text(actual.as_ref())),
+        );
+    }
+
+    let mut container = HtmlElement::new(HtmlTag::Div).with_attribute("class", "diff-container");
+    if !diff.has_differences() {
+        container.add_html(
+            HtmlElement::new(HtmlTag::ParagraphText)
+                .with_attribute("class", "diff-identical")
+                .with_raw("Expected and actual content are identical."),
+        );
+    }
+    container
+        .with_html(
+            HtmlElement::new(HtmlTag::Table)
+                .with_attribute("class", "diff diff-unified")
+                .with_html(unified),
+        )
+        .with_html(
+            HtmlElement::new(HtmlTag::Table)
+                .with_attribute("class", "diff diff-side-by-side")
+                .with_attribute("hidden", "hidden")
+                .with_html(
+                    HtmlElement::new(HtmlTag::TableHeader).with_html(
+                        HtmlElement::new(HtmlTag::TableRow)
+                            .with_html(
+                                HtmlElement::new(HtmlTag::TableHeaderCell)
+                                    .with_attribute("colspan", 2)
+                                    .with_raw("Expected"),
+                            )
+                            .with_html(
+                                HtmlElement::new(HtmlTag::TableHeaderCell)
+                                    .with_attribute("colspan", 2)
+                                    .with_raw("Actual"),
+                            ),
+                    ),
+                )
+                .with_html(side_by_side),
+        )
+}
+
+/// Convert a traceability matrix to an HTML section, with a row for each requirement and a column
+/// for each test case. Requirements that are uncovered or failing are highlighted.
+fn traceability_to_html(matrix: &TraceabilityMatrix) -> HtmlElement {
+    let mut header = HtmlElement::new(HtmlTag::TableRow)
+        .with_html(HtmlElement::new(HtmlTag::TableHeaderCell).with_raw("Requirement"))
+        .with_html(HtmlElement::new(HtmlTag::TableHeaderCell).with_raw("Coverage"));
+    for test_case in matrix.test_cases() {
+        header.add_html(
+            HtmlElement::new(HtmlTag::TableHeaderCell)
+                .with_raw(html_escape::encode_text(test_case.title())),
+        );
+    }
+
+    let mut body = HtmlElement::new(HtmlTag::TableBody);
+    for coverage in matrix.requirements() {
+        let requirement = coverage.requirement();
+        let mut name = html_escape::encode_text(requirement.id()).to_string();
+        if let Some(link) = requirement
+            .link()
+            .as_ref()
+            .and_then(|link| Link::new(link.clone()).ok())
+            .filter(Link::is_safe_to_open)
+        {
+            name = HtmlElement::new(HtmlTag::Link)
+                .with_attribute(
+                    "href",
+                    html_escape::encode_double_quoted_attribute(link.url()),
+                )
+                .with_attribute("target", "_blank")
+                .with_attribute("rel", "noopener noreferrer")
+                .with_raw(name)
+                .to_html_string();
+        }
+        if let Some(title) = requirement.title() {
+            let _ = write!(name, " {}", html_escape::encode_text(title));
+        }
+        let (class, status) = match coverage.status() {
+            CoverageStatus::Uncovered => ("coverage-uncovered", "⚠ Uncovered"),
+            CoverageStatus::Failing => ("coverage-failing", "❌ Failing"),
+            CoverageStatus::NotRun => ("coverage-not-run", "Not run"),
+            CoverageStatus::Passing => ("coverage-passing", "✅ Passing"),
+        };
+
+        let mut row = HtmlElement::new(HtmlTag::TableRow)
+            .with_attribute("class", class)
+            .with_html(HtmlElement::new(HtmlTag::TableCell).with_raw(name))
+            .with_html(
+                HtmlElement::new(HtmlTag::TableCell)
+                    .with_attribute("class", "coverage-status")
+                    .with_raw(status),
+            );
+        for test_case in matrix.test_cases() {
+            let mark = if coverage.is_covered_by(test_case.id()) {
+                match test_case.status() {
+                    None => "●",
+                    Some(TestCasePassStatus::Pass) => "✅",
+                    Some(TestCasePassStatus::Fail) => "❌",
+                }
+            } else {
+                ""
+            };
+            row.add_html(
+                HtmlElement::new(HtmlTag::TableCell)
+                    .with_attribute("class", "coverage-mark")
+                    .with_raw(mark),
+            );
+        }
+        body.add_html(row);
+    }
+
+    HtmlElement::new(HtmlTag::Div)
+        .with_attribute("class", "traceability")
+        .with_html(HtmlElement::new(HtmlTag::Heading2).with_raw("Traceability"))
+        .with_html(
+            HtmlElement::new(HtmlTag::Table)
+                .with_attribute("class", "traceability-table")
+                .with_html(HtmlElement::new(HtmlTag::TableHeader).with_html(header))
+                .with_html(body),
+        )
+}
+
+/// Convert the statistics of a package to an HTML summary, with the status of test cases
+/// overall and by the value of each custom field.
+fn summary_to_html(package: &EvidencePackage, statistics: &PackageStatistics) -> HtmlElement {
+    /// Create a row of a summary table with a heading and a value.
+    fn figure(label: &str, value: &dyn std::fmt::Display) -> HtmlElement {
+        HtmlElement::new(HtmlTag::TableRow)
+            .with_html(HtmlElement::new(HtmlTag::TableHeaderCell).with_raw(label))
+            .with_html(HtmlElement::new(HtmlTag::TableCell).with_raw(value.to_string()))
+    }
+
+    let statuses = statistics.statuses();
+    let mut figures = HtmlElement::new(HtmlTag::TableBody)
+        .with_html(figure("Test Cases", &statistics.test_cases()))
+        .with_html(figure("✅ Passed", &statuses.passed()))
+        .with_html(figure("❌ Failed", &statuses.failed()))
+        .with_html(figure("Not Run", &statuses.not_run()));
+    if let Some(pass_rate) = statistics.pass_rate() {
+        figures.add_html(figure("Pass Rate", &format!("{:.1}%", pass_rate * 100.0)));
+    }
+    if let (Some(first), Some(last)) = (statistics.first_executed(), statistics.last_executed()) {
+        figures.add_html(figure(
+            "Executed",
+            &format!(
+                "{} to {}",
+                first.format("%Y-%m-%d %H:%M:%S"),
+                last.format("%Y-%m-%d %H:%M:%S")
+            ),
+        ));
+    }
+    figures.add_html(figure("Evidence", &statistics.evidence()));
+    figures.add_html(figure(
+        "Media",
+        &format!(
+            "{} files, {} bytes",
+            statistics.media_count(),
+            statistics.media_size()
+        ),
+    ));
+
+    let mut summary = HtmlElement::new(HtmlTag::Div)
+        .with_attribute("class", "summary")
+        .with_html(HtmlElement::new(HtmlTag::Heading2).with_raw("Summary"))
+        .with_html(
+            HtmlElement::new(HtmlTag::Table)
+                .with_attribute("class", "summary-table")
+                .with_html(figures),
+        );
+
+    // Show the status of test cases by the value of each custom field
+    if let Some(fields) = package.metadata().custom_test_case_metadata() {
+        let mut fields = fields.iter().collect::<Vec<_>>();
+        fields.sort_by_key(|(key, _)| *key);
+        for (key, field) in fields {
+            let Some(values) = statistics.custom_fields().get(key) else {
+                continue;
+            };
+            let mut header = HtmlElement::new(HtmlTag::TableRow).with_html(
+                HtmlElement::new(HtmlTag::TableHeaderCell)
+                    .with_raw(html_escape::encode_text(field.name())),
+            );
+            for column in ["✅ Passed", "❌ Failed", "Not Run"] {
+                header.add_html(HtmlElement::new(HtmlTag::TableHeaderCell).with_raw(column));
+            }
+
+            let mut values = values.iter().collect::<Vec<_>>();
+            values.sort_by_key(|(value, _)| *value);
+            let mut body = HtmlElement::new(HtmlTag::TableBody);
+            for (value, counts) in values {
+                let mut row = HtmlElement::new(HtmlTag::TableRow).with_html(
+                    HtmlElement::new(HtmlTag::TableCell).with_raw(html_escape::encode_text(value)),
+                );
+                for count in [counts.passed(), counts.failed(), counts.not_run()] {
+                    row.add_html(HtmlElement::new(HtmlTag::TableCell).with_raw(count));
+                }
+                body.add_html(row);
+            }
+
+            summary.add_html(
+                HtmlElement::new(HtmlTag::Table)
+                    .with_attribute("class", "summary-table")
+                    .with_html(HtmlElement::new(HtmlTag::TableHeader).with_html(header))
+                    .with_html(body),
+            );
+        }
+    }
+
+    summary
+}
+
+/// Get the ID of the anchor for the evidence at an index in a test case.
+fn evidence_anchor(test_case: &TestCase, index: usize) -> String {
+    format!("evidence-{}-{}", test_case.id(), index + 1)
+}
+
+/// Convert the steps of a test case to an HTML table, with each step marked with its status so
+/// that it can be coloured, and links to the evidence attached to each step.
+fn steps_to_html(test_case: &TestCase) -> HtmlElement {
+    let mut header = HtmlElement::new(HtmlTag::TableRow);
+    for column in ["#", "Action", "Expected", "Actual", "Status", "Evidence"] {
+        header.add_html(HtmlElement::new(HtmlTag::TableHeaderCell).with_raw(column));
+    }
+
+    let mut body = HtmlElement::new(HtmlTag::TableBody);
+    for (idx, step) in test_case.steps().iter().enumerate() {
+        let (class, status) = match step.status() {
+            None => ("step-not-run", ""),
+            Some(StepStatus::Pass) => ("step-pass", "✅ Pass"),
+            Some(StepStatus::Fail) => ("step-fail", "❌ Fail"),
+            Some(StepStatus::Blocked) => ("step-blocked", "⛔ Blocked"),
+            Some(StepStatus::Skipped) => ("step-skipped", "Skipped"),
+        };
+        let evidence = step
+            .evidence()
+            .iter()
+            .filter(|index| **index < test_case.evidence().len())
+            .map(|index| {
+                HtmlElement::new(HtmlTag::Link)
+                    .with_attribute("href", format!("#{}", evidence_anchor(test_case, *index)))
+                    .with_raw(format!("#{}", index + 1))
+                    .to_html_string()
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        let text_cell = |text: Option<&String>| {
+            HtmlElement::new(HtmlTag::TableCell)
+                .with_raw(html_escape::encode_text(text.map_or("", String::as_str)))
+        };
+        body.add_html(
+            HtmlElement::new(HtmlTag::TableRow)
+                .with_attribute("class", class)
+                .with_html(HtmlElement::new(HtmlTag::TableCell).with_raw(idx + 1))
+                .with_html(text_cell(Some(step.action())))
+                .with_html(text_cell(step.expected().as_ref()))
+                .with_html(text_cell(step.actual().as_ref()))
+                .with_html(
+                    HtmlElement::new(HtmlTag::TableCell)
+                        .with_attribute("class", "step-status")
+                        .with_raw(status),
+                )
+                .with_html(HtmlElement::new(HtmlTag::TableCell).with_raw(evidence)),
+        );
+    }
+
+    HtmlElement::new(HtmlTag::Div)
+        .with_attribute("class", "steps-table-container")
+        .with_html(
+            HtmlElement::new(HtmlTag::Table)
+                .with_attribute("class", "steps-table")
+                .with_html(HtmlElement::new(HtmlTag::TableHeader).with_html(header))
+                .with_html(body),
+        )
+}
+
+/// Convert a table to an HTML table. Each cell is marked with its type so that numbers can be
+/// aligned, and the table is made sortable by the page script.
+fn table_to_html(table: &Table) -> HtmlElement {
+    let mut header = HtmlElement::new(HtmlTag::TableRow);
+    for column in table.columns() {
+        header.add_html(
+            HtmlElement::new(HtmlTag::TableHeaderCell).with_raw(html_escape::encode_text(column)),
+        );
+    }
+
+    let mut body = HtmlElement::new(HtmlTag::TableBody);
+    for cells in table.rows() {
+        let mut row = HtmlElement::new(HtmlTag::TableRow);
+        for cell in cells {
+            let mut td = HtmlElement::new(HtmlTag::TableCell);
+            match cell {
+                TableCell::Empty => td.add_attribute("class", "cell-empty"),
+                TableCell::Boolean(_) => td.add_attribute("class", "cell-boolean"),
+                TableCell::Number(value) => {
+                    td.add_attribute("class", "cell-number");
+                    td.add_attribute("data-sort-value", value);
+                }
+                TableCell::Text(_) => td.add_attribute("class", "cell-text"),
+            }
+            td.add_raw(html_escape::encode_text(&cell.to_string()));
+            row.add_html(td);
+        }
+        body.add_html(row);
+    }
+
+    HtmlElement::new(HtmlTag::Div)
+        .with_attribute("class", "data-table-container")
+        .with_html(
+            HtmlElement::new(HtmlTag::Table)
+                .with_attribute("class", "data-table")
+                .with_html(HtmlElement::new(HtmlTag::TableHeader).with_html(header))
+                .with_html(body),
+        )
+}
+
+/// Convert a log to an HTML table. Each row is marked with its level so that it can be coloured,
+/// and the table is given filtering controls by the page script.
+fn log_to_html(log: &Log) -> HtmlElement {
+    let mut body = HtmlElement::new(HtmlTag::TableBody);
+    for entry in log.entries() {
+        let mut row = HtmlElement::new(HtmlTag::TableRow);
+        if let Some(level) = entry.level() {
+            let level_index = LogLevel::ALL.iter().position(|l| *l == level).unwrap_or(0);
+            row.add_attribute(
+                "class",
+                format!("log-entry log-level-{}", level.to_string().to_lowercase()),
+            );
+            row.add_attribute("data-level", level_index);
+        } else {
+            row.add_attribute("class", "log-entry");
+        }
+        row.add_html(
+            HtmlElement::new(HtmlTag::TableCell)
+                .with_attribute("class", "log-timestamp")
+                .with_raw(
+                    entry
+                        .timestamp()
+                        .map(|timestamp| timestamp.format("%Y-%m-%d %H:%M:%S%.3f").to_string())
+                        .unwrap_or_default(),
+                ),
+        );
+        row.add_html(
+            HtmlElement::new(HtmlTag::TableCell)
+                .with_attribute("class", "log-level")
+                .with_raw(
+                    entry
+                        .level()
+                        .map(|level| level.to_string())
+                        .unwrap_or_default(),
+                ),
+        );
+        row.add_html(
+            HtmlElement::new(HtmlTag::TableCell)
+                .with_attribute("class", "log-source")
+                .with_raw(html_escape::encode_text(
+                    entry.source().as_deref().unwrap_or_default(),
+                )),
+        );
+        row.add_html(
+            HtmlElement::new(HtmlTag::TableCell)
+                .with_attribute("class", "log-message")
+                .with_raw(html_escape::encode_text(entry.message())),
+        );
+        body.add_html(row);
+    }
+
+    let mut header = HtmlElement::new(HtmlTag::TableRow);
+    for title in ["Time", "Level", "Source", "Message"] {
+        header.add_html(HtmlElement::new(HtmlTag::TableHeaderCell).with_raw(title));
+    }
+    HtmlElement::new(HtmlTag::Div)
+        .with_attribute("class", "log-container")
+        .with_html(
+            HtmlElement::new(HtmlTag::Table)
+                .with_attribute("class", "log")
+                .with_html(HtmlElement::new(HtmlTag::TableHeader).with_html(header))
+                .with_html(body),
+        )
+}
+
 /// Convert Angelmark to HTML elements
 fn angelmark_to_html(angelmark: &AngelmarkText, mut elem: HtmlElement) -> HtmlElement {
     match angelmark {
@@ -444,3 +1327,68 @@ fn angelmark_to_html(angelmark: &AngelmarkText, mut elem: HtmlElement) -> HtmlEl
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EvidenceBuilder, PackageBuilder, TestCaseBuilder};
+
+    /// Text which would add an event handler if it broke out of an HTML attribute.
+    const HOSTILE: &str = "a\" onmouseover=\"alert(document.domain)\" x=\"";
+
+    #[test]
+    fn file_attributes_are_escaped() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut package = PackageBuilder::new(dir.path().join("package.evp"), "Package")
+            .test_case(TestCaseBuilder::new("<b>\"onclick=\"</b>").evidence(
+                EvidenceBuilder::file_data(format!("{HOSTILE}.bin"), b"data".to_vec()),
+            ))
+            .build()
+            .unwrap();
+
+        let html = HtmlExporter
+            .render_package(&mut package, &ExportOptions::new())
+            .unwrap();
+        // The filename is still shown as the text of the link, where quotes are harmless
+        assert!(!html.contains("x=\".bin\">"));
+        assert!(html.contains(
+            "download=\"a&quot; onmouseover=&quot;alert(document.domain)&quot; x=&quot;.bin\""
+        ));
+        assert!(!html.contains("<b>"));
+    }
+
+    #[test]
+    fn video_attributes_are_escaped() {
+        let video = [
+            b"\x00\x00\x00\x18ftypmp42\x00\x00\x00\x00mp42isom".as_slice(),
+            &[0; 64],
+        ]
+        .concat();
+        let poster = [b"\x89PNG\r\n\x1a\n".as_slice(), &[0; 64]].concat();
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut package = PackageBuilder::new(dir.path().join("package.evp"), "Package")
+            .build()
+            .unwrap();
+        // Register the poster first with a hostile MIME type and filename, as if it had been
+        // read from a tampered package
+        let mut hostile_poster = MediaFile::from(poster.clone());
+        hostile_poster.set_explicit_mime_type(Some(format!("image/{HOSTILE}")));
+        hostile_poster.set_original_filename(Some(HOSTILE.to_string()));
+        package.add_media(hostile_poster).unwrap();
+        TestCaseBuilder::new("Video")
+            .evidence(EvidenceBuilder::video(video).poster(poster))
+            .build(&mut package)
+            .unwrap();
+        package.save().unwrap();
+
+        let html = HtmlExporter
+            .render_package(&mut package, &ExportOptions::new())
+            .unwrap();
+        assert!(!html.contains("onmouseover"));
+        assert!(html.contains("poster=\"data:image/png;base64,"));
+        assert!(
+            html.contains("<video controls=\"\" preload=\"metadata\" src=\"data:video/mp4;base64,")
+        );
+    }
+}
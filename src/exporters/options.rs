@@ -0,0 +1,512 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, FixedOffset};
+use getset::{CopyGetters, Getters, MutGetters, Setters};
+use uuid::Uuid;
+
+use crate::{EvidenceKind, EvidencePackage, TestCase, TestCasePassStatus};
+
+/// The kind of value an [`ExportOption`] takes.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ExportOptionKind {
+    /// The option is either on or off, and is off unless set.
+    Flag,
+    /// The option takes some text.
+    Text,
+}
+
+impl ExportOptionKind {
+    /// Parse the value of an option of this kind from a string, such as a command line
+    /// argument. An empty string turns a flag on. Returns [`None`] if the string isn't a valid
+    /// value.
+    #[must_use]
+    pub fn parse(self, value: &str) -> Option<ExportOptionValue> {
+        match self {
+            Self::Flag => match value.to_ascii_lowercase().as_str() {
+                "" | "true" | "yes" | "on" => Some(ExportOptionValue::Flag(true)),
+                "false" | "no" | "off" => Some(ExportOptionValue::Flag(false)),
+                _ => None,
+            },
+            Self::Text => Some(ExportOptionValue::Text(value.to_string())),
+        }
+    }
+}
+
+/// The value of an option set on [`ExportOptions`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ExportOptionValue {
+    /// The value of a [`ExportOptionKind::Flag`] option.
+    Flag(bool),
+    /// The value of a [`ExportOptionKind::Text`] option.
+    Text(String),
+}
+
+/// A description of an option understood by an exporter, so that it can be presented to
+/// users.
+#[derive(Clone, Debug, PartialEq, Eq, Getters, CopyGetters)]
+pub struct ExportOption {
+    /// The ID of this option, used to set it on [`ExportOptions`].
+    #[getset(get = "pub")]
+    id: String,
+    /// The name of this option, for users.
+    #[getset(get = "pub")]
+    name: String,
+    /// A description of what this option does, for users.
+    #[getset(get = "pub")]
+    description: String,
+    /// The kind of value this option takes.
+    #[getset(get_copy = "pub")]
+    kind: ExportOptionKind,
+}
+
+impl ExportOption {
+    /// Describe a new export option.
+    pub fn new<S, T, U>(id: S, name: T, description: U, kind: ExportOptionKind) -> Self
+    where
+        S: Into<String>,
+        T: Into<String>,
+        U: Into<String>,
+    {
+        Self {
+            id: id.into(),
+            name: name.into(),
+            description: description.into(),
+            kind,
+        }
+    }
+}
+
+/// Which test cases to include in an export. Test cases must match every condition that is
+/// set.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Getters, MutGetters, Setters)]
+#[getset(get = "pub", get_mut = "pub", set = "pub")]
+pub struct TestCaseFilter {
+    /// Only include the test cases with these IDs, if set.
+    test_cases: Option<Vec<Uuid>>,
+    /// Only include test cases with one of these statuses, if set. [`None`] matches test cases
+    /// that haven't been run.
+    statuses: Option<Vec<Option<TestCasePassStatus>>>,
+    /// Only include test cases with these values of custom metadata fields, by field ID.
+    custom_fields: HashMap<String, String>,
+    /// Only include test cases executed at or after this time, if set.
+    executed_after: Option<DateTime<FixedOffset>>,
+    /// Only include test cases executed at or before this time, if set.
+    executed_before: Option<DateTime<FixedOffset>>,
+}
+
+impl TestCaseFilter {
+    /// Whether this filter includes every test case.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Whether a test case is included by this filter.
+    #[must_use]
+    pub fn matches(&self, test_case: &TestCase) -> bool {
+        let metadata = test_case.metadata();
+        let executed = *metadata.execution_datetime();
+        self.test_cases
+            .as_ref()
+            .is_none_or(|ids| ids.contains(test_case.id()))
+            && self
+                .statuses
+                .as_ref()
+                .is_none_or(|statuses| statuses.contains(metadata.passed()))
+            && self.custom_fields.iter().all(|(field, value)| {
+                metadata
+                    .custom()
+                    .as_ref()
+                    .and_then(|custom| custom.get(field))
+                    .is_some_and(|v| v == value)
+            })
+            && self.executed_after.is_none_or(|after| executed >= after)
+            && self.executed_before.is_none_or(|before| executed <= before)
+    }
+}
+
+/// The order of test cases in an export.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum TestCaseOrder {
+    /// The order of the test cases in the package.
+    #[default]
+    Package,
+    /// Alphabetically by title.
+    Title,
+    /// From the earliest executed to the latest.
+    ExecutionTime,
+    /// Failed test cases first, then those that haven't been run, then those that passed.
+    Status,
+}
+
+/// Options passed to an exporter. Exporters describe the options they understand with
+/// [`Exporter::export_options`](super::Exporter::export_options), and ignore any others.
+///
+/// Options also scope what is exported, which is applied with
+/// [`ExportOptions::while_applied`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Getters, CopyGetters, MutGetters, Setters)]
+pub struct ExportOptions {
+    /// The values of the options that have been set, by option ID.
+    values: HashMap<String, ExportOptionValue>,
+    /// Which test cases to include when exporting a package.
+    #[getset(get = "pub", get_mut = "pub", set = "pub")]
+    filter: TestCaseFilter,
+    /// The order of test cases when exporting a package.
+    #[getset(get_copy = "pub", set = "pub")]
+    order: TestCaseOrder,
+    /// The kinds of evidence to leave out.
+    #[getset(get = "pub", get_mut = "pub", set = "pub")]
+    excluded_evidence: HashSet<EvidenceKind>,
+    /// Leave out the authors of the package.
+    #[getset(get_copy = "pub", set = "pub")]
+    omit_authors: bool,
+    /// Leave out the email addresses of the authors of the package.
+    #[getset(get_copy = "pub", set = "pub")]
+    omit_author_emails: bool,
+}
+
+impl ExportOptions {
+    /// Create a new set of options, with nothing set.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set an option, returning these options.
+    #[must_use]
+    pub fn with<S>(mut self, id: S, value: ExportOptionValue) -> Self
+    where
+        S: Into<String>,
+    {
+        self.set(id, value);
+        self
+    }
+
+    /// Set an option, replacing any value it already had.
+    pub fn set<S>(&mut self, id: S, value: ExportOptionValue)
+    where
+        S: Into<String>,
+    {
+        self.values.insert(id.into(), value);
+    }
+
+    /// Get the value of an option, if it has been set.
+    #[must_use]
+    pub fn get(&self, id: &str) -> Option<&ExportOptionValue> {
+        self.values.get(id)
+    }
+
+    /// Get whether a flag is turned on. Flags that haven't been set are off.
+    #[must_use]
+    pub fn flag(&self, id: &str) -> bool {
+        matches!(self.get(id), Some(ExportOptionValue::Flag(true)))
+    }
+
+    /// Get the text of an option, if it has been set.
+    #[must_use]
+    pub fn text(&self, id: &str) -> Option<&str> {
+        match self.get(id) {
+            Some(ExportOptionValue::Text(text)) => Some(text),
+            _ => None,
+        }
+    }
+
+    /// Get the IDs of the test cases to export from a package, in order. If a test case is
+    /// given, only it is exported, regardless of the filter.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the test cases couldn't be read.
+    pub fn selected_test_cases(
+        &self,
+        package: &EvidencePackage,
+        case: Option<Uuid>,
+    ) -> crate::Result<Vec<Uuid>> {
+        if let Some(case) = case {
+            return Ok(vec![case]);
+        }
+
+        let mut test_cases: Vec<&TestCase> = package
+            .test_case_iter()?
+            .filter(|test_case| self.filter.matches(test_case))
+            .collect();
+        match self.order {
+            TestCaseOrder::Package => (),
+            TestCaseOrder::Title => {
+                test_cases.sort_by_key(|test_case| test_case.metadata().title().to_lowercase());
+            }
+            TestCaseOrder::ExecutionTime => {
+                test_cases.sort_by_key(|test_case| *test_case.metadata().execution_datetime());
+            }
+            TestCaseOrder::Status => {
+                test_cases.sort_by_key(|test_case| match test_case.metadata().passed() {
+                    Some(TestCasePassStatus::Fail) => 0,
+                    None => 1,
+                    Some(TestCasePassStatus::Pass) => 2,
+                });
+            }
+        }
+        Ok(test_cases
+            .into_iter()
+            .map(|test_case| *test_case.id())
+            .collect())
+    }
+
+    /// Run a function, such as an export, with a package scoped by these options: only the
+    /// selected test cases in order, without excluded evidence, and without authors or their
+    /// email addresses if they are omitted. The package is put back afterwards, so this can be
+    /// used on packages with unsaved changes.
+    ///
+    /// If a test case is given, the package only contains it, regardless of the filter.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error from the function. The package is put back regardless.
+    pub fn while_applied<T, F>(
+        &self,
+        package: &mut EvidencePackage,
+        case: Option<Uuid>,
+        f: F,
+    ) -> crate::Result<T>
+    where
+        F: FnOnce(&mut EvidencePackage) -> crate::Result<T>,
+    {
+        let test_cases = self.selected_test_cases(package, case)?;
+        package.while_scoped(&test_cases, |package| {
+            for test_case in package.test_case_iter_mut()? {
+                for index in (0..test_case.evidence().len()).rev() {
+                    if self
+                        .excluded_evidence
+                        .contains(test_case.evidence()[index].kind())
+                    {
+                        test_case.remove_evidence(index);
+                    }
+                }
+            }
+            let authors = package.metadata_mut().authors_mut();
+            if self.omit_authors {
+                authors.clear();
+            } else if self.omit_author_emails {
+                for author in authors {
+                    author.set_email(None);
+                }
+            }
+            f(package)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::{Author, Error, PackageBuilder, TestCaseBuilder, TestStep};
+
+    /// Parse a time for a test case to be executed at.
+    fn at(time: &str) -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339(time).unwrap()
+    }
+
+    /// Create a package with three test cases, returning it with the IDs of the test cases in
+    /// package order.
+    fn package(dir: &TempDir) -> (EvidencePackage, Vec<Uuid>) {
+        let mut step = TestStep::new("Log in");
+        step.attach_evidence(0);
+        step.attach_evidence(1);
+        let package = PackageBuilder::new(dir.path().join("package.evp"), "Package")
+            .author(Author::new_with_email("Tester", "tester@example.com"))
+            .test_case(
+                TestCaseBuilder::new("Beta")
+                    .executed_at(at("2024-01-02T10:00:00Z"))
+                    .custom("env", "ci")
+                    .text("Plain")
+                    .rich_text("**Rich**")
+                    .step(step)
+                    .failed(),
+            )
+            .test_case(
+                TestCaseBuilder::new("alpha")
+                    .executed_at(at("2024-01-03T10:00:00Z"))
+                    .custom("env", "dev")
+                    .passed(),
+            )
+            .test_case(TestCaseBuilder::new("Gamma").executed_at(at("2024-01-01T10:00:00Z")))
+            .build()
+            .unwrap();
+        let ids = package
+            .test_case_iter()
+            .unwrap()
+            .map(|test_case| *test_case.id())
+            .collect();
+        (package, ids)
+    }
+
+    /// Capture everything about a package that [`ExportOptions::while_applied`] could change.
+    fn snapshot(package: &EvidencePackage) -> serde_json::Value {
+        serde_json::json!({
+            "manifest": package,
+            "test_cases": package.test_case_iter().unwrap().collect::<Vec<_>>(),
+        })
+    }
+
+    #[test]
+    fn empty_filters_match_everything() {
+        let dir = tempfile::tempdir().unwrap();
+        let (package, _) = package(&dir);
+        let filter = TestCaseFilter::default();
+        assert!(filter.is_empty());
+        assert!(
+            package
+                .test_case_iter()
+                .unwrap()
+                .all(|test_case| filter.matches(test_case))
+        );
+    }
+
+    #[test]
+    fn filters_match_every_condition() {
+        let dir = tempfile::tempdir().unwrap();
+        let (package, ids) = package(&dir);
+        let options = |filter: TestCaseFilter| {
+            let mut options = ExportOptions::new();
+            options.set_filter(filter);
+            options.selected_test_cases(&package, None).unwrap()
+        };
+
+        let mut filter = TestCaseFilter::default();
+        filter.set_statuses(Some(vec![None, Some(TestCasePassStatus::Fail)]));
+        assert!(!filter.is_empty());
+        assert_eq!(options(filter.clone()), vec![ids[0], ids[2]]);
+
+        filter
+            .custom_fields_mut()
+            .insert("env".to_string(), "ci".to_string());
+        assert_eq!(options(filter.clone()), vec![ids[0]]);
+
+        filter.set_test_cases(Some(vec![ids[1], ids[2]]));
+        assert!(options(filter).is_empty());
+
+        let mut filter = TestCaseFilter::default();
+        filter.set_executed_after(Some(at("2024-01-02T10:00:00Z")));
+        assert_eq!(options(filter.clone()), vec![ids[0], ids[1]]);
+        filter.set_executed_before(Some(at("2024-01-02T10:00:00Z")));
+        assert_eq!(options(filter), vec![ids[0]]);
+
+        let mut filter = TestCaseFilter::default();
+        filter
+            .custom_fields_mut()
+            .insert("missing".to_string(), String::new());
+        assert!(options(filter).is_empty());
+    }
+
+    #[test]
+    fn test_cases_are_ordered() {
+        let dir = tempfile::tempdir().unwrap();
+        let (package, ids) = package(&dir);
+        let order = |order: TestCaseOrder| {
+            let mut options = ExportOptions::new();
+            options.set_order(order);
+            options.selected_test_cases(&package, None).unwrap()
+        };
+
+        assert_eq!(order(TestCaseOrder::Package), ids);
+        assert_eq!(order(TestCaseOrder::Title), vec![ids[1], ids[0], ids[2]]);
+        assert_eq!(
+            order(TestCaseOrder::ExecutionTime),
+            vec![ids[2], ids[0], ids[1]]
+        );
+        assert_eq!(order(TestCaseOrder::Status), vec![ids[0], ids[2], ids[1]]);
+    }
+
+    #[test]
+    fn a_given_test_case_is_selected_regardless_of_the_filter() {
+        let dir = tempfile::tempdir().unwrap();
+        let (package, ids) = package(&dir);
+        let mut options = ExportOptions::new();
+        options.filter_mut().set_test_cases(Some(vec![ids[0]]));
+        assert_eq!(
+            options.selected_test_cases(&package, Some(ids[1])).unwrap(),
+            vec![ids[1]]
+        );
+    }
+
+    #[test]
+    fn options_are_applied_and_the_package_is_put_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let (mut package, ids) = package(&dir);
+        let before = snapshot(&package);
+
+        let mut options = ExportOptions::new();
+        options.set_order(TestCaseOrder::Title);
+        options
+            .filter_mut()
+            .set_test_cases(Some(vec![ids[0], ids[1]]));
+        options.excluded_evidence_mut().insert(EvidenceKind::Text);
+        options.set_omit_author_emails(true);
+
+        let titles = options
+            .while_applied(&mut package, None, |package| {
+                let author = &package.metadata().authors()[0];
+                assert_eq!(author.name(), "Tester");
+                assert_eq!(*author.email(), None);
+
+                let test_case = package.test_case(ids[0])?.unwrap();
+                assert_eq!(test_case.evidence().len(), 1);
+                assert_eq!(*test_case.evidence()[0].kind(), EvidenceKind::RichText);
+                assert_eq!(*test_case.steps()[0].evidence(), vec![0]);
+
+                Ok(package
+                    .test_case_iter()?
+                    .map(|test_case| test_case.metadata().title().clone())
+                    .collect::<Vec<_>>())
+            })
+            .unwrap();
+
+        assert_eq!(titles, vec!["alpha", "Beta"]);
+        assert_eq!(snapshot(&package), before);
+    }
+
+    #[test]
+    fn authors_can_be_omitted() {
+        let dir = tempfile::tempdir().unwrap();
+        let (mut package, _) = package(&dir);
+        let before = snapshot(&package);
+
+        let mut options = ExportOptions::new();
+        options.set_omit_authors(true);
+        options
+            .while_applied(&mut package, None, |package| {
+                assert!(package.metadata().authors().is_empty());
+                Ok(())
+            })
+            .unwrap();
+
+        assert_eq!(snapshot(&package), before);
+    }
+
+    #[test]
+    fn the_package_is_put_back_after_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let (mut package, ids) = package(&dir);
+        let before = snapshot(&package);
+
+        let mut options = ExportOptions::new();
+        options
+            .excluded_evidence_mut()
+            .insert(EvidenceKind::RichText);
+        options.set_omit_authors(true);
+        let result = options.while_applied(&mut package, Some(ids[0]), |package| {
+            assert_eq!(package.test_case_iter()?.count(), 1);
+            package
+                .test_case_mut(ids[0])?
+                .unwrap()
+                .metadata_mut()
+                .set_title("Changed".to_string());
+            Err::<(), _>(Error::DoesntExist(ids[1]))
+        });
+
+        assert!(matches!(result, Err(Error::DoesntExist(id)) if id == ids[1]));
+        assert_eq!(snapshot(&package), before);
+    }
+}
@@ -0,0 +1,131 @@
+use std::{fmt, sync::Arc};
+
+use getset::Getters;
+
+use super::{ExportOption, Exporter};
+
+/// A function that creates a new exporter.
+type ExporterFactory = Arc<dyn Fn() -> Box<dyn Exporter> + Send + Sync>;
+
+/// A format that can be exported to, as listed in an [`ExporterRegistry`].
+#[derive(Clone, Getters)]
+#[getset(get = "pub")]
+pub struct ExportFormat {
+    /// The ID of this format, such as `html`.
+    id: String,
+    /// The name of this format, from [`Exporter::export_name`].
+    name: String,
+    /// The file extension to suggest when saving this format, from
+    /// [`Exporter::export_extension`].
+    extension: String,
+    /// The options understood by the exporter, from [`Exporter::export_options`].
+    options: Vec<ExportOption>,
+    /// Create a new exporter for this format.
+    #[getset(skip)]
+    factory: ExporterFactory,
+}
+
+impl ExportFormat {
+    /// Create a new exporter to this format.
+    #[must_use]
+    pub fn create(&self) -> Box<dyn Exporter> {
+        (self.factory)()
+    }
+
+    /// Get the option of this format with an ID, if it has one.
+    #[must_use]
+    pub fn option(&self, id: &str) -> Option<&ExportOption> {
+        self.options.iter().find(|option| option.id() == id)
+    }
+}
+
+impl fmt::Debug for ExportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ExportFormat")
+            .field("id", &self.id)
+            .field("name", &self.name)
+            .field("extension", &self.extension)
+            .field("options", &self.options)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A list of the formats that can be exported to. The default registry holds every exporter
+/// compiled into this crate, and other exporters can be registered alongside them.
+#[derive(Clone, Debug)]
+pub struct ExporterRegistry {
+    /// The formats, in the order they were registered.
+    formats: Vec<ExportFormat>,
+}
+
+impl Default for ExporterRegistry {
+    fn default() -> Self {
+        #[allow(
+            unused_mut,
+            reason = "no exporters are compiled in without their features"
+        )]
+        let mut registry = Self::empty();
+        #[cfg(feature = "exporter-html")]
+        registry.register("html", || Box::new(super::html::HtmlExporter));
+        #[cfg(feature = "exporter-excel")]
+        registry.register("excel", || Box::new(super::excel::ExcelExporter));
+        #[cfg(feature = "exporter-zip-of-files")]
+        registry.register("zip-of-files", || {
+            Box::new(super::zip_of_files::ZipOfFilesExporter)
+        });
+        registry
+    }
+}
+
+impl ExporterRegistry {
+    /// Create a registry without any formats.
+    #[must_use]
+    pub fn empty() -> Self {
+        Self {
+            formats: Vec::new(),
+        }
+    }
+
+    /// Register an exporter with an ID, replacing any format already registered with that ID.
+    /// The factory is called once now to describe the format, then each time an exporter is
+    /// needed.
+    pub fn register<S, F>(&mut self, id: S, factory: F)
+    where
+        S: Into<String>,
+        F: Fn() -> Box<dyn Exporter> + Send + Sync + 'static,
+    {
+        let exporter = factory();
+        let format = ExportFormat {
+            id: id.into(),
+            name: exporter.export_name(),
+            extension: exporter.export_extension(),
+            options: exporter.export_options(),
+            factory: Arc::new(factory),
+        };
+        if let Some(existing) = self.formats.iter_mut().find(|f| f.id == format.id) {
+            *existing = format;
+        } else {
+            self.formats.push(format);
+        }
+    }
+
+    /// Get all the registered formats.
+    #[must_use]
+    pub fn formats(&self) -> &[ExportFormat] {
+        &self.formats
+    }
+
+    /// Get the format with an ID, ignoring case.
+    #[must_use]
+    pub fn get(&self, id: &str) -> Option<&ExportFormat> {
+        self.formats
+            .iter()
+            .find(|format| format.id.eq_ignore_ascii_case(id))
+    }
+
+    /// Create a new exporter to the format with an ID, ignoring case.
+    #[must_use]
+    pub fn create(&self, id: &str) -> Option<Box<dyn Exporter>> {
+        self.get(id).map(ExportFormat::create)
+    }
+}
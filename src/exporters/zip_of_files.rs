@@ -10,7 +10,7 @@ use zip::{ZipWriter, write::SimpleFileOptions};
 
 use crate::{EvidenceKind, EvidencePackage, TestCase};
 
-use super::Exporter;
+use super::{ExportOptions, Exporter};
 
 /// An exporter to an ZIP of the files in the package.
 #[derive(Default)]
@@ -25,11 +25,11 @@ pub enum ZipOfFilesError {
 }
 
 impl Exporter for ZipOfFilesExporter {
-    fn export_name() -> String {
+    fn export_name(&self) -> String {
         "ZIP Archive of Files".to_string()
     }
 
-    fn export_extension() -> String {
+    fn export_extension(&self) -> String {
         ".zip".to_string()
     }
 
@@ -37,6 +37,7 @@ impl Exporter for ZipOfFilesExporter {
         &mut self,
         package: &mut EvidencePackage,
         path: std::path::PathBuf,
+        options: &ExportOptions,
     ) -> crate::Result<()> {
         fn safely_add_cases_to_zip(
             mut zip: ZipWriter<BufWriter<fs::File>>,
@@ -53,30 +54,32 @@ impl Exporter for ZipOfFilesExporter {
             Ok(())
         }
 
-        let mut has_files = false;
-        for test_case in package.test_case_iter()? {
-            if check_has_files(test_case) {
-                has_files = true;
-                break;
+        options.while_applied(package, None, |package| {
+            let mut has_files = false;
+            for test_case in package.test_case_iter()? {
+                if check_has_files(test_case) {
+                    has_files = true;
+                    break;
+                }
+            }
+            if !has_files {
+                return Err(crate::Error::OtherExportError(Box::new(
+                    ZipOfFilesError::NoFilesToExport,
+                )));
             }
-        }
-        if !has_files {
-            return Err(crate::Error::OtherExportError(Box::new(
-                ZipOfFilesError::NoFilesToExport,
-            )));
-        }
 
-        let zip = ZipWriter::new(BufWriter::new(
-            fs::File::create(&path).map_err(|e| crate::Error::OtherExportError(Box::new(e)))?,
-        ));
-        if let Err(e) = safely_add_cases_to_zip(zip, package) {
-            // Delete file if exists
-            let _ = fs::remove_file(path);
+            let zip = ZipWriter::new(BufWriter::new(
+                fs::File::create(&path).map_err(|e| crate::Error::OtherExportError(Box::new(e)))?,
+            ));
+            if let Err(e) = safely_add_cases_to_zip(zip, package) {
+                // Delete file if exists
+                let _ = fs::remove_file(path);
 
-            return Err(e);
-        }
+                return Err(e);
+            }
 
-        Ok(())
+            Ok(())
+        })
     }
 
     fn export_case(
@@ -84,6 +87,7 @@ impl Exporter for ZipOfFilesExporter {
         package: &mut EvidencePackage,
         case: Uuid,
         path: std::path::PathBuf,
+        options: &ExportOptions,
     ) -> crate::Result<()> {
         fn inner(
             mut zip: ZipWriter<BufWriter<fs::File>>,
@@ -99,30 +103,32 @@ impl Exporter for ZipOfFilesExporter {
             Ok(())
         }
 
-        let case = package
-            .test_case(case)?
-            .ok_or(crate::Error::OtherExportError(
-                "Test case not found!".into(),
-            ))?
-            .clone();
-
-        if !check_has_files(&case) {
-            return Err(crate::Error::OtherExportError(Box::new(
-                ZipOfFilesError::NoFilesToExport,
-            )));
-        }
+        options.while_applied(package, Some(case), |package| {
+            let case = package
+                .test_case(case)?
+                .ok_or(crate::Error::OtherExportError(
+                    "Test case not found!".into(),
+                ))?
+                .clone();
+
+            if !check_has_files(&case) {
+                return Err(crate::Error::OtherExportError(Box::new(
+                    ZipOfFilesError::NoFilesToExport,
+                )));
+            }
 
-        let file =
-            fs::File::create(&path).map_err(|e| crate::Error::OtherExportError(Box::new(e)))?;
-        let zip = ZipWriter::new(BufWriter::new(file));
-        if let Err(e) = inner(zip, package, &case) {
-            // Delete file if exists
-            let _ = fs::remove_file(path);
+            let file =
+                fs::File::create(&path).map_err(|e| crate::Error::OtherExportError(Box::new(e)))?;
+            let zip = ZipWriter::new(BufWriter::new(file));
+            if let Err(e) = inner(zip, package, &case) {
+                // Delete file if exists
+                let _ = fs::remove_file(path);
 
-            return Err(e);
-        }
+                return Err(e);
+            }
 
-        Ok(())
+            Ok(())
+        })
     }
 }
 
@@ -160,10 +166,19 @@ fn add_test_case_to_zip(
         if let EvidenceKind::File = evidence.kind() {
             let data = evidence.value().get_data(&mut package)?;
 
-            let name = if let Some(filename) = evidence.original_filename() {
+            let name = if let crate::EvidenceData::Media { hash } = evidence.value() {
+                // Use the details recorded against the media to name the file correctly
+                match package.get_media(hash)? {
+                    Some(media) => {
+                        media.suggested_filename(evidence.original_filename().as_deref())
+                    }
+                    None => evidence
+                        .original_filename()
+                        .clone()
+                        .unwrap_or_else(|| hash.clone()),
+                }
+            } else if let Some(filename) = evidence.original_filename() {
                 filename.clone()
-            } else if let crate::EvidenceData::Media { hash } = evidence.value() {
-                hash.clone()
             } else {
                 unnamed_counter += 1;
                 format!("unnamed-{unnamed_counter}")
@@ -16,13 +16,29 @@ mod lock_file;
 /// The types of data in a package
 mod package;
 pub use package::{
-    Author, CustomMetadataField, Evidence, EvidenceData, EvidenceKind, EvidencePackage, MediaFile,
-    Metadata, TestCase, TestCaseMetadata, TestCasePassStatus,
+    Annotation, AnnotationColor, AnsiColor, AnsiSpan, AnsiStyle, Author, CommandExecution,
+    CompressionAlgorithm, CompressionPolicy, CoverageStatus, CustomMetadataField, Diff, DiffChange,
+    DiffFormat, DiffLine, Evidence, EvidenceBuilder, EvidenceData, EvidenceKind, EvidencePackage,
+    HTTP_SEPARATOR, HarEntry, HarTimings, HttpBodyFormat, HttpExchange, HttpHeader, HttpRequest,
+    HttpResponse, Link, LinkSystem, Log, LogEntry, LogFormat, LogLevel, MediaFile, MediaStorage,
+    Metadata, PackageBuilder, PackageStatistics, REDACTION_MASK, RedactionReport,
+    RedactionReportEntry, RedactionSettings, RedactionStyle, Requirement, RequirementCoverage,
+    SecretMatch, SecretRedactor, SecretRule, SqlQuery, SqlToken, StatusCounts, StepStatus,
+    StorageReport, StructuredEvidence, THUMBNAIL_MAX_SIZE, Table, TableCell, TableFormat,
+    TemplateLibrary, TestCase, TestCaseBuilder, TestCaseMetadata, TestCasePassStatus,
+    TestCaseStorage, TestCaseTemplate, TestStep, TraceabilityMatrix, TracedTestCase, parse_ansi,
+    parse_har, render_annotations, strip_ansi,
 };
 /// The results of this crate
 mod result;
 pub use result::{Error, Result};
 /// Exporters allow packages and test cases to be exported to different file formats.
 pub mod exporters;
+/// Record evidence from Rust tests into a package.
+#[cfg(feature = "testing")]
+pub mod testing;
+/// Capture `tracing` events as evidence.
+#[cfg(feature = "tracing-layer")]
+pub mod tracing_layer;
 /// Open a ZIP file in a fashion that allows it to be switched between reading and writing.
 mod zip_read_writer;
@@ -6,14 +6,31 @@ use std::{
 };
 
 use chrono::{DateTime, FixedOffset, Local};
-use getset::{Getters, MutGetters};
+use getset::{Getters, MutGetters, Setters};
 use serde::{Deserialize, Serialize};
 use test_cases::TESTCASE_SCHEMA;
 use uuid::Uuid;
-use zip::{result::ZipError, write::SimpleFileOptions};
+use zip::result::ZipError;
 
 use crate::{Result, result::Error, zip_read_writer::ZipReaderWriter};
 
+/// Terminal escape codes in command output
+mod ansi;
+pub use ansi::{AnsiColor, AnsiSpan, AnsiStyle, parse_ansi, strip_ansi};
+
+/// Annotations drawn over image evidence
+mod annotations;
+pub use annotations::{Annotation, AnnotationColor, RedactionStyle, render_annotations};
+
+/// Structured kinds of evidence
+mod kinds;
+pub use kinds::{
+    CommandExecution, Diff, DiffChange, DiffFormat, DiffLine, HTTP_SEPARATOR, HarEntry, HarTimings,
+    HttpBodyFormat, HttpExchange, HttpHeader, HttpRequest, HttpResponse, Link, LinkSystem, Log,
+    LogEntry, LogFormat, LogLevel, SqlQuery, SqlToken, StructuredEvidence, Table, TableCell,
+    TableFormat, parse_har,
+};
+
 /// Package manifests
 mod manifest;
 pub use manifest::*;
@@ -22,6 +39,45 @@ pub use manifest::*;
 mod media;
 pub use media::MediaFile;
 
+/// Fluent builders for creating packages, test cases and evidence
+mod builders;
+pub use builders::{EvidenceBuilder, PackageBuilder, TestCaseBuilder};
+
+/// Secret redaction in evidence
+mod secrets;
+pub use secrets::{
+    REDACTION_MASK, RedactionReport, RedactionReportEntry, RedactionSettings, SecretMatch,
+    SecretRedactor, SecretRule,
+};
+
+/// Test case templates
+mod templates;
+pub use templates::{TemplateLibrary, TestCaseTemplate};
+
+/// Requirements covered by test cases
+mod requirements;
+pub use requirements::{
+    CoverageStatus, Requirement, RequirementCoverage, TraceabilityMatrix, TracedTestCase,
+};
+
+/// Steps of scripted test cases
+mod steps;
+pub use steps::{StepStatus, TestStep};
+
+/// Statistics summarising packages
+mod statistics;
+pub use statistics::{PackageStatistics, StatusCounts};
+
+/// Storage and compression of package contents
+mod storage;
+pub use storage::{
+    CompressionAlgorithm, CompressionPolicy, MediaStorage, StorageReport, TestCaseStorage,
+};
+
+/// Thumbnails of image media
+mod thumbnails;
+pub use thumbnails::THUMBNAIL_MAX_SIZE;
+
 /// Test cases from packages
 mod test_cases;
 pub use test_cases::{
@@ -37,7 +93,7 @@ const MANIFEST_SCHEMA: &str = include_str!("../schemas/manifest.2.schema.json");
 const MANIFEST_SCHEMA_V1: &str = include_str!("../schemas/manifest.1.schema.json");
 
 /// An Evidence Package.
-#[derive(Serialize, Deserialize, Getters, MutGetters)]
+#[derive(Serialize, Deserialize, Getters, MutGetters, Setters)]
 pub struct EvidencePackage {
     /// The internal ZIP file. This will never be `None`, as long as it has been correctly parsed.
     #[serde(skip)]
@@ -45,6 +101,9 @@ pub struct EvidencePackage {
     /// The actual media data from this package
     #[serde(skip)]
     media_data: HashMap<String, MediaFile>,
+    /// The thumbnails of image media from this package
+    #[serde(skip)]
+    thumbnail_data: HashMap<String, MediaFile>,
     /// The actual test data from this package
     #[serde(skip)]
     test_case_data: HashMap<Uuid, TestCase>,
@@ -52,6 +111,14 @@ pub struct EvidencePackage {
     /// The JSON schema for for this package
     #[serde(rename = "$schema")]
     schema: Option<String>,
+    /// The policy used to compress entries when this package is saved
+    #[serde(
+        default,
+        rename = "compression",
+        skip_serializing_if = "CompressionPolicy::is_default"
+    )]
+    #[getset(get = "pub", set = "pub")]
+    compression_policy: CompressionPolicy,
     /// The metadata for the package.
     #[getset(get = "pub", get_mut = "pub")]
     metadata: Metadata,
@@ -70,7 +137,9 @@ impl Clone for EvidencePackage {
         Self {
             zip: self.zip.clone(),
             media_data: HashMap::new(),
+            thumbnail_data: HashMap::new(),
             test_case_data: self.test_case_data.clone(),
+            compression_policy: self.compression_policy,
             extra_fields: HashMap::new(),
 
             schema: Some(MANIFEST_SCHEMA_LOCATION.to_string()),
@@ -129,7 +198,9 @@ impl EvidencePackage {
         let mut manifest = Self {
             zip: ZipReaderWriter::new(path)?,
             media_data: HashMap::new(),
+            thumbnail_data: HashMap::new(),
             test_case_data: HashMap::new(),
+            compression_policy: CompressionPolicy::default(),
 
             schema: Some(MANIFEST_SCHEMA_LOCATION.to_string()),
             media: vec![],
@@ -139,6 +210,9 @@ impl EvidencePackage {
                 description,
                 authors,
                 custom_test_case_metadata: None,
+                redaction: None,
+                templates: None,
+                requirements: None,
                 extra_fields: HashMap::new(),
             },
             extra_fields: HashMap::new(),
@@ -146,8 +220,8 @@ impl EvidencePackage {
         let manifest_clone = manifest.clone_serde();
 
         // Create ZIP file
+        let options = manifest.compression_policy.options_for(None);
         let (_, zip) = manifest.zip.as_writer()?;
-        let options = SimpleFileOptions::default();
 
         // Create empty structure.
         zip.add_directory("media", options)?;
@@ -192,12 +266,13 @@ impl EvidencePackage {
             // This needs to be here to load the archive in read mode first, so that media can be migrated over.
             let _reader = self.zip.as_reader()?;
         }
+        let options = self.compression_policy.options_for(None);
         let (mut maybe_old_archive, zip) = self.zip.as_writer()?;
-        let options = SimpleFileOptions::default();
 
         // Create empty structure.
         zip.add_directory("media", options)?;
         zip.add_directory("testcases", options)?;
+        zip.add_directory("thumbnails", options)?;
 
         tracing::trace!("Current media cache: {:?}", self.media_data);
 
@@ -209,9 +284,7 @@ impl EvidencePackage {
             if let Some(data) = self.test_case_data.get(id) {
                 // Whilst we are here, let's figure out what media we use.
                 for evidence in data.evidence() {
-                    if let EvidenceData::Media { hash } = evidence.value() {
-                        media_used.push(hash);
-                    }
+                    media_used.extend(evidence.media_hashes());
                 }
 
                 let data = serde_json::to_string(data)
@@ -241,12 +314,17 @@ impl EvidencePackage {
         clone
             .media_data
             .retain(|hash, _val| media_used.contains(&hash));
+        self.thumbnail_data
+            .retain(|hash, _val| media_used.contains(&hash));
 
         // Save media to package, either sourcing it from memory if present, or from the previous package.
         tracing::debug!("Media entries: {:?}", self.media);
         for entry in &self.media {
             let hash = entry.sha256_checksum();
-            zip.start_file(format!("media/{hash}"), options)?;
+            zip.start_file(
+                format!("media/{hash}"),
+                self.compression_policy.options_for(Some(entry.mime_type())),
+            )?;
             if self.media_data.contains_key(hash) {
                 // If in memory, write from there
                 tracing::trace!("Writing from cache {hash}");
@@ -276,6 +354,41 @@ impl EvidencePackage {
             }
         }
 
+        // Save thumbnails to package, either from memory if generated, or from the previous package.
+        for entry in &self.media {
+            let hash = entry.sha256_checksum();
+            if let Some(thumbnail) = self.thumbnail_data.get(hash) {
+                tracing::trace!("Writing thumbnail from cache {hash}");
+                zip.start_file(
+                    format!("thumbnails/{hash}"),
+                    self.compression_policy
+                        .options_for(Some(thumbnail.media_type())),
+                )?;
+                zip.write_all(thumbnail.data())?;
+            } else if let Some(old_archive) = maybe_old_archive.as_mut() {
+                match old_archive.by_name(&format!("thumbnails/{hash}")) {
+                    Err(ZipError::FileNotFound) => (),
+                    Err(e) => {
+                        tracing::error!("Error migrating thumbnail from old package: {e}");
+                        return Err(e.into());
+                    }
+                    Ok(file) => {
+                        tracing::debug!("Migrating thumbnail with hash {hash} from old file");
+                        // Thumbnails are small, so read them to find their type
+                        let mut data = Vec::with_capacity(file.size() as usize);
+                        BufReader::new(file).read_to_end(&mut data)?;
+                        let thumbnail = MediaFile::from(data);
+                        zip.start_file(
+                            format!("thumbnails/{hash}"),
+                            self.compression_policy
+                                .options_for(Some(thumbnail.media_type())),
+                        )?;
+                        zip.write_all(thumbnail.data())?;
+                    }
+                }
+            }
+        }
+
         // Write manifest. This has to be done last to ensure media is scrubbed as needed.
         let manifest_data = serde_json::to_string(&clone).map_err(Error::FailedToCreatePackage)?;
         if !jsonschema::is_valid(
@@ -306,8 +419,38 @@ impl EvidencePackage {
     /// - [`Error::InvalidManifest`] if the manifest passes schema validation but is somehow still invalid.
     /// - [`Error::TestCaseSchemaValidationFailed`] if one of the test case manifests fails schema validation.
     pub fn open(path: PathBuf) -> Result<Self> {
+        Self::read(ZipReaderWriter::new(path)?)
+    }
+
+    /// Open an evidence package from its contents in memory, such as a file uploaded to a web
+    /// page. The package isn't locked, and saving it replaces the contents held in memory, which
+    /// can then be read with [`EvidencePackage::in_memory_data`].
+    ///
+    /// # Panics
+    ///
+    /// All the potential panics are checked statically ahead of time, so should never trigger at runtime.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::Zip`] if the evp file couldn't be read correctly.
+    /// - [`Error::CorruptEvidencePackage`] if the evp is corrupt in it's internal structure.
+    /// - [`Error::ManifestSchemaValidationFailed`] if the manifest is invalid.
+    /// - [`Error::InvalidManifest`] if the manifest passes schema validation but is somehow still invalid.
+    /// - [`Error::TestCaseSchemaValidationFailed`] if one of the test case manifests fails schema validation.
+    pub fn open_from_bytes(data: Vec<u8>) -> Result<Self> {
+        Self::read(ZipReaderWriter::from_bytes(data))
+    }
+
+    /// Get the contents of a package opened with [`EvidencePackage::open_from_bytes`], as of
+    /// when it was last saved. Returns [`None`] for packages on disk.
+    #[must_use]
+    pub fn in_memory_data(&self) -> Option<&[u8]> {
+        self.zip.bytes()
+    }
+
+    /// Read an evidence package from a ZIP file.
+    fn read(mut zip_rw: ZipReaderWriter) -> Result<Self> {
         // Open ZIP file
-        let mut zip_rw = ZipReaderWriter::new(path)?;
         let zip = zip_rw.as_reader()?;
 
         // Read manifest
@@ -384,7 +527,9 @@ impl EvidencePackage {
         Self {
             zip: ZipReaderWriter::default(),
             media_data: HashMap::new(),
+            thumbnail_data: HashMap::new(),
             test_case_data: HashMap::new(),
+            compression_policy: self.compression_policy,
 
             schema: Some(MANIFEST_SCHEMA_LOCATION.to_string()),
             metadata: self.metadata.clone(),
@@ -448,6 +593,34 @@ impl EvidencePackage {
         Ok(())
     }
 
+    /// Run a function with this package temporarily reduced to some of its test cases, in the
+    /// order given. The test cases and the package metadata are put back afterwards, so the
+    /// function can change them without the changes being kept.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error from the function. The package is put back regardless.
+    pub(crate) fn while_scoped<T, F>(&mut self, test_cases: &[Uuid], f: F) -> Result<T>
+    where
+        F: FnOnce(&mut Self) -> Result<T>,
+    {
+        let original_order = self.test_cases.clone();
+        let original_data = self.test_case_data.clone();
+        let original_metadata = self.metadata.clone();
+
+        self.test_cases = test_cases
+            .iter()
+            .filter_map(|id| original_order.iter().find(|tcme| tcme.id() == id).cloned())
+            .collect();
+        self.test_case_data.retain(|id, _| test_cases.contains(id));
+        let result = f(self);
+
+        self.test_cases = original_order;
+        self.test_case_data = original_data;
+        self.metadata = original_metadata;
+        result
+    }
+
     /// Create a new test case.
     ///
     /// # Errors
@@ -587,8 +760,11 @@ impl EvidencePackage {
     /// - [`Error::Io`] if the evp couldn't be read at all.
     /// - [`Error::Zip`] if the evp file couldn't be read correctly.
     #[allow(clippy::missing_panics_doc)]
-    pub fn add_media(&mut self, media_file: MediaFile) -> Result<&MediaFile> {
+    pub fn add_media(&mut self, mut media_file: MediaFile) -> Result<&MediaFile> {
         let hash = media_file.hash();
+        if media_file.captured_at().is_none() {
+            media_file.set_captured_at(Some(Local::now().fixed_offset()));
+        }
 
         if !self
             .media
@@ -643,8 +819,15 @@ impl EvidencePackage {
                 let mut data = BufReader::new(file);
                 data.read_to_end(&mut buf)?;
 
-                // Add to in-memory cache
-                let media: MediaFile = buf.into();
+                // Add to in-memory cache, restoring details from the manifest
+                let mut media: MediaFile = buf.into();
+                if let Some(entry) = self
+                    .media
+                    .iter()
+                    .find(|entry| entry.sha256_checksum() == &hash)
+                {
+                    entry.apply_to(&mut media);
+                }
                 tracing::trace!("New media cache entry: {hash}");
                 self.media_data.insert(hash.clone(), media);
 
@@ -0,0 +1,676 @@
+#[cfg(feature = "image-processing")]
+use std::io::Cursor;
+use std::{fmt, str::FromStr};
+
+#[cfg(feature = "image-processing")]
+use font8x8::{BASIC_FONTS, UnicodeFonts};
+use getset::CopyGetters;
+#[cfg(feature = "image-processing")]
+use image::{ImageFormat, ImageReader, Rgba, RgbaImage, imageops};
+use serde::{Deserialize, Serialize};
+
+use super::{Evidence, EvidenceData, EvidencePackage, MediaFile};
+
+/// An annotation drawn over image [`Evidence`]. Annotations don't modify the underlying media,
+/// and are rendered whenever the image is displayed or exported.
+///
+/// All coordinates and sizes are in pixels of the original image, measured from its top-left
+/// corner.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum Annotation {
+    /// An outlined rectangle.
+    Rectangle {
+        /// The left edge of the rectangle.
+        x: u32,
+        /// The top edge of the rectangle.
+        y: u32,
+        /// The width of the rectangle.
+        width: u32,
+        /// The height of the rectangle.
+        height: u32,
+        /// The color of the outline.
+        color: AnnotationColor,
+    },
+    /// An arrow pointing from one point to another.
+    Arrow {
+        /// The horizontal position of the tail of the arrow.
+        from_x: u32,
+        /// The vertical position of the tail of the arrow.
+        from_y: u32,
+        /// The horizontal position of the head of the arrow.
+        to_x: u32,
+        /// The vertical position of the head of the arrow.
+        to_y: u32,
+        /// The color of the arrow.
+        color: AnnotationColor,
+    },
+    /// A text label on a light background.
+    Text {
+        /// The left edge of the label.
+        x: u32,
+        /// The top edge of the label.
+        y: u32,
+        /// The text of the label. Characters outside of the basic Latin set are shown as `?`.
+        text: String,
+        /// The color of the text.
+        color: AnnotationColor,
+    },
+    /// An area of the image that is hidden.
+    Redaction {
+        /// The left edge of the redacted area.
+        x: u32,
+        /// The top edge of the redacted area.
+        y: u32,
+        /// The width of the redacted area.
+        width: u32,
+        /// The height of the redacted area.
+        height: u32,
+        /// How the area is hidden.
+        style: RedactionStyle,
+    },
+}
+
+impl Annotation {
+    /// Determine if this annotation is a redaction.
+    #[must_use]
+    pub fn is_redaction(&self) -> bool {
+        matches!(self, Self::Redaction { .. })
+    }
+
+    /// Draw this annotation onto an image.
+    #[cfg(feature = "image-processing")]
+    fn draw(&self, image: &mut RgbaImage) {
+        let (width, height) = image.dimensions();
+        let stroke_width = (width.min(height) / 200).max(2);
+        let stroke = i64::from(stroke_width);
+
+        match self {
+            Self::Rectangle {
+                x,
+                y,
+                width,
+                height,
+                color,
+            } => {
+                let (x, y) = (i64::from(*x), i64::from(*y));
+                let (x2, y2) = (x + i64::from(*width), y + i64::from(*height));
+                let color = color.to_rgba();
+                fill_rect(image, x, y, x2, y + stroke, color);
+                fill_rect(image, x, y2 - stroke, x2, y2, color);
+                fill_rect(image, x, y, x + stroke, y2, color);
+                fill_rect(image, x2 - stroke, y, x2, y2, color);
+            }
+            Self::Arrow {
+                from_x,
+                from_y,
+                to_x,
+                to_y,
+                color,
+            } => {
+                let from = (f64::from(*from_x), f64::from(*from_y));
+                let to = (f64::from(*to_x), f64::from(*to_y));
+                let color = color.to_rgba();
+                draw_line(image, from, to, stroke, color);
+
+                // Draw the head as two lines angled back from the tip
+                let angle = (to.1 - from.1).atan2(to.0 - from.0);
+                let head_length = f64::from(stroke_width * 6);
+                for offset in [-0.5, 0.5] {
+                    let head_angle = angle + std::f64::consts::PI + offset;
+                    let end = (
+                        to.0 + head_length * head_angle.cos(),
+                        to.1 + head_length * head_angle.sin(),
+                    );
+                    draw_line(image, to, end, stroke, color);
+                }
+            }
+            Self::Text { x, y, text, color } => {
+                let scale = stroke;
+                let padding = scale * 2;
+                let (x, y) = (i64::from(*x), i64::from(*y));
+                let lines: Vec<&str> = text.lines().collect();
+                let longest = lines
+                    .iter()
+                    .map(|line| line.chars().count())
+                    .max()
+                    .unwrap_or_default();
+                let longest = i64::try_from(longest).unwrap_or(i64::MAX);
+                let line_count = i64::try_from(lines.len()).unwrap_or(i64::MAX);
+
+                fill_rect(
+                    image,
+                    x,
+                    y,
+                    x + longest * 8 * scale + padding * 2,
+                    y + line_count * 8 * scale + padding * 2,
+                    Rgba([255, 255, 255, 255]),
+                );
+                let color = color.to_rgba();
+                for (row, line) in (0..).zip(&lines) {
+                    for (column, c) in (0..).zip(line.chars()) {
+                        let glyph = BASIC_FONTS
+                            .get(c)
+                            .or_else(|| BASIC_FONTS.get('?'))
+                            .unwrap_or_default();
+                        let glyph_x = x + padding + column * 8 * scale;
+                        let glyph_y = y + padding + row * 8 * scale;
+                        for (gy, bits) in (0..).zip(glyph) {
+                            for gx in 0..8 {
+                                if bits & (1 << gx) != 0 {
+                                    let px = glyph_x + i64::from(gx) * scale;
+                                    let py = glyph_y + gy * scale;
+                                    fill_rect(image, px, py, px + scale, py + scale, color);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            Self::Redaction {
+                x,
+                y,
+                width,
+                height,
+                style,
+            } => {
+                let x = (*x).min(image.width());
+                let y = (*y).min(image.height());
+                let width = (*width).min(image.width() - x);
+                let height = (*height).min(image.height() - y);
+                if width == 0 || height == 0 {
+                    return;
+                }
+                match style {
+                    RedactionStyle::BlackOut => fill_rect(
+                        image,
+                        i64::from(x),
+                        i64::from(y),
+                        i64::from(x + width),
+                        i64::from(y + height),
+                        Rgba([0, 0, 0, 255]),
+                    ),
+                    RedactionStyle::Blur => {
+                        // Pixelate before blurring so that no detail of the original survives
+                        let area = imageops::crop_imm(image, x, y, width, height).to_image();
+                        let block = (width.min(height) / 4).max(8);
+                        let pixelated = imageops::resize(
+                            &area,
+                            (width / block).max(1),
+                            (height / block).max(1),
+                            imageops::FilterType::Triangle,
+                        );
+                        let pixelated = imageops::resize(
+                            &pixelated,
+                            width,
+                            height,
+                            imageops::FilterType::Nearest,
+                        );
+                        #[allow(clippy::cast_precision_loss)]
+                        let blurred = imageops::blur(&pixelated, block as f32 / 2.0);
+                        imageops::replace(image, &blurred, i64::from(x), i64::from(y));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// How a [`Annotation::Redaction`] hides an area of an image.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RedactionStyle {
+    /// The area is filled with black.
+    #[default]
+    BlackOut,
+    /// The area is pixelated and blurred.
+    Blur,
+}
+
+/// The color of an [`Annotation`], stored as a `#rrggbb` string.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, CopyGetters)]
+#[serde(try_from = "String", into = "String")]
+#[getset(get_copy = "pub")]
+pub struct AnnotationColor {
+    /// The red component.
+    red: u8,
+    /// The green component.
+    green: u8,
+    /// The blue component.
+    blue: u8,
+}
+
+impl AnnotationColor {
+    /// Create a new color from its components.
+    #[must_use]
+    pub fn new(red: u8, green: u8, blue: u8) -> Self {
+        Self { red, green, blue }
+    }
+
+    /// Convert this color to an opaque pixel.
+    #[cfg(feature = "image-processing")]
+    fn to_rgba(self) -> Rgba<u8> {
+        Rgba([self.red, self.green, self.blue, 255])
+    }
+}
+
+impl Default for AnnotationColor {
+    fn default() -> Self {
+        Self::new(0xE0, 0x1B, 0x24)
+    }
+}
+
+impl fmt::Display for AnnotationColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.red, self.green, self.blue)
+    }
+}
+
+impl FromStr for AnnotationColor {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("{s} is not a valid #rrggbb color");
+        let hex = s.strip_prefix('#').ok_or_else(invalid)?;
+        if hex.len() != 6 || !hex.is_ascii() {
+            return Err(invalid());
+        }
+        let component = |at: usize| u8::from_str_radix(&hex[at..at + 2], 16).map_err(|_| invalid());
+        Ok(Self::new(component(0)?, component(2)?, component(4)?))
+    }
+}
+
+impl TryFrom<String> for AnnotationColor {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<AnnotationColor> for String {
+    fn from(value: AnnotationColor) -> Self {
+        value.to_string()
+    }
+}
+
+/// Fill a rectangle from `(x1, y1)` to `(x2, y2)` exclusive, clipped to the image.
+#[cfg(feature = "image-processing")]
+fn fill_rect(image: &mut RgbaImage, x1: i64, y1: i64, x2: i64, y2: i64, color: Rgba<u8>) {
+    let (width, height) = image.dimensions();
+    let x1 = x1.clamp(0, i64::from(width)) as u32;
+    let x2 = x2.clamp(0, i64::from(width)) as u32;
+    let y1 = y1.clamp(0, i64::from(height)) as u32;
+    let y2 = y2.clamp(0, i64::from(height)) as u32;
+    for y in y1..y2 {
+        for x in x1..x2 {
+            image.put_pixel(x, y, color);
+        }
+    }
+}
+
+/// Draw a line with a given thickness between two points.
+#[cfg(feature = "image-processing")]
+fn draw_line(
+    image: &mut RgbaImage,
+    from: (f64, f64),
+    to: (f64, f64),
+    stroke: i64,
+    color: Rgba<u8>,
+) {
+    let length = (to.0 - from.0).hypot(to.1 - from.1).max(1.0);
+    let half = stroke / 2;
+    let mut travelled = 0.0;
+    while travelled <= length {
+        let x = (from.0 + (to.0 - from.0) * travelled / length) as i64;
+        let y = (from.1 + (to.1 - from.1) * travelled / length) as i64;
+        fill_rect(
+            image,
+            x - half,
+            y - half,
+            x + stroke - half,
+            y + stroke - half,
+            color,
+        );
+        travelled += 1.0;
+    }
+}
+
+/// Render annotations over some image data, returning the new image encoded as JPEG if the
+/// original was a JPEG, or as PNG otherwise.
+///
+/// # Errors
+///
+/// - [`Error::Image`](crate::Error::Image) if the data couldn't be decoded as an image, or the
+///   result couldn't be encoded.
+#[cfg(feature = "image-processing")]
+pub fn render_annotations(data: &[u8], annotations: &[Annotation]) -> crate::Result<Vec<u8>> {
+    let reader = ImageReader::new(Cursor::new(data)).with_guessed_format()?;
+    let format = match reader.format() {
+        Some(ImageFormat::Jpeg) => ImageFormat::Jpeg,
+        _ => ImageFormat::Png,
+    };
+    let mut image = reader.decode()?.to_rgba8();
+    for annotation in annotations {
+        annotation.draw(&mut image);
+    }
+
+    let mut buf = Cursor::new(vec![]);
+    if format == ImageFormat::Jpeg {
+        // JPEG can't store transparency
+        image::DynamicImage::ImageRgba8(image)
+            .to_rgb8()
+            .write_to(&mut buf, format)?;
+    } else {
+        image.write_to(&mut buf, format)?;
+    }
+    Ok(buf.into_inner())
+}
+
+/// Render annotations over some image data. Without the `image-processing` feature, this always
+/// fails with [`Error::ImageProcessingUnavailable`](crate::Error::ImageProcessingUnavailable).
+///
+/// # Errors
+///
+/// - [`Error::ImageProcessingUnavailable`](crate::Error::ImageProcessingUnavailable) always.
+#[cfg(not(feature = "image-processing"))]
+pub fn render_annotations(_data: &[u8], _annotations: &[Annotation]) -> crate::Result<Vec<u8>> {
+    Err(crate::Error::ImageProcessingUnavailable)
+}
+
+impl Evidence {
+    /// Get the data of this evidence with its annotations rendered over it. If there are no
+    /// annotations, this is the same as [`EvidenceData::get_data`].
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::MediaMissing`](crate::Error::MediaMissing) if the media referred to by this
+    ///   evidence is missing from the package.
+    /// - [`Error::Image`](crate::Error::Image) if the evidence has annotations but isn't an image
+    ///   that can be decoded.
+    /// - [`Error::ImageProcessingUnavailable`](crate::Error::ImageProcessingUnavailable) if the
+    ///   evidence has annotations but the `image-processing` feature isn't enabled.
+    pub fn annotated_data(&self, pkg: &mut EvidencePackage) -> crate::Result<Vec<u8>> {
+        let data = self.value().get_data(pkg)?;
+        if self.annotations().is_empty() {
+            return Ok(data);
+        }
+        render_annotations(&data, self.annotations())
+    }
+
+    /// Permanently apply the redactions of this evidence to its media, replacing the media with a
+    /// redacted copy and removing the redaction annotations. Other annotations are kept.
+    ///
+    /// The original media is removed from the package when it is next saved, unless it is still
+    /// referenced by other evidence. Returns `false` if there were no redactions to apply.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::MediaMissing`](crate::Error::MediaMissing) if the media referred to by this
+    ///   evidence is missing from the package.
+    /// - [`Error::Image`](crate::Error::Image) if the evidence isn't an image that can be decoded.
+    /// - [`Error::ImageProcessingUnavailable`](crate::Error::ImageProcessingUnavailable) if the
+    ///   `image-processing` feature isn't enabled.
+    /// - [`Error::Io`](crate::Error::Io) if the evp couldn't be read at all.
+    /// - [`Error::Zip`](crate::Error::Zip) if the evp file couldn't be read correctly.
+    pub fn burn_in_redactions(&mut self, pkg: &mut EvidencePackage) -> crate::Result<bool> {
+        let (redactions, others): (Vec<_>, Vec<_>) = self
+            .annotations()
+            .iter()
+            .cloned()
+            .partition(Annotation::is_redaction);
+        if redactions.is_empty() {
+            return Ok(false);
+        }
+
+        let mut original_filename = None;
+        let mut captured_at = None;
+        if let EvidenceData::Media { hash } = self.value()
+            && let Some(media) = pkg.get_media(hash)?
+        {
+            original_filename.clone_from(media.original_filename());
+            captured_at = *media.captured_at();
+        }
+
+        let data = render_annotations(&self.value().get_data(pkg)?, &redactions)?;
+        let mut media = MediaFile::from(data);
+        media.set_original_filename(original_filename);
+        media.set_captured_at(captured_at);
+        let hash = pkg.add_media(media)?.hash();
+
+        self.set_value(EvidenceData::Media { hash });
+        self.set_annotations(others);
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EvidenceBuilder, PackageBuilder, TestCaseBuilder};
+
+    /// Create a white PNG image.
+    #[cfg(feature = "image-processing")]
+    fn white_png(width: u32, height: u32) -> Vec<u8> {
+        let mut buf = Cursor::new(vec![]);
+        RgbaImage::from_pixel(width, height, Rgba([255, 255, 255, 255]))
+            .write_to(&mut buf, ImageFormat::Png)
+            .unwrap();
+        buf.into_inner()
+    }
+
+    /// Decode a PNG image.
+    #[cfg(feature = "image-processing")]
+    fn decode(data: &[u8]) -> RgbaImage {
+        image::load_from_memory(data).unwrap().to_rgba8()
+    }
+
+    /// One annotation of each kind.
+    fn annotations() -> Vec<Annotation> {
+        vec![
+            Annotation::Rectangle {
+                x: 1,
+                y: 2,
+                width: 3,
+                height: 4,
+                color: AnnotationColor::default(),
+            },
+            Annotation::Arrow {
+                from_x: 5,
+                from_y: 6,
+                to_x: 7,
+                to_y: 8,
+                color: AnnotationColor::new(0, 0x80, 0xff),
+            },
+            Annotation::Text {
+                x: 9,
+                y: 10,
+                text: "Here".to_string(),
+                color: AnnotationColor::new(0, 0, 0),
+            },
+            Annotation::Redaction {
+                x: 11,
+                y: 12,
+                width: 13,
+                height: 14,
+                style: RedactionStyle::Blur,
+            },
+        ]
+    }
+
+    #[test]
+    fn colors_are_parsed() {
+        assert_eq!(
+            "#e01b24".parse::<AnnotationColor>(),
+            Ok(AnnotationColor::default())
+        );
+        assert_eq!(AnnotationColor::new(0, 0x80, 0xff).to_string(), "#0080ff");
+        for invalid in ["e01b24", "#e01b2", "#e01b2g", "#e01b24ff", "#é01b2"] {
+            assert!(invalid.parse::<AnnotationColor>().is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn annotations_round_trip_through_json() {
+        let json = serde_json::to_value(annotations()).unwrap();
+        assert_eq!(json[0]["type"], "Rectangle");
+        assert_eq!(json[0]["color"], "#e01b24");
+        assert_eq!(json[3]["style"], "Blur");
+        let read: Vec<Annotation> = serde_json::from_value(json).unwrap();
+        assert_eq!(read, annotations());
+
+        assert!(
+            serde_json::from_str::<Annotation>(
+                r#"{"type": "Rectangle", "x": 0, "y": 0, "width": 1, "height": 1, "color": "red"}"#
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn annotations_round_trip_through_packages() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("package.evp");
+        let mut evidence = Evidence::new(
+            crate::EvidenceKind::Image,
+            EvidenceData::Text {
+                content: String::new(),
+            },
+        );
+        evidence.set_annotations(annotations());
+        let package = PackageBuilder::new(path.clone(), "Package")
+            .test_case(TestCaseBuilder::new("Test").evidence(EvidenceBuilder::existing(evidence)))
+            .build()
+            .unwrap();
+        let id = *package.test_case_iter().unwrap().next().unwrap().id();
+        drop(package);
+
+        let package = EvidencePackage::open(path).unwrap();
+        let test_case = package.test_case(id).unwrap().unwrap();
+        assert_eq!(*test_case.evidence()[0].annotations(), annotations());
+    }
+
+    #[cfg(feature = "image-processing")]
+    #[test]
+    fn shapes_past_the_edges_are_clipped() {
+        let png = white_png(20, 20);
+        let rendered = render_annotations(
+            &png,
+            &[
+                Annotation::Rectangle {
+                    x: 15,
+                    y: 15,
+                    width: u32::MAX,
+                    height: u32::MAX,
+                    color: AnnotationColor::default(),
+                },
+                Annotation::Arrow {
+                    from_x: 10,
+                    from_y: 10,
+                    to_x: 500,
+                    to_y: 300,
+                    color: AnnotationColor::default(),
+                },
+                Annotation::Text {
+                    x: 18,
+                    y: 18,
+                    text: "Off the edge\nand below".to_string(),
+                    color: AnnotationColor::default(),
+                },
+                Annotation::Redaction {
+                    x: 500,
+                    y: 500,
+                    width: 10,
+                    height: 10,
+                    style: RedactionStyle::BlackOut,
+                },
+                Annotation::Redaction {
+                    x: 12,
+                    y: 0,
+                    width: u32::MAX,
+                    height: 5,
+                    style: RedactionStyle::Blur,
+                },
+                Annotation::Redaction {
+                    x: 0,
+                    y: 10,
+                    width: 5,
+                    height: u32::MAX,
+                    style: RedactionStyle::BlackOut,
+                },
+            ],
+        )
+        .unwrap();
+
+        let image = decode(&rendered);
+        assert_eq!(image.dimensions(), (20, 20));
+        assert_eq!(*image.get_pixel(0, 19), Rgba([0, 0, 0, 255]));
+        assert_eq!(*image.get_pixel(4, 10), Rgba([0, 0, 0, 255]));
+        assert_eq!(*image.get_pixel(5, 10), Rgba([255, 255, 255, 255]));
+    }
+
+    #[cfg(feature = "image-processing")]
+    #[test]
+    fn redactions_are_burned_in() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("package.evp");
+        let redaction = Annotation::Redaction {
+            x: 0,
+            y: 0,
+            width: 10,
+            height: 20,
+            style: RedactionStyle::BlackOut,
+        };
+        let rectangle = Annotation::Rectangle {
+            x: 12,
+            y: 2,
+            width: 4,
+            height: 4,
+            color: AnnotationColor::default(),
+        };
+        let mut package = PackageBuilder::new(path.clone(), "Package")
+            .test_case(
+                TestCaseBuilder::new("Test").evidence(
+                    EvidenceBuilder::screenshot(white_png(20, 20))
+                        .annotation(redaction)
+                        .annotation(rectangle.clone()),
+                ),
+            )
+            .build()
+            .unwrap();
+        package.save().unwrap();
+        let id = *package.test_case_iter().unwrap().next().unwrap().id();
+
+        let mut evidence = package.test_case(id).unwrap().unwrap().evidence()[0].clone();
+        let EvidenceData::Media { hash: original } = evidence.value().clone() else {
+            panic!("screenshots should be stored as media");
+        };
+        assert!(evidence.burn_in_redactions(&mut package).unwrap());
+        assert!(!evidence.burn_in_redactions(&mut package).unwrap());
+        assert_eq!(*evidence.annotations(), vec![rectangle]);
+        let EvidenceData::Media { hash: redacted } = evidence.value().clone() else {
+            panic!("redacted screenshots should be stored as media");
+        };
+        assert_ne!(redacted, original);
+        package.test_case_mut(id).unwrap().unwrap().evidence_mut()[0] = evidence;
+        package.save().unwrap();
+        drop(package);
+
+        let mut package = EvidencePackage::open(path).unwrap();
+        assert!(package.get_media(&original).unwrap().is_none());
+        let image = decode(package.get_media(&redacted).unwrap().unwrap().data());
+        assert_eq!(*image.get_pixel(0, 0), Rgba([0, 0, 0, 255]));
+        assert_eq!(*image.get_pixel(9, 19), Rgba([0, 0, 0, 255]));
+        assert_eq!(*image.get_pixel(10, 0), Rgba([255, 255, 255, 255]));
+        // The rectangle is still only drawn over the image when it is displayed
+        assert_eq!(*image.get_pixel(12, 2), Rgba([255, 255, 255, 255]));
+    }
+
+    #[cfg(not(feature = "image-processing"))]
+    #[test]
+    fn rendering_requires_image_processing() {
+        assert!(matches!(
+            render_annotations(&[], &annotations()),
+            Err(crate::Error::ImageProcessingUnavailable)
+        ));
+    }
+}
@@ -0,0 +1,343 @@
+use getset::{CopyGetters, Getters};
+
+/// A colour set by a terminal escape code.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AnsiColor {
+    /// A colour from the 256 colour palette. The first 16 are the standard and bright colours.
+    Indexed(u8),
+    /// A 24-bit colour.
+    Rgb(u8, u8, u8),
+}
+
+impl AnsiColor {
+    /// Get the red, green and blue components of this colour, using the xterm palette for
+    /// indexed colours.
+    #[must_use]
+    pub fn rgb(self) -> (u8, u8, u8) {
+        /// The standard and bright colours
+        const BASIC: [(u8, u8, u8); 16] = [
+            (0, 0, 0),
+            (205, 49, 49),
+            (13, 188, 121),
+            (229, 229, 16),
+            (36, 114, 200),
+            (188, 63, 188),
+            (17, 168, 205),
+            (229, 229, 229),
+            (102, 102, 102),
+            (241, 76, 76),
+            (35, 209, 139),
+            (245, 245, 67),
+            (59, 142, 234),
+            (214, 112, 214),
+            (41, 184, 219),
+            (255, 255, 255),
+        ];
+        /// The levels of each component in the 6x6x6 colour cube
+        const CUBE: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+        match self {
+            Self::Rgb(r, g, b) => (r, g, b),
+            Self::Indexed(index @ 0..16) => BASIC[index as usize],
+            Self::Indexed(index @ 16..232) => {
+                let index = index - 16;
+                (
+                    CUBE[(index / 36) as usize],
+                    CUBE[(index / 6 % 6) as usize],
+                    CUBE[(index % 6) as usize],
+                )
+            }
+            Self::Indexed(index) => {
+                let level = 8 + (index - 232) * 10;
+                (level, level, level)
+            }
+        }
+    }
+
+    /// Get this colour as a CSS hex colour, such as `#cd3131`.
+    #[must_use]
+    pub fn to_hex(self) -> String {
+        let (r, g, b) = self.rgb();
+        format!("#{r:02x}{g:02x}{b:02x}")
+    }
+}
+
+/// The style of some terminal text.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, CopyGetters)]
+#[getset(get_copy = "pub")]
+#[allow(clippy::struct_excessive_bools)]
+pub struct AnsiStyle {
+    /// The text colour, if not the default.
+    foreground: Option<AnsiColor>,
+    /// The background colour, if not the default.
+    background: Option<AnsiColor>,
+    /// Whether the text is bold.
+    bold: bool,
+    /// Whether the text is dimmed.
+    dim: bool,
+    /// Whether the text is italic.
+    italic: bool,
+    /// Whether the text is underlined.
+    underline: bool,
+}
+
+impl AnsiStyle {
+    /// Determine if this is the default style.
+    #[must_use]
+    pub fn is_plain(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Apply the parameters of a Select Graphic Rendition escape code to this style.
+    fn apply(&mut self, params: &[u16]) {
+        let mut params = params.iter().copied();
+        while let Some(param) = params.next() {
+            match param {
+                0 => *self = Self::default(),
+                1 => self.bold = true,
+                2 => self.dim = true,
+                3 => self.italic = true,
+                4 => self.underline = true,
+                22 => {
+                    self.bold = false;
+                    self.dim = false;
+                }
+                23 => self.italic = false,
+                24 => self.underline = false,
+                30..=37 => self.foreground = Some(AnsiColor::Indexed((param - 30) as u8)),
+                38 => self.foreground = extended_color(&mut params),
+                39 => self.foreground = None,
+                40..=47 => self.background = Some(AnsiColor::Indexed((param - 40) as u8)),
+                48 => self.background = extended_color(&mut params),
+                49 => self.background = None,
+                90..=97 => self.foreground = Some(AnsiColor::Indexed((param - 90 + 8) as u8)),
+                100..=107 => self.background = Some(AnsiColor::Indexed((param - 100 + 8) as u8)),
+                _ => (),
+            }
+        }
+    }
+}
+
+/// Read a 256 colour (`5;n`) or 24-bit colour (`2;r;g;b`) from the parameters following a `38` or
+/// `48` code.
+fn extended_color<I: Iterator<Item = u16>>(params: &mut I) -> Option<AnsiColor> {
+    let component = |value: Option<u16>| value.and_then(|value| u8::try_from(value).ok());
+    match params.next()? {
+        5 => component(params.next()).map(AnsiColor::Indexed),
+        2 => Some(AnsiColor::Rgb(
+            component(params.next())?,
+            component(params.next())?,
+            component(params.next())?,
+        )),
+        _ => None,
+    }
+}
+
+/// A run of terminal text with a single style.
+#[derive(Clone, Debug, PartialEq, Eq, Getters, CopyGetters)]
+pub struct AnsiSpan {
+    /// The text of this span.
+    #[getset(get = "pub")]
+    text: String,
+    /// The style of this span.
+    #[getset(get_copy = "pub")]
+    style: AnsiStyle,
+}
+
+/// Split terminal output into runs of styled text, following the colour and style escape codes it
+/// contains. Any other escape codes, such as those moving the cursor, are removed.
+#[must_use]
+pub fn parse_ansi(text: &str) -> Vec<AnsiSpan> {
+    let mut spans: Vec<AnsiSpan> = vec![];
+    let mut style = AnsiStyle::default();
+    let mut chars = text.chars().peekable();
+    let mut current = String::new();
+
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            current.push(c);
+            continue;
+        }
+        match chars.next() {
+            // Control Sequence Introducer, ending in a byte in the range `@` to `~`
+            Some('[') => {
+                let mut sequence = String::new();
+                let mut terminator = None;
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        terminator = Some(c);
+                        break;
+                    }
+                    sequence.push(c);
+                }
+                if terminator == Some('m') {
+                    let params = sequence
+                        .split([';', ':'])
+                        .map(|param| param.parse().unwrap_or(0))
+                        .collect::<Vec<u16>>();
+                    let mut new_style = style;
+                    new_style.apply(&params);
+                    if new_style != style {
+                        if !current.is_empty() {
+                            spans.push(AnsiSpan {
+                                text: std::mem::take(&mut current),
+                                style,
+                            });
+                        }
+                        style = new_style;
+                    }
+                }
+            }
+            // Operating System Command, ending in BEL or ST
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == '\x07' {
+                        break;
+                    }
+                    if c == '\x1b' && chars.peek() == Some(&'\\') {
+                        chars.next();
+                        break;
+                    }
+                }
+            }
+            // Any other escape is a single character
+            _ => (),
+        }
+    }
+    if !current.is_empty() {
+        spans.push(AnsiSpan {
+            text: current,
+            style,
+        });
+    }
+    spans
+}
+
+/// Remove all terminal escape codes from some text.
+#[must_use]
+pub fn strip_ansi(text: &str) -> String {
+    parse_ansi(text).into_iter().map(|span| span.text).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Get the text and style of each span.
+    fn spans(text: &str) -> Vec<(String, AnsiStyle)> {
+        parse_ansi(text)
+            .into_iter()
+            .map(|span| (span.text, span.style))
+            .collect()
+    }
+
+    #[test]
+    fn empty_input_has_no_spans() {
+        assert!(parse_ansi("").is_empty());
+        assert!(parse_ansi("\x1b[31m\x1b[0m").is_empty());
+    }
+
+    #[test]
+    fn plain_text_is_one_span() {
+        assert_eq!(
+            spans("hello\r\nworld"),
+            [("hello\r\nworld".to_string(), AnsiStyle::default())]
+        );
+    }
+
+    #[test]
+    fn colours_and_styles_are_applied() {
+        let red_bold = AnsiStyle {
+            foreground: Some(AnsiColor::Indexed(1)),
+            bold: true,
+            ..AnsiStyle::default()
+        };
+        assert_eq!(
+            spans("a\x1b[1;31mb\x1b[0mc"),
+            [
+                ("a".to_string(), AnsiStyle::default()),
+                ("b".to_string(), red_bold),
+                ("c".to_string(), AnsiStyle::default()),
+            ]
+        );
+    }
+
+    #[test]
+    fn extended_colours_are_applied() {
+        let parsed = spans("\x1b[38;5;196;48;2;1;2;3mx\x1b[97;104my");
+        assert_eq!(parsed[0].1.foreground(), Some(AnsiColor::Indexed(196)));
+        assert_eq!(parsed[0].1.background(), Some(AnsiColor::Rgb(1, 2, 3)));
+        assert_eq!(parsed[1].1.foreground(), Some(AnsiColor::Indexed(15)));
+        assert_eq!(parsed[1].1.background(), Some(AnsiColor::Indexed(12)));
+
+        let parsed = spans("\x1b[38:5:46mx");
+        assert_eq!(parsed[0].1.foreground(), Some(AnsiColor::Indexed(46)));
+    }
+
+    #[test]
+    fn invalid_extended_colours_are_ignored() {
+        for sequence in [
+            "\x1b[38mx",
+            "\x1b[38;5mx",
+            "\x1b[38;5;256mx",
+            "\x1b[38;2;1;2mx",
+            "\x1b[38;9;1mx",
+            "\x1b[48;2;999;0;0mx",
+        ] {
+            let parsed = spans(sequence);
+            assert_eq!(parsed.len(), 1, "{sequence:?}");
+            assert_eq!(parsed[0].0, "x");
+            assert_eq!(parsed[0].1.foreground(), None, "{sequence:?}");
+            assert_eq!(parsed[0].1.background(), None, "{sequence:?}");
+        }
+    }
+
+    #[test]
+    fn empty_and_invalid_parameters_reset() {
+        let parsed = spans("\x1b[1ma\x1b[mb\x1b[1mc\x1b[99999999md");
+        let texts: Vec<_> = parsed.iter().map(|(text, _)| text.as_str()).collect();
+        assert_eq!(texts, ["a", "b", "c", "d"]);
+        assert!(parsed[0].1.bold());
+        assert!(parsed[1].1.is_plain());
+        assert!(parsed[2].1.bold());
+        assert!(parsed[3].1.is_plain());
+    }
+
+    #[test]
+    fn other_escapes_are_removed() {
+        assert_eq!(
+            strip_ansi("\x1b[2J\x1b[1;1Hclear\x1b]0;title\x07 \x1b]8;;url\x1b\\link\x1b7"),
+            "clear link"
+        );
+    }
+
+    #[test]
+    fn unterminated_escapes_do_not_panic() {
+        assert_eq!(strip_ansi("\x1b"), "");
+        for text in [
+            "text\x1b",
+            "text\x1b[",
+            "text\x1b[31",
+            "text\x1b[38;5;",
+            "text\x1b]0;title",
+            "text\x1b]0;title\x1b",
+        ] {
+            assert_eq!(strip_ansi(text), "text", "{text:?}");
+        }
+    }
+
+    #[test]
+    fn unicode_is_kept() {
+        assert_eq!(strip_ansi("\x1b[32m✔ passé\x1b[0m 日本"), "✔ passé 日本");
+    }
+
+    #[test]
+    fn indexed_colours_are_converted() {
+        assert_eq!(AnsiColor::Indexed(1).to_hex(), "#cd3131");
+        assert_eq!(AnsiColor::Indexed(16).rgb(), (0, 0, 0));
+        assert_eq!(AnsiColor::Indexed(231).rgb(), (255, 255, 255));
+        assert_eq!(AnsiColor::Indexed(232).rgb(), (8, 8, 8));
+        assert_eq!(AnsiColor::Indexed(255).rgb(), (238, 238, 238));
+        assert_eq!(AnsiColor::Rgb(1, 2, 3).to_hex(), "#010203");
+    }
+}
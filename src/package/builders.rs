@@ -0,0 +1,747 @@
+use std::{fs, path::PathBuf};
+
+use chrono::{DateTime, FixedOffset, Local};
+
+use super::{
+    Annotation, Author, Evidence, EvidenceData, EvidenceKind, EvidencePackage, HttpExchange,
+    HttpRequest, HttpResponse, MediaFile, Requirement, StructuredEvidence, TestCase,
+    TestCasePassStatus, TestStep,
+};
+use crate::{Error, Result};
+
+/// The MIME types of images that can be used as image evidence.
+const SUPPORTED_IMAGE_MIME_TYPES: &[&str] = &["image/png", "image/jpeg"];
+
+/// A builder for creating a new [`EvidencePackage`] with its test cases in a few lines.
+///
+/// ```no_run
+/// use evidenceangel::{Author, PackageBuilder, TestCaseBuilder};
+///
+/// # fn main() -> evidenceangel::Result<()> {
+/// let png = std::fs::read("screenshot.png")?;
+/// let package = PackageBuilder::new("results.evp", "Nightly regression")
+///     .author(Author::new("CI"))
+///     .test_case(
+///         TestCaseBuilder::new("Log in")
+///             .text("Logged in as a standard user")
+///             .screenshot(png)
+///             .passed(),
+///     )
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct PackageBuilder {
+    /// The path to create the package at.
+    path: PathBuf,
+    /// The title of the package.
+    title: String,
+    /// The description of the package.
+    description: Option<String>,
+    /// The authors of the package.
+    authors: Vec<Author>,
+    /// Custom metadata fields for test cases, as (ID, name, description, primary).
+    custom_fields: Vec<(String, String, String, bool)>,
+    /// The catalogue of requirements.
+    requirements: Vec<Requirement>,
+    /// The test cases to create.
+    test_cases: Vec<TestCaseBuilder>,
+}
+
+impl PackageBuilder {
+    /// Start building a package which will be created at `path`.
+    pub fn new<P: Into<PathBuf>, S: Into<String>>(path: P, title: S) -> Self {
+        Self {
+            path: path.into(),
+            title: title.into(),
+            description: None,
+            authors: vec![],
+            custom_fields: vec![],
+            requirements: vec![],
+            test_cases: vec![],
+        }
+    }
+
+    /// Set the description of the package.
+    #[must_use]
+    pub fn description<S: Into<String>>(mut self, description: S) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Add an author to the package.
+    #[must_use]
+    pub fn author(mut self, author: Author) -> Self {
+        self.authors.push(author);
+        self
+    }
+
+    /// Add a custom metadata field for test cases. If `primary` is true, this replaces any
+    /// previous primary field.
+    #[must_use]
+    pub fn custom_field<I, N, D>(mut self, id: I, name: N, description: D, primary: bool) -> Self
+    where
+        I: Into<String>,
+        N: Into<String>,
+        D: Into<String>,
+    {
+        self.custom_fields
+            .push((id.into(), name.into(), description.into(), primary));
+        self
+    }
+
+    /// Add a requirement to the package's catalogue.
+    #[must_use]
+    pub fn requirement(mut self, requirement: Requirement) -> Self {
+        self.requirements.push(requirement);
+        self
+    }
+
+    /// Add a test case to the package.
+    #[must_use]
+    pub fn test_case(mut self, test_case: TestCaseBuilder) -> Self {
+        self.test_cases.push(test_case);
+        self
+    }
+
+    /// Create the package, add its test cases and save it.
+    ///
+    /// # Errors
+    ///
+    /// - Any error from [`EvidencePackage::new_with_description`] if the package couldn't be
+    ///   created.
+    /// - Any error from [`TestCaseBuilder::build`] if a test case isn't valid.
+    /// - Any error from [`EvidencePackage::save`] if the package couldn't be saved.
+    pub fn build(self) -> Result<EvidencePackage> {
+        let mut package = EvidencePackage::new_with_description(
+            self.path,
+            self.title,
+            self.description,
+            self.authors,
+        )?;
+
+        let metadata = package.metadata_mut();
+        for (id, name, description, primary) in self.custom_fields {
+            metadata.insert_custom_metadata_field(Some(id), name, description, primary);
+        }
+        if !self.requirements.is_empty() {
+            metadata.requirements_mut().extend(self.requirements);
+        }
+
+        for test_case in self.test_cases {
+            test_case.build(&mut package)?;
+        }
+
+        package.save()?;
+        Ok(package)
+    }
+}
+
+/// A builder for adding a [`TestCase`] and its evidence to an [`EvidencePackage`], registering
+/// any media the evidence needs with the package.
+#[derive(Clone, Debug)]
+pub struct TestCaseBuilder {
+    /// The title of the test case.
+    title: String,
+    /// When the test case was executed, or [`None`] for when it is built.
+    executed_at: Option<DateTime<FixedOffset>>,
+    /// The status of the test case.
+    status: Option<TestCasePassStatus>,
+    /// Custom metadata values, as (field ID, value).
+    custom: Vec<(String, String)>,
+    /// The requirements the test case covers.
+    requirements: Vec<Requirement>,
+    /// The steps of the test case.
+    steps: Vec<TestStep>,
+    /// The evidence of the test case.
+    evidence: Vec<EvidenceBuilder>,
+}
+
+impl TestCaseBuilder {
+    /// Start building a test case.
+    pub fn new<S: Into<String>>(title: S) -> Self {
+        Self {
+            title: title.into(),
+            executed_at: None,
+            status: None,
+            custom: vec![],
+            requirements: vec![],
+            steps: vec![],
+            evidence: vec![],
+        }
+    }
+
+    /// Set when the test case was executed. By default, this is when it is built.
+    #[must_use]
+    pub fn executed_at(mut self, at: DateTime<FixedOffset>) -> Self {
+        self.executed_at = Some(at);
+        self
+    }
+
+    /// Set the status of the test case. If this isn't set, it is worked out from the results of
+    /// the steps, if there are any.
+    #[must_use]
+    pub fn status(mut self, status: Option<TestCasePassStatus>) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// Mark the test case as passed.
+    #[must_use]
+    pub fn passed(self) -> Self {
+        self.status(Some(TestCasePassStatus::Pass))
+    }
+
+    /// Mark the test case as failed.
+    #[must_use]
+    pub fn failed(self) -> Self {
+        self.status(Some(TestCasePassStatus::Fail))
+    }
+
+    /// Set the value of a custom metadata field.
+    #[must_use]
+    pub fn custom<K: Into<String>, V: Into<String>>(mut self, field: K, value: V) -> Self {
+        self.custom.push((field.into(), value.into()));
+        self
+    }
+
+    /// Record that the test case covers a requirement.
+    #[must_use]
+    pub fn requirement(mut self, requirement: Requirement) -> Self {
+        self.requirements.push(requirement);
+        self
+    }
+
+    /// Add a step to the test case. Evidence attached to the step refers to the evidence of this
+    /// builder by its zero-based index.
+    #[must_use]
+    pub fn step(mut self, step: TestStep) -> Self {
+        self.steps.push(step);
+        self
+    }
+
+    /// Add evidence to the test case.
+    #[must_use]
+    pub fn evidence(mut self, evidence: EvidenceBuilder) -> Self {
+        self.evidence.push(evidence);
+        self
+    }
+
+    /// Add plain text evidence.
+    #[must_use]
+    pub fn text<S: Into<String>>(self, text: S) -> Self {
+        self.evidence(EvidenceBuilder::text(text))
+    }
+
+    /// Add rich text evidence, written in Markdown.
+    #[must_use]
+    pub fn rich_text<S: Into<String>>(self, text: S) -> Self {
+        self.evidence(EvidenceBuilder::rich_text(text))
+    }
+
+    /// Add a PNG or JPEG image as evidence.
+    #[must_use]
+    pub fn screenshot<D: Into<Vec<u8>>>(self, data: D) -> Self {
+        self.evidence(EvidenceBuilder::screenshot(data))
+    }
+
+    /// Add a file from disk as evidence.
+    #[must_use]
+    pub fn file<P: Into<PathBuf>>(self, path: P) -> Self {
+        self.evidence(EvidenceBuilder::file(path))
+    }
+
+    /// Add an HTTP request and its response as evidence.
+    #[must_use]
+    pub fn http(self, request: HttpRequest, response: HttpResponse) -> Self {
+        self.evidence(EvidenceBuilder::http(request, response))
+    }
+
+    /// Add an MP4 or `WebM` video as evidence.
+    #[must_use]
+    pub fn video<D: Into<Vec<u8>>>(self, data: D) -> Self {
+        self.evidence(EvidenceBuilder::video(data))
+    }
+
+    /// Add structured evidence, such as a [`Log`](super::Log) or [`Table`](super::Table).
+    #[must_use]
+    pub fn structured<S: StructuredEvidence>(self, structure: &S) -> Self {
+        self.evidence(EvidenceBuilder::structured(structure))
+    }
+
+    /// Add the test case to a package, registering any media its evidence needs. The package
+    /// isn't saved.
+    ///
+    /// # Errors
+    ///
+    /// - Any error from [`EvidenceBuilder::build`] if some evidence isn't valid.
+    /// - [`Error::InvalidEvidenceData`] if a step refers to evidence that doesn't exist.
+    pub fn build(self, package: &mut EvidencePackage) -> Result<&mut TestCase> {
+        let evidence_count = self.evidence.len();
+        for (idx, step) in self.steps.iter().enumerate() {
+            if let Some(missing) = step.evidence().iter().find(|i| **i >= evidence_count) {
+                return Err(Error::InvalidEvidenceData(format!(
+                    "step {} refers to evidence {}, but the test case only has {evidence_count} pieces of evidence",
+                    idx + 1,
+                    missing + 1
+                )));
+            }
+        }
+
+        // Build the evidence first so an invalid piece doesn't leave a partial test case behind
+        let evidence = self
+            .evidence
+            .into_iter()
+            .map(|evidence| evidence.build(package))
+            .collect::<Result<Vec<_>>>()?;
+
+        let test_case = package.create_test_case_at(
+            self.title,
+            self.executed_at
+                .unwrap_or_else(|| Local::now().fixed_offset()),
+        )?;
+        let metadata = test_case.metadata_mut();
+        for (field, value) in self.custom {
+            metadata.custom_mut().insert(field, value);
+        }
+        if !self.requirements.is_empty() {
+            metadata.requirements_mut().extend(self.requirements);
+        }
+        *test_case.evidence_mut() = evidence;
+        *test_case.steps_mut() = self.steps;
+
+        if self.status.is_some() {
+            test_case.metadata_mut().set_passed(self.status);
+        } else {
+            test_case.update_status_from_steps();
+        }
+        Ok(test_case)
+    }
+}
+
+/// The content of evidence being built, before any media is registered with a package.
+#[derive(Clone, Debug)]
+enum EvidenceSource {
+    /// Evidence which doesn't need any media.
+    Ready(Evidence),
+    /// A PNG or JPEG image.
+    Screenshot(Vec<u8>),
+    /// A file, read from disk when built.
+    File(PathBuf),
+    /// A file already in memory.
+    FileData {
+        /// The name of the file.
+        filename: String,
+        /// The contents of the file.
+        data: Vec<u8>,
+    },
+    /// An MP4 or `WebM` video.
+    Video(Vec<u8>),
+}
+
+/// A builder for a piece of [`Evidence`], registering any media it needs with the package it is
+/// built for.
+#[derive(Clone, Debug)]
+pub struct EvidenceBuilder {
+    /// The content of the evidence.
+    source: EvidenceSource,
+    /// The caption of the evidence.
+    caption: Option<String>,
+    /// A PNG or JPEG poster frame, if this is a video.
+    poster: Option<Vec<u8>>,
+    /// Annotations drawn over the evidence, if it is an image.
+    annotations: Vec<Annotation>,
+}
+
+impl EvidenceBuilder {
+    /// Create a builder for evidence of some content.
+    fn from_source(source: EvidenceSource) -> Self {
+        Self {
+            source,
+            caption: None,
+            poster: None,
+            annotations: vec![],
+        }
+    }
+
+    /// Build plain text evidence.
+    pub fn text<S: Into<String>>(text: S) -> Self {
+        Self::from_source(EvidenceSource::Ready(Evidence::new(
+            EvidenceKind::Text,
+            EvidenceData::Text {
+                content: text.into(),
+            },
+        )))
+    }
+
+    /// Build rich text evidence, written in Markdown.
+    pub fn rich_text<S: Into<String>>(text: S) -> Self {
+        Self::from_source(EvidenceSource::Ready(Evidence::new(
+            EvidenceKind::RichText,
+            EvidenceData::Text {
+                content: text.into(),
+            },
+        )))
+    }
+
+    /// Build image evidence from a PNG or JPEG image.
+    pub fn screenshot<D: Into<Vec<u8>>>(data: D) -> Self {
+        Self::from_source(EvidenceSource::Screenshot(data.into()))
+    }
+
+    /// Build file evidence from a file on disk, which is read when the evidence is built.
+    pub fn file<P: Into<PathBuf>>(path: P) -> Self {
+        Self::from_source(EvidenceSource::File(path.into()))
+    }
+
+    /// Build file evidence from the contents of a file.
+    pub fn file_data<S: Into<String>, D: Into<Vec<u8>>>(filename: S, data: D) -> Self {
+        Self::from_source(EvidenceSource::FileData {
+            filename: filename.into(),
+            data: data.into(),
+        })
+    }
+
+    /// Build HTTP evidence from a request and its response.
+    #[must_use]
+    pub fn http(request: HttpRequest, response: HttpResponse) -> Self {
+        Self::http_exchange(&HttpExchange::new(request, Some(response)))
+    }
+
+    /// Build HTTP evidence from an exchange, which may not have a response.
+    #[must_use]
+    pub fn http_exchange(exchange: &HttpExchange) -> Self {
+        Self::from_source(EvidenceSource::Ready(exchange.to_evidence()))
+    }
+
+    /// Build video evidence from an MP4 or `WebM` video.
+    pub fn video<D: Into<Vec<u8>>>(data: D) -> Self {
+        Self::from_source(EvidenceSource::Video(data.into()))
+    }
+
+    /// Build structured evidence, such as a [`Log`](super::Log) or [`Table`](super::Table).
+    pub fn structured<S: StructuredEvidence>(structure: &S) -> Self {
+        Self::from_source(EvidenceSource::Ready(structure.to_evidence()))
+    }
+
+    /// Build evidence which has already been created.
+    #[must_use]
+    pub fn existing(evidence: Evidence) -> Self {
+        Self::from_source(EvidenceSource::Ready(evidence))
+    }
+
+    /// Set the caption of the evidence.
+    #[must_use]
+    pub fn caption<S: Into<String>>(mut self, caption: S) -> Self {
+        self.caption = Some(caption.into());
+        self
+    }
+
+    /// Set a PNG or JPEG poster frame for video evidence.
+    #[must_use]
+    pub fn poster<D: Into<Vec<u8>>>(mut self, data: D) -> Self {
+        self.poster = Some(data.into());
+        self
+    }
+
+    /// Draw an annotation over image evidence.
+    #[must_use]
+    pub fn annotation(mut self, annotation: Annotation) -> Self {
+        self.annotations.push(annotation);
+        self
+    }
+
+    /// Create the evidence, registering any media it needs with a package. The package isn't
+    /// saved.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::InvalidEvidenceData`] if a screenshot or poster isn't a PNG or JPEG image, a
+    ///   video isn't an MP4 or `WebM` video, or a poster or annotations are given for the wrong
+    ///   kind of evidence.
+    /// - [`Error::Io`] if a file couldn't be read.
+    /// - Any error from [`EvidencePackage::add_media`] if media couldn't be registered.
+    pub fn build(self, package: &mut EvidencePackage) -> Result<Evidence> {
+        let mut evidence = match self.source {
+            EvidenceSource::Ready(evidence) => evidence,
+            EvidenceSource::Screenshot(data) => {
+                let hash = add_image(package, data.into())?;
+                // Generate the thumbnail now so that it is stored when the package is saved
+                package.get_thumbnail(&hash)?;
+                Evidence::new(EvidenceKind::Image, EvidenceData::Media { hash })
+            }
+            EvidenceSource::File(path) => {
+                let data = fs::read(&path)?;
+                let filename = path
+                    .file_name()
+                    .map(|s| s.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                add_file(package, filename, data)?
+            }
+            EvidenceSource::FileData { filename, data } => add_file(package, filename, data)?,
+            EvidenceSource::Video(data) => {
+                let media = MediaFile::from(data);
+                if !media.is_supported_video() {
+                    return Err(Error::InvalidEvidenceData(
+                        "videos must be MP4 or WebM".to_string(),
+                    ));
+                }
+                let hash = package.add_media(media)?.hash();
+                Evidence::new(EvidenceKind::Video, EvidenceData::Media { hash })
+            }
+        };
+
+        if let Some(poster) = self.poster {
+            if *evidence.kind() != EvidenceKind::Video {
+                return Err(Error::InvalidEvidenceData(
+                    "only video evidence can have a poster".to_string(),
+                ));
+            }
+            let hash = add_image(package, poster.into())?;
+            evidence.set_poster(Some(hash));
+        }
+        if !self.annotations.is_empty() {
+            if *evidence.kind() != EvidenceKind::Image {
+                return Err(Error::InvalidEvidenceData(
+                    "only image evidence can be annotated".to_string(),
+                ));
+            }
+            evidence.annotations_mut().extend(self.annotations);
+        }
+        if self.caption.is_some() {
+            evidence.set_caption(self.caption);
+        }
+        Ok(evidence)
+    }
+}
+
+/// Check media is a PNG or JPEG image and register it with a package, returning its hash.
+fn add_image(package: &mut EvidencePackage, media: MediaFile) -> Result<String> {
+    if !SUPPORTED_IMAGE_MIME_TYPES.contains(&media.media_type()) {
+        return Err(Error::InvalidEvidenceData(
+            "images must be PNG or JPEG".to_string(),
+        ));
+    }
+    Ok(package.add_media(media)?.hash())
+}
+
+/// Register a file with a package, returning file evidence referring to it.
+fn add_file(package: &mut EvidencePackage, filename: String, data: Vec<u8>) -> Result<Evidence> {
+    let mut media = MediaFile::from(data);
+    media.set_original_filename(Some(filename.clone()));
+    let hash = package.add_media(media)?.hash();
+    let mut evidence = Evidence::new(EvidenceKind::File, EvidenceData::Media { hash });
+    evidence.set_original_filename(Some(filename));
+    Ok(evidence)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{RedactionStyle, StepStatus};
+
+    /// Bytes which start like a PNG image.
+    const PNG: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+
+    /// Bytes which start like an MP4 video.
+    const MP4: &[u8] = b"\x00\x00\x00\x18ftypmp42\x00\x00\x00\x00mp42isom";
+
+    /// Create an empty package in a temporary directory.
+    fn package(dir: &tempfile::TempDir) -> EvidencePackage {
+        PackageBuilder::new(dir.path().join("package.evp"), "Package")
+            .build()
+            .unwrap()
+    }
+
+    /// Build some evidence, returning the error it fails with.
+    fn build_error(evidence: EvidenceBuilder) -> Error {
+        let dir = tempfile::tempdir().unwrap();
+        evidence.build(&mut package(&dir)).unwrap_err()
+    }
+
+    #[test]
+    fn packages_are_built_and_saved() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("package.evp");
+        let mut step = TestStep::new("Log in");
+        step.attach_evidence(1);
+        let package = PackageBuilder::new(path.clone(), "Package")
+            .description("Nightly run")
+            .author(Author::new("CI"))
+            .custom_field("env", "Environment", "Where the tests ran", true)
+            .requirement(Requirement::new("REQ-1"))
+            .test_case(
+                TestCaseBuilder::new("Test")
+                    .custom("env", "ci")
+                    .requirement(Requirement::new("REQ-1"))
+                    .text("Plain")
+                    .evidence(EvidenceBuilder::text("Captioned").caption("A caption"))
+                    .step(step),
+            )
+            .build()
+            .unwrap();
+        drop(package);
+
+        let package = EvidencePackage::open(path).unwrap();
+        assert_eq!(package.metadata().title(), "Package");
+        assert_eq!(
+            package.metadata().description().as_deref(),
+            Some("Nightly run")
+        );
+        assert_eq!(package.metadata().authors(), &vec![Author::new("CI")]);
+        let test_case = package.test_case_iter().unwrap().next().unwrap();
+        assert_eq!(test_case.evidence().len(), 2);
+        assert_eq!(
+            test_case.evidence()[1].caption().as_deref(),
+            Some("A caption")
+        );
+        assert_eq!(
+            test_case
+                .metadata()
+                .custom()
+                .as_ref()
+                .and_then(|custom| custom.get("env"))
+                .map(String::as_str),
+            Some("ci")
+        );
+        assert_eq!(*test_case.steps()[0].evidence(), vec![1]);
+        assert_eq!(*test_case.metadata().passed(), None);
+    }
+
+    #[test]
+    fn statuses_default_to_those_of_the_steps() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut package = package(&dir);
+        let mut step = TestStep::new("Log in");
+        step.set_status(Some(StepStatus::Pass));
+
+        let test_case = TestCaseBuilder::new("From steps")
+            .step(step.clone())
+            .build(&mut package)
+            .unwrap();
+        assert_eq!(
+            *test_case.metadata().passed(),
+            Some(TestCasePassStatus::Pass)
+        );
+        let test_case = TestCaseBuilder::new("Explicit")
+            .step(step)
+            .failed()
+            .build(&mut package)
+            .unwrap();
+        assert_eq!(
+            *test_case.metadata().passed(),
+            Some(TestCasePassStatus::Fail)
+        );
+    }
+
+    #[test]
+    fn invalid_titles_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        for title in ["", "A title that is far too long for a package"] {
+            assert!(matches!(
+                PackageBuilder::new(dir.path().join("package.evp"), title).build(),
+                Err(Error::ManifestSchemaValidationFailed)
+            ));
+        }
+        assert!(matches!(
+            PackageBuilder::new(dir.path().join("package.evp"), "Package")
+                .test_case(TestCaseBuilder::new(""))
+                .build(),
+            Err(Error::TestCaseSchemaValidationFailed)
+        ));
+    }
+
+    #[test]
+    fn steps_referring_to_missing_evidence_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut package = package(&dir);
+        let mut step = TestStep::new("Log in");
+        step.attach_evidence(1);
+
+        let result = TestCaseBuilder::new("Test")
+            .text("Only evidence")
+            .step(step)
+            .build(&mut package);
+        assert!(matches!(result, Err(Error::InvalidEvidenceData(_))));
+        assert_eq!(package.test_case_iter().unwrap().count(), 0);
+    }
+
+    #[test]
+    fn invalid_evidence_leaves_no_test_case_behind() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut package = package(&dir);
+
+        let result = TestCaseBuilder::new("Test")
+            .text("Valid")
+            .screenshot(b"not an image".to_vec())
+            .build(&mut package);
+        assert!(matches!(result, Err(Error::InvalidEvidenceData(_))));
+        assert_eq!(package.test_case_iter().unwrap().count(), 0);
+    }
+
+    #[test]
+    fn invalid_media_is_rejected() {
+        assert!(matches!(
+            build_error(EvidenceBuilder::screenshot(MP4)),
+            Error::InvalidEvidenceData(_)
+        ));
+        assert!(matches!(
+            build_error(EvidenceBuilder::video(PNG)),
+            Error::InvalidEvidenceData(_)
+        ));
+        assert!(matches!(
+            build_error(EvidenceBuilder::video(MP4).poster(MP4)),
+            Error::InvalidEvidenceData(_)
+        ));
+    }
+
+    #[test]
+    fn details_for_the_wrong_kind_of_evidence_are_rejected() {
+        assert!(matches!(
+            build_error(EvidenceBuilder::screenshot(PNG).poster(PNG)),
+            Error::InvalidEvidenceData(_)
+        ));
+        assert!(matches!(
+            build_error(
+                EvidenceBuilder::text("Text").annotation(Annotation::Redaction {
+                    x: 0,
+                    y: 0,
+                    width: 1,
+                    height: 1,
+                    style: RedactionStyle::BlackOut,
+                })
+            ),
+            Error::InvalidEvidenceData(_)
+        ));
+    }
+
+    #[test]
+    fn missing_files_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(matches!(
+            build_error(EvidenceBuilder::file(dir.path().join("missing.txt"))),
+            Error::Io(_)
+        ));
+    }
+
+    #[test]
+    fn media_is_registered() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut package = package(&dir);
+        let path = dir.path().join("notes.txt");
+        fs::write(&path, "Some notes").unwrap();
+
+        let file = EvidenceBuilder::file(&path).build(&mut package).unwrap();
+        assert_eq!(*file.kind(), EvidenceKind::File);
+        assert_eq!(file.original_filename().as_deref(), Some("notes.txt"));
+        assert_eq!(
+            file.value().get_data(&mut package).unwrap(),
+            b"Some notes".to_vec()
+        );
+
+        let video = EvidenceBuilder::video(MP4).build(&mut package).unwrap();
+        assert_eq!(*video.kind(), EvidenceKind::Video);
+        assert_eq!(video.value().get_data(&mut package).unwrap(), MP4.to_vec());
+    }
+}
@@ -0,0 +1,73 @@
+use serde::{Serialize, de::DeserializeOwned};
+
+use super::{Evidence, EvidenceData, EvidenceKind, EvidencePackage};
+
+/// Command execution evidence
+mod command;
+pub use command::CommandExecution;
+/// Expected and actual content comparison evidence
+mod diff;
+pub use diff::{Diff, DiffChange, DiffFormat, DiffLine};
+/// HTTP Archive import
+mod har;
+pub use har::{HarEntry, HarTimings, parse_har};
+/// HTTP request and response evidence
+mod http;
+pub use http::{
+    HTTP_SEPARATOR, HttpBodyFormat, HttpExchange, HttpHeader, HttpRequest, HttpResponse,
+};
+/// Link evidence
+mod link;
+pub use link::{Link, LinkSystem};
+/// Log evidence
+mod log;
+pub use log::{Log, LogEntry, LogFormat, LogLevel};
+/// SQL query evidence
+mod sql;
+pub use sql::{SqlQuery, SqlToken};
+/// Table evidence
+mod table;
+pub use table::{Table, TableCell, TableFormat};
+
+/// Evidence with a structure of its own, which is stored as JSON in the value of [`Evidence`] of a
+/// particular [`EvidenceKind`].
+pub trait StructuredEvidence: Serialize + DeserializeOwned {
+    /// The kind of evidence this structure is stored in.
+    const KIND: EvidenceKind;
+
+    /// Create a new piece of evidence containing this data.
+    ///
+    /// # Panics
+    ///
+    /// This will panic if the structure can't be serialized to JSON, which shouldn't happen for
+    /// any of the structures provided by this crate.
+    fn to_evidence(&self) -> Evidence {
+        Evidence::new(
+            Self::KIND,
+            EvidenceData::Text {
+                content: serde_json::to_string(self)
+                    .expect("structured evidence should always serialize"),
+            },
+        )
+    }
+
+    /// Read this structure from a piece of evidence.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::InvalidEvidenceData`](crate::Error::InvalidEvidenceData) if the evidence is of
+    ///   a different kind, or doesn't contain valid data for this kind.
+    /// - [`Error::MediaMissing`](crate::Error::MediaMissing) if the media referred to by the
+    ///   evidence is missing from the package.
+    fn from_evidence(evidence: &Evidence, package: &mut EvidencePackage) -> crate::Result<Self> {
+        if *evidence.kind() != Self::KIND {
+            return Err(crate::Error::InvalidEvidenceData(format!(
+                "expected {:?} evidence, found {:?}",
+                Self::KIND,
+                evidence.kind()
+            )));
+        }
+        let data = evidence.value().get_data(package)?;
+        serde_json::from_slice(&data).map_err(|e| crate::Error::InvalidEvidenceData(e.to_string()))
+    }
+}
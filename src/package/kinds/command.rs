@@ -0,0 +1,84 @@
+use std::{collections::BTreeMap, time::Duration};
+
+use getset::{CopyGetters, Getters, MutGetters, Setters};
+use serde::{Deserialize, Serialize};
+
+use super::StructuredEvidence;
+use crate::EvidenceKind;
+
+/// Evidence of a command being executed, with its output and result.
+#[derive(
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Getters,
+    CopyGetters,
+    MutGetters,
+    Setters,
+)]
+pub struct CommandExecution {
+    /// The command line that was executed.
+    #[getset(get = "pub", set = "pub")]
+    command: String,
+    /// The directory the command was executed in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub", set = "pub")]
+    working_directory: Option<String>,
+    /// The environment variables of interest that were set when the command was executed. This
+    /// isn't expected to be the whole environment.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    #[getset(get = "pub", get_mut = "pub")]
+    environment: BTreeMap<String, String>,
+    /// The code the command exited with, if it exited normally.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get_copy = "pub", set = "pub")]
+    exit_code: Option<i32>,
+    /// How long the command took to execute, in milliseconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    duration_ms: Option<u64>,
+    /// The standard output of the command, which may contain terminal escape codes.
+    #[serde(default)]
+    #[getset(get = "pub", set = "pub")]
+    stdout: String,
+    /// The standard error of the command, which may contain terminal escape codes.
+    #[serde(default)]
+    #[getset(get = "pub", set = "pub")]
+    stderr: String,
+}
+
+impl StructuredEvidence for CommandExecution {
+    const KIND: EvidenceKind = EvidenceKind::Command;
+}
+
+impl CommandExecution {
+    /// Create new evidence of a command line being executed.
+    #[must_use]
+    pub fn new<S: Into<String>>(command: S) -> Self {
+        Self {
+            command: command.into(),
+            ..Default::default()
+        }
+    }
+
+    /// How long the command took to execute, if known.
+    #[must_use]
+    pub fn duration(&self) -> Option<Duration> {
+        self.duration_ms.map(Duration::from_millis)
+    }
+
+    /// Set how long the command took to execute. This is stored to the nearest millisecond.
+    pub fn set_duration(&mut self, duration: Option<Duration>) -> &mut Self {
+        self.duration_ms = duration.map(|duration| duration.as_millis() as u64);
+        self
+    }
+
+    /// Determine if the command succeeded, which is when it exited with a code of zero.
+    #[must_use]
+    pub fn succeeded(&self) -> bool {
+        self.exit_code == Some(0)
+    }
+}
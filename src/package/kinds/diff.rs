@@ -0,0 +1,522 @@
+use std::str::FromStr;
+
+use getset::{CopyGetters, Getters};
+use serde::{Deserialize, Serialize};
+
+use super::StructuredEvidence;
+use crate::EvidenceKind;
+
+/// Evidence comparing the expected and actual content of some check, which is displayed as a diff.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, Getters, CopyGetters)]
+pub struct Diff {
+    /// The format of the content being compared.
+    #[getset(get_copy = "pub")]
+    format: DiffFormat,
+    /// The content that was expected.
+    #[getset(get = "pub")]
+    expected: String,
+    /// The content that was actually found.
+    #[getset(get = "pub")]
+    actual: String,
+}
+
+impl StructuredEvidence for Diff {
+    const KIND: EvidenceKind = EvidenceKind::Diff;
+}
+
+impl Diff {
+    /// Create a new diff between two pieces of text.
+    #[must_use]
+    pub fn new<S: Into<String>, T: Into<String>>(expected: S, actual: T) -> Self {
+        Self {
+            format: DiffFormat::Text,
+            expected: expected.into(),
+            actual: actual.into(),
+        }
+    }
+
+    /// Create a new diff between two JSON documents. Both documents are reformatted with their
+    /// keys sorted, so that only differences in their values are shown.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::InvalidEvidenceData`](crate::Error::InvalidEvidenceData) if either document
+    ///   isn't valid JSON.
+    pub fn json(expected: &str, actual: &str) -> crate::Result<Self> {
+        Ok(Self {
+            format: DiffFormat::Json,
+            expected: normalize_json(expected)?,
+            actual: normalize_json(actual)?,
+        })
+    }
+
+    /// Create a new diff in the given format. If the format is [`DiffFormat::Auto`], the content
+    /// is compared as JSON if both sides are JSON objects or arrays, and as text otherwise.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::InvalidEvidenceData`](crate::Error::InvalidEvidenceData) if JSON is requested
+    ///   and either side isn't valid JSON.
+    pub fn parse(expected: &str, actual: &str, format: DiffFormat) -> crate::Result<Self> {
+        match format {
+            DiffFormat::Json => Self::json(expected, actual),
+            DiffFormat::Text => Ok(Self::new(expected, actual)),
+            DiffFormat::Auto => {
+                Ok(Self::json(expected, actual).unwrap_or_else(|_| Self::new(expected, actual)))
+            }
+        }
+    }
+
+    /// Determine if the expected and actual content differ.
+    #[must_use]
+    pub fn has_differences(&self) -> bool {
+        self.expected != self.actual
+    }
+
+    /// Compute the differences between the expected and actual content, line by line, in the
+    /// order they would appear in a unified diff.
+    #[must_use]
+    pub fn lines(&self) -> Vec<DiffLine> {
+        let expected = self.expected.lines().collect::<Vec<_>>();
+        let actual = self.actual.lines().collect::<Vec<_>>();
+        diff_lines(&expected, &actual)
+            .into_iter()
+            .map(|op| match op {
+                DiffOp::Unchanged(e, a) => DiffLine {
+                    change: DiffChange::Unchanged,
+                    expected_line: Some(e + 1),
+                    actual_line: Some(a + 1),
+                    text: expected[e].to_string(),
+                },
+                DiffOp::Removed(e) => DiffLine {
+                    change: DiffChange::Removed,
+                    expected_line: Some(e + 1),
+                    actual_line: None,
+                    text: expected[e].to_string(),
+                },
+                DiffOp::Added(a) => DiffLine {
+                    change: DiffChange::Added,
+                    expected_line: None,
+                    actual_line: Some(a + 1),
+                    text: actual[a].to_string(),
+                },
+            })
+            .collect()
+    }
+
+    /// Compute the differences between the expected and actual content as rows of a side by side
+    /// view. Each row has the expected line on the left and the actual line on the right, with
+    /// removed lines paired up against the lines that were added in their place.
+    #[must_use]
+    pub fn side_by_side(&self) -> Vec<(Option<DiffLine>, Option<DiffLine>)> {
+        let mut rows = vec![];
+        let mut removed = vec![];
+        let mut added = vec![];
+        let flush = |rows: &mut Vec<_>, removed: &mut Vec<_>, added: &mut Vec<_>| {
+            let len = removed.len().max(added.len());
+            let mut removed = std::mem::take(removed).into_iter();
+            let mut added = std::mem::take(added).into_iter();
+            for _ in 0..len {
+                rows.push((removed.next(), added.next()));
+            }
+        };
+
+        for line in self.lines() {
+            match line.change {
+                DiffChange::Removed => removed.push(line),
+                DiffChange::Added => added.push(line),
+                DiffChange::Unchanged => {
+                    flush(&mut rows, &mut removed, &mut added);
+                    rows.push((Some(line.clone()), Some(line)));
+                }
+            }
+        }
+        flush(&mut rows, &mut removed, &mut added);
+        rows
+    }
+}
+
+/// The format of the content compared by a [`Diff`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DiffFormat {
+    /// Detect the format from the content. This is only used when creating a diff, and is never
+    /// stored.
+    #[serde(skip)]
+    Auto,
+    /// Plain text.
+    #[default]
+    Text,
+    /// JSON documents, which have been reformatted with their keys sorted.
+    Json,
+}
+
+impl FromStr for DiffFormat {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "auto" => Ok(Self::Auto),
+            "text" | "plain" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            _ => Err(()),
+        }
+    }
+}
+
+/// How a line changed between the expected and actual content.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DiffChange {
+    /// The line is the same in both.
+    Unchanged,
+    /// The line was expected, but is missing from the actual content.
+    Removed,
+    /// The line is in the actual content, but wasn't expected.
+    Added,
+}
+
+/// A single line of a [`Diff`].
+#[derive(Clone, Debug, PartialEq, Eq, Getters, CopyGetters)]
+pub struct DiffLine {
+    /// How this line changed.
+    #[getset(get_copy = "pub")]
+    change: DiffChange,
+    /// The one-based line number in the expected content, if this line is present there.
+    #[getset(get_copy = "pub")]
+    expected_line: Option<usize>,
+    /// The one-based line number in the actual content, if this line is present there.
+    #[getset(get_copy = "pub")]
+    actual_line: Option<usize>,
+    /// The text of this line.
+    #[getset(get = "pub")]
+    text: String,
+}
+
+/// Reformat a JSON document with its keys sorted.
+fn normalize_json(text: &str) -> crate::Result<String> {
+    let value: serde_json::Value =
+        serde_json::from_str(text).map_err(|e| crate::Error::InvalidEvidenceData(e.to_string()))?;
+    if !(value.is_object() || value.is_array()) {
+        return Err(crate::Error::InvalidEvidenceData(
+            "expected a JSON object or array".to_string(),
+        ));
+    }
+    Ok(serde_json::to_string_pretty(&value).expect("JSON values should always serialize"))
+}
+
+/// A single step of an edit script, referring to zero-based line indexes.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum DiffOp {
+    /// The line at these indexes of the expected and actual content is the same.
+    Unchanged(usize, usize),
+    /// The line at this index of the expected content was removed.
+    Removed(usize),
+    /// The line at this index of the actual content was added.
+    Added(usize),
+}
+
+/// Compute the shortest edit script from `expected` to `actual`, using the linear space
+/// variant of Myers' algorithm, so that large comparisons don't need much memory.
+fn diff_lines(expected: &[&str], actual: &[&str]) -> Vec<DiffOp> {
+    let mut ops = Vec::with_capacity(expected.len().max(actual.len()));
+    diff_range(expected, actual, 0, 0, &mut ops);
+    ops
+}
+
+/// Add the edit script from `old` to `new` to `ops`, where `old` and `new` start at these line
+/// indexes of the whole content. The script is found by splitting the comparison where the
+/// shortest edit script crosses its middle, then comparing each half.
+fn diff_range(
+    old: &[&str],
+    new: &[&str],
+    old_start: usize,
+    new_start: usize,
+    ops: &mut Vec<DiffOp>,
+) {
+    // Lines in common at the start and end don't need to go through the algorithm
+    let prefix = old
+        .iter()
+        .zip(new)
+        .take_while(|(left, right)| left == right)
+        .count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(left, right)| left == right)
+        .count();
+    ops.extend((0..prefix).map(|line| DiffOp::Unchanged(old_start + line, new_start + line)));
+
+    let old_middle = &old[prefix..old.len() - suffix];
+    let new_middle = &new[prefix..new.len() - suffix];
+    let (old_middle_start, new_middle_start) = (old_start + prefix, new_start + prefix);
+    let split = if old_middle.is_empty() || new_middle.is_empty() {
+        None
+    } else {
+        middle_split(old_middle, new_middle)
+    };
+    if let Some((x, y)) = split {
+        diff_range(
+            &old_middle[..x],
+            &new_middle[..y],
+            old_middle_start,
+            new_middle_start,
+            ops,
+        );
+        diff_range(
+            &old_middle[x..],
+            &new_middle[y..],
+            old_middle_start + x,
+            new_middle_start + y,
+            ops,
+        );
+    } else {
+        ops.extend((0..old_middle.len()).map(|line| DiffOp::Removed(old_middle_start + line)));
+        ops.extend((0..new_middle.len()).map(|line| DiffOp::Added(new_middle_start + line)));
+    }
+
+    ops.extend((0..suffix).map(|line| {
+        DiffOp::Unchanged(
+            old_start + old.len() - suffix + line,
+            new_start + new.len() - suffix + line,
+        )
+    }));
+}
+
+/// Find where the shortest edit script from `old` to `new` crosses its middle, by searching
+/// forwards from the start and backwards from the end at the same time until the paths
+/// overlap. Only the furthest point reached on each diagonal is kept, so this needs space in
+/// proportion to the length of the content. Both `old` and `new` must be non-empty.
+fn middle_split(old: &[&str], new: &[&str]) -> Option<(usize, usize)> {
+    let old_len = old.len().cast_signed();
+    let new_len = new.len().cast_signed();
+    let max_d = (old_len + new_len + 1) / 2;
+    let offset = max_d;
+    // The furthest x reached on each diagonal k, indexed by `offset + k`, searching forwards
+    // and backwards. -1 marks diagonals that haven't been reached.
+    let mut forward = vec![-1isize; 2 * max_d as usize + 2];
+    let mut backward = forward.clone();
+    forward[(offset + 1) as usize] = 0;
+    backward[(offset + 1) as usize] = 0;
+    let delta = old_len - new_len;
+    // If the difference in length is odd, the paths meet on a forward step
+    let front = delta % 2 != 0;
+    // Diagonals that have run off the edge don't need to be searched again
+    let (mut k1_start, mut k1_end, mut k2_start, mut k2_end) = (0, 0, 0, 0);
+    let len = forward.len().cast_signed();
+    let in_range = |index: isize| (0..len).contains(&index);
+
+    for d in 0..max_d {
+        for k1 in (-d + k1_start..=d - k1_end).step_by(2) {
+            let k1_offset = (offset + k1) as usize;
+            let mut x1 = if k1 == -d || (k1 != d && forward[k1_offset - 1] < forward[k1_offset + 1])
+            {
+                forward[k1_offset + 1]
+            } else {
+                forward[k1_offset - 1] + 1
+            };
+            let mut y1 = x1 - k1;
+            while x1 < old_len && y1 < new_len && old[x1 as usize] == new[y1 as usize] {
+                x1 += 1;
+                y1 += 1;
+            }
+            forward[k1_offset] = x1;
+            if x1 > old_len {
+                k1_end += 2;
+            } else if y1 > new_len {
+                k1_start += 2;
+            } else if front {
+                let k2_offset = offset + delta - k1;
+                if in_range(k2_offset)
+                    && backward[k2_offset as usize] != -1
+                    && x1 >= old_len - backward[k2_offset as usize]
+                {
+                    return Some((x1 as usize, y1 as usize));
+                }
+            }
+        }
+
+        for k2 in (-d + k2_start..=d - k2_end).step_by(2) {
+            let k2_offset = (offset + k2) as usize;
+            let mut x2 =
+                if k2 == -d || (k2 != d && backward[k2_offset - 1] < backward[k2_offset + 1]) {
+                    backward[k2_offset + 1]
+                } else {
+                    backward[k2_offset - 1] + 1
+                };
+            let mut y2 = x2 - k2;
+            while x2 < old_len
+                && y2 < new_len
+                && old[(old_len - x2 - 1) as usize] == new[(new_len - y2 - 1) as usize]
+            {
+                x2 += 1;
+                y2 += 1;
+            }
+            backward[k2_offset] = x2;
+            if x2 > old_len {
+                k2_end += 2;
+            } else if y2 > new_len {
+                k2_start += 2;
+            } else if !front {
+                let k1_offset = offset + delta - k2;
+                if in_range(k1_offset) && forward[k1_offset as usize] != -1 {
+                    let x1 = forward[k1_offset as usize];
+                    let y1 = offset + x1 - k1_offset;
+                    if x1 >= old_len - x2 {
+                        return Some((x1 as usize, y1 as usize));
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Check that an edit script turns `expected` into `actual`, returning how many lines it
+    /// removes or adds.
+    fn check_script(expected: &[&str], actual: &[&str], ops: &[DiffOp]) -> usize {
+        let (mut e, mut a, mut edits) = (0, 0, 0);
+        for op in ops {
+            match *op {
+                DiffOp::Unchanged(old, new) => {
+                    assert_eq!((old, new), (e, a));
+                    assert_eq!(expected[old], actual[new]);
+                    e += 1;
+                    a += 1;
+                }
+                DiffOp::Removed(old) => {
+                    assert_eq!(old, e);
+                    e += 1;
+                    edits += 1;
+                }
+                DiffOp::Added(new) => {
+                    assert_eq!(new, a);
+                    a += 1;
+                    edits += 1;
+                }
+            }
+        }
+        assert_eq!((e, a), (expected.len(), actual.len()));
+        edits
+    }
+
+    /// The fewest lines that must be removed or added, from the longest common subsequence.
+    fn shortest_edits(expected: &[&str], actual: &[&str]) -> usize {
+        let mut lengths = vec![vec![0; actual.len() + 1]; expected.len() + 1];
+        for (i, left) in expected.iter().enumerate() {
+            for (j, right) in actual.iter().enumerate() {
+                lengths[i + 1][j + 1] = if left == right {
+                    lengths[i][j] + 1
+                } else {
+                    lengths[i][j + 1].max(lengths[i + 1][j])
+                };
+            }
+        }
+        expected.len() + actual.len() - 2 * lengths[expected.len()][actual.len()]
+    }
+
+    #[test]
+    fn empty_inputs_have_no_edits() {
+        assert!(diff_lines(&[], &[]).is_empty());
+    }
+
+    #[test]
+    fn identical_inputs_are_unchanged() {
+        let lines = ["a", "b", "c"];
+        assert_eq!(
+            diff_lines(&lines, &lines),
+            vec![
+                DiffOp::Unchanged(0, 0),
+                DiffOp::Unchanged(1, 1),
+                DiffOp::Unchanged(2, 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn pure_insertions_are_added() {
+        assert_eq!(
+            diff_lines(&[], &["a", "b"]),
+            vec![DiffOp::Added(0), DiffOp::Added(1)]
+        );
+        assert_eq!(
+            diff_lines(&["a", "c"], &["a", "b", "c", "d"]),
+            vec![
+                DiffOp::Unchanged(0, 0),
+                DiffOp::Added(1),
+                DiffOp::Unchanged(1, 2),
+                DiffOp::Added(3),
+            ]
+        );
+    }
+
+    #[test]
+    fn pure_deletions_are_removed() {
+        assert_eq!(
+            diff_lines(&["a", "b"], &[]),
+            vec![DiffOp::Removed(0), DiffOp::Removed(1)]
+        );
+        assert_eq!(
+            diff_lines(&["a", "b", "c", "d"], &["b", "d"]),
+            vec![
+                DiffOp::Removed(0),
+                DiffOp::Unchanged(1, 0),
+                DiffOp::Removed(2),
+                DiffOp::Unchanged(3, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn mixed_changes_are_shortest() {
+        let expected = ["a", "b", "c", "a", "b", "b", "a"];
+        let actual = ["c", "b", "a", "b", "a", "c"];
+        let ops = diff_lines(&expected, &actual);
+        assert_eq!(check_script(&expected, &actual, &ops), 5);
+    }
+
+    #[test]
+    fn generated_changes_are_shortest() {
+        // A simple linear congruential generator, so that the cases are the same every run
+        let mut state = 12345u32;
+        let mut next = move || {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            (state >> 16) as usize
+        };
+        let alphabet = ["a", "b", "c", "d"];
+        for _ in 0..200 {
+            let expected: Vec<&str> = (0..next() % 12).map(|_| alphabet[next() % 4]).collect();
+            let actual: Vec<&str> = (0..next() % 12).map(|_| alphabet[next() % 4]).collect();
+            let ops = diff_lines(&expected, &actual);
+            assert_eq!(
+                check_script(&expected, &actual, &ops),
+                shortest_edits(&expected, &actual),
+                "{expected:?} -> {actual:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn completely_different_inputs_are_replaced() {
+        let expected: Vec<String> = (0..2000).map(|line| format!("old {line}")).collect();
+        let actual: Vec<String> = (0..2000).map(|line| format!("new {line}")).collect();
+        let expected: Vec<&str> = expected.iter().map(String::as_str).collect();
+        let actual: Vec<&str> = actual.iter().map(String::as_str).collect();
+        let ops = diff_lines(&expected, &actual);
+        assert_eq!(check_script(&expected, &actual, &ops), 4000);
+    }
+
+    #[test]
+    fn lines_are_numbered_from_one() {
+        let diff = Diff::new("a\nb\n", "a\nc\n");
+        let lines = diff.lines();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[1].change(), DiffChange::Removed);
+        assert_eq!(lines[1].expected_line(), Some(2));
+        assert_eq!(lines[2].change(), DiffChange::Added);
+        assert_eq!(lines[2].actual_line(), Some(2));
+    }
+}
@@ -0,0 +1,569 @@
+use std::{fmt::Write, time::Duration};
+
+use base64::Engine;
+use chrono::{DateTime, FixedOffset};
+use getset::{CopyGetters, Getters};
+use serde::Deserialize;
+
+use super::{HttpExchange, HttpHeader, HttpRequest, HttpResponse};
+use crate::Evidence;
+
+/// An exchange read from a HAR (HTTP Archive) file, as exported by browser developer tools and
+/// proxies.
+#[derive(Clone, Debug, PartialEq, Getters, CopyGetters)]
+pub struct HarEntry {
+    /// When the request was started, if recorded.
+    #[getset(get_copy = "pub")]
+    started: Option<DateTime<FixedOffset>>,
+    /// How long each phase of the exchange took.
+    #[getset(get_copy = "pub")]
+    timings: HarTimings,
+    /// The request and response.
+    #[getset(get = "pub")]
+    exchange: HttpExchange,
+}
+
+impl HarEntry {
+    /// The full URL the request was sent to.
+    #[must_use]
+    pub fn url(&self) -> &str {
+        self.exchange.request().url()
+    }
+
+    /// Describe this entry and its timings, such as
+    /// `GET https://example.com/ → 200 OK in 120 ms (DNS 4 ms, connect 16 ms, wait 90 ms)`.
+    /// This is used as the caption of evidence created from the entry.
+    #[must_use]
+    pub fn summary(&self) -> String {
+        let request = self.exchange.request();
+        let mut summary = format!("{} {}", request.method(), request.url());
+        match self.exchange.response() {
+            Some(response) => {
+                let status = format!(
+                    "{} {}",
+                    response.status().unwrap_or_default(),
+                    response.reason()
+                );
+                let _ = write!(summary, " → {}", status.trim_end());
+            }
+            None => summary.push_str(" → no response"),
+        }
+        if let Some(duration) = self.exchange.duration() {
+            let _ = write!(summary, " in {} ms", duration.as_millis());
+        }
+        // Phases shorter than a millisecond would only add noise
+        let phases = self
+            .timings
+            .phases()
+            .into_iter()
+            .filter(|(_, duration)| duration.as_millis() > 0)
+            .map(|(name, duration)| format!("{name} {} ms", duration.as_millis()))
+            .collect::<Vec<_>>();
+        if !phases.is_empty() {
+            let _ = write!(summary, " ({})", phases.join(", "));
+        }
+        summary
+    }
+
+    /// Create a new piece of HTTP evidence from this entry, captioned with its summary.
+    #[must_use]
+    pub fn to_evidence(&self) -> Evidence {
+        let mut evidence = self.exchange.to_evidence();
+        evidence.set_caption(Some(self.summary()));
+        evidence
+    }
+}
+
+/// How long each phase of an exchange in a HAR file took. Phases that didn't happen, such as
+/// connecting when an existing connection was reused, aren't recorded.
+#[derive(Copy, Clone, Debug, Default, PartialEq, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct HarTimings {
+    /// Time spent waiting for a connection to become available.
+    blocked: Option<Duration>,
+    /// Time spent resolving the host name.
+    dns: Option<Duration>,
+    /// Time spent creating the connection, including negotiating TLS.
+    connect: Option<Duration>,
+    /// Time spent negotiating TLS.
+    ssl: Option<Duration>,
+    /// Time spent sending the request.
+    send: Option<Duration>,
+    /// Time spent waiting for the first byte of the response.
+    wait: Option<Duration>,
+    /// Time spent receiving the response.
+    receive: Option<Duration>,
+}
+
+impl HarTimings {
+    /// The phases that took some time, with the name of each, in the order they happen.
+    #[must_use]
+    pub fn phases(&self) -> Vec<(&'static str, Duration)> {
+        [
+            ("blocked", self.blocked),
+            ("DNS", self.dns),
+            ("connect", self.connect),
+            ("TLS", self.ssl),
+            ("send", self.send),
+            ("wait", self.wait),
+            ("receive", self.receive),
+        ]
+        .into_iter()
+        .filter_map(|(name, duration)| duration.map(|duration| (name, duration)))
+        .filter(|(_, duration)| !duration.is_zero())
+        .collect()
+    }
+}
+
+/// Read the entries of a HAR (HTTP Archive) file, in the order they were recorded.
+///
+/// Binary bodies, such as images, can't be shown as text so are replaced with a note of their
+/// size and type.
+///
+/// # Errors
+///
+/// - [`Error::InvalidEvidenceData`](crate::Error::InvalidEvidenceData) if the text isn't a HAR
+///   file.
+pub fn parse_har(text: &str) -> crate::Result<Vec<HarEntry>> {
+    let har: Har = serde_json::from_str(text)
+        .map_err(|e| crate::Error::InvalidEvidenceData(format!("invalid HAR file: {e}")))?;
+    Ok(har.log.entries.into_iter().map(HarEntry::from).collect())
+}
+
+/// Convert a time in milliseconds from a HAR file to a duration. Times that weren't recorded are
+/// given as `-1`.
+fn har_duration(ms: Option<f64>) -> Option<Duration> {
+    ms.filter(|ms| *ms >= 0.0)
+        .and_then(|ms| Duration::try_from_secs_f64(ms / 1000.0).ok())
+}
+
+/// Replace line breaks in part of the start line or a header, so that it can't be mistaken for
+/// further headers or the body.
+fn single_line(text: &str) -> String {
+    text.replace(['\r', '\n'], " ")
+}
+
+/// Convert the headers of a HAR message. HTTP/2 pseudo-headers, such as `:authority`, are
+/// already part of the start line so are left out, as are headers with names that couldn't be
+/// sent, and line breaks are removed from values so they can't be mistaken for further headers.
+fn har_headers(headers: Vec<HarHeader>) -> Vec<HttpHeader> {
+    headers
+        .into_iter()
+        .filter(|header| {
+            !header.name.is_empty()
+                && !header
+                    .name
+                    .contains(|c: char| c == ':' || c.is_whitespace() || c.is_control())
+        })
+        .map(|header| HttpHeader::new(header.name, single_line(&header.value)))
+        .collect()
+}
+
+impl From<HarRawEntry> for HarEntry {
+    fn from(entry: HarRawEntry) -> Self {
+        let mut request = HttpRequest::new(
+            single_line(&entry.request.method),
+            single_line(&entry.request.url),
+        );
+        // Without a version, the default is kept so the start line can be read back
+        if !entry.request.http_version.trim().is_empty() {
+            request.set_version(single_line(&entry.request.http_version));
+        }
+        request.set_headers(har_headers(entry.request.headers));
+        if let Some(post_data) = entry.request.post_data {
+            request.set_body(post_data.text.unwrap_or_else(|| {
+                post_data
+                    .params
+                    .iter()
+                    .map(|param| match &param.value {
+                        Some(value) => format!("{}={value}", param.name),
+                        None => param.name.clone(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join("&")
+            }));
+        }
+
+        // Requests that failed or were blocked are recorded with a status of 0
+        let response = entry
+            .response
+            .filter(|response| response.status > 0)
+            .map(|raw| {
+                let mut response = HttpResponse::new(raw.status, single_line(&raw.status_text));
+                if !raw.http_version.trim().is_empty() {
+                    response.set_version(single_line(&raw.http_version));
+                }
+                response.set_headers(har_headers(raw.headers));
+                let content = raw.content;
+                let body = match (content.text, content.encoding.as_deref()) {
+                    (Some(text), Some("base64")) => base64::prelude::BASE64_STANDARD
+                        .decode(text.trim())
+                        .ok()
+                        .and_then(|data| String::from_utf8(data).ok()),
+                    (text, _) => text,
+                };
+                response.set_body(body.unwrap_or_else(|| match content.size {
+                    Some(size) if size > 0 => format!(
+                        "[{size} bytes of {} omitted]",
+                        content.mime_type.as_deref().unwrap_or("binary content")
+                    ),
+                    _ => String::new(),
+                }));
+                response
+            });
+
+        let mut exchange = HttpExchange::new(request, response);
+        exchange.set_duration(har_duration(entry.time));
+        Self {
+            started: entry
+                .started_date_time
+                .and_then(|started| DateTime::parse_from_rfc3339(&started).ok()),
+            timings: entry
+                .timings
+                .map(|timings| HarTimings {
+                    blocked: har_duration(timings.blocked),
+                    dns: har_duration(timings.dns),
+                    connect: har_duration(timings.connect),
+                    ssl: har_duration(timings.ssl),
+                    send: har_duration(timings.send),
+                    wait: har_duration(timings.wait),
+                    receive: har_duration(timings.receive),
+                })
+                .unwrap_or_default(),
+            exchange,
+        }
+    }
+}
+
+/// The top level of a HAR file.
+#[derive(Deserialize)]
+struct Har {
+    /// The log of exchanges.
+    log: HarLog,
+}
+
+/// The log of exchanges in a HAR file.
+#[derive(Deserialize)]
+struct HarLog {
+    /// The exchanges, in the order they were recorded.
+    #[serde(default)]
+    entries: Vec<HarRawEntry>,
+}
+
+/// An exchange as it is stored in a HAR file.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HarRawEntry {
+    /// When the request was started, in ISO 8601 format.
+    started_date_time: Option<String>,
+    /// The total time of the exchange, in milliseconds.
+    time: Option<f64>,
+    /// The request.
+    request: HarRawRequest,
+    /// The response.
+    response: Option<HarRawResponse>,
+    /// The time of each phase of the exchange.
+    timings: Option<HarRawTimings>,
+}
+
+/// A request as it is stored in a HAR file.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HarRawRequest {
+    /// The method of the request.
+    #[serde(default)]
+    method: String,
+    /// The absolute URL of the request.
+    #[serde(default)]
+    url: String,
+    /// The HTTP version of the request.
+    #[serde(default)]
+    http_version: String,
+    /// The headers of the request.
+    #[serde(default)]
+    headers: Vec<HarHeader>,
+    /// The body of the request.
+    post_data: Option<HarPostData>,
+}
+
+/// The body of a request as it is stored in a HAR file.
+#[derive(Deserialize)]
+struct HarPostData {
+    /// The body as text.
+    text: Option<String>,
+    /// The body as form parameters, used if there is no text.
+    #[serde(default)]
+    params: Vec<HarParam>,
+}
+
+/// A form parameter in the body of a request.
+#[derive(Deserialize)]
+struct HarParam {
+    /// The name of the parameter.
+    name: String,
+    /// The value of the parameter.
+    value: Option<String>,
+}
+
+/// A response as it is stored in a HAR file.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HarRawResponse {
+    /// The status code of the response.
+    #[serde(default)]
+    status: u16,
+    /// The reason phrase of the response.
+    #[serde(default)]
+    status_text: String,
+    /// The HTTP version of the response.
+    #[serde(default)]
+    http_version: String,
+    /// The headers of the response.
+    #[serde(default)]
+    headers: Vec<HarHeader>,
+    /// The body of the response.
+    #[serde(default)]
+    content: HarContent,
+}
+
+/// The body of a response as it is stored in a HAR file.
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HarContent {
+    /// The length of the body in bytes.
+    size: Option<i64>,
+    /// The media type of the body.
+    mime_type: Option<String>,
+    /// The body, if it was recorded.
+    text: Option<String>,
+    /// How the body is encoded, which is `base64` for binary bodies.
+    encoding: Option<String>,
+}
+
+/// A header as it is stored in a HAR file.
+#[derive(Deserialize)]
+struct HarHeader {
+    /// The name of the header.
+    name: String,
+    /// The value of the header.
+    #[serde(default)]
+    value: String,
+}
+
+/// The time of each phase of an exchange, in milliseconds, as stored in a HAR file.
+#[derive(Deserialize)]
+struct HarRawTimings {
+    /// Time spent waiting for a connection.
+    blocked: Option<f64>,
+    /// Time spent resolving the host name.
+    dns: Option<f64>,
+    /// Time spent creating the connection.
+    connect: Option<f64>,
+    /// Time spent negotiating TLS.
+    ssl: Option<f64>,
+    /// Time spent sending the request.
+    send: Option<f64>,
+    /// Time spent waiting for the response.
+    wait: Option<f64>,
+    /// Time spent receiving the response.
+    receive: Option<f64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A HAR file with one complete entry.
+    const HAR: &str = r#"{
+        "log": {
+            "version": "1.2",
+            "entries": [{
+                "startedDateTime": "2024-05-01T10:00:00.000Z",
+                "time": 120.4,
+                "request": {
+                    "method": "POST",
+                    "url": "https://example.com/login",
+                    "httpVersion": "HTTP/2",
+                    "headers": [
+                        {"name": ":authority", "value": "example.com"},
+                        {"name": "Content-Type", "value": "application/json"}
+                    ],
+                    "postData": {"mimeType": "application/json", "text": "{\"user\":\"a\"}"}
+                },
+                "response": {
+                    "status": 200,
+                    "statusText": "OK",
+                    "httpVersion": "HTTP/2",
+                    "headers": [{"name": "Content-Type", "value": "text/plain"}],
+                    "content": {"size": 2, "mimeType": "text/plain", "text": "b2s=", "encoding": "base64"}
+                },
+                "timings": {"blocked": -1, "dns": 4.2, "connect": 16, "ssl": 0, "send": 0.1, "wait": 90, "receive": 10}
+            }]
+        }
+    }"#;
+
+    #[test]
+    fn entries_are_parsed() {
+        let entries = parse_har(HAR).unwrap();
+        assert_eq!(entries.len(), 1);
+        let entry = &entries[0];
+        assert_eq!(
+            entry.started(),
+            Some(DateTime::parse_from_rfc3339("2024-05-01T10:00:00Z").unwrap())
+        );
+        assert_eq!(entry.url(), "https://example.com/login");
+
+        let request = entry.exchange().request();
+        assert_eq!(request.method(), "POST");
+        assert_eq!(request.version(), "HTTP/2");
+        assert_eq!(
+            request.headers(),
+            &[HttpHeader::new("Content-Type", "application/json")]
+        );
+        assert_eq!(request.body(), "{\"user\":\"a\"}");
+
+        let response = entry.exchange().response().as_ref().unwrap();
+        assert_eq!(response.status(), Some(200));
+        assert_eq!(response.body(), "ok");
+
+        assert_eq!(entry.timings().blocked(), None);
+        assert_eq!(
+            entry.timings().phases(),
+            [
+                ("DNS", Duration::from_secs_f64(0.0042)),
+                ("connect", Duration::from_millis(16)),
+                ("send", Duration::from_secs_f64(0.0001)),
+                ("wait", Duration::from_millis(90)),
+                ("receive", Duration::from_millis(10)),
+            ]
+        );
+        assert_eq!(
+            entry.summary(),
+            "POST https://example.com/login → 200 OK in 120 ms \
+             (DNS 4 ms, connect 16 ms, wait 90 ms, receive 10 ms)"
+        );
+        assert_eq!(
+            entry.to_evidence().caption().as_deref(),
+            Some(entry.summary().as_str())
+        );
+    }
+
+    #[test]
+    fn empty_logs_have_no_entries() {
+        assert!(parse_har(r#"{"log": {}}"#).unwrap().is_empty());
+        assert!(parse_har(r#"{"log": {"entries": []}}"#).unwrap().is_empty());
+    }
+
+    #[test]
+    fn invalid_files_are_errors() {
+        for text in ["", "{", "[]", "{}", r#"{"log": {"entries": [{}]}}"#] {
+            assert!(
+                matches!(parse_har(text), Err(crate::Error::InvalidEvidenceData(_))),
+                "{text:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn minimal_entries_are_parsed() {
+        let entries = parse_har(r#"{"log": {"entries": [{"request": {}}]}}"#).unwrap();
+        let entry = &entries[0];
+        assert_eq!(entry.started(), None);
+        assert_eq!(entry.timings(), HarTimings::default());
+        assert_eq!(entry.exchange().response(), &None);
+        assert_eq!(entry.summary(), "  → no response");
+    }
+
+    #[test]
+    fn failed_requests_have_no_response() {
+        let entries = parse_har(
+            r#"{"log": {"entries": [{"request": {"method": "GET", "url": "https://a/"},
+                "response": {"status": 0, "statusText": ""}}]}}"#,
+        )
+        .unwrap();
+        assert_eq!(entries[0].exchange().response(), &None);
+    }
+
+    #[test]
+    fn form_parameters_become_the_body() {
+        let entries = parse_har(
+            r#"{"log": {"entries": [{"request": {"method": "POST", "url": "https://a/",
+                "postData": {"params": [{"name": "a", "value": "1"}, {"name": "flag"}]}}}]}}"#,
+        )
+        .unwrap();
+        assert_eq!(entries[0].exchange().request().body(), "a=1&flag");
+    }
+
+    #[test]
+    fn binary_bodies_are_omitted() {
+        let entries = parse_har(
+            r#"{"log": {"entries": [
+                {"request": {}, "response": {"status": 200,
+                    "content": {"size": 4, "mimeType": "image/png", "text": "/w==", "encoding": "base64"}}},
+                {"request": {}, "response": {"status": 200,
+                    "content": {"size": 4, "text": "not base64!", "encoding": "base64"}}},
+                {"request": {}, "response": {"status": 204, "content": {"size": 0}}}
+            ]}}"#,
+        )
+        .unwrap();
+        let bodies: Vec<_> = entries
+            .iter()
+            .map(|entry| entry.exchange().response().as_ref().unwrap().body().clone())
+            .collect();
+        assert_eq!(
+            bodies,
+            [
+                "[4 bytes of image/png omitted]",
+                "[4 bytes of binary content omitted]",
+                "",
+            ]
+        );
+    }
+
+    #[test]
+    fn line_breaks_cannot_inject_headers() {
+        let entries = parse_har(
+            r#"{"log": {"entries": [{
+                "request": {"method": "GET", "url": "https://a/\r\nX-Injected: 1",
+                    "headers": [
+                        {"name": "X-Value", "value": "a\r\nX-Injected: 2"},
+                        {"name": "X-Name\r\nX-Injected", "value": "3"},
+                        {"name": "Bad Name", "value": "4"},
+                        {"name": "", "value": "5"}
+                    ]},
+                "response": {"status": 200, "statusText": "OK\r\nX-Injected: 6"}
+            }]}}"#,
+        )
+        .unwrap();
+        let exchange = HttpExchange::parse(&entries[0].exchange().to_string());
+        assert_eq!(exchange.request().header("X-Injected"), None);
+        assert_eq!(
+            exchange.request().headers(),
+            &[HttpHeader::new("X-Value", "a  X-Injected: 2")]
+        );
+        let response = exchange.response().as_ref().unwrap();
+        assert_eq!(response.header("X-Injected"), None);
+        assert_eq!(response.reason(), "OK  X-Injected: 6");
+    }
+
+    #[test]
+    fn unusual_times_are_ignored() {
+        assert_eq!(har_duration(None), None);
+        assert_eq!(har_duration(Some(-1.0)), None);
+        assert_eq!(har_duration(Some(f64::NAN)), None);
+        assert_eq!(har_duration(Some(f64::INFINITY)), None);
+        assert_eq!(har_duration(Some(1e300)), None);
+        assert_eq!(
+            har_duration(Some(1500.0)),
+            Some(Duration::from_millis(1500))
+        );
+
+        let entries = parse_har(
+            r#"{"log": {"entries": [{"request": {}, "time": 1e300,
+                "timings": {"wait": 1e300, "receive": -5}}]}}"#,
+        )
+        .unwrap();
+        assert_eq!(entries[0].exchange().duration(), None);
+        assert!(entries[0].timings().phases().is_empty());
+    }
+}
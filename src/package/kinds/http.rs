@@ -0,0 +1,784 @@
+use std::{fmt, time::Duration};
+
+use getset::{CopyGetters, Getters, MutGetters, Setters};
+use serde::{Deserialize, Serialize};
+
+use crate::{Evidence, EvidenceData, EvidenceKind, EvidencePackage};
+
+/// The character separating the request, response and details of an exchange.
+pub const HTTP_SEPARATOR: char = '\x1e';
+
+/// An HTTP request and its response, as stored in [`EvidenceKind::Http`] evidence.
+///
+/// The evidence stores the request and response as they would be sent, separated by
+/// [`HTTP_SEPARATOR`], optionally followed by another separator and a JSON object of details
+/// such as how long the exchange took.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Getters, MutGetters, Setters)]
+#[getset(get = "pub", get_mut = "pub", set = "pub")]
+pub struct HttpExchange {
+    /// The request that was sent.
+    request: HttpRequest,
+    /// The response that was received, if there was one.
+    response: Option<HttpResponse>,
+    /// How long the exchange took, in milliseconds.
+    #[getset(skip)]
+    duration_ms: Option<u64>,
+}
+
+/// Details of an exchange that don't form part of the request or response.
+#[derive(Default, Serialize, Deserialize)]
+struct HttpDetails {
+    /// How long the exchange took, in milliseconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    duration_ms: Option<u64>,
+}
+
+impl HttpExchange {
+    /// Create a new exchange from a request and its response.
+    #[must_use]
+    pub fn new(request: HttpRequest, response: Option<HttpResponse>) -> Self {
+        Self {
+            request,
+            response,
+            duration_ms: None,
+        }
+    }
+
+    /// Parse an exchange from the format it is stored in. This never fails, as evidence may have
+    /// been entered by hand, so anything that can't be understood is kept in the body.
+    #[must_use]
+    pub fn parse(data: &str) -> Self {
+        let mut sections = data.splitn(3, HTTP_SEPARATOR);
+        let request = HttpRequest::parse(sections.next().unwrap_or_default());
+        let response = sections
+            .next()
+            .filter(|response| !response.trim().is_empty())
+            .map(HttpResponse::parse);
+        let details: HttpDetails = sections
+            .next()
+            .and_then(|details| serde_json::from_str(details).ok())
+            .unwrap_or_default();
+        Self {
+            request,
+            response,
+            duration_ms: details.duration_ms,
+        }
+    }
+
+    /// Read an exchange from a piece of evidence.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::InvalidEvidenceData`](crate::Error::InvalidEvidenceData) if the evidence isn't
+    ///   HTTP evidence.
+    /// - [`Error::MediaMissing`](crate::Error::MediaMissing) if the media referred to by the
+    ///   evidence is missing from the package.
+    pub fn from_evidence(
+        evidence: &Evidence,
+        package: &mut EvidencePackage,
+    ) -> crate::Result<Self> {
+        if *evidence.kind() != EvidenceKind::Http {
+            return Err(crate::Error::InvalidEvidenceData(format!(
+                "expected {:?} evidence, found {:?}",
+                EvidenceKind::Http,
+                evidence.kind()
+            )));
+        }
+        let data = evidence.value().get_data(package)?;
+        Ok(Self::parse(&String::from_utf8_lossy(&data)))
+    }
+
+    /// Create a new piece of evidence containing this exchange.
+    #[must_use]
+    pub fn to_evidence(&self) -> Evidence {
+        Evidence::new(
+            EvidenceKind::Http,
+            EvidenceData::Base64 {
+                data: self.to_string().into_bytes(),
+            },
+        )
+    }
+
+    /// How long the exchange took, if known.
+    #[must_use]
+    pub fn duration(&self) -> Option<Duration> {
+        self.duration_ms.map(Duration::from_millis)
+    }
+
+    /// Set how long the exchange took. This is stored to the nearest millisecond.
+    pub fn set_duration(&mut self, duration: Option<Duration>) -> &mut Self {
+        self.duration_ms = duration.map(|duration| duration.as_millis() as u64);
+        self
+    }
+}
+
+impl fmt::Display for HttpExchange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{HTTP_SEPARATOR}", self.request)?;
+        if let Some(response) = &self.response {
+            write!(f, "{response}")?;
+        }
+        if self.duration_ms.is_some() {
+            let details = HttpDetails {
+                duration_ms: self.duration_ms,
+            };
+            write!(
+                f,
+                "{HTTP_SEPARATOR}{}",
+                serde_json::to_string(&details).map_err(|_| fmt::Error)?
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// An HTTP request.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Getters, MutGetters, Setters)]
+#[getset(get = "pub", get_mut = "pub", set = "pub")]
+pub struct HttpRequest {
+    /// The method of the request, such as `GET`.
+    method: String,
+    /// The URL the request was sent to. This is usually a path, with the host in the `Host`
+    /// header, but may be an absolute URL.
+    url: String,
+    /// The HTTP version of the request, such as `HTTP/1.1`.
+    version: String,
+    /// The headers of the request, in the order they were sent.
+    headers: Vec<HttpHeader>,
+    /// The body of the request.
+    body: String,
+}
+
+impl HttpRequest {
+    /// Create a new request with no headers or body.
+    #[must_use]
+    pub fn new<M: Into<String>, U: Into<String>>(method: M, url: U) -> Self {
+        Self {
+            method: method.into(),
+            url: url.into(),
+            version: "HTTP/1.1".to_string(),
+            headers: vec![],
+            body: String::new(),
+        }
+    }
+
+    /// Parse a request from its text. This never fails, as anything that can't be understood is
+    /// kept in the body.
+    #[must_use]
+    pub fn parse(text: &str) -> Self {
+        let (start_line, headers, body) = parse_message(text);
+        let mut parts = start_line.split_whitespace();
+        Self {
+            method: parts.next().unwrap_or_default().to_string(),
+            url: parts.next().unwrap_or_default().to_string(),
+            version: parts.collect::<Vec<_>>().join(" "),
+            headers,
+            body,
+        }
+    }
+
+    /// Get the first value of a header, ignoring the case of its name.
+    #[must_use]
+    pub fn header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+
+    /// Get the start line of this request, such as `GET / HTTP/1.1`.
+    #[must_use]
+    pub fn start_line(&self) -> String {
+        [&self.method, &self.url, &self.version]
+            .into_iter()
+            .filter(|part| !part.is_empty())
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Determine the format of the body, from the `Content-Type` header or its content.
+    #[must_use]
+    pub fn body_format(&self) -> HttpBodyFormat {
+        HttpBodyFormat::detect(self.header("Content-Type"), &self.body)
+    }
+
+    /// Get the body, reformatted to be easier to read if it is JSON or XML.
+    #[must_use]
+    pub fn pretty_body(&self) -> String {
+        self.body_format().pretty(&self.body)
+    }
+}
+
+impl fmt::Display for HttpRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_message(f, &self.start_line(), &self.headers, &self.body)
+    }
+}
+
+/// An HTTP response.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Getters, CopyGetters, MutGetters, Setters)]
+pub struct HttpResponse {
+    /// The HTTP version of the response, such as `HTTP/1.1`.
+    #[getset(get = "pub", set = "pub")]
+    version: String,
+    /// The status code of the response, if it could be read.
+    #[getset(get_copy = "pub", set = "pub")]
+    status: Option<u16>,
+    /// The reason phrase following the status code, such as `OK`.
+    #[getset(get = "pub", set = "pub")]
+    reason: String,
+    /// The headers of the response, in the order they were received.
+    #[getset(get = "pub", get_mut = "pub", set = "pub")]
+    headers: Vec<HttpHeader>,
+    /// The body of the response.
+    #[getset(get = "pub", set = "pub")]
+    body: String,
+}
+
+impl HttpResponse {
+    /// Create a new response with a status code and no headers or body.
+    #[must_use]
+    pub fn new<R: Into<String>>(status: u16, reason: R) -> Self {
+        Self {
+            version: "HTTP/1.1".to_string(),
+            status: Some(status),
+            reason: reason.into(),
+            headers: vec![],
+            body: String::new(),
+        }
+    }
+
+    /// Parse a response from its text. This never fails, as anything that can't be understood is
+    /// kept in the body.
+    #[must_use]
+    pub fn parse(text: &str) -> Self {
+        let (start_line, headers, body) = parse_message(text);
+        let mut parts = start_line.splitn(3, ' ');
+        let version = parts.next().unwrap_or_default().to_string();
+        let status = parts.next().unwrap_or_default();
+        let reason = parts.next().unwrap_or_default().trim().to_string();
+        match status.parse() {
+            Ok(status) => Self {
+                version,
+                status: Some(status),
+                reason,
+                headers,
+                body,
+            },
+            // Without a status the start line isn't understood, so keep it as the reason
+            Err(_) => Self {
+                version: String::new(),
+                status: None,
+                reason: start_line.trim().to_string(),
+                headers,
+                body,
+            },
+        }
+    }
+
+    /// Get the first value of a header, ignoring the case of its name.
+    #[must_use]
+    pub fn header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+
+    /// Get the start line of this response, such as `HTTP/1.1 200 OK`.
+    #[must_use]
+    pub fn start_line(&self) -> String {
+        let status = self.status.map(|status| status.to_string());
+        [
+            Some(self.version.as_str()),
+            status.as_deref(),
+            Some(self.reason.as_str()),
+        ]
+        .into_iter()
+        .flatten()
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+    }
+
+    /// Determine if the status code indicates success, which is in the range 200 to 399.
+    #[must_use]
+    pub fn is_success(&self) -> bool {
+        self.status
+            .is_some_and(|status| (200..400).contains(&status))
+    }
+
+    /// Determine the format of the body, from the `Content-Type` header or its content.
+    #[must_use]
+    pub fn body_format(&self) -> HttpBodyFormat {
+        HttpBodyFormat::detect(self.header("Content-Type"), &self.body)
+    }
+
+    /// Get the body, reformatted to be easier to read if it is JSON or XML.
+    #[must_use]
+    pub fn pretty_body(&self) -> String {
+        self.body_format().pretty(&self.body)
+    }
+}
+
+impl fmt::Display for HttpResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_message(f, &self.start_line(), &self.headers, &self.body)
+    }
+}
+
+/// A header of an HTTP request or response.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Getters, Setters)]
+#[getset(get = "pub", set = "pub")]
+pub struct HttpHeader {
+    /// The name of the header.
+    name: String,
+    /// The value of the header.
+    value: String,
+}
+
+impl HttpHeader {
+    /// Create a new header.
+    #[must_use]
+    pub fn new<N: Into<String>, V: Into<String>>(name: N, value: V) -> Self {
+        Self {
+            name: name.into(),
+            value: value.into(),
+        }
+    }
+}
+
+/// The format of the body of an HTTP request or response.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum HttpBodyFormat {
+    /// A JSON document.
+    Json,
+    /// An XML document.
+    Xml,
+    /// Any other content, which is shown as it is.
+    Text,
+}
+
+impl HttpBodyFormat {
+    /// Detect the format of a body from its `Content-Type` header, or from its content if the
+    /// header is missing or isn't specific.
+    #[must_use]
+    pub fn detect(content_type: Option<&str>, body: &str) -> Self {
+        let content_type = content_type.unwrap_or_default().to_ascii_lowercase();
+        let media_type = content_type.split(';').next().unwrap_or_default().trim();
+        if media_type.ends_with("json") {
+            return Self::Json;
+        }
+        if media_type.ends_with("xml") {
+            return Self::Xml;
+        }
+
+        let trimmed = body.trim_start();
+        if (trimmed.starts_with('{') || trimmed.starts_with('['))
+            && serde_json::from_str::<serde::de::IgnoredAny>(body).is_ok()
+        {
+            Self::Json
+        } else if trimmed.starts_with("<?xml") {
+            Self::Xml
+        } else {
+            Self::Text
+        }
+    }
+
+    /// Reformat a body of this format to be easier to read. If it can't be reformatted, it is
+    /// returned unchanged.
+    #[must_use]
+    pub fn pretty(self, body: &str) -> String {
+        match self {
+            Self::Json if serde_json::from_str::<serde::de::IgnoredAny>(body).is_ok() => {
+                pretty_json(body)
+            }
+            Self::Xml => pretty_xml(body).unwrap_or_else(|| body.to_string()),
+            Self::Json | Self::Text => body.to_string(),
+        }
+    }
+}
+
+/// Find the first value of a header, ignoring the case of its name.
+fn find_header<'a>(headers: &'a [HttpHeader], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|header| header.name.eq_ignore_ascii_case(name))
+        .map(|header| header.value.as_str())
+}
+
+/// Split the text of a request or response into its start line, headers and body. Headers end at
+/// the first blank line, or at the first line that isn't a header, which begins the body.
+fn parse_message(text: &str) -> (String, Vec<HttpHeader>, String) {
+    let text = text.trim_start_matches(['\r', '\n']);
+    let mut lines = text.split_inclusive('\n');
+    let mut offset = 0;
+    let start_line = lines.next().unwrap_or_default();
+    offset += start_line.len();
+
+    let mut headers = vec![];
+    for line in lines {
+        let content = line.trim_end_matches(['\r', '\n']);
+        if content.is_empty() {
+            offset += line.len();
+            break;
+        }
+        let Some((name, value)) = content.split_once(':') else {
+            break;
+        };
+        if name.is_empty() || name.contains(char::is_whitespace) {
+            break;
+        }
+        headers.push(HttpHeader::new(name, value.trim()));
+        offset += line.len();
+    }
+
+    (
+        start_line.trim_end_matches(['\r', '\n']).to_string(),
+        headers,
+        text[offset..].to_string(),
+    )
+}
+
+/// Write a request or response in the format it would be sent in.
+fn write_message(
+    f: &mut fmt::Formatter<'_>,
+    start_line: &str,
+    headers: &[HttpHeader],
+    body: &str,
+) -> fmt::Result {
+    if start_line.is_empty() && headers.is_empty() && body.is_empty() {
+        return Ok(());
+    }
+    write!(f, "{start_line}\r\n")?;
+    for header in headers {
+        write!(f, "{}: {}\r\n", header.name, header.value)?;
+    }
+    write!(f, "\r\n{body}")
+}
+
+/// Reformat a JSON document with one value per line. Unlike reserializing it, this keeps the
+/// order of keys and the exact text of every value.
+fn pretty_json(json: &str) -> String {
+    /// The text used for each level of indentation
+    const INDENT: &str = "  ";
+
+    let mut output = String::with_capacity(json.len() * 2);
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut chars = json.trim().chars().peekable();
+    let newline = |output: &mut String, depth: usize| {
+        output.push('\n');
+        output.push_str(&INDENT.repeat(depth));
+    };
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            output.push(c);
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+        match c {
+            '"' => {
+                in_string = true;
+                output.push(c);
+            }
+            '{' | '[' => {
+                output.push(c);
+                // Keep empty objects and arrays on one line
+                while chars.peek().is_some_and(|c| c.is_whitespace()) {
+                    chars.next();
+                }
+                if chars.peek().is_some_and(|next| matches!(next, '}' | ']')) {
+                    output.push(chars.next().unwrap_or_default());
+                } else {
+                    depth += 1;
+                    newline(&mut output, depth);
+                }
+            }
+            '}' | ']' => {
+                depth = depth.saturating_sub(1);
+                newline(&mut output, depth);
+                output.push(c);
+            }
+            ',' => {
+                output.push(c);
+                newline(&mut output, depth);
+            }
+            ':' => output.push_str(": "),
+            c if c.is_whitespace() => (),
+            c => output.push(c),
+        }
+    }
+    output
+}
+
+/// Reformat an XML document with one element per line, indented by its depth. Elements that only
+/// contain text are kept on one line. Returns `None` if the document isn't well formed enough to
+/// be reformatted.
+fn pretty_xml(xml: &str) -> Option<String> {
+    /// The text used for each level of indentation
+    const INDENT: &str = "  ";
+
+    /// A piece of an XML document
+    enum Piece<'a> {
+        /// An opening tag
+        Open(&'a str),
+        /// A closing tag
+        Close(&'a str),
+        /// A tag that doesn't change the depth, such as a self-closing tag or a comment
+        Single(&'a str),
+        /// Text between tags
+        Text(&'a str),
+    }
+
+    let mut pieces = vec![];
+    let mut rest = xml.trim();
+    while !rest.is_empty() {
+        if rest.starts_with('<') {
+            let end = if rest.starts_with("<!--") {
+                rest.find("-->")? + 3
+            } else if rest.starts_with("<![CDATA[") {
+                rest.find("]]>")? + 3
+            } else {
+                rest.find('>')? + 1
+            };
+            let tag = &rest[..end];
+            pieces.push(if tag.starts_with("</") {
+                Piece::Close(tag)
+            } else if tag.ends_with("/>") || tag.starts_with("<?") || tag.starts_with("<!") {
+                Piece::Single(tag)
+            } else {
+                Piece::Open(tag)
+            });
+            rest = &rest[end..];
+        } else {
+            let end = rest.find('<').unwrap_or(rest.len());
+            let text = rest[..end].trim();
+            if !text.is_empty() {
+                pieces.push(Piece::Text(text));
+            }
+            rest = &rest[end..];
+        }
+    }
+
+    let mut lines: Vec<String> = vec![];
+    let mut depth = 0usize;
+    let mut index = 0;
+    while index < pieces.len() {
+        let indent = INDENT.repeat(depth);
+        match (&pieces[index], pieces.get(index + 1), pieces.get(index + 2)) {
+            // Keep elements that only contain text on one line
+            (Piece::Open(open), Some(Piece::Text(text)), Some(Piece::Close(close))) => {
+                lines.push(format!("{indent}{open}{text}{close}"));
+                index += 3;
+                continue;
+            }
+            (Piece::Open(open), _, _) => {
+                lines.push(format!("{indent}{open}"));
+                depth += 1;
+            }
+            (Piece::Close(close), _, _) => {
+                depth = depth.checked_sub(1)?;
+                lines.push(format!("{}{close}", INDENT.repeat(depth)));
+            }
+            (Piece::Single(text) | Piece::Text(text), _, _) => {
+                lines.push(format!("{indent}{text}"));
+            }
+        }
+        index += 1;
+    }
+    Some(lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_message_has_nothing() {
+        let (start_line, headers, body) = parse_message("");
+        assert_eq!(start_line, "");
+        assert!(headers.is_empty());
+        assert_eq!(body, "");
+
+        let request = HttpRequest::parse("");
+        assert_eq!(request, HttpRequest::default());
+        assert_eq!(request.to_string(), "");
+    }
+
+    #[test]
+    fn message_is_split() {
+        let (start_line, headers, body) =
+            parse_message("POST /login HTTP/1.1\r\nHost: example.com\r\nX-Empty:\r\n\r\na=1\r\n");
+        assert_eq!(start_line, "POST /login HTTP/1.1");
+        assert_eq!(
+            headers,
+            [
+                HttpHeader::new("Host", "example.com"),
+                HttpHeader::new("X-Empty", ""),
+            ]
+        );
+        assert_eq!(body, "a=1\r\n");
+    }
+
+    #[test]
+    fn lf_line_endings_are_accepted() {
+        let (start_line, headers, body) = parse_message("\n\nGET / HTTP/1.1\nAccept: */*\n\nbody");
+        assert_eq!(start_line, "GET / HTTP/1.1");
+        assert_eq!(headers, [HttpHeader::new("Accept", "*/*")]);
+        assert_eq!(body, "body");
+    }
+
+    #[test]
+    fn headers_without_body_are_parsed() {
+        for text in [
+            "HTTP/1.1 204 No Content\r\nContent-Length: 0",
+            "HTTP/1.1 204 No Content\r\nContent-Length: 0\r\n",
+            "HTTP/1.1 204 No Content\r\nContent-Length: 0\r\n\r\n",
+        ] {
+            let response = HttpResponse::parse(text);
+            assert_eq!(response.status(), Some(204));
+            assert_eq!(response.reason(), "No Content");
+            assert_eq!(
+                response.headers(),
+                &[HttpHeader::new("Content-Length", "0")]
+            );
+            assert_eq!(response.body(), "", "{text:?}");
+        }
+    }
+
+    #[test]
+    fn start_line_only_is_parsed() {
+        let request = HttpRequest::parse("DELETE /item/1");
+        assert_eq!(request.method(), "DELETE");
+        assert_eq!(request.url(), "/item/1");
+        assert_eq!(request.version(), "");
+        assert!(request.headers().is_empty());
+        assert_eq!(request.start_line(), "DELETE /item/1");
+    }
+
+    #[test]
+    fn malformed_headers_begin_the_body() {
+        let (_, headers, body) =
+            parse_message("GET / HTTP/1.1\r\nHost: a\r\nnot a header\r\nX-After: b\r\n");
+        assert_eq!(headers, [HttpHeader::new("Host", "a")]);
+        assert_eq!(body, "not a header\r\nX-After: b\r\n");
+
+        for line in [": no name", "Bad Name: value", " Folded: value"] {
+            let (_, headers, body) = parse_message(&format!("GET / HTTP/1.1\r\n{line}"));
+            assert!(headers.is_empty(), "{line:?}");
+            assert_eq!(body, line);
+        }
+    }
+
+    #[test]
+    fn responses_without_status_keep_start_line() {
+        let response = HttpResponse::parse("garbage here\r\nX: y\r\n\r\nbody");
+        assert_eq!(response.status(), None);
+        assert_eq!(response.reason(), "garbage here");
+        assert_eq!(response.header("x"), Some("y"));
+        assert_eq!(response.body(), "body");
+        assert!(!response.is_success());
+
+        let response = HttpResponse::parse("HTTP/1.1 99999 Too Big");
+        assert_eq!(response.status(), None);
+        assert_eq!(response.start_line(), "HTTP/1.1 99999 Too Big");
+    }
+
+    #[test]
+    fn headers_are_found_ignoring_case() {
+        let request =
+            HttpRequest::parse("GET / HTTP/1.1\r\nContent-Type: a\r\ncontent-type: b\r\n");
+        assert_eq!(request.header("CONTENT-TYPE"), Some("a"));
+        assert_eq!(request.header("Accept"), None);
+    }
+
+    #[test]
+    fn exchanges_round_trip() {
+        let mut request = HttpRequest::new("POST", "/api");
+        request.set_headers(vec![HttpHeader::new("Content-Type", "application/json")]);
+        request.set_body("{\"a\":1}".to_string());
+        let mut response = HttpResponse::new(201, "Created");
+        response.set_body("ok".to_string());
+        let mut exchange = HttpExchange::new(request, Some(response));
+        exchange.set_duration(Some(Duration::from_millis(250)));
+
+        let parsed = HttpExchange::parse(&exchange.to_string());
+        assert_eq!(parsed, exchange);
+        assert_eq!(parsed.duration(), Some(Duration::from_millis(250)));
+        assert!(parsed.response().as_ref().unwrap().is_success());
+    }
+
+    #[test]
+    fn exchanges_without_response_or_details_are_parsed() {
+        let exchange = HttpExchange::parse("GET / HTTP/1.1\r\n\r\n");
+        assert_eq!(exchange.request().method(), "GET");
+        assert_eq!(exchange.response(), &None);
+        assert_eq!(exchange.duration(), None);
+
+        let exchange = HttpExchange::parse("GET /\x1e \r\n\x1e{not json");
+        assert_eq!(exchange.response(), &None);
+        assert_eq!(exchange.duration(), None);
+
+        let exchange = HttpExchange::parse("\x1e\x1e\x1e\x1e");
+        assert_eq!(exchange.request(), &HttpRequest::default());
+    }
+
+    #[test]
+    fn body_formats_are_detected() {
+        assert_eq!(
+            HttpBodyFormat::detect(Some("application/problem+json; charset=utf-8"), ""),
+            HttpBodyFormat::Json
+        );
+        assert_eq!(
+            HttpBodyFormat::detect(Some("TEXT/XML"), ""),
+            HttpBodyFormat::Xml
+        );
+        assert_eq!(
+            HttpBodyFormat::detect(None, " [1, 2]"),
+            HttpBodyFormat::Json
+        );
+        assert_eq!(
+            HttpBodyFormat::detect(None, "{broken"),
+            HttpBodyFormat::Text
+        );
+        assert_eq!(
+            HttpBodyFormat::detect(None, "<?xml?><a/>"),
+            HttpBodyFormat::Xml
+        );
+        assert_eq!(HttpBodyFormat::detect(None, ""), HttpBodyFormat::Text);
+    }
+
+    #[test]
+    fn json_is_reformatted() {
+        assert_eq!(
+            HttpBodyFormat::Json.pretty(r#"{"b":[1,{}],"a":"x,y:\"z\"","c":[ ]}"#),
+            "{\n  \"b\": [\n    1,\n    {}\n  ],\n  \"a\": \"x,y:\\\"z\\\"\",\n  \"c\": []\n}"
+        );
+        assert_eq!(HttpBodyFormat::Json.pretty("{broken"), "{broken");
+    }
+
+    #[test]
+    fn xml_is_reformatted() {
+        assert_eq!(
+            HttpBodyFormat::Xml.pretty("<?xml version=\"1.0\"?><a><b>text</b><!-- c --><d/></a>"),
+            "<?xml version=\"1.0\"?>\n<a>\n  <b>text</b>\n  <!-- c -->\n  <d/>\n</a>"
+        );
+    }
+
+    #[test]
+    fn malformed_xml_is_unchanged() {
+        for xml in [
+            "<a",
+            "<a></b></c>",
+            "<!-- unterminated",
+            "<![CDATA[ x",
+            "</a>",
+        ] {
+            assert_eq!(HttpBodyFormat::Xml.pretty(xml), xml);
+        }
+    }
+}
@@ -0,0 +1,250 @@
+use std::{fmt, str::FromStr};
+
+use getset::{CopyGetters, Getters, Setters};
+use serde::{Deserialize, Serialize};
+
+use super::StructuredEvidence;
+use crate::EvidenceKind;
+
+/// Evidence referring to something outside of the package, such as a defect, ticket, build
+/// pipeline or page of documentation.
+#[derive(
+    Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, Getters, CopyGetters, Setters,
+)]
+pub struct Link {
+    /// The URL being referred to.
+    #[getset(get = "pub")]
+    url: String,
+    /// A title describing what is being referred to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub", set = "pub")]
+    title: Option<String>,
+    /// The type of system being referred to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get_copy = "pub", set = "pub")]
+    system: Option<LinkSystem>,
+}
+
+impl StructuredEvidence for Link {
+    const KIND: EvidenceKind = EvidenceKind::Link;
+}
+
+impl Link {
+    /// Create a new link to a URL.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::InvalidEvidenceData`](crate::Error::InvalidEvidenceData) if the URL isn't
+    ///   absolute, such as `https://example.com/`, or is one that runs a script or embeds data.
+    pub fn new<S: Into<String>>(url: S) -> crate::Result<Self> {
+        let url = url.into().trim().to_string();
+        if !is_absolute_url(&url) {
+            return Err(crate::Error::InvalidEvidenceData(format!(
+                "{url:?} isn't an absolute URL"
+            )));
+        }
+        let link = Self {
+            url,
+            title: None,
+            system: None,
+        };
+        if !link.is_safe_to_open() {
+            return Err(crate::Error::InvalidEvidenceData(format!(
+                "{:?} links can't be opened safely",
+                link.scheme()
+            )));
+        }
+        Ok(link)
+    }
+
+    /// Get the scheme of the URL, such as `https`, in lowercase.
+    #[must_use]
+    pub fn scheme(&self) -> String {
+        self.url
+            .split_once(':')
+            .map(|(scheme, _)| scheme.trim().to_ascii_lowercase())
+            .unwrap_or_default()
+    }
+
+    /// Determine if this link can be safely opened or rendered as a hyperlink. Links that could
+    /// run a script or embed data aren't, and as links can be read from untrusted packages,
+    /// they should be displayed as text instead.
+    #[must_use]
+    pub fn is_safe_to_open(&self) -> bool {
+        is_absolute_url(&self.url)
+            && !matches!(self.scheme().as_str(), "javascript" | "vbscript" | "data")
+    }
+
+    /// Get the text to show for this link, which is its title if it has one, or its URL
+    /// otherwise.
+    #[must_use]
+    pub fn display_title(&self) -> &str {
+        self.title
+            .as_deref()
+            .filter(|title| !title.trim().is_empty())
+            .unwrap_or(&self.url)
+    }
+}
+
+/// The type of system a [`Link`] refers to.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LinkSystem {
+    /// A defect or bug tracker.
+    Defect,
+    /// A ticket, story or task tracker.
+    Ticket,
+    /// A build or deployment pipeline.
+    Build,
+    /// Documentation, such as a specification or wiki page.
+    Documentation,
+    /// Any other system.
+    Other,
+}
+
+impl LinkSystem {
+    /// All types of system, in the order they should be listed.
+    pub const ALL: [LinkSystem; 5] = [
+        Self::Defect,
+        Self::Ticket,
+        Self::Build,
+        Self::Documentation,
+        Self::Other,
+    ];
+}
+
+impl fmt::Display for LinkSystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Defect => "Defect",
+                Self::Ticket => "Ticket",
+                Self::Build => "Build",
+                Self::Documentation => "Documentation",
+                Self::Other => "Other",
+            }
+        )
+    }
+}
+
+impl FromStr for LinkSystem {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "defect" | "bug" => Ok(Self::Defect),
+            "ticket" | "issue" | "story" | "task" => Ok(Self::Ticket),
+            "build" | "pipeline" => Ok(Self::Build),
+            "documentation" | "docs" | "doc" => Ok(Self::Documentation),
+            "other" => Ok(Self::Other),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Determine if some text is an absolute URL, made up of a scheme followed by a colon and the
+/// rest of the URL, with no whitespace.
+fn is_absolute_url(url: &str) -> bool {
+    let Some((scheme, rest)) = url.split_once(':') else {
+        return false;
+    };
+    let mut scheme_chars = scheme.chars();
+    scheme_chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && scheme_chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        && !rest.is_empty()
+        && !url.chars().any(char::is_whitespace)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn absolute_urls_are_accepted() {
+        for url in [
+            "https://example.com/",
+            "  http://example.com/path?q=1  ",
+            "https://example.com/\r\n",
+            "mailto:someone@example.com",
+            "jira+ssh://host/PROJ-1",
+            "file:///tmp/report.html",
+        ] {
+            let link = Link::new(url).unwrap();
+            assert_eq!(link.url(), url.trim());
+            assert!(link.is_safe_to_open());
+        }
+        assert_eq!(Link::new("HTTPS://example.com").unwrap().scheme(), "https");
+    }
+
+    #[test]
+    fn invalid_urls_are_rejected() {
+        for url in [
+            "",
+            "   ",
+            "example.com",
+            "/relative/path",
+            "https:",
+            ":no-scheme",
+            "1http://example.com",
+            "ht tp://example.com",
+            "https://example.com/a b",
+            "https://example.com/\r\npath",
+            "é://example.com",
+        ] {
+            assert!(
+                matches!(Link::new(url), Err(crate::Error::InvalidEvidenceData(_))),
+                "{url:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn unsafe_urls_are_rejected() {
+        for url in [
+            "javascript:alert(1)",
+            "JavaScript:alert(1)",
+            "vbscript:msgbox",
+            "data:text/html,<script>alert(1)</script>",
+        ] {
+            assert!(
+                matches!(Link::new(url), Err(crate::Error::InvalidEvidenceData(_))),
+                "{url:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn unsafe_deserialized_links_are_not_opened() {
+        for url in [
+            "javascript:alert(1)",
+            "java\tscript:alert(1)",
+            "\u{1}javascript:alert(1)",
+            "no scheme",
+            "",
+        ] {
+            let link: Link = serde_json::from_value(serde_json::json!({ "url": url })).unwrap();
+            assert!(!link.is_safe_to_open(), "{url:?}");
+        }
+    }
+
+    #[test]
+    fn display_title_falls_back_to_url() {
+        let mut link = Link::new("https://example.com/").unwrap();
+        assert_eq!(link.display_title(), "https://example.com/");
+        link.set_title(Some("  ".to_string()));
+        assert_eq!(link.display_title(), "https://example.com/");
+        link.set_title(Some("Example".to_string()));
+        assert_eq!(link.display_title(), "Example");
+    }
+
+    #[test]
+    fn systems_are_parsed() {
+        for system in LinkSystem::ALL {
+            assert_eq!(system.to_string().parse(), Ok(system));
+        }
+        assert_eq!("BUG".parse(), Ok(LinkSystem::Defect));
+        assert_eq!("pipeline".parse(), Ok(LinkSystem::Build));
+        assert_eq!("".parse::<LinkSystem>(), Err(()));
+    }
+}
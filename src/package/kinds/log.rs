@@ -0,0 +1,667 @@
+use std::{fmt, str::FromStr};
+
+use chrono::{DateTime, Datelike, FixedOffset, Local, NaiveDateTime, TimeZone};
+use getset::{CopyGetters, Getters, MutGetters, Setters};
+use serde::{Deserialize, Serialize};
+
+use super::StructuredEvidence;
+use crate::EvidenceKind;
+
+/// The keys checked, in order, for the timestamp of a JSON log line.
+const JSON_TIMESTAMP_KEYS: &[&str] = &["timestamp", "time", "ts", "@timestamp", "datetime", "date"];
+/// The keys checked, in order, for the level of a JSON log line.
+const JSON_LEVEL_KEYS: &[&str] = &["level", "severity", "lvl", "loglevel", "log.level"];
+/// The keys checked, in order, for the source of a JSON log line.
+const JSON_SOURCE_KEYS: &[&str] = &[
+    "source",
+    "target",
+    "logger",
+    "logger_name",
+    "module",
+    "component",
+    "name",
+];
+/// The keys checked, in order, for the message of a JSON log line.
+const JSON_MESSAGE_KEYS: &[&str] = &["message", "msg", "text", "@message"];
+
+/// Log evidence, made up of individual timestamped lines.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, Getters, MutGetters)]
+#[getset(get = "pub", get_mut = "pub")]
+pub struct Log {
+    /// The entries in this log, in the order they were written.
+    entries: Vec<LogEntry>,
+}
+
+impl StructuredEvidence for Log {
+    const KIND: EvidenceKind = EvidenceKind::Log;
+}
+
+impl Log {
+    /// Create a new log from a list of entries.
+    #[must_use]
+    pub fn new(entries: Vec<LogEntry>) -> Self {
+        Self { entries }
+    }
+
+    /// Parse a log from text. Lines that can't be parsed in the chosen format, such as stack
+    /// traces, are appended to the message of the previous entry.
+    #[must_use]
+    pub fn parse(text: &str, format: LogFormat) -> Self {
+        let format = match format {
+            LogFormat::Auto => LogFormat::detect(text),
+            format => format,
+        };
+
+        let mut entries: Vec<LogEntry> = vec![];
+        for line in text.lines() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry = match format {
+                LogFormat::JsonLines => parse_json_line(line),
+                LogFormat::Syslog => parse_syslog_line(line),
+                LogFormat::Plain | LogFormat::Auto => parse_plain_line(line),
+            };
+            match (entry, entries.last_mut()) {
+                (Some(entry), _) => entries.push(entry),
+                (None, Some(last)) => {
+                    last.message.push('\n');
+                    last.message.push_str(line);
+                }
+                (None, None) => entries.push(LogEntry::new(line)),
+            }
+        }
+        Self { entries }
+    }
+
+    /// Get the entries in this log that are at least as severe as the minimum level, and contain
+    /// the search text in their source or message, ignoring case. Entries without a level are
+    /// only excluded if a minimum level is set.
+    pub fn filter<'a>(
+        &'a self,
+        min_level: Option<LogLevel>,
+        search: &'a str,
+    ) -> impl Iterator<Item = &'a LogEntry> {
+        self.entries
+            .iter()
+            .filter(move |entry| entry.matches(min_level, search))
+    }
+}
+
+/// A single entry in a [`Log`].
+#[derive(
+    Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Getters, CopyGetters, MutGetters, Setters,
+)]
+pub struct LogEntry {
+    /// The time this entry was written, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get_copy = "pub", set = "pub")]
+    timestamp: Option<DateTime<FixedOffset>>,
+    /// The severity of this entry, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get_copy = "pub", set = "pub")]
+    level: Option<LogLevel>,
+    /// The component that wrote this entry, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub", set = "pub")]
+    source: Option<String>,
+    /// The message of this entry. This may span multiple lines.
+    #[getset(get = "pub", get_mut = "pub", set = "pub")]
+    message: String,
+}
+
+impl LogEntry {
+    /// Create a new log entry with just a message.
+    #[must_use]
+    pub fn new<S: Into<String>>(message: S) -> Self {
+        Self {
+            timestamp: None,
+            level: None,
+            source: None,
+            message: message.into(),
+        }
+    }
+
+    /// Determine if this entry is at least as severe as the minimum level, and contains the
+    /// search text in its source or message, ignoring case.
+    #[must_use]
+    pub fn matches(&self, min_level: Option<LogLevel>, search: &str) -> bool {
+        if let Some(min_level) = min_level
+            && self.level.is_none_or(|level| level < min_level)
+        {
+            return false;
+        }
+        if search.is_empty() {
+            return true;
+        }
+        let search = search.to_lowercase();
+        self.message.to_lowercase().contains(&search)
+            || self
+                .source
+                .as_ref()
+                .is_some_and(|source| source.to_lowercase().contains(&search))
+    }
+}
+
+/// The severity of a [`LogEntry`], from least to most severe.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum LogLevel {
+    /// Very detailed tracing information.
+    Trace,
+    /// Information useful when debugging.
+    Debug,
+    /// General information, including notices.
+    Info,
+    /// Something unexpected that could be recovered from.
+    Warn,
+    /// Something failed.
+    Error,
+    /// Something failed that the application couldn't recover from, including critical, alert
+    /// and emergency levels.
+    Fatal,
+}
+
+impl LogLevel {
+    /// All levels, from least to most severe.
+    pub const ALL: [LogLevel; 6] = [
+        Self::Trace,
+        Self::Debug,
+        Self::Info,
+        Self::Warn,
+        Self::Error,
+        Self::Fatal,
+    ];
+
+    /// Get the level matching a syslog severity, from 0 (emergency) to 7 (debug).
+    #[must_use]
+    pub fn from_syslog_severity(severity: u8) -> Self {
+        match severity {
+            0..=2 => Self::Fatal,
+            3 => Self::Error,
+            4 => Self::Warn,
+            5 | 6 => Self::Info,
+            _ => Self::Debug,
+        }
+    }
+}
+
+impl fmt::Display for LogLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Trace => "TRACE",
+                Self::Debug => "DEBUG",
+                Self::Info => "INFO",
+                Self::Warn => "WARN",
+                Self::Error => "ERROR",
+                Self::Fatal => "FATAL",
+            }
+        )
+    }
+}
+
+impl FromStr for LogLevel {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "trace" | "trc" | "finest" | "finer" | "verbose" => Ok(Self::Trace),
+            "debug" | "dbg" | "fine" => Ok(Self::Debug),
+            "info" | "inf" | "information" | "notice" => Ok(Self::Info),
+            "warn" | "wrn" | "warning" => Ok(Self::Warn),
+            "error" | "err" | "severe" => Ok(Self::Error),
+            "fatal" | "critical" | "crit" | "alert" | "emerg" | "emergency" | "panic" => {
+                Ok(Self::Fatal)
+            }
+            _ => Err(()),
+        }
+    }
+}
+
+/// The format of log text to parse.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Detect the format from the first line.
+    #[default]
+    Auto,
+    /// Plain lines, optionally starting with a timestamp, level and source, such as
+    /// `2024-05-01 10:00:00 INFO server: Started`.
+    Plain,
+    /// One JSON object per line, as written by most structured logging libraries.
+    JsonLines,
+    /// Syslog lines in either RFC 5424 or RFC 3164 (BSD) format.
+    Syslog,
+}
+
+impl LogFormat {
+    /// Detect the format of some log text from its first non-empty line.
+    #[must_use]
+    pub fn detect(text: &str) -> Self {
+        let Some(line) = text.lines().find(|line| !line.trim().is_empty()) else {
+            return Self::Plain;
+        };
+        let line = line.trim_start();
+        if line.starts_with('{') && serde_json::from_str::<serde_json::Value>(line).is_ok() {
+            Self::JsonLines
+        } else if parse_syslog_line(line).is_some() {
+            Self::Syslog
+        } else {
+            Self::Plain
+        }
+    }
+}
+
+impl FromStr for LogFormat {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "auto" => Ok(Self::Auto),
+            "plain" | "text" => Ok(Self::Plain),
+            "json" | "jsonl" | "json-lines" => Ok(Self::JsonLines),
+            "syslog" => Ok(Self::Syslog),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Split the first whitespace separated token from some text, returning it and the remaining
+/// text without leading whitespace.
+fn take_token(text: &str) -> Option<(&str, &str)> {
+    let text = text.trim_start();
+    if text.is_empty() {
+        return None;
+    }
+    match text.split_once(char::is_whitespace) {
+        Some((token, rest)) => Some((token, rest.trim_start())),
+        None => Some((text, "")),
+    }
+}
+
+/// Parse a timestamp in RFC 3339 format, or as a date and time without an offset, which is
+/// assumed to be in local time.
+fn parse_timestamp(text: &str) -> Option<DateTime<FixedOffset>> {
+    let text = text.trim_matches(|c| c == '[' || c == ']');
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(text) {
+        return Some(timestamp);
+    }
+    for format in [
+        "%Y-%m-%dT%H:%M:%S%.f",
+        "%Y-%m-%d %H:%M:%S%.f",
+        "%Y-%m-%d %H:%M:%S,%f",
+        "%Y/%m/%d %H:%M:%S%.f",
+    ] {
+        if let Ok(naive) = NaiveDateTime::parse_from_str(text, format) {
+            return Local
+                .from_local_datetime(&naive)
+                .earliest()
+                .map(|local| local.fixed_offset());
+        }
+    }
+    None
+}
+
+/// Parse a plain log line such as `2024-05-01 10:00:00 [INFO] server: Started`. Returns
+/// [`None`] if the line has neither a timestamp nor a level, so is likely a continuation of the
+/// previous line.
+fn parse_plain_line(line: &str) -> Option<LogEntry> {
+    let mut entry = LogEntry::new(line);
+    let mut rest = line;
+
+    // Timestamps may be one token, or a date and time as two tokens
+    if let Some((first, after_first)) = take_token(rest) {
+        if let Some(timestamp) = parse_timestamp(first) {
+            entry.timestamp = Some(timestamp);
+            rest = after_first;
+        } else if let Some((second, after_second)) = take_token(after_first)
+            && let Some(timestamp) = parse_timestamp(&format!("{first} {second}"))
+        {
+            entry.timestamp = Some(timestamp);
+            rest = after_second;
+        }
+    }
+
+    if let Some((token, after)) = take_token(rest)
+        && let Ok(level) = token
+            .trim_matches(|c: char| c == '[' || c == ']' || c == ':')
+            .parse()
+    {
+        entry.level = Some(level);
+        rest = after;
+    }
+
+    if entry.timestamp.is_none() && entry.level.is_none() {
+        return None;
+    }
+
+    // A source is a single token either in brackets or ending with a colon
+    if let Some((token, after)) = take_token(rest) {
+        if token.len() > 2 && token.starts_with('[') && token.ends_with(']') {
+            entry.source = Some(token[1..token.len() - 1].to_string());
+            rest = after;
+        } else if token.len() > 1 && token.ends_with(':') {
+            entry.source = Some(token[..token.len() - 1].to_string());
+            rest = after;
+        }
+    }
+
+    entry.message = rest.trim_start_matches(['-', ':', ' ']).to_string();
+    Some(entry)
+}
+
+/// Parse a JSON log line. Returns [`None`] if the line isn't a JSON object.
+fn parse_json_line(line: &str) -> Option<LogEntry> {
+    /// Find the first string or number value with one of the keys, looking within a nested
+    /// `fields` object as well.
+    fn find(object: &serde_json::Map<String, serde_json::Value>, keys: &[&str]) -> Option<String> {
+        keys.iter().find_map(|key| {
+            let value = object.get(*key).or_else(|| {
+                object
+                    .get("fields")
+                    .and_then(serde_json::Value::as_object)
+                    .and_then(|fields| fields.get(*key))
+            })?;
+            match value {
+                serde_json::Value::String(s) => Some(s.clone()),
+                serde_json::Value::Number(n) => Some(n.to_string()),
+                _ => None,
+            }
+        })
+    }
+
+    let serde_json::Value::Object(object) = serde_json::from_str(line).ok()? else {
+        return None;
+    };
+
+    let mut entry =
+        LogEntry::new(find(&object, JSON_MESSAGE_KEYS).unwrap_or_else(|| line.to_string()));
+    entry.timestamp = find(&object, JSON_TIMESTAMP_KEYS).and_then(|timestamp| {
+        parse_timestamp(&timestamp).or_else(|| {
+            // Unix timestamps, in either seconds or milliseconds
+            let number = timestamp.parse::<f64>().ok()?;
+            let millis = if number > 1e11 {
+                number
+            } else {
+                number * 1000.0
+            };
+            DateTime::from_timestamp_millis(millis as i64).map(|utc| utc.fixed_offset())
+        })
+    });
+    entry.level = find(&object, JSON_LEVEL_KEYS).and_then(|level| {
+        level.parse().ok().or_else(|| {
+            // Numeric levels, as used by bunyan and pino
+            match level.parse::<u32>().ok()? {
+                0..=10 => Some(LogLevel::Trace),
+                11..=20 => Some(LogLevel::Debug),
+                21..=30 => Some(LogLevel::Info),
+                31..=40 => Some(LogLevel::Warn),
+                41..=50 => Some(LogLevel::Error),
+                _ => Some(LogLevel::Fatal),
+            }
+        })
+    });
+    entry.source = find(&object, JSON_SOURCE_KEYS);
+    Some(entry)
+}
+
+/// Parse a syslog line in either RFC 5424 or RFC 3164 format. Returns [`None`] if the line
+/// isn't in either format.
+fn parse_syslog_line(line: &str) -> Option<LogEntry> {
+    let mut rest = line.trim_start();
+    let mut level = None;
+
+    // Priority, containing the facility and severity
+    if let Some(after) = rest.strip_prefix('<') {
+        let (priority, after) = after.split_once('>')?;
+        let priority: u8 = priority.parse().ok()?;
+        level = Some(LogLevel::from_syslog_severity(priority & 7));
+        rest = after;
+    }
+
+    // RFC 5424: VERSION TIMESTAMP HOSTNAME APP-NAME PROCID MSGID STRUCTURED-DATA MSG
+    if let Some(after) = rest.strip_prefix("1 ") {
+        let (timestamp, after) = take_token(after)?;
+        let timestamp = DateTime::parse_from_rfc3339(timestamp).ok();
+        let (hostname, after) = take_token(after)?;
+        let (app_name, after) = take_token(after)?;
+        let (_proc_id, after) = take_token(after)?;
+        let (_msg_id, mut after) = take_token(after)?;
+        if let Some(remaining) = after.strip_prefix('-') {
+            after = remaining;
+        } else {
+            while after.starts_with('[') {
+                let end = after.find(']')?;
+                after = &after[end + 1..];
+            }
+        }
+
+        let mut entry = LogEntry::new(after.trim_start().trim_start_matches('\u{feff}'));
+        entry.timestamp = timestamp;
+        entry.level = level;
+        entry.source = [app_name, hostname]
+            .into_iter()
+            .find(|name| *name != "-")
+            .map(ToString::to_string);
+        return Some(entry);
+    }
+
+    // RFC 3164: Mmm dd hh:mm:ss HOSTNAME TAG: MSG
+    let (month, after) = take_token(rest)?;
+    let (day, after) = take_token(after)?;
+    let (time, after) = take_token(after)?;
+    let naive = NaiveDateTime::parse_from_str(
+        &format!("{} {month} {day} {time}", Local::now().year()),
+        "%Y %b %d %H:%M:%S",
+    )
+    .ok()?;
+    let (_hostname, after) = take_token(after)?;
+    let (source, message) = match after.split_once(": ") {
+        Some((tag, message)) if !tag.contains(char::is_whitespace) => (
+            Some(tag.split('[').next().unwrap_or(tag).to_string()),
+            message,
+        ),
+        _ => (None, after),
+    };
+
+    let mut entry = LogEntry::new(message);
+    entry.timestamp = Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|local| local.fixed_offset());
+    entry.level = level;
+    entry.source = source;
+    Some(entry)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_input_has_no_entries() {
+        assert!(Log::parse("", LogFormat::Auto).entries().is_empty());
+        assert!(
+            Log::parse("\n  \r\n\t\n", LogFormat::Auto)
+                .entries()
+                .is_empty()
+        );
+        assert_eq!(LogFormat::detect(""), LogFormat::Plain);
+    }
+
+    #[test]
+    fn plain_lines_are_parsed() {
+        let log = Log::parse(
+            "2024-05-01T10:00:00Z INFO server: Started\n\
+             2024-05-01 10:00:01 [WARN] [db] Slow query\n\
+             ERROR Failed",
+            LogFormat::Plain,
+        );
+        let entries = log.entries();
+        assert_eq!(entries.len(), 3);
+
+        assert_eq!(
+            entries[0].timestamp(),
+            Some(DateTime::parse_from_rfc3339("2024-05-01T10:00:00Z").unwrap())
+        );
+        assert_eq!(entries[0].level(), Some(LogLevel::Info));
+        assert_eq!(entries[0].source().as_deref(), Some("server"));
+        assert_eq!(entries[0].message(), "Started");
+
+        assert!(entries[1].timestamp().is_some());
+        assert_eq!(entries[1].level(), Some(LogLevel::Warn));
+        assert_eq!(entries[1].source().as_deref(), Some("db"));
+        assert_eq!(entries[1].message(), "Slow query");
+
+        assert_eq!(entries[2].timestamp(), None);
+        assert_eq!(entries[2].level(), Some(LogLevel::Error));
+        assert_eq!(entries[2].message(), "Failed");
+    }
+
+    #[test]
+    fn crlf_line_endings_are_removed() {
+        let log = Log::parse(
+            "2024-05-01T10:00:00Z INFO Started\r\n2024-05-01T10:00:01Z ERROR Failed\r\n",
+            LogFormat::Auto,
+        );
+        let messages: Vec<_> = log.entries().iter().map(LogEntry::message).collect();
+        assert_eq!(messages, ["Started", "Failed"]);
+    }
+
+    #[test]
+    fn continuation_lines_are_appended() {
+        let log = Log::parse(
+            "stack trace without a header\n\
+             2024-05-01T10:00:00Z ERROR Panicked\n\
+             \tat main.rs:1\n\
+             \tat lib.rs:2",
+            LogFormat::Plain,
+        );
+        let entries = log.entries();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].message(), "stack trace without a header");
+        assert_eq!(entries[0].level(), None);
+        assert_eq!(
+            entries[1].message(),
+            "Panicked\n\tat main.rs:1\n\tat lib.rs:2"
+        );
+    }
+
+    #[test]
+    fn json_lines_are_parsed() {
+        let text = r#"{"timestamp":"2024-05-01T10:00:00Z","level":"warn","target":"app","message":"Hi"}
+{"time":1714557600,"level":50,"msg":"Numeric"}
+{"fields":{"message":"Nested","level":"debug"}}"#;
+        assert_eq!(LogFormat::detect(text), LogFormat::JsonLines);
+
+        let log = Log::parse(text, LogFormat::Auto);
+        let entries = log.entries();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].level(), Some(LogLevel::Warn));
+        assert_eq!(entries[0].source().as_deref(), Some("app"));
+        assert_eq!(entries[0].message(), "Hi");
+        assert_eq!(
+            entries[1].timestamp(),
+            Some(DateTime::parse_from_rfc3339("2024-05-01T10:00:00Z").unwrap())
+        );
+        assert_eq!(entries[1].level(), Some(LogLevel::Error));
+        assert_eq!(entries[2].level(), Some(LogLevel::Debug));
+        assert_eq!(entries[2].message(), "Nested");
+    }
+
+    #[test]
+    fn malformed_json_lines_are_continuations() {
+        let log = Log::parse(
+            "{\"message\":\"First\"}\n{not json\n[1, 2]\n\"string\"",
+            LogFormat::JsonLines,
+        );
+        let entries = log.entries();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].message(), "First\n{not json\n[1, 2]\n\"string\"");
+    }
+
+    #[test]
+    fn json_lines_with_odd_values_do_not_panic() {
+        let log = Log::parse(
+            "{}\n{\"time\":1e300}\n{\"time\":-1e30,\"level\":99999999999}\n{\"level\":[]}",
+            LogFormat::JsonLines,
+        );
+        assert_eq!(log.entries().len(), 4);
+        assert_eq!(log.entries()[0].message(), "{}");
+    }
+
+    #[test]
+    fn syslog_lines_are_parsed() {
+        let text = "<34>1 2024-05-01T10:00:00Z host app 123 ID47 [meta x=\"1\"] Failed login\n\
+                    <13>May  1 10:00:00 host sshd[42]: Accepted";
+        assert_eq!(LogFormat::detect(text), LogFormat::Syslog);
+
+        let log = Log::parse(text, LogFormat::Auto);
+        let entries = log.entries();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(
+            entries[0].timestamp(),
+            Some(DateTime::parse_from_rfc3339("2024-05-01T10:00:00Z").unwrap())
+        );
+        assert_eq!(entries[0].level(), Some(LogLevel::Fatal));
+        assert_eq!(entries[0].source().as_deref(), Some("app"));
+        assert_eq!(entries[0].message(), "Failed login");
+        assert_eq!(entries[1].level(), Some(LogLevel::Info));
+        assert_eq!(entries[1].source().as_deref(), Some("sshd"));
+        assert_eq!(entries[1].message(), "Accepted");
+    }
+
+    #[test]
+    fn malformed_syslog_lines_do_not_panic() {
+        for line in [
+            "<",
+            "<>",
+            "<999> message",
+            "<34",
+            "<34>1",
+            "<34>1 2024-05-01T10:00:00Z host app 1 ID [unterminated",
+            "<34>1 - - - - - -",
+            "Foo 99 25:61:61 host tag: message",
+        ] {
+            let _ = parse_syslog_line(line);
+            let _ = Log::parse(line, LogFormat::Syslog);
+            let _ = Log::parse(line, LogFormat::Auto);
+        }
+        assert!(parse_syslog_line("<999> message").is_none());
+        assert!(parse_syslog_line("<34>1 - - - - - -").is_some());
+    }
+
+    #[test]
+    fn unusual_plain_lines_do_not_panic() {
+        for line in [
+            "[",
+            "[]",
+            "]",
+            ":",
+            "INFO",
+            "INFO [",
+            "INFO ]:",
+            "ERROR é:",
+            "WARN [ü]",
+        ] {
+            let log = Log::parse(line, LogFormat::Plain);
+            assert_eq!(log.entries().len(), 1);
+        }
+    }
+
+    #[test]
+    fn entries_are_filtered() {
+        let log = Log::parse(
+            "INFO app: Started\nWARN db: Slow\nERROR db: Failed\n2024-05-01T10:00:00Z db: Done",
+            LogFormat::Plain,
+        );
+        let filtered: Vec<_> = log
+            .filter(Some(LogLevel::Warn), "DB")
+            .map(LogEntry::message)
+            .collect();
+        assert_eq!(filtered, ["Slow", "Failed"]);
+        assert_eq!(log.filter(None, "db").count(), 3);
+        assert_eq!(log.filter(None, "").count(), 4);
+    }
+}
@@ -0,0 +1,415 @@
+use std::time::Duration;
+
+use getset::{CopyGetters, Getters, MutGetters, Setters};
+use serde::{Deserialize, Serialize};
+
+use super::{StructuredEvidence, Table};
+use crate::EvidenceKind;
+
+/// Words that are highlighted as keywords in SQL queries. These are compared ignoring case.
+const KEYWORDS: &[&str] = &[
+    "ADD",
+    "ALL",
+    "ALTER",
+    "AND",
+    "ANY",
+    "AS",
+    "ASC",
+    "BEGIN",
+    "BETWEEN",
+    "BY",
+    "CASE",
+    "CAST",
+    "CHECK",
+    "COLUMN",
+    "COMMIT",
+    "CONSTRAINT",
+    "CREATE",
+    "CROSS",
+    "DATABASE",
+    "DEFAULT",
+    "DELETE",
+    "DESC",
+    "DISTINCT",
+    "DROP",
+    "ELSE",
+    "END",
+    "EXCEPT",
+    "EXISTS",
+    "FALSE",
+    "FETCH",
+    "FOREIGN",
+    "FROM",
+    "FULL",
+    "GROUP",
+    "HAVING",
+    "IN",
+    "INDEX",
+    "INNER",
+    "INSERT",
+    "INTERSECT",
+    "INTO",
+    "IS",
+    "JOIN",
+    "KEY",
+    "LEFT",
+    "LIKE",
+    "LIMIT",
+    "NOT",
+    "NULL",
+    "OFFSET",
+    "ON",
+    "OR",
+    "ORDER",
+    "OUTER",
+    "OVER",
+    "PARTITION",
+    "PRIMARY",
+    "REFERENCES",
+    "RETURNING",
+    "RIGHT",
+    "ROLLBACK",
+    "SELECT",
+    "SET",
+    "TABLE",
+    "THEN",
+    "TOP",
+    "TRUE",
+    "TRUNCATE",
+    "UNION",
+    "UNIQUE",
+    "UPDATE",
+    "USING",
+    "VALUES",
+    "VIEW",
+    "WHEN",
+    "WHERE",
+    "WITH",
+];
+
+/// Evidence of a SQL query being executed against a database, with the rows it returned.
+#[derive(
+    Clone,
+    Debug,
+    Default,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    Getters,
+    CopyGetters,
+    MutGetters,
+    Setters,
+)]
+pub struct SqlQuery {
+    /// The text of the query.
+    #[getset(get = "pub", set = "pub")]
+    query: String,
+    /// A label identifying the database the query was executed against.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub", set = "pub")]
+    database: Option<String>,
+    /// The number of rows returned or affected by the query. This may be more than the number of
+    /// rows in the result if only some of them were captured.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get_copy = "pub", set = "pub")]
+    row_count: Option<u64>,
+    /// How long the query took to execute, in milliseconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    duration_ms: Option<u64>,
+    /// The rows returned by the query.
+    #[serde(default)]
+    #[getset(get = "pub", get_mut = "pub", set = "pub")]
+    result: Table,
+}
+
+impl StructuredEvidence for SqlQuery {
+    const KIND: EvidenceKind = EvidenceKind::Sql;
+}
+
+impl SqlQuery {
+    /// Create new evidence of a SQL query being executed.
+    #[must_use]
+    pub fn new<S: Into<String>>(query: S) -> Self {
+        Self {
+            query: query.into(),
+            ..Default::default()
+        }
+    }
+
+    /// How long the query took to execute, if known.
+    #[must_use]
+    pub fn duration(&self) -> Option<Duration> {
+        self.duration_ms.map(Duration::from_millis)
+    }
+
+    /// Set how long the query took to execute. This is stored to the nearest millisecond.
+    pub fn set_duration(&mut self, duration: Option<Duration>) -> &mut Self {
+        self.duration_ms = duration.map(|duration| duration.as_millis() as u64);
+        self
+    }
+
+    /// The number of rows returned or affected by the query, or the number of rows in the result
+    /// if this wasn't recorded.
+    #[must_use]
+    pub fn effective_row_count(&self) -> u64 {
+        self.row_count.unwrap_or(self.result.rows().len() as u64)
+    }
+
+    /// Split the query into tokens for syntax highlighting. Joining the text of every token gives
+    /// the original query.
+    #[must_use]
+    pub fn tokens(&self) -> Vec<(SqlToken, &str)> {
+        tokenize(&self.query)
+    }
+}
+
+/// The kind of a token in a SQL query, used for syntax highlighting.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SqlToken {
+    /// A reserved word, such as `SELECT`.
+    Keyword,
+    /// A name, such as that of a table or column, which may be quoted.
+    Identifier,
+    /// A string literal.
+    String,
+    /// A numeric literal.
+    Number,
+    /// A line or block comment.
+    Comment,
+    /// A bind parameter, such as `?`, `$1`, `:name` or `@name`.
+    Parameter,
+    /// Whitespace, operators and punctuation.
+    Other,
+}
+
+/// Split a SQL query into highlighted tokens. This is deliberately forgiving, so unterminated
+/// strings and comments run to the end of the query rather than causing an error.
+fn tokenize(query: &str) -> Vec<(SqlToken, &str)> {
+    /// Find the end of a run of characters matching a predicate, starting at a byte offset
+    fn run_end(query: &str, start: usize, predicate: impl Fn(char) -> bool) -> usize {
+        query[start..]
+            .char_indices()
+            .find(|(_, c)| !predicate(*c))
+            .map_or(query.len(), |(offset, _)| start + offset)
+    }
+    /// Find the end of a quoted section, where the quote is escaped by doubling it
+    fn quoted_end(query: &str, start: usize, close: char) -> usize {
+        let mut chars = query[start + 1..].char_indices().peekable();
+        while let Some((offset, c)) = chars.next() {
+            if c == close {
+                if chars.peek().is_some_and(|(_, next)| *next == close) {
+                    chars.next();
+                    continue;
+                }
+                return start + 1 + offset + close.len_utf8();
+            }
+        }
+        query.len()
+    }
+    /// Whether a character can continue a word
+    fn is_word(c: char) -> bool {
+        c.is_alphanumeric() || c == '_' || c == '$'
+    }
+
+    let mut tokens: Vec<(SqlToken, &str)> = vec![];
+    let mut start = 0;
+    while let Some(c) = query[start..].chars().next() {
+        let rest = &query[start..];
+        let (kind, end) = if c.is_whitespace() {
+            (SqlToken::Other, run_end(query, start, char::is_whitespace))
+        } else if rest.starts_with("--") {
+            (SqlToken::Comment, run_end(query, start, |c| c != '\n'))
+        } else if let Some(comment) = rest.strip_prefix("/*") {
+            (
+                SqlToken::Comment,
+                comment
+                    .find("*/")
+                    .map_or(query.len(), |offset| start + 2 + offset + 2),
+            )
+        } else if c == '\'' {
+            (SqlToken::String, quoted_end(query, start, '\''))
+        } else if c == '"' || c == '`' {
+            (SqlToken::Identifier, quoted_end(query, start, c))
+        } else if c == '[' {
+            (SqlToken::Identifier, quoted_end(query, start, ']'))
+        } else if c.is_ascii_digit() {
+            (
+                SqlToken::Number,
+                run_end(query, start, |c| c.is_ascii_alphanumeric() || c == '.'),
+            )
+        } else if c == '?' {
+            (SqlToken::Parameter, start + 1)
+        } else if rest.starts_with("::") {
+            // A cast, such as `x::int`, rather than a parameter
+            (SqlToken::Other, start + 2)
+        } else if matches!(c, '$' | ':' | '@') && rest[1..].chars().next().is_some_and(is_word) {
+            (SqlToken::Parameter, run_end(query, start + 1, is_word))
+        } else if is_word(c) {
+            let end = run_end(query, start, is_word);
+            let word = &query[start..end];
+            let kind = if KEYWORDS
+                .iter()
+                .any(|keyword| keyword.eq_ignore_ascii_case(word))
+            {
+                SqlToken::Keyword
+            } else {
+                SqlToken::Identifier
+            };
+            (kind, end)
+        } else {
+            (SqlToken::Other, start + c.len_utf8())
+        };
+
+        // Merge runs of other characters so that renderers produce fewer pieces
+        match tokens.last_mut() {
+            Some((SqlToken::Other, text)) if kind == SqlToken::Other => {
+                *text = &query[start - text.len()..end];
+            }
+            _ => tokens.push((kind, &query[start..end])),
+        }
+        start = end;
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tokenize a query, leaving out whitespace and punctuation.
+    fn significant(query: &str) -> Vec<(SqlToken, &str)> {
+        tokenize(query)
+            .into_iter()
+            .filter(|(kind, _)| *kind != SqlToken::Other)
+            .collect()
+    }
+
+    #[test]
+    fn empty_query_has_no_tokens() {
+        assert!(tokenize("").is_empty());
+    }
+
+    #[test]
+    fn tokens_join_to_the_query() {
+        for query in [
+            "SELECT * FROM users WHERE id = ?",
+            "select 'it''s', \"col\"\"name\", [weird name] from `t`\r\n-- done\r\n",
+            "/* unterminated",
+            "'unterminated",
+            "\"unterminated",
+            "[unterminated",
+            "x::int + $1 - :name * @var / $",
+            "SELECT 'ünïcödé', 名前 FROM テーブル; -- コメント",
+            "1.5e10 0x1F .5 -- trailing",
+        ] {
+            let joined: String = tokenize(query).into_iter().map(|(_, text)| text).collect();
+            assert_eq!(joined, query);
+        }
+    }
+
+    #[test]
+    fn keywords_and_identifiers_are_found() {
+        assert_eq!(
+            significant("select Name from users where active = TRUE"),
+            [
+                (SqlToken::Keyword, "select"),
+                (SqlToken::Identifier, "Name"),
+                (SqlToken::Keyword, "from"),
+                (SqlToken::Identifier, "users"),
+                (SqlToken::Keyword, "where"),
+                (SqlToken::Identifier, "active"),
+                (SqlToken::Keyword, "TRUE"),
+            ]
+        );
+    }
+
+    #[test]
+    fn literals_and_quoted_identifiers_are_found() {
+        assert_eq!(
+            significant("'it''s' \"a\"\"b\" `c` [d e] 42 3.14"),
+            [
+                (SqlToken::String, "'it''s'"),
+                (SqlToken::Identifier, "\"a\"\"b\""),
+                (SqlToken::Identifier, "`c`"),
+                (SqlToken::Identifier, "[d e]"),
+                (SqlToken::Number, "42"),
+                (SqlToken::Number, "3.14"),
+            ]
+        );
+    }
+
+    #[test]
+    fn comments_are_found() {
+        assert_eq!(
+            tokenize("-- line\r\n/* block */x"),
+            [
+                (SqlToken::Comment, "-- line\r"),
+                (SqlToken::Other, "\n"),
+                (SqlToken::Comment, "/* block */"),
+                (SqlToken::Identifier, "x"),
+            ]
+        );
+    }
+
+    #[test]
+    fn unterminated_literals_run_to_the_end() {
+        assert_eq!(
+            significant("SELECT 'abc"),
+            [(SqlToken::Keyword, "SELECT"), (SqlToken::String, "'abc")]
+        );
+        assert_eq!(
+            significant("SELECT /* abc"),
+            [(SqlToken::Keyword, "SELECT"), (SqlToken::Comment, "/* abc")]
+        );
+        assert_eq!(significant("'"), [(SqlToken::String, "'")]);
+        assert_eq!(significant("/*"), [(SqlToken::Comment, "/*")]);
+    }
+
+    #[test]
+    fn parameters_are_found() {
+        assert_eq!(
+            significant("? $1 :name @var x::int"),
+            [
+                (SqlToken::Parameter, "?"),
+                (SqlToken::Parameter, "$1"),
+                (SqlToken::Parameter, ":name"),
+                (SqlToken::Parameter, "@var"),
+                (SqlToken::Identifier, "x"),
+                (SqlToken::Identifier, "int"),
+            ]
+        );
+        assert_eq!(tokenize(":"), [(SqlToken::Other, ":")]);
+        assert_eq!(tokenize("@"), [(SqlToken::Other, "@")]);
+    }
+
+    #[test]
+    fn other_characters_are_merged() {
+        assert_eq!(
+            tokenize("a <> (b)"),
+            [
+                (SqlToken::Identifier, "a"),
+                (SqlToken::Other, " <> ("),
+                (SqlToken::Identifier, "b"),
+                (SqlToken::Other, ")"),
+            ]
+        );
+    }
+
+    #[test]
+    fn row_count_falls_back_to_result() {
+        let mut query = SqlQuery::new("SELECT 1");
+        assert_eq!(query.effective_row_count(), 0);
+        query.set_result(Table::new(vec!["a".to_string()], vec![vec![], vec![]]));
+        assert_eq!(query.effective_row_count(), 2);
+        query.set_row_count(Some(100));
+        assert_eq!(query.effective_row_count(), 100);
+    }
+
+    #[test]
+    fn duration_is_stored_in_milliseconds() {
+        let mut query = SqlQuery::new("SELECT 1");
+        assert_eq!(query.duration(), None);
+        query.set_duration(Some(Duration::from_micros(12_345)));
+        assert_eq!(query.duration(), Some(Duration::from_millis(12)));
+    }
+}
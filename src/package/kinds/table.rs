@@ -0,0 +1,547 @@
+use std::{cmp::Ordering, fmt, str::FromStr};
+
+use getset::{Getters, MutGetters};
+use serde::{Deserialize, Serialize};
+
+use super::StructuredEvidence;
+use crate::EvidenceKind;
+
+/// The longest whole number, in digits, that is read as a number when importing. Longer numbers,
+/// such as account or card numbers, would lose precision so are kept as text.
+const MAX_NUMBER_DIGITS: usize = 15;
+
+/// Tabular evidence, made up of named columns and rows of typed cells.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, Getters, MutGetters)]
+#[getset(get = "pub", get_mut = "pub")]
+pub struct Table {
+    /// The headers of the columns in this table.
+    columns: Vec<String>,
+    /// The rows of this table. Each row has one cell for each column.
+    rows: Vec<Vec<TableCell>>,
+}
+
+impl StructuredEvidence for Table {
+    const KIND: EvidenceKind = EvidenceKind::Table;
+}
+
+impl Table {
+    /// Create a new table from column headers and rows. Rows shorter than the number of columns
+    /// are padded with empty cells, and extra columns are added for rows that are longer.
+    #[must_use]
+    pub fn new(mut columns: Vec<String>, mut rows: Vec<Vec<TableCell>>) -> Self {
+        let width = rows
+            .iter()
+            .map(Vec::len)
+            .max()
+            .unwrap_or_default()
+            .max(columns.len());
+        while columns.len() < width {
+            columns.push(format!("Column {}", columns.len() + 1));
+        }
+        for row in &mut rows {
+            row.resize(width, TableCell::Empty);
+        }
+        Self { columns, rows }
+    }
+
+    /// Parse a table from text. The first row of CSV and TSV is used as the column headers, and
+    /// each cell is read as a number, boolean or text, or left empty.
+    ///
+    /// JSON can either be an array of objects, where each key becomes a column, or an array of
+    /// arrays, where the first array contains the column headers.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::InvalidEvidenceData`](crate::Error::InvalidEvidenceData) if JSON is provided
+    ///   that isn't in one of the supported shapes.
+    pub fn parse(text: &str, format: TableFormat) -> crate::Result<Self> {
+        let format = match format {
+            TableFormat::Auto => TableFormat::detect(text),
+            format => format,
+        };
+        match format {
+            TableFormat::Json => parse_json(text),
+            TableFormat::Tsv => Ok(parse_delimited(text, '\t')),
+            TableFormat::Csv | TableFormat::Auto => Ok(parse_delimited(text, ',')),
+        }
+    }
+}
+
+/// A single cell in a [`Table`].
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum TableCell {
+    /// A cell with no value.
+    #[default]
+    Empty,
+    /// A true or false value.
+    Boolean(bool),
+    /// A numeric value.
+    Number(f64),
+    /// A text value.
+    Text(String),
+}
+
+impl TableCell {
+    /// Read a cell from text, detecting its type. Empty text gives an empty cell, `true` and
+    /// `false` give booleans, and plain decimal numbers give numbers. Anything else, including
+    /// numbers with leading zeros, is kept as text.
+    #[must_use]
+    pub fn detect(text: &str) -> Self {
+        let trimmed = text.trim();
+        if trimmed.is_empty() {
+            return Self::Empty;
+        }
+        if trimmed.eq_ignore_ascii_case("true") {
+            return Self::Boolean(true);
+        }
+        if trimmed.eq_ignore_ascii_case("false") {
+            return Self::Boolean(false);
+        }
+        if is_plain_number(trimmed)
+            && let Ok(number) = trimmed.parse()
+        {
+            return Self::Number(number);
+        }
+        Self::Text(text.to_string())
+    }
+
+    /// Compare two cells for sorting. Empty cells sort first, followed by booleans, numbers and
+    /// then text, which is compared ignoring case.
+    #[must_use]
+    pub fn compare(&self, other: &Self) -> Ordering {
+        /// The order of each type of cell relative to the others
+        fn rank(cell: &TableCell) -> u8 {
+            match cell {
+                TableCell::Empty => 0,
+                TableCell::Boolean(_) => 1,
+                TableCell::Number(_) => 2,
+                TableCell::Text(_) => 3,
+            }
+        }
+
+        match (self, other) {
+            (Self::Boolean(a), Self::Boolean(b)) => a.cmp(b),
+            (Self::Number(a), Self::Number(b)) => a.total_cmp(b),
+            (Self::Text(a), Self::Text(b)) => a.to_lowercase().cmp(&b.to_lowercase()),
+            _ => rank(self).cmp(&rank(other)),
+        }
+    }
+}
+
+impl fmt::Display for TableCell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => Ok(()),
+            Self::Boolean(value) => write!(f, "{value}"),
+            Self::Number(value) => write!(f, "{value}"),
+            Self::Text(value) => write!(f, "{value}"),
+        }
+    }
+}
+
+impl From<serde_json::Value> for TableCell {
+    fn from(value: serde_json::Value) -> Self {
+        match value {
+            serde_json::Value::Null => Self::Empty,
+            serde_json::Value::Bool(value) => Self::Boolean(value),
+            serde_json::Value::Number(value) => value
+                .as_f64()
+                .map_or_else(|| Self::Text(value.to_string()), Self::Number),
+            serde_json::Value::String(value) => Self::Text(value),
+            value => Self::Text(value.to_string()),
+        }
+    }
+}
+
+/// The format of table text to parse.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum TableFormat {
+    /// Detect the format from the content.
+    #[default]
+    Auto,
+    /// Comma separated values, with the column headers on the first line.
+    Csv,
+    /// Tab separated values, with the column headers on the first line.
+    Tsv,
+    /// A JSON array of objects or arrays.
+    Json,
+}
+
+impl TableFormat {
+    /// Detect the format of some table text. JSON is detected by its content, and otherwise TSV
+    /// is assumed if the first line contains a tab.
+    #[must_use]
+    pub fn detect(text: &str) -> Self {
+        let trimmed = text.trim_start();
+        if trimmed.starts_with('[')
+            && serde_json::from_str::<serde::de::IgnoredAny>(trimmed).is_ok()
+        {
+            Self::Json
+        } else if trimmed
+            .lines()
+            .next()
+            .is_some_and(|line| line.contains('\t'))
+        {
+            Self::Tsv
+        } else {
+            Self::Csv
+        }
+    }
+}
+
+impl FromStr for TableFormat {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "auto" => Ok(Self::Auto),
+            "csv" => Ok(Self::Csv),
+            "tsv" | "tab" => Ok(Self::Tsv),
+            "json" => Ok(Self::Json),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Determine if some text is a plain decimal number, such as `-12.5`, without leading zeros,
+/// exponents or a leading `+`, and short enough to be represented exactly.
+fn is_plain_number(text: &str) -> bool {
+    let unsigned = text.strip_prefix('-').unwrap_or(text);
+    let (whole, fraction) = match unsigned.split_once('.') {
+        Some((whole, fraction)) => (whole, Some(fraction)),
+        None => (unsigned, None),
+    };
+    let is_digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    is_digits(whole)
+        && whole.len() <= MAX_NUMBER_DIGITS
+        && (whole == "0" || !whole.starts_with('0'))
+        && fraction.is_none_or(is_digits)
+}
+
+/// Split delimited text into records of fields, following RFC 4180 quoting rules.
+fn split_records(text: &str, delimiter: char) -> Vec<Vec<String>> {
+    let mut records = vec![];
+    let mut record = vec![];
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                c => field.push(c),
+            }
+        } else {
+            match c {
+                '"' if field.is_empty() => in_quotes = true,
+                '\r' if chars.peek() == Some(&'\n') => (),
+                '\n' => {
+                    record.push(std::mem::take(&mut field));
+                    records.push(std::mem::take(&mut record));
+                }
+                c if c == delimiter => record.push(std::mem::take(&mut field)),
+                c => field.push(c),
+            }
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    // Skip blank lines
+    records.retain(|record| !(record.len() == 1 && record[0].trim().is_empty()));
+    records
+}
+
+/// Parse a table from delimited text, using the first record as the column headers.
+fn parse_delimited(text: &str, delimiter: char) -> Table {
+    let mut records = split_records(text, delimiter).into_iter();
+    let columns = records.next().unwrap_or_default();
+    let rows = records
+        .map(|record| {
+            record
+                .iter()
+                .map(|field| TableCell::detect(field))
+                .collect()
+        })
+        .collect();
+    Table::new(columns, rows)
+}
+
+/// Parse a table from a JSON array of objects or arrays.
+fn parse_json(text: &str) -> crate::Result<Table> {
+    let invalid = |message: &str| crate::Error::InvalidEvidenceData(message.to_string());
+    let serde_json::Value::Array(items) =
+        serde_json::from_str(text).map_err(|e| crate::Error::InvalidEvidenceData(e.to_string()))?
+    else {
+        return Err(invalid("expected a JSON array"));
+    };
+
+    if items.iter().all(serde_json::Value::is_object) {
+        // Columns are every key found, in the order they are first seen
+        let mut columns: Vec<String> = vec![];
+        for item in &items {
+            if let serde_json::Value::Object(object) = item {
+                for key in object.keys() {
+                    if !columns.contains(key) {
+                        columns.push(key.clone());
+                    }
+                }
+            }
+        }
+        let rows = items
+            .into_iter()
+            .filter_map(|item| match item {
+                serde_json::Value::Object(mut object) => Some(
+                    columns
+                        .iter()
+                        .map(|column| {
+                            object
+                                .remove(column)
+                                .map(TableCell::from)
+                                .unwrap_or_default()
+                        })
+                        .collect(),
+                ),
+                _ => None,
+            })
+            .collect();
+        Ok(Table::new(columns, rows))
+    } else if items.iter().all(serde_json::Value::is_array) {
+        let mut rows = items.into_iter().filter_map(|item| match item {
+            serde_json::Value::Array(cells) => {
+                Some(cells.into_iter().map(TableCell::from).collect::<Vec<_>>())
+            }
+            _ => None,
+        });
+        let columns = rows
+            .next()
+            .unwrap_or_default()
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        Ok(Table::new(columns, rows.collect()))
+    } else {
+        Err(invalid(
+            "expected an array of objects or an array of arrays",
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Make a text cell.
+    fn text(value: &str) -> TableCell {
+        TableCell::Text(value.to_string())
+    }
+
+    #[test]
+    fn empty_input_has_no_columns_or_rows() {
+        for format in [TableFormat::Auto, TableFormat::Csv, TableFormat::Tsv] {
+            let table = Table::parse("", format).unwrap();
+            assert!(table.columns().is_empty());
+            assert!(table.rows().is_empty());
+        }
+        let table = Table::parse("[]", TableFormat::Json).unwrap();
+        assert!(table.columns().is_empty());
+        assert!(table.rows().is_empty());
+    }
+
+    #[test]
+    fn csv_is_parsed() {
+        let table = Table::parse("name,count,active\nwidget,3,true\n", TableFormat::Auto).unwrap();
+        assert_eq!(table.columns(), &["name", "count", "active"]);
+        assert_eq!(
+            table.rows(),
+            &[vec![
+                text("widget"),
+                TableCell::Number(3.0),
+                TableCell::Boolean(true)
+            ]]
+        );
+    }
+
+    #[test]
+    fn tsv_is_detected() {
+        assert_eq!(TableFormat::detect("a\tb\n1\t2"), TableFormat::Tsv);
+        let table = Table::parse("a\tb\n1,5\t2", TableFormat::Auto).unwrap();
+        assert_eq!(table.columns(), &["a", "b"]);
+        assert_eq!(table.rows(), &[vec![text("1,5"), TableCell::Number(2.0)]]);
+    }
+
+    #[test]
+    fn crlf_line_endings_are_removed() {
+        let table = Table::parse("a,b\r\n1,x\r\n\r\n2,y\r\n", TableFormat::Csv).unwrap();
+        assert_eq!(table.columns(), &["a", "b"]);
+        assert_eq!(
+            table.rows(),
+            &[
+                vec![TableCell::Number(1.0), text("x")],
+                vec![TableCell::Number(2.0), text("y")],
+            ]
+        );
+    }
+
+    #[test]
+    fn quoted_fields_are_unescaped() {
+        let table = Table::parse(
+            "a,b\n\"x, y\",\"say \"\"hi\"\"\"\n\"multi\r\nline\",z",
+            TableFormat::Csv,
+        )
+        .unwrap();
+        assert_eq!(
+            table.rows(),
+            &[
+                vec![text("x, y"), text("say \"hi\"")],
+                vec![text("multi\r\nline"), text("z")],
+            ]
+        );
+    }
+
+    #[test]
+    fn unterminated_quotes_do_not_panic() {
+        let table = Table::parse("a,b\n\"unterminated,1\n2,3", TableFormat::Csv).unwrap();
+        assert_eq!(table.columns(), &["a", "b"]);
+        assert_eq!(
+            table.rows(),
+            &[vec![text("unterminated,1\n2,3"), TableCell::Empty]]
+        );
+    }
+
+    #[test]
+    fn ragged_rows_are_padded() {
+        let table = Table::parse("a,b,c\n1\n1,2,3,4\n", TableFormat::Csv).unwrap();
+        assert_eq!(table.columns(), &["a", "b", "c", "Column 4"]);
+        assert_eq!(
+            table.rows(),
+            &[
+                vec![
+                    TableCell::Number(1.0),
+                    TableCell::Empty,
+                    TableCell::Empty,
+                    TableCell::Empty,
+                ],
+                vec![
+                    TableCell::Number(1.0),
+                    TableCell::Number(2.0),
+                    TableCell::Number(3.0),
+                    TableCell::Number(4.0),
+                ],
+            ]
+        );
+    }
+
+    #[test]
+    fn header_only_has_no_rows() {
+        let table = Table::parse("a,b\n", TableFormat::Csv).unwrap();
+        assert_eq!(table.columns(), &["a", "b"]);
+        assert!(table.rows().is_empty());
+    }
+
+    #[test]
+    fn cells_are_detected() {
+        assert_eq!(TableCell::detect(""), TableCell::Empty);
+        assert_eq!(TableCell::detect("  "), TableCell::Empty);
+        assert_eq!(TableCell::detect("FALSE"), TableCell::Boolean(false));
+        assert_eq!(TableCell::detect("-12.5"), TableCell::Number(-12.5));
+        assert_eq!(TableCell::detect("0.5"), TableCell::Number(0.5));
+        for kept in [
+            "007",
+            "+1",
+            "1e5",
+            "1.",
+            ".5",
+            "-",
+            "NaN",
+            "inf",
+            "1234567890123456",
+        ] {
+            assert_eq!(TableCell::detect(kept), text(kept), "{kept}");
+        }
+    }
+
+    #[test]
+    fn json_objects_are_parsed() {
+        let table = Table::parse(
+            r#"[{"a": 1, "b": "x"}, {"b": null, "c": [1]}]"#,
+            TableFormat::Auto,
+        )
+        .unwrap();
+        assert_eq!(table.columns(), &["a", "b", "c"]);
+        assert_eq!(
+            table.rows(),
+            &[
+                vec![TableCell::Number(1.0), text("x"), TableCell::Empty],
+                vec![TableCell::Empty, TableCell::Empty, text("[1]")],
+            ]
+        );
+    }
+
+    #[test]
+    fn json_arrays_are_parsed() {
+        let table = Table::parse(r#"[["a", "b"], [true], [1, 2, 3]]"#, TableFormat::Json).unwrap();
+        assert_eq!(table.columns(), &["a", "b", "Column 3"]);
+        assert_eq!(
+            table.rows(),
+            &[
+                vec![TableCell::Boolean(true), TableCell::Empty, TableCell::Empty],
+                vec![
+                    TableCell::Number(1.0),
+                    TableCell::Number(2.0),
+                    TableCell::Number(3.0),
+                ],
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_json_is_an_error() {
+        for json in ["", "{", "{}", "[1, 2]", r#"[{"a": 1}, [1]]"#] {
+            assert!(
+                matches!(
+                    Table::parse(json, TableFormat::Json),
+                    Err(crate::Error::InvalidEvidenceData(_))
+                ),
+                "{json}"
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_json_is_read_as_csv_when_detecting() {
+        assert_eq!(TableFormat::detect("[not json"), TableFormat::Csv);
+        let table = Table::parse("[not json", TableFormat::Auto).unwrap();
+        assert_eq!(table.columns(), &["[not json"]);
+    }
+
+    #[test]
+    fn cells_are_compared() {
+        let mut cells = vec![
+            text("b"),
+            TableCell::Number(2.0),
+            text("A"),
+            TableCell::Empty,
+            TableCell::Boolean(true),
+            TableCell::Number(-1.0),
+        ];
+        cells.sort_by(TableCell::compare);
+        assert_eq!(
+            cells,
+            [
+                TableCell::Empty,
+                TableCell::Boolean(true),
+                TableCell::Number(-1.0),
+                TableCell::Number(2.0),
+                text("A"),
+                text("b"),
+            ]
+        );
+    }
+}
@@ -1,8 +1,11 @@
+use chrono::{DateTime, FixedOffset};
 use getset::{Getters, MutGetters, Setters};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use std::{collections::HashMap, fmt};
+use std::{collections::HashMap, fmt, time::Duration};
+
+use super::{RedactionSettings, Requirement, TemplateLibrary};
 
 /// [`EvidencePackage`](super::EvidencePackage) metadata.
 #[derive(Clone, Debug, Getters, MutGetters, Setters, Serialize, Deserialize)]
@@ -23,6 +26,18 @@ pub struct Metadata {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(super) custom_test_case_metadata: Option<HashMap<String, CustomMetadataField>>,
 
+    /// How secrets are redacted from evidence in this package
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) redaction: Option<RedactionSettings>,
+
+    /// Templates for creating test cases in this package
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) templates: Option<TemplateLibrary>,
+
+    /// The catalogue of requirements that test cases in this package can cover
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) requirements: Option<Vec<Requirement>>,
+
     /// Extra fields that this implementation doesn't understand.
     #[get = "pub"]
     #[serde(flatten)]
@@ -40,6 +55,37 @@ impl Metadata {
         self.custom_test_case_metadata.as_mut().unwrap()
     }
 
+    /// Get a mutable reference to how secrets are redacted from evidence in this package
+    #[allow(clippy::missing_panics_doc, reason = "safety is explained inline")]
+    pub fn redaction_mut(&mut self) -> &mut RedactionSettings {
+        if self.redaction.is_none() {
+            self.redaction = Some(RedactionSettings::default());
+        }
+        // SAFETY: just initialised if wasn't previously
+        self.redaction.as_mut().unwrap()
+    }
+
+    /// Get a mutable reference to templates for creating test cases in this package
+    #[allow(clippy::missing_panics_doc, reason = "safety is explained inline")]
+    pub fn templates_mut(&mut self) -> &mut TemplateLibrary {
+        if self.templates.is_none() {
+            self.templates = Some(TemplateLibrary::default());
+        }
+        // SAFETY: just initialised if wasn't previously
+        self.templates.as_mut().unwrap()
+    }
+
+    /// Get a mutable reference to the catalogue of requirements that test cases in this package
+    /// can cover
+    #[allow(clippy::missing_panics_doc, reason = "safety is explained inline")]
+    pub fn requirements_mut(&mut self) -> &mut Vec<Requirement> {
+        if self.requirements.is_none() {
+            self.requirements = Some(vec![]);
+        }
+        // SAFETY: just initialised if wasn't previously
+        self.requirements.as_mut().unwrap()
+    }
+
     /// Create a new custom metadata field
     pub fn insert_custom_metadata_field(
         &mut self,
@@ -139,7 +185,26 @@ pub(super) struct MediaFileManifestEntry {
     /// The SHA256 checksum of the media file.
     sha256_checksum: String,
     /// The MIME type of the media file.
+    #[serde(deserialize_with = "deserialize_mime_type")]
     mime_type: String,
+    /// The size of the media file in bytes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    size: Option<u64>,
+    /// The name of the file this media was originally loaded from.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    original_filename: Option<String>,
+    /// The time this media was captured.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    captured_at: Option<DateTime<FixedOffset>>,
+    /// The width of the media in pixels, if it is an image.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    image_width: Option<u32>,
+    /// The height of the media in pixels, if it is an image.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    image_height: Option<u32>,
+    /// The duration of the media in milliseconds, if it is a video.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    video_duration_ms: Option<u64>,
 
     /// Extra fields that this implementation doesn't understand.
     #[get = "pub"]
@@ -147,14 +212,48 @@ pub(super) struct MediaFileManifestEntry {
     extra_fields: HashMap<String, serde_json::Value>,
 }
 
+/// Read the MIME type of a media file, rejecting any that aren't valid, as they are used in
+/// exported documents. `unknown` is accepted, as it was recorded by older versions.
+fn deserialize_mime_type<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let mime_type = String::deserialize(deserializer)?;
+    if mime_type != "unknown" && !crate::MediaFile::is_valid_mime_type(&mime_type) {
+        return Err(serde::de::Error::custom(format!(
+            "invalid MIME type {mime_type:?}"
+        )));
+    }
+    Ok(mime_type)
+}
+
+impl MediaFileManifestEntry {
+    /// Restore the details recorded in this manifest entry onto media read from the package.
+    pub(super) fn apply_to(&self, media: &mut crate::MediaFile) {
+        media.set_original_filename(self.original_filename.clone());
+        media.set_captured_at(self.captured_at);
+        media.set_video_duration(self.video_duration_ms.map(Duration::from_millis));
+        // "unknown" was recorded by older versions when the type couldn't be detected
+        if self.mime_type != "unknown" {
+            media.set_explicit_mime_type(Some(self.mime_type.clone()));
+        }
+    }
+}
+
 impl From<&crate::MediaFile> for MediaFileManifestEntry {
     fn from(value: &crate::MediaFile) -> Self {
+        let dimensions = value.image_dimensions();
         Self {
             sha256_checksum: value.hash(),
-            mime_type: value
-                .mime_type()
-                .map_or("unknown", |t| t.mime_type())
-                .to_string(),
+            mime_type: value.media_type().to_string(),
+            size: Some(value.size()),
+            original_filename: value.original_filename().clone(),
+            captured_at: *value.captured_at(),
+            image_width: dimensions.map(|(width, _)| width),
+            image_height: dimensions.map(|(_, height)| height),
+            video_duration_ms: value
+                .video_duration()
+                .map(|duration| u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)),
             extra_fields: HashMap::new(),
         }
     }
@@ -184,3 +283,80 @@ impl TestCaseManifestEntry {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        io::{Read, Write},
+    };
+
+    use super::*;
+    use crate::{EvidenceBuilder, EvidencePackage, PackageBuilder, TestCaseBuilder};
+
+    /// Make the manifest entry for a media file with a MIME type.
+    fn media_entry(mime_type: &str) -> serde_json::Result<MediaFileManifestEntry> {
+        serde_json::from_value(serde_json::json!({
+            "sha256_checksum": "0".repeat(64),
+            "mime_type": mime_type,
+        }))
+    }
+
+    #[test]
+    fn valid_mime_types_are_read() {
+        assert_eq!(media_entry("text/plain").unwrap().mime_type(), "text/plain");
+        assert_eq!(media_entry("unknown").unwrap().mime_type(), "unknown");
+    }
+
+    #[test]
+    fn invalid_mime_types_are_rejected() {
+        for mime_type in [
+            "",
+            "text",
+            "text/plain; charset=utf-8",
+            "text/plain\" onmouseover=\"alert(document.domain)\" x=\"",
+        ] {
+            assert!(media_entry(mime_type).is_err(), "{mime_type:?}");
+        }
+    }
+
+    #[test]
+    fn packages_with_invalid_mime_types_are_not_opened() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("package.evp");
+        PackageBuilder::new(&path, "Package")
+            .test_case(
+                TestCaseBuilder::new("Test")
+                    .evidence(EvidenceBuilder::file_data("a.bin", b"data".to_vec())),
+            )
+            .build()
+            .unwrap();
+
+        // Rewrite the package with a MIME type that would break out of an HTML attribute
+        let mut archive = zip::ZipArchive::new(fs::File::open(&path).unwrap()).unwrap();
+        let tampered_path = dir.path().join("tampered.evp");
+        let mut writer = zip::ZipWriter::new(fs::File::create(&tampered_path).unwrap());
+        for idx in 0..archive.len() {
+            let mut file = archive.by_index(idx).unwrap();
+            let mut data = vec![];
+            file.read_to_end(&mut data).unwrap();
+            if file.name() == "manifest.json" {
+                let mut manifest: serde_json::Value = serde_json::from_slice(&data).unwrap();
+                manifest["media"][0]["mime_type"] =
+                    "text/plain\" onmouseover=\"alert(document.domain)\" x=\"".into();
+                data = serde_json::to_vec(&manifest).unwrap();
+            }
+            writer
+                .start_file(file.name(), zip::write::SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(&data).unwrap();
+        }
+        writer.finish().unwrap();
+
+        assert!(EvidencePackage::open(path).is_ok());
+        assert!(matches!(
+            EvidencePackage::open(tampered_path),
+            Err(crate::Error::ManifestSchemaValidationFailed)
+        ));
+    }
+}
@@ -1,12 +1,67 @@
-use getset::Getters;
-use std::fmt;
+use chrono::{DateTime, FixedOffset};
+use getset::{Getters, Setters};
+use std::{fmt, path::Path, time::Duration};
+
+/// File extensions and the MIME type they are associated with. This is used both to detect
+/// text-based formats, which can't be detected from their content alone, and to pick an
+/// extension for a MIME type.
+const EXTENSION_MIME_TYPES: &[(&str, &str)] = &[
+    ("txt", "text/plain"),
+    ("log", "text/plain"),
+    ("csv", "text/csv"),
+    ("tsv", "text/tab-separated-values"),
+    ("json", "application/json"),
+    ("jsonl", "application/jsonl"),
+    ("har", "application/json"),
+    ("xml", "application/xml"),
+    ("html", "text/html"),
+    ("htm", "text/html"),
+    ("md", "text/markdown"),
+    ("yaml", "application/yaml"),
+    ("yml", "application/yaml"),
+    ("sql", "application/sql"),
+    ("css", "text/css"),
+    ("js", "text/javascript"),
+    ("png", "image/png"),
+    ("jpg", "image/jpeg"),
+    ("gif", "image/gif"),
+    ("webp", "image/webp"),
+    ("bmp", "image/bmp"),
+    ("pdf", "application/pdf"),
+    ("zip", "application/zip"),
+    ("mp4", "video/mp4"),
+    ("webm", "video/webm"),
+    ("bin", "application/octet-stream"),
+];
+
+/// The MIME types of videos that can be used as video evidence.
+const SUPPORTED_VIDEO_MIME_TYPES: &[&str] = &["video/mp4", "video/webm"];
+
+/// The MIME type used for data that can't be identified.
+const FALLBACK_MIME_TYPE: &str = "application/octet-stream";
 
 /// A media file stored within an [`EvidencePackage`](super::EvidencePackage).
-#[derive(Clone, Getters)]
+#[derive(Clone, Getters, Setters)]
 pub struct MediaFile {
     /// The raw data of this media file.
     #[getset(get = "pub")]
     data: Vec<u8>,
+    /// The name of the file this media was originally loaded from, if known.
+    #[getset(get = "pub")]
+    original_filename: Option<String>,
+    /// The time this media was captured, if known.
+    #[getset(get = "pub", set = "pub")]
+    captured_at: Option<DateTime<FixedOffset>>,
+    /// A MIME type explicitly set by the creator of this media, which takes precedence over any
+    /// detected type.
+    explicit_mime_type: Option<String>,
+    /// The duration of this media, if it is a video whose duration was set explicitly. This takes
+    /// precedence over any duration read from the video itself.
+    #[getset(set = "pub")]
+    video_duration: Option<Duration>,
+    /// The MIME type of this media, determined when it was created or its filename or explicit
+    /// MIME type changed.
+    media_type: String,
 }
 
 impl fmt::Debug for MediaFile {
@@ -22,15 +77,455 @@ impl MediaFile {
         sha256::digest(&self.data)
     }
 
-    /// Determine the MIME type of this data.
+    /// Get the size of this data in bytes.
+    #[must_use]
+    pub fn size(&self) -> u64 {
+        self.data.len() as u64
+    }
+
+    /// Determine the MIME type of this data from its content. This only recognises binary
+    /// formats. See [`MediaFile::media_type`] for the MIME type stored in the package.
     #[must_use]
     pub fn mime_type(&self) -> Option<infer::Type> {
         infer::get(&self.data)
     }
+
+    /// Get the MIME type of this data, such as `image/png`.
+    ///
+    /// A valid explicitly set MIME type is always preferred. Otherwise, the type is detected from the
+    /// content of binary formats, then from the extension of the original filename, and then by
+    /// checking if the content is text. If none of these succeed, `application/octet-stream` is
+    /// used.
+    #[must_use]
+    pub fn media_type(&self) -> &str {
+        &self.media_type
+    }
+
+    /// Set the name of the file this media was originally loaded from.
+    pub fn set_original_filename(&mut self, original_filename: Option<String>) -> &mut Self {
+        self.original_filename = original_filename;
+        self.media_type = self.detect_media_type();
+        self
+    }
+
+    /// Set a MIME type for this media, which takes precedence over any detected type. Types that
+    /// aren't valid, as described by [`MediaFile::is_valid_mime_type`], are ignored.
+    pub fn set_explicit_mime_type(&mut self, explicit_mime_type: Option<String>) -> &mut Self {
+        self.explicit_mime_type = explicit_mime_type;
+        self.media_type = self.detect_media_type();
+        self
+    }
+
+    /// Determine if some text is a valid MIME type, made up of a type and subtype such as
+    /// `image/png`, without parameters. Each part must follow the restricted name grammar of
+    /// RFC 6838, so can't contain quotes, spaces or anything else that would need escaping.
+    #[must_use]
+    pub fn is_valid_mime_type(mime_type: &str) -> bool {
+        /// Whether some text is a restricted name, as used for types and subtypes
+        fn is_restricted_name(name: &str) -> bool {
+            name.len() <= 127
+                && name
+                    .bytes()
+                    .next()
+                    .is_some_and(|b| b.is_ascii_alphanumeric())
+                && name.bytes().all(|b| {
+                    b.is_ascii_alphanumeric()
+                        || matches!(
+                            b,
+                            b'!' | b'#' | b'$' | b'&' | b'-' | b'^' | b'_' | b'.' | b'+'
+                        )
+                })
+        }
+
+        mime_type
+            .split_once('/')
+            .is_some_and(|(kind, subtype)| is_restricted_name(kind) && is_restricted_name(subtype))
+    }
+
+    /// Determine the MIME type of this data, as described by [`MediaFile::media_type`].
+    fn detect_media_type(&self) -> String {
+        if let Some(mime_type) = &self.explicit_mime_type
+            && Self::is_valid_mime_type(mime_type)
+        {
+            return mime_type.clone();
+        }
+        if let Some(kind) = infer::get(&self.data) {
+            return kind.mime_type().to_string();
+        }
+        if let Some(mime_type) = self
+            .filename_extension()
+            .and_then(|ext| mime_type_for_extension(&ext))
+        {
+            return mime_type.to_string();
+        }
+        if let Ok(text) = std::str::from_utf8(&self.data) {
+            let trimmed = text.trim_start();
+            if (trimmed.starts_with('{') || trimmed.starts_with('['))
+                && serde_json::from_str::<serde::de::IgnoredAny>(text).is_ok()
+            {
+                return "application/json".to_string();
+            }
+            if trimmed.starts_with("<?xml") {
+                return "application/xml".to_string();
+            }
+            return "text/plain".to_string();
+        }
+        FALLBACK_MIME_TYPE.to_string()
+    }
+
+    /// Determine a suitable file extension (without a leading `.`) for this data, taken from the
+    /// original filename if present, or otherwise derived from the MIME type.
+    #[must_use]
+    pub fn extension(&self) -> Option<String> {
+        if let Some(ext) = self.filename_extension() {
+            return Some(ext);
+        }
+        if self.explicit_mime_type.is_none()
+            && let Some(kind) = infer::get(&self.data)
+        {
+            return Some(kind.extension().to_string());
+        }
+        extension_for_mime_type(&self.media_type).map(ToString::to_string)
+    }
+
+    /// Suggest a filename for this media. The preferred name is used if provided, then the
+    /// original filename, and then the hash. If the chosen name has no extension, one is added
+    /// based on the type of this media.
+    #[must_use]
+    pub fn suggested_filename(&self, preferred_name: Option<&str>) -> String {
+        let name = preferred_name
+            .map(ToString::to_string)
+            .or_else(|| self.original_filename.clone())
+            .unwrap_or_else(|| self.hash());
+        if Path::new(&name).extension().is_some() {
+            return name;
+        }
+        match self.extension() {
+            Some(ext) => format!("{name}.{ext}"),
+            None => name,
+        }
+    }
+
+    /// Determine the width and height of this media in pixels, if it is a PNG, JPEG, GIF, BMP or
+    /// WebP image. This is always [`None`] without the `image-processing` feature.
+    #[must_use]
+    pub fn image_dimensions(&self) -> Option<(u32, u32)> {
+        image_dimensions(&self.data)
+    }
+
+    /// Determine the duration of this media, if it is a video. An explicitly set duration is
+    /// preferred, otherwise the duration is read from the header of MP4 and `WebM` videos.
+    #[must_use]
+    pub fn video_duration(&self) -> Option<Duration> {
+        self.video_duration.or_else(|| video_duration(&self.data))
+    }
+
+    /// Determine if this media is a video that can be used as [`Video`](crate::EvidenceKind::Video)
+    /// evidence, i.e. an MP4 or `WebM` video.
+    #[must_use]
+    pub fn is_supported_video(&self) -> bool {
+        SUPPORTED_VIDEO_MIME_TYPES.contains(&self.media_type.as_str())
+    }
+
+    /// Get the lowercase extension of the original filename, if present.
+    fn filename_extension(&self) -> Option<String> {
+        self.original_filename
+            .as_ref()
+            .and_then(|name| Path::new(name).extension())
+            .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+    }
 }
 
 impl<D: Into<Vec<u8>>> From<D> for MediaFile {
     fn from(value: D) -> Self {
-        Self { data: value.into() }
+        let mut media = Self {
+            data: value.into(),
+            original_filename: None,
+            captured_at: None,
+            explicit_mime_type: None,
+            video_duration: None,
+            media_type: String::new(),
+        };
+        media.media_type = media.detect_media_type();
+        media
+    }
+}
+
+/// Get the MIME type associated with a file extension.
+fn mime_type_for_extension(extension: &str) -> Option<&'static str> {
+    EXTENSION_MIME_TYPES
+        .iter()
+        .find(|(ext, _)| *ext == extension)
+        .map(|(_, mime)| *mime)
+}
+
+/// Get a file extension for a MIME type.
+fn extension_for_mime_type(mime_type: &str) -> Option<&'static str> {
+    EXTENSION_MIME_TYPES
+        .iter()
+        .find(|(_, mime)| *mime == mime_type)
+        .map(|(ext, _)| *ext)
+}
+
+/// Read the dimensions of an image from its header.
+#[cfg(feature = "image-processing")]
+fn image_dimensions(data: &[u8]) -> Option<(u32, u32)> {
+    image::ImageReader::new(std::io::Cursor::new(data))
+        .with_guessed_format()
+        .ok()?
+        .into_dimensions()
+        .ok()
+}
+
+/// Images can't be read without the `image-processing` feature.
+#[cfg(not(feature = "image-processing"))]
+fn image_dimensions(_data: &[u8]) -> Option<(u32, u32)> {
+    None
+}
+
+/// Read the duration of an MP4 or `WebM` video from its header.
+fn video_duration(data: &[u8]) -> Option<Duration> {
+    match infer::get(data)?.mime_type() {
+        "video/mp4" | "video/quicktime" | "video/x-m4v" => mp4_duration(data),
+        "video/webm" | "video/x-matroska" => matroska_duration(data),
+        _ => None,
+    }
+}
+
+/// Read the duration of an MP4 video from the movie header (`moov/mvhd`) box.
+fn mp4_duration(data: &[u8]) -> Option<Duration> {
+    /// Read a big-endian u32 at an offset
+    fn be_u32(data: &[u8], at: usize) -> Option<u64> {
+        Some(u64::from(u32::from_be_bytes(
+            data.get(at..at + 4)?.try_into().ok()?,
+        )))
+    }
+    /// Read a big-endian u64 at an offset
+    fn be_u64(data: &[u8], at: usize) -> Option<u64> {
+        Some(u64::from_be_bytes(data.get(at..at + 8)?.try_into().ok()?))
+    }
+    /// Find the content of the first box with a type within some data
+    fn find_box<'a>(data: &'a [u8], box_type: &[u8]) -> Option<&'a [u8]> {
+        let mut at = 0;
+        while at + 8 <= data.len() {
+            let (size, header) = match be_u32(data, at)? {
+                // The size is stored as a u64 after the type
+                1 => (be_u64(data, at + 8)?, 16),
+                // The box extends to the end of the data
+                0 => ((data.len() - at) as u64, 8),
+                size => (size, 8),
+            };
+            let size = usize::try_from(size).ok()?;
+            if size < header {
+                return None;
+            }
+            if data.get(at + 4..at + 8)? == box_type {
+                return data.get(at + header..at + size);
+            }
+            at += size;
+        }
+        None
+    }
+
+    let mvhd = find_box(find_box(data, b"moov")?, b"mvhd")?;
+    let (timescale, duration) = match mvhd.first()? {
+        0 => (be_u32(mvhd, 12)?, be_u32(mvhd, 16)?),
+        1 => (be_u32(mvhd, 20)?, be_u64(mvhd, 24)?),
+        _ => return None,
+    };
+    if timescale == 0 || duration == u64::MAX || duration == u64::from(u32::MAX) {
+        return None;
+    }
+    Some(
+        Duration::from_secs(duration / timescale)
+            + Duration::from_nanos((duration % timescale) * 1_000_000_000 / timescale),
+    )
+}
+
+/// Read the duration of a `WebM` or Matroska video from the `Segment/Info` element.
+fn matroska_duration(data: &[u8]) -> Option<Duration> {
+    /// The ID of the segment element
+    const SEGMENT: u64 = 0x1853_8067;
+    /// The ID of the segment information element
+    const INFO: u64 = 0x1549_A966;
+    /// The ID of the first cluster element, after which the information element won't be found
+    const CLUSTER: u64 = 0x1F43_B675;
+    /// The ID of the timestamp scale element
+    const TIMESTAMP_SCALE: u64 = 0x2A_D7B1;
+    /// The ID of the duration element
+    const DURATION: u64 = 0x4489;
+
+    /// Read a variable length integer at an offset, returning the value and its length. IDs keep
+    /// their length marker, whereas sizes don't. Sizes with all bits set are unknown.
+    fn read_vint(data: &[u8], at: usize, keep_marker: bool) -> Option<(Option<u64>, usize)> {
+        let first = *data.get(at)?;
+        let len = first.leading_zeros() as usize + 1;
+        if len > 8 {
+            return None;
+        }
+        let mut value = if keep_marker {
+            u64::from(first)
+        } else {
+            u64::from(first) & (0xFF >> len)
+        };
+        for byte in data.get(at + 1..at + len)? {
+            value = (value << 8) | u64::from(*byte);
+        }
+        let unknown = !keep_marker && value == (1 << (7 * len)) - 1;
+        Some(((!unknown).then_some(value), len))
+    }
+    /// Iterate over the elements within some data, giving their ID and content. An element of
+    /// unknown size extends to the end of the data.
+    fn elements(data: &[u8]) -> impl Iterator<Item = (u64, &[u8])> {
+        let mut at = 0;
+        std::iter::from_fn(move || {
+            let (id, id_len) = read_vint(data, at, true)?;
+            let (size, size_len) = read_vint(data, at + id_len, false)?;
+            let start = at + id_len + size_len;
+            let end = size.map_or(Some(data.len()), |size| {
+                start.checked_add(usize::try_from(size).ok()?)
+            })?;
+            let content = data.get(start..end.min(data.len()))?;
+            at = end;
+            Some((id?, content))
+        })
+    }
+
+    let (_, segment) = elements(data).find(|(id, _)| *id == SEGMENT)?;
+    let (_, info) = elements(segment)
+        .take_while(|(id, _)| *id != CLUSTER)
+        .find(|(id, _)| *id == INFO)?;
+
+    let mut timestamp_scale = 1_000_000.0;
+    let mut duration = None;
+    for (id, content) in elements(info) {
+        match id {
+            TIMESTAMP_SCALE => {
+                #[allow(clippy::cast_precision_loss)]
+                let scale = content
+                    .iter()
+                    .fold(0_u64, |value, byte| (value << 8) | u64::from(*byte))
+                    as f64;
+                timestamp_scale = scale;
+            }
+            DURATION => {
+                duration = match content.len() {
+                    4 => Some(f64::from(f32::from_be_bytes(content.try_into().ok()?))),
+                    8 => Some(f64::from_be_bytes(content.try_into().ok()?)),
+                    _ => None,
+                };
+            }
+            _ => (),
+        }
+    }
+    Duration::try_from_secs_f64(duration? * timestamp_scale / 1_000_000_000.0).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "image-processing")]
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[cfg(feature = "image-processing")]
+    /// Encode a blank image of a size in a format.
+    fn encode_image(width: u32, height: u32, format: image::ImageFormat) -> Vec<u8> {
+        let mut buf = Cursor::new(vec![]);
+        image::DynamicImage::new_rgb8(width, height)
+            .write_to(&mut buf, format)
+            .unwrap();
+        buf.into_inner()
+    }
+
+    #[cfg(feature = "image-processing")]
+    #[test]
+    fn image_dimensions_are_read() {
+        for format in [
+            image::ImageFormat::Png,
+            image::ImageFormat::Jpeg,
+            image::ImageFormat::Gif,
+            image::ImageFormat::Bmp,
+        ] {
+            let media = MediaFile::from(encode_image(12, 34, format));
+            assert_eq!(media.image_dimensions(), Some((12, 34)), "{format:?}");
+        }
+    }
+
+    #[cfg(feature = "image-processing")]
+    #[test]
+    fn non_images_have_no_dimensions() {
+        assert_eq!(
+            MediaFile::from(b"not an image".to_vec()).image_dimensions(),
+            None
+        );
+        assert_eq!(MediaFile::from(vec![]).image_dimensions(), None);
+        // A PNG signature without a header
+        assert_eq!(
+            MediaFile::from(b"\x89PNG\r\n\x1a\n".to_vec()).image_dimensions(),
+            None
+        );
+    }
+
+    #[cfg(feature = "image-processing")]
+    #[test]
+    fn media_type_is_detected() {
+        let png = MediaFile::from(encode_image(1, 1, image::ImageFormat::Png));
+        assert_eq!(png.media_type(), "image/png");
+        assert_eq!(
+            png.mime_type().map(|kind| kind.mime_type()),
+            Some("image/png")
+        );
+        assert_eq!(
+            MediaFile::from(r#"{"a": 1}"#).media_type(),
+            "application/json"
+        );
+        assert_eq!(MediaFile::from("hello").media_type(), "text/plain");
+        assert_eq!(
+            MediaFile::from(vec![0xFF, 0x00, 0xFE]).media_type(),
+            FALLBACK_MIME_TYPE
+        );
+    }
+
+    #[test]
+    fn media_type_follows_filename_and_explicit_type() {
+        let mut media = MediaFile::from("a,b\n1,2\n");
+        assert_eq!(media.media_type(), "text/plain");
+        media.set_original_filename(Some("data.csv".to_string()));
+        assert_eq!(media.media_type(), "text/csv");
+        media.set_explicit_mime_type(Some("application/x-custom".to_string()));
+        assert_eq!(media.media_type(), "application/x-custom");
+        media.set_explicit_mime_type(None);
+        assert_eq!(media.media_type(), "text/csv");
+        media.set_explicit_mime_type(Some("text/plain\" onmouseover=\"alert(1)".to_string()));
+        assert_eq!(media.media_type(), "text/csv");
+    }
+
+    #[test]
+    fn mime_types_are_validated() {
+        for valid in [
+            "image/png",
+            "application/vnd.ms-excel",
+            "application/problem+json",
+            "application/x-custom_type",
+            "video/mp4",
+        ] {
+            assert!(MediaFile::is_valid_mime_type(valid), "{valid}");
+        }
+        for invalid in [
+            "",
+            "unknown",
+            "image/",
+            "/png",
+            "image/png/extra",
+            "text/plain; charset=utf-8",
+            "text/plain\" onmouseover=\"alert(1)",
+            "text/<script>",
+            "text/plain\n",
+            "-text/plain",
+            &format!("text/{}", "a".repeat(128)),
+        ] {
+            assert!(!MediaFile::is_valid_mime_type(invalid), "{invalid:?}");
+        }
     }
 }
@@ -0,0 +1,365 @@
+use std::collections::HashMap;
+
+use getset::{Getters, Setters};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{EvidencePackage, TestCasePassStatus};
+
+/// A requirement that test cases can demonstrate coverage of. Requirements are stored in the
+/// package catalogue, and referenced by ID from test cases.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Getters, Setters)]
+#[getset(get = "pub")]
+pub struct Requirement {
+    /// The ID of this requirement, such as `REQ-12`.
+    id: String,
+    /// The title of this requirement.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(set = "pub")]
+    title: Option<String>,
+    /// A link to this requirement in another system.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(set = "pub")]
+    link: Option<String>,
+
+    /// Extra fields that this implementation doesn't understand.
+    #[get = "pub"]
+    #[serde(flatten)]
+    extra_fields: HashMap<String, serde_json::Value>,
+}
+
+impl Requirement {
+    /// Create a new requirement without a title or link.
+    pub fn new<S: Into<String>>(id: S) -> Self {
+        Self {
+            id: id.into(),
+            title: None,
+            link: None,
+            extra_fields: HashMap::new(),
+        }
+    }
+
+    /// Create a new requirement with a title and link.
+    pub fn new_with_details<S: Into<String>>(
+        id: S,
+        title: Option<String>,
+        link: Option<String>,
+    ) -> Self {
+        let mut requirement = Self::new(id);
+        requirement.title = title;
+        requirement.link = link;
+        requirement
+    }
+}
+
+/// How well a requirement is covered by test cases.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum CoverageStatus {
+    /// No test cases cover this requirement.
+    Uncovered,
+    /// At least one test case covering this requirement failed.
+    Failing,
+    /// Test cases cover this requirement, but not all of them have a status yet.
+    NotRun,
+    /// Every test case covering this requirement passed.
+    Passing,
+}
+
+impl CoverageStatus {
+    /// Work out the coverage of a requirement from the statuses of the test cases covering it.
+    fn from_statuses<I: IntoIterator<Item = Option<TestCasePassStatus>>>(statuses: I) -> Self {
+        let statuses: Vec<_> = statuses.into_iter().collect();
+        if statuses.is_empty() {
+            Self::Uncovered
+        } else if statuses.contains(&Some(TestCasePassStatus::Fail)) {
+            Self::Failing
+        } else if statuses.contains(&None) {
+            Self::NotRun
+        } else {
+            Self::Passing
+        }
+    }
+
+    /// Check if this requirement needs attention, because it is uncovered or failing.
+    #[must_use]
+    pub fn needs_attention(self) -> bool {
+        matches!(self, Self::Uncovered | Self::Failing)
+    }
+}
+
+/// A test case in a [`TraceabilityMatrix`].
+#[derive(Clone, Debug, Getters)]
+#[getset(get = "pub")]
+pub struct TracedTestCase {
+    /// The ID of the test case.
+    id: Uuid,
+    /// The title of the test case.
+    title: String,
+    /// The status of the test case.
+    status: Option<TestCasePassStatus>,
+    /// The IDs of the requirements this test case covers.
+    requirements: Vec<String>,
+}
+
+/// A requirement in a [`TraceabilityMatrix`], and the test cases covering it.
+#[derive(Clone, Debug, Getters)]
+#[getset(get = "pub")]
+pub struct RequirementCoverage {
+    /// The requirement.
+    requirement: Requirement,
+    /// Whether this requirement is in the package catalogue, rather than only referenced by test
+    /// cases.
+    catalogued: bool,
+    /// The IDs of the test cases covering this requirement, in package order.
+    test_cases: Vec<Uuid>,
+    /// How well this requirement is covered.
+    status: CoverageStatus,
+}
+
+impl RequirementCoverage {
+    /// Check if a test case covers this requirement.
+    #[must_use]
+    pub fn is_covered_by(&self, test_case: &Uuid) -> bool {
+        self.test_cases.contains(test_case)
+    }
+}
+
+/// A report of which requirements each test case in a package covers, and the status of each
+/// requirement.
+#[derive(Clone, Debug, Getters)]
+#[getset(get = "pub")]
+pub struct TraceabilityMatrix {
+    /// The requirements, with those in the catalogue first, followed by those only referenced by
+    /// test cases.
+    requirements: Vec<RequirementCoverage>,
+    /// All test cases in the package, in package order.
+    test_cases: Vec<TracedTestCase>,
+}
+
+impl TraceabilityMatrix {
+    /// Check if this matrix has no requirements.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.requirements.is_empty()
+    }
+
+    /// Iterate over the requirements that need attention, because they are uncovered or failing.
+    pub fn needing_attention(&self) -> impl Iterator<Item = &RequirementCoverage> {
+        self.requirements
+            .iter()
+            .filter(|r| r.status.needs_attention())
+    }
+
+    /// Iterate over the test cases that don't cover any requirements.
+    pub fn untraced_test_cases(&self) -> impl Iterator<Item = &TracedTestCase> {
+        self.test_cases
+            .iter()
+            .filter(|tc| tc.requirements.is_empty())
+    }
+}
+
+impl EvidencePackage {
+    /// Create a traceability matrix of the requirements covered by the test cases in this
+    /// package. Requirements referenced by test cases but missing from the catalogue are
+    /// included, using the details from the first test case referencing them.
+    ///
+    /// # Errors
+    ///
+    /// Currently cannot fail.
+    pub fn traceability_matrix(&self) -> crate::Result<TraceabilityMatrix> {
+        let mut requirements: Vec<RequirementCoverage> = self
+            .metadata()
+            .requirements()
+            .iter()
+            .flatten()
+            .map(|requirement| RequirementCoverage {
+                requirement: requirement.clone(),
+                catalogued: true,
+                test_cases: vec![],
+                status: CoverageStatus::Uncovered,
+            })
+            .collect();
+        let mut test_cases = vec![];
+
+        for test_case in self.test_case_iter()? {
+            let mut covered = vec![];
+            for reference in test_case.metadata().requirements().iter().flatten() {
+                if covered.contains(reference.id()) {
+                    continue;
+                }
+                covered.push(reference.id().clone());
+
+                let idx = if let Some(idx) = requirements
+                    .iter()
+                    .position(|r| r.requirement.id == reference.id)
+                {
+                    idx
+                } else {
+                    requirements.push(RequirementCoverage {
+                        requirement: reference.clone(),
+                        catalogued: false,
+                        test_cases: vec![],
+                        status: CoverageStatus::Uncovered,
+                    });
+                    requirements.len() - 1
+                };
+                requirements[idx].test_cases.push(*test_case.id());
+            }
+            test_cases.push(TracedTestCase {
+                id: *test_case.id(),
+                title: test_case.metadata().title().clone(),
+                status: *test_case.metadata().passed(),
+                requirements: covered,
+            });
+        }
+
+        for requirement in &mut requirements {
+            requirement.status = CoverageStatus::from_statuses(
+                test_cases
+                    .iter()
+                    .filter(|tc| requirement.test_cases.contains(&tc.id))
+                    .map(|tc| tc.status),
+            );
+        }
+
+        Ok(TraceabilityMatrix {
+            requirements,
+            test_cases,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PackageBuilder, TestCaseBuilder};
+
+    /// Create a test case covering some requirements.
+    fn test_case(
+        title: &str,
+        status: Option<TestCasePassStatus>,
+        requirements: &[&str],
+    ) -> TestCaseBuilder {
+        requirements.iter().fold(
+            TestCaseBuilder::new(title).status(status),
+            |test_case, id| test_case.requirement(Requirement::new(*id)),
+        )
+    }
+
+    /// Get the coverage status of each requirement in a matrix, by ID.
+    fn statuses(matrix: &TraceabilityMatrix) -> Vec<(&str, CoverageStatus)> {
+        matrix
+            .requirements()
+            .iter()
+            .map(|r| (r.requirement().id().as_str(), *r.status()))
+            .collect()
+    }
+
+    #[test]
+    fn coverage_is_derived_from_test_case_statuses() {
+        use TestCasePassStatus::{Fail, Pass};
+
+        assert_eq!(CoverageStatus::from_statuses([]), CoverageStatus::Uncovered);
+        assert_eq!(
+            CoverageStatus::from_statuses([Some(Pass), Some(Pass)]),
+            CoverageStatus::Passing
+        );
+        assert_eq!(
+            CoverageStatus::from_statuses([Some(Pass), None]),
+            CoverageStatus::NotRun
+        );
+        assert_eq!(
+            CoverageStatus::from_statuses([None, Some(Fail), Some(Pass)]),
+            CoverageStatus::Failing
+        );
+        assert!(CoverageStatus::Uncovered.needs_attention());
+        assert!(CoverageStatus::Failing.needs_attention());
+        assert!(!CoverageStatus::NotRun.needs_attention());
+        assert!(!CoverageStatus::Passing.needs_attention());
+    }
+
+    #[test]
+    fn requirements_are_traced_to_test_cases() {
+        let dir = tempfile::tempdir().unwrap();
+        let package = PackageBuilder::new(dir.path().join("package.evp"), "Package")
+            .requirement(Requirement::new_with_details(
+                "REQ-1",
+                Some("Log in".to_string()),
+                None,
+            ))
+            .requirement(Requirement::new("REQ-2"))
+            .requirement(Requirement::new("REQ-3"))
+            .requirement(Requirement::new("REQ-4"))
+            .test_case(test_case(
+                "Passes",
+                Some(TestCasePassStatus::Pass),
+                &["REQ-1", "REQ-2", "REQ-3", "REQ-1"],
+            ))
+            .test_case(test_case(
+                "Fails",
+                Some(TestCasePassStatus::Fail),
+                &["REQ-2"],
+            ))
+            .test_case(test_case("Not run", None, &["REQ-3", "REQ-5"]))
+            .test_case(test_case("Untraced", None, &[]))
+            .build()
+            .unwrap();
+        let ids: Vec<Uuid> = package
+            .test_case_iter()
+            .unwrap()
+            .map(|tc| *tc.id())
+            .collect();
+
+        let matrix = package.traceability_matrix().unwrap();
+        assert!(!matrix.is_empty());
+        assert_eq!(
+            statuses(&matrix),
+            vec![
+                ("REQ-1", CoverageStatus::Passing),
+                ("REQ-2", CoverageStatus::Failing),
+                ("REQ-3", CoverageStatus::NotRun),
+                ("REQ-4", CoverageStatus::Uncovered),
+                ("REQ-5", CoverageStatus::NotRun),
+            ]
+        );
+
+        let requirements = matrix.requirements();
+        assert_eq!(
+            requirements[0].requirement().title().as_deref(),
+            Some("Log in")
+        );
+        assert_eq!(*requirements[0].test_cases(), vec![ids[0]]);
+        assert_eq!(*requirements[1].test_cases(), vec![ids[0], ids[1]]);
+        assert!(requirements[2].is_covered_by(&ids[2]));
+        assert!(!requirements[2].is_covered_by(&ids[1]));
+        assert!(requirements[3].test_cases().is_empty());
+        assert!(requirements.iter().take(4).all(|r| *r.catalogued()));
+        assert!(!requirements[4].catalogued());
+
+        assert_eq!(
+            *matrix.test_cases()[0].requirements(),
+            vec!["REQ-1", "REQ-2", "REQ-3"]
+        );
+        let attention: Vec<_> = matrix
+            .needing_attention()
+            .map(|r| r.requirement().id().as_str())
+            .collect();
+        assert_eq!(attention, vec!["REQ-2", "REQ-4"]);
+        let untraced: Vec<_> = matrix.untraced_test_cases().map(|tc| *tc.id()).collect();
+        assert_eq!(untraced, vec![ids[3]]);
+    }
+
+    #[test]
+    fn packages_without_requirements_have_empty_matrices() {
+        let dir = tempfile::tempdir().unwrap();
+        let package = PackageBuilder::new(dir.path().join("package.evp"), "Package")
+            .test_case(test_case("Test", Some(TestCasePassStatus::Pass), &[]))
+            .build()
+            .unwrap();
+
+        let matrix = package.traceability_matrix().unwrap();
+        assert!(matrix.is_empty());
+        assert_eq!(matrix.needing_attention().count(), 0);
+        assert_eq!(matrix.untraced_test_cases().count(), 1);
+    }
+}
@@ -0,0 +1,337 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, FixedOffset, TimeDelta};
+use getset::{CopyGetters, Getters};
+
+use super::{EvidenceKind, EvidencePackage, MediaStorage, TestCase, TestCasePassStatus};
+
+/// The number of test cases with each status.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, CopyGetters)]
+#[getset(get_copy = "pub")]
+pub struct StatusCounts {
+    /// The number of test cases that passed.
+    passed: usize,
+    /// The number of test cases that failed.
+    failed: usize,
+    /// The number of test cases without a status.
+    not_run: usize,
+}
+
+impl StatusCounts {
+    /// Count a test case with a status.
+    fn count(&mut self, status: Option<TestCasePassStatus>) {
+        match status {
+            Some(TestCasePassStatus::Pass) => self.passed += 1,
+            Some(TestCasePassStatus::Fail) => self.failed += 1,
+            None => self.not_run += 1,
+        }
+    }
+
+    /// Add the counts from another set of counts to these.
+    fn add(&mut self, other: Self) {
+        self.passed += other.passed;
+        self.failed += other.failed;
+        self.not_run += other.not_run;
+    }
+
+    /// Get the total number of test cases counted.
+    #[must_use]
+    pub fn total(&self) -> usize {
+        self.passed + self.failed + self.not_run
+    }
+
+    /// Get the proportion of test cases with a status that passed, from 0 to 1. Test cases
+    /// without a status are ignored. Returns [`None`] if no test cases have a status.
+    #[must_use]
+    #[allow(
+        clippy::cast_precision_loss,
+        reason = "test case counts are far below the precision of an f64"
+    )]
+    pub fn pass_rate(&self) -> Option<f64> {
+        let run = self.passed + self.failed;
+        if run == 0 {
+            None
+        } else {
+            Some(self.passed as f64 / run as f64)
+        }
+    }
+}
+
+/// Statistics summarising the test cases and evidence in one or more [`EvidencePackage`]s.
+///
+/// Statistics for several packages can be combined with [`PackageStatistics::merge`], for
+/// example to build a dashboard across test runs.
+#[derive(Clone, Debug, Default, Getters, CopyGetters)]
+pub struct PackageStatistics {
+    /// The number of test cases with each status.
+    #[getset(get_copy = "pub")]
+    statuses: StatusCounts,
+    /// The number of test cases with each value of each custom metadata field, by field ID and
+    /// then by value. Test cases without a value for a field aren't counted for that field.
+    #[getset(get = "pub")]
+    custom_fields: HashMap<String, HashMap<String, StatusCounts>>,
+    /// The number of test cases in packages written by each author. Authors belong to
+    /// packages rather than test cases, so every test case is counted for every author of its
+    /// package.
+    #[getset(get = "pub")]
+    authors: HashMap<String, StatusCounts>,
+    /// The number of pieces of evidence of each kind.
+    #[getset(get = "pub")]
+    evidence_kinds: HashMap<EvidenceKind, usize>,
+    /// The number of media files referenced by evidence.
+    #[getset(get_copy = "pub")]
+    media_count: usize,
+    /// The total size of all media referenced by evidence, in bytes, before compression.
+    #[getset(get_copy = "pub")]
+    media_size: u64,
+    /// The size of the largest media file, in bytes, before compression.
+    #[getset(get_copy = "pub")]
+    largest_media_size: Option<u64>,
+    /// When the earliest test case was executed.
+    #[getset(get_copy = "pub")]
+    first_executed: Option<DateTime<FixedOffset>>,
+    /// When the latest test case was executed.
+    #[getset(get_copy = "pub")]
+    last_executed: Option<DateTime<FixedOffset>>,
+}
+
+impl PackageStatistics {
+    /// Get the total number of test cases.
+    #[must_use]
+    pub fn test_cases(&self) -> usize {
+        self.statuses.total()
+    }
+
+    /// Get the total number of pieces of evidence.
+    #[must_use]
+    pub fn evidence(&self) -> usize {
+        self.evidence_kinds.values().sum()
+    }
+
+    /// Get the proportion of test cases with a status that passed, from 0 to 1. See
+    /// [`StatusCounts::pass_rate`].
+    #[must_use]
+    pub fn pass_rate(&self) -> Option<f64> {
+        self.statuses.pass_rate()
+    }
+
+    /// Get the time between the earliest and latest test case executions.
+    #[must_use]
+    pub fn execution_span(&self) -> Option<TimeDelta> {
+        Some(self.last_executed? - self.first_executed?)
+    }
+
+    /// Add the statistics from another package to these.
+    pub fn merge(&mut self, other: &Self) {
+        self.statuses.add(other.statuses);
+        for (field, values) in &other.custom_fields {
+            let field = self.custom_fields.entry(field.clone()).or_default();
+            for (value, counts) in values {
+                field.entry(value.clone()).or_default().add(*counts);
+            }
+        }
+        for (author, counts) in &other.authors {
+            self.authors.entry(author.clone()).or_default().add(*counts);
+        }
+        for (kind, count) in &other.evidence_kinds {
+            *self.evidence_kinds.entry(*kind).or_default() += count;
+        }
+        self.media_count += other.media_count;
+        self.media_size += other.media_size;
+        self.largest_media_size = self.largest_media_size.max(other.largest_media_size);
+        self.first_executed = match (self.first_executed, other.first_executed) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        self.last_executed = self.last_executed.max(other.last_executed);
+    }
+
+    /// Count a test case, written by the specified authors.
+    fn count(&mut self, test_case: &TestCase, authors: &[String]) {
+        let metadata = test_case.metadata();
+        let status = *metadata.passed();
+        self.statuses.count(status);
+
+        for (field, value) in metadata.custom().iter().flatten() {
+            self.custom_fields
+                .entry(field.clone())
+                .or_default()
+                .entry(value.clone())
+                .or_default()
+                .count(status);
+        }
+        for author in authors {
+            self.authors
+                .entry(author.clone())
+                .or_default()
+                .count(status);
+        }
+        for evidence in test_case.evidence() {
+            *self.evidence_kinds.entry(*evidence.kind()).or_default() += 1;
+        }
+
+        let executed = *metadata.execution_datetime();
+        self.first_executed = Some(self.first_executed.map_or(executed, |t| t.min(executed)));
+        self.last_executed = Some(self.last_executed.map_or(executed, |t| t.max(executed)));
+    }
+}
+
+impl EvidencePackage {
+    /// Calculate statistics summarising the test cases and evidence in this package.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::Io`](crate::Error::Io) if the evp couldn't be read at all.
+    /// - [`Error::Zip`](crate::Error::Zip) if the evp file couldn't be read correctly.
+    pub fn statistics(&mut self) -> crate::Result<PackageStatistics> {
+        // Only count media that is referenced, so that media removed from test cases since the
+        // package was last compacted isn't counted
+        let report = self.storage_report()?;
+        let media: Vec<&MediaStorage> = report
+            .media()
+            .iter()
+            .filter(|media| media.references() > 0)
+            .collect();
+        let mut statistics = PackageStatistics {
+            media_count: media.len(),
+            media_size: media.iter().map(|media| media.size()).sum(),
+            largest_media_size: media.iter().map(|media| media.size()).max(),
+            ..Default::default()
+        };
+
+        let authors: Vec<String> = self
+            .metadata()
+            .authors()
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        for test_case in self.test_case_iter()? {
+            statistics.count(test_case, &authors);
+        }
+        Ok(statistics)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::{Author, EvidenceBuilder, PackageBuilder, TestCaseBuilder};
+
+    /// Parse a time for a test case to be executed at.
+    fn at(time: &str) -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339(time).unwrap()
+    }
+
+    /// Create a package with a test case of each status, returning its statistics.
+    fn statistics(dir: &TempDir) -> PackageStatistics {
+        let data = vec![0u8; 100];
+        PackageBuilder::new(dir.path().join("package.evp"), "Package")
+            .author(Author::new("CI"))
+            .author(Author::new_with_email("Tester", "tester@example.com"))
+            .test_case(
+                TestCaseBuilder::new("Passes")
+                    .executed_at(at("2024-01-01T10:00:00Z"))
+                    .custom("env", "ci")
+                    .text("Text")
+                    .evidence(EvidenceBuilder::file_data("a.bin", data.clone()))
+                    .passed(),
+            )
+            .test_case(
+                TestCaseBuilder::new("Fails")
+                    .executed_at(at("2024-01-01T12:30:00Z"))
+                    .custom("env", "ci")
+                    .evidence(EvidenceBuilder::file_data("a.bin", data))
+                    .evidence(EvidenceBuilder::file_data("b.bin", vec![1u8; 10]))
+                    .failed(),
+            )
+            .test_case(
+                TestCaseBuilder::new("Not run")
+                    .executed_at(at("2024-01-01T11:00:00Z"))
+                    .custom("env", "dev")
+                    .rich_text("**Rich**"),
+            )
+            .build()
+            .unwrap()
+            .statistics()
+            .unwrap()
+    }
+
+    #[test]
+    fn statistics_are_counted() {
+        let dir = tempfile::tempdir().unwrap();
+        let statistics = statistics(&dir);
+
+        assert_eq!(statistics.test_cases(), 3);
+        assert_eq!(statistics.statuses().passed(), 1);
+        assert_eq!(statistics.statuses().failed(), 1);
+        assert_eq!(statistics.statuses().not_run(), 1);
+        assert_eq!(statistics.pass_rate(), Some(0.5));
+
+        let env = &statistics.custom_fields()["env"];
+        assert_eq!(env["ci"].passed(), 1);
+        assert_eq!(env["ci"].failed(), 1);
+        assert_eq!(env["dev"].not_run(), 1);
+        assert_eq!(env.len(), 2);
+
+        assert_eq!(statistics.authors().len(), 2);
+        assert_eq!(statistics.authors()["CI"].total(), 3);
+        assert_eq!(
+            statistics.authors()["Tester <tester@example.com>"].total(),
+            3
+        );
+
+        assert_eq!(statistics.evidence(), 5);
+        assert_eq!(statistics.evidence_kinds()[&EvidenceKind::Text], 1);
+        assert_eq!(statistics.evidence_kinds()[&EvidenceKind::RichText], 1);
+        assert_eq!(statistics.evidence_kinds()[&EvidenceKind::File], 3);
+        assert_eq!(statistics.media_count(), 2);
+        assert_eq!(statistics.media_size(), 110);
+        assert_eq!(statistics.largest_media_size(), Some(100));
+
+        assert_eq!(
+            statistics.first_executed(),
+            Some(at("2024-01-01T10:00:00Z"))
+        );
+        assert_eq!(statistics.last_executed(), Some(at("2024-01-01T12:30:00Z")));
+        assert_eq!(statistics.execution_span(), Some(TimeDelta::minutes(150)));
+    }
+
+    #[test]
+    fn empty_packages_have_no_rates_or_times() {
+        let dir = tempfile::tempdir().unwrap();
+        let statistics = PackageBuilder::new(dir.path().join("package.evp"), "Package")
+            .build()
+            .unwrap()
+            .statistics()
+            .unwrap();
+
+        assert_eq!(statistics.test_cases(), 0);
+        assert_eq!(statistics.evidence(), 0);
+        assert_eq!(statistics.pass_rate(), None);
+        assert_eq!(statistics.largest_media_size(), None);
+        assert_eq!(statistics.execution_span(), None);
+    }
+
+    #[test]
+    fn statistics_are_merged() {
+        let dir = tempfile::tempdir().unwrap();
+        let single = statistics(&dir);
+        let other_dir = tempfile::tempdir().unwrap();
+        let mut merged = PackageStatistics::default();
+        merged.merge(&single);
+        merged.merge(&statistics(&other_dir));
+
+        assert_eq!(merged.test_cases(), 6);
+        assert_eq!(merged.statuses().passed(), 2);
+        assert_eq!(merged.custom_fields()["env"]["ci"].total(), 4);
+        assert_eq!(merged.authors()["CI"].total(), 6);
+        assert_eq!(merged.evidence_kinds()[&EvidenceKind::File], 6);
+        assert_eq!(merged.media_count(), 4);
+        assert_eq!(merged.media_size(), 220);
+        assert_eq!(merged.largest_media_size(), Some(100));
+        assert_eq!(merged.first_executed(), single.first_executed());
+        assert_eq!(merged.last_executed(), single.last_executed());
+    }
+}
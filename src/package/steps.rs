@@ -0,0 +1,347 @@
+use std::{collections::HashMap, fmt, str::FromStr};
+
+use getset::{Getters, MutGetters, Setters};
+use serde::{Deserialize, Serialize};
+
+use super::TestCasePassStatus;
+
+/// A step in a scripted [`TestCase`](super::TestCase).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Getters, MutGetters, Setters)]
+#[getset(get = "pub", set = "pub")]
+pub struct TestStep {
+    /// What the tester should do.
+    action: String,
+    /// What should happen.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    expected: Option<String>,
+    /// What actually happened.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    actual: Option<String>,
+    /// The result of this step, or [`None`] if it hasn't been run.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    status: Option<StepStatus>,
+    /// The zero-based indexes of the evidence in the test case attached to this step.
+    ///
+    /// These are kept up to date by [`TestCase::insert_evidence`](super::TestCase::insert_evidence),
+    /// [`TestCase::remove_evidence`](super::TestCase::remove_evidence) and
+    /// [`TestCase::move_evidence`](super::TestCase::move_evidence), but not if the evidence is
+    /// changed directly.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[get_mut = "pub"]
+    evidence: Vec<usize>,
+
+    /// Extra fields that this implementation doesn't understand.
+    #[get = "pub"]
+    #[serde(flatten)]
+    extra_fields: HashMap<String, serde_json::Value>,
+}
+
+impl TestStep {
+    /// Create a new step that hasn't been run.
+    pub fn new<S: Into<String>>(action: S) -> Self {
+        Self {
+            action: action.into(),
+            expected: None,
+            actual: None,
+            status: None,
+            evidence: vec![],
+            extra_fields: HashMap::new(),
+        }
+    }
+
+    /// Create a new step with an expected result.
+    pub fn new_with_expected<A: Into<String>, E: Into<String>>(action: A, expected: E) -> Self {
+        let mut step = Self::new(action);
+        step.expected = Some(expected.into());
+        step
+    }
+
+    /// Clear the actual result and status of this step, so it can be run again.
+    pub fn reset(&mut self) {
+        self.actual = None;
+        self.status = None;
+    }
+
+    /// Attach the evidence at an index in the test case to this step, if it isn't already.
+    pub fn attach_evidence(&mut self, index: usize) {
+        if !self.evidence.contains(&index) {
+            self.evidence.push(index);
+            self.evidence.sort_unstable();
+        }
+    }
+
+    /// Detach the evidence at an index in the test case from this step, returning whether it
+    /// was attached.
+    pub fn detach_evidence(&mut self, index: usize) -> bool {
+        let len = self.evidence.len();
+        self.evidence.retain(|i| *i != index);
+        self.evidence.len() != len
+    }
+
+    /// Update the evidence references of this step after evidence is inserted at an index.
+    pub(super) fn evidence_inserted(&mut self, index: usize) {
+        for i in &mut self.evidence {
+            if *i >= index {
+                *i += 1;
+            }
+        }
+    }
+
+    /// Update the evidence references of this step after the evidence at an index is removed.
+    pub(super) fn evidence_removed(&mut self, index: usize) {
+        self.evidence.retain(|i| *i != index);
+        for i in &mut self.evidence {
+            if *i > index {
+                *i -= 1;
+            }
+        }
+    }
+
+    /// Update the evidence references of this step after evidence is moved from one index to
+    /// another.
+    pub(super) fn evidence_moved(&mut self, from: usize, to: usize) {
+        for i in &mut self.evidence {
+            if *i == from {
+                *i = to;
+            } else if from < to && *i > from && *i <= to {
+                *i -= 1;
+            } else if to < from && *i >= to && *i < from {
+                *i += 1;
+            }
+        }
+        self.evidence.sort_unstable();
+    }
+}
+
+/// The result of a [`TestStep`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum StepStatus {
+    /// The step passed.
+    #[serde(rename = "pass")]
+    Pass,
+    /// The step failed.
+    #[serde(rename = "fail")]
+    Fail,
+    /// The step couldn't be run, for example because an earlier step failed.
+    #[serde(rename = "blocked")]
+    Blocked,
+    /// The step was deliberately not run.
+    #[serde(rename = "skipped")]
+    Skipped,
+}
+
+impl StepStatus {
+    /// All step statuses, in the order they should be offered to users.
+    pub const ALL: [StepStatus; 4] = [Self::Pass, Self::Fail, Self::Blocked, Self::Skipped];
+
+    /// Get the identifier used for this status when serialized.
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Pass => "pass",
+            Self::Fail => "fail",
+            Self::Blocked => "blocked",
+            Self::Skipped => "skipped",
+        }
+    }
+}
+
+impl fmt::Display for StepStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for StepStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|status| status.as_str().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| format!("{s} is not a valid step status"))
+    }
+}
+
+/// Work out the status of a test case from the results of its steps. A test case fails if any
+/// step failed or was blocked, and passes once every step has been run and at least one passed.
+/// Otherwise, its status can't be derived yet.
+pub(super) fn status_from_steps(steps: &[TestStep]) -> Option<TestCasePassStatus> {
+    if steps
+        .iter()
+        .any(|s| matches!(s.status, Some(StepStatus::Fail | StepStatus::Blocked)))
+    {
+        Some(TestCasePassStatus::Fail)
+    } else if steps.iter().all(|s| s.status.is_some())
+        && steps.iter().any(|s| s.status == Some(StepStatus::Pass))
+    {
+        Some(TestCasePassStatus::Pass)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Local;
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::{Evidence, EvidenceData, EvidenceKind, TestCase};
+
+    /// Create a step with a status.
+    fn step(status: Option<StepStatus>) -> TestStep {
+        let mut step = TestStep::new("Do something");
+        step.set_status(status);
+        step
+    }
+
+    /// Create a test case with five pieces of text evidence, numbered from 0, and a step
+    /// attached to some of them.
+    fn test_case(attached: &[usize]) -> TestCase {
+        let mut test_case = TestCase::new(
+            Uuid::new_v4(),
+            "Test".to_string(),
+            Local::now().fixed_offset(),
+        );
+        for i in 0..5 {
+            test_case.evidence_mut().push(Evidence::new(
+                EvidenceKind::Text,
+                EvidenceData::Text {
+                    content: i.to_string(),
+                },
+            ));
+        }
+        let mut step = TestStep::new("Do something");
+        for index in attached {
+            step.attach_evidence(*index);
+        }
+        test_case.steps_mut().push(step);
+        test_case
+    }
+
+    /// Get the content of the evidence attached to the first step of a test case.
+    fn attached(test_case: &TestCase) -> Vec<String> {
+        test_case.steps()[0]
+            .evidence()
+            .iter()
+            .map(|index| match test_case.evidence()[*index].value() {
+                EvidenceData::Text { content } => content.clone(),
+                _ => panic!("evidence should be text"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn statuses_are_derived_from_steps() {
+        use StepStatus::{Blocked, Fail, Pass, Skipped};
+
+        let cases: &[(&[Option<StepStatus>], Option<TestCasePassStatus>)] = &[
+            (&[], None),
+            (&[None], None),
+            (&[Some(Pass), None], None),
+            (&[Some(Skipped)], None),
+            (&[Some(Skipped), Some(Skipped)], None),
+            (&[Some(Pass)], Some(TestCasePassStatus::Pass)),
+            (&[Some(Pass), Some(Skipped)], Some(TestCasePassStatus::Pass)),
+            (&[Some(Pass), Some(Fail)], Some(TestCasePassStatus::Fail)),
+            (&[Some(Fail), None], Some(TestCasePassStatus::Fail)),
+            (&[Some(Pass), Some(Blocked)], Some(TestCasePassStatus::Fail)),
+            (
+                &[None, Some(Blocked), Some(Skipped)],
+                Some(TestCasePassStatus::Fail),
+            ),
+        ];
+        for (statuses, expected) in cases {
+            let steps: Vec<_> = statuses.iter().copied().map(step).collect();
+            assert_eq!(status_from_steps(&steps), *expected, "{statuses:?}");
+        }
+    }
+
+    #[test]
+    fn statuses_are_only_updated_when_they_can_be_derived() {
+        let mut test_case = test_case(&[]);
+        test_case
+            .metadata_mut()
+            .set_passed(Some(TestCasePassStatus::Pass));
+        assert_eq!(test_case.update_status_from_steps(), None);
+        assert_eq!(
+            *test_case.metadata().passed(),
+            Some(TestCasePassStatus::Pass)
+        );
+
+        test_case.steps_mut()[0].set_status(Some(StepStatus::Fail));
+        assert_eq!(
+            test_case.update_status_from_steps(),
+            Some(TestCasePassStatus::Fail)
+        );
+        assert_eq!(
+            *test_case.metadata().passed(),
+            Some(TestCasePassStatus::Fail)
+        );
+    }
+
+    #[test]
+    fn statuses_are_parsed() {
+        for status in StepStatus::ALL {
+            assert_eq!(status.to_string().parse(), Ok(status));
+        }
+        assert_eq!(" Blocked ".parse(), Ok(StepStatus::Blocked));
+        assert!("passed".parse::<StepStatus>().is_err());
+    }
+
+    #[test]
+    fn evidence_is_attached_once_in_order() {
+        let mut step = TestStep::new("Do something");
+        step.attach_evidence(3);
+        step.attach_evidence(1);
+        step.attach_evidence(3);
+        assert_eq!(*step.evidence(), vec![1, 3]);
+        assert!(step.detach_evidence(3));
+        assert!(!step.detach_evidence(3));
+        assert_eq!(*step.evidence(), vec![1]);
+    }
+
+    #[test]
+    fn references_follow_inserted_evidence() {
+        let mut test_case = test_case(&[1, 3]);
+        test_case.insert_evidence(
+            2,
+            Evidence::new(
+                EvidenceKind::Text,
+                EvidenceData::Text {
+                    content: "new".to_string(),
+                },
+            ),
+        );
+        assert_eq!(*test_case.steps()[0].evidence(), vec![1, 4]);
+        assert_eq!(attached(&test_case), vec!["1", "3"]);
+    }
+
+    #[test]
+    fn references_follow_removed_evidence() {
+        let mut test_case = test_case(&[0, 2, 4]);
+        assert!(test_case.remove_evidence(2).is_some());
+        assert_eq!(attached(&test_case), vec!["0", "4"]);
+        assert!(test_case.remove_evidence(0).is_some());
+        assert_eq!(attached(&test_case), vec!["4"]);
+        assert!(test_case.remove_evidence(10).is_none());
+        assert_eq!(attached(&test_case), vec!["4"]);
+    }
+
+    #[test]
+    fn references_follow_moved_evidence() {
+        for (from, to) in [(0, 4), (4, 0), (1, 3), (3, 1), (2, 2)] {
+            let mut test_case = test_case(&[0, 1, 3]);
+            assert!(test_case.move_evidence(from, to));
+            let mut moved = attached(&test_case);
+            moved.sort();
+            assert_eq!(moved, vec!["0", "1", "3"], "{from} to {to}");
+            assert!(test_case.steps()[0].evidence().is_sorted());
+        }
+
+        let mut test_case = test_case(&[0]);
+        assert!(!test_case.move_evidence(0, 5));
+        assert!(!test_case.move_evidence(5, 0));
+    }
+}
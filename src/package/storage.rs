@@ -0,0 +1,412 @@
+use std::{collections::HashMap, fmt, ops::RangeInclusive};
+
+use getset::{CopyGetters, Getters};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use zip::{CompressionMethod, result::ZipError, write::SimpleFileOptions};
+
+use super::{EvidenceData, EvidenceKind, EvidencePackage};
+
+/// MIME types (or MIME type prefixes, when ending in `/`) of data that is
+/// already compressed, and as such won't benefit from being compressed again.
+const PRECOMPRESSED_MIME_TYPES: &[&str] = &[
+    "image/png",
+    "image/jpeg",
+    "image/gif",
+    "image/webp",
+    "image/avif",
+    "image/heif",
+    "video/",
+    "audio/",
+    "application/zip",
+    "application/gzip",
+    "application/zstd",
+    "application/x-7z-compressed",
+    "application/x-bzip2",
+    "application/x-xz",
+    "application/x-rar-compressed",
+    "application/vnd.rar",
+];
+
+/// The algorithm used to compress entries that benefit from compression.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CompressionAlgorithm {
+    /// Deflate, readable by every ZIP implementation.
+    #[default]
+    Deflate,
+    /// Zstandard, which compresses text and logs better, but is not supported by every ZIP
+    /// implementation, including WebAssembly builds of this library.
+    Zstd,
+}
+
+impl CompressionAlgorithm {
+    /// The compression levels this algorithm accepts.
+    #[must_use]
+    pub fn levels(self) -> RangeInclusive<i64> {
+        match self {
+            Self::Deflate => 0..=9,
+            Self::Zstd => 1..=22,
+        }
+    }
+}
+
+impl fmt::Display for CompressionAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Deflate => write!(f, "deflate"),
+            Self::Zstd => write!(f, "zstd"),
+        }
+    }
+}
+
+/// The policy determining how each entry of an [`EvidencePackage`] is compressed when it is
+/// saved.
+///
+/// Media with a MIME type that is already compressed (such as PNG, JPEG or MP4) is always stored
+/// without further compression. Everything else is compressed with the configured
+/// [`CompressionAlgorithm`] and level.
+///
+/// The policy is stored in the manifest of the package, so it is used every time the package is
+/// saved until it is changed.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, CopyGetters, Serialize, Deserialize)]
+#[serde(try_from = "CompressionPolicyManifest")]
+#[getset(get_copy = "pub")]
+pub struct CompressionPolicy {
+    /// The algorithm used for compressible entries.
+    algorithm: CompressionAlgorithm,
+    /// The compression level, or [`None`] to use the algorithm's default. Deflate accepts levels
+    /// 0 to 9, and zstd accepts levels 1 to 22.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    level: Option<i64>,
+}
+
+/// A [`CompressionPolicy`] as read from a manifest, before its level is checked.
+#[derive(Deserialize)]
+struct CompressionPolicyManifest {
+    /// The algorithm used for compressible entries.
+    #[serde(default)]
+    algorithm: CompressionAlgorithm,
+    /// The compression level, if set.
+    #[serde(default)]
+    level: Option<i64>,
+}
+
+impl TryFrom<CompressionPolicyManifest> for CompressionPolicy {
+    type Error = crate::Error;
+
+    fn try_from(manifest: CompressionPolicyManifest) -> crate::Result<Self> {
+        Self::new(manifest.algorithm, manifest.level)
+    }
+}
+
+impl CompressionPolicy {
+    /// Create a new compression policy.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::InvalidCompressionLevel`](crate::Error::InvalidCompressionLevel) if the level
+    ///   isn't accepted by the algorithm.
+    pub fn new(algorithm: CompressionAlgorithm, level: Option<i64>) -> crate::Result<Self> {
+        if let Some(level) = level
+            && !algorithm.levels().contains(&level)
+        {
+            let levels = algorithm.levels();
+            return Err(crate::Error::InvalidCompressionLevel(format!(
+                "{algorithm} accepts levels {} to {}, not {level}",
+                levels.start(),
+                levels.end()
+            )));
+        }
+        Ok(Self { algorithm, level })
+    }
+
+    /// Determine if this is the default policy, which doesn't need to be stored.
+    #[must_use]
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Determine if data with this MIME type is already compressed.
+    #[must_use]
+    pub fn is_precompressed<S: AsRef<str>>(mime_type: S) -> bool {
+        let mime_type = mime_type.as_ref();
+        PRECOMPRESSED_MIME_TYPES.iter().any(|precompressed| {
+            if precompressed.ends_with('/') {
+                mime_type.starts_with(precompressed)
+            } else {
+                mime_type == *precompressed
+            }
+        })
+    }
+
+    /// Get the ZIP file options for an entry with the specified MIME type. Entries without a
+    /// MIME type (such as the manifest and test cases) are always compressed.
+    pub(super) fn options_for(&self, mime_type: Option<&str>) -> SimpleFileOptions {
+        if mime_type.is_some_and(Self::is_precompressed) {
+            return SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+        }
+
+        SimpleFileOptions::default()
+            .compression_method(match self.algorithm {
+                CompressionAlgorithm::Deflate => CompressionMethod::Deflated,
+                CompressionAlgorithm::Zstd => CompressionMethod::ZSTD,
+            })
+            .compression_level(self.level)
+    }
+}
+
+/// A report of what is taking up space within an [`EvidencePackage`].
+///
+/// All sizes are in bytes, before compression unless stated otherwise.
+#[derive(Clone, Debug, Getters)]
+#[getset(get = "pub")]
+pub struct StorageReport {
+    /// The size of the evidence in each test case, in package order. Media referenced more
+    /// than once in a test case is counted each time.
+    test_cases: Vec<TestCaseStorage>,
+    /// The total size of evidence of each kind.
+    evidence_kinds: HashMap<EvidenceKind, u64>,
+    /// The media in this package, largest first.
+    media: Vec<MediaStorage>,
+    /// The number of bytes saved by storing media referenced multiple times only once.
+    dedup_savings: u64,
+}
+
+impl StorageReport {
+    /// Get the largest media file in this package, if any media is present.
+    #[must_use]
+    pub fn largest_media(&self) -> Option<&MediaStorage> {
+        self.media.first()
+    }
+
+    /// Get the total size of all media in this package.
+    #[must_use]
+    pub fn total_media_size(&self) -> u64 {
+        self.media.iter().map(MediaStorage::size).sum()
+    }
+
+    /// Get the total compressed size of media that has been saved to disk. Media that has only
+    /// been added in memory is not included.
+    #[must_use]
+    pub fn total_media_compressed_size(&self) -> u64 {
+        self.media
+            .iter()
+            .filter_map(MediaStorage::compressed_size)
+            .sum()
+    }
+}
+
+/// The storage used by a single test case.
+#[derive(Clone, Debug, Getters, CopyGetters)]
+pub struct TestCaseStorage {
+    /// The ID of the test case.
+    #[getset(get_copy = "pub")]
+    id: Uuid,
+    /// The title of the test case.
+    #[getset(get = "pub")]
+    title: String,
+    /// The total size of the evidence in this test case.
+    #[getset(get_copy = "pub")]
+    size: u64,
+}
+
+/// The storage used by a single media file.
+#[derive(Clone, Debug, Getters, CopyGetters)]
+pub struct MediaStorage {
+    /// The SHA256 hash of the media.
+    #[getset(get = "pub")]
+    hash: String,
+    /// The MIME type of the media.
+    #[getset(get = "pub")]
+    mime_type: String,
+    /// The uncompressed size of the media.
+    #[getset(get_copy = "pub")]
+    size: u64,
+    /// The size of the media as stored on disk, or [`None`] if it hasn't been saved yet.
+    #[getset(get_copy = "pub")]
+    compressed_size: Option<u64>,
+    /// The number of pieces of evidence referencing this media.
+    #[getset(get_copy = "pub")]
+    references: usize,
+}
+
+impl EvidencePackage {
+    /// Generate a [`StorageReport`] describing what is taking up space within this package.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::Io`](crate::Error::Io) if the evp couldn't be read at all.
+    /// - [`Error::Zip`](crate::Error::Zip) if the evp file couldn't be read correctly.
+    pub fn storage_report(&mut self) -> crate::Result<StorageReport> {
+        // Count references to media
+        let mut references: HashMap<String, usize> = HashMap::new();
+        for test_case in self.test_case_data.values() {
+            for evidence in test_case.evidence() {
+                for hash in evidence.media_hashes() {
+                    *references.entry(hash.clone()).or_default() += 1;
+                }
+            }
+        }
+
+        // Determine media sizes, from memory if present or from the archive otherwise
+        let mut media = vec![];
+        for entry in &self.media {
+            let hash = entry.sha256_checksum();
+            let (size, compressed_size) = if let Some(file) = self.media_data.get(hash) {
+                (file.data().len() as u64, None)
+            } else {
+                let zip = self.zip.as_reader()?;
+                match zip.by_name(&format!("media/{hash}")) {
+                    Ok(file) => (file.size(), Some(file.compressed_size())),
+                    Err(ZipError::FileNotFound) => {
+                        tracing::warn!("{hash} not found in package!");
+                        continue;
+                    }
+                    Err(e) => return Err(e.into()),
+                }
+            };
+            media.push(MediaStorage {
+                hash: hash.clone(),
+                mime_type: entry.mime_type().clone(),
+                size,
+                compressed_size,
+                references: references.get(hash).copied().unwrap_or_default(),
+            });
+        }
+        media.sort_by_key(|m| std::cmp::Reverse(m.size));
+
+        let media_sizes: HashMap<&String, u64> = media.iter().map(|m| (&m.hash, m.size)).collect();
+        let mut evidence_kinds = HashMap::new();
+        let mut test_cases = vec![];
+        for test_case in self.test_case_iter()? {
+            let mut size = 0;
+            for evidence in test_case.evidence() {
+                let evidence_size = match evidence.value() {
+                    EvidenceData::Text { content } => content.len() as u64,
+                    EvidenceData::Base64 { data } => data.len() as u64,
+                    EvidenceData::Media { hash } => {
+                        media_sizes.get(hash).copied().unwrap_or_default()
+                    }
+                };
+                size += evidence_size;
+                *evidence_kinds.entry(*evidence.kind()).or_default() += evidence_size;
+            }
+            test_cases.push(TestCaseStorage {
+                id: *test_case.id(),
+                title: test_case.metadata().title().clone(),
+                size,
+            });
+        }
+
+        let dedup_savings = media
+            .iter()
+            .map(|m| m.size * m.references.saturating_sub(1) as u64)
+            .sum();
+
+        Ok(StorageReport {
+            test_cases,
+            evidence_kinds,
+            media,
+            dedup_savings,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EvidenceBuilder, PackageBuilder, TestCaseBuilder};
+
+    #[test]
+    fn levels_are_checked_against_the_algorithm() {
+        assert!(CompressionPolicy::new(CompressionAlgorithm::Deflate, None).is_ok());
+        assert!(CompressionPolicy::new(CompressionAlgorithm::Deflate, Some(0)).is_ok());
+        assert!(CompressionPolicy::new(CompressionAlgorithm::Deflate, Some(9)).is_ok());
+        assert!(CompressionPolicy::new(CompressionAlgorithm::Zstd, Some(1)).is_ok());
+        assert!(CompressionPolicy::new(CompressionAlgorithm::Zstd, Some(22)).is_ok());
+
+        for (algorithm, level) in [
+            (CompressionAlgorithm::Deflate, -1),
+            (CompressionAlgorithm::Deflate, 10),
+            (CompressionAlgorithm::Zstd, 0),
+            (CompressionAlgorithm::Zstd, 23),
+        ] {
+            assert!(matches!(
+                CompressionPolicy::new(algorithm, Some(level)),
+                Err(crate::Error::InvalidCompressionLevel(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn levels_are_checked_when_read() {
+        let policy: CompressionPolicy =
+            serde_json::from_str(r#"{"algorithm": "zstd", "level": 19}"#).unwrap();
+        assert_eq!(
+            policy,
+            CompressionPolicy::new(CompressionAlgorithm::Zstd, Some(19)).unwrap()
+        );
+        let policy: CompressionPolicy = serde_json::from_str("{}").unwrap();
+        assert!(policy.is_default());
+
+        let error = serde_json::from_str::<CompressionPolicy>(r#"{"level": 19}"#).unwrap_err();
+        assert!(error.to_string().contains("deflate accepts levels 0 to 9"));
+        assert!(
+            serde_json::from_str::<CompressionPolicy>(r#"{"algorithm": "zstd", "level": 0}"#)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn storage_is_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("package.evp");
+        let data = vec![0u8; 100];
+        let mut package = PackageBuilder::new(path.clone(), "Package")
+            .test_case(
+                TestCaseBuilder::new("First")
+                    .text("hello")
+                    .evidence(EvidenceBuilder::file_data("a.bin", data.clone()))
+                    .evidence(EvidenceBuilder::file_data("a.bin", data.clone())),
+            )
+            .test_case(
+                TestCaseBuilder::new("Second")
+                    .evidence(EvidenceBuilder::file_data("a.bin", data))
+                    .evidence(EvidenceBuilder::file_data("b.bin", vec![1u8; 10])),
+            )
+            .build()
+            .unwrap();
+
+        let report = package.storage_report().unwrap();
+        let sizes: Vec<_> = report
+            .test_cases()
+            .iter()
+            .map(|tc| (tc.title().as_str(), tc.size()))
+            .collect();
+        assert_eq!(sizes, vec![("First", 205), ("Second", 110)]);
+        assert_eq!(report.evidence_kinds()[&EvidenceKind::Text], 5);
+        assert_eq!(report.evidence_kinds()[&EvidenceKind::File], 310);
+        assert_eq!(report.media().len(), 2);
+        assert_eq!(report.largest_media().unwrap().size(), 100);
+        assert_eq!(report.largest_media().unwrap().references(), 3);
+        assert_eq!(report.total_media_size(), 110);
+        assert_eq!(report.total_media_compressed_size(), 0);
+        assert_eq!(*report.dedup_savings(), 200);
+
+        package.save().unwrap();
+        drop(package);
+        let report = EvidencePackage::open(path)
+            .unwrap()
+            .storage_report()
+            .unwrap();
+        assert_eq!(report.total_media_size(), 110);
+        assert_eq!(*report.dedup_savings(), 200);
+        assert!(
+            report
+                .media()
+                .iter()
+                .all(|media| media.compressed_size().is_some())
+        );
+    }
+}
@@ -0,0 +1,297 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use chrono::{DateTime, FixedOffset, Local};
+use getset::{Getters, MutGetters, Setters};
+use serde::{Deserialize, Serialize};
+
+use super::{Evidence, EvidencePackage, TestCase, TestStep};
+
+/// The maximum length of a test case title, which titles created from templates are truncated to.
+const MAX_TITLE_LENGTH: usize = 30;
+
+/// A template for creating test cases that follow a standard shape.
+///
+/// The title is a pattern which may contain the following placeholders:
+///
+/// - `{n}`: the one-based position the new test case will have in the package,
+/// - `{date}`: the date of execution, as `YYYY-MM-DD`,
+/// - `{time}`: the time of execution, as `HH:MM`,
+/// - `{template}`: the name of the template.
+///
+/// Placeholder evidence can't refer to media, so that templates can be shared between packages.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Getters, MutGetters, Setters)]
+#[getset(get = "pub")]
+pub struct TestCaseTemplate {
+    /// The name of this template.
+    #[getset(set = "pub")]
+    name: String,
+    /// A description of when this template should be used.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(set = "pub")]
+    description: Option<String>,
+    /// The pattern the titles of new test cases are created from.
+    #[getset(set = "pub")]
+    title: String,
+    /// Default values for custom metadata fields, keyed by field ID.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    #[get_mut = "pub"]
+    custom_metadata: HashMap<String, String>,
+    /// Evidence that new test cases start with.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    evidence: Vec<Evidence>,
+    /// Steps that new test cases start with, which haven't been run.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[get_mut = "pub"]
+    steps: Vec<TestStep>,
+
+    /// Extra fields that this implementation doesn't understand.
+    #[get = "pub"]
+    #[serde(flatten)]
+    extra_fields: HashMap<String, serde_json::Value>,
+}
+
+impl TestCaseTemplate {
+    /// Create a new template without any default values or placeholder evidence.
+    pub fn new<N: Into<String>, T: Into<String>>(name: N, title: T) -> Self {
+        Self {
+            name: name.into(),
+            description: None,
+            title: title.into(),
+            custom_metadata: HashMap::new(),
+            evidence: vec![],
+            steps: vec![],
+            extra_fields: HashMap::new(),
+        }
+    }
+
+    /// Create a new template from an existing test case, taking its custom metadata, evidence and
+    /// steps. Evidence that refers to media is left out, and the results of steps are cleared.
+    pub fn from_test_case<N: Into<String>, T: Into<String>>(
+        name: N,
+        title: T,
+        test_case: &TestCase,
+    ) -> Self {
+        let mut template = Self::new(name, title);
+        if let Some(custom) = test_case.metadata().custom() {
+            template.custom_metadata.clone_from(custom);
+        }
+        // Keep track of where each piece of evidence ends up, so steps stay attached to it
+        let mut new_indexes = vec![None; test_case.evidence().len()];
+        for (index, evidence) in test_case.evidence().iter().enumerate() {
+            if evidence.media_hashes().next().is_none() {
+                new_indexes[index] = Some(template.evidence.len());
+                template.evidence.push(evidence.clone());
+            }
+        }
+        template.steps = test_case
+            .steps()
+            .iter()
+            .cloned()
+            .map(|mut step| {
+                step.reset();
+                let attached = step
+                    .evidence()
+                    .iter()
+                    .filter_map(|i| new_indexes.get(*i).copied().flatten())
+                    .collect();
+                step.set_evidence(attached);
+                step
+            })
+            .collect();
+        template
+    }
+
+    /// Add placeholder evidence to this template.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::InvalidTemplate`](crate::Error::InvalidTemplate) if the evidence refers to
+    ///   media.
+    pub fn push_evidence(&mut self, evidence: Evidence) -> crate::Result<()> {
+        if evidence.media_hashes().next().is_some() {
+            return Err(crate::Error::InvalidTemplate(format!(
+                "{}: placeholder evidence can't refer to media",
+                self.name
+            )));
+        }
+        self.evidence.push(evidence);
+        Ok(())
+    }
+
+    /// Remove the placeholder evidence at an index, returning it if there was any.
+    pub fn remove_evidence(&mut self, index: usize) -> Option<Evidence> {
+        (index < self.evidence.len()).then(|| self.evidence.remove(index))
+    }
+
+    /// Create a title from this template's pattern, for a test case at a position in a package
+    /// executed at a time. Titles are truncated to the maximum length of a test case title.
+    #[must_use]
+    pub fn expand_title(&self, number: usize, at: DateTime<FixedOffset>) -> String {
+        let title = self
+            .title
+            .replace("{n}", &number.to_string())
+            .replace("{date}", &at.format("%Y-%m-%d").to_string())
+            .replace("{time}", &at.format("%H:%M").to_string())
+            .replace("{template}", &self.name);
+        title.trim().chars().take(MAX_TITLE_LENGTH).collect()
+    }
+
+    /// Check this template can be used, as templates read from files aren't checked.
+    fn validate(&self) -> crate::Result<()> {
+        if self
+            .evidence
+            .iter()
+            .any(|ev| ev.media_hashes().next().is_some())
+        {
+            return Err(crate::Error::InvalidTemplate(format!(
+                "{}: placeholder evidence can't refer to media",
+                self.name
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// A collection of [`TestCaseTemplate`]s with unique names. These can be stored in the metadata
+/// of a package, or in a file for all packages a user works with.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct TemplateLibrary {
+    /// The templates, in the order they were added.
+    templates: Vec<TestCaseTemplate>,
+}
+
+impl TemplateLibrary {
+    /// Read a library from a JSON file. If the file doesn't exist, an empty library is returned.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::Io`](crate::Error::Io) if the file exists but couldn't be read.
+    /// - [`Error::InvalidTemplate`](crate::Error::InvalidTemplate) if the file isn't valid.
+    pub fn load(path: &Path) -> crate::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(text) => serde_json::from_str(&text)
+                .map_err(|e| crate::Error::InvalidTemplate(format!("{}: {e}", path.display()))),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Write this library to a JSON file, creating the directory containing it if needed.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::Io`](crate::Error::Io) if the file couldn't be written.
+    #[allow(clippy::missing_panics_doc, reason = "templates always serialize")]
+    pub fn save(&self, path: &Path) -> crate::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(
+            path,
+            serde_json::to_string_pretty(self).expect("templates should always serialize"),
+        )?;
+        Ok(())
+    }
+
+    /// Iterate over the templates in this library.
+    pub fn iter(&self) -> impl Iterator<Item = &TestCaseTemplate> {
+        self.templates.iter()
+    }
+
+    /// Get the number of templates in this library.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.templates.len()
+    }
+
+    /// Check if this library has no templates.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.templates.is_empty()
+    }
+
+    /// Get the template with a name.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&TestCaseTemplate> {
+        self.templates.iter().find(|t| t.name == name)
+    }
+
+    /// Get a mutable reference to the template with a name.
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut TestCaseTemplate> {
+        self.templates.iter_mut().find(|t| t.name == name)
+    }
+
+    /// Add a template, replacing any existing template with the same name.
+    pub fn insert(&mut self, template: TestCaseTemplate) {
+        if let Some(existing) = self.get_mut(&template.name) {
+            *existing = template;
+        } else {
+            self.templates.push(template);
+        }
+    }
+
+    /// Remove the template with a name, returning it if there was one.
+    pub fn remove(&mut self, name: &str) -> Option<TestCaseTemplate> {
+        let idx = self.templates.iter().position(|t| t.name == name)?;
+        Some(self.templates.remove(idx))
+    }
+}
+
+impl EvidencePackage {
+    /// Create a new test case from a template.
+    ///
+    /// Default values are only set for custom metadata fields that exist in this package.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::InvalidTemplate`](crate::Error::InvalidTemplate) if the template's placeholder
+    ///   evidence refers to media.
+    pub fn create_test_case_from_template(
+        &mut self,
+        template: &TestCaseTemplate,
+    ) -> crate::Result<&mut TestCase> {
+        self.create_test_case_from_template_at(template, Local::now().fixed_offset())
+    }
+
+    /// Create a new test case from a template at a specified time.
+    ///
+    /// Default values are only set for custom metadata fields that exist in this package.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::InvalidTemplate`](crate::Error::InvalidTemplate) if the template's placeholder
+    ///   evidence refers to media.
+    pub fn create_test_case_from_template_at(
+        &mut self,
+        template: &TestCaseTemplate,
+        at: DateTime<FixedOffset>,
+    ) -> crate::Result<&mut TestCase> {
+        template.validate()?;
+
+        let title = template.expand_title(self.test_cases.len() + 1, at);
+        let fields = self
+            .metadata()
+            .custom_test_case_metadata()
+            .clone()
+            .unwrap_or_default();
+
+        let case = self.create_test_case_at(title, at)?;
+        for (key, value) in template.custom_metadata() {
+            if fields.contains_key(key) {
+                case.metadata_mut()
+                    .custom_mut()
+                    .insert(key.clone(), value.clone());
+            } else {
+                tracing::warn!(
+                    "Template {} sets custom field {key}, which doesn't exist in this package",
+                    template.name()
+                );
+            }
+        }
+        case.evidence_mut()
+            .extend(template.evidence().iter().cloned());
+        case.steps_mut().extend(template.steps().iter().cloned());
+        Ok(case)
+    }
+}
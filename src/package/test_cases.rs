@@ -9,6 +9,8 @@ use serde::{
 };
 use uuid::Uuid;
 
+use super::{Annotation, Requirement, TestStep, steps::status_from_steps};
+
 /// The URL for $schema in the test case manifests
 const TESTCASE_SCHEMA_LOCATION: &str =
     "https://evidenceangel-schemas.hpkns.uk/testcase.1.schema.json";
@@ -35,6 +37,11 @@ pub struct TestCase {
     #[getset(get = "pub", get_mut = "pub")]
     evidence: Vec<Evidence>,
 
+    /// The steps of this test case, if it is scripted.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[getset(get = "pub", get_mut = "pub")]
+    steps: Vec<TestStep>,
+
     /// Extra fields that this implementation doesn't understand.
     #[get = "pub"]
     #[serde(flatten)]
@@ -52,9 +59,11 @@ impl TestCase {
                 execution_datetime,
                 passed: None,
                 custom: None,
+                requirements: None,
                 extra_fields: HashMap::new(),
             },
             evidence: vec![],
+            steps: vec![],
             extra_fields: HashMap::new(),
         }
     }
@@ -63,6 +72,61 @@ impl TestCase {
     pub(super) fn update_schema(&mut self) {
         self.schema = Some(TESTCASE_SCHEMA_LOCATION.to_string());
     }
+
+    /// Insert evidence at an index, keeping the evidence attached to steps up to date. If the
+    /// index is past the end of the evidence, it is added to the end.
+    pub fn insert_evidence(&mut self, index: usize, evidence: Evidence) {
+        let index = index.min(self.evidence.len());
+        self.evidence.insert(index, evidence);
+        for step in &mut self.steps {
+            step.evidence_inserted(index);
+        }
+    }
+
+    /// Remove the evidence at an index, detaching it from any steps and keeping the evidence
+    /// attached to steps up to date. Returns the evidence if there was any at the index.
+    pub fn remove_evidence(&mut self, index: usize) -> Option<Evidence> {
+        if index >= self.evidence.len() {
+            return None;
+        }
+        let evidence = self.evidence.remove(index);
+        for step in &mut self.steps {
+            step.evidence_removed(index);
+        }
+        Some(evidence)
+    }
+
+    /// Move evidence from one index to another, keeping the evidence attached to steps up to
+    /// date. Returns false if either index is past the end of the evidence.
+    pub fn move_evidence(&mut self, from: usize, to: usize) -> bool {
+        if from >= self.evidence.len() || to >= self.evidence.len() {
+            return false;
+        }
+        let evidence = self.evidence.remove(from);
+        self.evidence.insert(to, evidence);
+        for step in &mut self.steps {
+            step.evidence_moved(from, to);
+        }
+        true
+    }
+
+    /// Work out the status of this test case from the results of its steps. A test case fails if
+    /// any step failed or was blocked, and passes once every step has been run and at least one
+    /// passed. Otherwise, or if there are no steps, [`None`] is returned.
+    #[must_use]
+    pub fn status_from_steps(&self) -> Option<TestCasePassStatus> {
+        status_from_steps(&self.steps)
+    }
+
+    /// Set the status of this test case from the results of its steps, if it can be worked out
+    /// from them. Returns the status that was set.
+    pub fn update_status_from_steps(&mut self) -> Option<TestCasePassStatus> {
+        let status = self.status_from_steps();
+        if status.is_some() {
+            self.metadata.passed = status;
+        }
+        status
+    }
 }
 
 /// The metadata of a [`TestCase`].
@@ -78,6 +142,9 @@ pub struct TestCaseMetadata {
     /// Custom metadata parameters
     #[serde(skip_serializing_if = "Option::is_none")]
     custom: Option<HashMap<String, String>>,
+    /// The requirements the associated [`TestCase`] covers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    requirements: Option<Vec<Requirement>>,
 
     /// Extra fields that this implementation doesn't understand.
     #[get = "pub"]
@@ -95,6 +162,16 @@ impl TestCaseMetadata {
         // SAFETY: just initialised if wasn't previously
         self.custom.as_mut().unwrap()
     }
+
+    /// Get a mutable reference to the requirements the associated [`TestCase`] covers
+    #[allow(clippy::missing_panics_doc, reason = "safety is explained inline")]
+    pub fn requirements_mut(&mut self) -> &mut Vec<Requirement> {
+        if self.requirements.is_none() {
+            self.requirements = Some(vec![]);
+        }
+        // SAFETY: just initialised if wasn't previously
+        self.requirements.as_mut().unwrap()
+    }
 }
 
 /// Valid test case statuses.
@@ -130,6 +207,17 @@ pub struct Evidence {
     #[getset(get_mut = "pub", set = "pub")]
     original_filename: Option<String>,
 
+    /// The hash of media containing a poster frame, if this is `Video` evidence.
+    /// This MUST be None for any other kind of evidence.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[getset(get_mut = "pub", set = "pub")]
+    poster: Option<String>,
+
+    /// Annotations drawn over this evidence, if it is an image.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[getset(get_mut = "pub", set = "pub")]
+    annotations: Vec<Annotation>,
+
     /// Extra fields that this implementation doesn't understand.
     #[get = "pub"]
     #[serde(flatten)]
@@ -145,6 +233,8 @@ impl Evidence {
             value,
             caption: None,
             original_filename: None,
+            poster: None,
+            annotations: vec![],
             extra_fields: HashMap::new(),
         }
     }
@@ -171,6 +261,16 @@ impl Evidence {
         }
     }
 
+    /// Get the hashes of all media referred to by this evidence, including its value and any
+    /// poster frame.
+    pub fn media_hashes(&self) -> impl Iterator<Item = &String> {
+        let value = match self.value() {
+            EvidenceData::Media { hash } => Some(hash),
+            _ => None,
+        };
+        value.into_iter().chain(self.poster.as_ref())
+    }
+
     /// Gets the associated media file media type, if one is present.
     pub fn media_mime(&self, pkg: &mut super::EvidencePackage) -> Option<String> {
         match self.value() {
@@ -187,7 +287,7 @@ impl Evidence {
 }
 
 /// Kinds of [`Evidence`].
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum EvidenceKind {
     /// A text entry.
     Text,
@@ -199,6 +299,26 @@ pub enum EvidenceKind {
     File,
     /// An HTTP request and response.
     Http,
+    /// A log made up of timestamped lines with levels and sources, stored as a
+    /// [`Log`](crate::Log).
+    Log,
+    /// A table of typed cells with column headers, stored as a [`Table`](crate::Table).
+    Table,
+    /// A comparison of expected and actual content, shown as a diff, stored as a
+    /// [`Diff`](crate::Diff).
+    Diff,
+    /// A command that was executed, with its output and exit code, stored as a
+    /// [`CommandExecution`](crate::CommandExecution).
+    Command,
+    /// A SQL query that was executed, with the rows it returned, stored as a
+    /// [`SqlQuery`](crate::SqlQuery).
+    Sql,
+    /// A reference to something outside of the package, such as a defect or build pipeline,
+    /// stored as a [`Link`](crate::Link).
+    Link,
+    /// A video or screen recording in MP4 or `WebM` format, stored as media, optionally with a
+    /// poster frame.
+    Video,
 }
 
 /// Data in a piece of [`Evidence`].
@@ -0,0 +1,109 @@
+use std::io::{BufReader, Read};
+
+use zip::result::ZipError;
+
+use super::{EvidencePackage, MediaFile};
+
+/// The maximum width or height of a generated thumbnail, in pixels. Images smaller than this
+/// are used as their own thumbnail.
+pub const THUMBNAIL_MAX_SIZE: u32 = 640;
+
+/// Generate a thumbnail for some image media, encoded as JPEG if the source is a JPEG or as PNG
+/// otherwise. Returns [`None`] if the media can't be decoded as an image.
+#[cfg(feature = "image-processing")]
+fn generate_thumbnail(media: &MediaFile) -> Option<MediaFile> {
+    use image::{ImageFormat, ImageReader};
+    use std::io::Cursor;
+
+    let reader = ImageReader::new(Cursor::new(media.data()))
+        .with_guessed_format()
+        .ok()?;
+    let format = match reader.format()? {
+        ImageFormat::Jpeg => ImageFormat::Jpeg,
+        _ => ImageFormat::Png,
+    };
+    let image = reader.decode().ok()?;
+    let thumbnail = image.thumbnail(THUMBNAIL_MAX_SIZE, THUMBNAIL_MAX_SIZE);
+
+    let mut buf = Cursor::new(vec![]);
+    if format == ImageFormat::Jpeg {
+        // JPEG can't store transparency
+        thumbnail.to_rgb8().write_to(&mut buf, format).ok()?;
+    } else {
+        thumbnail.write_to(&mut buf, format).ok()?;
+    }
+    Some(buf.into_inner().into())
+}
+
+/// Thumbnails can't be generated without the `image-processing` feature.
+#[cfg(not(feature = "image-processing"))]
+fn generate_thumbnail(_media: &MediaFile) -> Option<MediaFile> {
+    None
+}
+
+impl EvidencePackage {
+    /// Get a thumbnail of some image media from this package by its sha256 hash.
+    ///
+    /// Thumbnails are stored within the package in the `thumbnails` directory, and will be
+    /// generated and cached in memory if not already present, then written to the package when
+    /// [`EvidencePackage::save`] is next called. Images that are already no larger than
+    /// [`THUMBNAIL_MAX_SIZE`] are returned as-is.
+    ///
+    /// Returns [`None`] if the media couldn't be found with that hash, or isn't an image that can
+    /// be decoded. Without the `image-processing` feature, only thumbnails already stored in the
+    /// package are returned.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::Io`](crate::Error::Io) if the evp couldn't be read at all.
+    /// - [`Error::Zip`](crate::Error::Zip) if the evp file couldn't be read correctly.
+    #[allow(clippy::missing_panics_doc)]
+    pub fn get_thumbnail<S>(&mut self, hash: S) -> crate::Result<Option<&MediaFile>>
+    where
+        S: Into<String>,
+    {
+        let hash = hash.into();
+
+        // Check in-memory cache
+        if self.thumbnail_data.contains_key(&hash) {
+            tracing::debug!("Thumbnail for {hash} found in cache.");
+            return Ok(self.thumbnail_data.get(&hash));
+        }
+
+        // Read from ZIP file
+        let zip = self.zip.as_reader()?;
+        match zip.by_name(&format!("thumbnails/{hash}")) {
+            Ok(file) => {
+                let mut buf = Vec::with_capacity(file.size() as usize);
+                BufReader::new(file).read_to_end(&mut buf)?;
+                tracing::trace!("New thumbnail cache entry: {hash}");
+                self.thumbnail_data.insert(hash.clone(), buf.into());
+                return Ok(self.thumbnail_data.get(&hash));
+            }
+            Err(ZipError::FileNotFound) => (),
+            Err(e) => return Err(e.into()),
+        }
+
+        // Generate from the original media
+        let Some(media) = self.get_media(&hash)? else {
+            return Ok(None);
+        };
+        match media.image_dimensions() {
+            None => return Ok(None),
+            Some((width, height))
+                if width <= THUMBNAIL_MAX_SIZE && height <= THUMBNAIL_MAX_SIZE =>
+            {
+                return self.get_media(&hash);
+            }
+            Some(_) => (),
+        }
+        tracing::debug!("Generating thumbnail for {hash}");
+        let Some(thumbnail) = generate_thumbnail(media) else {
+            tracing::warn!("Couldn't generate thumbnail for {hash}");
+            return Ok(None);
+        };
+        tracing::trace!("New thumbnail cache entry: {hash}");
+        self.thumbnail_data.insert(hash.clone(), thumbnail);
+        Ok(self.thumbnail_data.get(&hash))
+    }
+}
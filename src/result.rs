@@ -36,6 +36,19 @@ pub enum Error {
     #[error("Failed to read test case {1}: {0}")]
     InvalidTestCase(serde_json::Error, Uuid),
 
+    /// An image couldn't be decoded or encoded.
+    #[cfg(feature = "image-processing")]
+    #[error("Image error: {0}")]
+    Image(#[from] image::ImageError),
+
+    /// Images can't be processed, as the `image-processing` feature isn't enabled.
+    #[error("Images can't be processed, as this build doesn't support it.")]
+    ImageProcessingUnavailable,
+
+    /// The data of some evidence isn't valid for its kind.
+    #[error("The evidence data is invalid: {0}")]
+    InvalidEvidenceData(String),
+
     /// Some media is missing from the package.
     #[error("Media is missing from the package with hash {0}")]
     MediaMissing(String),
@@ -52,6 +65,18 @@ pub enum Error {
     )]
     TestCaseSchemaValidationFailed,
 
+    /// A rule for redacting secrets isn't valid.
+    #[error("Invalid secret redaction rule: {0}")]
+    InvalidSecretRule(String),
+
+    /// A test case template isn't valid.
+    #[error("Invalid test case template: {0}")]
+    InvalidTemplate(String),
+
+    /// A compression level isn't accepted by the compression algorithm.
+    #[error("Invalid compression level: {0}")]
+    InvalidCompressionLevel(String),
+
     /// The specified test case doesn't exist
     #[error("The specified test case doesn't exist")]
     DoesntExist(Uuid),
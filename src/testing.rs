@@ -0,0 +1,503 @@
+use std::{
+    env, fs,
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+    sync::{Mutex, PoisonError},
+    thread,
+    time::Duration,
+};
+
+use chrono::Local;
+
+use crate::{
+    Error, EvidenceBuilder, EvidencePackage, HttpRequest, HttpResponse, Requirement, Result,
+    StructuredEvidence, TestCaseBuilder, TestCasePassStatus, TestStep,
+};
+
+/// The environment variable which sets the package used by [`record`].
+pub const PACKAGE_ENV_VAR: &str = "EVIDENCEANGEL_TEST_PACKAGE";
+
+/// The maximum length of a test case title, which test names are truncated to.
+const MAX_TITLE_LENGTH: usize = 30;
+
+/// How many times to try to open a package locked by another process before giving up.
+const LOCK_ATTEMPTS: u32 = 50;
+
+/// How long to wait between attempts to open a package locked by another process.
+const LOCK_RETRY_DELAY: Duration = Duration::from_millis(100);
+
+/// The recorder used by [`record`] and [`record_as`].
+static DEFAULT_RECORDER: EvidenceRecorder = EvidenceRecorder {
+    path: None,
+    title: None,
+    created: Mutex::new(false),
+};
+
+/// A package that tests record evidence into. Tests can run on many threads at once, so this is
+/// usually kept in a `static` shared by all the tests in a test binary:
+///
+/// ```no_run
+/// use evidenceangel::testing::EvidenceRecorder;
+///
+/// static EVIDENCE: EvidenceRecorder =
+///     EvidenceRecorder::new("target/evidence/api.evp", "API tests");
+///
+/// #[test]
+/// fn health_check() {
+///     EVIDENCE.record(|evidence| {
+///         evidence.text("The service reported it is healthy");
+///     });
+/// }
+/// ```
+///
+/// The package is created afresh by the first test to finish, replacing any package from a
+/// previous run, so each test binary should record into its own package.
+#[derive(Debug)]
+pub struct EvidenceRecorder {
+    /// The path of the package, or [`None`] to use the default.
+    path: Option<&'static str>,
+    /// The title of the package, or [`None`] to use the name of the test binary.
+    title: Option<&'static str>,
+    /// Whether the package has been created by this process yet. This is locked whilst the
+    /// package is being changed.
+    created: Mutex<bool>,
+}
+
+impl EvidenceRecorder {
+    /// Create a recorder for a package at `path`.
+    #[must_use]
+    pub const fn new(path: &'static str, title: &'static str) -> Self {
+        Self {
+            path: Some(path),
+            title: Some(title),
+            created: Mutex::new(false),
+        }
+    }
+
+    /// Run a test, recording its evidence into a test case named after the test.
+    ///
+    /// The test case is named after the current thread, which the Rust test harness names after
+    /// the test being run. The module path is left out of the name, and long names are
+    /// truncated to the maximum length of a test case title. If the test panics or returns an
+    /// error, the test case fails, otherwise it passes.
+    ///
+    /// # Panics
+    ///
+    /// This re-raises any panic from the test, and panics if the evidence couldn't be recorded.
+    pub fn record<F, T>(&self, test: F) -> T
+    where
+        F: FnOnce(&mut TestEvidence) -> T,
+        T: TestOutcome,
+    {
+        let thread = thread::current();
+        let name = thread.name().unwrap_or("unnamed test");
+        let name = name.rsplit("::").next().unwrap_or(name);
+        self.record_as(
+            name.chars().take(MAX_TITLE_LENGTH).collect::<String>(),
+            test,
+        )
+    }
+
+    /// Run a test, recording its evidence into a test case with a particular name.
+    ///
+    /// If the test panics or returns an error, the test case fails, otherwise it passes.
+    ///
+    /// # Panics
+    ///
+    /// This re-raises any panic from the test, and panics if the evidence couldn't be recorded.
+    pub fn record_as<S, F, T>(&self, name: S, test: F) -> T
+    where
+        S: Into<String>,
+        F: FnOnce(&mut TestEvidence) -> T,
+        T: TestOutcome,
+    {
+        let name = name.into();
+        let mut evidence = TestEvidence {
+            test_case: Some(
+                TestCaseBuilder::new(name.clone()).executed_at(Local::now().fixed_offset()),
+            ),
+        };
+        let outcome = panic::catch_unwind(AssertUnwindSafe(|| test(&mut evidence)));
+
+        let status = match &outcome {
+            Ok(outcome) if outcome.passed() => TestCasePassStatus::Pass,
+            _ => TestCasePassStatus::Fail,
+        };
+        let test_case = evidence.into_test_case().status(Some(status));
+        let recorded = self.add(test_case);
+
+        match outcome {
+            Ok(outcome) => {
+                if let Err(e) = recorded {
+                    panic!("Failed to record evidence for {name}: {e}");
+                }
+                outcome
+            }
+            Err(payload) => {
+                if let Err(e) = recorded {
+                    tracing::error!("Failed to record evidence for {name}: {e}");
+                }
+                panic::resume_unwind(payload)
+            }
+        }
+    }
+
+    /// Get the path of the package.
+    fn package_path(&self) -> PathBuf {
+        if let Some(path) = self.path {
+            return PathBuf::from(path);
+        }
+        if let Some(path) = env::var_os(PACKAGE_ENV_VAR) {
+            return PathBuf::from(path);
+        }
+        PathBuf::from("target")
+            .join("evidence")
+            .join(format!("{}.evp", test_binary_name()))
+    }
+
+    /// Add a test case to the package, creating the package if this is the first test case
+    /// recorded by this process.
+    fn add(&self, test_case: TestCaseBuilder) -> Result<()> {
+        // Tests on other threads record into the same package, so only one can change it at a
+        // time
+        let mut created = self.created.lock().unwrap_or_else(PoisonError::into_inner);
+        let path = self.package_path();
+
+        let mut package = if *created {
+            open_when_unlocked(&path)?
+        } else {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let title = self
+                .title
+                .map_or_else(test_binary_name, ToString::to_string);
+            EvidencePackage::new(path, title, vec![])?
+        };
+        *created = true;
+
+        test_case.build(&mut package)?;
+        package.save()
+    }
+}
+
+/// The evidence recorded by a test, which is added to the package when the test finishes.
+#[derive(Debug)]
+pub struct TestEvidence {
+    /// The test case being built. This is only [`None`] whilst it is being changed.
+    test_case: Option<TestCaseBuilder>,
+}
+
+impl TestEvidence {
+    /// Change the test case being built.
+    fn update<F>(&mut self, change: F) -> &mut Self
+    where
+        F: FnOnce(TestCaseBuilder) -> TestCaseBuilder,
+    {
+        // SAFETY: the test case is always put back after being changed
+        let test_case = self.test_case.take().unwrap();
+        self.test_case = Some(change(test_case));
+        self
+    }
+
+    /// Take the test case being built.
+    fn into_test_case(self) -> TestCaseBuilder {
+        // SAFETY: the test case is always put back after being changed
+        self.test_case.unwrap()
+    }
+
+    /// Set the value of a custom metadata field.
+    pub fn custom<K: Into<String>, V: Into<String>>(&mut self, field: K, value: V) -> &mut Self {
+        self.update(|tc| tc.custom(field, value))
+    }
+
+    /// Record that the test covers a requirement.
+    pub fn requirement(&mut self, requirement: Requirement) -> &mut Self {
+        self.update(|tc| tc.requirement(requirement))
+    }
+
+    /// Add a step to the test. Evidence attached to the step refers to the evidence recorded by
+    /// the test by its zero-based index.
+    pub fn step(&mut self, step: TestStep) -> &mut Self {
+        self.update(|tc| tc.step(step))
+    }
+
+    /// Add evidence.
+    pub fn evidence(&mut self, evidence: EvidenceBuilder) -> &mut Self {
+        self.update(|tc| tc.evidence(evidence))
+    }
+
+    /// Add plain text evidence.
+    pub fn text<S: Into<String>>(&mut self, text: S) -> &mut Self {
+        self.update(|tc| tc.text(text))
+    }
+
+    /// Add rich text evidence, written in Markdown.
+    pub fn rich_text<S: Into<String>>(&mut self, text: S) -> &mut Self {
+        self.update(|tc| tc.rich_text(text))
+    }
+
+    /// Add a PNG or JPEG image as evidence.
+    pub fn screenshot<D: Into<Vec<u8>>>(&mut self, data: D) -> &mut Self {
+        self.update(|tc| tc.screenshot(data))
+    }
+
+    /// Add a file from disk as evidence.
+    pub fn file<P: Into<PathBuf>>(&mut self, path: P) -> &mut Self {
+        self.update(|tc| tc.file(path))
+    }
+
+    /// Add an HTTP request and its response as evidence.
+    pub fn http(&mut self, request: HttpRequest, response: HttpResponse) -> &mut Self {
+        self.update(|tc| tc.http(request, response))
+    }
+
+    /// Add an MP4 or `WebM` video as evidence.
+    pub fn video<D: Into<Vec<u8>>>(&mut self, data: D) -> &mut Self {
+        self.update(|tc| tc.video(data))
+    }
+
+    /// Add structured evidence, such as a [`Log`](crate::Log) or [`Table`](crate::Table).
+    pub fn structured<S: StructuredEvidence>(&mut self, structure: &S) -> &mut Self {
+        self.update(|tc| tc.structured(structure))
+    }
+}
+
+/// The result of a test, which decides whether its test case passes.
+pub trait TestOutcome {
+    /// Check if the test passed.
+    fn passed(&self) -> bool;
+}
+
+impl TestOutcome for () {
+    fn passed(&self) -> bool {
+        true
+    }
+}
+
+impl<T, E> TestOutcome for std::result::Result<T, E> {
+    fn passed(&self) -> bool {
+        self.is_ok()
+    }
+}
+
+/// Run a test, recording its evidence into a test case named after the test, in the default
+/// package for this test binary. See [`EvidenceRecorder::record`].
+///
+/// The default package is at the path in the `EVIDENCEANGEL_TEST_PACKAGE` environment variable,
+/// or otherwise `target/evidence/<test binary>.evp`.
+///
+/// ```no_run
+/// #[test]
+/// fn health_check() {
+///     evidenceangel::testing::record(|evidence| {
+///         evidence.text("The service reported it is healthy");
+///     });
+/// }
+/// ```
+///
+/// # Panics
+///
+/// This re-raises any panic from the test, and panics if the evidence couldn't be recorded.
+pub fn record<F, T>(test: F) -> T
+where
+    F: FnOnce(&mut TestEvidence) -> T,
+    T: TestOutcome,
+{
+    DEFAULT_RECORDER.record(test)
+}
+
+/// Run a test, recording its evidence into a test case with a particular name, in the default
+/// package for this test binary. See [`EvidenceRecorder::record_as`] and [`record`].
+///
+/// # Panics
+///
+/// This re-raises any panic from the test, and panics if the evidence couldn't be recorded.
+pub fn record_as<S, F, T>(name: S, test: F) -> T
+where
+    S: Into<String>,
+    F: FnOnce(&mut TestEvidence) -> T,
+    T: TestOutcome,
+{
+    DEFAULT_RECORDER.record_as(name, test)
+}
+
+/// Open a package, waiting for any other process recording into it to finish.
+fn open_when_unlocked(path: &Path) -> Result<EvidencePackage> {
+    let mut attempts = 1;
+    loop {
+        match EvidencePackage::open(path.to_path_buf()) {
+            Err(Error::LockNotObtained) if attempts < LOCK_ATTEMPTS => {
+                attempts += 1;
+                thread::sleep(LOCK_RETRY_DELAY);
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Get the name of the running test binary, without the hash Cargo adds to it.
+fn test_binary_name() -> String {
+    let name = env::current_exe()
+        .ok()
+        .and_then(|exe| exe.file_stem().map(|s| s.to_string_lossy().to_string()))
+        .unwrap_or_else(|| "tests".to_string());
+    match name.rsplit_once('-') {
+        Some((name, hash)) if hash.chars().all(|c| c.is_ascii_hexdigit()) => name.to_string(),
+        _ => name,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::{EvidenceData, TestCase};
+
+    /// Create a recorder for a package in a temporary directory.
+    fn recorder(dir: &TempDir) -> EvidenceRecorder {
+        let path = dir.path().join("package.evp").to_string_lossy().to_string();
+        EvidenceRecorder::new(path.leak(), "Tests")
+    }
+
+    /// Open the package recorded into by a recorder, and get its test cases by title.
+    fn recorded(recorder: &EvidenceRecorder) -> Vec<TestCase> {
+        let package = EvidencePackage::open(recorder.package_path()).unwrap();
+        let mut test_cases = package
+            .test_case_iter()
+            .unwrap()
+            .cloned()
+            .collect::<Vec<_>>();
+        test_cases.sort_by(|a, b| a.metadata().title().cmp(b.metadata().title()));
+        test_cases
+    }
+
+    /// Get the text of a piece of plain text evidence.
+    fn text(test_case: &TestCase, idx: usize) -> &str {
+        let EvidenceData::Text { content } = test_case.evidence()[idx].value() else {
+            panic!("evidence should be text");
+        };
+        content
+    }
+
+    #[test]
+    fn passing_tests_are_recorded() {
+        let dir = tempfile::tempdir().unwrap();
+        let recorder = recorder(&dir);
+        recorder.record_as("Health check", |evidence| {
+            evidence.text("Healthy").custom("env", "ci");
+        });
+
+        let test_cases = recorded(&recorder);
+        assert_eq!(test_cases.len(), 1);
+        assert_eq!(test_cases[0].metadata().title(), "Health check");
+        assert_eq!(
+            *test_cases[0].metadata().passed(),
+            Some(TestCasePassStatus::Pass)
+        );
+        assert_eq!(text(&test_cases[0], 0), "Healthy");
+    }
+
+    #[test]
+    fn panicking_tests_fail_and_panic_again() {
+        let dir = tempfile::tempdir().unwrap();
+        let recorder = recorder(&dir);
+        let payload = panic::catch_unwind(AssertUnwindSafe(|| {
+            recorder.record_as("Panics", |evidence| -> std::result::Result<(), String> {
+                evidence.text("Before the panic");
+                panic!("the test failed");
+            })
+        }))
+        .unwrap_err();
+        assert_eq!(payload.downcast_ref::<&str>(), Some(&"the test failed"));
+
+        let test_cases = recorded(&recorder);
+        assert_eq!(test_cases.len(), 1);
+        assert_eq!(
+            *test_cases[0].metadata().passed(),
+            Some(TestCasePassStatus::Fail)
+        );
+        assert_eq!(text(&test_cases[0], 0), "Before the panic");
+    }
+
+    #[test]
+    fn results_decide_whether_tests_pass() {
+        let dir = tempfile::tempdir().unwrap();
+        let recorder = recorder(&dir);
+        let ok: std::result::Result<u8, String> = recorder.record_as("Ok", |_| Ok(1));
+        assert_eq!(ok, Ok(1));
+        let err: std::result::Result<u8, String> =
+            recorder.record_as("Err", |_| Err("failed".to_string()));
+        assert_eq!(err, Err("failed".to_string()));
+
+        let test_cases = recorded(&recorder);
+        assert_eq!(test_cases.len(), 2);
+        assert_eq!(test_cases[0].metadata().title(), "Err");
+        assert_eq!(
+            *test_cases[0].metadata().passed(),
+            Some(TestCasePassStatus::Fail)
+        );
+        assert_eq!(test_cases[1].metadata().title(), "Ok");
+        assert_eq!(
+            *test_cases[1].metadata().passed(),
+            Some(TestCasePassStatus::Pass)
+        );
+    }
+
+    #[test]
+    fn tests_are_named_after_their_thread() {
+        let dir = tempfile::tempdir().unwrap();
+        let recorder = recorder(&dir);
+        thread::scope(|s| {
+            thread::Builder::new()
+                .name("module::tests::a_test_with_a_very_long_name".to_string())
+                .spawn_scoped(s, || recorder.record(|_| ()))
+                .unwrap()
+                .join()
+                .unwrap();
+        });
+
+        let test_cases = recorded(&recorder);
+        assert_eq!(
+            test_cases[0].metadata().title(),
+            "a_test_with_a_very_long_name"
+        );
+    }
+
+    #[test]
+    fn tests_on_many_threads_record_into_one_package() {
+        let dir = tempfile::tempdir().unwrap();
+        let recorder = recorder(&dir);
+        thread::scope(|s| {
+            for i in 0..8 {
+                let recorder = &recorder;
+                s.spawn(move || {
+                    recorder.record_as(format!("Test {i}"), |evidence| {
+                        evidence.text(format!("Evidence from test {i}"));
+                    });
+                });
+            }
+        });
+
+        let test_cases = recorded(&recorder);
+        assert_eq!(test_cases.len(), 8);
+        for (i, test_case) in test_cases.iter().enumerate() {
+            assert_eq!(test_case.metadata().title(), &format!("Test {i}"));
+            assert_eq!(test_case.evidence().len(), 1);
+            assert_eq!(text(test_case, 0), format!("Evidence from test {i}"));
+        }
+    }
+
+    #[test]
+    fn packages_from_previous_runs_are_replaced() {
+        let dir = tempfile::tempdir().unwrap();
+        recorder(&dir).record_as("Previous run", |_| ());
+
+        let recorder = recorder(&dir);
+        recorder.record_as("This run", |_| ());
+        let test_cases = recorded(&recorder);
+        assert_eq!(test_cases.len(), 1);
+        assert_eq!(test_cases[0].metadata().title(), "This run");
+    }
+}
@@ -0,0 +1,803 @@
+use std::{
+    fmt::{self, Write},
+    sync::{
+        Arc, Mutex, PoisonError,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Instant,
+};
+
+use chrono::Local;
+use getset::Getters;
+use tracing::{
+    Event, Level, Metadata, Subscriber,
+    field::{Field, Visit},
+    span::{Attributes, Id, Record},
+};
+use tracing_subscriber::{Layer, filter::Targets, layer::Context, registry::LookupSpan};
+use uuid::Uuid;
+
+use crate::{
+    Error, Evidence, EvidenceData, EvidenceKind, EvidencePackage, Log, LogEntry, LogLevel, Result,
+    StructuredEvidence,
+};
+
+/// The name of the spans captured by default.
+pub const DEFAULT_SPAN_NAME: &str = "evidence";
+
+/// The ID of the next [`EvidenceLayer`] to be created.
+static NEXT_LAYER_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// The span field naming the test case that a captured span is written to.
+const TEST_CASE_FIELD: &str = "test_case";
+
+/// The span field setting the caption of the evidence captured from a span.
+const CAPTION_FIELD: &str = "caption";
+
+/// How events captured by an [`EvidenceLayer`] are written as evidence.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum CaptureFormat {
+    /// [`Log`](EvidenceKind::Log) evidence, with a structured entry for each event.
+    #[default]
+    Log,
+    /// [`Text`](EvidenceKind::Text) evidence, with a line for each event.
+    Text,
+    /// [`RichText`](EvidenceKind::RichText) evidence, with a table of the events.
+    RichText,
+}
+
+/// A [`Layer`] which captures the events and span timings within particular spans as evidence.
+///
+/// Each span named [`DEFAULT_SPAN_NAME`], or the name set with [`EvidenceLayer::span_name`], is
+/// captured as one piece of evidence when it closes. The evidence is written to the test case
+/// whose ID is in the span's `test_case` field, or otherwise the test case set with
+/// [`EvidenceLayer::test_case`]. A `caption` field sets the caption of the evidence. Both fields
+/// can be declared [`Empty`](tracing::field::Empty) and recorded later, before the span closes.
+///
+/// ```no_run
+/// use evidenceangel::tracing_layer::EvidenceLayer;
+/// use tracing_subscriber::prelude::*;
+///
+/// # fn main() -> evidenceangel::Result<()> {
+/// let mut package = evidenceangel::EvidencePackage::new(
+///     "results.evp".into(),
+///     "Service logs".to_string(),
+///     vec![],
+/// )?;
+/// let layer = EvidenceLayer::new();
+/// let captured = layer.captured();
+/// tracing_subscriber::registry().with(layer).init();
+///
+/// let test_case = *package.create_test_case("Log in")?.id();
+/// tracing::info_span!("evidence", test_case = %test_case, caption = "Service log").in_scope(|| {
+///     tracing::info!("Logging in");
+/// });
+///
+/// captured.write_to(&mut package)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct EvidenceLayer {
+    /// A unique ID for this layer, distinguishing its state from other layers' in span
+    /// extensions.
+    id: usize,
+    /// The name of the spans to capture.
+    span_name: String,
+    /// How captured events are written as evidence.
+    format: CaptureFormat,
+    /// Which events and span timings are captured, or [`None`] to capture everything.
+    filter: Option<Targets>,
+    /// The test case captured spans are written to if they don't name one.
+    test_case: Option<Uuid>,
+    /// The evidence captured so far.
+    captured: CapturedEvidence,
+}
+
+impl Default for EvidenceLayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EvidenceLayer {
+    /// Create a layer which captures spans named [`DEFAULT_SPAN_NAME`] as log evidence.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            id: NEXT_LAYER_ID.fetch_add(1, Ordering::Relaxed),
+            span_name: DEFAULT_SPAN_NAME.to_string(),
+            format: CaptureFormat::default(),
+            filter: None,
+            test_case: None,
+            captured: CapturedEvidence::default(),
+        }
+    }
+
+    /// Set the name of the spans to capture.
+    #[must_use]
+    pub fn span_name<S: Into<String>>(mut self, name: S) -> Self {
+        self.span_name = name.into();
+        self
+    }
+
+    /// Set how captured events are written as evidence.
+    #[must_use]
+    pub fn format(mut self, format: CaptureFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Only capture events and span timings with targets and levels enabled by a filter. This
+    /// doesn't affect which spans are captured.
+    #[must_use]
+    pub fn filter(mut self, filter: Targets) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    /// Set the test case that captured spans are written to if they don't have a `test_case`
+    /// field.
+    #[must_use]
+    pub fn test_case(mut self, test_case: Uuid) -> Self {
+        self.test_case = Some(test_case);
+        self
+    }
+
+    /// Get a handle to the evidence captured by this layer, which can be used after the layer
+    /// has been added to a subscriber.
+    #[must_use]
+    pub fn captured(&self) -> CapturedEvidence {
+        self.captured.clone()
+    }
+
+    /// Check if the filter enables an event or span.
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        self.filter
+            .as_ref()
+            .is_none_or(|filter| filter.would_enable(metadata.target(), metadata.level()))
+    }
+}
+
+impl<S> Layer<S> for EvidenceLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+
+        let capture = if attrs.metadata().name() == self.span_name {
+            let mut fields = SpanFieldVisitor::default();
+            attrs.record(&mut fields);
+            Some(SpanCapture {
+                test_case: fields
+                    .test_case
+                    .and_then(|id| Uuid::parse_str(&id).ok())
+                    .or(self.test_case),
+                caption: fields
+                    .caption
+                    .unwrap_or_else(|| attrs.metadata().name().to_string()),
+                entries: vec![],
+            })
+        } else {
+            None
+        };
+        let within_capture = span.scope().skip(1).any(|parent| {
+            parent
+                .extensions()
+                .get::<SpanState>()
+                .is_some_and(|state| state.capture(self.id).is_some())
+        });
+        if capture.is_none() && !within_capture {
+            return;
+        }
+
+        let mut extensions = span.extensions_mut();
+        if extensions.get_mut::<SpanState>().is_none() {
+            extensions.insert(SpanState::default());
+        }
+        let Some(state) = extensions.get_mut::<SpanState>() else {
+            return;
+        };
+        if let Some(capture) = capture {
+            state.captures.push((self.id, capture));
+        } else {
+            state.started.get_or_insert_with(Instant::now);
+        }
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut extensions = span.extensions_mut();
+        let Some(capture) = extensions
+            .get_mut::<SpanState>()
+            .and_then(|state| state.capture_mut(self.id))
+        else {
+            return;
+        };
+
+        let mut fields = SpanFieldVisitor::default();
+        values.record(&mut fields);
+        if let Some(test_case) = fields.test_case.and_then(|id| Uuid::parse_str(&id).ok()) {
+            capture.test_case = Some(test_case);
+        }
+        if let Some(caption) = fields.caption {
+            capture.caption = caption;
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        if !self.enabled(event.metadata()) {
+            return;
+        }
+        let Some(scope) = ctx.event_scope(event) else {
+            return;
+        };
+        for span in scope {
+            let mut extensions = span.extensions_mut();
+            if let Some(capture) = extensions
+                .get_mut::<SpanState>()
+                .and_then(|state| state.capture_mut(self.id))
+            {
+                let mut fields = EventFieldVisitor::default();
+                event.record(&mut fields);
+                capture
+                    .entries
+                    .push(log_entry(event.metadata(), fields.into_message()));
+                return;
+            }
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+
+        let (capture, started) = {
+            let mut extensions = span.extensions_mut();
+            let Some(state) = extensions.get_mut::<SpanState>() else {
+                return;
+            };
+            (state.remove_capture(self.id), state.started)
+        };
+
+        if let Some(capture) = capture {
+            if !capture.entries.is_empty() {
+                let mut evidence = evidence_from_entries(capture.entries, self.format);
+                evidence.set_caption(Some(capture.caption));
+                self.captured.push(CapturedSpan {
+                    test_case: capture.test_case,
+                    evidence,
+                });
+            }
+            return;
+        }
+
+        let Some(started) = started else {
+            return;
+        };
+        if !self.enabled(span.metadata()) {
+            return;
+        }
+        for parent in span.scope().skip(1) {
+            let mut extensions = parent.extensions_mut();
+            if let Some(capture) = extensions
+                .get_mut::<SpanState>()
+                .and_then(|state| state.capture_mut(self.id))
+            {
+                capture.entries.push(log_entry(
+                    span.metadata(),
+                    format!(
+                        "span {} took {:.3}ms",
+                        span.name(),
+                        started.elapsed().as_secs_f64() * 1000.0
+                    ),
+                ));
+                return;
+            }
+        }
+    }
+}
+
+/// The state of a span, stored in the span's extensions. This is shared by every
+/// [`EvidenceLayer`] in a subscriber, as extensions can only hold one value of each type.
+#[derive(Default)]
+struct SpanState {
+    /// When the span started, if it is within a captured span.
+    started: Option<Instant>,
+    /// The events captured so far within the span, by the ID of the layer capturing them.
+    captures: Vec<(usize, SpanCapture)>,
+}
+
+impl SpanState {
+    /// Get the events captured within this span by a layer.
+    fn capture(&self, layer: usize) -> Option<&SpanCapture> {
+        self.captures
+            .iter()
+            .find(|(id, _)| *id == layer)
+            .map(|(_, capture)| capture)
+    }
+
+    /// Mutably get the events captured within this span by a layer.
+    fn capture_mut(&mut self, layer: usize) -> Option<&mut SpanCapture> {
+        self.captures
+            .iter_mut()
+            .find(|(id, _)| *id == layer)
+            .map(|(_, capture)| capture)
+    }
+
+    /// Remove the events captured within this span by a layer.
+    fn remove_capture(&mut self, layer: usize) -> Option<SpanCapture> {
+        let idx = self.captures.iter().position(|(id, _)| *id == layer)?;
+        Some(self.captures.remove(idx).1)
+    }
+}
+
+/// The events captured so far within a span by a layer.
+struct SpanCapture {
+    /// The test case the evidence is written to.
+    test_case: Option<Uuid>,
+    /// The caption of the evidence.
+    caption: String,
+    /// The events captured so far.
+    entries: Vec<LogEntry>,
+}
+
+/// The fields of a captured span that affect where its evidence is written.
+#[derive(Default)]
+struct SpanFieldVisitor {
+    /// The `test_case` field.
+    test_case: Option<String>,
+    /// The `caption` field.
+    caption: Option<String>,
+}
+
+impl Visit for SpanFieldVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        match field.name() {
+            TEST_CASE_FIELD => self.test_case = Some(value.to_string()),
+            CAPTION_FIELD => self.caption = Some(value.to_string()),
+            _ => (),
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.record_str(field, &format!("{value:?}"));
+    }
+}
+
+/// The message and other fields of an event.
+#[derive(Default)]
+struct EventFieldVisitor {
+    /// The message of the event.
+    message: String,
+    /// The other fields of the event, formatted as `name=value`.
+    fields: Vec<String>,
+}
+
+impl EventFieldVisitor {
+    /// Get the message of the event, followed by its other fields.
+    fn into_message(self) -> String {
+        let mut message = self.message;
+        for field in self.fields {
+            if !message.is_empty() {
+                message.push(' ');
+            }
+            message.push_str(&field);
+        }
+        message
+    }
+}
+
+impl Visit for EventFieldVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_string();
+        } else {
+            self.fields.push(format!("{}={value}", field.name()));
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{value:?}");
+        } else {
+            self.fields.push(format!("{}={value:?}", field.name()));
+        }
+    }
+}
+
+/// Create a log entry for an event or span timing happening now.
+fn log_entry(metadata: &Metadata<'_>, message: String) -> LogEntry {
+    let mut entry = LogEntry::new(message);
+    entry.set_timestamp(Some(Local::now().fixed_offset()));
+    entry.set_level(Some(log_level(*metadata.level())));
+    entry.set_source(Some(metadata.target().to_string()));
+    entry
+}
+
+/// Convert a `tracing` level to a log level.
+fn log_level(level: Level) -> LogLevel {
+    match level {
+        Level::TRACE => LogLevel::Trace,
+        Level::DEBUG => LogLevel::Debug,
+        Level::INFO => LogLevel::Info,
+        Level::WARN => LogLevel::Warn,
+        Level::ERROR => LogLevel::Error,
+    }
+}
+
+/// Create evidence from captured log entries.
+fn evidence_from_entries(entries: Vec<LogEntry>, format: CaptureFormat) -> Evidence {
+    match format {
+        CaptureFormat::Log => Log::new(entries).to_evidence(),
+        CaptureFormat::Text => {
+            let content = entries
+                .iter()
+                .map(|entry| {
+                    format!(
+                        "{} {} {}: {}",
+                        entry
+                            .timestamp()
+                            .map(|t| t.to_rfc3339())
+                            .unwrap_or_default(),
+                        entry.level().map(|l| l.to_string()).unwrap_or_default(),
+                        entry.source().as_deref().unwrap_or_default(),
+                        entry.message()
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            Evidence::new(EvidenceKind::Text, EvidenceData::Text { content })
+        }
+        CaptureFormat::RichText => {
+            let mut content =
+                "| Time | Level | Target | Message |\n|---|---|---|---|\n".to_string();
+            for entry in &entries {
+                let _ = writeln!(
+                    content,
+                    "| {} | {} | {} | {} |",
+                    entry
+                        .timestamp()
+                        .map(|t| t.format("%H:%M:%S%.3f").to_string())
+                        .unwrap_or_default(),
+                    entry.level().map(|l| l.to_string()).unwrap_or_default(),
+                    escape_angelmark(entry.source().as_deref().unwrap_or_default()),
+                    escape_angelmark(entry.message())
+                );
+            }
+            Evidence::new(EvidenceKind::RichText, EvidenceData::Text { content })
+        }
+    }
+}
+
+/// Escape text so it appears as-is in a table cell of rich text.
+fn escape_angelmark(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' | '_' | '*' | '`' | '|' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\r' | '\n' => escaped.push(' '),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Evidence captured from a span by an [`EvidenceLayer`].
+#[derive(Clone, Debug, Getters)]
+#[getset(get = "pub")]
+pub struct CapturedSpan {
+    /// The test case the evidence should be written to, if known.
+    test_case: Option<Uuid>,
+    /// The evidence.
+    evidence: Evidence,
+}
+
+impl CapturedSpan {
+    /// Take the evidence.
+    #[must_use]
+    pub fn into_evidence(self) -> Evidence {
+        self.evidence
+    }
+}
+
+/// A handle to the evidence captured by an [`EvidenceLayer`].
+#[derive(Clone, Debug, Default)]
+pub struct CapturedEvidence {
+    /// The spans captured so far, in the order they closed.
+    spans: Arc<Mutex<Vec<CapturedSpan>>>,
+}
+
+impl CapturedEvidence {
+    /// Add a captured span.
+    fn push(&self, span: CapturedSpan) {
+        self.spans
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(span);
+    }
+
+    /// Check if no spans have been captured since they were last taken.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.spans
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .is_empty()
+    }
+
+    /// Take all the spans captured so far.
+    #[must_use]
+    pub fn take(&self) -> Vec<CapturedSpan> {
+        std::mem::take(&mut *self.spans.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    /// Add the evidence captured so far to the test cases it was captured for, returning how
+    /// many pieces of evidence were added. Evidence which wasn't captured for a particular test
+    /// case is kept, and can be taken with [`CapturedEvidence::take`]. The package isn't saved.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::DoesntExist`] if a test case evidence was captured for isn't in the package.
+    ///   No evidence is added in this case.
+    pub fn write_to(&self, package: &mut EvidencePackage) -> Result<usize> {
+        let mut spans = self.spans.lock().unwrap_or_else(PoisonError::into_inner);
+        for test_case in spans.iter().filter_map(|span| span.test_case) {
+            if package.test_case(test_case)?.is_none() {
+                return Err(Error::DoesntExist(test_case));
+            }
+        }
+
+        let (to_write, to_keep) = std::mem::take(&mut *spans)
+            .into_iter()
+            .partition::<Vec<_>, _>(|span| span.test_case.is_some());
+        *spans = to_keep;
+        let written = to_write.len();
+        for span in to_write {
+            if let Some(test_case) = span.test_case
+                && let Some(test_case) = package.test_case_mut(test_case)?
+            {
+                test_case.evidence_mut().push(span.evidence);
+            }
+        }
+        Ok(written)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing::field::Empty;
+    use tracing_subscriber::prelude::*;
+
+    use super::*;
+
+    /// Run a closure with a subscriber made of just an [`EvidenceLayer`], returning the spans it
+    /// captured.
+    fn capture<F: FnOnce()>(layer: EvidenceLayer, f: F) -> Vec<CapturedSpan> {
+        let captured = layer.captured();
+        tracing::subscriber::with_default(tracing_subscriber::registry().with(layer), f);
+        captured.take()
+    }
+
+    /// Get the messages of the log entries in captured log evidence.
+    fn messages(span: &CapturedSpan) -> Vec<String> {
+        let EvidenceData::Text { content } = span.evidence().value() else {
+            panic!("captured evidence should be text");
+        };
+        serde_json::from_str::<Log>(content)
+            .unwrap()
+            .entries()
+            .iter()
+            .map(|entry| entry.message().clone())
+            .collect()
+    }
+
+    #[test]
+    fn events_in_captured_spans_are_captured() {
+        let test_case = Uuid::new_v4();
+        let spans = capture(EvidenceLayer::new(), || {
+            tracing::info!("Before the span");
+            tracing::info_span!("evidence", test_case = %test_case, caption = "Service log")
+                .in_scope(|| {
+                    tracing::info!("Logging in");
+                    tracing::warn!(user = "admin", "Slow response");
+                });
+            tracing::info_span!("other").in_scope(|| tracing::info!("Elsewhere"));
+        });
+
+        assert_eq!(spans.len(), 1);
+        assert_eq!(*spans[0].test_case(), Some(test_case));
+        assert_eq!(*spans[0].evidence().kind(), EvidenceKind::Log);
+        assert_eq!(
+            spans[0].evidence().caption().as_deref(),
+            Some("Service log")
+        );
+        assert_eq!(
+            messages(&spans[0]),
+            vec!["Logging in", "Slow response user=admin"]
+        );
+    }
+
+    #[test]
+    fn spans_without_events_are_not_captured() {
+        let spans = capture(EvidenceLayer::new(), || {
+            tracing::info_span!("evidence").in_scope(|| ());
+        });
+        assert!(spans.is_empty());
+    }
+
+    #[test]
+    fn fields_recorded_later_are_used() {
+        let test_case = Uuid::new_v4();
+        let spans = capture(EvidenceLayer::new(), || {
+            let span = tracing::info_span!("evidence", test_case = Empty, caption = Empty);
+            span.in_scope(|| tracing::info!("Logging in"));
+            span.record("test_case", tracing::field::display(test_case));
+            span.record("caption", "Recorded later");
+        });
+
+        assert_eq!(spans.len(), 1);
+        assert_eq!(*spans[0].test_case(), Some(test_case));
+        assert_eq!(
+            spans[0].evidence().caption().as_deref(),
+            Some("Recorded later")
+        );
+    }
+
+    #[test]
+    fn default_test_case_and_caption_are_used() {
+        let test_case = Uuid::new_v4();
+        let spans = capture(
+            EvidenceLayer::new()
+                .span_name("captured")
+                .test_case(test_case),
+            || {
+                tracing::info_span!("evidence").in_scope(|| tracing::info!("Not captured"));
+                tracing::info_span!("captured").in_scope(|| tracing::info!("Captured"));
+            },
+        );
+
+        assert_eq!(spans.len(), 1);
+        assert_eq!(*spans[0].test_case(), Some(test_case));
+        assert_eq!(spans[0].evidence().caption().as_deref(), Some("captured"));
+        assert_eq!(messages(&spans[0]), vec!["Captured"]);
+    }
+
+    #[test]
+    fn nested_spans_are_captured_by_the_innermost_captured_span() {
+        let spans = capture(EvidenceLayer::new(), || {
+            tracing::info_span!("evidence", caption = "Outer").in_scope(|| {
+                tracing::info!("Outer event");
+                tracing::info_span!("evidence", caption = "Inner").in_scope(|| {
+                    tracing::info_span!("step").in_scope(|| tracing::info!("Inner event"));
+                });
+            });
+        });
+
+        assert_eq!(spans.len(), 2);
+        assert_eq!(spans[0].evidence().caption().as_deref(), Some("Inner"));
+        let inner = messages(&spans[0]);
+        assert_eq!(inner.len(), 2);
+        assert_eq!(inner[0], "Inner event");
+        assert!(inner[1].starts_with("span step took "));
+        assert_eq!(spans[1].evidence().caption().as_deref(), Some("Outer"));
+        assert_eq!(messages(&spans[1]), vec!["Outer event"]);
+    }
+
+    #[test]
+    fn timings_of_spans_within_captured_spans_are_captured() {
+        let spans = capture(EvidenceLayer::new(), || {
+            tracing::info_span!("evidence").in_scope(|| {
+                tracing::info_span!("request").in_scope(|| tracing::info!("Requesting"));
+            });
+        });
+
+        let messages = messages(&spans[0]);
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0], "Requesting");
+        assert!(messages[1].starts_with("span request took "));
+        assert!(messages[1].ends_with("ms"));
+    }
+
+    #[test]
+    fn events_and_timings_are_filtered() {
+        let filter = Targets::new()
+            .with_target("noisy", Level::ERROR)
+            .with_default(Level::INFO);
+        let spans = capture(EvidenceLayer::new().filter(filter), || {
+            tracing::info_span!("evidence").in_scope(|| {
+                tracing::debug!("Too verbose");
+                tracing::info!("Kept");
+                tracing::warn!(target: "noisy", "Filtered by target");
+                tracing::error!(target: "noisy", "Kept by target");
+                tracing::debug_span!("quiet").in_scope(|| ());
+            });
+        });
+
+        assert_eq!(messages(&spans[0]), vec!["Kept", "Kept by target"]);
+    }
+
+    #[test]
+    fn text_and_rich_text_formats_are_written() {
+        let spans = capture(EvidenceLayer::new().format(CaptureFormat::Text), || {
+            tracing::info_span!("evidence").in_scope(|| tracing::info!("Plain"));
+        });
+        assert_eq!(*spans[0].evidence().kind(), EvidenceKind::Text);
+        let EvidenceData::Text { content } = spans[0].evidence().value() else {
+            panic!("captured evidence should be text");
+        };
+        assert!(content.ends_with(": Plain"));
+
+        let spans = capture(EvidenceLayer::new().format(CaptureFormat::RichText), || {
+            tracing::info_span!("evidence").in_scope(|| tracing::info!("a | *b*"));
+        });
+        assert_eq!(*spans[0].evidence().kind(), EvidenceKind::RichText);
+        let EvidenceData::Text { content } = spans[0].evidence().value() else {
+            panic!("captured evidence should be text");
+        };
+        assert!(content.contains("| a \\| \\*b\\* |"));
+    }
+
+    #[test]
+    fn captured_evidence_is_written_to_a_package() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("package.evp");
+        let mut package =
+            EvidencePackage::new(path.clone(), "Package".to_string(), vec![]).unwrap();
+        let test_case = *package.create_test_case("Log in").unwrap().id();
+
+        let layer = EvidenceLayer::new();
+        let captured = layer.captured();
+        tracing::subscriber::with_default(tracing_subscriber::registry().with(layer), || {
+            tracing::info_span!("evidence", test_case = %test_case, caption = "Service log")
+                .in_scope(|| tracing::info!("Logging in"));
+            tracing::info_span!("evidence").in_scope(|| tracing::info!("No test case"));
+        });
+
+        assert_eq!(captured.write_to(&mut package).unwrap(), 1);
+        assert!(!captured.is_empty());
+        package.save().unwrap();
+        drop(package);
+
+        let mut package = EvidencePackage::open(path).unwrap();
+        let evidence = package.test_case(test_case).unwrap().unwrap().evidence()[0].clone();
+        assert_eq!(evidence.caption().as_deref(), Some("Service log"));
+        let log = Log::from_evidence(&evidence, &mut package).unwrap();
+        assert_eq!(log.entries()[0].message(), "Logging in");
+        assert_eq!(log.entries()[0].level(), Some(LogLevel::Info));
+    }
+
+    #[test]
+    fn captured_evidence_for_missing_test_cases_is_not_written() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut package = EvidencePackage::new(
+            dir.path().join("package.evp"),
+            "Package".to_string(),
+            vec![],
+        )
+        .unwrap();
+        let test_case = Uuid::new_v4();
+
+        let layer = EvidenceLayer::new();
+        let captured = layer.captured();
+        tracing::subscriber::with_default(tracing_subscriber::registry().with(layer), || {
+            tracing::info_span!("evidence", test_case = %test_case)
+                .in_scope(|| tracing::info!("Logging in"));
+        });
+
+        assert!(matches!(
+            captured.write_to(&mut package),
+            Err(Error::DoesntExist(id)) if id == test_case
+        ));
+        assert_eq!(captured.take().len(), 1);
+    }
+}
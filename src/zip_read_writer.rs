@@ -1,34 +1,107 @@
 use std::{
     fmt, fs,
-    io::{BufReader, BufWriter},
+    io::{self, BufReader, BufWriter, Cursor, Read, Seek, SeekFrom, Write},
     path,
+    sync::Arc,
 };
 
 use zip::{ZipArchive, ZipWriter};
 
 use crate::lock_file::LockFile;
 
+/// Where the contents of a [`ZipReaderWriter`] are kept.
+#[derive(Clone, Default)]
+enum ZipStorage {
+    /// Nothing has been set yet.
+    #[default]
+    Unset,
+    /// A file on disk, which is locked whilst it is open.
+    File(path::PathBuf),
+    /// Memory, holding the contents as of the last write. Nothing is locked, so this is used
+    /// where there is no filesystem, such as in a browser.
+    Memory(Arc<[u8]>),
+}
+
+/// The data a [`ZipReaderWriter`] reads from.
+pub(crate) enum ZipSource {
+    /// A file on disk.
+    File(BufReader<fs::File>),
+    /// The contents of a ZIP file held in memory.
+    Memory(Cursor<Arc<[u8]>>),
+}
+
+impl Read for ZipSource {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Self::File(file) => file.read(buf),
+            Self::Memory(data) => data.read(buf),
+        }
+    }
+}
+
+impl Seek for ZipSource {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        match self {
+            Self::File(file) => file.seek(pos),
+            Self::Memory(data) => data.seek(pos),
+        }
+    }
+}
+
+/// The data a [`ZipReaderWriter`] writes to.
+pub(crate) enum ZipSink {
+    /// A temporary file on disk.
+    File(BufWriter<fs::File>),
+    /// A buffer in memory.
+    Memory(Cursor<Vec<u8>>),
+}
+
+impl Write for ZipSink {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::File(file) => file.write(buf),
+            Self::Memory(data) => data.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::File(file) => file.flush(),
+            Self::Memory(data) => data.flush(),
+        }
+    }
+}
+
+impl Seek for ZipSink {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        match self {
+            Self::File(file) => file.seek(pos),
+            Self::Memory(data) => data.seek(pos),
+        }
+    }
+}
+
 /// A convenient type which can read and write to a ZIP file and cleanly switch between the two modes.
 ///
 /// Whilst writing, you can also read the previous file, as it writes to a new temporary file, until
 /// [`ZipReaderWriter::conclude_write`] is called.
 #[derive(Default)]
 pub(crate) struct ZipReaderWriter {
-    /// The path of this zip file, if set
-    path: Option<path::PathBuf>,
+    /// Where the contents of this zip file are kept
+    storage: ZipStorage,
     /// The locking file for this evidence package. If this is [`Some`],
     /// you can be assured that the lock is obtained.
     lock_file: Option<LockFile>,
     /// The reader, if in write mode, of this reader/writer
-    reader: Option<ZipArchive<BufReader<fs::File>>>,
+    reader: Option<ZipArchive<ZipSource>>,
     /// The writer, if in write mode, of this reader/writer
-    writer: Option<ZipWriter<BufWriter<fs::File>>>,
+    writer: Option<ZipWriter<ZipSink>>,
 }
 
 impl Clone for ZipReaderWriter {
     fn clone(&self) -> Self {
         Self {
-            path: self.path.clone(),
+            storage: self.storage.clone(),
             ..Default::default()
         }
     }
@@ -43,8 +116,13 @@ impl fmt::Debug for ZipReaderWriter {
         } else {
             "idle"
         };
+        let file = match &self.storage {
+            ZipStorage::Unset => None,
+            ZipStorage::File(path) => Some(path.display().to_string()),
+            ZipStorage::Memory(_) => Some("<memory>".to_string()),
+        };
         f.debug_struct("ZipReadWriter")
-            .field("file", &self.path)
+            .field("file", &file)
             .field("current_mode", &mode)
             .finish_non_exhaustive()
     }
@@ -54,16 +132,37 @@ impl ZipReaderWriter {
     /// Create a new [`ZipReaderWriter`] instance.
     pub fn new(path: path::PathBuf) -> crate::Result<Self> {
         let mut o = Self {
-            path: Some(path),
+            storage: ZipStorage::File(path),
             ..Default::default()
         };
         o.update_lock_file()?;
         Ok(o)
     }
 
+    /// Create a new [`ZipReaderWriter`] instance holding a ZIP file in memory. Writes replace
+    /// the data held, and nothing is written to disk.
+    pub fn from_bytes(data: Vec<u8>) -> Self {
+        Self {
+            storage: ZipStorage::Memory(data.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Get the contents of the ZIP file as of the last write, if it is held in memory.
+    pub fn bytes(&self) -> Option<&[u8]> {
+        match &self.storage {
+            ZipStorage::Memory(data) => Some(data),
+            _ => None,
+        }
+    }
+
     /// Validate that the currently held lock is still locking the
     /// package.
     fn validate_lock(&mut self) -> crate::Result<()> {
+        if let ZipStorage::Memory(_) = self.storage {
+            // Nothing else can change data held in memory
+            return Ok(());
+        }
         if let Some(lock_file) = self.lock_file.as_mut() {
             lock_file.ensure_still_locked().map_err(|e| {
                 tracing::error!("The lock was lost! {e}");
@@ -78,7 +177,7 @@ impl ZipReaderWriter {
     /// either obtain it (if a path is set), drop it (if a path isn't
     /// set), or will return a [`crate::Error::Locking`] error.
     fn update_lock_file(&mut self) -> crate::Result<()> {
-        if let Some(path) = &self.path {
+        if let ZipStorage::File(path) = &self.storage {
             let mut lock_path = path.clone();
             // SAFETY: only a file can be specified here
             lock_path.set_file_name(format!(
@@ -100,8 +199,26 @@ impl ZipReaderWriter {
         Ok(())
     }
 
+    /// Get the path of the temporary file written to, if this is a file on disk.
+    fn temporary_path(&self) -> Option<path::PathBuf> {
+        match &self.storage {
+            ZipStorage::Unset => {
+                panic!("zipreadwriter must not be called upon until file is set.")
+            }
+            ZipStorage::File(path) => {
+                let mut path = path.clone();
+                path.set_file_name(format!(
+                    "{}.tmp",
+                    path.file_name().unwrap().to_string_lossy()
+                ));
+                Some(path)
+            }
+            ZipStorage::Memory(_) => None,
+        }
+    }
+
     /// Get this [`ZipReaderWriter`] instance in read mode.
-    pub fn as_reader(&mut self) -> crate::Result<&mut ZipArchive<BufReader<fs::File>>> {
+    pub fn as_reader(&mut self) -> crate::Result<&mut ZipArchive<ZipSource>> {
         if self.reader.is_none() {
             // Close writer
             tracing::debug!("Closing writer");
@@ -109,11 +226,14 @@ impl ZipReaderWriter {
 
             // Open reader
             tracing::debug!("Opening reader");
-            self.reader = Some(ZipArchive::new(BufReader::new(fs::File::open(
-                self.path
-                    .as_ref()
-                    .expect("zipreadwriter must not be called upon until file is set."),
-            )?))?);
+            let source = match &self.storage {
+                ZipStorage::Unset => {
+                    panic!("zipreadwriter must not be called upon until file is set.")
+                }
+                ZipStorage::File(path) => ZipSource::File(BufReader::new(fs::File::open(path)?)),
+                ZipStorage::Memory(data) => ZipSource::Memory(Cursor::new(data.clone())),
+            };
+            self.reader = Some(ZipArchive::new(source)?);
         }
         Ok(self.reader.as_mut().unwrap())
     }
@@ -122,27 +242,16 @@ impl ZipReaderWriter {
     #[allow(clippy::type_complexity)]
     pub fn as_writer(
         &mut self,
-    ) -> crate::Result<(
-        Option<&mut ZipArchive<BufReader<fs::File>>>,
-        &mut ZipWriter<BufWriter<fs::File>>,
-    )> {
+    ) -> crate::Result<(Option<&mut ZipArchive<ZipSource>>, &mut ZipWriter<ZipSink>)> {
         self.validate_lock()?;
         if self.writer.is_none() {
             tracing::debug!("Opening writer");
             // Open writer
-            self.writer = Some(ZipWriter::new(BufWriter::new(fs::File::create(
-                self.path
-                    .as_ref()
-                    .map(|p| {
-                        let mut p = p.clone();
-                        p.set_file_name(format!(
-                            "{}.tmp",
-                            p.file_name().unwrap().to_string_lossy()
-                        ));
-                        p
-                    })
-                    .expect("zipreadwriter must not be called upon until file is set."),
-            )?)));
+            let sink = match self.temporary_path() {
+                Some(tmp_path) => ZipSink::File(BufWriter::new(fs::File::create(tmp_path)?)),
+                None => ZipSink::Memory(Cursor::new(Vec::new())),
+            };
+            self.writer = Some(ZipWriter::new(sink));
         }
         Ok((self.reader.as_mut(), self.writer.as_mut().unwrap()))
     }
@@ -154,28 +263,25 @@ impl ZipReaderWriter {
             // Close write
             tracing::debug!("Closing writer");
             let writer = self.writer.take().unwrap();
-            writer.finish()?;
+            let sink = writer.finish()?;
 
             tracing::debug!("Closing reader");
             self.reader = None;
 
-            // Move temp file
-            tracing::debug!("Moving temp file to overwrite package");
-            let tmp_path = self
-                .path
-                .as_ref()
-                .map(|p| {
-                    let mut p = p.clone();
-                    p.set_file_name(format!("{}.tmp", p.file_name().unwrap().to_string_lossy()));
-                    p
-                })
-                .expect("zipreadwriter must not be called upon until file is set.");
-            fs::rename(
-                tmp_path,
-                self.path
-                    .as_ref()
-                    .expect("zipreadwriter must not be called upon until file is set."),
-            )?;
+            match sink {
+                ZipSink::File(_) => {
+                    // Move temp file
+                    tracing::debug!("Moving temp file to overwrite package");
+                    let tmp_path = self.temporary_path().unwrap();
+                    if let ZipStorage::File(path) = &self.storage {
+                        fs::rename(tmp_path, path)?;
+                    }
+                }
+                ZipSink::Memory(data) => {
+                    tracing::debug!("Replacing package in memory");
+                    self.storage = ZipStorage::Memory(data.into_inner().into());
+                }
+            }
         }
         Ok(())
     }
@@ -193,17 +299,10 @@ impl ZipReaderWriter {
             self.reader = None;
 
             // Delete temp file
-            tracing::debug!("Moving temp file to overwrite package");
-            let tmp_path = self
-                .path
-                .as_ref()
-                .map(|p| {
-                    let mut p = p.clone();
-                    p.set_file_name(format!("{}.tmp", p.file_name().unwrap().to_string_lossy()));
-                    p
-                })
-                .expect("zipreadwriter must not be called upon until file is set.");
-            fs::remove_file(tmp_path)?;
+            if let Some(tmp_path) = self.temporary_path() {
+                tracing::debug!("Removing temp file");
+                fs::remove_file(tmp_path)?;
+            }
         }
         Ok(())
     }